@@ -1,19 +1,19 @@
 use std::rc::Rc;
 
-use web_sys::PointerEvent;
-
 use crate::{
-    axis::Axis,
-    coordinates::{Offset, Position, ScreenSpace},
+    api::PointerModifiers,
+    axis::{self, Axis, Element},
+    coordinates::{Aabb, Offset, Position, ScreenSpace, WorldSpace},
     event::Event,
-    lerp::InverseLerp,
+    lerp::{InverseLerp, Lerp},
     selection::{Direction, EasingType, Selection, SelectionCurveBuilder},
-    wasm_bridge::InteractionMode,
+    wasm_bridge::{InteractionMode, PointerInput},
 };
 
 #[derive(Debug)]
 pub struct Action {
     inner: ActionInner,
+    modifiers: PointerModifiers,
 }
 
 #[derive(Debug)]
@@ -23,6 +23,7 @@ enum ActionInner {
     CreateBrush(CreateBrush),
     SelectBrush(SelectBrush),
     SelectCP(SelectCP),
+    MoveColorBar(MoveColorBar),
     // SelectAxisCP(SelectAxisCP),
     // SelectCurveCP(SelectCurveCP),
 }
@@ -30,7 +31,7 @@ enum ActionInner {
 impl Action {
     pub fn new_move_axis(
         axis: Rc<Axis>,
-        event: PointerEvent,
+        event: PointerInput,
         active_label_idx: Option<usize>,
         interaction_mode: InteractionMode,
     ) -> Self {
@@ -41,6 +42,7 @@ pub fn new_move_axis(
                 active_label_idx,
                 interaction_mode,
             )),
+            modifiers: PointerModifiers::default(),
         }
     }
 
@@ -57,12 +59,13 @@ pub fn new_select_group(
                 active_label_idx,
                 easing_type,
             )),
+            modifiers: PointerModifiers::default(),
         }
     }
 
     pub fn new_create_brush(
         axis: Rc<Axis>,
-        event: PointerEvent,
+        event: PointerInput,
         active_label_idx: usize,
         easing_type: EasingType,
     ) -> Self {
@@ -73,6 +76,28 @@ pub fn new_create_brush(
                 active_label_idx,
                 easing_type,
             )),
+            modifiers: PointerModifiers::default(),
+        }
+    }
+
+    /// Creates a brush spanning the bins of the histogram of an axis, from
+    /// the bin `bin` to the bin under the pointer.
+    pub fn new_create_histogram_brush(
+        axis: Rc<Axis>,
+        bin: usize,
+        num_bins: usize,
+        active_label_idx: usize,
+        easing_type: EasingType,
+    ) -> Self {
+        Self {
+            inner: ActionInner::CreateBrush(CreateBrush::new_spanning_bins(
+                axis,
+                bin,
+                num_bins,
+                active_label_idx,
+                easing_type,
+            )),
+            modifiers: PointerModifiers::default(),
         }
     }
 
@@ -89,6 +114,7 @@ pub fn new_select_brush(
                 active_label_idx,
                 easing_type,
             )),
+            modifiers: PointerModifiers::default(),
         }
     }
 
@@ -108,6 +134,7 @@ pub fn new_select_axis_control_point(
                 easing_type,
                 false,
             )),
+            modifiers: PointerModifiers::default(),
         }
     }
 
@@ -127,21 +154,119 @@ pub fn new_select_curve_control_point(
                 easing_type,
                 true,
             )),
+            modifiers: PointerModifiers::default(),
         }
     }
 
-    pub fn update(&mut self, event: PointerEvent) -> Event {
+    pub fn new_move_color_bar(event: PointerInput, position: Position<ScreenSpace>) -> Self {
+        Self {
+            inner: ActionInner::MoveColorBar(MoveColorBar::new(event, position)),
+            modifiers: PointerModifiers::default(),
+        }
+    }
+
+    pub fn update(&mut self, event: PointerInput) -> Event {
+        self.modifiers = self.modifiers.union(PointerModifiers::of(&event));
         match &mut self.inner {
             ActionInner::MoveAxis(e) => e.update(event),
             ActionInner::SelectGroup(e) => e.update(event),
             ActionInner::CreateBrush(e) => e.update(event),
             ActionInner::SelectBrush(e) => e.update(event),
             ActionInner::SelectCP(e) => e.update(event),
+            ActionInner::MoveColorBar(e) => e.update(event),
             // ActionInner::SelectAxisCP(e) => e.update(event),
             // ActionInner::SelectCurveCP(e) => e.update(event),
         }
     }
 
+    /// Advances the action by one frame.
+    ///
+    /// With `reduced_motion`, continuous movements advance in full steps.
+    pub fn tick(&mut self, reduced_motion: bool) -> Event {
+        match &mut self.inner {
+            ActionInner::MoveAxis(e) => e.tick(reduced_motion),
+            _ => Event::NONE,
+        }
+    }
+
+    /// Returns the world space bounding box of an axis that would be removed.
+    pub fn removal_marker(&self) -> Option<Aabb<WorldSpace>> {
+        match &self.inner {
+            ActionInner::MoveAxis(e) => e.removal_marker(),
+            _ => None,
+        }
+    }
+
+    /// Returns the world space line where the moved axis would be inserted.
+    pub fn insertion_marker(&self) -> Option<(Position<WorldSpace>, Position<WorldSpace>)> {
+        match &self.inner {
+            ActionInner::MoveAxis(e) => e.insertion_marker(),
+            _ => None,
+        }
+    }
+
+    /// Returns the axis and the range of a brush that is being created, but
+    /// which is not yet committed.
+    pub fn preview_selection(&self) -> Option<(Rc<Axis>, [f32; 2])> {
+        match &self.inner {
+            ActionInner::CreateBrush(e) => e.preview_selection(),
+            _ => None,
+        }
+    }
+
+    /// Returns the control point that is being dragged.
+    pub fn control_point(&self) -> Option<Element> {
+        match &self.inner {
+            ActionInner::SelectCP(e) => e.control_point(),
+            _ => None,
+        }
+    }
+
+    /// Returns the screen space position of the top-left corner of the
+    /// color bar that is being dragged.
+    pub fn color_bar_position(&self) -> Option<Position<ScreenSpace>> {
+        match &self.inner {
+            ActionInner::MoveColorBar(e) => Some(e.position),
+            _ => None,
+        }
+    }
+
+    /// Returns a short description of the action and its state, for debugging.
+    pub fn describe(&self) -> String {
+        match &self.inner {
+            ActionInner::MoveAxis(e) => {
+                format!("MoveAxis {{ axis: {}, moved: {} }}", e.axis.key(), e.moved)
+            }
+            ActionInner::SelectGroup(e) => format!(
+                "SelectGroup {{ axis: {}, group: {}, moved: {} }}",
+                e.axis.key(),
+                e.group_idx,
+                e.moved
+            ),
+            ActionInner::CreateBrush(e) => format!(
+                "CreateBrush {{ axis: {}, start: {} }}",
+                e.axis.key(),
+                e.start_axis_value
+            ),
+            ActionInner::SelectBrush(e) => format!(
+                "SelectBrush {{ axis: {}, selection: {}, moved: {} }}",
+                e.axis.key(),
+                e.selection_idx,
+                e.moved
+            ),
+            ActionInner::SelectCP(e) => e.describe(),
+            ActionInner::MoveColorBar(e) => {
+                let (x, y) = e.position.extract();
+                format!("MoveColorBar {{ position: ({x}, {y}) }}")
+            }
+        }
+    }
+
+    /// Returns the modifier keys which were held during the action.
+    pub fn modifiers(&self) -> PointerModifiers {
+        self.modifiers
+    }
+
     pub fn finish(self) -> Event {
         match self.inner {
             ActionInner::MoveAxis(e) => e.finish(),
@@ -149,12 +274,78 @@ pub fn finish(self) -> Event {
             ActionInner::CreateBrush(e) => e.finish(),
             ActionInner::SelectBrush(e) => e.finish(),
             ActionInner::SelectCP(e) => e.finish(),
+            ActionInner::MoveColorBar(e) => e.finish(),
             // ActionInner::SelectAxisCP(e) => e.finish(),
             // ActionInner::SelectCurveCP(e) => e.finish(),
         }
     }
 }
 
+#[derive(Debug)]
+struct MoveColorBar {
+    start_pointer: Position<ScreenSpace>,
+    start_position: Position<ScreenSpace>,
+    position: Position<ScreenSpace>,
+}
+
+impl MoveColorBar {
+    fn new(event: PointerInput, position: Position<ScreenSpace>) -> Self {
+        let pointer =
+            Position::<ScreenSpace>::new((event.offset_x() as f32, event.offset_y() as f32));
+
+        Self {
+            start_pointer: pointer,
+            start_position: position,
+            position,
+        }
+    }
+
+    fn update(&mut self, event: PointerInput) -> Event {
+        let pointer =
+            Position::<ScreenSpace>::new((event.offset_x() as f32, event.offset_y() as f32));
+        let offset = pointer - self.start_pointer;
+        self.position = self.start_position + offset;
+
+        Event::REDRAW
+    }
+
+    fn finish(self) -> Event {
+        Event::NONE
+    }
+}
+
+/// Width of the edge region of the view, in world space, that triggers the
+/// auto-scrolling.
+const AUTO_SCROLL_EDGE_WIDTH: f32 = 0.25;
+
+/// Maximum distance, in world space, the view is scrolled per frame.
+const AUTO_SCROLL_MAX_SPEED: f32 = 0.05;
+
+/// Returns the distance the view is scrolled per frame, while the pointer is
+/// at the world space `offset`. The speed increases towards the edges of the
+/// view `window`.
+fn auto_scroll_speed(offset: f32, window: (f32, f32)) -> f32 {
+    let (start, end) = window;
+    if offset < start + AUTO_SCROLL_EDGE_WIDTH {
+        let t = (start + AUTO_SCROLL_EDGE_WIDTH - offset) / AUTO_SCROLL_EDGE_WIDTH;
+        -AUTO_SCROLL_MAX_SPEED * t.min(1.0)
+    } else if offset > end - AUTO_SCROLL_EDGE_WIDTH {
+        let t = (offset - end + AUTO_SCROLL_EDGE_WIDTH) / AUTO_SCROLL_EDGE_WIDTH;
+        AUTO_SCROLL_MAX_SPEED * t.min(1.0)
+    } else {
+        0.0
+    }
+}
+
+/// Limits the scrolling of the view `window` by `delta`, such that it does
+/// not leave the `bounds` containing all axes. A window that already lies
+/// partially outside of the bounds may only be scrolled back towards them.
+fn clamp_view_scroll(window: (f32, f32), bounds: (f32, f32), delta: f32) -> f32 {
+    let min_delta = (bounds.0 - window.0).min(0.0);
+    let max_delta = (bounds.1 - window.1).max(0.0);
+    delta.clamp(min_delta, max_delta)
+}
+
 #[derive(Debug)]
 struct MoveAxis {
     axis: Rc<Axis>,
@@ -162,12 +353,14 @@ struct MoveAxis {
     active_label_idx: Option<usize>,
     start_position: Position<ScreenSpace>,
     interaction_mode: InteractionMode,
+    scroll_speed: f32,
+    remove: bool,
 }
 
 impl MoveAxis {
     fn new(
         axis: Rc<Axis>,
-        event: PointerEvent,
+        event: PointerInput,
         active_label_idx: Option<usize>,
         interaction_mode: InteractionMode,
     ) -> Self {
@@ -180,12 +373,15 @@ fn new(
             active_label_idx,
             start_position: position,
             interaction_mode,
+            scroll_speed: 0.0,
+            remove: false,
         }
     }
 
-    fn update(&mut self, event: PointerEvent) -> Event {
-        let offset = {
-            let position = Position::<ScreenSpace>::new((event.offset_x() as f32, 0.0));
+    fn update(&mut self, event: PointerInput) -> Event {
+        let (position, num_visible_axes, view_window) = {
+            let position =
+                Position::<ScreenSpace>::new((event.offset_x() as f32, event.offset_y() as f32));
             if position.x != self.start_position.x {
                 self.moved = true;
             }
@@ -193,9 +389,74 @@ fn update(&mut self, event: PointerEvent) -> Event {
             let axes = self.axis.axes();
             let axes = axes.borrow();
             let position = position.transform(&axes.space_transformer());
-            position.x.clamp(-0.5, axes.num_visible_axes() as f32)
+            (position, axes.num_visible_axes(), axes.view_window())
         };
+        let offset = position.x;
+        let (min_offset, max_offset) = Self::offset_range(num_visible_axes);
+
+        // Dragging the label above or below the axis marks it for removal.
+        let enable_removal = num_visible_axes > 1
+            && matches!(
+                self.interaction_mode,
+                InteractionMode::Restricted | InteractionMode::Full
+            );
+        self.remove = if enable_removal {
+            let position = position.transform(&self.axis.space_transformer());
+            let (axis_start, _) = self.axis.axis_line_range();
+            let min_label_start = self.axis.min_label_position().y.max(0.0);
+            let top = self.axis.label_bounding_box().end().y;
+            let bottom = min_label_start.lerp(axis_start.y, 0.5);
+            position.y > top || position.y < bottom
+        } else {
+            false
+        };
+        if self.remove {
+            self.moved = true;
+        }
 
+        // Scroll the view towards the edge, if the pointer is near it.
+        self.scroll_speed = auto_scroll_speed(offset, view_window);
+
+        self.move_to(offset.clamp(min_offset, max_offset))
+    }
+
+    /// Scrolls the view of a zoomed plot, while the pointer is near its edge.
+    /// The axis is moved along with the view, such that it stays below the
+    /// pointer.
+    fn tick(&mut self, reduced_motion: bool) -> Event {
+        if self.scroll_speed == 0.0 {
+            return Event::NONE;
+        }
+        let speed = if reduced_motion {
+            AUTO_SCROLL_MAX_SPEED.copysign(self.scroll_speed)
+        } else {
+            self.scroll_speed
+        };
+
+        let (delta, (min_offset, max_offset)) = {
+            let axes = self.axis.axes();
+            let axes = axes.borrow();
+            let (start, end) = axes.view_window();
+            let delta = clamp_view_scroll((start, end), axes.default_view_window(), speed);
+            if delta != 0.0 {
+                axes.set_view_window(Some((start + delta, end + delta)));
+            }
+            (delta, Self::offset_range(axes.num_visible_axes()))
+        };
+        if delta == 0.0 {
+            return Event::NONE;
+        }
+
+        let offset = self.axis.world_offset() + delta;
+        self.moved = true;
+        self.move_to(offset.clamp(min_offset, max_offset)) | Event::VIEW_CHANGE
+    }
+
+    fn offset_range(num_visible_axes: usize) -> (f32, f32) {
+        (-0.5, num_visible_axes as f32)
+    }
+
+    fn move_to(&mut self, offset: f32) -> Event {
         self.axis.set_world_offset(offset);
         let bounding_box = self
             .axis
@@ -231,11 +492,64 @@ fn update(&mut self, event: PointerEvent) -> Event {
         Event::AXIS_POSITION_CHANGE
     }
 
-    fn finish(self) -> Event {
+    fn insertion_offset(&self) -> Option<f32> {
         if let Some(left) = self.axis.left_neighbor() {
-            self.axis.set_world_offset(left.world_offset() + 1.0);
-        } else if let Some(right) = self.axis.right_neighbor() {
-            self.axis.set_world_offset(right.world_offset() - 1.0);
+            Some(left.world_offset() + 1.0)
+        } else {
+            self.axis
+                .right_neighbor()
+                .map(|right| right.world_offset() - 1.0)
+        }
+    }
+
+    fn removal_marker(&self) -> Option<Aabb<WorldSpace>> {
+        if !self.remove {
+            return None;
+        }
+
+        let bounding_box = self
+            .axis
+            .bounding_box(self.active_label_idx)
+            .transform(&self.axis.space_transformer());
+        Some(bounding_box)
+    }
+
+    fn insertion_marker(&self) -> Option<(Position<WorldSpace>, Position<WorldSpace>)> {
+        if !self.moved || self.remove {
+            return None;
+        }
+
+        let offset = self.insertion_offset()?;
+        let (start, end) = self.axis.axis_line_range();
+        let start = start.transform(&self.axis.space_transformer());
+        let end = end.transform(&self.axis.space_transformer());
+
+        Some((
+            Position::new((offset, start.y)),
+            Position::new((offset, end.y)),
+        ))
+    }
+
+    fn finish(self) -> Event {
+        if self.remove {
+            let axes = self.axis.axes();
+            let mut axes = axes.borrow_mut();
+            let key = self.axis.key();
+            let order = axes
+                .axes_order()
+                .into_vec()
+                .into_iter()
+                .filter(|ax| **ax != *key)
+                .collect::<Vec<_>>();
+            axes.set_axes_order(&order);
+
+            return Event::AXIS_POSITION_CHANGE
+                | Event::AXIS_ORDER_CHANGE
+                | Event::AXIS_VISIBILITY_CHANGE;
+        }
+
+        if let Some(offset) = self.insertion_offset() {
+            self.axis.set_world_offset(offset);
         }
 
         let enable_state_change = matches!(
@@ -289,7 +603,7 @@ fn new(
         }
     }
 
-    fn update(&mut self, event: PointerEvent) -> Event {
+    fn update(&mut self, event: PointerInput) -> Event {
         if event.movement_y() == 0 {
             return Event::NONE;
         }
@@ -342,10 +656,23 @@ fn finish(self) -> Event {
     }
 }
 
+/// Returns the normalized range of the axis covered by the histogram bins
+/// between `start_bin` and `end_bin`, inclusive.
+fn bins_range(start_bin: usize, end_bin: usize, num_bins: usize) -> [f32; 2] {
+    let (first, last) = (start_bin.min(end_bin), start_bin.max(end_bin));
+    [
+        first as f32 / num_bins as f32,
+        (last + 1) as f32 / num_bins as f32,
+    ]
+}
+
 #[derive(Debug)]
 struct CreateBrush {
     axis: Rc<Axis>,
     start_axis_value: f32,
+    /// Starting bin and number of bins, if the brush snaps to the bins of
+    /// the histogram.
+    bins: Option<(usize, usize)>,
     active_label_idx: usize,
     easing_type: EasingType,
     selection: Selection,
@@ -355,7 +682,7 @@ struct CreateBrush {
 impl CreateBrush {
     fn new(
         axis: Rc<Axis>,
-        event: PointerEvent,
+        event: PointerInput,
         active_label_idx: usize,
         easing_type: EasingType,
     ) -> Self {
@@ -384,10 +711,37 @@ fn new(
             selection,
             curve_builder,
             start_axis_value: axis_value,
+            bins: None,
         }
     }
 
-    fn update(&mut self, event: PointerEvent) -> Event {
+    fn new_spanning_bins(
+        axis: Rc<Axis>,
+        bin: usize,
+        num_bins: usize,
+        active_label_idx: usize,
+        easing_type: EasingType,
+    ) -> Self {
+        let curve_builder = axis
+            .borrow_selection_curve_builder(active_label_idx)
+            .clone();
+
+        // A click without dragging selects the starting bin.
+        let [start, end] = bins_range(bin, bin, num_bins);
+        let selection = Selection::new([start, 1.0], [end, 1.0]);
+
+        Self {
+            axis,
+            active_label_idx,
+            easing_type,
+            selection,
+            curve_builder,
+            start_axis_value: start,
+            bins: Some((bin, num_bins)),
+        }
+    }
+
+    fn update(&mut self, event: PointerInput) -> Event {
         if event.movement_y() == 0 {
             return Event::NONE;
         }
@@ -404,12 +758,19 @@ fn update(&mut self, event: PointerEvent) -> Event {
             position.y.inv_lerp(axis_start.y, axis_end.y)
         };
 
-        if axis_value <= self.start_axis_value {
+        if let Some((start_bin, num_bins)) = self.bins {
+            let bin = axis::histogram_bin(axis_value.clamp(0.0, 1.0), num_bins)
+                .expect("the clamped value should lie on the axis");
+            let [start, end] = bins_range(start_bin, bin, num_bins);
+            self.selection = Selection::new([start, 1.0], [end, 1.0]);
+        } else if axis_value <= self.start_axis_value {
             self.selection.set_control_point_x(0, axis_value);
         } else {
             self.selection.set_control_point_x(1, axis_value);
         }
 
+        // Only the curve reflects the new brush, the brush itself is drawn
+        // as a preview until it is committed.
         let mut curve_builder = self.curve_builder.clone();
         curve_builder.add_selection(self.selection.clone());
 
@@ -417,13 +778,20 @@ fn update(&mut self, event: PointerEvent) -> Event {
         self.axis
             .borrow_selection_curve_mut(self.active_label_idx)
             .set_curve(curve_builder.build(datums_range, self.easing_type));
-        *self
-            .axis
-            .borrow_selection_curve_builder_mut(self.active_label_idx) = curve_builder;
 
         Event::SELECTIONS_CHANGE
     }
 
+    fn preview_selection(&self) -> Option<(Rc<Axis>, [f32; 2])> {
+        let start = self.selection.control_point_x(0);
+        let end = self.selection.control_point_x(1);
+        if start == end {
+            return None;
+        }
+
+        Some((self.axis.clone(), [start, end]))
+    }
+
     fn finish(self) -> Event {
         let mut curve_builder = self.curve_builder;
         let datums_range = self.axis.visible_data_range_normalized().into();
@@ -477,7 +845,7 @@ fn new(
         }
     }
 
-    fn update(&mut self, event: PointerEvent) -> Event {
+    fn update(&mut self, event: PointerInput) -> Event {
         self.moved |= event.movement_y() != 0;
 
         let offset = {
@@ -562,6 +930,24 @@ enum SelectCP {
         selection: Selection,
         curve_builder: SelectionCurveBuilder,
     },
+    ResizedSymmetric {
+        axis: Rc<Axis>,
+        selection_idx: usize,
+        center: f32,
+        original: Selection,
+        active_label_idx: usize,
+        easing_type: EasingType,
+        selection: Selection,
+        curve_builder: SelectionCurveBuilder,
+    },
+    Moved {
+        axis: Rc<Axis>,
+        selection_idx: usize,
+        active_label_idx: usize,
+        easing_type: EasingType,
+        selection: Selection,
+        curve_builder: SelectionCurveBuilder,
+    },
     Undefined,
 }
 
@@ -591,7 +977,7 @@ fn new(
         }
     }
 
-    fn update(&mut self, event: PointerEvent) -> Event {
+    fn update(&mut self, event: PointerInput) -> Event {
         if event.movement_x() == 0 && event.movement_y() == 0 {
             return Event::NONE;
         }
@@ -607,6 +993,50 @@ fn update(&mut self, event: PointerEvent) -> Event {
                 curve_builder,
                 modify_curve_value,
             } => 'block: {
+                // Dragging an edge of the selection with the shift key resizes it
+                // around its center, while the alt key moves the whole selection.
+                let is_edge = *control_point_idx == 0
+                    || *control_point_idx == selection.num_control_points() - 1;
+                if is_edge && !*modify_curve_value && (event.shift_key() || event.alt_key()) {
+                    let this = std::mem::replace(self, Self::Undefined);
+                    let Self::Selected {
+                        axis,
+                        selection_idx,
+                        active_label_idx,
+                        easing_type,
+                        selection,
+                        curve_builder,
+                        ..
+                    } = this
+                    else {
+                        unreachable!()
+                    };
+
+                    *self = if event.alt_key() {
+                        Self::Moved {
+                            axis,
+                            selection_idx,
+                            active_label_idx,
+                            easing_type,
+                            selection,
+                            curve_builder,
+                        }
+                    } else {
+                        let [lower, upper] = selection.selection_range();
+                        Self::ResizedSymmetric {
+                            axis,
+                            selection_idx,
+                            center: (lower + upper) / 2.0,
+                            original: selection.clone(),
+                            active_label_idx,
+                            easing_type,
+                            selection,
+                            curve_builder,
+                        }
+                    };
+                    return self.update(event);
+                }
+
                 let (curve_value, axis_value) = {
                     let axes = axis.axes();
                     let axes = axes.borrow();
@@ -878,12 +1308,147 @@ fn update(&mut self, event: PointerEvent) -> Event {
                     .set_curve(curve_builder.build(datums_range, *easing_type));
                 *axis.borrow_selection_curve_builder_mut(*active_label_idx) = curve_builder;
             }
+            Self::ResizedSymmetric {
+                axis,
+                selection_idx,
+                center,
+                original,
+                active_label_idx,
+                easing_type,
+                selection,
+                curve_builder,
+            } => {
+                let axis_value = axis_value_at(axis, &event);
+
+                // Keep the resized selection inside of the axis.
+                let [lower, upper] = original.selection_range();
+                let half_width = (axis_value - *center).abs().min(*center).min(1.0 - *center);
+                let original_half_width = (upper - lower) / 2.0;
+                let factor = if original_half_width > 0.0 {
+                    half_width / original_half_width
+                } else {
+                    1.0
+                };
+
+                *selection = original.clone();
+                selection.scale(*center, factor);
+
+                let mut curve_builder = curve_builder.clone();
+                curve_builder.insert_selection(selection.clone(), *selection_idx);
+
+                let datums_range = axis.visible_data_range_normalized().into();
+                axis.borrow_selection_curve_mut(*active_label_idx)
+                    .set_curve(curve_builder.build(datums_range, *easing_type));
+                *axis.borrow_selection_curve_builder_mut(*active_label_idx) = curve_builder;
+            }
+            Self::Moved {
+                axis,
+                selection_idx,
+                active_label_idx,
+                easing_type,
+                selection,
+                curve_builder,
+            } => {
+                let offset = {
+                    let axes = axis.axes();
+                    let axes = axes.borrow();
+                    let offset = Offset::<ScreenSpace>::new((0.0, event.movement_y() as f32));
+                    let offset = offset.transform(&axes.space_transformer());
+                    let offset = offset.transform(&axis.space_transformer());
+
+                    let (axis_start, axis_end) = axis.axis_line_range();
+                    -offset.y / (axis_end.y - axis_start.y).abs()
+                };
+
+                // Keep the moved selection inside of the axis.
+                let [lower, upper] = selection.selection_range();
+                selection.offset(offset.clamp(-lower, 1.0 - upper));
+
+                let mut curve_builder = curve_builder.clone();
+                curve_builder.insert_selection(selection.clone(), *selection_idx);
+
+                let datums_range = axis.visible_data_range_normalized().into();
+                axis.borrow_selection_curve_mut(*active_label_idx)
+                    .set_curve(curve_builder.build(datums_range, *easing_type));
+                *axis.borrow_selection_curve_builder_mut(*active_label_idx) = curve_builder;
+            }
             Self::Undefined => unreachable!(),
         }
 
         Event::SELECTIONS_CHANGE
     }
 
+    fn describe(&self) -> String {
+        let (state, axis, selection_idx) = match self {
+            Self::Selected {
+                axis,
+                selection_idx,
+                ..
+            } => ("Selected", axis, selection_idx),
+            Self::DraggedSingle {
+                axis,
+                selection_idx,
+                ..
+            } => ("DraggedSingle", axis, selection_idx),
+            Self::DraggedSymmetric {
+                axis,
+                selection_idx,
+                ..
+            } => ("DraggedSymmetric", axis, selection_idx),
+            Self::ResizedSymmetric {
+                axis,
+                selection_idx,
+                ..
+            } => ("ResizedSymmetric", axis, selection_idx),
+            Self::Moved {
+                axis,
+                selection_idx,
+                ..
+            } => ("Moved", axis, selection_idx),
+            Self::Undefined => return "SelectCP::Undefined".into(),
+        };
+        format!(
+            "SelectCP::{state} {{ axis: {}, selection: {selection_idx} }}",
+            axis.key()
+        )
+    }
+
+    fn control_point(&self) -> Option<Element> {
+        match self {
+            Self::Selected {
+                axis,
+                modify_curve_value,
+                selection_idx,
+                control_point_idx,
+                ..
+            }
+            | Self::DraggedSingle {
+                axis,
+                modify_curve_value,
+                selection_idx,
+                control_point_idx,
+                ..
+            } => {
+                let (axis, selection_idx, control_point_idx) =
+                    (axis.clone(), *selection_idx, *control_point_idx);
+                if *modify_curve_value {
+                    Some(Element::CurveControlPoint {
+                        axis,
+                        selection_idx,
+                        control_point_idx,
+                    })
+                } else {
+                    Some(Element::AxisControlPoint {
+                        axis,
+                        selection_idx,
+                        control_point_idx,
+                    })
+                }
+            }
+            _ => None,
+        }
+    }
+
     fn finish(self) -> Event {
         match self {
             Self::Selected {
@@ -929,6 +1494,23 @@ fn finish(self) -> Event {
                 selection,
                 mut curve_builder,
                 ..
+            }
+            | Self::ResizedSymmetric {
+                axis,
+                selection_idx,
+                active_label_idx,
+                easing_type,
+                selection,
+                mut curve_builder,
+                ..
+            }
+            | Self::Moved {
+                axis,
+                selection_idx,
+                active_label_idx,
+                easing_type,
+                selection,
+                mut curve_builder,
             } => {
                 curve_builder.insert_selection(selection, selection_idx);
                 let datums_range = axis.visible_data_range_normalized().into();
@@ -942,3 +1524,68 @@ fn finish(self) -> Event {
         Event::SELECTIONS_CHANGE
     }
 }
+
+/// Returns the normalized value on the `axis` at the position of the pointer.
+fn axis_value_at(axis: &Axis, event: &PointerInput) -> f32 {
+    let axes = axis.axes();
+    let axes = axes.borrow();
+    let position = Position::<ScreenSpace>::new((event.offset_x() as f32, event.offset_y() as f32));
+    let position = position.transform(&axes.space_transformer());
+    let position = position.transform(&axis.space_transformer());
+
+    let (axis_start, axis_end) = axis.axis_line_range();
+    position
+        .y
+        .inv_lerp(axis_start.y, axis_end.y)
+        .clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_scroll_is_limited_to_the_edges_of_the_view() {
+        let window = (1.5, 3.5);
+        assert_eq!(auto_scroll_speed(2.5, window), 0.0);
+        assert_eq!(auto_scroll_speed(1.5, window), -AUTO_SCROLL_MAX_SPEED);
+        assert_eq!(auto_scroll_speed(3.5, window), AUTO_SCROLL_MAX_SPEED);
+        assert_eq!(auto_scroll_speed(0.0, window), -AUTO_SCROLL_MAX_SPEED);
+        assert!(auto_scroll_speed(3.4, window) > 0.0);
+        assert!(auto_scroll_speed(3.4, window) < AUTO_SCROLL_MAX_SPEED);
+    }
+
+    #[test]
+    fn view_scrolling_stays_within_the_axes() {
+        let bounds = (-0.5, 4.5);
+        assert_eq!(clamp_view_scroll((1.5, 3.5), bounds, 0.5), 0.5);
+        assert_eq!(clamp_view_scroll((1.5, 3.5), bounds, 2.0), 1.0);
+        assert_eq!(clamp_view_scroll((1.5, 3.5), bounds, -3.0), -2.0);
+        assert_eq!(clamp_view_scroll(bounds, bounds, 0.5), 0.0);
+    }
+
+    #[test]
+    fn views_outside_of_the_axes_only_scroll_back() {
+        let bounds = (-0.5, 4.5);
+        assert_eq!(clamp_view_scroll((-1.5, 0.5), bounds, -0.5), 0.0);
+        assert_eq!(clamp_view_scroll((-1.5, 0.5), bounds, 0.5), 0.5);
+    }
+
+    #[test]
+    fn histogram_bins_partition_the_axis() {
+        assert_eq!(axis::histogram_bin(0.0, 4), Some(0));
+        assert_eq!(axis::histogram_bin(0.3, 4), Some(1));
+        assert_eq!(axis::histogram_bin(1.0, 4), Some(3));
+        assert_eq!(axis::histogram_bin(1.1, 4), None);
+        assert_eq!(axis::histogram_bin(-0.1, 4), None);
+        assert_eq!(axis::histogram_bin(0.5, 0), None);
+    }
+
+    #[test]
+    fn histogram_brushes_span_whole_bins() {
+        assert_eq!(bins_range(1, 1, 4), [0.25, 0.5]);
+        assert_eq!(bins_range(1, 3, 4), [0.25, 1.0]);
+        assert_eq!(bins_range(3, 1, 4), [0.25, 1.0]);
+        assert_eq!(bins_range(0, 0, 1), [0.0, 1.0]);
+    }
+}
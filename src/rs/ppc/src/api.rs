@@ -0,0 +1,1084 @@
+//! Typed bindings of the public API.
+//!
+//! The types in this module are exchanged with the host application as
+//! plain JavaScript objects, and are described by the generated TypeScript
+//! declarations, such that framework specific wrappers can remain thin.
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use wasm_bindgen::prelude::*;
+
+use crate::wasm_bridge::{
+    Annotation, AnnotationAnchor, AnnotationKind, AxisDef, AxisTicksDef, Brush, CanvasAlphaMode,
+    ColorBarEdge, ColorBarOptions, ColorBarTickFormat, ColorDescription, LabelMeanMode,
+    PointerInput, StateTransaction, StateTransactionBuilder,
+};
+
+#[wasm_bindgen]
+extern "C" {
+    /// Callback receiving the changes of the plot.
+    #[wasm_bindgen(extends = js_sys::Function, typescript_type = "(diffs: PlotDiff[]) => void")]
+    pub type PlotDiffCallback;
+
+    /// Plain object with the contents of a [`DescriptionValue`].
+    #[wasm_bindgen(extends = js_sys::Object, typescript_type = "DescriptionValue")]
+    pub type DescriptionObject;
+
+    /// Plain object with the contents of a [`CurvesValue`].
+    #[wasm_bindgen(extends = js_sys::Object, typescript_type = "CurvesValue")]
+    pub type CurvesObject;
+
+    /// Plain object with the contents of a [`LabelHistoryValue`].
+    #[wasm_bindgen(extends = js_sys::Object, typescript_type = "LabelHistoryValue")]
+    pub type LabelHistoryObject;
+
+    /// Plain object with the values of a data point, keyed by the axes.
+    #[wasm_bindgen(extends = js_sys::Object, typescript_type = "Record<string, number | null>")]
+    pub type DatumObject;
+
+    /// Array with a [`DatumObject`] for each requested data point, or
+    /// `undefined` for the indices outside of the data.
+    #[wasm_bindgen(
+        extends = js_sys::Array,
+        typescript_type = "(Record<string, number | null> | undefined)[]"
+    )]
+    pub type DataObject;
+}
+
+/// Definition of an axis.
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct AxisSpec {
+    pub label: String,
+    #[serde(default)]
+    #[tsify(optional)]
+    pub data_points: Vec<f32>,
+    #[serde(default)]
+    #[tsify(optional)]
+    pub range: Option<(f32, f32)>,
+    #[serde(default)]
+    #[tsify(optional)]
+    pub visible_range: Option<(f32, f32)>,
+    #[serde(default)]
+    #[tsify(optional)]
+    pub tick_positions: Option<Vec<f32>>,
+    #[serde(default)]
+    #[tsify(optional)]
+    pub tick_labels: Option<Vec<String>>,
+    /// Number of data points in equally sized bins spanning the range.
+    #[serde(default)]
+    #[tsify(optional)]
+    pub histogram: Option<Vec<f32>>,
+}
+
+/// Color in one of the supported color spaces.
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorSpec {
+    #[tsify(type = "'srgb' | 'xyz' | 'cie_lab' | 'cie_lch' | 'oklab' | 'oklch'")]
+    pub color_space: String,
+    pub values: Vec<f32>,
+}
+
+/// Definition of a label.
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelSpec {
+    #[serde(default)]
+    #[tsify(optional)]
+    pub color: Option<ColorSpec>,
+    #[serde(default)]
+    #[tsify(optional)]
+    pub selection_bounds: Option<(f32, f32)>,
+    #[serde(default)]
+    #[tsify(optional, type = "'linear' | 'in' | 'out' | 'inout'")]
+    pub easing: Option<String>,
+    #[serde(default)]
+    #[tsify(optional)]
+    pub group: Option<String>,
+    #[serde(default)]
+    #[tsify(optional)]
+    pub visible: Option<bool>,
+}
+
+/// Definition of a group of labels.
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelGroupSpec {
+    #[serde(default)]
+    #[tsify(optional)]
+    pub color: Option<ColorSpec>,
+}
+
+/// A brush on an axis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct BrushSpec {
+    pub control_points: Vec<(f32, f32)>,
+    pub main_segment_idx: usize,
+}
+
+/// Brushes of each label, indexed by the label and the axis.
+pub type BrushesSpec = BTreeMap<String, BTreeMap<String, Vec<BrushSpec>>>;
+
+/// Probability curve replacing the brushes of a label on an axis.
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[serde(untagged)]
+pub enum CurveSpec {
+    /// Samples, evenly spaced over the data range of the axis.
+    Samples(Vec<f32>),
+    Segments(Vec<CurveSegmentDescription>),
+}
+
+/// Placement of the color bar.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub enum ColorBarPlacementSpec {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// Floats above the plot, with the top-left corner at the position.
+    Floating {
+        x: f32,
+        y: f32,
+    },
+}
+
+/// Format of the tick labels of the color bar.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub enum ColorBarTickFormatSpec {
+    Automatic,
+    Number,
+    Percent,
+}
+
+/// Ticks and unit of the color bar.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ColorBarOptionsSpec {
+    #[tsify(optional)]
+    pub tick_count: Option<u32>,
+    /// Positions of the ticks on the color scale, in the range `[0, 1]`.
+    #[tsify(optional)]
+    pub tick_positions: Option<Vec<f32>>,
+    #[tsify(optional)]
+    pub tick_format: Option<ColorBarTickFormatSpec>,
+    #[tsify(optional)]
+    pub unit: Option<String>,
+}
+
+/// Compositing of the plot with the page.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub enum CanvasAlphaModeSpec {
+    Opaque,
+    Premultiplied,
+}
+
+/// Display of the weighted mean of the values selected by each label.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub enum LabelMeanModeSpec {
+    Hidden,
+    Mean,
+    MeanWithDeviation,
+}
+
+/// A static shaded band on an axis.
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct AxisRegionSpec {
+    pub axis: String,
+    pub range: (f32, f32),
+    #[serde(default)]
+    #[tsify(optional)]
+    pub label: Option<String>,
+    #[serde(default)]
+    #[tsify(optional)]
+    pub color: Option<ColorSpec>,
+}
+
+/// Shape of an annotation.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub enum AnnotationKindSpec {
+    Text,
+    Arrow,
+    Marker,
+}
+
+/// Anchor of an annotation.
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub enum AnnotationAnchorSpec {
+    Value {
+        axis: String,
+        value: f32,
+    },
+    /// Value of the datum with the index `index` on the axis.
+    Datum {
+        axis: String,
+        index: usize,
+    },
+}
+
+/// A note anchored to the data coordinates of the plot.
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationSpec {
+    pub kind: AnnotationKindSpec,
+    pub anchor: AnnotationAnchorSpec,
+    #[serde(default)]
+    #[tsify(optional)]
+    pub text: String,
+    /// Offset of the text from the anchor, in CSS pixels.
+    #[serde(default)]
+    #[tsify(optional)]
+    pub offset: Option<(f32, f32)>,
+}
+
+/// Easing of the animated visible ranges of a story step.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub enum StoryEasingSpec {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+/// A step of a story, applying a transaction to the plot.
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryStepSpec {
+    pub transaction: TransactionSpec,
+    /// Time to wait before applying the step, in milliseconds.
+    #[serde(default)]
+    #[tsify(optional)]
+    pub delay: f64,
+    /// Duration of the transition of the visible ranges, in milliseconds.
+    #[serde(default)]
+    #[tsify(optional)]
+    pub duration: f64,
+    #[serde(default)]
+    #[tsify(optional)]
+    pub easing: StoryEasingSpec,
+}
+
+/// Scripted sequence of transactions, played back by [`crate::story::play_story`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Tsify)]
+#[tsify(from_wasm_abi)]
+#[serde(rename_all = "camelCase")]
+pub struct StorySpec {
+    pub steps: Vec<StoryStepSpec>,
+}
+
+/// Declarative description of a state transaction.
+///
+/// Can be converted into a [`StateTransaction`] with [`transaction_from_spec`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Tsify)]
+#[tsify(from_wasm_abi)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TransactionSpec {
+    #[tsify(optional)]
+    pub remove_axes: Vec<String>,
+    #[tsify(optional)]
+    pub add_axes: BTreeMap<String, AxisSpec>,
+    /// Replaces the data points of existing axes, retaining their brushes.
+    #[tsify(optional)]
+    pub axis_points: BTreeMap<String, Vec<f32>>,
+    #[tsify(optional)]
+    pub visible_ranges: BTreeMap<String, (f32, f32)>,
+    /// Jitter amplitudes of the lines, indexed by the axis.
+    #[tsify(optional)]
+    pub axis_jitter: BTreeMap<String, f32>,
+    /// Replaces the reference dataset, indexed by the axis.
+    #[tsify(optional)]
+    pub reference_points: Option<BTreeMap<String, Vec<f32>>>,
+    #[tsify(optional)]
+    pub order: Option<Vec<String>>,
+    #[tsify(optional)]
+    pub remove_label_groups: Vec<String>,
+    #[tsify(optional)]
+    pub add_label_groups: BTreeMap<String, LabelGroupSpec>,
+    #[tsify(optional)]
+    pub remove_labels: Vec<String>,
+    #[tsify(optional)]
+    pub add_labels: BTreeMap<String, LabelSpec>,
+    #[tsify(optional)]
+    pub active_label: Option<String>,
+    #[tsify(optional)]
+    pub brushes: Option<BrushesSpec>,
+    /// Imported probability curves, indexed by the label and the axis.
+    #[tsify(optional)]
+    pub curves: BTreeMap<String, BTreeMap<String, CurveSpec>>,
+    #[tsify(optional)]
+    pub color_bar_visible: Option<bool>,
+    #[tsify(optional)]
+    pub color_bar_placement: Option<ColorBarPlacementSpec>,
+    #[tsify(optional)]
+    pub color_bar_options: Option<ColorBarOptionsSpec>,
+    /// Height of the header slots above the axes, in `rem`.
+    #[tsify(optional)]
+    pub axis_header_height: Option<f32>,
+    /// Width of the curve area of the expanded axes.
+    #[tsify(optional)]
+    pub curve_area_width: Option<f32>,
+    #[tsify(optional)]
+    pub all_selections_visible: Option<bool>,
+    #[tsify(optional)]
+    pub label_agreement_metrics: Option<bool>,
+    #[tsify(optional)]
+    pub linear_blending: Option<bool>,
+    #[tsify(optional)]
+    pub canvas_alpha_mode: Option<CanvasAlphaModeSpec>,
+    #[tsify(optional)]
+    pub remove_axis_regions: Vec<String>,
+    #[tsify(optional)]
+    pub add_axis_regions: BTreeMap<String, AxisRegionSpec>,
+    #[tsify(optional)]
+    pub flow_ribbons: Option<bool>,
+    #[tsify(optional)]
+    pub label_mean_mode: Option<LabelMeanModeSpec>,
+    #[tsify(optional)]
+    pub remove_annotations: Vec<String>,
+    #[tsify(optional)]
+    pub add_annotations: BTreeMap<String, AnnotationSpec>,
+}
+
+/// Converts a declarative transaction into a [`StateTransaction`].
+#[wasm_bindgen(js_name = transactionFromSpec)]
+pub fn transaction_from_spec(spec: TransactionSpec) -> StateTransaction {
+    let mut builder = StateTransactionBuilder::new();
+
+    for axis in spec.remove_axes {
+        builder.remove_axis(axis);
+    }
+
+    for (key, axis) in spec.add_axes {
+        let ticks = axis.tick_positions.map(|positions| {
+            let mut ticks = AxisTicksDef::new();
+            for position in positions {
+                ticks.add_tick(position);
+            }
+            for label in axis.tick_labels.iter().flatten() {
+                ticks.add_label(label);
+            }
+            ticks
+        });
+
+        let mut axis_def = AxisDef::new(
+            &key,
+            &axis.label,
+            axis.data_points.into_boxed_slice(),
+            axis.range
+                .map(|(min, max)| Box::new([min, max]) as Box<[f32]>),
+            axis.visible_range
+                .map(|(min, max)| Box::new([min, max]) as Box<[f32]>),
+            ticks,
+        );
+        if let Some(histogram) = axis.histogram {
+            axis_def.with_histogram(histogram.into_boxed_slice());
+        }
+        builder.add_axis(axis_def);
+    }
+
+    for (axis, points) in spec.axis_points {
+        builder.set_axis_points(axis, points.into_boxed_slice());
+    }
+
+    for (axis, (min, max)) in spec.visible_ranges {
+        builder.set_visible_range(axis, min, max);
+    }
+
+    for (axis, amplitude) in spec.axis_jitter {
+        builder.set_axis_jitter(axis, amplitude);
+    }
+
+    if let Some(reference_points) = spec.reference_points {
+        builder.clear_reference_data();
+        for (axis, points) in reference_points {
+            builder.set_reference_points(axis, points.into_boxed_slice());
+        }
+    }
+
+    if let Some(order) = spec.order {
+        builder.set_custom_axis_order(order.into_boxed_slice());
+    }
+
+    for group in spec.remove_label_groups {
+        builder.remove_label_group(group);
+    }
+
+    for (id, group) in spec.add_label_groups {
+        let color = group
+            .color
+            .map(|color| ColorDescription::new(&color.color_space, &color.values));
+        builder.add_label_group(id, color);
+    }
+
+    for label in spec.remove_labels {
+        builder.remove_label(label);
+    }
+
+    for (id, label) in spec.add_labels {
+        let color = label
+            .color
+            .map(|color| ColorDescription::new(&color.color_space, &color.values));
+        let (start, end) = label.selection_bounds.unwrap_or((0.0, 0.0));
+        builder.add_label(
+            id.clone(),
+            color,
+            label.selection_bounds.is_some(),
+            start,
+            end,
+            label.easing,
+        );
+        if let Some(visible) = label.visible {
+            builder.set_label_visibility(id.clone(), visible);
+        }
+        if let Some(group) = label.group {
+            builder.set_label_group(id, Some(group));
+        }
+    }
+
+    if let Some(id) = spec.active_label {
+        builder.switch_active_label(Some(id));
+    }
+
+    if let Some(brushes) = spec.brushes {
+        let brushes = brushes
+            .into_iter()
+            .map(|(label, axes)| {
+                let axes = axes
+                    .into_iter()
+                    .map(|(axis, brushes)| (axis, brushes.into_iter().map(Into::into).collect()))
+                    .collect();
+                (label, axes)
+            })
+            .collect();
+        builder.set_brush_map(brushes);
+    }
+
+    for (label, axes) in spec.curves {
+        for (axis, curve) in axes {
+            match curve {
+                CurveSpec::Samples(samples) => {
+                    builder.import_curve_samples(label.clone(), axis, samples.into_boxed_slice());
+                }
+                CurveSpec::Segments(segments) => {
+                    let bounds = segments.iter().flat_map(|s| [s.bounds.0, s.bounds.1]);
+                    let t_ranges = segments.iter().flat_map(|s| [s.t_range.0, s.t_range.1]);
+                    let coefficients = segments.iter().flat_map(|s| s.coefficients);
+                    builder.import_curve_segments(
+                        label.clone(),
+                        axis,
+                        bounds.collect(),
+                        t_ranges.collect(),
+                        coefficients.collect(),
+                    );
+                }
+            }
+        }
+    }
+
+    if let Some(visible) = spec.color_bar_visible {
+        builder.set_color_bar_visibility(visible);
+    }
+
+    if let Some(placement) = spec.color_bar_placement {
+        match placement {
+            ColorBarPlacementSpec::Left => builder.set_color_bar_edge(ColorBarEdge::Left),
+            ColorBarPlacementSpec::Right => builder.set_color_bar_edge(ColorBarEdge::Right),
+            ColorBarPlacementSpec::Top => builder.set_color_bar_edge(ColorBarEdge::Top),
+            ColorBarPlacementSpec::Bottom => builder.set_color_bar_edge(ColorBarEdge::Bottom),
+            ColorBarPlacementSpec::Floating { x, y } => builder.set_color_bar_floating(x, y),
+        }
+    }
+
+    if let Some(options) = spec.color_bar_options {
+        let tick_format = match options.tick_format {
+            None | Some(ColorBarTickFormatSpec::Automatic) => ColorBarTickFormat::Automatic,
+            Some(ColorBarTickFormatSpec::Number) => ColorBarTickFormat::Number,
+            Some(ColorBarTickFormatSpec::Percent) => ColorBarTickFormat::Percent,
+        };
+        builder.set_color_bar_options(ColorBarOptions {
+            tick_count: options.tick_count,
+            tick_positions: options.tick_positions,
+            tick_format,
+            unit: options.unit,
+        });
+    }
+
+    if let Some(height) = spec.axis_header_height {
+        builder.set_axis_header_height(height);
+    }
+
+    if let Some(width) = spec.curve_area_width {
+        builder.set_curve_area_width(width);
+    }
+
+    if let Some(visible) = spec.all_selections_visible {
+        builder.set_all_selections_visibility(visible);
+    }
+
+    if let Some(enabled) = spec.label_agreement_metrics {
+        builder.set_label_agreement_metrics(enabled);
+    }
+
+    if let Some(enabled) = spec.linear_blending {
+        builder.set_linear_blending(enabled);
+    }
+
+    if let Some(mode) = spec.canvas_alpha_mode {
+        builder.set_canvas_alpha_mode(match mode {
+            CanvasAlphaModeSpec::Opaque => CanvasAlphaMode::Opaque,
+            CanvasAlphaModeSpec::Premultiplied => CanvasAlphaMode::Premultiplied,
+        });
+    }
+
+    for id in spec.remove_axis_regions {
+        builder.remove_axis_region(id);
+    }
+
+    for (id, region) in spec.add_axis_regions {
+        let color = region
+            .color
+            .map(|color| ColorDescription::new(&color.color_space, &color.values));
+        let (start, end) = region.range;
+        builder.add_axis_region(id, region.axis, start, end, region.label, color);
+    }
+
+    if let Some(enabled) = spec.flow_ribbons {
+        builder.set_flow_ribbons(enabled);
+    }
+
+    if let Some(mode) = spec.label_mean_mode {
+        builder.set_label_mean_mode(match mode {
+            LabelMeanModeSpec::Hidden => LabelMeanMode::Hidden,
+            LabelMeanModeSpec::Mean => LabelMeanMode::Mean,
+            LabelMeanModeSpec::MeanWithDeviation => LabelMeanMode::MeanWithDeviation,
+        });
+    }
+
+    for id in spec.remove_annotations {
+        builder.remove_annotation(id);
+    }
+
+    for (id, annotation) in spec.add_annotations {
+        builder.add_annotation(id, annotation.into());
+    }
+
+    builder.build()
+}
+
+impl From<AnnotationSpec> for Annotation {
+    fn from(spec: AnnotationSpec) -> Self {
+        let kind = match spec.kind {
+            AnnotationKindSpec::Text => AnnotationKind::Text,
+            AnnotationKindSpec::Arrow => AnnotationKind::Arrow,
+            AnnotationKindSpec::Marker => AnnotationKind::Marker,
+        };
+        let anchor = match spec.anchor {
+            AnnotationAnchorSpec::Value { axis, value } => AnnotationAnchor::Value { axis, value },
+            AnnotationAnchorSpec::Datum { axis, index } => AnnotationAnchor::Datum { axis, index },
+        };
+
+        let mut annotation = Annotation::new(kind, anchor, spec.text);
+        if let Some((x, y)) = spec.offset {
+            annotation.set_offset(x, y);
+        }
+        annotation
+    }
+}
+
+impl From<&Annotation> for AnnotationSpec {
+    fn from(annotation: &Annotation) -> Self {
+        let kind = match annotation.kind {
+            AnnotationKind::Text => AnnotationKindSpec::Text,
+            AnnotationKind::Arrow => AnnotationKindSpec::Arrow,
+            AnnotationKind::Marker => AnnotationKindSpec::Marker,
+        };
+        let anchor = match &annotation.anchor {
+            AnnotationAnchor::Value { axis, value } => AnnotationAnchorSpec::Value {
+                axis: axis.clone(),
+                value: *value,
+            },
+            AnnotationAnchor::Datum { axis, index } => AnnotationAnchorSpec::Datum {
+                axis: axis.clone(),
+                index: *index,
+            },
+        };
+
+        Self {
+            kind,
+            anchor,
+            text: annotation.text.clone(),
+            offset: Some(annotation.offset),
+        }
+    }
+}
+
+impl From<BrushSpec> for Brush {
+    fn from(brush: BrushSpec) -> Self {
+        Self {
+            control_points: brush.control_points,
+            main_segment_idx: brush.main_segment_idx,
+        }
+    }
+}
+
+/// Description of a visible axis.
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct AxisDescription {
+    pub key: String,
+    pub label: String,
+    pub min: f32,
+    pub max: f32,
+    pub expanded: bool,
+}
+
+/// Description of a label.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelDescription {
+    pub id: String,
+    pub num_selections: usize,
+    pub num_selected: usize,
+}
+
+/// Description of an annotation.
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct AnnotationDescription {
+    pub id: String,
+    /// Definition of the annotation, as accepted by [`TransactionSpec`].
+    pub annotation: AnnotationSpec,
+}
+
+/// Textual and structured description of the plot.
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct DescriptionValue {
+    pub text: String,
+    pub axes: Vec<AxisDescription>,
+    pub labels: Vec<LabelDescription>,
+    pub annotations: Vec<AnnotationDescription>,
+}
+
+impl DescriptionValue {
+    /// Converts the description into a plain JavaScript object.
+    pub fn to_js(&self) -> JsValue {
+        self.serialize(&serde_wasm_bindgen::Serializer::new())
+            .unwrap()
+    }
+}
+
+/// Segment of a probability curve.
+///
+/// A value `x`, normalized to the data range of the axis, lying inside of
+/// `bounds` is mapped to `t = mix(t_range[0], t_range[1], s)`, with
+/// `s = (x - bounds[0]) / (bounds[1] - bounds[0])`, and has the probability
+/// `clamp(c[0] * t^3 + c[1] * t^2 + c[2] * t + c[3], 0, 1)`.
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct CurveSegmentDescription {
+    pub bounds: (f32, f32),
+    pub t_range: (f32, f32),
+    pub coefficients: [f32; 4],
+}
+
+/// Probability curve of a label on a visible axis.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct AxisCurveDescription {
+    pub key: String,
+    /// Range used to normalize the data of the axis.
+    pub data_range: (f32, f32),
+    pub segments: Vec<CurveSegmentDescription>,
+}
+
+/// Probability curves of a label.
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct LabelCurvesDescription {
+    pub id: String,
+    pub axes: Vec<AxisCurveDescription>,
+}
+
+/// Fitted probability curves of all labels.
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct CurvesValue {
+    pub labels: Vec<LabelCurvesDescription>,
+}
+
+impl CurvesValue {
+    /// Converts the curves into a plain JavaScript object.
+    pub fn to_js(&self) -> JsValue {
+        self.serialize(&serde_wasm_bindgen::Serializer::new())
+            .unwrap()
+    }
+}
+
+/// A step of the edit history of the brushes of a label.
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct LabelHistoryStep {
+    /// Identifier of the step, which increases with each edit.
+    pub step: usize,
+    /// Time of the edit, in milliseconds since the unix epoch.
+    pub timestamp: f64,
+    pub brushes: BTreeMap<String, Vec<BrushSpec>>,
+}
+
+/// Edit history of the brushes of a label, from the oldest to the newest step.
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct LabelHistoryValue {
+    pub label: String,
+    pub steps: Vec<LabelHistoryStep>,
+}
+
+impl LabelHistoryValue {
+    /// Converts the history into a plain JavaScript object.
+    pub fn to_js(&self) -> JsValue {
+        let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+        self.serialize(&serializer).unwrap()
+    }
+}
+
+/// Semantic state of the plot, from which the state fingerprint is computed.
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct PlotStateValue {
+    pub axes: BTreeMap<String, AxisStateValue>,
+    /// Keys of the visible axes, from left to right.
+    pub order: Vec<String>,
+    pub brushes: BrushesSpec,
+    pub labels: BTreeMap<String, LabelStateValue>,
+    pub coloring: ColoringStateValue,
+}
+
+/// State of an axis, as part of a [`PlotStateValue`].
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct AxisStateValue {
+    pub label: String,
+    pub range: (f32, f32),
+    pub visible_range: (f32, f32),
+    pub hidden: bool,
+}
+
+/// State of a label, as part of a [`PlotStateValue`].
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelStateValue {
+    /// Color of the label, in the `XYZ` color space.
+    pub color: [f32; 3],
+    pub group: Option<String>,
+    pub visible: bool,
+    pub selection_bounds: (f32, f32),
+}
+
+/// Coloring of the plot, as part of a [`PlotStateValue`].
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct ColoringStateValue {
+    /// One of `constant`, `attribute`, `attribute_density`, `probability`
+    /// or `group_probability`.
+    pub mode: String,
+    /// Value of the `constant` mode.
+    pub constant: Option<f32>,
+    /// Attribute or label group of the other modes.
+    pub attribute: Option<String>,
+    /// Background color, in the `sRGB` color space with alpha.
+    pub background: [f32; 4],
+    /// Brush color, in the `XYZ` color space.
+    pub brush: [f32; 3],
+    /// Color of the unselected lines, in the `XYZ` color space with alpha.
+    pub unselected: [f32; 4],
+}
+
+impl PlotStateValue {
+    /// Computes a stable fingerprint of the state.
+    ///
+    /// The state is canonicalized by serializing it with `JSON.stringify`,
+    /// where the fields are ordered as declared, the keys of the maps are
+    /// sorted, missing options are omitted, and the numbers are widened to
+    /// double precision and written in their shortest round-trip form. The
+    /// fingerprint is the 64-bit FNV-1a hash of the UTF-8 encoded JSON,
+    /// written as 16 lowercase hexadecimal digits.
+    pub fn fingerprint(&self) -> String {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+
+        let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+        let value = self.serialize(&serializer).unwrap();
+        let json = String::from(js_sys::JSON::stringify(&value).unwrap());
+
+        let hash = json.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        });
+        format!("{hash:016x}")
+    }
+}
+
+/// Operation on the synchronized state of the plot, exchanged between the
+/// clients of a collaborative session.
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(from_wasm_abi)]
+pub struct SyncOperation {
+    /// Client which issued the operation.
+    pub client: String,
+    /// Lamport timestamp of the operation, which together with the client
+    /// identifies the operation.
+    pub clock: u32,
+    pub kind: SyncOperationKind,
+}
+
+/// Kind of a [`SyncOperation`]. Brushes are identified by the id assigned
+/// on their creation.
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SyncOperationKind {
+    CreateBrush {
+        label: String,
+        axis: String,
+        id: String,
+        brush: BrushSpec,
+    },
+    MoveBrush {
+        label: String,
+        axis: String,
+        id: String,
+        brush: BrushSpec,
+    },
+    DeleteBrush {
+        label: String,
+        axis: String,
+        id: String,
+    },
+    ReorderAxes {
+        order: Vec<String>,
+    },
+}
+
+/// Values of a data point, keyed by the axes, where missing values are `None`.
+pub type DatumValue = BTreeMap<String, Option<f32>>;
+
+/// Converts the values of the data points into a plain JavaScript array.
+pub fn data_to_js(data: &[Option<DatumValue>]) -> JsValue {
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+    data.serialize(&serializer).unwrap()
+}
+
+/// Modifier keys held during a pointer interaction.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Tsify)]
+pub struct PointerModifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl PointerModifiers {
+    pub fn of(event: &PointerInput) -> Self {
+        Self {
+            shift: event.shift_key(),
+            ctrl: event.ctrl_key(),
+            alt: event.alt_key(),
+        }
+    }
+
+    /// Returns the modifiers held in either `self` or `other`.
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            shift: self.shift || other.shift,
+            ctrl: self.ctrl || other.ctrl,
+            alt: self.alt || other.alt,
+        }
+    }
+}
+
+/// Selection probabilities of the labels that changed.
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct ProbabilitiesValue {
+    #[serde(with = "serde_wasm_bindgen::preserve")]
+    #[tsify(type = "{ [label: string]: Float32Array }")]
+    pub probabilities: js_sys::Object,
+    #[serde(with = "serde_wasm_bindgen::preserve")]
+    #[tsify(type = "{ [label: string]: BigUint64Array }")]
+    pub indices: js_sys::Object,
+    pub removals: Vec<String>,
+    /// Agreement of each pair of labels, if enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub agreements: Option<Vec<LabelAgreement>>,
+}
+
+/// Agreement of the selections of two labels.
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct LabelAgreement {
+    pub labels: (String, String),
+    /// Jaccard index of the selected data points, or `1` if both are empty.
+    pub jaccard: f32,
+    /// Pearson correlation of the probabilities, or `None` if either of
+    /// them is constant.
+    pub correlation: Option<f32>,
+}
+
+/// Frozen snapshots of the labels that changed.
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct SnapshotsValue {
+    #[serde(with = "serde_wasm_bindgen::preserve")]
+    #[tsify(type = "{ [label: string]: BigUint64Array }")]
+    pub indices: js_sys::Object,
+    pub removals: Vec<String>,
+}
+
+/// Geometry of the rendered plot, in CSS pixels relative to the canvas.
+#[derive(Debug, Clone, PartialEq, Serialize, Tsify)]
+pub struct LayoutValue {
+    pub axes: Vec<AxisLayout>,
+}
+
+/// Geometry of a visible axis.
+#[derive(Debug, Clone, PartialEq, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct AxisLayout {
+    pub key: String,
+    /// Horizontal position of the axis line.
+    pub x: f32,
+    /// Vertical positions of the bottom and top of the axis line.
+    pub line_range: (f32, f32),
+    pub ticks: Vec<TickLayout>,
+    pub label_box: LayoutBox,
+    /// Header slot above the axis, if reserved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub header_box: Option<LayoutBox>,
+}
+
+/// Vertical position of a tick on its axis line.
+#[derive(Debug, Clone, PartialEq, Serialize, Tsify)]
+pub struct TickLayout {
+    pub label: String,
+    pub y: f32,
+}
+
+/// Box with the top-left corner at `(x, y)`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Tsify)]
+pub struct LayoutBox {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Kind of a pointer event inside of a header slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub enum HeaderPointerKind {
+    Down,
+    Up,
+    Move,
+}
+
+/// Pointer event inside of the header slot of an axis.
+#[derive(Debug, Clone, Serialize, Tsify)]
+pub struct HeaderPointerValue {
+    pub axis: String,
+    pub kind: HeaderPointerKind,
+    /// Position relative to the top-left corner of the slot, in CSS pixels.
+    pub position: (f32, f32),
+    pub button: i16,
+    pub modifiers: PointerModifiers,
+}
+
+/// Minimal state of the renderer, sent with the report of a panic.
+#[derive(Debug, Clone, Default, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct StateSnapshot {
+    pub num_axes: usize,
+    pub num_visible_axes: usize,
+    pub num_data_points: usize,
+    pub labels: Vec<String>,
+    pub active_label: Option<String>,
+    /// Events handled in the last frames, oldest first.
+    pub last_events: Vec<String>,
+}
+
+/// A change of the plot, forwarded to the [`PlotDiffCallback`].
+#[derive(Debug, Clone, Serialize, Tsify)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PlotDiff {
+    AxisOrder {
+        value: Vec<String>,
+    },
+    HiddenAxes {
+        value: Vec<String>,
+    },
+    HiddenLabels {
+        value: Vec<String>,
+    },
+    Brushes {
+        value: BrushesSpec,
+        /// Modifier keys of the interaction that changed the brushes.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[tsify(optional)]
+        modifiers: Option<PointerModifiers>,
+    },
+    Probabilities {
+        value: ProbabilitiesValue,
+    },
+    Snapshots {
+        value: SnapshotsValue,
+    },
+    /// Requests the host application to edit the visible range of an axis,
+    /// e.g. after clicking its min or max label.
+    #[serde(rename_all = "camelCase")]
+    AxisRangeEditRequested {
+        axis: String,
+        range: (f32, f32),
+        visible_range: (f32, f32),
+    },
+    Description {
+        value: DescriptionValue,
+    },
+    /// Emitted whenever the geometry of the plot changes, e.g. to align
+    /// external widgets to the axes.
+    Layout {
+        value: LayoutValue,
+    },
+    /// Forwards a pointer event inside of a header slot to the host.
+    HeaderPointer {
+        value: HeaderPointerValue,
+    },
+    /// Operation performed by the user, to be forwarded to the other clients
+    /// while the operation sync is enabled.
+    Operation {
+        value: SyncOperation,
+    },
+    Diagnostic {
+        code: String,
+        message: String,
+        context: BTreeMap<String, String>,
+    },
+    /// Message logged by the renderer, forwarded while `mirrorLogs` is
+    /// enabled in the debug options.
+    Log {
+        level: String,
+        message: String,
+    },
+    /// Sent once the renderer panicked, after which it stops working.
+    Panic {
+        message: String,
+        snapshot: StateSnapshot,
+    },
+}
+
+impl PlotDiff {
+    /// Converts the diff into a plain JavaScript object.
+    pub fn to_js(&self) -> JsValue {
+        let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+        self.serialize(&serializer).unwrap()
+    }
+}
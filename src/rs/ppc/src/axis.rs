@@ -10,18 +10,19 @@
 
 use crate::{
     coordinates::{
-        Aabb, CoordinateSystem, CoordinateSystemTransformer, Length, LocalSpace, Offset, Position,
-        ScreenSpace, ScreenViewTransformer, ViewSpace, ViewWorldTransformer, WorldLocalTransformer,
-        WorldSpace,
+        self, Aabb, CoordinateSystem, CoordinateSystemTransformer, Length, LocalSpace, Offset,
+        Position, ScreenSpace, ScreenViewTransformer, ViewSpace, ViewWorldTransformer,
+        WorldLocalTransformer, WorldSpace,
     },
     lerp::{InverseLerp, Lerp},
-    selection::{SelectionCurve, SelectionCurveBuilder},
+    selection::{Selection, SelectionCurve, SelectionCurveBuilder},
 };
 
 const AXIS_LOCAL_Y_SCALE: f32 = 1.0;
 const AXIS_LINE_SIZE_REM: f32 = 0.05;
 const AXIS_LINE_PADDING_REM: f32 = 0.1;
 const AXIS_TOP_PADDING: f32 = 1.0;
+const AXIS_HEADER_HALF_WIDTH: f32 = 0.4;
 const LOCAL_AXIS_HEIGHT: f32 = 1.0;
 
 const SELECTION_LINE_SIZE_REM: f32 = 0.1;
@@ -30,57 +31,68 @@
 
 const CURVE_LINE_SIZE_REM: f32 = 0.075;
 const DATA_LINE_SIZE_REM: f32 = 0.1;
-const CONTROL_POINTS_RADIUS_REM: f32 = 0.3;
+pub const CONTROL_POINTS_RADIUS_REM: f32 = 0.3;
 
 const LABEL_PADDING_REM: f32 = 1.0;
 const LABEL_MARGIN_REM: f32 = 1.0;
 
 const TICKS_PADDING_REM: f32 = 0.5;
 
+const JITTER_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+const DEFAULT_CURVE_AREA_WIDTH: f32 = 0.4;
+
 const MIN_CURVE_T: f32 = 0.1;
 const MAX_CURVE_T: f32 = 0.95;
 
+/// Position of a tick relative to the visible range, its value and an
+/// optional user provided label.
+type TickValue = (f32, f32, Option<Rc<str>>);
+
 #[derive(Debug)]
 pub struct AxisArgs {
     label: Rc<str>,
     data: Box<[f32]>,
     range: (f32, f32),
-    min_range: (f32, f32),
+    min_range: Option<(f32, f32)>,
     visible_range: Option<(f32, f32)>,
     ticks: Option<Vec<(f32, Option<Rc<str>>)>>,
+    histogram: Option<Box<[f32]>>,
     state: AxisState,
 }
 
 impl AxisArgs {
     /// Constructs a new instance with default settings.
+    ///
+    /// `NaN` values are treated as missing values and are ignored when
+    /// computing the range of the axis.
     pub fn new(label: &str, data: Box<[f32]>) -> Self {
-        let mut data: Vec<_> = data.into();
-        data.retain(|x| !x.is_nan());
-
         let min = data
             .iter()
             .cloned()
+            .filter(|x| !x.is_nan())
             .min_by(|x, y| x.partial_cmp(y).unwrap());
         let max = data
             .iter()
             .cloned()
+            .filter(|x| !x.is_nan())
             .max_by(|x, y| x.partial_cmp(y).unwrap());
 
-        let mut range = min.zip(max).unwrap_or((0.0, 1.0));
+        let min_range = min.zip(max);
+        let mut range = min_range.unwrap_or((0.0, 1.0));
         if range.0 == range.1 {
             range.0 -= 0.5;
             range.1 += 0.5;
         }
 
-        let min_range = range;
-
         Self {
             label: label.into(),
-            data: data.into(),
+            data,
             range,
             min_range,
             visible_range: None,
             ticks: None,
+            histogram: None,
             state: AxisState::Collapsed,
         }
     }
@@ -93,23 +105,22 @@ pub fn with_range(mut self, min: f32, max: f32) -> Self {
         );
         assert!(min.is_finite(), "the minimum must be finite");
         assert!(max.is_finite(), "the maximum must be finite");
-        assert!(
-            min <= self.min_range.0 && max >= self.min_range.1,
-            "the range must be bigger or equal to the min/max of the data, min = {min}, max = {max}, range = {:?}", 
-            self.min_range
-        );
+        if let Some((data_min, data_max)) = self.min_range {
+            assert!(
+                min <= data_min && max >= data_max,
+                "the range must be bigger or equal to the min/max of the data, min = {min}, max = {max}, range = {:?}", 
+                self.min_range
+            );
+        }
 
         self.range = (min, max);
-        let (ticks_min, ticks_max) = if let Some(visible_range) = &mut self.visible_range {
+        if let Some(visible_range) = &mut self.visible_range {
             visible_range.0 = visible_range.0.clamp(self.range.0, self.range.1);
             visible_range.1 = visible_range.1.clamp(self.range.0, self.range.1);
-            *visible_range
-        } else {
-            self.range
-        };
+        }
 
         if let Some(ticks) = &mut self.ticks {
-            ticks.retain(|(x, _)| (ticks_min..=ticks_max).contains(x))
+            ticks.retain(|(x, _)| (min..=max).contains(x))
         }
 
         self
@@ -129,21 +140,23 @@ pub fn with_visible_range(mut self, min: f32, max: f32) -> Self {
             self.range
         );
 
-        if let Some(ticks) = &mut self.ticks {
-            ticks.retain(|(x, _)| (min..=max).contains(x))
-        }
-
         self.visible_range = Some((min, max));
         self
     }
 
     pub fn with_ticks(mut self, mut ticks: Vec<(f32, Option<Rc<str>>)>) -> Self {
-        let (min, max) = self.visible_range.unwrap_or(self.range);
+        let (min, max) = self.range;
         ticks.retain(|(x, _)| (min..=max).contains(x));
         self.ticks = Some(ticks);
 
         self
     }
+
+    /// Sets the histogram summarizing the distribution of the data.
+    pub fn with_histogram(mut self, histogram: Box<[f32]>) -> Self {
+        self.histogram = Some(histogram);
+        self
+    }
 }
 
 /// A PPC axis.
@@ -152,22 +165,30 @@ pub struct Axis {
     key: Rc<str>,
 
     label: Rc<str>,
-    min_label: Rc<str>,
-    max_label: Rc<str>,
+    min_label: RefCell<Rc<str>>,
+    max_label: RefCell<Rc<str>>,
 
     state: Cell<AxisState>,
+    expansion: Cell<f32>,
+    curve_area_width: Cell<f32>,
     axis_index: Cell<Option<usize>>,
 
-    data: Box<[f32]>,
-    data_density: Box<[f32]>,
-    data_normalized: Box<[f32]>,
+    data: RefCell<Box<[f32]>>,
+    data_density: RefCell<Box<[f32]>>,
+    data_normalized: RefCell<Box<[f32]>>,
 
-    data_range: (f32, f32),
-    visible_data_range: (f32, f32),
-    visible_data_range_normalized: (f32, f32),
+    data_range: Cell<(f32, f32)>,
+    histogram: Option<Box<[f32]>>,
+    visible_data_range: Cell<(f32, f32)>,
+    visible_data_range_normalized: Cell<(f32, f32)>,
+    jitter: Cell<f32>,
+    header_height: Cell<f32>,
+    category_positions: RefCell<Option<Box<[(f32, f32)]>>>,
 
-    ticks: Vec<(f32, Rc<str>)>,
-    max_tick_height: Length<LocalSpace>,
+    tick_definitions: Option<Box<[(f32, Option<Rc<str>>)]>>,
+    tick_values: RefCell<Box<[TickValue]>>,
+    ticks: RefCell<Vec<(f32, Rc<str>)>>,
+    max_tick_height: Cell<Length<LocalSpace>>,
 
     selection_curves: RefCell<Vec<SelectionCurve>>,
     curve_builders: RefCell<Vec<SelectionCurveBuilder>>,
@@ -192,6 +213,7 @@ fn new(
         world_offset: f32,
         num_labels: usize,
         axes: &Rc<RefCell<Axes>>,
+        number_format: &js_sys::Intl::NumberFormat,
         get_rem_length: Rc<dyn Fn(f32) -> (Length<LocalSpace>, Length<LocalSpace>)>,
         get_text_length: Rc<dyn Fn(&str) -> (Length<LocalSpace>, Length<LocalSpace>)>,
     ) -> Self {
@@ -200,87 +222,22 @@ fn new(
         let data_range = args.range;
         let visible_data_range = args.visible_range.unwrap_or(data_range);
         let ticks = args.ticks;
+        let histogram = args.histogram;
         let state = args.state;
 
         let data_normalized = data
             .iter()
             .map(|d| d.inv_lerp(data_range.0, data_range.1))
             .collect::<Box<[_]>>();
-
-        // Compute the density of each point by counting the number
-        // of points contained within a window. Note: This could be
-        // optimized to a complexity of O(N log N) by sorting the data
-        // first.
-        let data_density = data_normalized
-            .iter()
-            .map(|&d| {
-                const WINDOW_SIZE: f32 = 0.05;
-                let window = d - WINDOW_SIZE..=d + WINDOW_SIZE;
-                let count = data_normalized
-                    .iter()
-                    .filter(|&x| window.contains(x))
-                    .count() as f64;
-                let density = count / data_normalized.len() as f64;
-                density as f32
-            })
-            .collect::<Box<[_]>>();
+        let data_density = Self::compute_density(&data_normalized);
 
         let visible_data_range_normalized = (
             visible_data_range.0.inv_lerp(data_range.0, data_range.1),
             visible_data_range.1.inv_lerp(data_range.0, data_range.1),
         );
 
-        let locales = wasm_bindgen::JsValue::undefined().unchecked_into();
-        let options = wasm_bindgen::JsValue::undefined().unchecked_into();
-        let formatter = js_sys::Intl::NumberFormat::new(&locales, &options);
-        let format = formatter.format();
-
-        let min_num = wasm_bindgen::JsValue::from_f64(visible_data_range.0 as f64);
-        let max_num = wasm_bindgen::JsValue::from_f64(visible_data_range.1 as f64);
-        let min_label = format.call1(&formatter, &min_num).unwrap();
-        let max_label = format.call1(&formatter, &max_num).unwrap();
-
-        let min_label = min_label.as_string().unwrap().into();
-        let max_label = max_label.as_string().unwrap().into();
         let axes = Rc::downgrade(axes);
 
-        let ticks = if let Some(ticks) = ticks {
-            ticks
-                .into_iter()
-                .map(|(t, label)| {
-                    let label = label.unwrap_or_else(|| {
-                        let label_v = wasm_bindgen::JsValue::from_f64(t as f64);
-                        let label = format.call1(&formatter, &label_v).unwrap();
-                        label.as_string().unwrap().into()
-                    });
-
-                    (
-                        t.inv_lerp(visible_data_range.0, visible_data_range.1),
-                        label,
-                    )
-                })
-                .collect::<Vec<_>>()
-        } else {
-            [0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0]
-                .into_iter()
-                .filter(|t| {
-                    (visible_data_range_normalized.0..=visible_data_range_normalized.1).contains(t)
-                })
-                .map(|t| {
-                    let label_v = data_range.0.lerp(data_range.1, t);
-                    let label_v = wasm_bindgen::JsValue::from_f64(label_v as f64);
-                    let label = format.call1(&formatter, &label_v).unwrap();
-                    let label = label.as_string().unwrap().into();
-                    (t, label)
-                })
-                .collect::<Vec<_>>()
-        };
-        let max_tick_height = ticks
-            .iter()
-            .map(|(_, tick)| get_text_length(tick).1)
-            .max_by(|&l, &r| l.0.total_cmp(&r.0))
-            .unwrap_or(Length::new(0.0));
-
         let selection_curves = (0..num_labels)
             .map(|_| SelectionCurve::new(visible_data_range_normalized.into()))
             .collect();
@@ -288,21 +245,33 @@ fn new(
             .map(|_| SelectionCurveBuilder::new())
             .collect();
 
-        Self {
+        let this = Self {
             key: key.into(),
             label,
-            min_label,
-            max_label,
+            min_label: RefCell::new("".into()),
+            max_label: RefCell::new("".into()),
             state: Cell::new(state),
+            expansion: Cell::new(if state == AxisState::Expanded {
+                1.0
+            } else {
+                0.0
+            }),
+            curve_area_width: Cell::new(DEFAULT_CURVE_AREA_WIDTH),
             axis_index: Cell::new(axis_index),
-            data,
-            data_density,
-            data_normalized,
-            data_range,
-            visible_data_range,
-            visible_data_range_normalized,
-            ticks,
-            max_tick_height,
+            data: RefCell::new(data),
+            data_density: RefCell::new(data_density),
+            data_normalized: RefCell::new(data_normalized),
+            data_range: Cell::new(data_range),
+            histogram,
+            visible_data_range: Cell::new(visible_data_range),
+            visible_data_range_normalized: Cell::new(visible_data_range_normalized),
+            jitter: Cell::new(0.0),
+            header_height: Cell::new(0.0),
+            category_positions: RefCell::new(None),
+            tick_definitions: ticks.map(Vec::into_boxed_slice),
+            tick_values: RefCell::new(Box::new([])),
+            ticks: RefCell::new(Vec::new()),
+            max_tick_height: Cell::new(Length::new(0.0)),
             selection_curves: RefCell::new(selection_curves),
             curve_builders: RefCell::new(curve_builders),
             world_offset: Cell::new(world_offset),
@@ -311,9 +280,101 @@ fn new(
             axes,
             left: RefCell::new(None),
             right: RefCell::new(None),
+        };
+        *this.tick_values.borrow_mut() = this.compute_tick_values();
+        this.set_number_format(number_format);
+
+        this
+    }
+
+    /// Computes the density of each point by counting the number of points
+    /// contained within a window.
+    ///
+    /// Note: This could be optimized to a complexity of O(N log N) by sorting
+    /// the data first.
+    fn compute_density(data_normalized: &[f32]) -> Box<[f32]> {
+        data_normalized
+            .iter()
+            .map(|&d| {
+                const WINDOW_SIZE: f32 = 0.05;
+                let window = d - WINDOW_SIZE..=d + WINDOW_SIZE;
+                let count = data_normalized
+                    .iter()
+                    .filter(|&x| window.contains(x))
+                    .count() as f64;
+                let density = count / data_normalized.len() as f64;
+                density as f32
+            })
+            .collect()
+    }
+
+    /// Computes the ticks contained in the visible range.
+    ///
+    /// Ticks are stored as their position relative to the visible range,
+    /// their value and an optional user provided label. Missing labels are
+    /// generated by formatting the value with the current number format.
+    fn compute_tick_values(&self) -> Box<[TickValue]> {
+        let (min, max) = self.visible_data_range.get();
+        if let Some(positions) = &*self.category_positions.borrow() {
+            // Reordered categories are labeled at their new positions.
+            positions
+                .iter()
+                .filter(|(_, position)| (min..=max).contains(position))
+                .map(|&(value, position)| {
+                    let label = self
+                        .tick_definitions
+                        .iter()
+                        .flatten()
+                        .find(|(t, _)| *t == value)
+                        .and_then(|(_, label)| label.clone());
+                    (position.inv_lerp(min, max), value, label)
+                })
+                .collect()
+        } else if let Some(ticks) = &self.tick_definitions {
+            ticks
+                .iter()
+                .filter(|(t, _)| (min..=max).contains(t))
+                .map(|(t, label)| (t.inv_lerp(min, max), *t, label.clone()))
+                .collect()
+        } else {
+            let (start, end) = self.data_range.get();
+            [0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0]
+                .into_iter()
+                .map(|t| start.lerp(end, t))
+                .filter(|t| (min..=max).contains(t))
+                .map(|t| (t.inv_lerp(min, max), t, None))
+                .collect()
         }
     }
 
+    /// Formats the labels and ticks of the axis with the provided number format.
+    pub fn set_number_format(&self, number_format: &js_sys::Intl::NumberFormat) {
+        let format = |value: f32| -> Rc<str> {
+            let value = wasm_bindgen::JsValue::from_f64(value as f64);
+            let label = number_format.format().call1(number_format, &value).unwrap();
+            label.as_string().unwrap().into()
+        };
+
+        let (min, max) = self.visible_data_range.get();
+        *self.min_label.borrow_mut() = format(min);
+        *self.max_label.borrow_mut() = format(max);
+
+        let ticks = self
+            .tick_values
+            .borrow()
+            .iter()
+            .map(|(t, value, label)| (*t, label.clone().unwrap_or_else(|| format(*value))))
+            .collect::<Vec<_>>();
+        let max_tick_height = ticks
+            .iter()
+            .map(|(_, tick)| (self.get_text_length)(tick).1)
+            .max_by(|&l, &r| l.0.total_cmp(&r.0))
+            .unwrap_or(Length::new(0.0));
+
+        *self.ticks.borrow_mut() = ticks;
+        self.max_tick_height.set(max_tick_height);
+    }
+
     /// Fetches the key of the axis.
     pub fn key(&self) -> Rc<str> {
         self.key.clone()
@@ -326,17 +387,17 @@ pub fn label(&self) -> Rc<str> {
 
     /// Fetches the label of the minimum element.
     pub fn min_label(&self) -> Rc<str> {
-        self.min_label.clone()
+        self.min_label.borrow().clone()
     }
 
     /// Fetches the label of the maximum element.
     pub fn max_label(&self) -> Rc<str> {
-        self.max_label.clone()
+        self.max_label.borrow().clone()
     }
 
     /// Fetches the ticks and their positions.
-    pub fn ticks(&self) -> &[(f32, Rc<str>)] {
-        &self.ticks
+    pub fn ticks(&self) -> Ref<'_, [(f32, Rc<str>)]> {
+        Ref::map(self.ticks.borrow(), |x| &x[..])
     }
 
     /// Fetches the state of the axis.
@@ -354,6 +415,37 @@ pub fn is_expanded(&self) -> bool {
         matches!(self.state.get(), AxisState::Expanded)
     }
 
+    /// Returns the animated expansion of the axis, where `0` is fully
+    /// collapsed and `1` is fully expanded.
+    pub fn expansion(&self) -> f32 {
+        self.expansion.get()
+    }
+
+    /// Advances the expansion towards the state of the axis by `step`.
+    ///
+    /// Returns whether the expansion changed.
+    pub fn tick_expansion(&self, step: f32) -> bool {
+        let target = if self.is_expanded() { 1.0 } else { 0.0 };
+        let expansion = self.expansion.get();
+        if expansion == target {
+            return false;
+        }
+
+        let expansion = if expansion < target {
+            (expansion + step).min(target)
+        } else {
+            (expansion - step).max(target)
+        };
+        self.expansion.set(expansion);
+        true
+    }
+
+    /// Sets the width of the area of the probability curves of the expanded
+    /// axis, in local units.
+    pub fn set_curve_area_width(&self, width: f32) {
+        self.curve_area_width.set(width);
+    }
+
     /// Checks whether the axis is in a hidden state.
     pub fn is_hidden(&self) -> bool {
         self.axis_index.get().is_none()
@@ -385,41 +477,218 @@ pub fn axis_index(&self) -> Option<usize> {
     }
 
     /// Fetches the data of the axis.
-    #[allow(dead_code)]
-    pub fn data(&self) -> &[f32] {
-        &self.data
+    pub fn data(&self) -> Ref<'_, [f32]> {
+        Ref::map(self.data.borrow(), |x| &x[..])
     }
 
     /// Fetches the density of the data.
-    pub fn data_density(&self) -> &[f32] {
-        &self.data_density
+    pub fn data_density(&self) -> Ref<'_, [f32]> {
+        Ref::map(self.data_density.borrow(), |x| &x[..])
     }
 
     /// Fetches the normalized data of the axis.
-    pub fn data_normalized(&self) -> &[f32] {
-        &self.data_normalized
+    pub fn data_normalized(&self) -> Ref<'_, [f32]> {
+        Ref::map(self.data_normalized.borrow(), |x| &x[..])
     }
 
     /// Returns the `min` and `max` value of the data.
-    #[allow(dead_code)]
     pub fn data_range(&self) -> (f32, f32) {
-        self.data_range
+        self.data_range.get()
+    }
+
+    /// Returns the histogram of the data over the range of the axis, if
+    /// it was provided in place of, or in addition to, the data points.
+    pub fn histogram(&self) -> Option<&[f32]> {
+        self.histogram.as_deref()
+    }
+
+    /// Replaces the data of the axis.
+    ///
+    /// The range of the axis is recomputed from the new data, while the
+    /// brushes and the visible range retain their values, as far as they
+    /// are contained in the new range. The order of the categories is reset.
+    /// The selection curves must be rebuilt by the caller.
+    pub fn set_data(&self, data: Box<[f32]>, number_format: &js_sys::Intl::NumberFormat) {
+        let AxisArgs {
+            data,
+            range: (start, end),
+            ..
+        } = AxisArgs::new(&self.label, data);
+        *self.category_positions.borrow_mut() = None;
+        let (old_start, old_end) = self.data_range.replace((start, end));
+        let renormalize = |x: f32| {
+            let value = old_start.lerp(old_end, x);
+            value.inv_lerp(start, end).clamp(0.0, 1.0)
+        };
+
+        // Brushes which lie outside of the new range are dropped.
+        for builder in self.curve_builders.borrow_mut().iter_mut() {
+            let mut new_builder = SelectionCurveBuilder::new();
+            for selection in builder.selections() {
+                let control_points = selection
+                    .control_points()
+                    .iter()
+                    .map(|&(x, y)| (renormalize(x), y))
+                    .collect::<Vec<_>>();
+                if control_points[0].0 >= control_points[control_points.len() - 1].0 {
+                    continue;
+                }
+
+                new_builder.add_selection(Selection::from_control_points(
+                    control_points,
+                    selection.primary_segment_idx(),
+                ));
+            }
+            *builder = new_builder;
+        }
+
+        let data_normalized = data
+            .iter()
+            .map(|d| d.inv_lerp(start, end))
+            .collect::<Box<[_]>>();
+        *self.data_density.borrow_mut() = Self::compute_density(&data_normalized);
+        *self.data_normalized.borrow_mut() = data_normalized;
+        *self.data.borrow_mut() = data;
+
+        let (min, max) = self.visible_data_range.get();
+        let (min, max) = (min.clamp(start, end), max.clamp(start, end));
+        let visible_range = if min < max { (min, max) } else { (start, end) };
+        self.set_visible_data_range(visible_range, number_format);
     }
 
     /// Returns the `min` and `max` value of the visible data.
     #[allow(dead_code)]
     pub fn visible_data_range(&self) -> (f32, f32) {
-        self.visible_data_range
+        self.visible_data_range.get()
     }
 
     /// Returns the `min` and `max` value of the visible data, normalized in
     /// relation the the `min` and `max` of all data.
     pub fn visible_data_range_normalized(&self) -> (f32, f32) {
-        self.visible_data_range_normalized
+        self.visible_data_range_normalized.get()
+    }
+
+    /// Returns the position of a value on the axis, which only differs from
+    /// the value if the categories of the axis were reordered.
+    pub fn category_position(&self, value: f32) -> f32 {
+        let positions = self.category_positions.borrow();
+        positions
+            .as_deref()
+            .and_then(|positions| {
+                let idx = positions
+                    .binary_search_by(|(v, _)| v.total_cmp(&value))
+                    .ok()?;
+                Some(positions[idx].1)
+            })
+            .unwrap_or(value)
+    }
+
+    /// Places the categories of the axis in the provided order, from the
+    /// bottom to the top.
+    ///
+    /// The categories occupy the positions of their sorted values, such that
+    /// the range of the axis remains unchanged, and are labeled at their new
+    /// positions. The reported values of the data points are not affected.
+    /// The selection curves must be rebuilt by the caller.
+    pub fn set_category_order(&self, order: &[f32], number_format: &js_sys::Intl::NumberFormat) {
+        let mut sorted = order.to_vec();
+        sorted.sort_by(f32::total_cmp);
+        let mut positions = order.iter().copied().zip(sorted).collect::<Vec<_>>();
+        positions.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        *self.category_positions.borrow_mut() = Some(positions.into());
+
+        let (start, end) = self.data_range.get();
+        let data_normalized = self
+            .data
+            .borrow()
+            .iter()
+            .map(|&d| self.category_position(d).inv_lerp(start, end))
+            .collect::<Box<[_]>>();
+        *self.data_density.borrow_mut() = Self::compute_density(&data_normalized);
+        *self.data_normalized.borrow_mut() = data_normalized;
+
+        *self.tick_values.borrow_mut() = self.compute_tick_values();
+        self.set_number_format(number_format);
+    }
+
+    /// Sets the amplitude of the jitter of the lines, in normalized units.
+    pub fn set_jitter(&self, amplitude: f32) {
+        self.jitter.set(amplitude);
+    }
+
+    /// Sets the height of the header slot above the axis, in `rem`.
+    pub fn set_header_height(&self, height: f32) {
+        self.header_height.set(height);
+    }
+
+    /// Returns the bounding box of the header slot above the axis, if any.
+    pub fn header_bounding_box(&self) -> Option<Aabb<LocalSpace>> {
+        let height = self.header_height.get();
+        if height == 0.0 {
+            return None;
+        }
+
+        let (_, height) = (self.get_rem_length)(height);
+        let start = Position::new((-AXIS_HEADER_HALF_WIDTH, LOCAL_AXIS_HEIGHT - height.0));
+        let end = Position::new((AXIS_HEADER_HALF_WIDTH, LOCAL_AXIS_HEIGHT));
+        Some(Aabb::new(start, end))
+    }
+
+    /// Returns the padding between the top of the plot and the axis label,
+    /// which includes the header slot.
+    fn top_padding(&self) -> Length<LocalSpace> {
+        let (_, top_padding) = (self.get_rem_length)(AXIS_TOP_PADDING + self.header_height.get());
+        top_padding
+    }
+
+    /// Offsets the normalized value of a data point by the jitter of the axis.
+    ///
+    /// The offset only depends on the key of the axis and the index of the
+    /// data point, such that it remains stable across redraws and reorderings
+    /// of the axes. The result is clamped to the visible range.
+    pub fn jitter_value(&self, idx: usize, value: f32) -> f32 {
+        let amplitude = self.jitter.get();
+        if amplitude == 0.0 {
+            return value;
+        }
+
+        // Hash the key together with the index, and finalize with splitmix64.
+        let seed = self.key.bytes().fold(JITTER_SEED, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01B3)
+        });
+        let mut x = seed ^ (idx as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        x ^= x >> 31;
+
+        let t = (x >> 40) as f32 / (1u64 << 24) as f32;
+        let (start, end) = self.visible_data_range_normalized.get();
+        (value + amplitude * (2.0 * t - 1.0)).clamp(start, end)
+    }
+
+    /// Sets the `min` and `max` value of the visible data.
+    ///
+    /// The selection curves are clipped to the new range, but must be
+    /// rebuilt by the caller.
+    pub fn set_visible_data_range(
+        &self,
+        (min, max): (f32, f32),
+        number_format: &js_sys::Intl::NumberFormat,
+    ) {
+        let (start, end) = self.data_range.get();
+        let normalized = (min.inv_lerp(start, end), max.inv_lerp(start, end));
+        self.visible_data_range.set((min, max));
+        self.visible_data_range_normalized.set(normalized);
+
+        for curve in self.selection_curves.borrow_mut().iter_mut() {
+            curve.set_range(normalized.into());
+        }
+
+        *self.tick_values.borrow_mut() = self.compute_tick_values();
+        self.set_number_format(number_format);
     }
 
     /// Borrows the selection curve.
-    #[allow(dead_code)]
     pub fn borrow_selection_curve(&self, active_label_idx: usize) -> Ref<'_, SelectionCurve> {
         Ref::map(self.selection_curves.borrow(), |x| &x[active_label_idx])
     }
@@ -455,7 +724,7 @@ pub fn borrow_selection_curve_builder_mut(
     /// Signals that the axis must allocate another selection curve and selection curve builder for the new label.
     pub fn push_label(&self) {
         self.selection_curves.borrow_mut().push(SelectionCurve::new(
-            self.visible_data_range_normalized.into(),
+            self.visible_data_range_normalized.get().into(),
         ));
         self.curve_builders
             .borrow_mut()
@@ -490,8 +759,9 @@ pub fn bounding_box(&self, active_label_idx: Option<usize>) -> Aabb<LocalSpace>
             max_x = max_x.max(selections_bb.end().x);
         }
 
-        min_x = min_x.clamp(-0.4, 0.4);
-        max_x = max_x.clamp(-0.4, 0.4);
+        let min_extent = -self.curve_area_width.get().max(DEFAULT_CURVE_AREA_WIDTH);
+        min_x = min_x.clamp(min_extent, 0.4);
+        max_x = max_x.clamp(min_extent, 0.4);
 
         let start = Position::<LocalSpace>::new((min_x, 0.0));
         let end = Position::<LocalSpace>::new((max_x, 1.0));
@@ -501,11 +771,14 @@ pub fn bounding_box(&self, active_label_idx: Option<usize>) -> Aabb<LocalSpace>
 
     /// Returns the bounding box of the axis line.
     pub fn axis_line_bounding_box(&self) -> Aabb<LocalSpace> {
+        self.axis_line_hit_box(AXIS_LINE_PADDING_REM + (AXIS_LINE_SIZE_REM / 2.0))
+    }
+
+    /// Returns the bounding box of the axis line, extended by a radius in `rem`.
+    pub fn axis_line_hit_box(&self, radius: f32) -> Aabb<LocalSpace> {
         let (mut start, mut end) = self.axis_line_range();
 
-        let (axis_width, _) = (self.get_rem_length)(
-            AXIS_LINE_PADDING_REM + AXIS_LINE_PADDING_REM + AXIS_LINE_SIZE_REM,
-        );
+        let (axis_width, _) = (self.get_rem_length)(radius + radius);
         let half_width = axis_width / Length::new(2.0);
         let width_offset = Offset::<LocalSpace>::new((1.0, 0.0)) * half_width;
 
@@ -517,7 +790,7 @@ pub fn axis_line_bounding_box(&self) -> Aabb<LocalSpace> {
 
     pub fn curves_bounding_box(&self) -> Aabb<LocalSpace> {
         let start = if self.is_expanded() {
-            Position::new((-0.4, 0.0))
+            Position::new((-self.curve_area_width.get(), 0.0))
         } else {
             Position::new((0.0, 1.0))
         };
@@ -548,7 +821,7 @@ pub fn label_bounding_box(&self) -> Aabb<LocalSpace> {
         const POSITION_X: f32 = 0.0;
 
         let (label_width, label_height) = (self.get_text_length)(&self.label);
-        let (_, top_padding) = (self.get_rem_length)(AXIS_TOP_PADDING);
+        let top_padding = self.top_padding();
         let (padding_width, padding_height) = (self.get_rem_length)(AXIS_LINE_PADDING_REM);
 
         let start = Position::new((
@@ -569,7 +842,7 @@ pub fn label_bounding_box(&self) -> Aabb<LocalSpace> {
 
     pub fn curve_offset_at_curve_value(&self, curve_value: f32) -> Offset<LocalSpace> {
         let t = MIN_CURVE_T.lerp(MAX_CURVE_T, curve_value);
-        let x_offset = 0.0.lerp(-0.4, t);
+        let x_offset = 0.0.lerp(-self.curve_area_width.get(), t);
         Offset::new((x_offset, 0.0))
     }
 
@@ -586,15 +859,15 @@ pub fn selection_rank_at_position(
         &self,
         position: &Position<LocalSpace>,
         active_label_idx: usize,
+        radius: Length<LocalSpace>,
     ) -> Option<usize> {
         let curve_builders = self.curve_builders.borrow();
         let max_rank = curve_builders[active_label_idx].max_rank();
-        let (control_point_radius_w, _) = self.axes().borrow().control_points_radius_local();
 
         for i in 0..=max_rank {
             let rank_middle = self.selection_offset_at_rank(i).x;
-            let rank_start = rank_middle - control_point_radius_w.0;
-            let rank_end = rank_middle + control_point_radius_w.0;
+            let rank_start = rank_middle - radius.0;
+            let rank_end = rank_middle + radius.0;
 
             if (rank_start..=rank_end).contains(&position.x) {
                 return Some(i);
@@ -617,7 +890,7 @@ pub fn expanded_extends(&self, active_label_idx: Option<usize>) -> Aabb<LocalSpa
 
         let end_x = self.selection_offset_at_rank(max_rank).x;
 
-        let start = Position::new((-0.4, 0.0));
+        let start = Position::new((-self.curve_area_width.get(), 0.0));
         let end = Position::new((end_x, 1.0));
         Aabb::new(start, end)
     }
@@ -625,12 +898,12 @@ pub fn expanded_extends(&self, active_label_idx: Option<usize>) -> Aabb<LocalSpa
     /// Returns the range of the axis line.
     pub fn axis_line_range(&self) -> (Position<LocalSpace>, Position<LocalSpace>) {
         const POSITION_X: f32 = 0.0;
-        let (_, top_padding) = (self.get_rem_length)(AXIS_TOP_PADDING);
+        let top_padding = self.top_padding();
         let (_, label_padding) = (self.get_rem_length)(LABEL_PADDING_REM);
         let (_, label_margin) = (self.get_rem_length)(LABEL_MARGIN_REM);
 
-        let (_, min_label_height) = (self.get_text_length)(&self.min_label);
-        let (_, max_label_height) = (self.get_text_length)(&self.max_label);
+        let (_, min_label_height) = (self.get_text_length)(&self.min_label.borrow());
+        let (_, max_label_height) = (self.get_text_length)(&self.max_label.borrow());
         let (_, label_height) = (self.get_text_length)(&self.label);
 
         let start = min_label_height + label_margin;
@@ -642,8 +915,8 @@ pub fn axis_line_range(&self) -> (Position<LocalSpace>, Position<LocalSpace>) {
             - max_label_height
             - label_margin;
 
-        let start = start.lerp(end, self.visible_data_range_normalized.0);
-        let end = start.lerp(end, self.visible_data_range_normalized.1);
+        let start = start.lerp(end, self.visible_data_range_normalized.get().0);
+        let end = start.lerp(end, self.visible_data_range_normalized.get().1);
 
         (
             Position::new((POSITION_X, start.0)),
@@ -655,7 +928,7 @@ pub fn axis_line_range(&self) -> (Position<LocalSpace>, Position<LocalSpace>) {
     pub fn label_position(&self) -> Position<LocalSpace> {
         const POSITION_X: f32 = 0.0;
 
-        let (_, top_padding) = (self.get_rem_length)(AXIS_TOP_PADDING);
+        let top_padding = self.top_padding();
         let (_, label_height) = (self.get_text_length)(&self.label);
         let (_, padding_height) = (self.get_rem_length)(AXIS_LINE_PADDING_REM);
 
@@ -668,7 +941,7 @@ pub fn label_position(&self) -> Position<LocalSpace> {
     /// Returns the local position of the min label.
     pub fn min_label_position(&self) -> Position<LocalSpace> {
         let (_, label_margin) = (self.get_rem_length)(LABEL_MARGIN_REM);
-        let (_, min_label_height) = (self.get_text_length)(&self.min_label);
+        let (_, min_label_height) = (self.get_text_length)(&self.min_label.borrow());
 
         let (start, _) = self.axis_line_range();
 
@@ -678,13 +951,52 @@ pub fn min_label_position(&self) -> Position<LocalSpace> {
     /// Returns the local position of the max label.
     pub fn max_label_position(&self) -> Position<LocalSpace> {
         let (_, label_margin) = (self.get_rem_length)(LABEL_MARGIN_REM);
-        let (_, max_label_height) = (self.get_text_length)(&self.max_label);
+        let (_, max_label_height) = (self.get_text_length)(&self.max_label.borrow());
 
         let (_, end) = self.axis_line_range();
 
         Position::new((end.x, end.y + label_margin.0 + max_label_height.0))
     }
 
+    /// Returns the bounding box of the min label.
+    pub fn min_label_bounding_box(&self) -> Aabb<LocalSpace> {
+        self.range_label_bounding_box(&self.min_label.borrow(), self.min_label_position())
+    }
+
+    /// Returns the bounding box of the max label.
+    pub fn max_label_bounding_box(&self) -> Aabb<LocalSpace> {
+        self.range_label_bounding_box(&self.max_label.borrow(), self.max_label_position())
+    }
+
+    fn range_label_bounding_box(
+        &self,
+        label: &str,
+        position: Position<LocalSpace>,
+    ) -> Aabb<LocalSpace> {
+        let (label_width, label_height) = (self.get_text_length)(label);
+
+        let start = Position::new((position.x - (label_width.0 / 2.0), position.y));
+        let end = Position::new((
+            position.x + (label_width.0 / 2.0),
+            position.y + label_height.0,
+        ));
+
+        Aabb::new(start, end)
+    }
+
+    /// Returns the position of a value on the axis line, if it lies inside
+    /// of the visible range of the axis.
+    pub fn value_position(&self, value: f32) -> Option<Position<LocalSpace>> {
+        let (min, max) = self.visible_data_range();
+        let t = value.inv_lerp(min, max);
+        if !(0.0..=1.0).contains(&t) {
+            return None;
+        }
+
+        let (start, end) = self.axis_line_range();
+        Some(start.lerp(end, t))
+    }
+
     pub fn ticks_range(&self, expanded: bool) -> (Position<LocalSpace>, Position<LocalSpace>) {
         let (start, end) = self.axis_line_range();
 
@@ -701,7 +1013,7 @@ pub fn ticks_range(&self, expanded: bool) -> (Position<LocalSpace>, Position<Loc
         };
 
         let ticks_padding = (self.get_rem_length)(TICKS_PADDING_REM).0;
-        let offset = Offset::new((ticks_padding.0, self.max_tick_height.0 / 2.0));
+        let offset = Offset::new((ticks_padding.0, self.max_tick_height.get().0 / 2.0));
 
         let start = start - offset;
         let end = end - offset;
@@ -844,6 +1156,8 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 "visible_data_range_normalized",
                 &self.visible_data_range_normalized,
             )
+            .field("jitter", &self.jitter)
+            .field("category_positions", &self.category_positions)
             .field("world_offset", &self.world_offset)
             .field("axes", &self.axes)
             .field("left", &self.left)
@@ -863,6 +1177,52 @@ pub enum AxisState {
 type RemLengthFunc2<T> = dyn Fn(f32) -> (Length<T>, Length<T>);
 pub type TextLengthFunc<T> = dyn Fn(&str) -> (Length<T>, Length<T>);
 
+/// Radii used for hit testing the elements of an axis, in `rem`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HitTestRadii {
+    pub control_point: f32,
+    pub axis_line: f32,
+    pub selection_edge: f32,
+}
+
+impl HitTestRadii {
+    /// Returns the radii multiplied by `scale`.
+    pub fn scaled(self, scale: f32) -> Self {
+        Self {
+            control_point: self.control_point * scale,
+            axis_line: self.axis_line * scale,
+            selection_edge: self.selection_edge * scale,
+        }
+    }
+}
+
+impl Default for HitTestRadii {
+    fn default() -> Self {
+        Self {
+            control_point: CONTROL_POINTS_RADIUS_REM,
+            axis_line: AXIS_LINE_PADDING_REM + (AXIS_LINE_SIZE_REM / 2.0),
+            selection_edge: CONTROL_POINTS_RADIUS_REM,
+        }
+    }
+}
+
+/// Geometry of the histograms drawn to the right of the axes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistogramLayout {
+    pub num_bins: usize,
+    /// Width of the largest bar, in world units.
+    pub width: f32,
+}
+
+/// Returns the histogram bin containing the normalized axis value `t`, or
+/// `None` if the value lies outside of the axis line.
+pub fn histogram_bin(t: f32, num_bins: usize) -> Option<usize> {
+    if num_bins == 0 || !(0.0..=1.0).contains(&t) {
+        return None;
+    }
+    Some(((t * num_bins as f32) as usize).min(num_bins - 1))
+}
+
 /// A collection of axes.
 #[derive(Clone)]
 pub struct Axes {
@@ -875,6 +1235,13 @@ pub struct Axes {
     num_data_points: Option<usize>,
     next_axis_index: usize,
 
+    hit_test_radii: HitTestRadii,
+    histogram_layout: Option<HistogramLayout>,
+    control_point_radius: f32,
+    header_height: f32,
+    curve_area_width: f32,
+    number_format: js_sys::Intl::NumberFormat,
+
     coordinate_mappings: Rc<RefCell<AxesCoordinateMappings>>,
 
     get_rem_length_screen: Rc<RemLengthFunc<ScreenSpace>>,
@@ -889,12 +1256,32 @@ pub struct Axes {
 struct AxesCoordinateMappings {
     view_height: f32,
     view_width: f32,
+    view_offset: (f32, f32),
     world_width: f32,
+    world_view: Option<(f32, f32)>,
 
     view_bounding_box: Aabb<ViewSpace>,
     world_bounding_box: Aabb<WorldSpace>,
 }
 
+impl AxesCoordinateMappings {
+    /// Returns the horizontal range of the world space containing all axes.
+    fn default_view_window(&self) -> (f32, f32) {
+        (-0.5, (self.world_width - 1.5).max(0.5))
+    }
+
+    /// Returns the horizontal range of the world space shown in the view.
+    fn view_window(&self) -> (f32, f32) {
+        self.world_view
+            .unwrap_or_else(|| self.default_view_window())
+    }
+
+    fn view_world_transformer(&self) -> ViewWorldTransformer {
+        let (start, end) = self.view_window();
+        ViewWorldTransformer::new(self.view_height, self.view_width, end - start + 1.0, -start)
+    }
+}
+
 impl Axes {
     /// Constructs a new instance.
     #[allow(clippy::type_complexity)]
@@ -907,7 +1294,9 @@ pub fn new(
         let coordinate_mappings = Rc::new(RefCell::new(AxesCoordinateMappings {
             view_height,
             view_width,
+            view_offset: (0.0, 0.0),
             world_width: 1.0,
+            world_view: None,
             view_bounding_box,
             world_bounding_box: Aabb::new(Position::new((-0.5, 0.0)), Position::new((1.0, 1.0))),
         }));
@@ -928,12 +1317,7 @@ pub fn new(
                 let p1 = p1.transform(&mapper);
                 let p2 = p2.transform(&mapper);
 
-                let mapper = ViewWorldTransformer::new(
-                    mappings.view_height,
-                    mappings.view_width,
-                    mappings.world_width,
-                    0.5,
-                );
+                let mapper = mappings.view_world_transformer();
                 let p0 = p0.transform(&mapper);
                 let p1 = p1.transform(&mapper);
                 let p2 = p2.transform(&mapper);
@@ -961,12 +1345,7 @@ pub fn new(
                 let p1 = p1.transform(&mapper);
                 let p2 = p2.transform(&mapper);
 
-                let mapper = ViewWorldTransformer::new(
-                    mappings.view_height,
-                    mappings.view_width,
-                    mappings.world_width,
-                    0.5,
-                );
+                let mapper = mappings.view_world_transformer();
                 let p0 = p0.transform(&mapper);
                 let p1 = p1.transform(&mapper);
                 let p2 = p2.transform(&mapper);
@@ -999,12 +1378,7 @@ pub fn new(
                 let p1 = p1.transform(&mapper);
                 let p2 = p2.transform(&mapper);
 
-                let mapper = ViewWorldTransformer::new(
-                    mappings.view_height,
-                    mappings.view_width,
-                    mappings.world_width,
-                    0.5,
-                );
+                let mapper = mappings.view_world_transformer();
                 let p0 = p0.transform(&mapper);
                 let p1 = p1.transform(&mapper);
                 let p2 = p2.transform(&mapper);
@@ -1021,6 +1395,10 @@ pub fn new(
             })
         };
 
+        let locales = wasm_bindgen::JsValue::undefined().unchecked_into();
+        let options = wasm_bindgen::JsValue::undefined().unchecked_into();
+        let number_format = js_sys::Intl::NumberFormat::new(&locales, &options);
+
         Self {
             axes: Default::default(),
             num_visible_axes: 0,
@@ -1028,6 +1406,12 @@ pub fn new(
             visible_axis_end: None,
             num_data_points: None,
             next_axis_index: 0,
+            hit_test_radii: Default::default(),
+            histogram_layout: None,
+            control_point_radius: CONTROL_POINTS_RADIUS_REM,
+            header_height: 0.0,
+            curve_area_width: DEFAULT_CURVE_AREA_WIDTH,
+            number_format,
             coordinate_mappings,
             get_rem_length_screen,
             get_rem_length_world,
@@ -1071,6 +1455,7 @@ pub fn construct_axis(
         range: Option<(f32, f32)>,
         visible_range: Option<(f32, f32)>,
         ticks: Option<Vec<(f32, Option<Rc<str>>)>>,
+        histogram: Option<Box<[f32]>>,
         num_labels: usize,
     ) -> Rc<Axis> {
         if !std::ptr::eq(self, this.as_ptr()) {
@@ -1099,6 +1484,9 @@ pub fn construct_axis(
         if let Some(ticks) = ticks {
             args = args.with_ticks(ticks);
         }
+        if let Some(histogram) = histogram {
+            args = args.with_histogram(histogram);
+        }
 
         let axis = Rc::new(Axis::new(
             key,
@@ -1107,9 +1495,12 @@ pub fn construct_axis(
             0.0,
             num_labels,
             this,
+            &self.number_format,
             self.get_rem_length_local.clone(),
             self.get_text_length_local.clone(),
         ));
+        axis.set_header_height(self.header_height);
+        axis.set_curve_area_width(self.curve_area_width);
 
         self.axes.insert(key.into(), axis.clone());
 
@@ -1258,14 +1649,16 @@ pub fn axis(&self, key: &str) -> Option<Rc<Axis>> {
         self.axes.get(key).cloned()
     }
 
-    /// Sets the bounding box of the view space.
-    pub fn set_view_bounding_box(&self, view_bounding_box: Aabb<ViewSpace>) {
-        let (view_width, view_height) = view_bounding_box.size().extract();
+    /// Sets the area of the screen covered by the view space.
+    pub fn set_plot_area(&self, area: Aabb<ScreenSpace>) {
+        let (view_width, view_height) = area.size().extract();
 
         let mut mappings = self.coordinate_mappings.borrow_mut();
-        mappings.view_bounding_box = view_bounding_box;
+        mappings.view_bounding_box =
+            Aabb::new(Position::zero(), Position::new((view_width, view_height)));
         mappings.view_width = view_width;
         mappings.view_height = view_height;
+        mappings.view_offset = area.start().extract();
     }
 
     /// Returns the axis line size.
@@ -1289,24 +1682,196 @@ pub fn curve_line_size(&self) -> (Length<WorldSpace>, Length<WorldSpace>) {
     }
 
     pub fn control_points_radius(&self) -> Length<ScreenSpace> {
-        (self.get_rem_length_screen)(CONTROL_POINTS_RADIUS_REM)
+        (self.get_rem_length_screen)(self.control_point_radius)
     }
 
     fn control_points_radius_local(&self) -> (Length<LocalSpace>, Length<LocalSpace>) {
-        (self.get_rem_length_local)(CONTROL_POINTS_RADIUS_REM)
+        (self.get_rem_length_local)(self.control_point_radius)
+    }
+
+    /// Sets the radii used for hit testing.
+    pub fn set_hit_test_radii(&mut self, radii: HitTestRadii) {
+        self.hit_test_radii = radii;
+    }
+
+    /// Sets the geometry of the drawn histograms, or `None` if they are hidden.
+    pub fn set_histogram_layout(&mut self, layout: Option<HistogramLayout>) {
+        self.histogram_layout = layout;
+    }
+
+    /// Returns the geometry of the drawn histograms, if they are shown.
+    pub fn histogram_layout(&self) -> Option<HistogramLayout> {
+        self.histogram_layout
+    }
+
+    /// Returns the histogram bin of a collapsed axis, whose bar area contains
+    /// the world space position.
+    fn histogram_bin_at_position(
+        &self,
+        ax: &Axis,
+        position: Position<WorldSpace>,
+    ) -> Option<usize> {
+        let layout = self.histogram_layout?;
+        if ax.is_expanded() {
+            return None;
+        }
+
+        let offset = ax.world_offset();
+        if !(offset..=offset + layout.width).contains(&position.x) {
+            return None;
+        }
+
+        let (axis_start, axis_end) = ax.axis_line_range();
+        let axis_start = axis_start.transform(&ax.space_transformer());
+        let axis_end = axis_end.transform(&ax.space_transformer());
+        histogram_bin(
+            position.y.inv_lerp(axis_start.y, axis_end.y),
+            layout.num_bins,
+        )
+    }
+
+    /// Sets the radius of the drawn control points, in `rem`.
+    pub fn set_control_point_radius(&mut self, radius: f32) {
+        self.control_point_radius = radius;
+    }
+
+    /// Sets the height of the header slots above the axes, in `rem`.
+    pub fn set_header_height(&mut self, height: f32) {
+        for axis in self.axes.values() {
+            axis.set_header_height(height);
+        }
+        self.header_height = height;
+    }
+
+    /// Sets the width of the area of the probability curves of the expanded
+    /// axes, in local units.
+    pub fn set_curve_area_width(&mut self, width: f32) {
+        for axis in self.axes.values() {
+            axis.set_curve_area_width(width);
+        }
+        self.curve_area_width = width;
+    }
+
+    /// Advances the expansion animation of the visible axes by `step`.
+    ///
+    /// Returns whether the expansion of any axis changed.
+    pub fn tick_expansions(&self, step: f32) -> bool {
+        // All axes must advance, so the result is not short-circuited.
+        self.visible_axes()
+            .fold(false, |changed, ax| ax.tick_expansion(step) | changed)
+    }
+
+    /// Returns the visible axis whose header slot contains the position.
+    pub fn header_at_position(&self, position: Position<ScreenSpace>) -> Option<Rc<Axis>> {
+        let position = position.transform(&self.space_transformer());
+        self.visible_axes().find(|ax| {
+            let position = position.transform(&ax.space_transformer());
+            ax.header_bounding_box()
+                .is_some_and(|bounding_box| bounding_box.contains_point(&position))
+        })
     }
 
+    /// Returns the expanded axis whose curve area contains the position,
+    /// together with the normalized value of the axis at the position.
+    pub fn curve_area_at_position(
+        &self,
+        position: Position<ScreenSpace>,
+    ) -> Option<(Rc<Axis>, f32)> {
+        let position = position.transform(&self.space_transformer());
+        self.visible_axes().find_map(|ax| {
+            let position = position.transform(&ax.space_transformer());
+            if !ax.is_expanded() || !ax.curves_bounding_box().contains_point(&position) {
+                return None;
+            }
+
+            let (axis_start, axis_end) = ax.axis_line_range();
+            let axis_value = position.y.inv_lerp(axis_start.y, axis_end.y);
+            (0.0..=1.0)
+                .contains(&axis_value)
+                .then_some((ax, axis_value))
+        })
+    }
+
+    /// Sets the visible range of an axis.
+    pub fn set_visible_range(&self, key: &str, range: (f32, f32)) {
+        let axis = self.axes.get(key).expect("axis should exist");
+        axis.set_visible_data_range(range, &self.number_format);
+    }
+
+    /// Sets the amplitude of the jitter of an axis.
+    pub fn set_jitter(&self, key: &str, amplitude: f32) {
+        let axis = self.axes.get(key).expect("axis should exist");
+        axis.set_jitter(amplitude);
+    }
+
+    /// Places the categories of an axis in the provided order.
+    pub fn set_category_order(&self, key: &str, order: &[f32]) {
+        let axis = self.axes.get(key).expect("axis should exist");
+        axis.set_category_order(order, &self.number_format);
+    }
+
+    /// Replaces the data of an axis.
+    pub fn set_axis_data(&self, key: &str, data: Box<[f32]>) {
+        let axis = self.axes.get(key).expect("axis should exist");
+        axis.set_data(data, &self.number_format);
+    }
+
+    /// Replaces the data of all axes, which may change the number of data
+    /// points. The selection curves must be rebuilt by the caller.
+    pub fn replace_data(&mut self, data: Vec<(Rc<str>, Box<[f32]>)>) {
+        if data.len() != self.axes.len() {
+            panic!("the data must contain all axes");
+        }
+
+        let num_data_points = data.first().map(|(_, data)| data.len());
+        if data
+            .iter()
+            .any(|(_, data)| Some(data.len()) != num_data_points)
+        {
+            panic!("all axes must contain the same number of data points");
+        }
+
+        for (key, data) in data {
+            self.set_axis_data(&key, data);
+        }
+        self.num_data_points = num_data_points;
+    }
+
+    /// Sets the number format used for the labels and ticks of the axes.
+    pub fn set_number_format(&mut self, number_format: js_sys::Intl::NumberFormat) {
+        for axis in self.axes.values() {
+            axis.set_number_format(&number_format);
+        }
+        self.number_format = number_format;
+    }
+
+    /// Returns the element at the position.
+    ///
+    /// The hit test radii are multiplied by `radius_scale`, to allow for
+    /// less precise input devices.
     pub fn element_at_position(
         &self,
         position: Position<ScreenSpace>,
         active_label_idx: Option<usize>,
+        radius_scale: f32,
     ) -> Option<Element> {
+        let radii = self.hit_test_radii.scaled(radius_scale);
+        let (cp_radius_w, cp_radius_h) = (self.get_rem_length_local)(radii.control_point);
+        let (_, edge_radius_h) = (self.get_rem_length_local)(radii.selection_edge);
+
         let position = position.transform(&self.space_transformer());
         {
             let mappings = self.coordinate_mappings.borrow();
             if !mappings.world_bounding_box.contains_point(&position) {
                 return None;
             }
+
+            // Elements scrolled out of the view are clipped.
+            if let Some((start, end)) = mappings.world_view {
+                if !(start..=end).contains(&position.x) {
+                    return None;
+                }
+            }
         }
 
         let handle_collapsed = |ax: Rc<Axis>, position: Position<LocalSpace>, active_label_idx| {
@@ -1334,15 +1899,17 @@ pub fn element_at_position(
             // Check if we are hovering a selection.
             let bounding_box = ax.selections_bounding_box(active_label_idx);
             if bounding_box.contains_point(&position) {
-                if let Some(rank) = ax.selection_rank_at_position(&position, active_label_idx) {
+                if let Some(rank) =
+                    ax.selection_rank_at_position(&position, active_label_idx, cp_radius_w)
+                {
                     let (axis_start, axis_end) = ax.axis_line_range();
                     let control_points = {
                         let curve_builder = ax.borrow_selection_curve_builder(active_label_idx);
                         curve_builder.get_selection_control_points().into_vec()
                     };
 
-                    let (_, control_point_height) = self.control_points_radius_local();
-                    let padding = control_point_height.extract::<f32>();
+                    let padding = cp_radius_h.extract::<f32>();
+                    let edge_padding = edge_radius_h.extract::<f32>();
 
                     for (selection_idx, (selection_rank, control_points)) in
                         control_points.into_iter().enumerate()
@@ -1359,7 +1926,8 @@ pub fn element_at_position(
                         let (_, end_y) = selection_end.extract();
 
                         let selection_range = start_y..=end_y;
-                        let extended_selection_range = start_y - padding..=end_y + padding;
+                        let extended_selection_range =
+                            start_y - edge_padding.max(padding)..=end_y + edge_padding.max(padding);
 
                         if !extended_selection_range.contains(&position.y) {
                             continue;
@@ -1380,7 +1948,9 @@ pub fn element_at_position(
                             }
                         }
 
-                        if selection_range.contains(&position.y) {
+                        let edge_range = start_y - edge_padding..=end_y + edge_padding;
+                        if selection_range.contains(&position.y) || edge_range.contains(&position.y)
+                        {
                             return Some(Element::Brush {
                                 axis: ax,
                                 selection_idx,
@@ -1390,7 +1960,6 @@ pub fn element_at_position(
                 }
             }
 
-            let (cp_radius_w, cp_radius_h) = self.control_points_radius_local();
             let bounding_box = ax.curves_bounding_box();
             if bounding_box.contains_point(&position) {
                 let (axis_start, axis_end) = ax.axis_line_range();
@@ -1399,6 +1968,11 @@ pub fn element_at_position(
                     for (control_point_idx, &(x, y)) in
                         selection.control_points().iter().enumerate()
                     {
+                        // Control points outside of the visible range are clipped.
+                        if !(0.0..=1.0).contains(&x) {
+                            continue;
+                        }
+
                         let cp_position = axis_start.lerp(axis_end, x);
                         let cp_position = cp_position + ax.curve_offset_at_curve_value(y);
 
@@ -1421,12 +1995,26 @@ pub fn element_at_position(
             None
         };
 
+        // The bars of the histograms may only be brushed for the active label.
+        let hovered_histogram_bin = |ax: &Rc<Axis>| {
+            active_label_idx?;
+            let bin = self.histogram_bin_at_position(ax, position)?;
+            Some(Element::HistogramBin {
+                axis: ax.clone(),
+                bin,
+            })
+        };
+
         for ax in self.visible_axes() {
             let position = position.transform(&ax.space_transformer());
 
-            // Check if we are inside the bounding box of the axis.
+            // Check if we are inside the bounding box of the axis. The bars
+            // of the histogram may extend past it.
             let bounding_box = ax.bounding_box(active_label_idx);
             if !bounding_box.contains_point(&position) {
+                if let Some(el) = hovered_histogram_bin(&ax) {
+                    return Some(el);
+                }
                 continue;
             }
 
@@ -1436,6 +2024,14 @@ pub fn element_at_position(
                 return Some(Element::Label { axis: ax });
             }
 
+            // Check if we are hovering the min or max label.
+            if ax.min_label_bounding_box().contains_point(&position) {
+                return Some(Element::MinLabel { axis: ax });
+            }
+            if ax.max_label_bounding_box().contains_point(&position) {
+                return Some(Element::MaxLabel { axis: ax });
+            }
+
             let el = if let Some(active_label_idx) = active_label_idx {
                 if ax.is_expanded() {
                     handle_expanded(ax.clone(), position, active_label_idx)
@@ -1451,12 +2047,12 @@ pub fn element_at_position(
             }
 
             // Check if we are hovering the axis line.
-            let bounding_box = ax.axis_line_bounding_box();
+            let bounding_box = ax.axis_line_hit_box(radii.axis_line);
             if bounding_box.contains_point(&position) {
                 return Some(Element::AxisLine { axis: ax });
             }
 
-            return None;
+            return hovered_histogram_bin(&ax);
         }
 
         None
@@ -1474,6 +2070,60 @@ pub fn world_width(&self) -> f32 {
         mappings.world_width
     }
 
+    /// Returns the horizontal range of the world space shown in the view.
+    pub fn view_window(&self) -> (f32, f32) {
+        self.coordinate_mappings.borrow().view_window()
+    }
+
+    /// Returns the horizontal range of the world space containing all
+    /// visible axes.
+    pub fn default_view_window(&self) -> (f32, f32) {
+        self.coordinate_mappings.borrow().default_view_window()
+    }
+
+    /// Returns whether the view shows a custom range of the world space,
+    /// instead of fitting all visible axes.
+    pub fn is_view_zoomed(&self) -> bool {
+        self.coordinate_mappings.borrow().world_view.is_some()
+    }
+
+    /// Shows the horizontal range of the world space in the view, or all
+    /// visible axes if `window` is `None`.
+    pub fn set_view_window(&self, window: Option<(f32, f32)>) {
+        let mut mappings = self.coordinate_mappings.borrow_mut();
+        let default = mappings.default_view_window();
+        mappings.world_view = window.filter(|&(start, end)| end > start && (start, end) != default);
+    }
+
+    /// Returns the range of the world space that frames the visible axes
+    /// with the given keys, or `None` if none of them is visible.
+    pub fn framing_window<'a>(
+        &self,
+        keys: impl IntoIterator<Item = &'a str>,
+    ) -> Option<(f32, f32)> {
+        let offsets = keys
+            .into_iter()
+            .filter_map(|key| self.axis(key))
+            .filter(|ax| !ax.is_hidden())
+            .map(|ax| ax.world_offset());
+
+        let (min, max) = offsets.fold(None, |range, offset| match range {
+            None => Some((offset, offset)),
+            Some((min, max)) => Some((f32::min(min, offset), f32::max(max, offset))),
+        })?;
+        Some((min - 0.5, max + 0.5))
+    }
+
+    /// Returns the range of the world space of the current width, that is
+    /// centered on the axis, or `None` if the axis is not visible.
+    pub fn centered_window(&self, key: &str) -> Option<(f32, f32)> {
+        let axis = self.axis(key).filter(|ax| !ax.is_hidden())?;
+        let (start, end) = self.view_window();
+        let half_width = (end - start) / 2.0;
+        let center = axis.world_offset();
+        Some((center - half_width, center + half_width))
+    }
+
     /// Returns a transformer to map between the screen space and world space.
     pub fn space_transformer(
         &self,
@@ -1545,13 +2195,8 @@ fn transform_offset(
         }
 
         let mappings = self.coordinate_mappings.borrow();
-        let screen = ScreenViewTransformer::new(mappings.view_height);
-        let world = ViewWorldTransformer::new(
-            mappings.view_height,
-            mappings.view_width,
-            mappings.world_width,
-            0.5,
-        );
+        let screen = ScreenViewTransformer::with_offset(mappings.view_height, mappings.view_offset);
+        let world = mappings.view_world_transformer();
 
         ScreenWorldTransformer { screen, world }
     }
@@ -1574,21 +2219,10 @@ pub fn visible_axes(&self) -> VisibleAxes<'_> {
 
     pub fn viewport(&self, pixel_ratio: f32) -> ((f32, f32), (f32, f32)) {
         let mappings = self.coordinate_mappings.borrow();
-        let (start_x, start_y) = mappings.view_bounding_box.start().extract();
-        let (end_x, end_y) = mappings.view_bounding_box.end().extract();
-
-        let width = end_x - start_x;
-        let height = end_y - start_y;
-
-        let start = (
-            (start_x * pixel_ratio).floor(),
-            ((mappings.view_height - end_y) * pixel_ratio).floor(),
-        );
-        let size = (
-            (width * pixel_ratio).floor(),
-            (height * pixel_ratio).floor(),
-        );
-        (start, size)
+        let (width, height) = mappings.view_bounding_box.size().extract();
+        let (start_x, start_y) = mappings.view_offset;
+        let end = (start_x + width, start_y + height);
+        coordinates::device_pixel_rect((start_x, start_y), end, pixel_ratio)
     }
 
     fn is_first_visible_axis(&self, axis: &Rc<Axis>) -> bool {
@@ -1636,6 +2270,12 @@ pub enum Element {
     Label {
         axis: Rc<Axis>,
     },
+    MinLabel {
+        axis: Rc<Axis>,
+    },
+    MaxLabel {
+        axis: Rc<Axis>,
+    },
     Group {
         axis: Rc<Axis>,
         group_idx: usize,
@@ -1657,6 +2297,121 @@ pub enum Element {
     AxisLine {
         axis: Rc<Axis>,
     },
+    HistogramBin {
+        axis: Rc<Axis>,
+        bin: usize,
+    },
+}
+
+impl Element {
+    /// Returns a short description of the element, for debugging.
+    pub fn describe(&self) -> String {
+        match self {
+            Element::Label { axis } => format!("Label {{ axis: {} }}", axis.key()),
+            Element::MinLabel { axis } => format!("MinLabel {{ axis: {} }}", axis.key()),
+            Element::MaxLabel { axis } => format!("MaxLabel {{ axis: {} }}", axis.key()),
+            Element::Group { axis, group_idx } => {
+                format!("Group {{ axis: {}, group: {group_idx} }}", axis.key())
+            }
+            Element::Brush {
+                axis,
+                selection_idx,
+            } => format!(
+                "Brush {{ axis: {}, selection: {selection_idx} }}",
+                axis.key()
+            ),
+            Element::AxisControlPoint {
+                axis,
+                selection_idx,
+                control_point_idx,
+            } => format!(
+                "AxisControlPoint {{ axis: {}, selection: {}, point: {} }}",
+                axis.key(),
+                selection_idx,
+                control_point_idx
+            ),
+            Element::CurveControlPoint {
+                axis,
+                selection_idx,
+                control_point_idx,
+            } => format!(
+                "CurveControlPoint {{ axis: {}, selection: {}, point: {} }}",
+                axis.key(),
+                selection_idx,
+                control_point_idx
+            ),
+            Element::AxisLine { axis } => format!("AxisLine {{ axis: {} }}", axis.key()),
+            Element::HistogramBin { axis, bin } => {
+                format!("HistogramBin {{ axis: {}, bin: {bin} }}", axis.key())
+            }
+        }
+    }
+}
+
+impl PartialEq for Element {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Element::Label { axis: a }, Element::Label { axis: b })
+            | (Element::MinLabel { axis: a }, Element::MinLabel { axis: b })
+            | (Element::MaxLabel { axis: a }, Element::MaxLabel { axis: b })
+            | (Element::AxisLine { axis: a }, Element::AxisLine { axis: b }) => Rc::ptr_eq(a, b),
+            (
+                Element::Group {
+                    axis: a,
+                    group_idx: a_idx,
+                },
+                Element::Group {
+                    axis: b,
+                    group_idx: b_idx,
+                },
+            )
+            | (
+                Element::Brush {
+                    axis: a,
+                    selection_idx: a_idx,
+                },
+                Element::Brush {
+                    axis: b,
+                    selection_idx: b_idx,
+                },
+            )
+            | (
+                Element::HistogramBin {
+                    axis: a,
+                    bin: a_idx,
+                },
+                Element::HistogramBin {
+                    axis: b,
+                    bin: b_idx,
+                },
+            ) => Rc::ptr_eq(a, b) && a_idx == b_idx,
+            (
+                Element::AxisControlPoint {
+                    axis: a,
+                    selection_idx: a_sel,
+                    control_point_idx: a_cp,
+                },
+                Element::AxisControlPoint {
+                    axis: b,
+                    selection_idx: b_sel,
+                    control_point_idx: b_cp,
+                },
+            )
+            | (
+                Element::CurveControlPoint {
+                    axis: a,
+                    selection_idx: a_sel,
+                    control_point_idx: a_cp,
+                },
+                Element::CurveControlPoint {
+                    axis: b,
+                    selection_idx: b_sel,
+                    control_point_idx: b_cp,
+                },
+            ) => Rc::ptr_eq(a, b) && a_sel == b_sel && a_cp == b_cp,
+            _ => false,
+        }
+    }
 }
 
 /// An iterator over the visible axes.
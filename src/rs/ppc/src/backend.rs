@@ -0,0 +1,344 @@
+//! Abstraction over the gpu operations required for updating buffers.
+use std::fmt::Debug;
+
+use crate::{
+    webgpu::{Buffer, BufferDescriptor, Device},
+    wgsl::HostSharable,
+};
+
+/// Backend capable of creating, destroying and writing gpu buffers.
+///
+/// The buffer update logic is generic over the backend, which allows
+/// substituting the [`Device`] with a recording backend, or with a
+/// native `wgpu` device.
+pub trait GpuBackend {
+    type Buffer: Debug + Clone;
+
+    /// Creates a new buffer.
+    fn create_buffer(&self, descriptor: BufferDescriptor<'_>) -> Self::Buffer;
+
+    /// Destroys the buffer.
+    fn destroy_buffer(&self, buffer: &Self::Buffer);
+
+    /// Returns the size of the buffer in bytes.
+    fn buffer_size(buffer: &Self::Buffer) -> usize;
+
+    /// Writes the data into the buffer, starting at the byte offset `buffer_offset`.
+    fn write_buffer<T: HostSharable>(&self, buffer: &Self::Buffer, buffer_offset: u32, data: &[T]);
+}
+
+impl GpuBackend for Device {
+    type Buffer = Buffer;
+
+    fn create_buffer(&self, descriptor: BufferDescriptor<'_>) -> Self::Buffer {
+        Device::create_buffer(self, descriptor)
+    }
+
+    fn destroy_buffer(&self, buffer: &Self::Buffer) {
+        buffer.destroy()
+    }
+
+    fn buffer_size(buffer: &Self::Buffer) -> usize {
+        buffer.size()
+    }
+
+    fn write_buffer<T: HostSharable>(&self, buffer: &Self::Buffer, buffer_offset: u32, data: &[T]) {
+        self.queue().write_buffer(buffer, buffer_offset, data)
+    }
+}
+
+/// Recording backend for exercising the buffer logic without a gpu.
+///
+/// The backend does not depend on `web_sys`, so the tests using it run
+/// natively with `cargo test`. A `wasm-bindgen-test` target would execute
+/// the same code paths, and is therefore not provided.
+#[cfg(test)]
+pub mod mock {
+    use std::cell::{Cell, RefCell};
+
+    use super::GpuBackend;
+    use crate::{webgpu::BufferDescriptor, wgsl::HostSharable};
+
+    /// Buffer handle of the [`MockBackend`].
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct MockBuffer {
+        pub id: usize,
+        pub size: usize,
+    }
+
+    /// Operation recorded by the [`MockBackend`].
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum MockOperation {
+        Create {
+            id: usize,
+            label: Option<String>,
+            size: usize,
+        },
+        Destroy {
+            id: usize,
+        },
+        Write {
+            id: usize,
+            offset: u32,
+            data: Vec<u8>,
+        },
+    }
+
+    /// A [`GpuBackend`] that records all operations instead of executing them.
+    #[derive(Debug, Default, Clone)]
+    pub struct MockBackend {
+        next_id: Cell<usize>,
+        operations: RefCell<Vec<MockOperation>>,
+    }
+
+    impl MockBackend {
+        /// Constructs a new backend without any recorded operations.
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Returns all recorded operations.
+        pub fn operations(&self) -> Vec<MockOperation> {
+            self.operations.borrow().clone()
+        }
+
+        /// Removes all recorded operations.
+        pub fn clear(&self) {
+            self.operations.borrow_mut().clear();
+        }
+    }
+
+    impl GpuBackend for MockBackend {
+        type Buffer = MockBuffer;
+
+        fn create_buffer(&self, descriptor: BufferDescriptor<'_>) -> Self::Buffer {
+            let id = self.next_id.get();
+            self.next_id.set(id + 1);
+
+            self.operations.borrow_mut().push(MockOperation::Create {
+                id,
+                label: descriptor.label.map(|l| l.into_owned()),
+                size: descriptor.size,
+            });
+
+            MockBuffer {
+                id,
+                size: descriptor.size,
+            }
+        }
+
+        fn destroy_buffer(&self, buffer: &Self::Buffer) {
+            self.operations
+                .borrow_mut()
+                .push(MockOperation::Destroy { id: buffer.id });
+        }
+
+        fn buffer_size(buffer: &Self::Buffer) -> usize {
+            buffer.size
+        }
+
+        fn write_buffer<T: HostSharable>(
+            &self,
+            buffer: &Self::Buffer,
+            buffer_offset: u32,
+            data: &[T],
+        ) {
+            let size = std::mem::size_of_val(data);
+            assert!(buffer_offset as usize + size <= buffer.size);
+
+            // Copies the raw memory, like the WebGPU backend. The contents of
+            // padding bytes are unspecified, and should not be compared.
+            let data =
+                unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, size) }.to_vec();
+
+            self.operations.borrow_mut().push(MockOperation::Write {
+                id: buffer.id,
+                offset: buffer_offset,
+                data,
+            });
+        }
+    }
+}
+
+/// Native backend executing the buffer operations with `wgpu`.
+///
+/// Shares the buffer layouts with the WebGPU backend, such that the buffer
+/// logic can be exercised natively, e.g. from a desktop viewer.
+#[cfg(feature = "wgpu-backend")]
+pub mod native {
+    use std::sync::Arc;
+
+    use super::GpuBackend;
+    use crate::{webgpu::BufferDescriptor, wgsl::HostSharable};
+
+    /// A [`GpuBackend`] backed by a `wgpu` device.
+    #[derive(Debug)]
+    pub struct WgpuBackend {
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+    }
+
+    impl WgpuBackend {
+        /// Constructs a new backend from a `wgpu` device and its queue.
+        pub fn new(device: wgpu::Device, queue: wgpu::Queue) -> Self {
+            Self { device, queue }
+        }
+
+        /// Returns the device used for creating the buffers.
+        pub fn device(&self) -> &wgpu::Device {
+            &self.device
+        }
+
+        /// Returns the queue used for writing the buffers.
+        pub fn queue(&self) -> &wgpu::Queue {
+            &self.queue
+        }
+    }
+
+    impl GpuBackend for WgpuBackend {
+        type Buffer = Arc<wgpu::Buffer>;
+
+        fn create_buffer(&self, descriptor: BufferDescriptor<'_>) -> Self::Buffer {
+            let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: descriptor.label.as_deref(),
+                size: descriptor.size as u64,
+                usage: descriptor.usage.into(),
+                mapped_at_creation: descriptor.mapped_at_creation.unwrap_or(false),
+            });
+            Arc::new(buffer)
+        }
+
+        fn destroy_buffer(&self, buffer: &Self::Buffer) {
+            buffer.destroy()
+        }
+
+        fn buffer_size(buffer: &Self::Buffer) -> usize {
+            buffer.size() as usize
+        }
+
+        fn write_buffer<T: HostSharable>(
+            &self,
+            buffer: &Self::Buffer,
+            buffer_offset: u32,
+            data: &[T],
+        ) {
+            let size = std::mem::size_of_val(data);
+            let Some(size) = wgpu::BufferSize::new(size as u64) else {
+                return;
+            };
+
+            let mut view = self
+                .queue
+                .write_buffer_with(buffer, buffer_offset as u64, size)
+                .expect("the write should lie inside of the buffer");
+
+            // As with the WebGPU backend, viewing the data as a `[u8]` is
+            // unsound, as the padding bytes are uninitialized. Instead, we
+            // copy the raw memory directly into the staging area of the queue.
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    data.as_ptr() as *const u8,
+                    view.as_mut_ptr(),
+                    size.get() as usize,
+                );
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::{
+            borrow::Cow,
+            future::Future,
+            pin::pin,
+            task::{Context, Poll, Waker},
+        };
+
+        use super::*;
+        use crate::webgpu::BufferUsage;
+
+        /// Polls the future until it completes. The futures of native `wgpu`
+        /// devices complete without being woken.
+        fn block_on<F: Future>(future: F) -> F::Output {
+            let mut future = pin!(future);
+            let mut cx = Context::from_waker(Waker::noop());
+            loop {
+                if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                    return output;
+                }
+                std::thread::yield_now();
+            }
+        }
+
+        /// Returns a backend for the default adapter, if the system provides one.
+        fn backend() -> Option<WgpuBackend> {
+            let instance = wgpu::Instance::default();
+            let adapter = block_on(instance.request_adapter(&Default::default()))?;
+            let (device, queue) =
+                block_on(adapter.request_device(&Default::default(), None)).ok()?;
+            Some(WgpuBackend::new(device, queue))
+        }
+
+        #[test]
+        fn buffer_usages_share_the_bits_of_webgpu() {
+            let usage = BufferUsage::STORAGE | BufferUsage::COPY_DST | BufferUsage::COPY_SRC;
+            let usages: wgpu::BufferUsages = usage.into();
+            assert_eq!(
+                usages,
+                wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_DST
+                    | wgpu::BufferUsages::COPY_SRC
+            );
+
+            let usages: wgpu::BufferUsages = (BufferUsage::MAP_READ | BufferUsage::UNIFORM).into();
+            assert_eq!(
+                usages,
+                wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::UNIFORM
+            );
+        }
+
+        #[test]
+        fn written_data_is_read_back() {
+            // Machines without a gpu, e.g. continuous integration runners,
+            // can only check that the backend compiles.
+            let Some(backend) = backend() else {
+                return;
+            };
+
+            let data = [1.0f32, 2.0, 3.0, 4.0];
+            let buffer = backend.create_buffer(BufferDescriptor {
+                label: Some(Cow::Borrowed("test buffer")),
+                size: 32,
+                usage: BufferUsage::STORAGE | BufferUsage::COPY_DST | BufferUsage::COPY_SRC,
+                mapped_at_creation: None,
+            });
+            assert_eq!(WgpuBackend::buffer_size(&buffer), 32);
+            backend.write_buffer(&buffer, 16, &data);
+
+            let readback = backend.create_buffer(BufferDescriptor {
+                label: Some(Cow::Borrowed("test readback buffer")),
+                size: 16,
+                usage: BufferUsage::MAP_READ | BufferUsage::COPY_DST,
+                mapped_at_creation: None,
+            });
+            let mut encoder = backend.device().create_command_encoder(&Default::default());
+            encoder.copy_buffer_to_buffer(&buffer, 16, &readback, 0, 16);
+            backend.queue().submit([encoder.finish()]);
+
+            let slice = readback.slice(..);
+            slice.map_async(wgpu::MapMode::Read, |result| result.unwrap());
+            backend.device().poll(wgpu::Maintain::Wait);
+
+            let bytes = slice.get_mapped_range();
+            let values = bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+                .collect::<Vec<_>>();
+            assert_eq!(values, data);
+
+            drop(bytes);
+            backend.destroy_buffer(&buffer);
+            backend.destroy_buffer(&readback);
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//! Synthetic datasets and scripted scenarios for benchmarking the renderer.
+use std::collections::BTreeMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::wasm_bridge::{
+    AxisDef, AxisOrder, Brush, Event, EventQueue, StateTransaction, StateTransactionBuilder,
+};
+
+const BENCH_LABEL: &str = "bench";
+const NUM_CLUSTERS: usize = 4;
+
+/// Distribution of a synthetic dataset.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatasetKind {
+    /// Values are uniformly distributed in `[0, 1]`.
+    Uniform,
+    /// Values are grouped into a small number of gaussian clusters.
+    Clustered,
+    /// Neighboring axes are alternately positively and negatively correlated.
+    Correlated,
+}
+
+/// Interaction performed during each iteration of a benchmark.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchScenario {
+    /// Draws the plot without modifying it.
+    Static,
+    /// Rotates the axis order by one position.
+    Reorder,
+    /// Moves a brush along the first axis.
+    Brush,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct BenchOptions {
+    #[wasm_bindgen(js_name = datasetKind)]
+    pub dataset_kind: DatasetKind,
+    #[wasm_bindgen(js_name = numAxes)]
+    pub num_axes: usize,
+    #[wasm_bindgen(js_name = numPoints)]
+    pub num_points: usize,
+    pub scenario: BenchScenario,
+    pub iterations: usize,
+    #[wasm_bindgen(js_name = warmupFrames)]
+    pub warmup_frames: usize,
+    pub seed: u32,
+}
+
+#[wasm_bindgen]
+impl BenchOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            dataset_kind: DatasetKind::Uniform,
+            num_axes: 8,
+            num_points: 10_000,
+            scenario: BenchScenario::Static,
+            iterations: 100,
+            warmup_frames: 10,
+            seed: 0,
+        }
+    }
+}
+
+/// Timings collected by a benchmark run.
+///
+/// All times are measured in milliseconds on the CPU side, i.e., from
+/// sending the event until the renderer finished submitting the frame.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct BenchReport {
+    dataset_time: f64,
+    baseline_frame_time: f64,
+    frame_times: Vec<f64>,
+}
+
+#[wasm_bindgen]
+impl BenchReport {
+    /// Time required to upload the dataset and draw the first frame.
+    #[wasm_bindgen(getter, js_name = datasetTime)]
+    pub fn dataset_time(&self) -> f64 {
+        self.dataset_time
+    }
+
+    /// Mean time of a frame without any state changes.
+    #[wasm_bindgen(getter, js_name = baselineFrameTime)]
+    pub fn baseline_frame_time(&self) -> f64 {
+        self.baseline_frame_time
+    }
+
+    /// Times of each iteration of the scenario.
+    #[wasm_bindgen(getter, js_name = frameTimes)]
+    pub fn frame_times(&self) -> Box<[f64]> {
+        self.frame_times.clone().into_boxed_slice()
+    }
+
+    /// Mean time of an iteration of the scenario.
+    #[wasm_bindgen(getter, js_name = meanFrameTime)]
+    pub fn mean_frame_time(&self) -> f64 {
+        mean(&self.frame_times)
+    }
+
+    /// Mean time spent updating the buffers in each iteration.
+    ///
+    /// Approximated as the difference between the mean frame time and
+    /// the baseline frame time.
+    #[wasm_bindgen(getter, js_name = meanUpdateTime)]
+    pub fn mean_update_time(&self) -> f64 {
+        (self.mean_frame_time() - self.baseline_frame_time).max(0.0)
+    }
+}
+
+/// Generates a synthetic dataset.
+///
+/// The returned transaction adds `num_axes` axes with `num_points` data
+/// points each, and a `bench` label which is set as the active label.
+/// The same seed always produces the same dataset.
+#[wasm_bindgen(js_name = generateDataset)]
+pub fn generate_dataset(
+    kind: DatasetKind,
+    num_axes: usize,
+    num_points: usize,
+    seed: u32,
+) -> StateTransaction {
+    let mut rng = Rng::new(seed);
+    let mut axes = vec![Vec::with_capacity(num_points); num_axes];
+
+    match kind {
+        DatasetKind::Uniform => {
+            for axis in &mut axes {
+                axis.extend((0..num_points).map(|_| rng.next_f32()));
+            }
+        }
+        DatasetKind::Clustered => {
+            let centers = (0..NUM_CLUSTERS)
+                .map(|_| (0..num_axes).map(|_| rng.next_f32()).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            for _ in 0..num_points {
+                let center = &centers[rng.next_index(NUM_CLUSTERS)];
+                for (axis, &c) in axes.iter_mut().zip(center) {
+                    axis.push((c + 0.05 * rng.next_normal()).clamp(0.0, 1.0));
+                }
+            }
+        }
+        DatasetKind::Correlated => {
+            for _ in 0..num_points {
+                let t = rng.next_f32();
+                for (i, axis) in axes.iter_mut().enumerate() {
+                    let value = if i % 2 == 0 { t } else { 1.0 - t };
+                    axis.push((value + 0.1 * rng.next_normal()).clamp(0.0, 1.0));
+                }
+            }
+        }
+    }
+
+    let mut builder = StateTransactionBuilder::new();
+    for (i, points) in axes.into_iter().enumerate() {
+        builder.add_axis(AxisDef::new(
+            &axis_key(i),
+            &format!("Axis {i}"),
+            points.into_boxed_slice(),
+            Some(Box::new([0.0, 1.0])),
+            None,
+            None,
+        ));
+    }
+    builder.add_label(BENCH_LABEL.into(), None, false, 0.0, 0.0, None);
+    builder.switch_active_label(Some(BENCH_LABEL.into()));
+    builder.build()
+}
+
+/// Uploads a synthetic dataset and runs a scripted scenario on it.
+///
+/// Resolves to a [`BenchReport`].
+#[wasm_bindgen(js_name = runBenchmark)]
+pub fn run_benchmark(queue: &EventQueue, options: BenchOptions) -> js_sys::Promise {
+    let queue = EventQueue {
+        sender: queue.sender.clone(),
+    };
+
+    wasm_bindgen_futures::future_to_promise(async move {
+        let dataset = generate_dataset(
+            options.dataset_kind,
+            options.num_axes,
+            options.num_points,
+            options.seed,
+        );
+
+        let start = now();
+        queue
+            .sender
+            .send(Event::CommitTransaction {
+                transaction: dataset,
+            })
+            .await
+            .expect("the channel should be open when trying to send a message");
+        queue.draw().await;
+        let dataset_time = now() - start;
+
+        let mut baseline = Vec::with_capacity(options.warmup_frames);
+        for _ in 0..options.warmup_frames {
+            let start = now();
+            queue.draw().await;
+            baseline.push(now() - start);
+        }
+
+        let mut frame_times = Vec::with_capacity(options.iterations);
+        for i in 0..options.iterations {
+            let transaction = scenario_transaction(options.scenario, options.num_axes, i);
+
+            let start = now();
+            if let Some(transaction) = transaction {
+                queue
+                    .sender
+                    .send(Event::CommitTransaction { transaction })
+                    .await
+                    .expect("the channel should be open when trying to send a message");
+            }
+            queue.draw().await;
+            frame_times.push(now() - start);
+        }
+
+        let report = BenchReport {
+            dataset_time,
+            baseline_frame_time: mean(&baseline),
+            frame_times,
+        };
+        Ok(report.into())
+    })
+}
+
+fn scenario_transaction(
+    scenario: BenchScenario,
+    num_axes: usize,
+    iteration: usize,
+) -> Option<StateTransaction> {
+    let mut transaction = StateTransactionBuilder::new().build();
+    match scenario {
+        BenchScenario::Static => return None,
+        BenchScenario::Reorder => {
+            let order = (0..num_axes)
+                .map(|i| axis_key((i + iteration + 1) % num_axes))
+                .collect();
+            transaction.order_change = Some(AxisOrder::Custom { order });
+        }
+        BenchScenario::Brush => {
+            if num_axes == 0 {
+                return None;
+            }
+
+            let offset = (iteration % 50) as f32 / 100.0;
+            let brush = Brush {
+                control_points: vec![(offset, 1.0), (offset + 0.5, 1.0)],
+                main_segment_idx: 0,
+            };
+            let axes = BTreeMap::from([(axis_key(0), vec![brush])]);
+            transaction.brushes_change = Some(BTreeMap::from([(BENCH_LABEL.into(), axes)]));
+        }
+    }
+
+    Some(transaction)
+}
+
+fn axis_key(index: usize) -> String {
+    format!("axis_{index}")
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+/// Returns a high resolution timestamp in milliseconds, if available.
+fn now() -> f64 {
+    let performance = js_sys::Reflect::get(&js_sys::global(), &"performance".into())
+        .ok()
+        .filter(|p| p.is_object());
+    if let Some(performance) = performance {
+        let now = js_sys::Reflect::get(&performance, &"now".into()).unwrap();
+        if let Ok(now) = now.dyn_into::<js_sys::Function>() {
+            if let Some(time) = now.call0(&performance).ok().and_then(|t| t.as_f64()) {
+                return time;
+            }
+        }
+    }
+
+    js_sys::Date::now()
+}
+
+/// Small deterministic xorshift generator.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u32) -> Self {
+        // The state of a xorshift generator must not be zero.
+        Self(((seed as u64) << 32) | 0x9E37_79B9)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn next_index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+
+    fn next_normal(&mut self) -> f32 {
+        // Box-Muller transform.
+        let u1 = self.next_f32().max(f32::EPSILON);
+        let u2 = self.next_f32();
+        (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+    }
+}
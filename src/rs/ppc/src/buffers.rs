@@ -1,12 +1,14 @@
 use std::{borrow::Cow, mem::MaybeUninit};
 
 use crate::{
+    backend::GpuBackend,
     webgpu::{
-        Buffer, BufferDescriptor, BufferUsage, Device, Texture, TextureDescriptor,
+        Buffer, BufferDescriptor, BufferUsage, CommandEncoder, CommandEncoderDescriptor, Device,
+        ImageCopyTexture, ImageDataLayout, IndexFormat, MapMode, Texture, TextureDescriptor,
         TextureDimension, TextureFormat, TextureUsage, TextureView, TextureViewDescriptor,
         TextureViewDimension,
     },
-    wgsl::{HostSharable, Matrix4x4, Vec2, Vec3, Vec4},
+    wgsl::{Atomic, HostSharable, Matrix4x4, Vec2, Vec3, Vec4, WgslStruct},
 };
 
 /// Buffer containing the MVP matrices.
@@ -19,14 +21,23 @@ pub struct Matrices {
 
 impl Matrices {
     pub fn new(num_visible_axes: usize) -> Self {
+        // Keep the projection finite while all axes are hidden.
+        let num_visible_axes = num_visible_axes.max(1);
+        Self::with_view((-0.5, num_visible_axes as f32 - 0.5))
+    }
+
+    /// Constructs the matrices projecting the horizontal `view` range of
+    /// the world space onto the viewport.
+    pub fn with_view(view: (f32, f32)) -> Self {
+        let (start, end) = view;
         let mv_matrix = Matrix4x4::from_columns_array([
             [1.0, 0.0, 0.0, 0.0],
             [0.0, 1.0, 0.0, 0.0],
             [0.0, 0.0, 1.0, 0.0],
-            [0.5, 0.0, 0.0, 1.0],
+            [-start, 0.0, 0.0, 1.0],
         ]);
         let p_matrix = Matrix4x4::from_columns_array([
-            [2.0 / num_visible_axes as f32, 0.0, 0.0, 0.0],
+            [2.0 / (end - start), 0.0, 0.0, 0.0],
             [0.0, 2.0, 0.0, 0.0],
             [0.0, 0.0, -1.0, 0.0],
             [-1.0, -1.0, 0.0, 1.0],
@@ -41,6 +52,15 @@ pub fn new(num_visible_axes: usize) -> Self {
 
 unsafe impl HostSharable for Matrices {}
 
+impl WgslStruct for Matrices {
+    const DEFINITION: &'static str = r"
+struct Matrices {
+    mv_matrix: mat4x4<f32>,
+    p_matrix: mat4x4<f32>,
+}
+";
+}
+
 /// Buffer layout of the axes.
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
@@ -53,6 +73,17 @@ pub struct Axis {
 
 unsafe impl HostSharable for Axis {}
 
+impl WgslStruct for Axis {
+    const DEFINITION: &'static str = r"
+struct Axes {
+    expanded_val: f32,
+    center_x: f32,
+    position_x: vec2<f32>,
+    range_y: vec2<f32>,
+}
+";
+}
+
 /// Buffer layout of a label color pair.
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
@@ -63,12 +94,23 @@ pub struct LabelColor {
 
 unsafe impl HostSharable for LabelColor {}
 
+impl WgslStruct for LabelColor {
+    const DEFINITION: &'static str = r"
+struct LabelColor {
+    color_high: vec4<f32>,
+    color_low: vec4<f32>,
+}
+";
+}
+
 /// Config for rendering the axes lines.
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub struct AxesConfig {
     pub line_width: Vec2<f32>,
+    pub snap_resolution: Vec2<f32>,
     pub color: Vec3<f32>,
+    pub marker_color: Vec3<f32>,
 }
 
 unsafe impl HostSharable for AxesConfig {}
@@ -80,6 +122,7 @@ pub struct AxisLineInfo {
     pub axis: u32,
     pub axis_position: f32,
     pub min_expanded_val: f32,
+    pub marker: u32,
 }
 
 impl AxisLineInfo {
@@ -98,6 +141,7 @@ pub struct DataLineConfig {
     pub selection_bounds: Vec2<f32>,
     pub color_probabilities: u32,
     pub render_order: u32,
+    pub line_join: u32,
     pub unselected_color: Vec4<f32>,
 }
 
@@ -108,6 +152,10 @@ impl DataLineConfig {
     pub const ORDER_SELECTED_UNORDERED: u32 = 3;
     pub const ORDER_SELECTED_PROBABILITY: u32 = 4;
     pub const ORDER_SELECTED_PROBABILITY_INVERTED: u32 = 5;
+
+    pub const LINE_JOIN_BUTT: u32 = 0;
+    pub const LINE_JOIN_ROUND: u32 = 1;
+    pub const LINE_JOIN_MITER: u32 = 2;
 }
 
 unsafe impl HostSharable for DataLineConfig {}
@@ -125,6 +173,16 @@ pub struct DataLine {
 
 unsafe impl HostSharable for DataLine {}
 
+/// Config for rendering the lines of the reference data and of the label snapshots.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct ReferenceConfig {
+    pub line_width: Vec2<f32>,
+    pub color: Vec4<f32>,
+}
+
+unsafe impl HostSharable for ReferenceConfig {}
+
 /// Config for rendering probability curves.
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
@@ -146,6 +204,16 @@ pub struct CurveLineInfo {
 
 unsafe impl HostSharable for CurveLineInfo {}
 
+impl WgslStruct for CurveLineInfo {
+    const DEFINITION: &'static str = r"
+struct CurveLineInfo {
+    x_t_values: vec2<f32>,
+    y_t_values: vec2<f32>,
+    axis: u32,
+}
+";
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub struct CurveSegmentConfig {
@@ -179,11 +247,47 @@ pub fn buffer(&self) -> &Buffer {
     }
 }
 
+/// Config for rendering the comparison of the curves of two labels.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct CurveComparisonConfig {
+    pub label_a: u32,
+    pub label_b: u32,
+    pub curve_t_range: Vec2<f32>,
+}
+
+unsafe impl HostSharable for CurveComparisonConfig {}
+
+#[derive(Debug, Clone)]
+pub struct CurveComparisonConfigBuffer {
+    buffer: Buffer,
+}
+
+impl CurveComparisonConfigBuffer {
+    pub fn new(device: &Device, config: CurveComparisonConfig) -> Self {
+        let buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("curve comparison config buffer")),
+            size: std::mem::size_of_val(&config),
+            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+        device.queue().write_buffer_single(&buffer, 0, &config);
+
+        Self { buffer }
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+}
+
 /// Selection line rendering config buffer layout.
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub struct SelectionConfig {
     pub line_width: Vec2<f32>,
+    pub snap_resolution: Vec2<f32>,
+    pub pattern: u32,
     pub high_color: Vec3<f32>,
     pub low_color: Vec3<f32>,
 }
@@ -198,11 +302,30 @@ pub struct SelectionLineInfo {
     pub use_left: u32,
     pub offset_x: f32,
     pub color_idx: u32,
+    pub style: u32,
     pub range: Vec2<f32>,
 }
 
+impl SelectionLineInfo {
+    pub const STYLE_DEFAULT: u32 = 0;
+    pub const STYLE_DIMMED: u32 = 1;
+    pub const STYLE_PREVIEW: u32 = 2;
+}
+
 unsafe impl HostSharable for SelectionLineInfo {}
 
+/// Buffer layout of a shaded region of an axis.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct AxisRegion {
+    pub axis: u32,
+    pub half_width: f32,
+    pub range: Vec2<f32>,
+    pub color: Vec4<f32>,
+}
+
+unsafe impl HostSharable for AxisRegion {}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub struct ColorScaleElement {
@@ -240,10 +363,103 @@ pub fn buffer(&self) -> &Buffer {
 pub struct ColorScaleBounds {
     pub start: f32,
     pub end: f32,
+    pub horizontal: u32,
 }
 
 unsafe impl HostSharable for ColorScaleBounds {}
 
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct BackgroundConfig {
+    pub opacity: f32,
+    pub gradient: u32,
+    pub direction: Vec2<f32>,
+}
+
+unsafe impl HostSharable for BackgroundConfig {}
+
+/// Maximum number of categories of an axis drawn with flow ribbons.
+pub const MAX_FLOW_CATEGORIES: usize = 16;
+
+/// Flow aggregation and ribbon config buffer layout.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct FlowConfig {
+    pub color: Vec4<f32>,
+    pub selection_bounds: Vec2<f32>,
+    pub num_datums: u32,
+    pub use_selection: u32,
+}
+
+unsafe impl HostSharable for FlowConfig {}
+
+/// Buffer layout of a pair of adjacent categorical axes connected by flow ribbons.
+///
+/// The positions of the categories are normalized to the visible range of the axes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct FlowPair {
+    pub axis_a: u32,
+    pub axis_b: u32,
+    pub num_categories_a: u32,
+    pub num_categories_b: u32,
+    pub scale: f32,
+    pub positions_a: [f32; MAX_FLOW_CATEGORIES],
+    pub positions_b: [f32; MAX_FLOW_CATEGORIES],
+}
+
+impl FlowPair {
+    /// Category pair of a datum missing a value on one of the axes.
+    pub const MISSING_CATEGORY: u32 = u32::MAX;
+
+    /// Number of segments of the curve of a ribbon.
+    pub const NUM_SEGMENTS: u32 = 16;
+}
+
+unsafe impl HostSharable for FlowPair {}
+
+/// Density estimation and curve config buffer layout.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct DensityConfig {
+    pub color: Vec4<f32>,
+    pub selected_color: Vec4<f32>,
+    pub line_width: Vec2<f32>,
+    pub selection_bounds: Vec2<f32>,
+    pub num_datums: u32,
+    pub num_axes: u32,
+    pub bandwidth: f32,
+    pub width: f32,
+    pub use_selection: u32,
+}
+
+impl DensityConfig {
+    /// Number of samples of the density curve of an axis.
+    pub const NUM_SAMPLES: u32 = 64;
+}
+
+unsafe impl HostSharable for DensityConfig {}
+
+/// Histogram binning and bar config buffer layout.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct HistogramConfig {
+    pub color: Vec4<f32>,
+    pub selected_color: Vec4<f32>,
+    pub num_datums: u32,
+    pub num_axes: u32,
+    pub num_bins: u32,
+    pub width: f32,
+    pub use_selection: u32,
+}
+
+impl HistogramConfig {
+    /// Maximum number of bins of the histogram of an axis.
+    pub const MAX_BINS: u32 = 256;
+}
+
+unsafe impl HostSharable for HistogramConfig {}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub struct SplineSegment {
@@ -254,20 +470,150 @@ pub struct SplineSegment {
 
 unsafe impl HostSharable for SplineSegment {}
 
+/// Range of the segments of the spline of an axis, inside of a [`SplineSegmentsBuffer`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplineRange {
+    pub axis_idx: u32,
+    pub segments_start: u32,
+    pub segments_end: u32,
+}
+
+unsafe impl HostSharable for SplineRange {}
+
+/// Spline segments of multiple axes, packed into a single storage buffer.
 #[derive(Debug, Clone)]
 pub struct SplineSegmentsBuffer {
-    buffer: Buffer,
+    segments: Buffer,
+    ranges: Buffer,
+    num_ranges: usize,
 }
 
 impl SplineSegmentsBuffer {
-    pub fn new(device: &Device, elements: &[SplineSegment]) -> Self {
-        let buffer = device.create_buffer(BufferDescriptor {
+    pub fn new(device: &Device, segments: &[SplineSegment], ranges: &[SplineRange]) -> Self {
+        let segments_buffer = device.create_buffer(BufferDescriptor {
             label: Some(Cow::Borrowed("spline segment buffer")),
-            size: std::mem::size_of_val(elements),
+            size: std::mem::size_of_val(segments),
             usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
             mapped_at_creation: None,
         });
-        device.queue().write_buffer(&buffer, 0, elements);
+        device.queue().write_buffer(&segments_buffer, 0, segments);
+
+        let ranges_buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("spline range buffer")),
+            size: std::mem::size_of_val(ranges),
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+        device.queue().write_buffer(&ranges_buffer, 0, ranges);
+
+        Self {
+            segments: segments_buffer,
+            ranges: ranges_buffer,
+            num_ranges: ranges.len(),
+        }
+    }
+
+    pub fn segments(&self) -> &Buffer {
+        &self.segments
+    }
+
+    pub fn ranges(&self) -> &Buffer {
+        &self.ranges
+    }
+
+    pub fn num_ranges(&self) -> usize {
+        self.num_ranges
+    }
+}
+
+/// Buffer of `u32` vertex indices, for indexed draws.
+///
+/// Allows instances to share the vertices of a common mesh, without
+/// duplicating them in the vertex buffers.
+#[derive(Debug, Clone)]
+pub struct IndexBuffer {
+    buffer: Buffer,
+}
+
+#[allow(dead_code)]
+impl IndexBuffer {
+    pub const FORMAT: IndexFormat = IndexFormat::UInt32;
+
+    pub fn new(device: &Device, indices: &[u32]) -> Self {
+        let buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("index buffer")),
+            size: std::mem::size_of_val(indices),
+            usage: BufferUsage::INDEX | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+        device.queue().write_buffer(&buffer, 0, indices);
+
+        Self { buffer }
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.size() / std::mem::size_of::<u32>()
+    }
+
+    pub fn update(&mut self, device: &Device, indices: &[u32]) {
+        if self.len() != indices.len() {
+            self.buffer.destroy();
+            *self = Self::new(device, indices);
+        } else {
+            device.queue().write_buffer(&self.buffer, 0, indices);
+        }
+    }
+}
+
+/// Arguments of an indirect draw.
+///
+/// The instance count is atomic, such that a compute pass may count the
+/// instances which remain after culling them on the gpu.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DrawIndirectArgs {
+    pub vertex_count: u32,
+    pub instance_count: Atomic<u32>,
+    pub first_vertex: u32,
+    pub first_instance: u32,
+}
+
+unsafe impl HostSharable for DrawIndirectArgs {}
+
+impl WgslStruct for DrawIndirectArgs {
+    const DEFINITION: &'static str = r"
+struct DrawIndirectArgs {
+    vertex_count: u32,
+    instance_count: atomic<u32>,
+    first_vertex: u32,
+    first_instance: u32,
+}
+";
+}
+
+/// Buffer containing the arguments of an indirect draw.
+///
+/// Can be bound as a storage buffer of a compute pass deciding the number
+/// of instances to draw, without a round trip to the cpu.
+#[derive(Debug, Clone)]
+pub struct DrawIndirectBuffer {
+    buffer: Buffer,
+}
+
+#[allow(dead_code)]
+impl DrawIndirectBuffer {
+    pub fn new(device: &Device) -> Self {
+        let buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("draw indirect buffer")),
+            size: std::mem::size_of::<DrawIndirectArgs>(),
+            usage: BufferUsage::INDIRECT | BufferUsage::STORAGE | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
 
         Self { buffer }
     }
@@ -275,6 +621,18 @@ pub fn new(device: &Device, elements: &[SplineSegment]) -> Self {
     pub fn buffer(&self) -> &Buffer {
         &self.buffer
     }
+
+    /// Resets the arguments for drawing `vertex_count` vertices, before
+    /// the instances are counted.
+    pub fn reset(&self, device: &Device, vertex_count: u32) {
+        let args = DrawIndirectArgs {
+            vertex_count,
+            instance_count: Atomic(0),
+            first_vertex: 0,
+            first_instance: 0,
+        };
+        device.queue().write_buffer_single(&self.buffer, 0, &args);
+    }
 }
 
 /// Collection of buffers.
@@ -285,6 +643,10 @@ pub struct Buffers {
     data: DataBuffers,
     curves: CurvesBuffers,
     selections: SelectionsBuffers,
+    background: BackgroundBuffers,
+    flows: FlowBuffers,
+    densities: DensityBuffers,
+    histograms: HistogramBuffers,
 }
 
 impl Buffers {
@@ -295,6 +657,10 @@ pub fn new(device: &Device) -> Self {
             data: DataBuffers::new(device),
             curves: CurvesBuffers::new(device),
             selections: SelectionsBuffers::new(device),
+            background: BackgroundBuffers::new(device),
+            flows: FlowBuffers::new(device),
+            densities: DensityBuffers::new(device),
+            histograms: HistogramBuffers::new(device),
         }
     }
 
@@ -337,6 +703,38 @@ pub fn selections(&self) -> &SelectionsBuffers {
     pub fn selections_mut(&mut self) -> &mut SelectionsBuffers {
         &mut self.selections
     }
+
+    pub fn background(&self) -> &BackgroundBuffers {
+        &self.background
+    }
+
+    pub fn background_mut(&mut self) -> &mut BackgroundBuffers {
+        &mut self.background
+    }
+
+    pub fn flows(&self) -> &FlowBuffers {
+        &self.flows
+    }
+
+    pub fn flows_mut(&mut self) -> &mut FlowBuffers {
+        &mut self.flows
+    }
+
+    pub fn densities(&self) -> &DensityBuffers {
+        &self.densities
+    }
+
+    pub fn densities_mut(&mut self) -> &mut DensityBuffers {
+        &mut self.densities
+    }
+
+    pub fn histograms(&self) -> &HistogramBuffers {
+        &self.histograms
+    }
+
+    pub fn histograms_mut(&mut self) -> &mut HistogramBuffers {
+        &mut self.histograms
+    }
 }
 
 /// Collection of shared buffers.
@@ -441,7 +839,7 @@ fn new(device: &Device) -> Self {
         let buffer = device.create_buffer(BufferDescriptor {
             label: Some(Cow::Borrowed("axes buffer")),
             size: 0,
-            usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_DST | BufferUsage::COPY_SRC,
             mapped_at_creation: None,
         });
 
@@ -462,7 +860,7 @@ pub fn update(&mut self, device: &Device, axes: &[MaybeUninit<Axis>]) {
             self.buffer = device.create_buffer(BufferDescriptor {
                 label: Some(Cow::Borrowed("axes buffer")),
                 size: std::mem::size_of_val(axes),
-                usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
+                usage: BufferUsage::STORAGE | BufferUsage::COPY_DST | BufferUsage::COPY_SRC,
                 mapped_at_creation: None,
             });
         }
@@ -512,48 +910,143 @@ pub fn update(&mut self, device: &Device, colors: &[LabelColor]) {
     }
 }
 
+/// Describes the colors and samples written by the render pipelines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputFormat {
+    /// Format of the canvas.
+    pub format: TextureFormat,
+    /// Whether the canvas uses the `display-p3` color space.
+    pub wide_gamut: bool,
+    /// Whether the colors are blended in linear space, in an intermediate
+    /// texture, and only encoded when they are resolved to the canvas.
+    pub linear: bool,
+    /// Number of samples of each pixel, where `1` disables the multisampling.
+    pub samples: u32,
+    /// Whether the edges of the lines are covered analytically, instead of
+    /// being feathered.
+    pub analytic_antialiasing: bool,
+}
+
+impl OutputFormat {
+    /// Format of the canvas when rendering to a wide-gamut display.
+    pub const WIDE_GAMUT_FORMAT: TextureFormat = TextureFormat::Rgba16float;
+
+    /// Format of the intermediate texture used for linear blending.
+    pub const LINEAR_FORMAT: TextureFormat = TextureFormat::Rgba16float;
+
+    /// Returns the format of the textures written by the render pipelines.
+    pub fn target_format(&self) -> TextureFormat {
+        if self.linear {
+            Self::LINEAR_FORMAT
+        } else {
+            self.format
+        }
+    }
+
+    /// Returns whether the pipelines render into a multisampled texture.
+    pub fn is_multisampled(&self) -> bool {
+        self.samples > 1
+    }
+
+    /// Returns the output format of the pass resolving the linear colors.
+    pub fn encoded(self) -> Self {
+        Self {
+            linear: false,
+            ..self
+        }
+    }
+}
+
 /// A texture for storing the rendered view.
 #[derive(Debug, Clone)]
 pub struct RenderTexture {
     texture: Texture,
+    resolve_texture: Option<Texture>,
+    output: OutputFormat,
+    size: (u32, u32),
 }
 
 impl RenderTexture {
     pub const MSAA_SAMPLES: u32 = 4;
 
-    pub fn new(device: &Device, format: TextureFormat) -> Self {
+    pub fn new(device: &Device, output: OutputFormat) -> Self {
         let texture = device.create_texture(TextureDescriptor::<3, 0> {
             label: Some(Cow::Borrowed("render texture")),
             dimension: Some(TextureDimension::D2),
-            format,
+            format: output.target_format(),
             mip_level_count: None,
-            sample_count: Some(RenderTexture::MSAA_SAMPLES),
+            sample_count: Some(output.samples),
             size: [1, 1, 1],
             usage: TextureUsage::RENDER_ATTACHMENT,
             view_formats: None,
         });
+        let resolve_texture = output
+            .linear
+            .then(|| Self::create_resolve_texture(device, [1, 1]));
 
-        Self { texture }
+        Self {
+            texture,
+            resolve_texture,
+            output,
+            size: (1, 1),
+        }
+    }
+
+    fn create_resolve_texture(device: &Device, size: [usize; 2]) -> Texture {
+        device.create_texture(TextureDescriptor::<2, 0> {
+            label: Some(Cow::Borrowed("linear resolve texture")),
+            dimension: Some(TextureDimension::D2),
+            format: OutputFormat::LINEAR_FORMAT,
+            mip_level_count: None,
+            sample_count: None,
+            size,
+            usage: TextureUsage::RENDER_ATTACHMENT | TextureUsage::TEXTURE_BINDING,
+            view_formats: None,
+        })
     }
 
     pub fn view(&self) -> TextureView {
         self.texture.create_view(None)
     }
 
-    pub fn resize(&mut self, device: &Device, width: u32, height: u32, device_pixel_ratio: f32) {
-        let width = (width.max(1) as f32 * device_pixel_ratio) as usize;
-        let height = (height.max(1) as f32 * device_pixel_ratio) as usize;
+    /// Returns the view of the texture containing the resolved linear colors.
+    pub fn resolve_view(&self) -> Option<TextureView> {
+        self.resolve_texture.as_ref().map(|t| t.create_view(None))
+    }
 
-        self.texture = device.create_texture(TextureDescriptor::<2, 0> {
-            label: Some(Cow::Borrowed("depth texture")),
-            dimension: Some(TextureDimension::D2),
-            format: self.texture.format(),
-            mip_level_count: None,
-            sample_count: Some(RenderTexture::MSAA_SAMPLES),
-            size: [width, height],
-            usage: TextureUsage::RENDER_ATTACHMENT,
-            view_formats: None,
-        });
+    pub fn output(&self) -> OutputFormat {
+        self.output
+    }
+
+    /// Resizes the texture to the size in device pixels.
+    pub fn resize(&mut self, device: &Device, width: u32, height: u32) {
+        self.size = (width, height);
+        let (width, height) = (width.max(1) as usize, height.max(1) as usize);
+
+        // Without multisampling, the pipelines render into the resolve target
+        // directly, so the texture is not used.
+        if self.output.is_multisampled() {
+            self.texture = device.create_texture(TextureDescriptor::<2, 0> {
+                label: Some(Cow::Borrowed("depth texture")),
+                dimension: Some(TextureDimension::D2),
+                format: self.texture.format(),
+                mip_level_count: None,
+                sample_count: Some(self.output.samples),
+                size: [width, height],
+                usage: TextureUsage::RENDER_ATTACHMENT,
+                view_formats: None,
+            });
+        }
+        if self.output.linear {
+            self.resolve_texture = Some(Self::create_resolve_texture(device, [width, height]));
+        }
+    }
+
+    /// Recreates the texture for rendering with the `output` format.
+    pub fn set_output(&mut self, device: &Device, output: OutputFormat) {
+        let (width, height) = self.size;
+        *self = Self::new(device, output);
+        self.resize(device, width, height);
     }
 }
 
@@ -561,45 +1054,53 @@ pub fn resize(&mut self, device: &Device, width: u32, height: u32, device_pixel_
 #[derive(Debug, Clone)]
 pub struct DepthTexture {
     texture: Texture,
+    samples: u32,
 }
 
 impl DepthTexture {
     pub const DEPTH_FORMAT: TextureFormat = TextureFormat::Depth24plus;
 
-    pub fn new(device: &Device) -> Self {
+    pub fn new(device: &Device, samples: u32) -> Self {
         let texture = device.create_texture(TextureDescriptor::<2, 0> {
             label: Some(Cow::Borrowed("depth texture")),
             dimension: Some(TextureDimension::D2),
             format: Self::DEPTH_FORMAT,
             mip_level_count: None,
-            sample_count: Some(RenderTexture::MSAA_SAMPLES),
+            sample_count: Some(samples),
             size: [1, 1],
             usage: TextureUsage::RENDER_ATTACHMENT,
             view_formats: None,
         });
 
-        Self { texture }
+        Self { texture, samples }
     }
 
     pub fn view(&self) -> TextureView {
         self.texture.create_view(None)
     }
 
-    pub fn resize(&mut self, device: &Device, width: u32, height: u32, device_pixel_ratio: f32) {
-        let width = (width.max(1) as f32 * device_pixel_ratio) as usize;
-        let height = (height.max(1) as f32 * device_pixel_ratio) as usize;
+    /// Resizes the texture to the size in device pixels.
+    pub fn resize(&mut self, device: &Device, width: u32, height: u32) {
+        let (width, height) = (width.max(1) as usize, height.max(1) as usize);
 
         self.texture = device.create_texture(TextureDescriptor::<3, 0> {
             label: Some(Cow::Borrowed("depth texture")),
             dimension: Some(TextureDimension::D2),
             format: Self::DEPTH_FORMAT,
             mip_level_count: None,
-            sample_count: Some(RenderTexture::MSAA_SAMPLES),
+            sample_count: Some(self.samples),
             size: [width, height, 1],
             usage: TextureUsage::RENDER_ATTACHMENT,
             view_formats: None,
         });
     }
+
+    /// Recreates the texture with the given number of samples of each pixel.
+    pub fn set_samples(&mut self, device: &Device, samples: u32) {
+        let (width, height) = (self.texture.width(), self.texture.height());
+        *self = Self::new(device, samples);
+        self.resize(device, width, height);
+    }
 }
 
 /// A texture for storing a sampled color scale.
@@ -612,20 +1113,30 @@ impl ColorScaleTexture {
     pub const COLOR_SCALE_RESOLUTION: usize = 2048;
 
     pub fn new(device: &Device) -> Self {
+        // Lower the resolution on devices which do not support it.
+        let max_resolution = device.limits().max_texture_dimension_2d as usize;
+        let resolution = Self::COLOR_SCALE_RESOLUTION.min(max_resolution);
+
         let texture = device.create_texture(TextureDescriptor::<2, 0> {
             label: Some(Cow::Borrowed("color scale texture")),
             dimension: Some(TextureDimension::D2),
             format: TextureFormat::Rgba32float,
             mip_level_count: None,
             sample_count: None,
-            size: [Self::COLOR_SCALE_RESOLUTION, 1],
-            usage: TextureUsage::STORAGE_BINDING | TextureUsage::TEXTURE_BINDING,
+            size: [resolution, 1],
+            usage: TextureUsage::STORAGE_BINDING
+                | TextureUsage::TEXTURE_BINDING
+                | TextureUsage::COPY_SRC,
             view_formats: None,
         });
 
         Self { texture }
     }
 
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
     pub fn view(&self) -> TextureView {
         self.texture.create_view(Some(TextureViewDescriptor {
             label: Some(Cow::Borrowed("color scale texture view")),
@@ -661,6 +1172,7 @@ fn new(device: &Device) -> Self {
             &ColorScaleBounds {
                 start: 0.0,
                 end: 1.0,
+                horizontal: 0,
             },
         );
 
@@ -676,45 +1188,454 @@ pub fn update(&mut self, device: &Device, bounds: &ColorScaleBounds) {
     }
 }
 
-/// Collection of buffers for drawing axes lines.
+/// Collection of buffers for drawing the background layer.
 #[derive(Debug, Clone)]
-pub struct AxesBuffers {
-    config: AxesConfigBuffer,
-    lines: AxisLinesBuffer,
+pub struct BackgroundBuffers {
+    config: Buffer,
+    image: Texture,
+    gradient: ColorScaleTexture,
 }
 
-impl AxesBuffers {
+impl BackgroundBuffers {
     fn new(device: &Device) -> Self {
+        let config = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("background config buffer")),
+            size: std::mem::size_of::<BackgroundConfig>(),
+            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
         Self {
-            config: AxesConfigBuffer::new(device),
-            lines: AxisLinesBuffer::new(device),
+            config,
+            image: Self::create_image_texture(device, 1, 1),
+            gradient: ColorScaleTexture::new(device),
         }
     }
 
-    pub fn config(&self) -> &AxesConfigBuffer {
+    fn create_image_texture(device: &Device, width: u32, height: u32) -> Texture {
+        device.create_texture(TextureDescriptor::<2, 0> {
+            label: Some(Cow::Borrowed("background image texture")),
+            dimension: Some(TextureDimension::D2),
+            format: TextureFormat::Rgba8Unorm,
+            mip_level_count: None,
+            sample_count: None,
+            size: [width as usize, height as usize],
+            usage: TextureUsage::TEXTURE_BINDING | TextureUsage::COPY_DST,
+            view_formats: None,
+        })
+    }
+
+    pub fn config(&self) -> &Buffer {
         &self.config
     }
 
-    pub fn config_mut(&mut self) -> &mut AxesConfigBuffer {
-        &mut self.config
+    pub fn image_view(&self) -> TextureView {
+        self.image.create_view(None)
     }
 
-    pub fn lines(&self) -> &AxisLinesBuffer {
-        &self.lines
+    pub fn gradient(&self) -> &ColorScaleTexture {
+        &self.gradient
     }
 
-    pub fn lines_mut(&mut self) -> &mut AxisLinesBuffer {
-        &mut self.lines
+    pub fn gradient_mut(&mut self) -> &mut ColorScaleTexture {
+        &mut self.gradient
+    }
+
+    pub fn update_config(&mut self, device: &Device, config: &BackgroundConfig) {
+        device.queue().write_buffer_single(&self.config, 0, config);
+    }
+
+    /// Replaces the image with the `pixels` in the `RGBA` format.
+    pub fn set_image(&mut self, device: &Device, width: u32, height: u32, pixels: &[u8]) {
+        // Each texel is packed into a single `u32`, with the red channel in
+        // the lowest byte.
+        let texels = pixels
+            .chunks_exact(4)
+            .map(|texel| u32::from_le_bytes([texel[0], texel[1], texel[2], texel[3]]))
+            .collect::<Vec<_>>();
+
+        self.image = Self::create_image_texture(device, width, height);
+        device.queue().write_texture(
+            ImageCopyTexture {
+                texture: self.image.clone(),
+                aspect: None,
+                mip_level: None,
+                origin: None,
+            },
+            &texels,
+            ImageDataLayout {
+                bytes_per_row: Some(width * 4),
+                ..Default::default()
+            },
+            [width, height],
+        );
     }
 }
 
-/// A uniform buffer containing a [`AxesConfig`] instance.
+/// Collection of buffers for aggregating and drawing the flows between categorical axes.
 #[derive(Debug, Clone)]
-pub struct AxesConfigBuffer {
-    buffer: Buffer,
+pub struct FlowBuffers {
+    config: Buffer,
+    pairs: Buffer,
+    categories: Buffer,
+    counts: Buffer,
+    num_pairs: usize,
 }
 
-impl AxesConfigBuffer {
+impl FlowBuffers {
+    fn new(device: &Device) -> Self {
+        let config = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("flow config buffer")),
+            size: std::mem::size_of::<FlowConfig>(),
+            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
+        Self {
+            config,
+            pairs: Self::create_buffer(device, "flow pairs buffer", 0),
+            categories: Self::create_buffer(device, "flow categories buffer", 0),
+            counts: Self::create_buffer(device, "flow counts buffer", 0),
+            num_pairs: 0,
+        }
+    }
+
+    fn create_buffer(device: &Device, label: &'static str, size: usize) -> Buffer {
+        device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed(label)),
+            size,
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        })
+    }
+
+    pub fn config(&self) -> &Buffer {
+        &self.config
+    }
+
+    pub fn pairs(&self) -> &Buffer {
+        &self.pairs
+    }
+
+    pub fn categories(&self) -> &Buffer {
+        &self.categories
+    }
+
+    pub fn counts(&self) -> &Buffer {
+        &self.counts
+    }
+
+    pub fn num_pairs(&self) -> usize {
+        self.num_pairs
+    }
+
+    pub fn update_config(&mut self, device: &Device, config: &FlowConfig) {
+        device.queue().write_buffer_single(&self.config, 0, config);
+    }
+
+    /// Replaces the axis pairs and the category pair of each datum.
+    ///
+    /// The `categories` contain one entry per datum for each pair.
+    pub fn set_pairs(&mut self, device: &Device, pairs: &[FlowPair], categories: &[u32]) {
+        let num_counts = pairs.len() * MAX_FLOW_CATEGORIES * MAX_FLOW_CATEGORIES;
+
+        self.pairs.destroy();
+        self.categories.destroy();
+        self.counts.destroy();
+        self.pairs = Self::create_buffer(device, "flow pairs buffer", std::mem::size_of_val(pairs));
+        self.categories = Self::create_buffer(
+            device,
+            "flow categories buffer",
+            std::mem::size_of_val(categories),
+        );
+        self.counts = Self::create_buffer(
+            device,
+            "flow counts buffer",
+            num_counts * std::mem::size_of::<u32>(),
+        );
+        self.num_pairs = pairs.len();
+
+        device.queue().write_buffer(&self.pairs, 0, pairs);
+        device.queue().write_buffer(&self.categories, 0, categories);
+    }
+}
+
+/// Collection of buffers for estimating and drawing the densities of the axes.
+#[derive(Debug, Clone)]
+pub struct DensityBuffers {
+    config: Buffer,
+    visible_ranges: Buffer,
+    densities: Buffer,
+    num_axes: usize,
+}
+
+impl DensityBuffers {
+    fn new(device: &Device) -> Self {
+        let config = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("density config buffer")),
+            size: std::mem::size_of::<DensityConfig>(),
+            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
+        Self {
+            config,
+            visible_ranges: Self::create_buffer(device, "density visible ranges buffer", 0),
+            densities: Self::create_buffer(device, "densities buffer", 0),
+            num_axes: 0,
+        }
+    }
+
+    fn create_buffer(device: &Device, label: &'static str, size: usize) -> Buffer {
+        device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed(label)),
+            size,
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        })
+    }
+
+    pub fn config(&self) -> &Buffer {
+        &self.config
+    }
+
+    pub fn visible_ranges(&self) -> &Buffer {
+        &self.visible_ranges
+    }
+
+    pub fn densities(&self) -> &Buffer {
+        &self.densities
+    }
+
+    pub fn num_axes(&self) -> usize {
+        self.num_axes
+    }
+
+    pub fn update_config(&mut self, device: &Device, config: &DensityConfig) {
+        device.queue().write_buffer_single(&self.config, 0, config);
+    }
+
+    /// Replaces the normalized visible range of each axis.
+    ///
+    /// The densities are reallocated, if the number of axes changes.
+    pub fn set_visible_ranges(&mut self, device: &Device, visible_ranges: &[Vec2<f32>]) {
+        if visible_ranges.len() != self.num_axes {
+            let num_densities = 2 * visible_ranges.len() * DensityConfig::NUM_SAMPLES as usize;
+
+            self.visible_ranges.destroy();
+            self.densities.destroy();
+            self.visible_ranges = Self::create_buffer(
+                device,
+                "density visible ranges buffer",
+                std::mem::size_of_val(visible_ranges),
+            );
+            self.densities = Self::create_buffer(
+                device,
+                "densities buffer",
+                num_densities * std::mem::size_of::<f32>(),
+            );
+            self.num_axes = visible_ranges.len();
+        }
+
+        device
+            .queue()
+            .write_buffer(&self.visible_ranges, 0, visible_ranges);
+    }
+
+    /// Replaces the densities with ones computed on the host.
+    pub fn set_densities(&self, device: &Device, densities: &[f32]) {
+        device.queue().write_buffer(&self.densities, 0, densities);
+    }
+}
+
+/// Collection of buffers for binning and drawing the histograms of the axes.
+#[derive(Debug, Clone)]
+pub struct HistogramBuffers {
+    config: Buffer,
+    visible_ranges: Buffer,
+    bins: Buffer,
+    num_axes: usize,
+    num_bins: usize,
+}
+
+impl HistogramBuffers {
+    fn new(device: &Device) -> Self {
+        let config = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("histogram config buffer")),
+            size: std::mem::size_of::<HistogramConfig>(),
+            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
+        Self {
+            config,
+            visible_ranges: Self::create_buffer(device, "histogram visible ranges buffer", 0),
+            bins: Self::create_buffer(device, "histogram bins buffer", 0),
+            num_axes: 0,
+            num_bins: 0,
+        }
+    }
+
+    fn create_buffer(device: &Device, label: &'static str, size: usize) -> Buffer {
+        device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed(label)),
+            size,
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        })
+    }
+
+    pub fn config(&self) -> &Buffer {
+        &self.config
+    }
+
+    pub fn visible_ranges(&self) -> &Buffer {
+        &self.visible_ranges
+    }
+
+    pub fn bins(&self) -> &Buffer {
+        &self.bins
+    }
+
+    pub fn num_axes(&self) -> usize {
+        self.num_axes
+    }
+
+    pub fn num_bins(&self) -> usize {
+        self.num_bins
+    }
+
+    pub fn update_config(&mut self, device: &Device, config: &HistogramConfig) {
+        device.queue().write_buffer_single(&self.config, 0, config);
+    }
+
+    /// Replaces the normalized visible range of each axis.
+    ///
+    /// The bins are reallocated, if the number of axes or bins changes.
+    pub fn set_visible_ranges(
+        &mut self,
+        device: &Device,
+        visible_ranges: &[Vec2<f32>],
+        num_bins: usize,
+    ) {
+        if visible_ranges.len() != self.num_axes || num_bins != self.num_bins {
+            self.visible_ranges.destroy();
+            self.bins.destroy();
+            self.visible_ranges = Self::create_buffer(
+                device,
+                "histogram visible ranges buffer",
+                std::mem::size_of_val(visible_ranges),
+            );
+            self.bins = Self::create_buffer(
+                device,
+                "histogram bins buffer",
+                2 * visible_ranges.len() * num_bins * std::mem::size_of::<f32>(),
+            );
+            self.num_axes = visible_ranges.len();
+            self.num_bins = num_bins;
+        }
+
+        device
+            .queue()
+            .write_buffer(&self.visible_ranges, 0, visible_ranges);
+    }
+
+    /// Replaces the bins with ones computed on the host.
+    pub fn set_bins(&self, device: &Device, bins: &[f32]) {
+        device.queue().write_buffer(&self.bins, 0, bins);
+    }
+}
+
+/// Collection of buffers for drawing axes lines.
+#[derive(Debug, Clone)]
+pub struct AxesBuffers {
+    config: AxesConfigBuffer,
+    lines: AxisLinesBuffer,
+    regions: AxisRegionsBuffer,
+}
+
+impl AxesBuffers {
+    fn new(device: &Device) -> Self {
+        Self {
+            config: AxesConfigBuffer::new(device),
+            lines: AxisLinesBuffer::new(device),
+            regions: AxisRegionsBuffer::new(device),
+        }
+    }
+
+    pub fn config(&self) -> &AxesConfigBuffer {
+        &self.config
+    }
+
+    pub fn config_mut(&mut self) -> &mut AxesConfigBuffer {
+        &mut self.config
+    }
+
+    pub fn lines(&self) -> &AxisLinesBuffer {
+        &self.lines
+    }
+
+    pub fn lines_mut(&mut self) -> &mut AxisLinesBuffer {
+        &mut self.lines
+    }
+
+    pub fn regions(&self) -> &AxisRegionsBuffer {
+        &self.regions
+    }
+
+    pub fn regions_mut(&mut self) -> &mut AxisRegionsBuffer {
+        &mut self.regions
+    }
+}
+
+/// A storage buffer containing the [`AxisRegion`] instances.
+#[derive(Debug, Clone)]
+pub struct AxisRegionsBuffer {
+    buffer: Buffer,
+}
+
+impl AxisRegionsBuffer {
+    fn new(device: &Device) -> Self {
+        let buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("axis regions buffer")),
+            size: 0,
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
+        Self { buffer }
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.size() / std::mem::size_of::<AxisRegion>()
+    }
+
+    pub fn update(&mut self, device: &Device, regions: &[AxisRegion]) {
+        if self.len() != regions.len() {
+            self.buffer.destroy();
+            self.buffer = device.create_buffer(BufferDescriptor {
+                label: Some(Cow::Borrowed("axis regions buffer")),
+                size: std::mem::size_of_val(regions),
+                usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
+                mapped_at_creation: None,
+            });
+        }
+
+        device.queue().write_buffer(&self.buffer, 0, regions)
+    }
+}
+
+/// A uniform buffer containing a [`AxesConfig`] instance.
+#[derive(Debug, Clone)]
+pub struct AxesConfigBuffer {
+    buffer: Buffer,
+}
+
+impl AxesConfigBuffer {
     fn new(device: &Device) -> Self {
         let buffer = device.create_buffer(BufferDescriptor {
             label: Some(Cow::Borrowed("axes config buffer")),
@@ -778,277 +1699,1197 @@ pub fn update(&mut self, device: &Device, lines: &[MaybeUninit<AxisLineInfo>]) {
 
 /// Collection of buffers for drawing values.
 #[derive(Debug, Clone)]
-pub struct DataBuffers {
-    config: DataConfigBuffer,
-    lines: DataLinesBuffer,
-    data: DataBuffer,
-    color_values: ColorValuesBuffer,
-    probabilities: Vec<ProbabilitiesBuffer>,
+pub struct DataBuffers<B: GpuBackend = Device> {
+    config: DataConfigBuffer<B>,
+    lines: DataLinesBuffer<B>,
+    reference_config: ReferenceConfigBuffer<B>,
+    reference_lines: DataLinesBuffer<B>,
+    data: DataBuffer<B>,
+    color_values: ColorValuesBuffer<B>,
+    snapshot_configs: Vec<ReferenceConfigBuffer<B>>,
+    snapshot_lines: Vec<DataLinesBuffer<B>>,
+    memberships: Vec<MembershipBuffer<B>>,
+    probabilities: Vec<ProbabilitiesBuffer<B>>,
+    probabilities_readback: Vec<ProbabilitiesReadbackBuffer<B>>,
+}
+
+impl<B: GpuBackend> DataBuffers<B> {
+    fn new(device: &B) -> Self {
+        Self {
+            config: DataConfigBuffer::new(device),
+            lines: DataLinesBuffer::new(device),
+            reference_config: ReferenceConfigBuffer::new(device),
+            reference_lines: DataLinesBuffer::new(device),
+            data: DataBuffer::new(device),
+            color_values: ColorValuesBuffer::new(device),
+            snapshot_configs: vec![],
+            snapshot_lines: vec![],
+            memberships: vec![],
+            probabilities: vec![],
+            probabilities_readback: vec![],
+        }
+    }
+
+    pub fn config(&self) -> &DataConfigBuffer<B> {
+        &self.config
+    }
+
+    pub fn config_mut(&mut self) -> &mut DataConfigBuffer<B> {
+        &mut self.config
+    }
+
+    pub fn lines(&self) -> &DataLinesBuffer<B> {
+        &self.lines
+    }
+
+    pub fn lines_mut(&mut self) -> &mut DataLinesBuffer<B> {
+        &mut self.lines
+    }
+
+    pub fn reference_config(&self) -> &ReferenceConfigBuffer<B> {
+        &self.reference_config
+    }
+
+    pub fn reference_config_mut(&mut self) -> &mut ReferenceConfigBuffer<B> {
+        &mut self.reference_config
+    }
+
+    pub fn reference_lines(&self) -> &DataLinesBuffer<B> {
+        &self.reference_lines
+    }
+
+    pub fn reference_lines_mut(&mut self) -> &mut DataLinesBuffer<B> {
+        &mut self.reference_lines
+    }
+
+    pub fn data(&self) -> &DataBuffer<B> {
+        &self.data
+    }
+
+    pub fn data_mut(&mut self) -> &mut DataBuffer<B> {
+        &mut self.data
+    }
+
+    pub fn color_values(&self) -> &ColorValuesBuffer<B> {
+        &self.color_values
+    }
+
+    pub fn color_values_mut(&mut self) -> &mut ColorValuesBuffer<B> {
+        &mut self.color_values
+    }
+
+    pub fn snapshot_config(&self, label_idx: usize) -> &ReferenceConfigBuffer<B> {
+        &self.snapshot_configs[label_idx]
+    }
+
+    pub fn snapshot_config_mut(&mut self, label_idx: usize) -> &mut ReferenceConfigBuffer<B> {
+        &mut self.snapshot_configs[label_idx]
+    }
+
+    pub fn snapshot_lines(&self, label_idx: usize) -> &DataLinesBuffer<B> {
+        &self.snapshot_lines[label_idx]
+    }
+
+    pub fn snapshot_lines_mut(&mut self, label_idx: usize) -> &mut DataLinesBuffer<B> {
+        &mut self.snapshot_lines[label_idx]
+    }
+
+    pub fn memberships(&self, label_idx: usize) -> &MembershipBuffer<B> {
+        &self.memberships[label_idx]
+    }
+
+    pub fn memberships_mut(&mut self, label_idx: usize) -> &mut MembershipBuffer<B> {
+        &mut self.memberships[label_idx]
+    }
+
+    pub fn probabilities(&self, label_idx: usize) -> &ProbabilitiesBuffer<B> {
+        &self.probabilities[label_idx]
+    }
+
+    pub fn probabilities_mut(&mut self, label_idx: usize) -> &mut ProbabilitiesBuffer<B> {
+        &mut self.probabilities[label_idx]
+    }
+
+    pub fn probabilities_readback(&self, label_idx: usize) -> &ProbabilitiesReadbackBuffer<B> {
+        &self.probabilities_readback[label_idx]
+    }
+
+    pub fn push_label(&mut self, device: &B) {
+        self.snapshot_configs
+            .push(ReferenceConfigBuffer::new(device));
+        self.snapshot_lines.push(DataLinesBuffer::new(device));
+        self.memberships.push(MembershipBuffer::new(device));
+        self.probabilities.push(ProbabilitiesBuffer::new(device));
+        self.probabilities_readback
+            .push(ProbabilitiesReadbackBuffer::new(device));
+    }
+
+    pub fn remove_label(&mut self, label_idx: usize) {
+        self.snapshot_configs.remove(label_idx);
+        self.snapshot_lines.remove(label_idx);
+        self.memberships.remove(label_idx);
+        self.probabilities.remove(label_idx);
+        self.probabilities_readback.remove(label_idx);
+    }
 }
 
-impl DataBuffers {
-    fn new(device: &Device) -> Self {
+impl DataBuffers {
+    /// Encodes a copy of the probabilities of the label into its readback buffer.
+    pub fn copy_probabilities_for_readback(
+        &mut self,
+        device: &Device,
+        encoder: &CommandEncoder,
+        label_idx: usize,
+    ) {
+        self.probabilities_readback[label_idx].copy_from(
+            device,
+            encoder,
+            &self.probabilities[label_idx],
+        );
+    }
+}
+
+/// A uniform buffer storing an instance of an [`DataLineConfig`].
+#[derive(Debug, Clone)]
+pub struct DataConfigBuffer<B: GpuBackend = Device> {
+    buffer: B::Buffer,
+}
+
+impl<B: GpuBackend> DataConfigBuffer<B> {
+    fn new(device: &B) -> Self {
+        let buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("data config buffer")),
+            size: std::mem::size_of::<DataLineConfig>(),
+            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
+        Self { buffer }
+    }
+
+    pub fn buffer(&self) -> &B::Buffer {
+        &self.buffer
+    }
+
+    pub fn update(&mut self, device: &B, config: &DataLineConfig) {
+        device.write_buffer(&self.buffer, 0, std::slice::from_ref(config));
+    }
+}
+
+/// A uniform buffer storing an instance of an [`ReferenceConfig`].
+#[derive(Debug, Clone)]
+pub struct ReferenceConfigBuffer<B: GpuBackend = Device> {
+    buffer: B::Buffer,
+}
+
+impl<B: GpuBackend> ReferenceConfigBuffer<B> {
+    fn new(device: &B) -> Self {
+        let buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("reference config buffer")),
+            size: std::mem::size_of::<ReferenceConfig>(),
+            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
+        Self { buffer }
+    }
+
+    pub fn buffer(&self) -> &B::Buffer {
+        &self.buffer
+    }
+
+    pub fn update(&mut self, device: &B, config: &ReferenceConfig) {
+        device.write_buffer(&self.buffer, 0, std::slice::from_ref(config));
+    }
+}
+
+/// A storage buffer containing the information required to draw the data lines.
+#[derive(Debug, Clone)]
+pub struct DataLinesBuffer<B: GpuBackend = Device> {
+    buffer: B::Buffer,
+}
+
+impl<B: GpuBackend> DataLinesBuffer<B> {
+    fn new(device: &B) -> Self {
+        let buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("data lines buffer")),
+            size: 0,
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_DST | BufferUsage::COPY_SRC,
+            mapped_at_creation: None,
+        });
+
+        Self { buffer }
+    }
+
+    pub fn buffer(&self) -> &B::Buffer {
+        &self.buffer
+    }
+
+    pub fn len(&self) -> usize {
+        B::buffer_size(&self.buffer) / std::mem::size_of::<DataLine>()
+    }
+
+    pub fn update(&mut self, device: &B, lines: &[DataLine]) {
+        if self.len() != lines.len() {
+            device.destroy_buffer(&self.buffer);
+            self.buffer = device.create_buffer(BufferDescriptor {
+                label: Some(Cow::Borrowed("data lines buffer")),
+                size: std::mem::size_of_val(lines),
+                usage: BufferUsage::STORAGE | BufferUsage::COPY_DST | BufferUsage::COPY_SRC,
+                mapped_at_creation: None,
+            });
+        }
+
+        device.write_buffer(&self.buffer, 0, lines)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DataBuffer<B: GpuBackend = Device> {
+    buffer: B::Buffer,
+}
+
+impl<B: GpuBackend> DataBuffer<B> {
+    fn new(device: &B) -> Self {
+        let buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("data buffer")),
+            size: 0,
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
+        Self { buffer }
+    }
+
+    pub fn buffer(&self) -> &B::Buffer {
+        &self.buffer
+    }
+
+    pub fn len(&self) -> usize {
+        B::buffer_size(&self.buffer) / std::mem::size_of::<f32>()
+    }
+
+    pub fn resize(&mut self, device: &B, num_data_points: usize, num_axes: usize) {
+        if self.len() != num_data_points * num_axes {
+            device.destroy_buffer(&self.buffer);
+            self.buffer = device.create_buffer(BufferDescriptor {
+                label: Some(Cow::Borrowed("data buffer")),
+                size: num_data_points * num_axes * std::mem::size_of::<f32>(),
+                usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
+                mapped_at_creation: None,
+            });
+        }
+    }
+
+    pub fn update(&self, device: &B, data: &[f32], index: usize) {
+        let buffer_offset = (index * std::mem::size_of_val(data)) as u32;
+        device.write_buffer(&self.buffer, buffer_offset, data)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ColorValuesBuffer<B: GpuBackend = Device> {
+    buffer: B::Buffer,
+}
+
+impl<B: GpuBackend> ColorValuesBuffer<B> {
+    fn new(device: &B) -> Self {
+        let buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("data color values buffer")),
+            size: 0,
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
+        Self { buffer }
+    }
+
+    pub fn buffer(&self) -> &B::Buffer {
+        &self.buffer
+    }
+
+    pub fn len(&self) -> usize {
+        B::buffer_size(&self.buffer) / std::mem::size_of::<f32>()
+    }
+
+    pub fn resize(&mut self, device: &B, num_data_points: usize) {
+        if self.len() != num_data_points {
+            device.destroy_buffer(&self.buffer);
+            self.buffer = device.create_buffer(BufferDescriptor {
+                label: Some(Cow::Borrowed("data color values buffer")),
+                size: num_data_points * std::mem::size_of::<f32>(),
+                usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
+                mapped_at_creation: None,
+            });
+        }
+    }
+
+    pub fn update(&self, device: &B, values: &[f32]) {
+        device.write_buffer(&self.buffer, 0, values)
+    }
+}
+
+/// A storage buffer containing the value of the probability curve of each
+/// axis at each data point, laid out like the [`DataBuffer`].
+///
+/// Persists between the probability computations, such that only the
+/// columns of the axes with a changed curve must be recomputed.
+#[derive(Debug, Clone)]
+pub struct MembershipBuffer<B: GpuBackend = Device> {
+    buffer: B::Buffer,
+}
+
+impl<B: GpuBackend> MembershipBuffer<B> {
+    fn new(device: &B) -> Self {
+        Self {
+            buffer: Self::create_buffer(device, 0),
+        }
+    }
+
+    fn create_buffer(device: &B, len: usize) -> B::Buffer {
+        device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("membership buffer")),
+            size: len * std::mem::size_of::<f32>(),
+            usage: BufferUsage::STORAGE,
+            mapped_at_creation: None,
+        })
+    }
+
+    pub fn buffer(&self) -> &B::Buffer {
+        &self.buffer
+    }
+
+    pub fn len(&self) -> usize {
+        B::buffer_size(&self.buffer) / std::mem::size_of::<f32>()
+    }
+
+    /// Resizes the buffer to contain `len` elements.
+    ///
+    /// Returns whether the buffer was reallocated, which discards its contents.
+    pub fn set_len(&mut self, device: &B, len: usize) -> bool {
+        if self.len() == len {
+            return false;
+        }
+
+        device.destroy_buffer(&self.buffer);
+        self.buffer = Self::create_buffer(device, len);
+        true
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProbabilitiesBuffer<B: GpuBackend = Device> {
+    buffer: B::Buffer,
+}
+
+impl<B: GpuBackend> ProbabilitiesBuffer<B> {
+    fn new(device: &B) -> Self {
+        let buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("probabilities buffer")),
+            size: 0,
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_SRC,
+            mapped_at_creation: None,
+        });
+
+        Self { buffer }
+    }
+
+    pub fn empty(device: &B) -> Self {
+        let buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("probabilities buffer")),
+            size: std::mem::size_of::<f32>(),
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_SRC,
+            mapped_at_creation: None,
+        });
+
+        Self { buffer }
+    }
+
+    pub fn buffer(&self) -> &B::Buffer {
+        &self.buffer
+    }
+
+    pub fn size(&self) -> usize {
+        B::buffer_size(&self.buffer)
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.size() / std::mem::size_of::<f32>()
+    }
+
+    pub fn set_len(&mut self, device: &B, len: usize) {
+        self.buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("probabilities buffer")),
+            size: len * std::mem::size_of::<f32>(),
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_SRC,
+            mapped_at_creation: None,
+        });
+    }
+}
+
+/// A pair of staging buffers for reading back the probabilities of a label.
+///
+/// The probabilities are copied into the back buffer each time they are
+/// computed, after which the buffers are swapped. This way, the compute
+/// passes of the following frames never wait for the front buffer, which
+/// may still be mapped for the readback.
+#[derive(Debug, Clone)]
+pub struct ProbabilitiesReadbackBuffer<B: GpuBackend = Device> {
+    buffers: [B::Buffer; 2],
+    front: usize,
+}
+
+impl<B: GpuBackend> ProbabilitiesReadbackBuffer<B> {
+    fn new(device: &B) -> Self {
+        Self {
+            buffers: [
+                Self::create_buffer(device, 0),
+                Self::create_buffer(device, 0),
+            ],
+            front: 0,
+        }
+    }
+
+    fn create_buffer(device: &B, size: usize) -> B::Buffer {
+        device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("probabilities readback buffer")),
+            size,
+            usage: BufferUsage::MAP_READ | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        })
+    }
+
+    /// Returns the buffer containing the last copied probabilities.
+    pub fn front(&self) -> &B::Buffer {
+        &self.buffers[self.front]
+    }
+}
+
+impl ProbabilitiesReadbackBuffer {
+    fn copy_from(
+        &mut self,
+        device: &Device,
+        encoder: &CommandEncoder,
+        probabilities: &ProbabilitiesBuffer,
+    ) {
+        let back = 1 - self.front;
+        let size = probabilities.size();
+        if self.buffers[back].size() != size {
+            self.buffers[back] = Self::create_buffer(device, size);
+        }
+
+        if size != 0 {
+            encoder.copy_buffer_to_buffer(probabilities.buffer(), 0, &self.buffers[back], 0, size);
+        }
+        self.front = back;
+    }
+}
+
+/// Selected range reduction config buffer layout.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct SelectedRangeConfig {
+    pub selection_bounds: Vec2<f32>,
+    pub num_datums: u32,
+    pub num_axes: u32,
+}
+
+unsafe impl HostSharable for SelectedRangeConfig {}
+
+/// Normalized ranges of the selected data points of each axis.
+///
+/// The ranges are reduced on the GPU, and copied into a readback buffer
+/// in the same submission.
+#[derive(Debug, Clone)]
+pub struct SelectedRangesBuffer {
+    ranges: Buffer,
+    readback: Buffer,
+    config: Buffer,
+    num_axes: usize,
+}
+
+impl SelectedRangesBuffer {
+    pub fn new(device: &Device, config: &SelectedRangeConfig) -> Self {
+        let num_axes = config.num_axes as usize;
+        let size = 2 * num_axes * std::mem::size_of::<u32>();
+
+        // The minimum starts at the largest and the maximum at the smallest
+        // bit pattern, such that axes without selected values are detectable.
+        let initial = (0..num_axes)
+            .flat_map(|_| [u32::MAX, 0])
+            .collect::<Vec<_>>();
+        let ranges = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("selected ranges buffer")),
+            size,
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_SRC | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+        device.queue().write_buffer(&ranges, 0, &initial);
+
+        let readback = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("selected ranges readback buffer")),
+            size,
+            usage: BufferUsage::MAP_READ | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
+        let config_buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("selected ranges config buffer")),
+            size: std::mem::size_of::<SelectedRangeConfig>(),
+            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+        device
+            .queue()
+            .write_buffer_single(&config_buffer, 0, config);
+
+        Self {
+            ranges,
+            readback,
+            config: config_buffer,
+            num_axes,
+        }
+    }
+
+    pub fn ranges(&self) -> &Buffer {
+        &self.ranges
+    }
+
+    pub fn config(&self) -> &Buffer {
+        &self.config
+    }
+
+    pub fn num_axes(&self) -> usize {
+        self.num_axes
+    }
+
+    pub fn copy_for_readback(&self, encoder: &CommandEncoder) {
+        encoder.copy_buffer_to_buffer(&self.ranges, 0, &self.readback, 0, self.ranges.size());
+    }
+
+    /// Reads the ranges back, returning `None` for the axes without
+    /// any selected value.
+    pub async fn read(&self) -> Box<[Option<(f32, f32)>]> {
+        self.readback.map_async(MapMode::READ).await;
+        let ranges = unsafe { self.readback.get_mapped_range::<u32>() };
+        self.readback.unmap();
+
+        ranges
+            .chunks_exact(2)
+            .map(|range| {
+                let (min, max) = (range[0], range[1]);
+                (min <= max).then_some((f32::from_bits(min), f32::from_bits(max)))
+            })
+            .collect()
+    }
+}
+
+/// Label agreement reduction config buffer layout.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct LabelAgreementConfig {
+    pub selection_bounds_a: Vec2<f32>,
+    pub selection_bounds_b: Vec2<f32>,
+    pub num_datums: u32,
+}
+
+unsafe impl HostSharable for LabelAgreementConfig {}
+
+/// Sums required for computing the agreement of the probabilities of two labels.
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+pub struct LabelAgreementSums {
+    pub num_intersection: f64,
+    pub num_union: f64,
+    pub sum_a: f64,
+    pub sum_b: f64,
+    pub sum_ab: f64,
+    pub sum_aa: f64,
+    pub sum_bb: f64,
+}
+
+/// Partial sums of each workgroup, for computing the agreement of two labels.
+///
+/// The partial sums are reduced on the GPU, and copied into a readback buffer
+/// in the same submission, where they are summed up.
+#[derive(Debug, Clone)]
+pub struct LabelAgreementBuffer {
+    partial_sums: Buffer,
+    readback: Buffer,
+    config: Buffer,
+    num_workgroups: u32,
+}
+
+impl LabelAgreementBuffer {
+    /// Number of values of the partial sums of a workgroup.
+    const NUM_SUMS: usize = 8;
+
+    pub fn new(device: &Device, config: &LabelAgreementConfig, num_workgroups: u32) -> Self {
+        let size = Self::NUM_SUMS * num_workgroups as usize * std::mem::size_of::<f32>();
+
+        let partial_sums = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("label agreement partial sums buffer")),
+            size,
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_SRC,
+            mapped_at_creation: None,
+        });
+
+        let readback = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("label agreement readback buffer")),
+            size,
+            usage: BufferUsage::MAP_READ | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
+        let config_buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("label agreement config buffer")),
+            size: std::mem::size_of::<LabelAgreementConfig>(),
+            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+        device
+            .queue()
+            .write_buffer_single(&config_buffer, 0, config);
+
+        Self {
+            partial_sums,
+            readback,
+            config: config_buffer,
+            num_workgroups,
+        }
+    }
+
+    pub fn partial_sums(&self) -> &Buffer {
+        &self.partial_sums
+    }
+
+    pub fn config(&self) -> &Buffer {
+        &self.config
+    }
+
+    pub fn num_workgroups(&self) -> u32 {
+        self.num_workgroups
+    }
+
+    pub fn copy_for_readback(&self, encoder: &CommandEncoder) {
+        let size = self.partial_sums.size();
+        encoder.copy_buffer_to_buffer(&self.partial_sums, 0, &self.readback, 0, size);
+    }
+
+    /// Reads the partial sums back, and sums them up.
+    pub async fn read(&self) -> LabelAgreementSums {
+        self.readback.map_async(MapMode::READ).await;
+        let partial_sums = unsafe { self.readback.get_mapped_range::<f32>() };
+        self.readback.unmap();
+
+        partial_sums.chunks_exact(Self::NUM_SUMS).fold(
+            LabelAgreementSums::default(),
+            |sums, partial| LabelAgreementSums {
+                num_intersection: sums.num_intersection + partial[0] as f64,
+                num_union: sums.num_union + partial[1] as f64,
+                sum_a: sums.sum_a + partial[2] as f64,
+                sum_b: sums.sum_b + partial[3] as f64,
+                sum_ab: sums.sum_ab + partial[4] as f64,
+                sum_aa: sums.sum_aa + partial[5] as f64,
+                sum_bb: sums.sum_bb + partial[6] as f64,
+            },
+        )
+    }
+}
+
+/// Label mean reduction config buffer layout.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct LabelMeanConfig {
+    pub selection_bounds: Vec2<f32>,
+    pub num_datums: u32,
+    pub num_axes: u32,
+}
+
+unsafe impl HostSharable for LabelMeanConfig {}
+
+/// Weighted mean and standard deviation of the normalized values of an axis.
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+pub struct LabelMean {
+    pub mean: f32,
+    pub deviation: f32,
+}
+
+/// Partial sums of each workgroup and axis, for computing the weighted mean
+/// of the values selected by a label.
+///
+/// The partial sums are reduced on the GPU, and copied into a readback buffer
+/// in the same submission, where they are summed up.
+#[derive(Debug, Clone)]
+pub struct LabelMeansBuffer {
+    partial_sums: Buffer,
+    readback: Buffer,
+    config: Buffer,
+    num_axes: usize,
+    num_workgroups: u32,
+}
+
+impl LabelMeansBuffer {
+    /// Number of values of the partial sums of a workgroup.
+    const NUM_SUMS: usize = 4;
+
+    pub fn new(device: &Device, config: &LabelMeanConfig, num_workgroups: u32) -> Self {
+        let num_axes = config.num_axes as usize;
+        let size = Self::NUM_SUMS * num_axes * num_workgroups as usize * std::mem::size_of::<f32>();
+
+        let partial_sums = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("label means partial sums buffer")),
+            size,
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_SRC,
+            mapped_at_creation: None,
+        });
+
+        let readback = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("label means readback buffer")),
+            size,
+            usage: BufferUsage::MAP_READ | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
+        let config_buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("label means config buffer")),
+            size: std::mem::size_of::<LabelMeanConfig>(),
+            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+        device
+            .queue()
+            .write_buffer_single(&config_buffer, 0, config);
+
         Self {
-            config: DataConfigBuffer::new(device),
-            lines: DataLinesBuffer::new(device),
-            data: DataBuffer::new(device),
-            color_values: ColorValuesBuffer::new(device),
-            probabilities: vec![],
+            partial_sums,
+            readback,
+            config: config_buffer,
+            num_axes,
+            num_workgroups,
         }
     }
 
-    pub fn config(&self) -> &DataConfigBuffer {
-        &self.config
-    }
-
-    pub fn config_mut(&mut self) -> &mut DataConfigBuffer {
-        &mut self.config
+    pub fn partial_sums(&self) -> &Buffer {
+        &self.partial_sums
     }
 
-    pub fn lines(&self) -> &DataLinesBuffer {
-        &self.lines
+    pub fn config(&self) -> &Buffer {
+        &self.config
     }
 
-    pub fn lines_mut(&mut self) -> &mut DataLinesBuffer {
-        &mut self.lines
+    pub fn num_axes(&self) -> usize {
+        self.num_axes
     }
 
-    pub fn data(&self) -> &DataBuffer {
-        &self.data
+    pub fn num_workgroups(&self) -> u32 {
+        self.num_workgroups
     }
 
-    pub fn data_mut(&mut self) -> &mut DataBuffer {
-        &mut self.data
+    pub fn copy_for_readback(&self, encoder: &CommandEncoder) {
+        let size = self.partial_sums.size();
+        encoder.copy_buffer_to_buffer(&self.partial_sums, 0, &self.readback, 0, size);
     }
 
-    pub fn color_values(&self) -> &ColorValuesBuffer {
-        &self.color_values
-    }
+    /// Reads the partial sums back, and computes the mean of each axis,
+    /// returning `None` for the axes without any selected value.
+    pub async fn read(&self) -> Box<[Option<LabelMean>]> {
+        self.readback.map_async(MapMode::READ).await;
+        let partial_sums = unsafe { self.readback.get_mapped_range::<f32>() };
+        self.readback.unmap();
 
-    pub fn color_values_mut(&mut self) -> &mut ColorValuesBuffer {
-        &mut self.color_values
-    }
+        let axis_len = Self::NUM_SUMS * self.num_workgroups as usize;
+        partial_sums
+            .chunks_exact(axis_len)
+            .map(|axis| {
+                let (weight, sum, sum_squares) = axis.chunks_exact(Self::NUM_SUMS).fold(
+                    (0.0f64, 0.0f64, 0.0f64),
+                    |(weight, sum, sum_squares), partial| {
+                        (
+                            weight + partial[0] as f64,
+                            sum + partial[1] as f64,
+                            sum_squares + partial[2] as f64,
+                        )
+                    },
+                );
+                if weight <= 0.0 {
+                    return None;
+                }
 
-    pub fn probabilities(&self, label_idx: usize) -> &ProbabilitiesBuffer {
-        &self.probabilities[label_idx]
+                let mean = sum / weight;
+                let variance = (sum_squares / weight - mean * mean).max(0.0);
+                Some(LabelMean {
+                    mean: mean as f32,
+                    deviation: variance.sqrt() as f32,
+                })
+            })
+            .collect()
     }
+}
 
-    pub fn probabilities_mut(&mut self, label_idx: usize) -> &mut ProbabilitiesBuffer {
-        &mut self.probabilities[label_idx]
-    }
+/// Outlier score computation config buffer layout.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct OutlierConfig {
+    pub num_datums: u32,
+    pub num_axes: u32,
+}
 
-    pub fn push_label(&mut self, device: &Device) {
-        self.probabilities.push(ProbabilitiesBuffer::new(device))
-    }
+unsafe impl HostSharable for OutlierConfig {}
 
-    pub fn remove_label(&mut self, label_idx: usize) {
-        self.probabilities.remove(label_idx);
-    }
+/// Mean and standard deviation of the normalized values of an axis. Axes
+/// with a deviation of `0` don't contribute to the outlier scores.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+pub struct AxisStatistics {
+    pub mean: f32,
+    pub deviation: f32,
 }
 
-/// A uniform buffer storing an instance of an [`DataLineConfig`].
+unsafe impl HostSharable for AxisStatistics {}
+
+/// Outlier score of each data point.
+///
+/// The scores are computed on the GPU, and copied into a readback buffer in
+/// the same submission.
 #[derive(Debug, Clone)]
-pub struct DataConfigBuffer {
-    buffer: Buffer,
-}
+pub struct OutlierScoresBuffer {
+    scores: Buffer,
+    readback: Buffer,
+    statistics: Buffer,
+    config: Buffer,
+    num_workgroups: u32,
+}
+
+impl OutlierScoresBuffer {
+    pub fn new(
+        device: &Device,
+        config: &OutlierConfig,
+        statistics: &[AxisStatistics],
+        num_workgroups: u32,
+    ) -> Self {
+        let size = config.num_datums as usize * std::mem::size_of::<f32>();
+
+        let scores = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("outlier scores buffer")),
+            size,
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_SRC,
+            mapped_at_creation: None,
+        });
 
-impl DataConfigBuffer {
-    fn new(device: &Device) -> Self {
-        let buffer = device.create_buffer(BufferDescriptor {
-            label: Some(Cow::Borrowed("data config buffer")),
-            size: std::mem::size_of::<DataLineConfig>(),
+        let readback = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("outlier scores readback buffer")),
+            size,
+            usage: BufferUsage::MAP_READ | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
+        let statistics_buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("outlier axis statistics buffer")),
+            size: std::mem::size_of_val(statistics),
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+        device
+            .queue()
+            .write_buffer(&statistics_buffer, 0, statistics);
+
+        let config_buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("outlier scores config buffer")),
+            size: std::mem::size_of::<OutlierConfig>(),
             usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
             mapped_at_creation: None,
         });
+        device
+            .queue()
+            .write_buffer_single(&config_buffer, 0, config);
 
-        Self { buffer }
+        Self {
+            scores,
+            readback,
+            statistics: statistics_buffer,
+            config: config_buffer,
+            num_workgroups,
+        }
     }
 
-    pub fn buffer(&self) -> &Buffer {
-        &self.buffer
+    pub fn scores(&self) -> &Buffer {
+        &self.scores
     }
 
-    pub fn update(&mut self, device: &Device, config: &DataLineConfig) {
-        device.queue().write_buffer_single(&self.buffer, 0, config);
+    pub fn statistics(&self) -> &Buffer {
+        &self.statistics
     }
-}
 
-/// A storage buffer containing the information required to draw the data lines.
-#[derive(Debug, Clone)]
-pub struct DataLinesBuffer {
-    buffer: Buffer,
-}
-
-impl DataLinesBuffer {
-    fn new(device: &Device) -> Self {
-        let buffer = device.create_buffer(BufferDescriptor {
-            label: Some(Cow::Borrowed("data lines buffer")),
-            size: 0,
-            usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
-            mapped_at_creation: None,
-        });
-
-        Self { buffer }
+    pub fn config(&self) -> &Buffer {
+        &self.config
     }
 
-    pub fn buffer(&self) -> &Buffer {
-        &self.buffer
+    pub fn num_workgroups(&self) -> u32 {
+        self.num_workgroups
     }
 
-    pub fn len(&self) -> usize {
-        self.buffer.size() / std::mem::size_of::<DataLine>()
+    pub fn copy_for_readback(&self, encoder: &CommandEncoder) {
+        let size = self.scores.size();
+        encoder.copy_buffer_to_buffer(&self.scores, 0, &self.readback, 0, size);
     }
 
-    pub fn update(&mut self, device: &Device, lines: &[DataLine]) {
-        if self.len() != lines.len() {
-            self.buffer.destroy();
-            self.buffer = device.create_buffer(BufferDescriptor {
-                label: Some(Cow::Borrowed("data lines buffer")),
-                size: std::mem::size_of_val(lines),
-                usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
-                mapped_at_creation: None,
-            });
-        }
-
-        device.queue().write_buffer(&self.buffer, 0, lines)
+    pub async fn read(&self) -> Box<[f32]> {
+        self.readback.map_async(MapMode::READ).await;
+        let scores = unsafe { self.readback.get_mapped_range::<f32>() };
+        self.readback.unmap();
+        scores
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct DataBuffer {
-    buffer: Buffer,
+/// Key-value pair sorted on the GPU.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+pub struct SortPair {
+    pub key: f32,
+    pub value: u32,
 }
 
-impl DataBuffer {
-    fn new(device: &Device) -> Self {
-        let buffer = device.create_buffer(BufferDescriptor {
-            label: Some(Cow::Borrowed("data buffer")),
-            size: 0,
-            usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
-            mapped_at_creation: None,
-        });
-
-        Self { buffer }
-    }
-
-    pub fn buffer(&self) -> &Buffer {
-        &self.buffer
-    }
+impl SortPair {
+    /// Value of the pairs padding the buffer to a power of two.
+    pub const INVALID_VALUE: u32 = u32::MAX;
+}
 
-    pub fn len(&self) -> usize {
-        self.buffer.size() / std::mem::size_of::<f32>()
-    }
+unsafe impl HostSharable for SortPair {}
 
-    pub fn resize(&mut self, device: &Device, num_data_points: usize, num_axes: usize) {
-        if self.len() != num_data_points * num_axes {
-            self.buffer.destroy();
-            self.buffer = device.create_buffer(BufferDescriptor {
-                label: Some(Cow::Borrowed("data buffer")),
-                size: num_data_points * num_axes * std::mem::size_of::<f32>(),
-                usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
-                mapped_at_creation: None,
-            });
-        }
-    }
+/// Sort config buffer layout, shared by the initialization and each step of
+/// the bitonic sort.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct SortConfig {
+    pub num_keys: u32,
+    pub num_pairs: u32,
+    pub block_size: u32,
+    pub distance: u32,
+    pub descending: u32,
+}
 
-    pub fn update(&self, device: &Device, data: &[f32], index: usize) {
-        let buffer_offset = (index * std::mem::size_of_val(data)) as u32;
-        device
-            .queue()
-            .write_buffer(&self.buffer, buffer_offset, data)
-    }
+impl SortConfig {
+    /// Offset between the configs of consecutive steps, which satisfies the
+    /// minimum uniform buffer offset alignment of all devices.
+    pub const STRIDE: usize = 256;
 }
 
+unsafe impl HostSharable for SortConfig {}
+
+/// Pairs of keys and values, sorted by the key on the GPU.
+///
+/// The number of pairs is padded to a power of two, as required by the
+/// bitonic sort. The sorted pairs are copied into a readback buffer in the
+/// same submission.
 #[derive(Debug, Clone)]
-pub struct ColorValuesBuffer {
-    buffer: Buffer,
+pub struct SortBuffer {
+    pairs: Buffer,
+    readback: Buffer,
+    num_keys: usize,
 }
 
-impl ColorValuesBuffer {
-    fn new(device: &Device) -> Self {
-        let buffer = device.create_buffer(BufferDescriptor {
-            label: Some(Cow::Borrowed("data color values buffer")),
-            size: 0,
-            usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
+impl SortBuffer {
+    pub fn new(device: &Device, num_keys: usize) -> Self {
+        let size = num_keys.max(1).next_power_of_two() * std::mem::size_of::<SortPair>();
+
+        let pairs = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("sort pairs buffer")),
+            size,
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_SRC,
             mapped_at_creation: None,
         });
 
-        Self { buffer }
+        let readback = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("sort readback buffer")),
+            size,
+            usage: BufferUsage::MAP_READ | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
+        Self {
+            pairs,
+            readback,
+            num_keys,
+        }
     }
 
-    pub fn buffer(&self) -> &Buffer {
-        &self.buffer
+    pub fn pairs(&self) -> &Buffer {
+        &self.pairs
     }
 
-    pub fn len(&self) -> usize {
-        self.buffer.size() / std::mem::size_of::<f32>()
+    pub fn num_keys(&self) -> usize {
+        self.num_keys
     }
 
-    pub fn resize(&mut self, device: &Device, num_data_points: usize) {
-        if self.len() != num_data_points {
-            self.buffer.destroy();
-            self.buffer = device.create_buffer(BufferDescriptor {
-                label: Some(Cow::Borrowed("data color values buffer")),
-                size: num_data_points * std::mem::size_of::<f32>(),
-                usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
-                mapped_at_creation: None,
-            });
-        }
+    /// Number of pairs, including the padding.
+    pub fn num_pairs(&self) -> usize {
+        self.pairs.size() / std::mem::size_of::<SortPair>()
+    }
+
+    pub fn copy_for_readback(&self, encoder: &CommandEncoder) {
+        let size = self.pairs.size();
+        encoder.copy_buffer_to_buffer(&self.pairs, 0, &self.readback, 0, size);
     }
 
-    pub fn update(&self, device: &Device, values: &[f32]) {
-        device.queue().write_buffer(&self.buffer, 0, values)
+    /// Reads the sorted pairs back, without the padding.
+    pub async fn read(&self) -> Box<[SortPair]> {
+        self.readback.map_async(MapMode::READ).await;
+        let pairs = unsafe { self.readback.get_mapped_range::<SortPair>() };
+        self.readback.unmap();
+
+        let mut pairs = pairs.into_vec();
+        pairs.truncate(self.num_keys);
+        pairs.into()
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct ProbabilitiesBuffer {
-    buffer: Buffer,
+/// Scan config buffer layout.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct ScanConfig {
+    pub num_values: u32,
 }
 
-impl ProbabilitiesBuffer {
-    fn new(device: &Device) -> Self {
-        let buffer = device.create_buffer(BufferDescriptor {
-            label: Some(Cow::Borrowed("probabilities buffer")),
-            size: 0,
-            usage: BufferUsage::STORAGE | BufferUsage::COPY_SRC,
+unsafe impl HostSharable for ScanConfig {}
+
+/// Selection compaction config buffer layout.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct CompactionConfig {
+    pub selection_bounds: Vec2<f32>,
+    pub num_datums: u32,
+}
+
+unsafe impl HostSharable for CompactionConfig {}
+
+/// Indices of the data points selected by a label, compacted on the GPU.
+///
+/// Each selected data point is flagged, and the exclusive scan of the flags
+/// yields the position of its index. The number of selected data points is
+/// stored in front of the indices, and is read back first, such that only
+/// the selected indices need to be read back afterwards.
+#[derive(Debug, Clone)]
+pub struct CompactionBuffer {
+    flags: Buffer,
+    offsets: Buffer,
+    indices: Buffer,
+    count_readback: Buffer,
+    config: Buffer,
+    num_datums: usize,
+}
+
+impl CompactionBuffer {
+    pub fn new(device: &Device, config: &CompactionConfig) -> Self {
+        let num_datums = config.num_datums as usize;
+        let size = num_datums * std::mem::size_of::<u32>();
+
+        let flags = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("compaction flags buffer")),
+            size,
+            usage: BufferUsage::STORAGE,
             mapped_at_creation: None,
         });
 
-        Self { buffer }
-    }
+        let offsets = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("compaction offsets buffer")),
+            size,
+            usage: BufferUsage::STORAGE,
+            mapped_at_creation: None,
+        });
 
-    pub fn empty(device: &Device) -> Self {
-        let buffer = device.create_buffer(BufferDescriptor {
-            label: Some(Cow::Borrowed("probabilities buffer")),
-            size: std::mem::size_of::<f32>(),
+        let indices = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("compaction indices buffer")),
+            size: size + std::mem::size_of::<u32>(),
             usage: BufferUsage::STORAGE | BufferUsage::COPY_SRC,
             mapped_at_creation: None,
         });
 
-        Self { buffer }
+        let count_readback = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("compaction count readback buffer")),
+            size: std::mem::size_of::<u32>(),
+            usage: BufferUsage::MAP_READ | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
+        let config_buffer = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("compaction config buffer")),
+            size: std::mem::size_of::<CompactionConfig>(),
+            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+        device
+            .queue()
+            .write_buffer_single(&config_buffer, 0, config);
+
+        Self {
+            flags,
+            offsets,
+            indices,
+            count_readback,
+            config: config_buffer,
+            num_datums,
+        }
     }
 
-    pub fn buffer(&self) -> &Buffer {
-        &self.buffer
+    pub fn flags(&self) -> &Buffer {
+        &self.flags
     }
 
-    pub fn size(&self) -> usize {
-        self.buffer.size()
+    pub fn offsets(&self) -> &Buffer {
+        &self.offsets
     }
 
-    #[allow(dead_code)]
-    pub fn len(&self) -> usize {
-        self.buffer.size() / std::mem::size_of::<f32>()
+    pub fn indices(&self) -> &Buffer {
+        &self.indices
     }
 
-    pub fn set_len(&mut self, device: &Device, len: usize) {
-        self.buffer = device.create_buffer(BufferDescriptor {
-            label: Some(Cow::Borrowed("probabilities buffer")),
-            size: len * std::mem::size_of::<f32>(),
-            usage: BufferUsage::STORAGE | BufferUsage::COPY_SRC,
+    pub fn config(&self) -> &Buffer {
+        &self.config
+    }
+
+    pub fn num_datums(&self) -> usize {
+        self.num_datums
+    }
+
+    pub fn copy_count_for_readback(&self, encoder: &CommandEncoder) {
+        let size = self.count_readback.size();
+        encoder.copy_buffer_to_buffer(&self.indices, 0, &self.count_readback, 0, size);
+    }
+
+    /// Reads the indices of the selected data points back.
+    ///
+    /// Requires that the count was copied with [`Self::copy_count_for_readback`],
+    /// after which only the selected indices are copied in a new submission.
+    pub async fn read(&self, device: &Device) -> Box<[u32]> {
+        self.count_readback.map_async(MapMode::READ).await;
+        let count = unsafe { self.count_readback.get_mapped_range::<u32>() }[0] as usize;
+        self.count_readback.unmap();
+        if count == 0 {
+            return Box::new([]);
+        }
+
+        let size = count * std::mem::size_of::<u32>();
+        let readback = device.create_buffer(BufferDescriptor {
+            label: Some(Cow::Borrowed("compaction readback buffer")),
+            size,
+            usage: BufferUsage::MAP_READ | BufferUsage::COPY_DST,
             mapped_at_creation: None,
         });
+        let encoder = device.create_command_encoder(CommandEncoderDescriptor { label: None });
+        encoder.copy_buffer_to_buffer(
+            &self.indices,
+            std::mem::size_of::<u32>(),
+            &readback,
+            0,
+            size,
+        );
+        device.queue().submit(&[encoder.finish(None)]);
+
+        readback.map_async(MapMode::READ).await;
+        let indices = unsafe { readback.get_mapped_range::<u32>() };
+        readback.unmap();
+        indices
     }
 }
 
@@ -1168,21 +3009,13 @@ pub fn array_view(&self) -> TextureView {
         }))
     }
 
-    pub fn axis_view(&self, axis: usize) -> TextureView {
-        self.texture.create_view(Some(TextureViewDescriptor {
-            label: Some(format!("axis {axis} probability curve sample texture view").into()),
-            array_layer_count: Some(1),
-            aspect: None,
-            base_array_layer: Some(axis as u32),
-            base_mip_level: None,
-            dimension: Some(TextureViewDimension::D2),
-            format: None,
-            mip_level_count: None,
-        }))
-    }
-
     pub fn set_num_curves(&mut self, device: &Device, num_curves: usize) {
         let num_layers = num_curves.max(1);
+        let max_layers = device.limits().max_texture_array_layers as usize;
+        assert!(
+            num_layers <= max_layers,
+            "the device supports at most {max_layers} probability curves"
+        );
         if self.texture.depth_or_array_layers() as usize == num_layers {
             return;
         }
@@ -1311,7 +3144,7 @@ fn new(device: &Device) -> Self {
         let buffer = device.create_buffer(BufferDescriptor {
             label: Some(Cow::Borrowed("selection lines buffer")),
             size: 0,
-            usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
+            usage: BufferUsage::STORAGE | BufferUsage::COPY_DST | BufferUsage::COPY_SRC,
             mapped_at_creation: None,
         });
 
@@ -1332,7 +3165,7 @@ pub fn update(&mut self, device: &Device, lines: &[SelectionLineInfo]) {
             self.buffer = device.create_buffer(BufferDescriptor {
                 label: Some(Cow::Borrowed("selection lines buffer")),
                 size: std::mem::size_of_val(lines),
-                usage: BufferUsage::STORAGE | BufferUsage::COPY_DST,
+                usage: BufferUsage::STORAGE | BufferUsage::COPY_DST | BufferUsage::COPY_SRC,
                 mapped_at_creation: None,
             });
         }
@@ -1340,3 +3173,284 @@ pub fn update(&mut self, device: &Device, lines: &[SelectionLineInfo]) {
         device.queue().write_buffer(&self.buffer, 0, lines)
     }
 }
+
+/// Reads the content of a buffer back, to inspect it while debugging.
+///
+/// The buffer must have been created with the `COPY_SRC` usage.
+pub async fn read_buffer<T: HostSharable>(device: &Device, buffer: &Buffer) -> Box<[T]> {
+    let size = buffer.size();
+    if size == 0 {
+        return Box::new([]);
+    }
+
+    let readback = device.create_buffer(BufferDescriptor {
+        label: Some(Cow::Borrowed("inspector readback buffer")),
+        size,
+        usage: BufferUsage::MAP_READ | BufferUsage::COPY_DST,
+        mapped_at_creation: None,
+    });
+    let encoder = device.create_command_encoder(CommandEncoderDescriptor { label: None });
+    encoder.copy_buffer_to_buffer(buffer, 0, &readback, 0, size);
+    device.queue().submit(&[encoder.finish(None)]);
+
+    readback.map_async(MapMode::READ).await;
+    let content = unsafe { readback.get_mapped_range::<T>() };
+    readback.unmap();
+    readback.destroy();
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::{MockBackend, MockOperation};
+
+    fn bytes(values: &[f32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_ne_bytes()).collect()
+    }
+
+    fn created_labels(device: &MockBackend) -> Vec<String> {
+        device
+            .operations()
+            .into_iter()
+            .filter_map(|op| match op {
+                MockOperation::Create { label, .. } => label,
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn assert_finite(matrices: &Matrices) {
+        let columns = matrices
+            .mv_matrix
+            .data
+            .iter()
+            .chain(&matrices.p_matrix.data);
+        assert!(columns.flat_map(|c| c.0).all(|v| v.is_finite()));
+    }
+
+    #[test]
+    fn matrices_stay_finite_without_visible_axes() {
+        assert_finite(&Matrices::new(0));
+        assert_eq!(Matrices::new(0), Matrices::new(1));
+    }
+
+    #[test]
+    fn matrices_stay_finite_with_a_single_visible_axis() {
+        assert_finite(&Matrices::new(1));
+        assert_ne!(Matrices::new(1), Matrices::new(2));
+    }
+
+    #[test]
+    fn default_view_fits_all_visible_axes() {
+        assert_eq!(Matrices::new(3), Matrices::with_view((-0.5, 2.5)));
+        assert_ne!(Matrices::new(3), Matrices::with_view((0.5, 2.5)));
+    }
+
+    #[test]
+    fn data_buffer_reallocates_only_on_resize() {
+        let device = MockBackend::new();
+        let mut buffer = DataBuffer::new(&device);
+        device.clear();
+
+        buffer.resize(&device, 4, 3);
+        buffer.resize(&device, 4, 3);
+        assert_eq!(buffer.len(), 12);
+        assert_eq!(
+            device.operations(),
+            [
+                MockOperation::Destroy { id: 0 },
+                MockOperation::Create {
+                    id: 1,
+                    label: Some("data buffer".into()),
+                    size: 48,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn data_buffer_writes_columns_at_axis_offsets() {
+        let device = MockBackend::new();
+        let mut buffer = DataBuffer::new(&device);
+        buffer.resize(&device, 4, 3);
+        device.clear();
+
+        let column = [1.0f32, 2.0, 3.0, 4.0];
+        buffer.update(&device, &column, 0);
+        buffer.update(&device, &column, 2);
+        assert_eq!(
+            device.operations(),
+            [
+                MockOperation::Write {
+                    id: 1,
+                    offset: 0,
+                    data: bytes(&column),
+                },
+                MockOperation::Write {
+                    id: 1,
+                    offset: 32,
+                    data: bytes(&column),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn data_lines_buffer_matches_the_number_of_lines() {
+        let device = MockBackend::new();
+        let mut buffer = DataLinesBuffer::new(&device);
+        let line = DataLine {
+            start_axis: 0,
+            end_axis: 1,
+            start_value: 0.0,
+            end_value: 1.0,
+            curve_idx: 0,
+        };
+
+        buffer.update(&device, &[line, line]);
+        assert_eq!(buffer.len(), 2);
+        device.clear();
+
+        buffer.update(&device, &[line, line]);
+        assert!(matches!(
+            &device.operations()[..],
+            [MockOperation::Write { id: 1, offset: 0, data }]
+                if data.len() == 2 * std::mem::size_of::<DataLine>()
+        ));
+    }
+
+    #[test]
+    fn membership_buffer_reports_reallocations() {
+        let device = MockBackend::new();
+        let mut buffer = MembershipBuffer::new(&device);
+
+        assert!(buffer.set_len(&device, 6));
+        assert!(!buffer.set_len(&device, 6));
+        assert!(buffer.set_len(&device, 3));
+        assert_eq!(buffer.len(), 3);
+    }
+
+    #[test]
+    fn probabilities_buffer_holds_one_value_per_data_point() {
+        let device = MockBackend::new();
+        let mut buffer = ProbabilitiesBuffer::new(&device);
+        assert_eq!(buffer.len(), 0);
+        assert_eq!(ProbabilitiesBuffer::empty(&device).len(), 1);
+
+        buffer.set_len(&device, 5);
+        assert_eq!(buffer.len(), 5);
+        assert_eq!(buffer.size(), 5 * std::mem::size_of::<f32>());
+    }
+
+    #[test]
+    fn data_buffers_keep_the_label_buffers_aligned() {
+        let device = MockBackend::new();
+        let mut buffers = DataBuffers::new(&device);
+        for _ in 0..3 {
+            buffers.push_label(&device);
+        }
+
+        buffers.memberships_mut(0).set_len(&device, 1);
+        buffers.memberships_mut(1).set_len(&device, 2);
+        buffers.memberships_mut(2).set_len(&device, 3);
+        buffers.probabilities_mut(1).set_len(&device, 2);
+        buffers.probabilities_mut(2).set_len(&device, 3);
+
+        buffers.remove_label(1);
+        assert_eq!(buffers.memberships(0).len(), 1);
+        assert_eq!(buffers.memberships(1).len(), 3);
+        assert_eq!(buffers.probabilities(0).len(), 0);
+        assert_eq!(buffers.probabilities(1).len(), 3);
+    }
+
+    #[test]
+    fn config_buffers_write_a_single_element() {
+        let device = MockBackend::new();
+        let mut buffers = DataBuffers::new(&device);
+        device.clear();
+
+        let config = DataLineConfig {
+            line_width: Vec2([1.0, 1.0]),
+            selection_bounds: Vec2([0.0, 1.0]),
+            color_probabilities: 0,
+            render_order: DataLineConfig::ORDER_UNORDERED,
+            line_join: DataLineConfig::LINE_JOIN_BUTT,
+            unselected_color: Vec4([0.0, 0.0, 0.0, 1.0]),
+        };
+        buffers.config_mut().update(&device, &config);
+        assert!(matches!(
+            &device.operations()[..],
+            [MockOperation::Write { offset: 0, data, .. }]
+                if data.len() == std::mem::size_of::<DataLineConfig>()
+        ));
+    }
+
+    #[test]
+    fn adding_a_label_creates_its_buffers() {
+        let device = MockBackend::new();
+        let mut buffers = DataBuffers::new(&device);
+        device.clear();
+
+        buffers.push_label(&device);
+        assert_eq!(
+            created_labels(&device),
+            [
+                "reference config buffer",
+                "data lines buffer",
+                "membership buffer",
+                "probabilities buffer",
+                "probabilities readback buffer",
+                "probabilities readback buffer",
+            ]
+        );
+    }
+
+    #[test]
+    fn removing_a_label_shifts_the_buffers_of_the_following_labels() {
+        let device = MockBackend::new();
+        let mut buffers = DataBuffers::new(&device);
+        for _ in 0..3 {
+            buffers.push_label(&device);
+        }
+        buffers.memberships_mut(2).set_len(&device, 2);
+        let memberships = buffers.memberships(2).buffer().clone();
+        let probabilities = buffers.probabilities(2).buffer().clone();
+
+        buffers.remove_label(1);
+        assert_eq!(buffers.memberships(1).buffer(), &memberships);
+        assert_eq!(buffers.probabilities(1).buffer(), &probabilities);
+        device.clear();
+
+        // Writes to the shifted label must target the buffers of the removed
+        // label's successor.
+        let config = ReferenceConfig {
+            line_width: Vec2([1.0, 2.0]),
+            color: Vec4([0.0, 0.0, 0.0, 1.0]),
+        };
+        let snapshot_config = buffers.snapshot_config(1).buffer.clone();
+        buffers.snapshot_config_mut(1).update(&device, &config);
+        assert!(matches!(
+            &device.operations()[..],
+            [MockOperation::Write { id, .. }] if *id == snapshot_config.id
+        ));
+    }
+
+    #[test]
+    fn resizing_the_probabilities_allocates_one_value_per_data_point() {
+        let device = MockBackend::new();
+        let mut buffers = DataBuffers::new(&device);
+        buffers.push_label(&device);
+        buffers.push_label(&device);
+        device.clear();
+
+        buffers.probabilities_mut(1).set_len(&device, 5);
+        assert_eq!(buffers.probabilities(0).len(), 0);
+        assert_eq!(buffers.probabilities(1).len(), 5);
+        assert!(matches!(
+            &device.operations()[..],
+            [MockOperation::Create { label: Some(label), size: 20, .. }]
+                if label == "probabilities buffer"
+        ));
+    }
+}
@@ -0,0 +1,133 @@
+//! Clustering of the data points.
+
+/// Seed of the initialization, such that the clusters of the same data are
+/// reproducible.
+const SEED: u64 = 0x853C_49E6_748F_EA9B;
+
+/// Minimal xorshift generator for choosing the initial centroids.
+struct XorShift(u64);
+
+impl XorShift {
+    /// Returns a number in the range `[0, 1)`.
+    fn next_f32(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Returns an index in the range `[0, len)`.
+    fn next_index(&mut self, len: usize) -> usize {
+        ((self.next_f32() * len as f32) as usize).min(len - 1)
+    }
+}
+
+/// Squared distance between a point and a centroid, ignoring the missing
+/// values of the point.
+fn distance(point: &[f32], centroid: &[f32]) -> f32 {
+    point
+        .iter()
+        .zip(centroid)
+        .filter(|(p, _)| !p.is_nan())
+        .map(|(p, c)| (p - c) * (p - c))
+        .sum()
+}
+
+/// Returns the index of the centroid nearest to the point.
+fn nearest(point: &[f32], centroids: &[f32]) -> usize {
+    centroids
+        .chunks_exact(point.len())
+        .map(|centroid| distance(point, centroid))
+        .enumerate()
+        .fold((0, f32::INFINITY), |nearest, (idx, distance)| {
+            if distance < nearest.1 {
+                (idx, distance)
+            } else {
+                nearest
+            }
+        })
+        .0
+}
+
+/// Partitions the points into `k` clusters with the k-means algorithm.
+///
+/// The points are stored row by row, with `dims` normalized values each,
+/// where missing values are `NaN`. The initial centroids are chosen with
+/// the k-means++ strategy from a fixed seed. Returns the cluster of each
+/// point.
+pub fn k_means(points: &[f32], dims: usize, k: usize, max_iterations: usize) -> Box<[u32]> {
+    let num_points = points.len().checked_div(dims).unwrap_or(0);
+    if num_points == 0 || k == 0 {
+        return vec![0; num_points].into();
+    }
+
+    // Missing values of a centroid are placed at the center of the axis.
+    let mut rng = XorShift(SEED);
+    let mut centroids = Vec::with_capacity(k * dims);
+    let add_centroid = |centroids: &mut Vec<f32>, idx: usize| {
+        let point = &points[idx * dims..][..dims];
+        centroids.extend(point.iter().map(|&v| if v.is_nan() { 0.5 } else { v }));
+    };
+
+    // Each further centroid is chosen with a probability proportional to
+    // the squared distance to the nearest centroid.
+    add_centroid(&mut centroids, rng.next_index(num_points));
+    let mut distances = points
+        .chunks_exact(dims)
+        .map(|point| distance(point, &centroids))
+        .collect::<Vec<_>>();
+    while centroids.len() < k * dims {
+        let total = distances.iter().sum::<f32>();
+        let idx = if total > 0.0 {
+            let mut target = rng.next_f32() * total;
+            distances
+                .iter()
+                .position(|&d| {
+                    target -= d;
+                    target < 0.0
+                })
+                .unwrap_or(num_points - 1)
+        } else {
+            rng.next_index(num_points)
+        };
+
+        let start = centroids.len();
+        add_centroid(&mut centroids, idx);
+        for (d, point) in distances.iter_mut().zip(points.chunks_exact(dims)) {
+            *d = d.min(distance(point, &centroids[start..]));
+        }
+    }
+
+    let mut assignments = vec![u32::MAX; num_points];
+    let mut sums = vec![0.0f64; k * dims];
+    let mut counts = vec![0usize; k * dims];
+    for _ in 0..max_iterations {
+        let mut changed = false;
+        for (assignment, point) in assignments.iter_mut().zip(points.chunks_exact(dims)) {
+            let cluster = nearest(point, &centroids) as u32;
+            changed |= *assignment != cluster;
+            *assignment = cluster;
+        }
+        if !changed {
+            break;
+        }
+
+        // Move the centroids to the mean of the present values of their points.
+        sums.fill(0.0);
+        counts.fill(0);
+        for (&cluster, point) in assignments.iter().zip(points.chunks_exact(dims)) {
+            let offset = cluster as usize * dims;
+            for (i, &v) in point.iter().enumerate().filter(|(_, v)| !v.is_nan()) {
+                sums[offset + i] += v as f64;
+                counts[offset + i] += 1;
+            }
+        }
+        for ((centroid, &sum), &count) in centroids.iter_mut().zip(&sums).zip(&counts) {
+            if count != 0 {
+                *centroid = (sum / count as f64) as f32;
+            }
+        }
+    }
+
+    assignments.into()
+}
@@ -1,14 +1,19 @@
 use std::rc::Rc;
 
+use wasm_bindgen::JsCast;
+
 use crate::{
     axis::Axis,
-    coordinates::{Aabb, Length, Position, ScreenSpace, ScreenViewTransformer, ViewSpace},
+    coordinates::{self, Aabb, Length, Position, ScreenSpace, ViewSpace},
+    lerp::Lerp,
+    wasm_bridge::{ColorBarOptions, ColorBarTickFormat},
 };
 
 const OUTER_PADDING_REM: f32 = 2.0;
 const TICKS_PADDING_REM: f32 = 0.0;
 const COLOR_BAR_PADDING_REM: f32 = 0.5;
 const COLOR_BAR_WIDTH_REM: f32 = 2.5;
+const FLOATING_HEIGHT_REM: f32 = 20.0;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ColorBarColorMode {
@@ -16,12 +21,52 @@ pub enum ColorBarColorMode {
     Probability,
 }
 
+/// Placement of the color bar.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum ColorBarPlacement {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// Floats above the plot, with the top-left corner at the position,
+    /// in screen space.
+    Floating {
+        x: f32,
+        y: f32,
+    },
+}
+
+/// Values mapped onto the color scale.
+#[derive(Debug, Clone)]
+enum ColorBarValues {
+    Unit,
+    Percent,
+    /// Visible range of an axis, together with the ticks of the axis.
+    Axis {
+        range: (f32, f32),
+        ticks: Vec<(f32, Rc<str>)>,
+    },
+}
+
+/// Positions of the elements of the color bar, in screen space.
+struct ColorBarLayout {
+    bounding_box: ((f32, f32), (f32, f32)),
+    bar: ((f32, f32), (f32, f32)),
+    label: (f32, f32),
+    /// Positions of the ticks at the start and the end of the color scale.
+    ticks: ((f32, f32), (f32, f32)),
+}
+
 #[allow(clippy::type_complexity)]
 pub struct ColorBar {
     visible: bool,
+    placement: ColorBarPlacement,
     color_mode: ColorBarColorMode,
     label: Rc<str>,
     screen_size: (f32, f32),
+    values: ColorBarValues,
+    options: ColorBarOptions,
+    number_format: js_sys::Intl::NumberFormat,
     ticks: Vec<(f32, Rc<str>)>,
     max_ticks_width: Length<ViewSpace>,
     max_ticks_height: Length<ViewSpace>,
@@ -48,37 +93,40 @@ pub fn new(
             },
         );
 
-        let ticks = default_ticks();
-        let max_ticks_width = ticks
-            .iter()
-            .map(|(_, tick)| get_text_length(tick).0)
-            .max_by(|&l, &r| l.0.total_cmp(&r.0))
-            .unwrap_or(Length::new(0.0));
-        let max_ticks_height = ticks
-            .iter()
-            .map(|(_, tick)| get_text_length(tick).1)
-            .max_by(|&l, &r| l.0.total_cmp(&r.0))
-            .unwrap_or(Length::new(0.0));
+        let locales = wasm_bindgen::JsValue::undefined().unchecked_into();
+        let options = wasm_bindgen::JsValue::undefined().unchecked_into();
+        let number_format = js_sys::Intl::NumberFormat::new(&locales, &options);
 
-        Self {
+        let mut this = Self {
             visible: false,
+            placement: ColorBarPlacement::Right,
             label: "".into(),
             color_mode: ColorBarColorMode::Color,
             screen_size: (width, height),
-            ticks,
-            max_ticks_width,
-            max_ticks_height,
+            values: ColorBarValues::Unit,
+            options: ColorBarOptions::default(),
+            number_format,
+            ticks: Vec::new(),
+            max_ticks_width: Length::new(0.0),
+            max_ticks_height: Length::new(0.0),
             get_rem_length,
             get_text_length,
-        }
+        };
+        this.update_ticks();
+        this
     }
 
     pub fn color_mode(&self) -> ColorBarColorMode {
         self.color_mode
     }
 
+    /// Returns the label of the color bar, including the unit.
     pub fn label(&self) -> Rc<str> {
-        self.label.clone()
+        match self.options.unit.as_deref() {
+            Some(unit) if !unit.is_empty() && self.label.is_empty() => unit.into(),
+            Some(unit) if !unit.is_empty() => format!("{} ({unit})", self.label).into(),
+            _ => self.label.clone(),
+        }
     }
 
     pub fn ticks(&self) -> &[(f32, Rc<str>)] {
@@ -93,22 +141,37 @@ pub fn set_visible(&mut self, visible: bool) {
         self.visible = visible;
     }
 
+    pub fn set_placement(&mut self, placement: ColorBarPlacement) {
+        self.placement = placement;
+    }
+
+    pub fn is_floating(&self) -> bool {
+        matches!(self.placement, ColorBarPlacement::Floating { .. })
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        matches!(
+            self.placement,
+            ColorBarPlacement::Top | ColorBarPlacement::Bottom
+        )
+    }
+
+    pub fn set_options(&mut self, options: ColorBarOptions) {
+        self.options = options;
+        self.update_ticks();
+    }
+
+    /// Formats the ticks of the color bar with the provided number format.
+    pub fn set_number_format(&mut self, number_format: js_sys::Intl::NumberFormat) {
+        self.number_format = number_format;
+        self.update_ticks();
+    }
+
     pub fn set_to_empty(&mut self) {
         self.label = "".into();
         self.color_mode = ColorBarColorMode::Color;
-        self.ticks = default_ticks();
-        self.max_ticks_width = self
-            .ticks
-            .iter()
-            .map(|(_, tick)| (self.get_text_length)(tick).0)
-            .max_by(|&l, &r| l.0.total_cmp(&r.0))
-            .unwrap_or(Length::new(0.0));
-        self.max_ticks_height = self
-            .ticks
-            .iter()
-            .map(|(_, tick)| (self.get_text_length)(tick).1)
-            .max_by(|&l, &r| l.0.total_cmp(&r.0))
-            .unwrap_or(Length::new(0.0));
+        self.values = ColorBarValues::Unit;
+        self.update_ticks();
     }
 
     pub fn set_to_label_probability(&mut self, label: &str) {
@@ -118,43 +181,90 @@ pub fn set_to_label_probability(&mut self, label: &str) {
             format!("Probability {label}").into()
         };
         self.color_mode = ColorBarColorMode::Probability;
-        self.ticks = percent_ticks();
-        self.max_ticks_width = self
-            .ticks
-            .iter()
-            .map(|(_, tick)| (self.get_text_length)(tick).0)
-            .max_by(|&l, &r| l.0.total_cmp(&r.0))
-            .unwrap_or(Length::new(0.0));
-        self.max_ticks_height = self
-            .ticks
-            .iter()
-            .map(|(_, tick)| (self.get_text_length)(tick).1)
-            .max_by(|&l, &r| l.0.total_cmp(&r.0))
-            .unwrap_or(Length::new(0.0));
+        self.values = ColorBarValues::Percent;
+        self.update_ticks();
+    }
+
+    pub fn set_to_group_probability(&mut self, group: &str) {
+        self.label = format!("Probability {group}").into();
+        self.color_mode = ColorBarColorMode::Color;
+        self.values = ColorBarValues::Percent;
+        self.update_ticks();
     }
 
     pub fn set_to_axis(&mut self, axis: &Axis) {
         self.label = axis.label();
         self.color_mode = ColorBarColorMode::Color;
-        self.ticks = axis.ticks().into();
-        self.max_ticks_width = self
-            .ticks
-            .iter()
-            .map(|(_, tick)| (self.get_text_length)(tick).0)
-            .max_by(|&l, &r| l.0.total_cmp(&r.0))
-            .unwrap_or(Length::new(0.0));
-        self.max_ticks_height = self
-            .ticks
-            .iter()
-            .map(|(_, tick)| (self.get_text_length)(tick).1)
-            .max_by(|&l, &r| l.0.total_cmp(&r.0))
-            .unwrap_or(Length::new(0.0));
+        self.values = ColorBarValues::Axis {
+            range: axis.visible_data_range(),
+            ticks: axis.ticks().to_vec(),
+        };
+        self.update_ticks();
     }
 
     pub fn set_to_axis_density(&mut self, axis: &Axis) {
         self.label = format!("Density {}", axis.label()).into();
         self.color_mode = ColorBarColorMode::Color;
-        self.ticks = percent_ticks();
+        self.values = ColorBarValues::Percent;
+        self.update_ticks();
+    }
+
+    pub fn set_screen_size(&mut self, width: f32, height: f32) {
+        self.screen_size = (width, height);
+    }
+
+    /// Recomputes the ticks from the mapped values and the options.
+    fn update_ticks(&mut self) {
+        let positions = if let Some(positions) = &self.options.tick_positions {
+            Some(positions.clone())
+        } else {
+            self.options.tick_count.map(|count| {
+                let count = count.max(2);
+                (0..count)
+                    .map(|i| i as f32 / (count - 1) as f32)
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        let format = |value: f32| -> String {
+            let value = wasm_bindgen::JsValue::from_f64(value as f64);
+            let label = self
+                .number_format
+                .format()
+                .call1(&self.number_format, &value)
+                .unwrap();
+            label.as_string().unwrap()
+        };
+        let format_tick = |t: f32| -> Rc<str> {
+            let tick_format = match (self.options.tick_format, &self.values) {
+                (ColorBarTickFormat::Automatic, ColorBarValues::Percent) => {
+                    ColorBarTickFormat::Percent
+                }
+                (ColorBarTickFormat::Automatic, _) => ColorBarTickFormat::Number,
+                (tick_format, _) => tick_format,
+            };
+            match (tick_format, &self.values) {
+                (ColorBarTickFormat::Percent, _) => format!("{}%", format(t * 100.0)).into(),
+                (_, ColorBarValues::Axis { range, .. }) => format(range.0.lerp(range.1, t)).into(),
+                _ => format(t).into(),
+            }
+        };
+
+        self.ticks = match (positions, &self.values, self.options.tick_format) {
+            (None, ColorBarValues::Unit, ColorBarTickFormat::Automatic) => default_ticks(),
+            (None, ColorBarValues::Percent, ColorBarTickFormat::Automatic) => percent_ticks(),
+            (None, ColorBarValues::Axis { ticks, .. }, ColorBarTickFormat::Automatic) => {
+                ticks.clone()
+            }
+            (None, ColorBarValues::Axis { ticks, .. }, _) => {
+                ticks.iter().map(|&(t, _)| (t, format_tick(t))).collect()
+            }
+            (None, _, _) => default_ticks()
+                .into_iter()
+                .map(|(t, _)| (t, format_tick(t)))
+                .collect(),
+            (Some(positions), _, _) => positions.into_iter().map(|t| (t, format_tick(t))).collect(),
+        };
         self.max_ticks_width = self
             .ticks
             .iter()
@@ -169,137 +279,140 @@ pub fn set_to_axis_density(&mut self, axis: &Axis) {
             .unwrap_or(Length::new(0.0));
     }
 
-    pub fn set_screen_size(&mut self, width: f32, height: f32) {
-        self.screen_size = (width, height);
-    }
-
     pub fn label_position(&self) -> Position<ScreenSpace> {
-        let outer_padding = (self.get_rem_length)(OUTER_PADDING_REM);
-        let bar_padding = (self.get_rem_length)(COLOR_BAR_PADDING_REM);
-        let color_bar_width = (self.get_rem_length)(COLOR_BAR_WIDTH_REM);
-        let (label_width, label_height) = if self.label.is_empty() {
-            (self.get_text_length)("empty")
-        } else {
-            (self.get_text_length)(&self.label)
-        };
-
-        let width = color_bar_width.0.max(label_width.0);
-        let half_width = width / 2.0;
-        let (screen_width, screen_height) = self.screen_size;
-
-        let x = screen_width - outer_padding.0 - bar_padding.0 - half_width;
-        let y = screen_height - outer_padding.0 - label_height.0;
-        let position = Position::<ViewSpace>::new((x, y));
-        position.transform(&ScreenViewTransformer::new(screen_height))
+        Position::new(self.layout().label)
     }
 
     pub fn ticks_range(&self) -> (Position<ScreenSpace>, Position<ScreenSpace>) {
-        let outer_padding = (self.get_rem_length)(OUTER_PADDING_REM);
-        let ticks_padding = (self.get_rem_length)(TICKS_PADDING_REM);
-        let bar_padding = (self.get_rem_length)(COLOR_BAR_PADDING_REM);
-        let color_bar_width = (self.get_rem_length)(COLOR_BAR_WIDTH_REM);
-        let (label_width, label_height) = if self.label.is_empty() {
-            (self.get_text_length)("empty")
-        } else {
-            (self.get_text_length)(&self.label)
-        };
-
-        let width = color_bar_width.0.max(label_width.0);
-        let (screen_width, screen_height) = self.screen_size;
-
-        let start_x = screen_width
-            - outer_padding.0
-            - ticks_padding.0
-            - bar_padding.0
-            - bar_padding.0
-            - width;
-
-        let start_y = outer_padding.0 - (self.max_ticks_height.0 / 2.0);
-        let end_y = screen_height
-            - outer_padding.0
-            - label_height.0
-            - bar_padding.0
-            - (self.max_ticks_height.0 / 2.0);
-
-        let start = Position::<ViewSpace>::new((start_x, start_y));
-        let end = Position::<ViewSpace>::new((start_x, end_y));
-
-        let transformer = ScreenViewTransformer::new(screen_height);
-        let start = start.transform(&transformer);
-        let end = end.transform(&transformer);
-
-        (start, end)
+        let (start, end) = self.layout().ticks;
+        (Position::new(start), Position::new(end))
     }
 
     pub fn bounding_box(&self) -> Aabb<ScreenSpace> {
-        let outer_padding = (self.get_rem_length)(OUTER_PADDING_REM);
-        let ticks_padding = (self.get_rem_length)(TICKS_PADDING_REM);
-        let bar_padding = (self.get_rem_length)(COLOR_BAR_PADDING_REM);
-        let color_bar_width = (self.get_rem_length)(COLOR_BAR_WIDTH_REM);
-        let (label_width, _) = if self.label.is_empty() {
-            (self.get_text_length)("empty")
-        } else {
-            (self.get_text_length)(&self.label)
-        };
+        let (start, end) = self.layout().bounding_box;
+        Aabb::new(Position::new(start), Position::new(end))
+    }
 
-        let width = color_bar_width.0.max(label_width.0);
+    /// Returns the area of the screen that remains for the plot.
+    pub fn plot_area(&self) -> Aabb<ScreenSpace> {
         let (screen_width, screen_height) = self.screen_size;
+        if !self.visible {
+            return Aabb::new(
+                Position::zero(),
+                Position::new((screen_width, screen_height)),
+            );
+        }
 
-        let start_x = screen_width
-            - outer_padding.0
-            - outer_padding.0
-            - ticks_padding.0
-            - ticks_padding.0
-            - bar_padding.0
-            - bar_padding.0
-            - self.max_ticks_width.0
-            - width;
-        let start_y = 0.0;
-        let start = Position::<ViewSpace>::new((start_x, start_y));
-
-        let end_x = screen_width;
-        let end_y = screen_height - 1.0;
-        let end = Position::<ViewSpace>::new((end_x, end_y));
-
-        let transformer = ScreenViewTransformer::new(screen_height);
-        let start = start.transform(&transformer);
-        let end = end.transform(&transformer);
-        Aabb::new(start, end)
+        let ((start_x, start_y), (end_x, end_y)) = self.layout().bounding_box;
+        let (start, end) = match self.placement {
+            ColorBarPlacement::Left => ((end_x, 0.0), (screen_width, screen_height)),
+            ColorBarPlacement::Right => ((0.0, 0.0), (start_x, screen_height)),
+            ColorBarPlacement::Top => ((0.0, end_y), (screen_width, screen_height)),
+            ColorBarPlacement::Bottom => ((0.0, 0.0), (screen_width, start_y)),
+            ColorBarPlacement::Floating { .. } => ((0.0, 0.0), (screen_width, screen_height)),
+        };
+        Aabb::new(Position::new(start), Position::new(end))
     }
 
     pub fn bar_viewport(&self, pixel_ratio: f32) -> ((f32, f32), (f32, f32)) {
-        let outer_padding = (self.get_rem_length)(OUTER_PADDING_REM);
-        let bar_padding = (self.get_rem_length)(COLOR_BAR_PADDING_REM);
-        let color_bar_width = (self.get_rem_length)(COLOR_BAR_WIDTH_REM);
-        let (label_width, label_height) = if self.label.is_empty() {
+        let (start, end) = self.layout().bar;
+        coordinates::device_pixel_rect(start, end, pixel_ratio)
+    }
+
+    fn layout(&self) -> ColorBarLayout {
+        let outer_padding = (self.get_rem_length)(OUTER_PADDING_REM).0;
+        let ticks_padding = (self.get_rem_length)(TICKS_PADDING_REM).0;
+        let bar_padding = (self.get_rem_length)(COLOR_BAR_PADDING_REM).0;
+        let color_bar_width = (self.get_rem_length)(COLOR_BAR_WIDTH_REM).0;
+        let label = self.label();
+        let (label_width, label_height) = if label.is_empty() {
             (self.get_text_length)("empty")
         } else {
-            (self.get_text_length)(&self.label)
+            (self.get_text_length)(&label)
         };
-
-        let full_width = color_bar_width.0.max(label_width.0);
-        let width = color_bar_width.0;
-
-        let half_full_width = full_width / 2.0;
-        let half_width = width / 2.0;
-
+        let (label_width, label_height) = (label_width.0, label_height.0);
+        let (max_ticks_width, max_ticks_height) = (self.max_ticks_width.0, self.max_ticks_height.0);
         let (screen_width, screen_height) = self.screen_size;
 
-        let start_x = screen_width - outer_padding.0 - bar_padding.0 - half_full_width - half_width;
-        let start_y = outer_padding.0;
+        if self.is_horizontal() {
+            // The label is placed above the bar, and the ticks below it.
+            let height = outer_padding
+                + label_height
+                + bar_padding
+                + color_bar_width
+                + bar_padding
+                + ticks_padding
+                + max_ticks_height
+                + outer_padding;
+            let start_y = match self.placement {
+                ColorBarPlacement::Top => 0.0,
+                _ => screen_height - height,
+            };
+
+            let bar_start_x = outer_padding + (max_ticks_width / 2.0);
+            let bar_end_x = screen_width - outer_padding - (max_ticks_width / 2.0);
+            let bar_start_y = start_y + outer_padding + label_height + bar_padding;
+            let bar_end_y = bar_start_y + color_bar_width;
+            let ticks_y = bar_end_y + bar_padding + ticks_padding + max_ticks_height;
+
+            return ColorBarLayout {
+                bounding_box: ((0.0, start_y), (screen_width, start_y + height)),
+                bar: ((bar_start_x, bar_start_y), (bar_end_x, bar_end_y)),
+                label: (screen_width / 2.0, start_y + outer_padding + label_height),
+                ticks: ((bar_start_x, ticks_y), (bar_end_x, ticks_y)),
+            };
+        }
+
+        // The label is placed above the bar, and the ticks on the side facing the plot.
+        let full_width = color_bar_width.max(label_width);
+        let width = outer_padding
+            + outer_padding
+            + ticks_padding
+            + ticks_padding
+            + bar_padding
+            + bar_padding
+            + max_ticks_width
+            + full_width;
+        let (start_x, start_y, end_y) = match self.placement {
+            ColorBarPlacement::Left => (0.0, 0.0, screen_height),
+            ColorBarPlacement::Floating { x, y } => {
+                let height = (self.get_rem_length)(FLOATING_HEIGHT_REM).0;
+                let x = x.clamp(0.0, (screen_width - width).max(0.0));
+                let y = y.clamp(0.0, (screen_height - height).max(0.0));
+                (x, y, y + height)
+            }
+            _ => (screen_width - width, 0.0, screen_height),
+        };
+        let end_x = start_x + width;
 
-        let end_y = screen_height - outer_padding.0 - label_height.0 - bar_padding.0;
-        let height = end_y - start_y;
+        let (bar_center_x, ticks_x) = if self.placement == ColorBarPlacement::Left {
+            (
+                start_x + outer_padding + bar_padding + (full_width / 2.0),
+                start_x + outer_padding + ticks_padding + bar_padding + bar_padding + full_width,
+            )
+        } else {
+            (
+                end_x - outer_padding - bar_padding - (full_width / 2.0),
+                end_x - outer_padding - ticks_padding - bar_padding - bar_padding - full_width,
+            )
+        };
 
-        let start = (
-            (start_x * pixel_ratio).floor(),
-            ((screen_height - end_y) * pixel_ratio).floor(),
-        );
-        let size = (
-            (width * pixel_ratio).floor(),
-            (height * pixel_ratio).floor(),
-        );
-        (start, size)
+        let bar_start_y = start_y + outer_padding + label_height + bar_padding;
+        let bar_end_y = end_y - outer_padding;
+        let half_bar_width = color_bar_width / 2.0;
+
+        ColorBarLayout {
+            bounding_box: ((start_x, start_y), (end_x, end_y)),
+            bar: (
+                (bar_center_x - half_bar_width, bar_start_y),
+                (bar_center_x + half_bar_width, bar_end_y),
+            ),
+            label: (bar_center_x, start_y + outer_padding + label_height),
+            ticks: (
+                (ticks_x, bar_end_y + (max_ticks_height / 2.0)),
+                (ticks_x, bar_start_y + (max_ticks_height / 2.0)),
+            ),
+        }
     }
 }
 
@@ -3,7 +3,8 @@
 use once_cell::sync::OnceCell;
 
 use crate::colors::{
-    CieLab, CieLch, Color, ColorQuery, ColorSpace, ColorSpaceTransform, ColorTransparent, SRgb, Xyz,
+    CieLab, CieLch, Color, ColorQuery, ColorSpace, ColorSpaceTransform, ColorTransparent, Oklab,
+    Oklch, SRgb, Xyz,
 };
 use crate::lerp::Lerp;
 
@@ -23,6 +24,8 @@ pub fn to_color_scale<T>(&self) -> ColorScale<T>
         Xyz: ColorSpaceTransform<T>,
         CieLab: ColorSpaceTransform<T>,
         CieLch: ColorSpaceTransform<T>,
+        Oklab: ColorSpaceTransform<T>,
+        Oklch: ColorSpaceTransform<T>,
     {
         match self {
             ColorScaleDescriptor::Named(name) => {
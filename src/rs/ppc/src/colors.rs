@@ -243,6 +243,8 @@ fn f(v: u8) -> f32 {
 indirect_transform! {SRgb, SRgbLinear, Xyz}
 indirect_transform! {SRgb, SRgbLinear, CieLab}
 indirect_transform! {SRgb, SRgbLinear, CieLch}
+indirect_transform! {SRgb, SRgbLinear, Oklab}
+indirect_transform! {SRgb, SRgbLinear, Oklch}
 
 /// The sRGB color space with a D65 white point and linear values in the range [0, 1].
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
@@ -286,16 +288,6 @@ fn f(v: f32) -> u8 {
 
 impl ColorSpaceTransform<Xyz> for SRgbLinear {
     fn transform(self) -> Xyz {
-        const SRGB_LINEAR_TO_XYZ_MATRIX: [[f32; 3]; 3] = [
-            [0.4124108464885388, 0.3575845678529519, 0.18045380393360833],
-            [0.21264934272065283, 0.7151691357059038, 0.07218152157344333],
-            [
-                0.019331758429150258,
-                0.11919485595098397,
-                0.9503900340503373,
-            ],
-        ];
-
         let row = self.to_f32();
         let xyz = matrix_multiply(SRGB_LINEAR_TO_XYZ_MATRIX, row);
         Xyz::from_f32(xyz)
@@ -305,6 +297,29 @@ fn transform(self) -> Xyz {
 to_unknown! {SRgbLinear}
 indirect_transform! {SRgbLinear, Xyz, CieLab}
 indirect_transform! {SRgbLinear, Xyz, CieLch}
+indirect_transform! {SRgbLinear, Xyz, Oklab}
+indirect_transform! {SRgbLinear, Xyz, Oklch}
+
+/// The Display P3 color space.
+///
+/// Only used as an output color space of wide-gamut canvases.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct DisplayP3 {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+impl ColorSpace for DisplayP3 {
+    fn to_f32(self) -> [f32; 3] {
+        [self.r, self.g, self.b]
+    }
+
+    fn from_f32(values: [f32; 3]) -> Self {
+        let [r, g, b] = values;
+        Self { r, g, b }
+    }
+}
 
 /// The XYZ color space with a D65 white point.
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
@@ -330,22 +345,24 @@ impl ColorSpace for Xyz {
 
 impl ColorSpaceTransform<SRgbLinear> for Xyz {
     fn transform(self) -> SRgbLinear {
-        const XYZ_TO_SRGB_LINEAR_MATRIX: [[f32; 3]; 3] = [
-            [3.240812398895283, -1.5373084456298136, -0.4985865229069666],
-            [-0.9692430170086407, 1.8759663029085742, 0.04155503085668564],
-            [
-                0.055638398436112804,
-                -0.20400746093241362,
-                1.0571295702861434,
-            ],
-        ];
-
         let xyz: [f32; 3] = self.to_f32();
         let srgb = matrix_multiply(XYZ_TO_SRGB_LINEAR_MATRIX, xyz);
         SRgbLinear::from_f32(srgb)
     }
 }
 
+impl ColorSpaceTransform<DisplayP3> for Xyz {
+    fn transform(self) -> DisplayP3 {
+        let xyz: [f32; 3] = self.to_f32();
+        let p3 = matrix_multiply(XYZ_TO_DISPLAY_P3_LINEAR_MATRIX, xyz);
+
+        // Display P3 shares the transfer function of sRGB.
+        let encoded: SRgb = SRgbLinear::from_f32(p3).transform();
+        let [r, g, b] = encoded.to_f32();
+        DisplayP3 { r, g, b }
+    }
+}
+
 impl ColorSpaceTransform<CieLab> for Xyz {
     fn transform(self) -> CieLab {
         const EPSILON: f32 = 216.0 / 24389.0;
@@ -369,6 +386,16 @@ fn f(v: f32) -> f32 {
 }
 
 indirect_transform! {Xyz, CieLab, CieLch}
+indirect_transform! {Xyz, Oklab, Oklch}
+
+impl ColorSpaceTransform<Oklab> for Xyz {
+    fn transform(self) -> Oklab {
+        let lms = matrix_multiply(XYZ_TO_LMS_MATRIX, self.to_f32());
+        let lms = lms.map(f32::cbrt);
+        let lab = matrix_multiply(LMS_TO_OKLAB_MATRIX, lms);
+        Oklab::from_f32(lab)
+    }
+}
 
 /// The CIE L*a*b color space with a D65 white point.
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
@@ -392,6 +419,8 @@ impl ColorSpace for CieLab {
 to_unknown! {CieLab}
 indirect_transform! {CieLab, Xyz, SRgb}
 indirect_transform! {CieLab, Xyz, SRgbLinear}
+indirect_transform! {CieLab, Xyz, Oklab}
+indirect_transform! {CieLab, Xyz, Oklch}
 
 impl ColorSpaceTransform<Xyz> for CieLab {
     fn transform(self) -> Xyz {
@@ -455,6 +484,8 @@ impl ColorSpace for CieLch {
 indirect_transform! {CieLch, CieLab, SRgb}
 indirect_transform! {CieLch, CieLab, SRgbLinear}
 indirect_transform! {CieLch, CieLab, Xyz}
+indirect_transform! {CieLch, CieLab, Oklab}
+indirect_transform! {CieLch, CieLab, Oklch}
 
 impl ColorSpaceTransform<CieLab> for CieLch {
     fn transform(self) -> CieLab {
@@ -468,6 +499,155 @@ fn transform(self) -> CieLab {
     }
 }
 
+/// The Oklab color space with a D65 white point.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Oklab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+impl ColorSpace for Oklab {
+    fn to_f32(self) -> [f32; 3] {
+        [self.l, self.a, self.b]
+    }
+
+    fn from_f32(values: [f32; 3]) -> Self {
+        let [l, a, b] = values;
+        Self { l, a, b }
+    }
+}
+
+to_unknown! {Oklab}
+indirect_transform! {Oklab, Xyz, SRgb}
+indirect_transform! {Oklab, Xyz, SRgbLinear}
+indirect_transform! {Oklab, Xyz, CieLab}
+indirect_transform! {Oklab, Xyz, CieLch}
+
+impl ColorSpaceTransform<Xyz> for Oklab {
+    fn transform(self) -> Xyz {
+        let lms = matrix_multiply(OKLAB_TO_LMS_MATRIX, self.to_f32());
+        let lms = lms.map(|v| v.powi(3));
+        let xyz = matrix_multiply(LMS_TO_XYZ_MATRIX, lms);
+        Xyz::from_f32(xyz)
+    }
+}
+
+impl ColorSpaceTransform<Oklch> for Oklab {
+    fn transform(self) -> Oklch {
+        let l = self.l;
+        let c = self.a.hypot(self.b);
+        let h = self.b.atan2(self.a).to_degrees().rem_euclid(360.0);
+
+        Oklch::from_f32([l, c, h])
+    }
+}
+
+/// The Oklch color space with a D65 white point and hue expressed in degrees.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Oklch {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+}
+
+impl ColorSpace for Oklch {
+    fn to_f32(self) -> [f32; 3] {
+        [self.l, self.c, self.h]
+    }
+
+    fn from_f32(values: [f32; 3]) -> Self {
+        let [l, c, h] = values;
+        Self { l, c, h }
+    }
+}
+
+to_unknown! {Oklch}
+indirect_transform! {Oklch, Oklab, SRgb}
+indirect_transform! {Oklch, Oklab, SRgbLinear}
+indirect_transform! {Oklch, Oklab, Xyz}
+indirect_transform! {Oklch, Oklab, CieLab}
+indirect_transform! {Oklch, Oklab, CieLch}
+
+impl ColorSpaceTransform<Oklab> for Oklch {
+    fn transform(self) -> Oklab {
+        let h_rad = self.h.to_radians();
+
+        let l = self.l;
+        let a = self.c * h_rad.cos();
+        let b = self.c * h_rad.sin();
+
+        Oklab::from_f32([l, a, b])
+    }
+}
+
+// Matrices of the Oklab color space, as published in `https://bottosson.github.io/posts/oklab/`.
+
+/// Row-major matrix converting from `XYZ` to the `LMS` cone responses.
+const XYZ_TO_LMS_MATRIX: [[f32; 3]; 3] = [
+    [0.8189330101, 0.3618667424, -0.1288597137],
+    [0.0329845436, 0.9293118715, 0.0361456387],
+    [0.0482003018, 0.2643662691, 0.6338517070],
+];
+
+/// Row-major matrix converting from the `LMS` cone responses to `XYZ`.
+const LMS_TO_XYZ_MATRIX: [[f32; 3]; 3] = [
+    [1.2270138511, -0.5577999807, 0.2812561490],
+    [-0.0405801784, 1.1122568696, -0.0716766787],
+    [-0.0763812845, -0.4214819784, 1.5861632204],
+];
+
+/// Row-major matrix converting from the non-linear `LMS` values to `Oklab`.
+const LMS_TO_OKLAB_MATRIX: [[f32; 3]; 3] = [
+    [0.2104542553, 0.7936177850, -0.0040720468],
+    [1.9779984951, -2.4285922050, 0.4505937099],
+    [0.0259040371, 0.7827717662, -0.8086757660],
+];
+
+/// Row-major matrix converting from `Oklab` to the non-linear `LMS` values.
+const OKLAB_TO_LMS_MATRIX: [[f32; 3]; 3] = [
+    [1.0, 0.3963377774, 0.2158037573],
+    [1.0, -0.1055613458, -0.0638541728],
+    [1.0, -0.0894841775, -1.2914855480],
+];
+
+/// Row-major matrix converting from linear `sRGB` to `XYZ`.
+pub const SRGB_LINEAR_TO_XYZ_MATRIX: [[f32; 3]; 3] = [
+    [0.4124108464885388, 0.3575845678529519, 0.18045380393360833],
+    [0.21264934272065283, 0.7151691357059038, 0.07218152157344333],
+    [
+        0.019331758429150258,
+        0.11919485595098397,
+        0.9503900340503373,
+    ],
+];
+
+/// Row-major matrix converting from `XYZ` to linear `sRGB`.
+pub const XYZ_TO_SRGB_LINEAR_MATRIX: [[f32; 3]; 3] = [
+    [3.240812398895283, -1.5373084456298136, -0.4985865229069666],
+    [-0.9692430170086407, 1.8759663029085742, 0.04155503085668564],
+    [
+        0.055638398436112804,
+        -0.20400746093241362,
+        1.0571295702861434,
+    ],
+];
+
+/// Row-major matrix converting from `XYZ` to linear `Display P3`.
+pub const XYZ_TO_DISPLAY_P3_LINEAR_MATRIX: [[f32; 3]; 3] = [
+    [2.493496911941425, -0.9313836179191239, -0.40271078445071684],
+    [
+        -0.8294889695615747,
+        1.7626640603183463,
+        0.023624685841943577,
+    ],
+    [
+        0.03584583024378447,
+        -0.07617238926804182,
+        0.9568845240076872,
+    ],
+];
+
 fn matrix_multiply<const N: usize, const M: usize>(matrix: [[f32; N]; M], v: [f32; N]) -> [f32; M] {
     matrix.map(|row| row.into_iter().zip(v).map(|(a, b)| a * b).sum())
 }
@@ -481,6 +661,8 @@ pub enum ColorQuery<'a> {
     Xyz([f32; 3], Option<f32>),
     Lab([f32; 3], Option<f32>),
     Lch([f32; 3], Option<f32>),
+    Oklab([f32; 3], Option<f32>),
+    Oklch([f32; 3], Option<f32>),
 }
 
 impl ColorQuery<'_> {
@@ -491,6 +673,8 @@ pub fn resolve<T>(&self) -> ColorOpaque<T>
         Xyz: ColorSpaceTransform<T>,
         CieLab: ColorSpaceTransform<T>,
         CieLch: ColorSpaceTransform<T>,
+        Oklab: ColorSpaceTransform<T>,
+        Oklch: ColorSpaceTransform<T>,
     {
         self.resolve_with_alpha::<T>().without_alpha()
     }
@@ -502,6 +686,8 @@ pub fn resolve_with_alpha<T>(&self) -> ColorTransparent<T>
         Xyz: ColorSpaceTransform<T>,
         CieLab: ColorSpaceTransform<T>,
         CieLch: ColorSpaceTransform<T>,
+        Oklab: ColorSpaceTransform<T>,
+        Oklch: ColorSpaceTransform<T>,
     {
         match self {
             ColorQuery::Named(name) => Self::resolve_named(name)
@@ -544,6 +730,24 @@ pub fn resolve_with_alpha<T>(&self) -> ColorTransparent<T>
                 }
                 .transform()
             }
+            ColorQuery::Oklab(values, alpha) => {
+                let values = <Oklab as ColorSpace>::from_f32(*values);
+                let alpha = alpha.unwrap_or(1.0);
+                ColorTransparent::<Oklab> {
+                    color: ColorOpaque { values },
+                    alpha,
+                }
+                .transform()
+            }
+            ColorQuery::Oklch(values, alpha) => {
+                let values = <Oklch as ColorSpace>::from_f32(*values);
+                let alpha = alpha.unwrap_or(1.0);
+                ColorTransparent::<Oklch> {
+                    color: ColorOpaque { values },
+                    alpha,
+                }
+                .transform()
+            }
         }
     }
 
@@ -867,6 +1071,86 @@ fn resolve_lch(lch: &str) -> ColorTransparent<CieLch> {
         }
     }
 
+    fn resolve_oklab(oklab: &str) -> ColorTransparent<Oklab> {
+        static MATCHER: OnceCell<Regex> = OnceCell::new();
+        let matcher =
+            MATCHER.get_or_init(|| Regex::new("oklab\\((?<L>[+-]?([0-9]*[.])?[0-9]+) (?<a>[+-]?([0-9]*[.])?[0-9]+) (?<b>[+-]?([0-9]*[.])?[0-9]+)( (?<A>[+-]?([0-9]*[.])?[0-9]+))?\\)").unwrap());
+        let captures = matcher.captures(oklab).expect("invalid oklab string");
+
+        let l = captures
+            .name("L")
+            .unwrap()
+            .as_str()
+            .parse::<f32>()
+            .expect("expected a float value");
+        let a_star = captures
+            .name("a")
+            .unwrap()
+            .as_str()
+            .parse::<f32>()
+            .expect("expected a float value");
+        let b_star = captures
+            .name("b")
+            .unwrap()
+            .as_str()
+            .parse::<f32>()
+            .expect("expected a float value");
+        let a = captures
+            .name("A")
+            .map(|m| m.as_str().parse::<f32>().expect("expected a float value"))
+            .unwrap_or(1.0);
+        if !(0.0..=1.0).contains(&a) {
+            panic!("invalid alpha range");
+        }
+
+        ColorTransparent {
+            color: ColorOpaque {
+                values: Oklab::from_f32([l, a_star, b_star]),
+            },
+            alpha: a,
+        }
+    }
+
+    fn resolve_oklch(oklch: &str) -> ColorTransparent<Oklch> {
+        static MATCHER: OnceCell<Regex> = OnceCell::new();
+        let matcher =
+            MATCHER.get_or_init(|| Regex::new("oklch\\((?<L>[+-]?([0-9]*[.])?[0-9]+) (?<C>[+-]?([0-9]*[.])?[0-9]+) (?<h>[+-]?([0-9]*[.])?[0-9]+)( (?<A>[+-]?([0-9]*[.])?[0-9]+))?\\)").unwrap());
+        let captures = matcher.captures(oklch).expect("invalid oklch string");
+
+        let l = captures
+            .name("L")
+            .unwrap()
+            .as_str()
+            .parse::<f32>()
+            .expect("expected a float value");
+        let c = captures
+            .name("C")
+            .unwrap()
+            .as_str()
+            .parse::<f32>()
+            .expect("expected a float value");
+        let h = captures
+            .name("h")
+            .unwrap()
+            .as_str()
+            .parse::<f32>()
+            .expect("expected a float value");
+        let a = captures
+            .name("A")
+            .map(|m| m.as_str().parse::<f32>().expect("expected a float value"))
+            .unwrap_or(1.0);
+        if !(0.0..=1.0).contains(&a) {
+            panic!("invalid alpha range");
+        }
+
+        ColorTransparent {
+            color: ColorOpaque {
+                values: Oklch::from_f32([l, c, h]),
+            },
+            alpha: a,
+        }
+    }
+
     fn resolve_css<T>(css: &str) -> ColorTransparent<T>
     where
         T: ColorSpace,
@@ -874,6 +1158,8 @@ fn resolve_css<T>(css: &str) -> ColorTransparent<T>
         Xyz: ColorSpaceTransform<T>,
         CieLab: ColorSpaceTransform<T>,
         CieLch: ColorSpaceTransform<T>,
+        Oklab: ColorSpaceTransform<T>,
+        Oklch: ColorSpaceTransform<T>,
     {
         if let Some(color) = Self::resolve_named(css) {
             color.transform()
@@ -885,8 +1171,116 @@ fn resolve_css<T>(css: &str) -> ColorTransparent<T>
             Self::resolve_lab(css).transform()
         } else if css.starts_with("lch") {
             Self::resolve_lch(css).transform()
+        } else if css.starts_with("oklab") {
+            Self::resolve_oklab(css).transform()
+        } else if css.starts_with("oklch") {
+            Self::resolve_oklch(css).transform()
         } else {
             panic!("unrecognized css string {css:?}")
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: [f32; 3], expected: [f32; 3], tolerance: f32) {
+        for (a, e) in actual.into_iter().zip(expected) {
+            assert!(
+                (a - e).abs() <= tolerance,
+                "expected {expected:?}, got {actual:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn xyz_to_oklab_matches_the_reference_values() {
+        // Table of `https://bottosson.github.io/posts/oklab/`.
+        let references = [
+            ([0.950, 1.000, 1.089], [1.000, 0.000, 0.000]),
+            ([1.000, 0.000, 0.000], [0.450, 1.236, -0.019]),
+            ([0.000, 1.000, 0.000], [0.922, -0.671, 0.263]),
+            ([0.000, 0.000, 1.000], [0.153, -1.415, -0.449]),
+        ];
+
+        for (xyz, expected) in references {
+            let oklab: Oklab = Xyz::from_f32(xyz).transform();
+            assert_close(oklab.to_f32(), expected, 1e-3);
+        }
+    }
+
+    #[test]
+    fn srgb_to_oklab_matches_the_reference_values() {
+        let references = [
+            (SRgb { r: 255, g: 0, b: 0 }, [0.628, 0.225, 0.126]),
+            (SRgb { r: 0, g: 255, b: 0 }, [0.866, -0.234, 0.179]),
+            (SRgb { r: 0, g: 0, b: 255 }, [0.452, -0.032, -0.312]),
+            (
+                SRgb {
+                    r: 255,
+                    g: 255,
+                    b: 255,
+                },
+                [1.000, 0.000, 0.000],
+            ),
+        ];
+
+        for (srgb, expected) in references {
+            let oklab: Oklab = srgb.transform();
+            assert_close(oklab.to_f32(), expected, 2e-3);
+        }
+    }
+
+    #[test]
+    fn oklab_round_trips_through_xyz_and_srgb() {
+        for srgb in [
+            SRgb { r: 255, g: 0, b: 0 },
+            SRgb {
+                r: 12,
+                g: 200,
+                b: 97,
+            },
+            SRgb {
+                r: 64,
+                g: 64,
+                b: 64,
+            },
+            SRgb {
+                r: 255,
+                g: 255,
+                b: 255,
+            },
+        ] {
+            let oklab: Oklab = srgb.transform();
+            let round_trip: SRgb = oklab.transform();
+            assert_eq!(round_trip, srgb);
+        }
+
+        let oklab = Oklab::from_f32([0.7, 0.1, -0.05]);
+        let xyz: Xyz = oklab.transform();
+        let round_trip: Oklab = xyz.transform();
+        assert_close(round_trip.to_f32(), oklab.to_f32(), 1e-5);
+    }
+
+    #[test]
+    fn oklch_round_trips_through_oklab() {
+        let oklch = Oklch::from_f32([0.7, 0.15, 200.0]);
+        let oklab: Oklab = oklch.transform();
+        let round_trip: Oklch = oklab.transform();
+        assert_close(round_trip.to_f32(), oklch.to_f32(), 1e-4);
+
+        // Negative hues are wrapped into the range [0, 360).
+        let oklab = Oklab::from_f32([0.5, 0.0, -0.1]);
+        let oklch: Oklch = oklab.transform();
+        assert_close(oklch.to_f32(), [0.5, 0.1, 270.0], 1e-4);
+    }
+
+    #[test]
+    fn css_oklab_and_oklch_resolve_to_the_same_color() {
+        let from_oklab = ColorQuery::Css("oklab(0.628 0.225 0.126)".into()).resolve::<SRgb>();
+        let from_oklch = ColorQuery::Css("oklch(0.628 0.258 29.2)".into()).resolve::<SRgb>();
+        assert_eq!(from_oklab.values, SRgb { r: 255, g: 0, b: 0 });
+        assert_eq!(from_oklch.values, SRgb { r: 255, g: 0, b: 0 });
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 use crate::lerp::{InverseLerp, Lerp};
+use serde::{Deserialize, Serialize};
 use std::{
     fmt::Debug,
     ops::{Add, AddAssign, Deref, DerefMut, Div, DivAssign, Mul, MulAssign, Sub, SubAssign},
@@ -127,6 +128,14 @@ pub trait ComponentAccessible {
 }
 
 /// A position in a coordinate system.
+#[derive(Serialize, Deserialize)]
+#[serde(
+    transparent,
+    bound(
+        serialize = "T::Position: Serialize",
+        deserialize = "T::Position: Deserialize<'de>"
+    )
+)]
 pub struct Position<T: CoordinateSystem> {
     value: T::Position,
 }
@@ -280,6 +289,14 @@ fn cmp_component(&self, rhs: &Self, idx: usize) -> Option<std::cmp::Ordering> {
 }
 
 /// An offset in a coordinate system.
+#[derive(Serialize, Deserialize)]
+#[serde(
+    transparent,
+    bound(
+        serialize = "T::Offset: Serialize",
+        deserialize = "T::Offset: Deserialize<'de>"
+    )
+)]
 pub struct Offset<T: CoordinateSystem> {
     value: T::Offset,
 }
@@ -470,6 +487,14 @@ fn cmp_component(&self, rhs: &Self, idx: usize) -> Option<std::cmp::Ordering> {
 }
 
 /// A length in a coordinate system.
+#[derive(Serialize, Deserialize)]
+#[serde(
+    transparent,
+    bound(
+        serialize = "T::Length: Serialize",
+        deserialize = "T::Length: Deserialize<'de>"
+    )
+)]
 pub struct Length<T: CoordinateSystem> {
     value: T::Length,
 }
@@ -616,7 +641,11 @@ fn deref_mut(&mut self) -> &mut Self::Target {
 }
 
 /// An axis-aligned bounding box.
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "T::Position: Serialize",
+    deserialize = "T::Position: Deserialize<'de>"
+))]
 pub struct Aabb<T: CoordinateSystem> {
     start: Position<T>,
     end: Position<T>,
@@ -740,7 +769,7 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 /// The screen coordinate system.
 ///
 /// Goes from `(0, height)` on the bottom left up to `(width, 0)` on the top right.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct ScreenSpace;
 
 impl CoordinateSystem for ScreenSpace {
@@ -752,7 +781,7 @@ impl CoordinateSystem for ScreenSpace {
 /// The view coordinate system.
 ///
 /// Goes from `(0, 0)` on the bottom left up to `(width, height)` on the top right.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct ViewSpace;
 
 impl CoordinateSystem for ViewSpace {
@@ -764,7 +793,7 @@ impl CoordinateSystem for ViewSpace {
 /// The global coordinate system.
 ///
 /// Goes from `(0, 0)` on the bottom left up to `(width, 1.0)` on the top right.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct WorldSpace;
 
 impl CoordinateSystem for WorldSpace {
@@ -776,7 +805,7 @@ impl CoordinateSystem for WorldSpace {
 /// The local coordinate system.
 ///
 /// Goes from `(0, 0)` on the bottom left up to `(width, 1.0)` on the top right.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct LocalSpace;
 
 impl CoordinateSystem for LocalSpace {
@@ -786,16 +815,24 @@ impl CoordinateSystem for LocalSpace {
 }
 
 /// A type for transforming between screen and view space.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct ScreenViewTransformer {
     max_y: f32,
+    offset: (f32, f32),
 }
 
 impl ScreenViewTransformer {
     /// Constructs a new instance.
     pub fn new(height: f32) -> Self {
+        Self::with_offset(height, (0.0, 0.0))
+    }
+
+    /// Constructs a new instance for a view, whose top-left corner lies at
+    /// `offset` in screen space.
+    pub fn with_offset(height: f32, offset: (f32, f32)) -> Self {
         Self {
             max_y: height - 1.0,
+            offset,
         }
     }
 }
@@ -806,8 +843,8 @@ fn transform_position(
         position: <ScreenSpace as CoordinateSystem>::Position,
     ) -> <ViewSpace as CoordinateSystem>::Position {
         CartesianPosition {
-            x: position.x,
-            y: self.max_y - position.y,
+            x: position.x - self.offset.0,
+            y: self.max_y - (position.y - self.offset.1),
         }
     }
 
@@ -825,8 +862,8 @@ fn transform_position(
         position: <ViewSpace as CoordinateSystem>::Position,
     ) -> <ScreenSpace as CoordinateSystem>::Position {
         CartesianPosition {
-            x: position.x,
-            y: self.max_y - position.y,
+            x: position.x + self.offset.0,
+            y: (self.max_y - position.y) + self.offset.1,
         }
     }
 
@@ -839,7 +876,7 @@ fn transform_offset(
 }
 
 /// A type for transforming between view and world space.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct ViewWorldTransformer {
     view_max_y: f32,
     world_offset: f32,
@@ -904,7 +941,7 @@ fn transform_offset(
 }
 
 /// A type for transforming between world and local space.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct WorldLocalTransformer {
     world_offset: f32,
     y_scaling: f32,
@@ -959,14 +996,59 @@ fn transform_offset(
     }
 }
 
+/// Maps a rectangle from logical pixels to the device pixels covered by it.
+///
+/// Returns the start and size of the rectangle. The edges are rounded
+/// separately, such that the rectangle stays within the rounded size of the
+/// canvas, and adjacent rectangles share their edges for fractional ratios.
+/// Inverted rectangles, e.g. of a collapsed plot area, map to an empty one.
+pub fn device_pixel_rect(
+    start: (f32, f32),
+    end: (f32, f32),
+    pixel_ratio: f32,
+) -> ((f32, f32), (f32, f32)) {
+    let start = (
+        (start.0 * pixel_ratio).round().max(0.0),
+        (start.1 * pixel_ratio).round().max(0.0),
+    );
+    let end = (
+        (end.0 * pixel_ratio).round().max(start.0),
+        (end.1 * pixel_ratio).round().max(start.1),
+    );
+    (start, (end.0 - start.0, end.1 - start.1))
+}
+
+/// Returns the pixel ratio, or `1.0` if it is not a positive finite value.
+///
+/// Detached elements may report a ratio of zero.
+pub fn valid_pixel_ratio(pixel_ratio: f32) -> f32 {
+    if pixel_ratio.is_finite() && pixel_ratio > 0.0 {
+        pixel_ratio
+    } else {
+        1.0
+    }
+}
+
+/// Maps a size from logical pixels to device pixels.
+///
+/// Uses the same rounding as [`device_pixel_rect`], such that the rectangles
+/// within the logical size never exceed the returned size.
+pub fn device_pixel_size(size: (u32, u32), pixel_ratio: f32) -> (u32, u32) {
+    (
+        (size.0 as f32 * pixel_ratio).round() as u32,
+        (size.1 as f32 * pixel_ratio).round() as u32,
+    )
+}
+
 mod cartesian {
     use crate::lerp::{InverseLerp, Lerp};
 
     use super::{ComponentAccessible, LengthType, OffsetType, PositionType};
+    use serde::{Deserialize, Serialize};
     use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
 
     /// Position in the 2d cartesian coordinate system.
-    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
     pub struct CartesianPosition<const INVERSE_Y: bool> {
         pub x: f32,
         pub y: f32,
@@ -1096,7 +1178,7 @@ fn abs_offset(self, rhs: Self) -> CartesianOffset {
     }
 
     /// Offset in the 2d cartesian coordinate system.
-    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
     pub struct CartesianOffset {
         pub x: f32,
         pub y: f32,
@@ -1262,7 +1344,8 @@ impl<const INVERSE_Y: bool> OffsetType<CartesianPosition<INVERSE_Y>, CartesianLe
 
     /// Length in the 2d cartesian coordinate system.
     #[repr(transparent)]
-    #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+    #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+    #[serde(transparent)]
     pub struct CartesianLength(pub f32);
 
     impl From<f32> for CartesianLength {
@@ -1367,3 +1450,102 @@ impl<const INVERSE_Y: bool> LengthType<CartesianPosition<INVERSE_Y>, CartesianOf
         const UNIT: Self = CartesianLength(1.0);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PIXEL_RATIOS: [f32; 5] = [1.0, 1.25, 1.5, 2.0, 3.0];
+
+    #[test]
+    fn device_pixel_rects_stay_inside_the_canvas() {
+        let canvas = (803, 457);
+        for ratio in PIXEL_RATIOS {
+            let (width, height) = device_pixel_size(canvas, ratio);
+            let (start, size) =
+                device_pixel_rect((0.0, 0.0), (canvas.0 as f32, canvas.1 as f32), ratio);
+            assert_eq!(start, (0.0, 0.0));
+            assert_eq!(size, (width as f32, height as f32));
+
+            let (start, size) = device_pixel_rect((12.3, 7.7), (790.6, 449.9), ratio);
+            assert!(start.0 >= 0.0 && start.1 >= 0.0);
+            assert!(start.0 + size.0 <= width as f32, "ratio {ratio}");
+            assert!(start.1 + size.1 <= height as f32, "ratio {ratio}");
+        }
+    }
+
+    #[test]
+    fn adjacent_device_pixel_rects_share_their_edges() {
+        let canvas = (803.0, 457.0);
+        for ratio in PIXEL_RATIOS {
+            // Split the canvas into columns and rows with fractional edges.
+            let columns = (0..=7)
+                .map(|i| i as f32 * canvas.0 / 7.0)
+                .collect::<Vec<_>>();
+            let rows = (0..=3)
+                .map(|i| i as f32 * canvas.1 / 3.0)
+                .collect::<Vec<_>>();
+
+            for y in rows.windows(2) {
+                let mut next_x = 0.0;
+                for x in columns.windows(2) {
+                    let (start, size) = device_pixel_rect((x[0], y[0]), (x[1], y[1]), ratio);
+                    assert_eq!(start.0, next_x, "ratio {ratio}");
+                    assert!(size.0 > 0.0 && size.1 > 0.0);
+                    next_x = start.0 + size.0;
+                }
+                assert_eq!(next_x, (canvas.0 * ratio).round());
+            }
+
+            for x in columns.windows(2) {
+                let mut next_y = 0.0;
+                for y in rows.windows(2) {
+                    let (start, size) = device_pixel_rect((x[0], y[0]), (x[1], y[1]), ratio);
+                    assert_eq!(start.1, next_y, "ratio {ratio}");
+                    next_y = start.1 + size.1;
+                }
+                assert_eq!(next_y, (canvas.1 * ratio).round());
+            }
+        }
+    }
+
+    #[test]
+    fn device_pixel_sizes_round_to_the_nearest_pixel() {
+        assert_eq!(device_pixel_size((801, 601), 1.0), (801, 601));
+        assert_eq!(device_pixel_size((801, 601), 1.25), (1001, 751));
+        assert_eq!(device_pixel_size((801, 601), 1.5), (1202, 902));
+        assert_eq!(device_pixel_size((801, 601), 2.0), (1602, 1202));
+        assert_eq!(device_pixel_size((801, 601), 3.0), (2403, 1803));
+    }
+
+    #[test]
+    fn zero_sized_canvases_map_to_empty_rects() {
+        for ratio in PIXEL_RATIOS {
+            assert_eq!(device_pixel_size((0, 0), ratio), (0, 0));
+            assert_eq!(device_pixel_size((0, 300), ratio).0, 0);
+
+            let (start, size) = device_pixel_rect((0.0, 0.0), (0.0, 0.0), ratio);
+            assert_eq!((start, size), ((0.0, 0.0), (0.0, 0.0)));
+        }
+    }
+
+    #[test]
+    fn collapsed_rects_are_clamped_to_the_canvas_origin() {
+        // A plot area behind the color bar of a zero sized canvas ends before it starts.
+        for ratio in PIXEL_RATIOS {
+            let (start, size) = device_pixel_rect((-4.0, 20.0), (-10.0, 8.0), ratio);
+            assert_eq!(start.0, 0.0);
+            assert!(start.1 >= 0.0);
+            assert_eq!(size, (0.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn invalid_pixel_ratios_fall_back_to_one() {
+        assert_eq!(valid_pixel_ratio(1.5), 1.5);
+        assert_eq!(valid_pixel_ratio(0.0), 1.0);
+        assert_eq!(valid_pixel_ratio(-2.0), 1.0);
+        assert_eq!(valid_pixel_ratio(f32::NAN), 1.0);
+        assert_eq!(valid_pixel_ratio(f32::INFINITY), 1.0);
+    }
+}
@@ -0,0 +1,373 @@
+//! Diagnostics reported by the renderer.
+
+/// A warning emitted by the renderer.
+///
+/// Each warning is identified by a stable code, which can be used by the
+/// host application to provide a translated message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Warning {
+    RemoveNonexistentAxis,
+    AddDuplicateAxis,
+    InfiniteAxisPoint,
+    MismatchedPointCount,
+    SetPointsOfNonexistentAxis,
+    AppendToNonexistentAxis,
+    InvalidAxisRange,
+    AxisRangeExcludesData,
+    InvalidVisibleRange,
+    VisibleRangeOutsideRange,
+    VisibleRangeOfNonexistentAxis,
+    InvalidAxisJitter,
+    JitterOfNonexistentAxis,
+    CategoryOrderOfNonexistentAxis,
+    CategoryOrderOfNonCategoricalAxis,
+    TickOutsideRange,
+    InvalidAxisHistogram,
+    DuplicateAxisInOrder,
+    NonexistentAxisInOrder,
+    ViewOfNonexistentAxis,
+    RemoveNonexistentLabel,
+    AddDuplicateLabel,
+    ModifyNonexistentLabel,
+    ActivateNonexistentLabel,
+    RemoveNonexistentLabelGroup,
+    AddDuplicateLabelGroup,
+    AssignNonexistentLabelGroup,
+    ColorByNonexistentLabelGroup,
+    InvalidColorBarPosition,
+    InvalidAxisHeaderHeight,
+    InvalidCurveAreaWidth,
+    InvalidColorBarTicks,
+    InvalidBackgroundLayer,
+    RemoveNonexistentAnnotation,
+    AddDuplicateAnnotation,
+    AnnotateNonexistentAxis,
+    InvalidAnnotationAnchor,
+    RemoveNonexistentAxisRegion,
+    AddDuplicateAxisRegion,
+    AxisRegionOfNonexistentAxis,
+    InvalidAxisRegionRange,
+    InvalidClusterOptions,
+    ColorByMissingClusters,
+    InvalidOutlierOptions,
+    ColorByMissingOutlierScores,
+    InvalidDensityOptions,
+    InvalidHistogramOptions,
+    InvalidLiveOptions,
+    FreezeWithoutLiveMode,
+    CompareNonexistentLabel,
+    BrushesOfNonexistentLabel,
+    BrushesOfNonexistentAxis,
+    TooFewBrushControlPoints,
+    MainBrushSegmentOutOfBounds,
+    InvalidBrushControlPoint,
+    UnorderedBrushControlPoints,
+    EditNonexistentBrush,
+    InvalidBrushEdit,
+    ImportCurveOfNonexistentLabel,
+    ImportCurveOfNonexistentAxis,
+    InvalidCurveSamples,
+    InvalidCurveSegments,
+    RevertOfNonexistentLabel,
+    RevertToNonexistentStep,
+    RemoteOperationWithoutSync,
+    InfiniteReferencePoint,
+    MismatchedReferencePointCount,
+    GpuValidationError,
+}
+
+impl Warning {
+    /// Returns the stable code of the warning.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Warning::RemoveNonexistentAxis => "transaction.remove_nonexistent_axis",
+            Warning::AddDuplicateAxis => "transaction.add_duplicate_axis",
+            Warning::InfiniteAxisPoint => "transaction.infinite_axis_point",
+            Warning::MismatchedPointCount => "transaction.mismatched_point_count",
+            Warning::SetPointsOfNonexistentAxis => "transaction.set_points_of_nonexistent_axis",
+            Warning::AppendToNonexistentAxis => "transaction.append_to_nonexistent_axis",
+            Warning::InvalidAxisRange => "transaction.invalid_axis_range",
+            Warning::AxisRangeExcludesData => "transaction.axis_range_excludes_data",
+            Warning::InvalidVisibleRange => "transaction.invalid_visible_range",
+            Warning::VisibleRangeOutsideRange => "transaction.visible_range_outside_range",
+            Warning::VisibleRangeOfNonexistentAxis => {
+                "transaction.visible_range_of_nonexistent_axis"
+            }
+            Warning::InvalidAxisJitter => "transaction.invalid_axis_jitter",
+            Warning::JitterOfNonexistentAxis => "transaction.jitter_of_nonexistent_axis",
+            Warning::CategoryOrderOfNonexistentAxis => {
+                "transaction.category_order_of_nonexistent_axis"
+            }
+            Warning::CategoryOrderOfNonCategoricalAxis => {
+                "transaction.category_order_of_non_categorical_axis"
+            }
+            Warning::TickOutsideRange => "transaction.tick_outside_range",
+            Warning::InvalidAxisHistogram => "transaction.invalid_axis_histogram",
+            Warning::DuplicateAxisInOrder => "transaction.duplicate_axis_in_order",
+            Warning::NonexistentAxisInOrder => "transaction.nonexistent_axis_in_order",
+            Warning::ViewOfNonexistentAxis => "transaction.view_of_nonexistent_axis",
+            Warning::RemoveNonexistentLabel => "transaction.remove_nonexistent_label",
+            Warning::AddDuplicateLabel => "transaction.add_duplicate_label",
+            Warning::ModifyNonexistentLabel => "transaction.modify_nonexistent_label",
+            Warning::ActivateNonexistentLabel => "transaction.activate_nonexistent_label",
+            Warning::RemoveNonexistentLabelGroup => "transaction.remove_nonexistent_label_group",
+            Warning::AddDuplicateLabelGroup => "transaction.add_duplicate_label_group",
+            Warning::AssignNonexistentLabelGroup => "transaction.assign_nonexistent_label_group",
+            Warning::ColorByNonexistentLabelGroup => "transaction.color_by_nonexistent_label_group",
+            Warning::InvalidColorBarPosition => "transaction.invalid_color_bar_position",
+            Warning::InvalidAxisHeaderHeight => "transaction.invalid_axis_header_height",
+            Warning::InvalidCurveAreaWidth => "transaction.invalid_curve_area_width",
+            Warning::InvalidColorBarTicks => "transaction.invalid_color_bar_ticks",
+            Warning::InvalidBackgroundLayer => "transaction.invalid_background_layer",
+            Warning::RemoveNonexistentAnnotation => "transaction.remove_nonexistent_annotation",
+            Warning::AddDuplicateAnnotation => "transaction.add_duplicate_annotation",
+            Warning::AnnotateNonexistentAxis => "transaction.annotate_nonexistent_axis",
+            Warning::InvalidAnnotationAnchor => "transaction.invalid_annotation_anchor",
+            Warning::RemoveNonexistentAxisRegion => "transaction.remove_nonexistent_axis_region",
+            Warning::AddDuplicateAxisRegion => "transaction.add_duplicate_axis_region",
+            Warning::AxisRegionOfNonexistentAxis => "transaction.axis_region_of_nonexistent_axis",
+            Warning::InvalidAxisRegionRange => "transaction.invalid_axis_region_range",
+            Warning::InvalidClusterOptions => "transaction.invalid_cluster_options",
+            Warning::ColorByMissingClusters => "transaction.color_by_missing_clusters",
+            Warning::InvalidOutlierOptions => "transaction.invalid_outlier_options",
+            Warning::ColorByMissingOutlierScores => "transaction.color_by_missing_outlier_scores",
+            Warning::InvalidDensityOptions => "transaction.invalid_density_options",
+            Warning::InvalidHistogramOptions => "transaction.invalid_histogram_options",
+            Warning::InvalidLiveOptions => "transaction.invalid_live_options",
+            Warning::FreezeWithoutLiveMode => "transaction.freeze_without_live_mode",
+            Warning::CompareNonexistentLabel => "transaction.compare_nonexistent_label",
+            Warning::BrushesOfNonexistentLabel => "transaction.brushes_of_nonexistent_label",
+            Warning::BrushesOfNonexistentAxis => "transaction.brushes_of_nonexistent_axis",
+            Warning::TooFewBrushControlPoints => "transaction.too_few_brush_control_points",
+            Warning::MainBrushSegmentOutOfBounds => "transaction.main_brush_segment_out_of_bounds",
+            Warning::InvalidBrushControlPoint => "transaction.invalid_brush_control_point",
+            Warning::UnorderedBrushControlPoints => "transaction.unordered_brush_control_points",
+            Warning::EditNonexistentBrush => "transaction.edit_nonexistent_brush",
+            Warning::InvalidBrushEdit => "transaction.invalid_brush_edit",
+            Warning::ImportCurveOfNonexistentLabel => {
+                "transaction.import_curve_of_nonexistent_label"
+            }
+            Warning::ImportCurveOfNonexistentAxis => "transaction.import_curve_of_nonexistent_axis",
+            Warning::InvalidCurveSamples => "transaction.invalid_curve_samples",
+            Warning::InvalidCurveSegments => "transaction.invalid_curve_segments",
+            Warning::RevertOfNonexistentLabel => "transaction.revert_of_nonexistent_label",
+            Warning::RevertToNonexistentStep => "transaction.revert_to_nonexistent_step",
+            Warning::RemoteOperationWithoutSync => "transaction.remote_operation_without_sync",
+            Warning::InfiniteReferencePoint => "transaction.infinite_reference_point",
+            Warning::MismatchedReferencePointCount => {
+                "transaction.mismatched_reference_point_count"
+            }
+            Warning::GpuValidationError => "gpu.validation_error",
+        }
+    }
+
+    /// Returns the default english message of the warning.
+    pub fn message(&self) -> &'static str {
+        match self {
+            Warning::RemoveNonexistentAxis => "Transaction removes a nonexistent axis.",
+            Warning::AddDuplicateAxis => "Transaction adds a duplicate axis.",
+            Warning::InfiniteAxisPoint => "Axis data points must not be infinite.",
+            Warning::MismatchedPointCount => "Axis contains more data points than the other axes.",
+            Warning::SetPointsOfNonexistentAxis => {
+                "Transaction sets the data points of a nonexistent axis."
+            }
+            Warning::AppendToNonexistentAxis => {
+                "Transaction appends data points to a nonexistent axis."
+            }
+            Warning::InvalidAxisRange => "Axis range must be finite with min < max.",
+            Warning::AxisRangeExcludesData => "Axis range must contain all data points.",
+            Warning::InvalidVisibleRange => "Axis visible range must be finite with min < max.",
+            Warning::VisibleRangeOutsideRange => {
+                "Axis visible range must be contained in the axis range."
+            }
+            Warning::VisibleRangeOfNonexistentAxis => {
+                "Transaction sets the visible range of a nonexistent axis."
+            }
+            Warning::InvalidAxisJitter => "Axis jitter must be in the range [0, 0.5].",
+            Warning::JitterOfNonexistentAxis => {
+                "Transaction sets the jitter of a nonexistent axis."
+            }
+            Warning::CategoryOrderOfNonexistentAxis => {
+                "Transaction optimizes the category order of a nonexistent axis."
+            }
+            Warning::CategoryOrderOfNonCategoricalAxis => {
+                "Only the categories of axes with few distinct values can be reordered."
+            }
+            Warning::TickOutsideRange => "Axis tick position lies outside of the axis range.",
+            Warning::InvalidAxisHistogram => {
+                "Axis histogram must contain at least one finite, non-negative bin."
+            }
+            Warning::DuplicateAxisInOrder => "Transaction axis order contains duplicates.",
+            Warning::NonexistentAxisInOrder => "Transaction axis order contains nonexistent axes.",
+            Warning::ViewOfNonexistentAxis => "Transaction moves the view to nonexistent axes.",
+            Warning::RemoveNonexistentLabel => "Transaction removes a nonexistent label.",
+            Warning::AddDuplicateLabel => "Transaction adds a duplicate label.",
+            Warning::ModifyNonexistentLabel => "Transaction modifies a nonexistent label.",
+            Warning::ActivateNonexistentLabel => {
+                "Transaction sets the active label to a nonexistent label."
+            }
+            Warning::RemoveNonexistentLabelGroup => {
+                "Transaction removes a nonexistent label group."
+            }
+            Warning::AddDuplicateLabelGroup => "Transaction adds a duplicate label group.",
+            Warning::AssignNonexistentLabelGroup => {
+                "Transaction assigns a label to a nonexistent label group."
+            }
+            Warning::ColorByNonexistentLabelGroup => {
+                "Transaction colors the data by the probability of a nonexistent label group."
+            }
+            Warning::InvalidColorBarPosition => "The position of the color bar must be finite.",
+            Warning::InvalidAxisHeaderHeight => {
+                "The height of the axis headers must lie between 0 and 10 rem."
+            }
+            Warning::InvalidCurveAreaWidth => {
+                "The width of the curve area must lie between 0 and 0.5."
+            }
+            Warning::InvalidColorBarTicks => {
+                "Color bars require at least two ticks, positioned in the range [0, 1]."
+            }
+            Warning::InvalidBackgroundLayer => {
+                "Background layers require an opacity in the range [0, 1] and a positive scale."
+            }
+            Warning::RemoveNonexistentAnnotation => "Transaction removes a nonexistent annotation.",
+            Warning::AddDuplicateAnnotation => "Transaction adds a duplicate annotation.",
+            Warning::AnnotateNonexistentAxis => {
+                "Transaction anchors an annotation to a nonexistent axis."
+            }
+            Warning::InvalidAnnotationAnchor => {
+                "Annotations must be anchored to a finite value or an existing datum."
+            }
+            Warning::RemoveNonexistentAxisRegion => {
+                "Transaction removes a nonexistent axis region."
+            }
+            Warning::AddDuplicateAxisRegion => "Transaction adds a duplicate axis region.",
+            Warning::AxisRegionOfNonexistentAxis => {
+                "Transaction adds a region to a nonexistent axis."
+            }
+            Warning::InvalidAxisRegionRange => "Axis region range must be finite with min < max.",
+            Warning::InvalidClusterOptions => {
+                "Clustering requires between 2 and 64 clusters and at least one iteration."
+            }
+            Warning::ColorByMissingClusters => {
+                "Transaction colors the data by the clusters, without computing them."
+            }
+            Warning::InvalidOutlierOptions => {
+                "The outlier threshold must lie between 0 and the maximum outlier score of 10."
+            }
+            Warning::ColorByMissingOutlierScores => {
+                "Transaction colors the data by the outlier scores, without computing them."
+            }
+            Warning::InvalidDensityOptions => {
+                "The bandwidth of the density curves must lie between 0 and 1."
+            }
+            Warning::InvalidHistogramOptions => {
+                "The number of histogram bins must lie between 1 and 256."
+            }
+            Warning::InvalidLiveOptions => {
+                "The live mode requires a finite, non-negative interval and at least one point."
+            }
+            Warning::FreezeWithoutLiveMode => {
+                "Transaction freezes the live data, without enabling the live mode."
+            }
+            Warning::CompareNonexistentLabel => {
+                "Transaction compares the curves of a nonexistent label."
+            }
+            Warning::BrushesOfNonexistentLabel => {
+                "Transaction specifies the brushes of a nonexistent label."
+            }
+            Warning::BrushesOfNonexistentAxis => {
+                "Transaction specifies the brushes of a nonexistent axis."
+            }
+            Warning::TooFewBrushControlPoints => {
+                "A brush must contain at least two control points."
+            }
+            Warning::MainBrushSegmentOutOfBounds => "Main brush segment is out of bounds.",
+            Warning::InvalidBrushControlPoint => "Invalid brush control point.",
+            Warning::UnorderedBrushControlPoints => {
+                "Brush control points must be ordered by increasing x value."
+            }
+            Warning::EditNonexistentBrush => "Transaction edits a nonexistent brush.",
+            Warning::InvalidBrushEdit => {
+                "Brush edits must be finite, with a lower bound not exceeding the upper bound."
+            }
+            Warning::ImportCurveOfNonexistentLabel => {
+                "Transaction imports a probability curve of a nonexistent label."
+            }
+            Warning::ImportCurveOfNonexistentAxis => {
+                "Transaction imports a probability curve of a nonexistent axis."
+            }
+            Warning::InvalidCurveSamples => {
+                "Probability curves require at least two samples, each in the range [0, 1]."
+            }
+            Warning::InvalidCurveSegments => {
+                "Probability curve segments must be ordered, non-overlapping and lie in [0, 1]."
+            }
+            Warning::RevertOfNonexistentLabel => {
+                "Transaction reverts the history of a nonexistent label."
+            }
+            Warning::RevertToNonexistentStep => {
+                "Transaction reverts a label to a step missing from its history."
+            }
+            Warning::RemoteOperationWithoutSync => {
+                "Remote operations require the operation sync to be enabled."
+            }
+            Warning::InfiniteReferencePoint => "Reference data points must not be infinite.",
+            Warning::MismatchedReferencePointCount => {
+                "All axes of the reference data must contain the same number of points."
+            }
+            Warning::GpuValidationError => "A WebGPU operation failed validation.",
+        }
+    }
+}
+
+/// A diagnostic forwarded to the host application.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    warning: Warning,
+    context: Vec<(&'static str, String)>,
+}
+
+impl Diagnostic {
+    /// Constructs a new diagnostic without any context.
+    pub fn new(warning: Warning) -> Self {
+        Self {
+            warning,
+            context: Vec::new(),
+        }
+    }
+
+    /// Attaches a context entry to the diagnostic.
+    pub fn with(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        self.context.push((key, value.into()));
+        self
+    }
+
+    /// Returns whether the diagnostic reports an internal error, instead of
+    /// an invalid input.
+    pub fn is_error(&self) -> bool {
+        self.warning == Warning::GpuValidationError
+    }
+
+    /// Converts the diagnostic into the diff sent to the callback.
+    pub fn into_diff(self) -> crate::api::PlotDiff {
+        crate::api::PlotDiff::Diagnostic {
+            code: self.warning.code().into(),
+            message: self.warning.message().into(),
+            context: self
+                .context
+                .into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.warning.code(), self.warning.message())?;
+        for (key, value) in &self.context {
+            write!(f, " {key}={value:?}")?;
+        }
+        Ok(())
+    }
+}
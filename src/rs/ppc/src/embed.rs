@@ -0,0 +1,224 @@
+//! One-call embedding of the renderer, e.g. into notebook environments.
+use std::{cell::Cell, rc::Rc};
+
+use wasm_bindgen::{closure::Closure, prelude::*};
+
+use crate::{
+    api::{self, TransactionSpec},
+    wasm_bridge::{EventQueue, FrameOptions, PowerProfile},
+    Renderer,
+};
+
+const CANVAS_STYLE: &str = "position: absolute; left: 0; top: 0; width: 100%; height: 100%;";
+
+type PointerListener = Closure<dyn FnMut(web_sys::PointerEvent)>;
+
+/// A plot embedded into a container element.
+///
+/// Owns the canvases, event listeners and observers created by [`embed`].
+#[wasm_bindgen]
+pub struct EmbeddedPlot {
+    queue: EventQueue,
+    exited: Rc<Cell<bool>>,
+    canvas_gpu: web_sys::HtmlCanvasElement,
+    canvas_2d: web_sys::HtmlCanvasElement,
+    pointer_listeners: Vec<(&'static str, PointerListener)>,
+    context_menu_listener: Closure<dyn FnMut(web_sys::Event)>,
+    resize_observer: web_sys::ResizeObserver,
+    _resize_listener: Closure<dyn FnMut()>,
+}
+
+#[wasm_bindgen]
+impl EmbeddedPlot {
+    /// Returns a new handle to the event queue of the renderer.
+    #[wasm_bindgen(getter)]
+    pub fn queue(&self) -> EventQueue {
+        EventQueue {
+            sender: self.queue.sender.clone(),
+        }
+    }
+
+    /// Applies a declarative transaction to the plot.
+    pub fn update(&self, spec: TransactionSpec) {
+        if self.exited.get() {
+            return;
+        }
+
+        self.queue
+            .commit_transaction(api::transaction_from_spec(spec));
+    }
+
+    /// Stops the renderer and removes the plot from its container.
+    pub fn destroy(&self) {
+        if self.exited.replace(true) {
+            return;
+        }
+
+        self.resize_observer.disconnect();
+        for (event, listener) in &self.pointer_listeners {
+            self.canvas_2d
+                .remove_event_listener_with_callback(event, listener.as_ref().unchecked_ref())
+                .unwrap();
+        }
+        self.canvas_2d
+            .remove_event_listener_with_callback(
+                "contextmenu",
+                self.context_menu_listener.as_ref().unchecked_ref(),
+            )
+            .unwrap();
+
+        self.canvas_gpu.remove();
+        self.canvas_2d.remove();
+        self.queue.exit();
+    }
+}
+
+/// Creates a plot inside of the `container` element.
+///
+/// Takes care of creating the canvases, wiring up the event queue, observing
+/// the size of the container, and scheduling the draws. The `data` may either
+/// be a [`TransactionSpec`] object, its JSON representation, or a `data:` URI
+/// containing the JSON representation, optionally encoded in base64.
+#[wasm_bindgen]
+pub async fn embed(
+    container: web_sys::HtmlElement,
+    data: JsValue,
+    callback: Option<api::PlotDiffCallback>,
+) -> EmbeddedPlot {
+    let window = web_sys::window().unwrap();
+    let document = window.document().unwrap();
+
+    let create_canvas = || {
+        let canvas = document
+            .create_element("canvas")
+            .unwrap()
+            .dyn_into::<web_sys::HtmlCanvasElement>()
+            .unwrap();
+        canvas.set_attribute("style", CANVAS_STYLE).unwrap();
+        container.append_child(&canvas).unwrap();
+        canvas
+    };
+
+    container
+        .style()
+        .set_property("position", "relative")
+        .unwrap();
+    let canvas_gpu = create_canvas();
+    let canvas_2d = create_canvas();
+
+    let callback = callback.unwrap_or_else(|| js_sys::Function::new_no_args("").unchecked_into());
+    let mut renderer = Renderer::new(
+        callback,
+        canvas_gpu.clone(),
+        canvas_2d.clone(),
+        PowerProfile::Auto,
+        None,
+    )
+    .await;
+    let queue = renderer.construct_event_queue();
+    wasm_bindgen_futures::spawn_local(async move { renderer.enter_event_loop().await });
+
+    let exited = Rc::new(Cell::new(false));
+
+    // Forward the pointer events.
+    let mut pointer_listeners = Vec::new();
+    for event in ["pointerdown", "pointerup", "pointerleave", "pointermove"] {
+        let queue = EventQueue {
+            sender: queue.sender.clone(),
+        };
+        let exited = exited.clone();
+        let listener = PointerListener::new(move |e: web_sys::PointerEvent| {
+            if exited.get() {
+                return;
+            }
+
+            match event {
+                "pointerdown" => queue.pointer_down(e),
+                "pointermove" => queue.pointer_move(e),
+                _ => queue.pointer_up(e),
+            }
+        });
+        canvas_2d
+            .add_event_listener_with_callback(event, listener.as_ref().unchecked_ref())
+            .unwrap();
+        pointer_listeners.push((event, listener));
+    }
+
+    let context_menu_listener =
+        Closure::<dyn FnMut(web_sys::Event)>::new(|e: web_sys::Event| e.prevent_default());
+    canvas_2d
+        .add_event_listener_with_callback(
+            "contextmenu",
+            context_menu_listener.as_ref().unchecked_ref(),
+        )
+        .unwrap();
+
+    // Resize the plot together with its container.
+    let resize = {
+        let queue = EventQueue {
+            sender: queue.sender.clone(),
+        };
+        let canvas = canvas_2d.clone();
+        let exited = exited.clone();
+        move || {
+            if exited.get() {
+                return;
+            }
+
+            let window = web_sys::window().unwrap();
+            queue.resize(
+                canvas.client_width() as u32,
+                canvas.client_height() as u32,
+                window.device_pixel_ratio() as f32,
+            );
+        }
+    };
+    resize();
+    let resize_listener = Closure::<dyn FnMut()>::new(resize);
+    let resize_observer =
+        web_sys::ResizeObserver::new(resize_listener.as_ref().unchecked_ref()).unwrap();
+    resize_observer.observe(&canvas_2d);
+
+    // Draw once per animation frame.
+    queue.set_frame_options(FrameOptions {
+        schedule_draws: true,
+        max_fps: None,
+    });
+
+    queue.commit_transaction(api::transaction_from_spec(parse_data(data)));
+
+    EmbeddedPlot {
+        queue,
+        exited,
+        canvas_gpu,
+        canvas_2d,
+        pointer_listeners,
+        context_menu_listener,
+        resize_observer,
+        _resize_listener: resize_listener,
+    }
+}
+
+fn parse_data(data: JsValue) -> TransactionSpec {
+    let data = match data.as_string() {
+        Some(text) => {
+            let json = match text.strip_prefix("data:") {
+                Some(uri) => {
+                    let (header, payload) = uri.split_once(',').expect("invalid data URI");
+                    if header.ends_with(";base64") {
+                        web_sys::window().unwrap().atob(payload).unwrap()
+                    } else {
+                        js_sys::decode_uri_component(payload)
+                            .expect("invalid data URI")
+                            .into()
+                    }
+                }
+                None => text,
+            };
+            js_sys::JSON::parse(&json).expect("the plot data should be valid JSON")
+        }
+        None => data,
+    };
+
+    serde_wasm_bindgen::from_value(data).expect("the plot data should be a valid transaction")
+}
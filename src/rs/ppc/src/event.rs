@@ -10,12 +10,39 @@ impl Event {
     // External events
     pub const RESIZE: Self = Self(1 << 0);
     pub const TRANSACTION_COMMIT: Self = Self(1 << 1);
+    pub const REDRAW: Self = Self(1 << 2);
 
     // Internal events
     pub const AXIS_STATE_CHANGE: Self = Self(1 << 20);
     pub const AXIS_POSITION_CHANGE: Self = Self(1 << 21);
     pub const AXIS_ORDER_CHANGE: Self = Self(1 << 22);
     pub const SELECTIONS_CHANGE: Self = Self(1 << 23);
+    pub const AXIS_VISIBILITY_CHANGE: Self = Self(1 << 24);
+    pub const HOVER_CHANGE: Self = Self(1 << 25);
+    pub const DIAGNOSTIC: Self = Self(1 << 26);
+    pub const AXIS_RANGE_EDIT_REQUEST: Self = Self(1 << 27);
+    pub const SNAPSHOTS_CHANGE: Self = Self(1 << 28);
+    pub const LABEL_VISIBILITY_CHANGE: Self = Self(1 << 29);
+    pub const VIEW_CHANGE: Self = Self(1 << 30);
+    pub const HEADER_POINTER: Self = Self(1 << 31);
+
+    const NAMES: [(Self, &'static str); 15] = [
+        (Self::RESIZE, "RESIZE"),
+        (Self::TRANSACTION_COMMIT, "TRANSACTION_COMMIT"),
+        (Self::REDRAW, "REDRAW"),
+        (Self::AXIS_STATE_CHANGE, "AXIS_STATE_CHANGE"),
+        (Self::AXIS_POSITION_CHANGE, "AXIS_POSITION_CHANGE"),
+        (Self::AXIS_ORDER_CHANGE, "AXIS_ORDER_CHANGE"),
+        (Self::SELECTIONS_CHANGE, "SELECTIONS_CHANGE"),
+        (Self::AXIS_VISIBILITY_CHANGE, "AXIS_VISIBILITY_CHANGE"),
+        (Self::HOVER_CHANGE, "HOVER_CHANGE"),
+        (Self::DIAGNOSTIC, "DIAGNOSTIC"),
+        (Self::AXIS_RANGE_EDIT_REQUEST, "AXIS_RANGE_EDIT_REQUEST"),
+        (Self::SNAPSHOTS_CHANGE, "SNAPSHOTS_CHANGE"),
+        (Self::LABEL_VISIBILITY_CHANGE, "LABEL_VISIBILITY_CHANGE"),
+        (Self::VIEW_CHANGE, "VIEW_CHANGE"),
+        (Self::HEADER_POINTER, "HEADER_POINTER"),
+    ];
 
     pub fn is_empty(&self) -> bool {
         *self == Self::NONE
@@ -51,6 +78,15 @@ pub fn signaled_any(&self, events: &[Self]) -> bool {
     pub fn signaled_all(&self, events: &[Self]) -> bool {
         events.iter().copied().all(|e| (*self & e).has_events())
     }
+
+    /// Returns the names of the signaled events.
+    pub fn names(&self) -> Vec<&'static str> {
+        Self::NAMES
+            .iter()
+            .filter(|&&(event, _)| self.signaled(event))
+            .map(|&(_, name)| name)
+            .collect()
+    }
 }
 
 impl BitAnd for Event {
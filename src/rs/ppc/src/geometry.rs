@@ -0,0 +1,15 @@
+//! Coordinate systems, interpolation and splines used by the renderer.
+//!
+//! Exposes the same world/screen transforms and spline evaluation that the
+//! renderer uses internally, so that host-side tools can reproduce the
+//! positions of the axes and the shapes of the probability curves. All plain
+//! data types implement [`serde::Serialize`] and [`serde::Deserialize`].
+
+pub use crate::coordinates::{
+    Aabb, AabbRelation, CartesianLength, CartesianOffset, CartesianPosition, ComponentAccessible,
+    CoordinateSystem, CoordinateSystemTransformer, Length, LengthType, LocalSpace, Offset,
+    OffsetType, Position, PositionType, ScreenSpace, ScreenViewTransformer, ViewSpace,
+    ViewWorldTransformer, WorldLocalTransformer, WorldSpace,
+};
+pub use crate::lerp::{InverseLerp, Lerp};
+pub use crate::spline::{SegmentRemovalOp, Spline, SplineSegment};
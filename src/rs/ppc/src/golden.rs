@@ -0,0 +1,619 @@
+//! Canonical scenes and image comparison for golden-image tests.
+//!
+//! Each [`GoldenScene`] is rendered on a newly created plot, captured
+//! without the text overlay, and compared against the image stored at
+//! `tests/golden/<name>.png`. The images are recorded on a canvas of
+//! [`GOLDEN_WIDTH`] x [`GOLDEN_HEIGHT`] pixels with a device pixel ratio
+//! of `1`, and are written uncompressed, such that they can be decoded
+//! without pulling in a compression library.
+use std::collections::BTreeMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::wasm_bridge::{
+    AccessibilityOptions, AxisDef, Brush, Event, EventQueue, StateTransaction,
+    StateTransactionBuilder,
+};
+
+/// Width of the canvas the golden images are recorded on.
+pub const GOLDEN_WIDTH: u32 = 256;
+/// Height of the canvas the golden images are recorded on.
+pub const GOLDEN_HEIGHT: u32 = 128;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+const MAX_STORED_BLOCK_LEN: usize = u16::MAX as usize;
+
+/// Canonical scene rendered by a golden-image test.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoldenScene {
+    /// Two axes without any labels.
+    TwoAxes,
+    /// Three axes with a single brush on the middle axis.
+    ThreeAxesBrushed,
+    /// Four axes with two labels brushing different axes.
+    FourAxesTwoLabels,
+}
+
+impl GoldenScene {
+    fn name(self) -> &'static str {
+        match self {
+            GoldenScene::TwoAxes => "two_axes",
+            GoldenScene::ThreeAxesBrushed => "three_axes_brushed",
+            GoldenScene::FourAxesTwoLabels => "four_axes_two_labels",
+        }
+    }
+}
+
+/// Returns the path of the golden image of a scene, relative to the crate root.
+#[wasm_bindgen(js_name = goldenImagePath)]
+pub fn golden_image_path(scene: GoldenScene) -> String {
+    format!("tests/golden/{}.png", scene.name())
+}
+
+/// Builds the transaction setting up a scene on an empty plot.
+///
+/// Animations are disabled, such that the first drawn frame is final.
+#[wasm_bindgen(js_name = goldenScene)]
+pub fn golden_scene(scene: GoldenScene) -> StateTransaction {
+    const POINTS: [[f32; 5]; 4] = [
+        [0.1, 0.3, 0.5, 0.7, 0.9],
+        [0.9, 0.6, 0.5, 0.4, 0.1],
+        [0.2, 0.8, 0.4, 0.6, 0.3],
+        [0.5, 0.1, 0.9, 0.3, 0.7],
+    ];
+
+    let (num_axes, brushes): (usize, &[(&str, usize, f32, f32)]) = match scene {
+        GoldenScene::TwoAxes => (2, &[]),
+        GoldenScene::ThreeAxesBrushed => (3, &[("a", 1, 0.35, 0.65)]),
+        GoldenScene::FourAxesTwoLabels => (4, &[("a", 0, 0.0, 0.4), ("b", 3, 0.6, 1.0)]),
+    };
+
+    let mut builder = StateTransactionBuilder::new();
+    builder.set_accessibility_options(AccessibilityOptions {
+        reduced_motion: true,
+        ..Default::default()
+    });
+    for (i, points) in POINTS.iter().take(num_axes).enumerate() {
+        builder.add_axis(AxisDef::new(
+            &axis_key(i),
+            &format!("Axis {i}"),
+            Box::new(*points),
+            Some(Box::new([0.0, 1.0])),
+            None,
+            None,
+        ));
+    }
+
+    let mut brushes_change = BTreeMap::<String, BTreeMap<String, Vec<Brush>>>::new();
+    for &(label, axis, start, end) in brushes {
+        if !brushes_change.contains_key(label) {
+            builder.add_label(label.into(), None, false, 0.0, 0.0, None);
+        }
+        let brush = Brush {
+            control_points: vec![(start, 1.0), (end, 1.0)],
+            main_segment_idx: 0,
+        };
+        brushes_change
+            .entry(label.into())
+            .or_default()
+            .entry(axis_key(axis))
+            .or_default()
+            .push(brush);
+    }
+    if let Some(label) = brushes.last().map(|&(label, ..)| label) {
+        builder.switch_active_label(Some(label.into()));
+    }
+
+    let mut transaction = builder.build();
+    if !brushes_change.is_empty() {
+        transaction.brushes_change = Some(brushes_change);
+    }
+    transaction
+}
+
+/// Maximum difference allowed for each channel of a pixel.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageTolerance {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+    /// Fraction of pixels allowed to exceed the channel tolerances.
+    #[wasm_bindgen(js_name = maxMismatch)]
+    pub max_mismatch: f64,
+}
+
+#[wasm_bindgen]
+impl ImageTolerance {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Applies the same tolerance to every channel.
+    pub fn uniform(tolerance: u8, max_mismatch: f64) -> Self {
+        Self {
+            red: tolerance,
+            green: tolerance,
+            blue: tolerance,
+            alpha: tolerance,
+            max_mismatch,
+        }
+    }
+
+    fn channels(&self) -> [u8; 4] {
+        [self.red, self.green, self.blue, self.alpha]
+    }
+}
+
+impl Default for ImageTolerance {
+    fn default() -> Self {
+        // Allows for small differences in the rasterization and blending
+        // between drivers, while still catching changes of the colors.
+        Self::uniform(2, 0.001)
+    }
+}
+
+/// Result of comparing two `RGBA` images.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageDiff {
+    /// Fraction of pixels where any channel exceeds its tolerance.
+    pub mismatch: f64,
+    /// Largest difference of each channel over all pixels.
+    pub max_difference: [u8; 4],
+}
+
+/// Compares two `RGBA` images of the same size channel by channel.
+///
+/// Images of different sizes are considered entirely different.
+pub fn diff_images(expected: &[u8], actual: &[u8], tolerance: [u8; 4]) -> ImageDiff {
+    if expected.len() != actual.len() || !expected.len().is_multiple_of(4) {
+        return ImageDiff {
+            mismatch: 1.0,
+            max_difference: [u8::MAX; 4],
+        };
+    }
+    if expected.is_empty() {
+        return ImageDiff {
+            mismatch: 0.0,
+            max_difference: [0; 4],
+        };
+    }
+
+    let mut mismatches = 0usize;
+    let mut max_difference = [0u8; 4];
+    for (e, a) in expected.chunks_exact(4).zip(actual.chunks_exact(4)) {
+        let mut mismatched = false;
+        for c in 0..4 {
+            let difference = e[c].abs_diff(a[c]);
+            max_difference[c] = max_difference[c].max(difference);
+            mismatched |= difference > tolerance[c];
+        }
+        mismatches += mismatched as usize;
+    }
+
+    ImageDiff {
+        mismatch: mismatches as f64 / (expected.len() / 4) as f64,
+        max_difference,
+    }
+}
+
+/// Compares two `RGBA` images, e.g. a captured frame against a golden image.
+///
+/// Returns the fraction of pixels where any channel differs by more than
+/// its tolerance. Images of different sizes are considered entirely different.
+#[wasm_bindgen(js_name = compareImages)]
+pub fn compare_images(expected: &[u8], actual: &[u8], tolerance: ImageTolerance) -> f64 {
+    diff_images(expected, actual, tolerance.channels()).mismatch
+}
+
+/// Outcome of a golden-image test.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone)]
+pub struct GoldenReport {
+    pub scene: GoldenScene,
+    pub width: u32,
+    pub height: u32,
+    /// Fraction of pixels exceeding the tolerance.
+    pub mismatch: f64,
+    /// Largest difference of each channel, in the `RGBA` order.
+    #[wasm_bindgen(js_name = maxDifference)]
+    pub max_difference: Box<[u8]>,
+    pub passed: bool,
+    /// Reason why the images could not be compared, if any.
+    pub error: Option<String>,
+    /// Captured frame encoded as a PNG, to record or update the golden image.
+    pub actual: Box<[u8]>,
+}
+
+/// Renders a scene and compares it against its golden image.
+///
+/// The plot must be newly created, with a canvas of [`GOLDEN_WIDTH`] x
+/// [`GOLDEN_HEIGHT`] pixels. A missing golden image fails the test, while
+/// the report still contains the captured frame to record it.
+/// Resolves to a [`GoldenReport`].
+#[wasm_bindgen(js_name = runGoldenTest)]
+pub fn run_golden_test(
+    queue: &EventQueue,
+    scene: GoldenScene,
+    golden: Option<Box<[u8]>>,
+    tolerance: Option<ImageTolerance>,
+) -> js_sys::Promise {
+    let queue = EventQueue {
+        sender: queue.sender.clone(),
+    };
+    let tolerance = tolerance.unwrap_or_default();
+
+    wasm_bindgen_futures::future_to_promise(async move {
+        queue
+            .sender
+            .send(Event::CommitTransaction {
+                transaction: golden_scene(scene),
+            })
+            .await
+            .expect("the channel should be open when trying to send a message");
+        queue.draw().await;
+
+        let capture = queue.capture().await;
+        let field = |name: &str| js_sys::Reflect::get(&capture, &name.into()).unwrap();
+        let width = field("width").as_f64().unwrap_or(0.0) as u32;
+        let height = field("height").as_f64().unwrap_or(0.0) as u32;
+        let pixels = field("data")
+            .unchecked_into::<js_sys::Uint8ClampedArray>()
+            .to_vec();
+
+        let expected = match golden.as_deref().map(decode_png) {
+            _ if (width, height) != (GOLDEN_WIDTH, GOLDEN_HEIGHT) => Err(format!(
+                "expected a canvas of {GOLDEN_WIDTH}x{GOLDEN_HEIGHT} pixels, \
+                captured {width}x{height} pixels"
+            )),
+            None => Err("missing golden image".to_string()),
+            Some(Ok(image)) if (image.width, image.height) != (width, height) => Err(format!(
+                "expected an image of {}x{} pixels, captured {width}x{height} pixels",
+                image.width, image.height
+            )),
+            Some(image) => image,
+        };
+        let (diff, error) = match expected {
+            Ok(image) => (
+                diff_images(&image.pixels, &pixels, tolerance.channels()),
+                None,
+            ),
+            Err(e) => {
+                let diff = ImageDiff {
+                    mismatch: 1.0,
+                    max_difference: [u8::MAX; 4],
+                };
+                (diff, Some(e))
+            }
+        };
+
+        let report = GoldenReport {
+            scene,
+            width,
+            height,
+            mismatch: diff.mismatch,
+            max_difference: diff.max_difference.into(),
+            passed: error.is_none() && diff.mismatch <= tolerance.max_mismatch,
+            error,
+            actual: encode_png(width, height, &pixels).into(),
+        };
+        Ok(report.into())
+    })
+}
+
+fn axis_key(index: usize) -> String {
+    format!("axis_{index}")
+}
+
+/// Decoded `RGBA` image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// Encodes an `RGBA` image as a PNG with uncompressed deflate blocks.
+pub fn encode_png(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+    assert_eq!(pixels.len(), width as usize * height as usize * 4);
+
+    // Each scanline is prefixed with its filter type, which is always `None`.
+    let row_size = width as usize * 4;
+    let mut scanlines = Vec::with_capacity((row_size + 1) * height as usize);
+    for row in pixels.chunks_exact(row_size.max(1)) {
+        scanlines.push(0);
+        scanlines.extend_from_slice(row);
+    }
+
+    let num_blocks = scanlines.len().div_ceil(MAX_STORED_BLOCK_LEN).max(1);
+    let mut zlib = Vec::with_capacity(scanlines.len() + num_blocks * 5 + 6);
+    zlib.extend_from_slice(&[0x78, 0x01]);
+    let mut blocks = scanlines.chunks(MAX_STORED_BLOCK_LEN).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+        zlib.push(blocks.peek().is_none() as u8);
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&scanlines).to_be_bytes());
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 8 bits per channel, RGBA, deflate, adaptive filtering, no interlacing.
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut png = PNG_SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib);
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// Decodes a PNG written by [`encode_png`].
+///
+/// Only non-interlaced `RGBA` images with 8 bits per channel and
+/// uncompressed deflate blocks are supported. All filter types are.
+pub fn decode_png(png: &[u8]) -> Result<Image, String> {
+    let mut data = png
+        .strip_prefix(&PNG_SIGNATURE[..])
+        .ok_or("missing PNG signature")?;
+
+    let mut header = None;
+    let mut zlib = Vec::new();
+    loop {
+        if data.len() < 12 {
+            return Err("truncated PNG chunk".into());
+        }
+        let len = u32::from_be_bytes(data[..4].try_into().unwrap()) as usize;
+        if data.len() < len + 12 {
+            return Err("truncated PNG chunk".into());
+        }
+        let kind = &data[4..8];
+        let contents = &data[8..8 + len];
+        let crc = u32::from_be_bytes(data[8 + len..12 + len].try_into().unwrap());
+        if crc != crc32(&data[4..8 + len]) {
+            return Err("corrupted PNG chunk".into());
+        }
+        data = &data[12 + len..];
+
+        match kind {
+            b"IHDR" => {
+                if contents.len() != 13 {
+                    return Err("invalid PNG header".into());
+                }
+                if contents[8..] != [8, 6, 0, 0, 0] {
+                    return Err("only 8-bit non-interlaced RGBA PNGs are supported".into());
+                }
+                let width = u32::from_be_bytes(contents[..4].try_into().unwrap());
+                let height = u32::from_be_bytes(contents[4..8].try_into().unwrap());
+                header = Some((width, height));
+            }
+            b"IDAT" => zlib.extend_from_slice(contents),
+            b"IEND" => break,
+            _ => {}
+        }
+    }
+
+    let (width, height) = header.ok_or("missing PNG header")?;
+    let scanlines = inflate_stored(&zlib)?;
+
+    let row_size = width as usize * 4;
+    if scanlines.len() != (row_size + 1) * height as usize {
+        return Err("unexpected size of the PNG image data".into());
+    }
+    let mut pixels = vec![0u8; row_size * height as usize];
+    for (y, line) in scanlines.chunks_exact(row_size + 1).enumerate() {
+        let (done, remaining) = pixels.split_at_mut(y * row_size);
+        let previous = y.checked_sub(1).map(|p| &done[p * row_size..]);
+        unfilter(line[0], &line[1..], previous, &mut remaining[..row_size])?;
+    }
+
+    Ok(Image {
+        width,
+        height,
+        pixels,
+    })
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], contents: &[u8]) {
+    let start = png.len() + 4;
+    png.extend_from_slice(&(contents.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(contents);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn inflate_stored(zlib: &[u8]) -> Result<Vec<u8>, String> {
+    if zlib.len() < 6
+        || zlib[0] & 0x0F != 8
+        || !u16::from_be_bytes([zlib[0], zlib[1]]).is_multiple_of(31)
+    {
+        return Err("invalid zlib stream".into());
+    }
+
+    let mut data = &zlib[2..];
+    let mut output = Vec::new();
+    loop {
+        if data.len() < 5 {
+            return Err("truncated deflate block".into());
+        }
+        let is_final = data[0] & 1 != 0;
+        if data[0] >> 1 & 3 != 0 {
+            return Err("compressed PNGs are not supported, re-record the image".into());
+        }
+        let len = u16::from_le_bytes([data[1], data[2]]);
+        let nlen = u16::from_le_bytes([data[3], data[4]]);
+        if len != !nlen || data.len() < 5 + len as usize {
+            return Err("invalid deflate block".into());
+        }
+        output.extend_from_slice(&data[5..5 + len as usize]);
+        data = &data[5 + len as usize..];
+        if is_final {
+            break;
+        }
+    }
+
+    let checksum = data.get(..4).ok_or("missing zlib checksum")?;
+    if u32::from_be_bytes(checksum.try_into().unwrap()) != adler32(&output) {
+        return Err("corrupted zlib stream".into());
+    }
+    Ok(output)
+}
+
+fn unfilter(
+    filter: u8,
+    line: &[u8],
+    previous: Option<&[u8]>,
+    output: &mut [u8],
+) -> Result<(), String> {
+    const BPP: usize = 4;
+
+    for i in 0..line.len() {
+        let a = if i >= BPP { output[i - BPP] } else { 0 };
+        let b = previous.map_or(0, |p| p[i]);
+        let c = if i >= BPP {
+            previous.map_or(0, |p| p[i - BPP])
+        } else {
+            0
+        };
+
+        let predictor = match filter {
+            0 => 0,
+            1 => a,
+            2 => b,
+            3 => ((a as u16 + b as u16) / 2) as u8,
+            4 => {
+                let p = a as i16 + b as i16 - c as i16;
+                let (pa, pb, pc) = (
+                    (p - a as i16).abs(),
+                    (p - b as i16).abs(),
+                    (p - c as i16).abs(),
+                );
+                if pa <= pb && pa <= pc {
+                    a
+                } else if pb <= pc {
+                    b
+                } else {
+                    c
+                }
+            }
+            _ => return Err(format!("unknown PNG filter type {filter}")),
+        };
+        output[i] = line[i].wrapping_add(predictor);
+    }
+
+    Ok(())
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient(width: u32, height: u32) -> Vec<u8> {
+        (0..width * height)
+            .flat_map(|i| [(i % 256) as u8, (i / 256) as u8, 128, 255])
+            .collect()
+    }
+
+    #[test]
+    fn checksums_match_the_reference_values() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn png_round_trips_through_multiple_blocks() {
+        let pixels = gradient(GOLDEN_WIDTH, GOLDEN_HEIGHT);
+        let png = encode_png(GOLDEN_WIDTH, GOLDEN_HEIGHT, &pixels);
+        let image = decode_png(&png).unwrap();
+        assert_eq!((image.width, image.height), (GOLDEN_WIDTH, GOLDEN_HEIGHT));
+        assert_eq!(image.pixels, pixels);
+    }
+
+    #[test]
+    fn png_filters_are_reverted() {
+        let previous = [10, 20, 30, 40, 50, 60, 70, 80];
+        let line = [1, 2, 3, 4, 5, 6, 7, 8];
+        let expected: [[u8; 8]; 5] = [
+            line,
+            [1, 2, 3, 4, 6, 8, 10, 12],
+            [11, 22, 33, 44, 55, 66, 77, 88],
+            [6, 12, 18, 24, 33, 42, 51, 60],
+            [11, 22, 33, 44, 55, 66, 77, 88],
+        ];
+        for (filter, expected) in expected.iter().enumerate() {
+            let mut output = [0; 8];
+            unfilter(filter as u8, &line, Some(&previous), &mut output).unwrap();
+            assert_eq!(&output, expected, "filter {filter}");
+        }
+        assert!(unfilter(5, &line, None, &mut [0; 8]).is_err());
+    }
+
+    #[test]
+    fn corrupted_pngs_are_rejected() {
+        let mut png = encode_png(2, 2, &[7; 16]);
+        let last = png.len() - 20;
+        png[last] ^= 1;
+        assert!(decode_png(&png).is_err());
+        assert!(decode_png(&png[8..]).is_err());
+    }
+
+    #[test]
+    fn channels_are_compared_with_their_own_tolerance() {
+        let expected = [100, 100, 100, 255, 100, 100, 100, 255];
+        let actual = [103, 100, 100, 255, 100, 100, 105, 250];
+
+        let diff = diff_images(&expected, &actual, [3, 0, 5, 5]);
+        assert_eq!(diff.mismatch, 0.0);
+        assert_eq!(diff.max_difference, [3, 0, 5, 5]);
+
+        let diff = diff_images(&expected, &actual, [2, 0, 5, 5]);
+        assert_eq!(diff.mismatch, 0.5);
+        let diff = diff_images(&expected, &actual, [3, 0, 4, 4]);
+        assert_eq!(diff.mismatch, 0.5);
+    }
+
+    #[test]
+    fn images_of_different_sizes_mismatch() {
+        let diff = diff_images(&[0; 8], &[0; 12], [u8::MAX; 4]);
+        assert_eq!(diff.mismatch, 1.0);
+        assert_eq!(diff_images(&[], &[], [0; 4]).mismatch, 0.0);
+    }
+}
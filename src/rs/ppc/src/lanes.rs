@@ -0,0 +1,87 @@
+//! Priority lanes of the event loop.
+use std::collections::VecDeque;
+
+use async_channel::Receiver;
+
+use crate::wasm_bridge::Event;
+
+/// Maximum number of consecutive interactive events processed while bulk
+/// events are pending.
+const MAX_INTERACTIVE_STREAK: usize = 8;
+
+/// Lane of an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lane {
+    /// Pointer events, which require immediate feedback.
+    Interactive,
+    /// All other events, e.g. transactions and draws.
+    Bulk,
+}
+
+impl Lane {
+    fn of(event: &Event) -> Self {
+        match event {
+            Event::PointerDown { .. } | Event::PointerUp { .. } | Event::PointerMove { .. } => {
+                Lane::Interactive
+            }
+            _ => Lane::Bulk,
+        }
+    }
+}
+
+/// Splits the events received by the renderer into priority lanes.
+///
+/// Interactive events are preferred over bulk events, such that a large
+/// data update does not delay the pointer feedback. To avoid starving the
+/// bulk lane, a bulk event is let through after a bounded number of
+/// consecutive interactive events. Events inside a lane retain their order.
+pub struct EventLanes {
+    receiver: Receiver<Event>,
+    interactive: VecDeque<Event>,
+    bulk: VecDeque<Event>,
+    interactive_streak: usize,
+}
+
+impl EventLanes {
+    pub fn new(receiver: Receiver<Event>) -> Self {
+        Self {
+            receiver,
+            interactive: VecDeque::new(),
+            bulk: VecDeque::new(),
+            interactive_streak: 0,
+        }
+    }
+
+    /// Waits for the next event to process.
+    pub async fn next(&mut self) -> Event {
+        if self.interactive.is_empty() && self.bulk.is_empty() {
+            let event = self
+                .receiver
+                .recv()
+                .await
+                .expect("the channel should be open");
+            self.push(event);
+        }
+
+        // Sort all events which are already available into their lanes.
+        while let Ok(event) = self.receiver.try_recv() {
+            self.push(event);
+        }
+
+        let starved = self.interactive_streak >= MAX_INTERACTIVE_STREAK;
+        if !self.bulk.is_empty() && (self.interactive.is_empty() || starved) {
+            self.interactive_streak = 0;
+            self.bulk.pop_front().unwrap()
+        } else {
+            self.interactive_streak += 1;
+            self.interactive.pop_front().unwrap()
+        }
+    }
+
+    fn push(&mut self, event: Event) {
+        match Lane::of(&event) {
+            Lane::Interactive => self.interactive.push_back(event),
+            Lane::Bulk => self.bulk.push_back(event),
+        }
+    }
+}
@@ -1,12 +1,12 @@
 use std::{
     borrow::Cow,
     cell::RefCell,
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, VecDeque},
     mem::MaybeUninit,
     rc::Rc,
 };
 
-use async_channel::{Receiver, Sender};
+use async_channel::Sender;
 use color_scale::ColorScaleDescriptor;
 use colors::{Color, ColorOpaque, ColorQuery, ColorTransparent, SRgb, SRgbLinear, Xyz};
 use coordinates::ScreenSpace;
@@ -19,17 +19,36 @@
 mod wgsl;
 
 mod action;
+mod api;
 mod axis;
+pub mod backend;
+mod bench;
 mod buffers;
+mod cluster;
 mod color_bar;
 mod color_scale;
 mod colors;
 mod coordinates;
+mod diagnostics;
+mod embed;
 mod event;
+pub mod geometry;
+mod golden;
+mod lanes;
 mod lerp;
+mod logging;
+mod optimizer;
+mod panic_report;
 mod pipelines;
+mod preprocessor;
+mod scheduler;
 mod selection;
 mod spline;
+mod story;
+mod sync;
+#[cfg(all(test, feature = "shader-validation"))]
+mod validation;
+mod visibility;
 mod wasm_bridge;
 
 const DEFAULT_BACKGROUND_COLOR: fn() -> ColorTransparent<SRgb> =
@@ -45,6 +64,31 @@
     query.resolve_with_alpha()
 };
 
+const DEFAULT_REFERENCE_COLOR: fn() -> ColorTransparent<Xyz> = || {
+    let query = ColorQuery::Css("rgb(128 128 128 0.15)".into());
+    query.resolve_with_alpha()
+};
+
+const DEFAULT_AXIS_REGION_COLOR: fn() -> ColorTransparent<Xyz> = || {
+    let query = ColorQuery::Css("rgb(100 150 220 0.2)".into());
+    query.resolve_with_alpha()
+};
+
+const DEFAULT_FLOW_COLOR: fn() -> ColorTransparent<Xyz> = || {
+    let query = ColorQuery::Css("rgb(128 128 128 0.35)".into());
+    query.resolve_with_alpha()
+};
+
+const DEFAULT_DENSITY_COLOR: fn() -> ColorTransparent<Xyz> = || {
+    let query = ColorQuery::Css("rgb(90 90 90 0.8)".into());
+    query.resolve_with_alpha()
+};
+
+const DEFAULT_HISTOGRAM_COLOR: fn() -> ColorTransparent<Xyz> = || {
+    let query = ColorQuery::Css("rgb(128 128 128 0.4)".into());
+    query.resolve_with_alpha()
+};
+
 const DEFAULT_DATA_COLOR_MODE: fn() -> wasm_bridge::DataColorMode =
     || wasm_bridge::DataColorMode::Constant(0.5);
 
@@ -53,10 +97,339 @@
 
 const DEFAULT_DRAW_ORDER: wasm_bridge::DrawOrder = wasm_bridge::DrawOrder::SelectedIncreasing;
 
+const HIGH_CONTRAST_LINE_SCALE: f32 = 2.0;
+
+/// Key of the synthetic axis containing the cluster of each data point.
+const CLUSTER_AXIS_KEY: &str = "__clusters";
+
+const MAX_CLUSTERS: usize = 64;
+
+/// Key of the synthetic axis containing the outlier score of each data point.
+const OUTLIER_AXIS_KEY: &str = "__outlier_scores";
+
+/// Upper bound of the outlier scores.
+const MAX_OUTLIER_SCORE: f32 = 10.0;
+
+/// Fixed range of the axis of the outlier scores. Like the range computed
+/// from the data, it is padded by `0.5`, such that constant scores are valid.
+const OUTLIER_AXIS_RANGE: (f32, f32) = (-0.5, MAX_OUTLIER_SCORE + 0.5);
+
+/// Upper bound of the jitter amplitude, in normalized units of the axis.
+const MAX_AXIS_JITTER: f32 = 0.5;
+
+/// Upper bound of the width of the curve area of the expanded axes, in local
+/// units, such that it does not overlap the previous axis.
+const MAX_CURVE_AREA_WIDTH: f32 = 0.5;
+
+/// Width of the largest bar of a histogram, in world units.
+const HISTOGRAM_WIDTH: f32 = 0.3;
+
+/// Fraction of the expansion of an axis animated per frame.
+const AXIS_EXPANSION_STEP: f32 = 0.2;
+
+/// Fraction of the remaining distance to the target view window animated per frame.
+const VIEW_ANIMATION_STEP: f32 = 0.2;
+
+/// Distance in world units, below which the view snaps to its target window.
+const VIEW_SNAP_DISTANCE: f32 = 1e-3;
+
+/// Maximum number of steps kept in the edit history of a label.
+const MAX_LABEL_HISTORY_STEPS: usize = 100;
+
+/// Upper bound of the height of the axis headers, in `rem`.
+const MAX_AXIS_HEADER_HEIGHT: f32 = 10.0;
+
+/// Number of pointer positions kept in the trail of the interaction trace.
+const POINTER_TRAIL_LENGTH: usize = 64;
+
+/// Number of handled events kept for the snapshot sent with a panic report.
+const PANIC_SNAPSHOT_EVENTS: usize = 32;
+
+/// Validates the definition of the added `axis`.
+///
+/// Shorter axes are padded with missing values, but an axis may not contain
+/// more than `max_points` points, i.e. more than the axes remaining in the plot.
+fn validate_axis_def(
+    axis: &str,
+    axis_def: &wasm_bridge::AxisDef,
+    max_points: Option<usize>,
+) -> Result<(), diagnostics::Diagnostic> {
+    use diagnostics::{Diagnostic, Warning};
+
+    let wasm_bridge::AxisDef {
+        points,
+        range,
+        visible_range,
+        ticks,
+        histogram,
+        ..
+    } = axis_def;
+
+    if points.iter().any(|p| p.is_infinite()) {
+        return Err(Diagnostic::new(Warning::InfiniteAxisPoint).with("axis", axis));
+    }
+
+    if let Some(max_points) = max_points.filter(|&n| points.len() > n) {
+        return Err(Diagnostic::new(Warning::MismatchedPointCount)
+            .with("axis", axis)
+            .with("expected", max_points.to_string())
+            .with("actual", points.len().to_string()));
+    }
+
+    let present_points = points.iter().copied().filter(|p| !p.is_nan());
+    let data_min = present_points.clone().reduce(f32::min);
+    let data_max = present_points.reduce(f32::max);
+    let data_range = data_min.zip(data_max);
+
+    let is_valid_range = |(min, max): (f32, f32)| min.is_finite() && max.is_finite() && min < max;
+    let range = if let Some(range) = *range {
+        if !is_valid_range(range) {
+            return Err(Diagnostic::new(Warning::InvalidAxisRange).with("axis", axis));
+        }
+        // Axes without data points, e.g. defined by a histogram, may span any range.
+        if data_range.is_some_and(|(min, max)| range.0 > min || range.1 < max) {
+            return Err(Diagnostic::new(Warning::AxisRangeExcludesData).with("axis", axis));
+        }
+        range
+    } else {
+        match data_range.unwrap_or((0.0, 1.0)) {
+            (min, max) if min == max => (min - 0.5, max + 0.5),
+            data_range => data_range,
+        }
+    };
+
+    if let Some(visible_range) = *visible_range {
+        if !is_valid_range(visible_range) {
+            return Err(Diagnostic::new(Warning::InvalidVisibleRange).with("axis", axis));
+        }
+        if visible_range.0 < range.0 || visible_range.1 > range.1 {
+            return Err(Diagnostic::new(Warning::VisibleRangeOutsideRange).with("axis", axis));
+        }
+    }
+
+    if let Some(ticks) = ticks {
+        let (min, max) = range;
+        if let Some((t, _)) = ticks.iter().find(|(t, _)| !(min..=max).contains(t)) {
+            return Err(Diagnostic::new(Warning::TickOutsideRange)
+                .with("axis", axis)
+                .with("tick", t.to_string()));
+        }
+    }
+
+    if let Some(histogram) = histogram {
+        let valid =
+            !histogram.is_empty() && histogram.iter().all(|&bin| bin.is_finite() && bin >= 0.0);
+        if !valid {
+            return Err(Diagnostic::new(Warning::InvalidAxisHistogram).with("axis", axis));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the number of data points of the plot after a transaction.
+///
+/// The existing axes dictate the number of points, while the longest added
+/// axis is used if no existing axis remains.
+fn ragged_num_data_points(
+    remaining: Option<usize>,
+    additions: impl Iterator<Item = usize>,
+) -> usize {
+    remaining.or_else(|| additions.max()).unwrap_or(0)
+}
+
+/// Appends missing values to the points, until they contain `num_data_points` entries.
+fn pad_missing_points(points: &mut Box<[f32]>, num_data_points: usize) {
+    if points.len() < num_data_points {
+        let mut padded = Vec::from(std::mem::take(points));
+        padded.resize(num_data_points, f32::NAN);
+        *points = padded.into();
+    }
+}
+
+/// Connects the values of each curve on neighboring axes.
+///
+/// Segments with a missing value at either end are skipped, such that curves
+/// with missing values are drawn up to the gap.
+fn curve_segments(
+    curves: impl IntoIterator<Item = (usize, Vec<f32>)>,
+    axis_indices: &[usize],
+) -> Vec<buffers::DataLine> {
+    let mut lines = Vec::new();
+    for (i, curve) in curves {
+        for (values, indices) in curve.windows(2).zip(axis_indices.windows(2)) {
+            if values.iter().any(|v| v.is_nan()) {
+                continue;
+            }
+
+            lines.push(buffers::DataLine {
+                curve_idx: i as u32,
+                start_axis: indices[0] as u32,
+                start_value: values[0],
+                end_axis: indices[1] as u32,
+                end_value: values[1],
+            });
+        }
+    }
+    lines
+}
+
+/// Draws a loading indicator, until the renderer is ready to draw the plot.
+fn render_loading_placeholder(
+    canvas: &web_sys::HtmlCanvasElement,
+    context: &web_sys::CanvasRenderingContext2d,
+    percent: f32,
+) {
+    let width = canvas.width() as f64;
+    let height = canvas.height() as f64;
+    context.clear_rect(0.0, 0.0, width, height);
+    context.save();
+    context.set_text_align("center");
+    context.set_text_baseline("middle");
+    context
+        .fill_text(&format!("Loading… {percent}%"), width / 2.0, height / 2.0)
+        .unwrap();
+    context.restore();
+}
+
+/// Starts capturing the validation errors of the following operations on the
+/// device. Only active in debug builds.
+fn push_validation_scope(device: &webgpu::Device) {
+    if cfg!(debug_assertions) {
+        device.push_error_scope(webgpu::ErrorFilter::Validation);
+    }
+}
+
+/// Stops capturing validation errors, and converts the first error captured
+/// during the `operation` into a diagnostic.
+async fn pop_validation_scope(
+    device: &webgpu::Device,
+    operation: &str,
+) -> Option<diagnostics::Diagnostic> {
+    if !cfg!(debug_assertions) {
+        return None;
+    }
+
+    let message = device.pop_error_scope().await?;
+    let diagnostic = diagnostics::Diagnostic::new(diagnostics::Warning::GpuValidationError)
+        .with("operation", operation)
+        .with("message", message);
+    Some(diagnostic)
+}
+
+/// Resamples a histogram over the normalized range `(start, end)` of its
+/// axis into `num_bins` bins. The count of each histogram bin is distributed
+/// over the resampled bins proportionally to their overlap.
+fn resample_histogram(histogram: &[f32], (start, end): (f32, f32), num_bins: usize) -> Vec<f32> {
+    let num_source_bins = histogram.len() as f32;
+    (0..num_bins)
+        .map(|i| {
+            let bin_start = start.lerp(end, i as f32 / num_bins as f32);
+            let bin_end = start.lerp(end, (i + 1) as f32 / num_bins as f32);
+            histogram
+                .iter()
+                .enumerate()
+                .map(|(j, &count)| {
+                    let source_start = j as f32 / num_source_bins;
+                    let source_end = (j + 1) as f32 / num_source_bins;
+                    let overlap = bin_end.min(source_end) - bin_start.max(source_start);
+                    count * overlap.max(0.0) * num_source_bins
+                })
+                .sum()
+        })
+        .collect()
+}
+
+/// Moves the view window by a fraction `step` towards the target window,
+/// snapping to the target once it is close enough.
+fn step_view_window(current: (f32, f32), target: (f32, f32), step: f32) -> (f32, f32) {
+    let lerp = |from: f32, to: f32| from + (to - from) * step.clamp(0.0, 1.0);
+    let next = (lerp(current.0, target.0), lerp(current.1, target.1));
+    if (next.0 - target.0).abs() < VIEW_SNAP_DISTANCE
+        && (next.1 - target.1).abs() < VIEW_SNAP_DISTANCE
+    {
+        target
+    } else {
+        next
+    }
+}
+
+/// Queues the appended rows at the end of the columns. The columns are
+/// padded with missing values, such that each call appends whole rows, and
+/// only the newest `max_points` rows are retained.
+///
+/// Returns the number of queued rows.
+fn queue_appended_rows(
+    columns: &mut BTreeMap<String, Vec<f32>>,
+    num_queued: usize,
+    appends: BTreeMap<String, Vec<f32>>,
+    max_points: Option<usize>,
+) -> usize {
+    let num_rows = appends
+        .values()
+        .map(|points| points.len())
+        .max()
+        .unwrap_or(0);
+    for (axis, points) in appends {
+        let column = columns.entry(axis).or_default();
+        column.resize(num_queued, f32::NAN);
+        column.extend(points);
+    }
+
+    let num_queued = num_queued + num_rows;
+    let excess = max_points.map_or(0, |max| num_queued.saturating_sub(max));
+    for column in columns.values_mut() {
+        column.resize(num_queued, f32::NAN);
+        column.drain(..excess);
+    }
+    num_queued - excess
+}
+
+/// Appends the queued rows to the points of an axis, evicting the
+/// `num_evicted` oldest points.
+fn append_rows(
+    points: &[f32],
+    mut rows: Vec<f32>,
+    num_rows: usize,
+    num_evicted: usize,
+) -> Box<[f32]> {
+    rows.resize(num_rows, f32::NAN);
+    let num_evicted = num_evicted.min(points.len() + num_rows);
+    let mut appended = points.to_vec();
+    appended.extend(rows);
+    appended.drain(..num_evicted);
+    appended.into_boxed_slice()
+}
+
+/// Shifts the data point indices of a snapshot by the number of evicted
+/// points, dropping the indices of the evicted points.
+fn shift_snapshot(snapshot: &[u64], num_evicted: usize) -> Box<[u64]> {
+    let num_evicted = num_evicted as u64;
+    snapshot
+        .iter()
+        .filter(|&&i| i >= num_evicted)
+        .map(|&i| i - num_evicted)
+        .collect()
+}
+
+/// Decodes an `IEEE 754` half precision float.
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1f) as i32;
+    let mantissa = (bits & 0x3ff) as f32;
+    match exponent {
+        0 => sign * mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0.0 => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
+
 /// Implementation of the renderer for the parallel coordinates.
 #[wasm_bindgen]
 pub struct Renderer {
     callback: js_sys::Function,
+    panic_reporter: panic_report::PanicReporter,
     canvas_gpu: web_sys::HtmlCanvasElement,
     canvas_2d: web_sys::HtmlCanvasElement,
     context_gpu: web_sys::GpuCanvasContext,
@@ -66,24 +439,57 @@ pub struct Renderer {
     buffers: buffers::Buffers,
     render_texture: buffers::RenderTexture,
     depth_texture: buffers::DepthTexture,
-    event_queue: Option<Receiver<wasm_bridge::Event>>,
+    event_queue: Option<lanes::EventLanes>,
     axes: Rc<RefCell<axis::Axes>>,
     color_bar: color_bar::ColorBar,
     events: Vec<event::Event>,
     handled_events: event::Event,
     active_action: Option<action::Action>,
+    hovered_element: Option<axis::Element>,
     active_label_idx: Option<usize>,
     labels: Vec<LabelInfo>,
+    label_groups: Vec<LabelGroupInfo>,
+    curve_comparison: Option<(String, String)>,
     label_color_generator: LabelColorGenerator,
+    reference_data: BTreeMap<String, Box<[f32]>>,
     data_color_mode: wasm_bridge::DataColorMode,
     background_color: ColorTransparent<SRgb>,
     brush_color: ColorOpaque<Xyz>,
     unselected_color: ColorTransparent<Xyz>,
     draw_order: wasm_bridge::DrawOrder,
     interaction_mode: wasm_bridge::InteractionMode,
+    show_all_selections: bool,
+    label_agreement_metrics: bool,
+    linear_blending: bool,
+    pixel_snapping: bool,
+    antialiasing: wasm_bridge::Antialiasing,
+    data_line_join: wasm_bridge::DataLineJoin,
+    canvas_alpha_mode: wasm_bridge::CanvasAlphaMode,
+    background_layer: Option<wasm_bridge::BackgroundLayer>,
+    annotations: BTreeMap<String, wasm_bridge::Annotation>,
+    axis_regions: BTreeMap<String, AxisRegionInfo>,
+    flow_ribbons: bool,
+    label_mean_mode: wasm_bridge::LabelMeanMode,
+    label_means: BTreeMap<String, BTreeMap<Rc<str>, buffers::LabelMean>>,
+    outlier_options: Option<wasm_bridge::OutlierOptions>,
+    density_options: Option<wasm_bridge::DensityOptions>,
+    histogram_options: Option<wasm_bridge::HistogramOptions>,
+    live_options: Option<wasm_bridge::LiveOptions>,
+    live_frozen: bool,
+    live_update: Option<scheduler::DelayedDraw>,
     debug: wasm_bridge::DebugOptions,
+    hit_test: wasm_bridge::HitTestOptions,
+    control_points: wasm_bridge::ControlPointOptions,
+    pointer_scale: f32,
+    accessibility: wasm_bridge::AccessibilityOptions,
     pixel_ratio: f32,
     staging_data: StagingData,
+    recording: Option<(f64, wasm_bridge::EventLog)>,
+    sync: Option<sync::SyncState>,
+    visibility_observer: Option<visibility::VisibilityObserver>,
+    visible: bool,
+    event_sender: Option<Sender<wasm_bridge::Event>>,
+    draw_scheduler: Option<scheduler::DrawScheduler>,
 }
 
 #[derive(Debug)]
@@ -94,6 +500,45 @@ struct LabelInfo {
     easing: selection::EasingType,
     color: ColorOpaque<Xyz>,
     color_dimmed: ColorOpaque<Xyz>,
+    /// Number of selected data points, or `None` if the probabilities
+    /// changed since they were last counted.
+    num_selected: Option<usize>,
+    snapshot: Option<Box<[u64]>>,
+    group: Option<String>,
+    inherits_group_color: bool,
+    visible: bool,
+    history: Vec<api::LabelHistoryStep>,
+}
+
+impl LabelInfo {
+    /// Returns the color and the dimmed color of the label, which are
+    /// inherited from its group, unless the label was assigned a color.
+    fn colors(&self, groups: &[LabelGroupInfo]) -> (ColorOpaque<Xyz>, ColorOpaque<Xyz>) {
+        let group = self
+            .group
+            .as_ref()
+            .filter(|_| self.inherits_group_color)
+            .and_then(|group| groups.iter().find(|g| g.id == *group));
+        match group {
+            Some(group) => (group.color, group.color_dimmed),
+            None => (self.color, self.color_dimmed),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct LabelGroupInfo {
+    id: String,
+    color: ColorOpaque<Xyz>,
+    color_dimmed: ColorOpaque<Xyz>,
+}
+
+#[derive(Debug)]
+struct AxisRegionInfo {
+    axis: String,
+    range: (f32, f32),
+    label: Option<String>,
+    color: ColorTransparent<Xyz>,
 }
 
 #[derive(Debug, Default)]
@@ -102,21 +547,38 @@ struct LabelColorGenerator {
 }
 
 impl LabelColorGenerator {
+    const PALETTE: [&'static str; 8] = [
+        "rgb(228 26 28)",
+        "rgb(55 126 184)",
+        "rgb(77 175 74)",
+        "rgb(152 78 163)",
+        "rgb(255 127 0)",
+        "rgb(255 255 51)",
+        "rgb(166 86 40)",
+        "rgb(247 129 191)",
+    ];
+
+    /// Hue increment, in degrees, between the colors generated past the
+    /// palette, such that consecutive hues are spread evenly.
+    const GOLDEN_ANGLE: f32 = 137.50776;
+
     fn next(&mut self) -> (ColorOpaque<Xyz>, ColorOpaque<Xyz>) {
-        let css_string = match self.idx {
-            0 => "rgb(228 26 28)",
-            1 => "rgb(55 126 184)",
-            2 => "rgb(77 175 74)",
-            3 => "rgb(152 78 163)",
-            4 => "rgb(255 127 0)",
-            5 => "rgb(255 255 51)",
-            6 => "rgb(166 86 40)",
-            7 => "rgb(247 129 191)",
-            _ => unreachable!(),
-        };
-
-        self.idx = (self.idx + 1) % 8;
-        let color = ColorQuery::Css(css_string.into()).resolve();
+        let idx = self.idx;
+        self.idx += 1;
+
+        let color: ColorOpaque<Xyz> = if let Some(css_string) = Self::PALETTE.get(idx) {
+            ColorQuery::Css((*css_string).into()).resolve()
+        } else {
+            // Alternate the lightness to further separate neighboring hues.
+            let step = idx - Self::PALETTE.len();
+            let lch = colors::CieLch {
+                l: if step.is_multiple_of(2) { 65.0 } else { 50.0 },
+                c: 55.0,
+                h: (step as f32 * Self::GOLDEN_ANGLE) % 360.0,
+            };
+            ColorOpaque { values: lch }.transform()
+        };
+
         (color, Self::dim(color))
     }
 
@@ -130,23 +592,51 @@ fn dim(color: ColorOpaque<Xyz>) -> ColorOpaque<Xyz> {
 #[derive(Default)]
 #[allow(clippy::type_complexity)]
 struct StagingData {
-    resize: Vec<(u32, u32, f32)>,
-    transactions: Vec<wasm_bridge::StateTransaction>,
+    resize: Option<(u32, u32, f32)>,
+    transactions: VecDeque<wasm_bridge::StateTransaction>,
     updated_probabilities: BTreeSet<usize>,
+    deferred_resample: bool,
+    action_modifiers: Option<api::PointerModifiers>,
+    range_edit_request: Option<String>,
+    range_fit: Option<wasm_bridge::FitAxes>,
+    view_change: Option<wasm_bridge::ViewChange>,
+    label_freezes: BTreeSet<String>,
+    updated_snapshots: BTreeSet<String>,
+    group_probabilities_update: bool,
+    label_means_update: bool,
+    outlier_scores_update: bool,
+    density_update: bool,
+    histogram_update: bool,
+    appended_points: BTreeMap<String, Vec<f32>>,
+    num_appended_points: usize,
+    last_live_update: f64,
     last_labels: BTreeSet<String>,
+    last_description: String,
+    last_layout: Option<api::LayoutValue>,
+    header_pointer_events: Vec<api::HeaderPointerValue>,
+    curve_readout: Option<(Position<ScreenSpace>, String)>,
+    pointer_trail: VecDeque<Position<ScreenSpace>>,
+    pointer_trace: Vec<(&'static str, Position<ScreenSpace>)>,
+    last_events: VecDeque<&'static str>,
+    diagnostics: Vec<diagnostics::Diagnostic>,
 }
 
 #[wasm_bindgen]
 impl Renderer {
     /// Constructs a new renderer.
+    ///
+    /// While the pipelines are being compiled, a placeholder is drawn onto the
+    /// canvas, and the optional `ready` callback is invoked with the percentage
+    /// of compiled pipelines.
     #[wasm_bindgen(constructor)]
     pub async fn new(
-        callback: js_sys::Function,
+        callback: api::PlotDiffCallback,
         canvas_gpu: web_sys::HtmlCanvasElement,
         canvas_2d: web_sys::HtmlCanvasElement,
         power_profile: wasm_bridge::PowerProfile,
+        ready: Option<js_sys::Function>,
     ) -> Self {
-        console_error_panic_hook::set_once();
+        let panic_reporter = panic_report::PanicReporter::register(callback.clone());
 
         let window = web_sys::window().unwrap();
         let navigator = window.navigator();
@@ -181,40 +671,22 @@ pub async fn new(
             Err(err) => panic!("Could not request gpu adapter. Error: '{err:?}'"),
         };
 
-        let required_limits = js_sys::Object::new();
-        js_sys::Reflect::set(
-            &required_limits,
-            &JsValue::from("maxBufferSize"),
-            &JsValue::from(2147483648usize),
-        )
-        .unwrap();
-        js_sys::Reflect::set(
-            &required_limits,
-            &JsValue::from("maxStorageBufferBindingSize"),
-            &JsValue::from(2147483648usize),
-        )
-        .unwrap();
-
-        let device_descriptor = web_sys::GpuDeviceDescriptor::new();
-        js_sys::Reflect::set(
-            &required_limits,
-            &JsValue::from("requiredLimits"),
-            &JsValue::from(&required_limits),
-        )
-        .unwrap();
-
-        let device = match wasm_bindgen_futures::JsFuture::from(
-            adapter.request_device_with_descriptor(&device_descriptor),
-        )
-        .await
-        {
-            Ok(device) => {
-                if device.is_falsy() {
-                    panic!("Could not request gpu device.");
-                }
-
-                device.dyn_into::<web_sys::GpuDevice>().unwrap()
-            }
+        let device_descriptor = webgpu::DeviceDescriptor {
+            label: None,
+            optional_features: webgpu::Features {
+                timestamp_query: true,
+                float32_filterable: true,
+            },
+            desired_limits: webgpu::Limits {
+                max_texture_dimension_2d: 16384,
+                max_texture_array_layers: 2048,
+                max_buffer_size: 2147483648,
+                max_storage_buffer_binding_size: 2147483648,
+                ..Default::default()
+            },
+        };
+        let device = match webgpu::Device::request(&adapter, device_descriptor).await {
+            Ok(device) => device,
             Err(err) => panic!("Could not request gpu device. Error: '{err:?}'"),
         };
 
@@ -232,17 +704,43 @@ pub async fn new(
             .dyn_into::<web_sys::CanvasRenderingContext2d>()
             .unwrap();
 
-        context_gpu.configure(
-            web_sys::GpuCanvasConfiguration::new(&device, gpu.get_preferred_canvas_format())
-                .alpha_mode(web_sys::GpuCanvasAlphaMode::Premultiplied),
-        );
-
-        let device = webgpu::Device::new(device);
-        let preferred_format = gpu.get_preferred_canvas_format().into();
-        let pipelines = pipelines::Pipelines::new(&device, preferred_format).await;
+        // Render into a wide-gamut canvas if the display supports it, so that
+        // saturated colors are not clipped to the sRGB gamut.
+        let wide_gamut = window
+            .match_media("(color-gamut: p3)")
+            .ok()
+            .flatten()
+            .is_some_and(|query| query.matches());
+        let preferred_format = if wide_gamut {
+            buffers::OutputFormat::WIDE_GAMUT_FORMAT
+        } else {
+            gpu.get_preferred_canvas_format().into()
+        };
+        let output = buffers::OutputFormat {
+            format: preferred_format,
+            wide_gamut,
+            linear: false,
+            samples: buffers::RenderTexture::MSAA_SAMPLES,
+            analytic_antialiasing: false,
+        };
+        let canvas_alpha_mode = wasm_bridge::CanvasAlphaMode::Premultiplied;
+        Self::configure_canvas(&context_gpu, &device, output, canvas_alpha_mode);
+
+        let mut report_progress = |progress: f32| {
+            let percent = (progress * 100.0).round();
+            render_loading_placeholder(&canvas_2d, &context_2d, percent);
+            if let Some(ready) = &ready {
+                ready.call1(&JsValue::null(), &percent.into()).unwrap();
+            }
+        };
+        report_progress(0.0);
+        let mut progress = pipelines::Progress::new(&mut report_progress);
+        push_validation_scope(&device);
+        let pipelines = pipelines::Pipelines::new(&device, output, &mut progress).await;
+        let pipeline_errors = pop_validation_scope(&device, "pipeline creation").await;
         let buffers = buffers::Buffers::new(&device);
-        let render_texture = buffers::RenderTexture::new(&device, preferred_format);
-        let depth_texture = buffers::DepthTexture::new(&device);
+        let render_texture = buffers::RenderTexture::new(&device, output);
+        let depth_texture = buffers::DepthTexture::new(&device, output.samples);
 
         let client_width = canvas_gpu.client_width() as f32;
         let client_height = canvas_gpu.client_height() as f32;
@@ -285,7 +783,8 @@ pub async fn new(
         );
 
         let mut this = Self {
-            callback,
+            callback: callback.into(),
+            panic_reporter,
             canvas_gpu,
             canvas_2d,
             context_gpu,
@@ -301,9 +800,13 @@ pub async fn new(
             events: Vec::default(),
             handled_events: event::Event::NONE,
             active_action: None,
+            hovered_element: None,
             active_label_idx: None,
             labels: vec![],
+            label_groups: vec![],
+            curve_comparison: None,
             label_color_generator: LabelColorGenerator::default(),
+            reference_data: BTreeMap::new(),
             pixel_ratio: window.device_pixel_ratio() as f32,
             data_color_mode: DEFAULT_DATA_COLOR_MODE(),
             background_color: DEFAULT_BACKGROUND_COLOR(),
@@ -311,10 +814,43 @@ pub async fn new(
             unselected_color: DEFAULT_UNSELECTED_COLOR(),
             draw_order: DEFAULT_DRAW_ORDER,
             interaction_mode: wasm_bridge::InteractionMode::Full,
+            show_all_selections: false,
+            label_agreement_metrics: false,
+            linear_blending: false,
+            pixel_snapping: false,
+            antialiasing: wasm_bridge::Antialiasing::Multisample,
+            data_line_join: wasm_bridge::DataLineJoin::Butt,
+            canvas_alpha_mode,
+            background_layer: None,
+            annotations: BTreeMap::new(),
+            axis_regions: BTreeMap::new(),
+            flow_ribbons: false,
+            label_mean_mode: wasm_bridge::LabelMeanMode::Hidden,
+            label_means: BTreeMap::new(),
+            outlier_options: None,
+            density_options: None,
+            histogram_options: None,
+            live_options: None,
+            live_frozen: false,
+            live_update: None,
             debug: Default::default(),
+            hit_test: Default::default(),
+            control_points: Default::default(),
+            pointer_scale: 1.0,
+            accessibility: Default::default(),
             staging_data: StagingData::default(),
+            recording: None,
+            sync: None,
+            visibility_observer: None,
+            visible: true,
+            event_sender: None,
+            draw_scheduler: None,
         };
 
+        if let Some(diagnostic) = pipeline_errors {
+            this.report_diagnostic(diagnostic);
+        }
+
         this.update_matrix_buffer();
         this.update_axes_buffer();
         this.update_label_colors_buffer();
@@ -339,7 +875,12 @@ pub fn construct_event_queue(&mut self) -> wasm_bridge::EventQueue {
         }
 
         let (sx, rx) = async_channel::unbounded();
-        self.event_queue = Some(rx);
+        self.event_queue = Some(lanes::EventLanes::new(rx));
+        self.visibility_observer = Some(visibility::VisibilityObserver::new(
+            &self.canvas_2d,
+            sx.clone(),
+        ));
+        self.event_sender = Some(sx.clone());
         wasm_bridge::EventQueue { sender: sx }
     }
 
@@ -354,28 +895,124 @@ pub async fn enter_event_loop(&mut self) {
             panic!("EventQueue was not initialized.");
         }
 
-        let events = self.event_queue.take().unwrap();
+        let mut events = self.event_queue.take().unwrap();
         loop {
-            match events.recv().await.expect("the channel should be open") {
+            let event = events.next().await;
+            self.record_event(&event);
+
+            match event {
                 wasm_bridge::Event::Exit => break,
                 wasm_bridge::Event::Resize {
                     width,
                     height,
                     device_pixel_ratio,
                 } => {
-                    self.staging_data
-                        .resize
-                        .push((width, height, device_pixel_ratio));
+                    self.staging_data.resize = Some((width, height, device_pixel_ratio));
                     self.events.push(event::Event::RESIZE);
                 }
                 wasm_bridge::Event::CommitTransaction { transaction } => {
-                    self.staging_data.transactions.push(transaction);
+                    self.staging_data.transactions.push_back(transaction);
                     self.events.push(event::Event::TRANSACTION_COMMIT);
                 }
                 wasm_bridge::Event::Draw { completion } => self.render(completion).await,
+                wasm_bridge::Event::Capture { completion } => {
+                    completion
+                        .send(self.capture().await)
+                        .await
+                        .expect("the channel should be open");
+                }
+                wasm_bridge::Event::PreviewColorScale {
+                    color_scale,
+                    completion,
+                } => {
+                    completion
+                        .send(self.preview_color_scale(color_scale).await)
+                        .await
+                        .expect("the channel should be open");
+                }
+                wasm_bridge::Event::Describe { completion } => {
+                    self.refresh_selected_counts().await;
+                    let description = self.create_description().to_js();
+                    completion
+                        .send(description.unchecked_into())
+                        .await
+                        .expect("the channel should be open");
+                }
+                wasm_bridge::Event::ExportCurves { completion } => {
+                    let curves = self.create_curves_export().to_js();
+                    completion
+                        .send(curves.unchecked_into())
+                        .await
+                        .expect("the channel should be open");
+                }
+                wasm_bridge::Event::QueryStateFingerprint { completion } => {
+                    let fingerprint = self.create_plot_state().fingerprint();
+                    completion
+                        .send(fingerprint)
+                        .await
+                        .expect("the channel should be open");
+                }
+                wasm_bridge::Event::QueryLabelHistory { label, completion } => {
+                    let history = self.create_label_history(&label).to_js();
+                    completion
+                        .send(history.unchecked_into())
+                        .await
+                        .expect("the channel should be open");
+                }
+                wasm_bridge::Event::QueryData {
+                    indices,
+                    completion,
+                } => {
+                    let data = api::data_to_js(&self.query_data(&indices));
+                    completion
+                        .send(data.unchecked_into())
+                        .await
+                        .expect("the channel should be open");
+                }
+                wasm_bridge::Event::SortByProbability { completion } => {
+                    let indices = self.sort_by_probability().await;
+                    completion
+                        .send(js_sys::BigUint64Array::from(&*indices))
+                        .await
+                        .expect("the channel should be open");
+                }
                 wasm_bridge::Event::PointerDown { event } => self.pointer_down(event),
                 wasm_bridge::Event::PointerUp { event } => self.pointer_up(event),
                 wasm_bridge::Event::PointerMove { event } => self.pointer_move(event),
+                wasm_bridge::Event::SetVisible { visible } => self.set_visible(visible),
+                wasm_bridge::Event::SetFrameOptions { options } => self.set_frame_options(options),
+                wasm_bridge::Event::StartRecording => {
+                    self.recording = Some((js_sys::Date::now(), Default::default()));
+                }
+                wasm_bridge::Event::StopRecording { completion } => {
+                    let log = self
+                        .recording
+                        .take()
+                        .map(|(_, log)| log)
+                        .unwrap_or_default();
+                    completion
+                        .send(log)
+                        .await
+                        .expect("the channel should be open");
+                }
+                wasm_bridge::Event::InspectBuffer { buffer, completion } => {
+                    completion
+                        .send(self.inspect_buffer(buffer).await)
+                        .await
+                        .expect("the channel should be open");
+                }
+                #[cfg(feature = "dev-tools")]
+                wasm_bridge::Event::ReloadShader {
+                    name,
+                    source,
+                    completion,
+                } => {
+                    let result = self.reload_shader(&name, &source).await;
+                    completion
+                        .send(result)
+                        .await
+                        .expect("the channel should be open");
+                }
             }
         }
 
@@ -383,25 +1020,32 @@ pub async fn enter_event_loop(&mut self) {
     }
 }
 
+// Recording
+impl Renderer {
+    fn record_event(&mut self, event: &wasm_bridge::Event) {
+        if let Some((start, log)) = &mut self.recording {
+            let time = js_sys::Date::now() - *start;
+            if let Some(event) = wasm_bridge::RecordedEvent::from_event(time, event) {
+                log.events.push(event);
+            }
+        }
+    }
+}
+
 // Rendering
 impl Renderer {
-    fn render_data(&self, render_pass: &webgpu::RenderPassEncoder) {
-        let axes = self.axes.borrow();
-        let (viewport_start, viewport_size) = axes.viewport(self.pixel_ratio);
-        let probabilities = if let Some(active_label_idx) = self.active_label_idx {
-            self.buffers.data().probabilities(active_label_idx).clone()
-        } else {
-            buffers::ProbabilitiesBuffer::empty(&self.device)
+    fn render_background(&self, render_pass: &webgpu::RenderPassEncoder) {
+        let Some(layer) = &self.background_layer else {
+            return;
         };
 
-        self.pipelines.render().data_lines().render(
-            self.buffers.shared().matrices(),
-            self.buffers.data().config(),
-            self.buffers.shared().axes(),
-            self.buffers.data().lines(),
-            self.buffers.data().color_values(),
-            &probabilities,
-            self.buffers.shared().color_scale(),
+        let (viewport_start, viewport_size) = self.background_viewport(layer);
+        if viewport_size.0 < 1.0 || viewport_size.1 < 1.0 {
+            return;
+        }
+
+        self.pipelines.render().background().render(
+            self.buffers.background(),
             viewport_start,
             viewport_size,
             &self.device,
@@ -409,38 +1053,29 @@ fn render_data(&self, render_pass: &webgpu::RenderPassEncoder) {
         );
     }
 
-    fn render_axes(&self, render_pass: &webgpu::RenderPassEncoder) {
+    fn render_axis_regions(&self, render_pass: &webgpu::RenderPassEncoder) {
         let axes = self.axes.borrow();
-        let (viewport_start, viewport_size) = axes.viewport(self.pixel_ratio);
+        let (start, size) = axes.viewport(self.pixel_ratio);
 
-        self.pipelines.render().axis_lines().render(
+        self.pipelines.render().axis_regions().render(
             self.buffers.shared().matrices(),
-            self.buffers.axes().config(),
             self.buffers.shared().axes(),
-            self.buffers.axes().lines(),
-            viewport_start,
-            viewport_size,
+            self.buffers.axes().regions(),
+            pipelines::Viewport { start, size },
             &self.device,
             render_pass,
         );
     }
 
-    fn render_selections(&self, render_pass: &webgpu::RenderPassEncoder) {
-        if self.active_label_idx.is_none() {
-            return;
-        }
-        let active_label_idx = self.active_label_idx.unwrap();
-
+    fn render_reference_data(&self, render_pass: &webgpu::RenderPassEncoder) {
         let axes = self.axes.borrow();
         let (viewport_start, viewport_size) = axes.viewport(self.pixel_ratio);
 
-        self.pipelines.render().selections().render(
+        self.pipelines.render().reference_lines().render(
             self.buffers.shared().matrices(),
-            self.buffers.selections().config(),
+            self.buffers.data().reference_config(),
             self.buffers.shared().axes(),
-            self.buffers.selections().lines(active_label_idx),
-            self.buffers.shared().label_colors(),
-            self.buffers.curves().sample_texture(active_label_idx),
+            self.buffers.data().reference_lines(),
             viewport_start,
             viewport_size,
             &self.device,
@@ -448,55 +1083,63 @@ fn render_selections(&self, render_pass: &webgpu::RenderPassEncoder) {
         );
     }
 
-    fn render_curve_segments(&self, render_pass: &webgpu::RenderPassEncoder) {
-        if self.active_label_idx.is_none() {
+    fn render_flow_ribbons(&self, render_pass: &webgpu::RenderPassEncoder) {
+        if !self.flow_ribbons {
             return;
         }
-        let active_label_idx = self.active_label_idx.unwrap();
 
+        let axes = self.axes.borrow();
+        let (start, size) = axes.viewport(self.pixel_ratio);
+
+        self.pipelines.render().flow_ribbons().render(
+            self.buffers.shared().matrices(),
+            self.buffers.shared().axes(),
+            self.buffers.flows(),
+            pipelines::Viewport { start, size },
+            &self.device,
+            render_pass,
+        );
+    }
+
+    fn render_snapshots(&self, render_pass: &webgpu::RenderPassEncoder) {
         let axes = self.axes.borrow();
         let (viewport_start, viewport_size) = axes.viewport(self.pixel_ratio);
-        let (min_curve_t, _) = axes.curve_t_range();
 
-        let render = |label| {
-            self.pipelines.render().curve_segments().render(
-                label,
-                active_label_idx,
-                min_curve_t,
+        for (label_idx, label) in self.labels.iter().enumerate() {
+            if label.snapshot.is_none() || !label.visible {
+                continue;
+            }
+
+            self.pipelines.render().reference_lines().render(
                 self.buffers.shared().matrices(),
+                self.buffers.data().snapshot_config(label_idx),
                 self.buffers.shared().axes(),
-                self.buffers.curves().lines(label),
-                self.buffers.shared().label_colors(),
+                self.buffers.data().snapshot_lines(label_idx),
                 viewport_start,
                 viewport_size,
                 &self.device,
                 render_pass,
             );
-        };
-
-        for i in 0..self.labels.len() {
-            if i == active_label_idx {
-                continue;
-            }
-            render(i)
         }
-        render(active_label_idx)
     }
 
-    fn render_curves(&self, render_pass: &webgpu::RenderPassEncoder) {
-        if self.active_label_idx.is_none() {
-            return;
-        }
-        let active_label_idx = self.active_label_idx.unwrap();
-
+    fn render_data(&self, render_pass: &webgpu::RenderPassEncoder) {
         let axes = self.axes.borrow();
         let (viewport_start, viewport_size) = axes.viewport(self.pixel_ratio);
+        let probabilities = if let Some(active_label_idx) = self.active_label_idx {
+            self.buffers.data().probabilities(active_label_idx).clone()
+        } else {
+            buffers::ProbabilitiesBuffer::empty(&self.device)
+        };
 
-        self.pipelines.render().curve_lines().render(
+        self.pipelines.render().data_lines().render(
             self.buffers.shared().matrices(),
-            self.buffers.curves().config(),
+            self.buffers.data().config(),
             self.buffers.shared().axes(),
-            self.buffers.curves().lines(active_label_idx),
+            self.buffers.data().lines(),
+            self.buffers.data().color_values(),
+            &probabilities,
+            self.buffers.shared().color_scale(),
             viewport_start,
             viewport_size,
             &self.device,
@@ -504,17 +1147,197 @@ fn render_curves(&self, render_pass: &webgpu::RenderPassEncoder) {
         );
     }
 
-    fn render_color_bar(&self, render_pass: &webgpu::RenderPassEncoder) {
-        if !self.color_bar.is_visible() {
+    fn render_histograms(&self, render_pass: &webgpu::RenderPassEncoder) {
+        if self.histogram_options.is_none() {
             return;
         }
 
-        let (viewport_start, viewport_size) = self.color_bar.bar_viewport(self.pixel_ratio);
+        let axes = self.axes.borrow();
+        let (start, size) = axes.viewport(self.pixel_ratio);
 
-        self.pipelines.render().color_bar().render(
-            self.buffers.shared().color_scale(),
-            self.buffers.shared().color_scale_bounds(),
-            viewport_start,
+        self.pipelines.render().histogram_bars().render(
+            self.buffers.shared().matrices(),
+            self.buffers.shared().axes(),
+            self.buffers.histograms(),
+            pipelines::Viewport { start, size },
+            &self.device,
+            render_pass,
+        );
+    }
+
+    fn render_density_curves(&self, render_pass: &webgpu::RenderPassEncoder) {
+        if self.density_options.is_none() {
+            return;
+        }
+
+        let axes = self.axes.borrow();
+        let (start, size) = axes.viewport(self.pixel_ratio);
+
+        self.pipelines.render().density_curves().render(
+            self.buffers.shared().matrices(),
+            self.buffers.shared().axes(),
+            self.buffers.densities(),
+            pipelines::Viewport { start, size },
+            &self.device,
+            render_pass,
+        );
+    }
+
+    fn render_axes(&self, render_pass: &webgpu::RenderPassEncoder) {
+        let axes = self.axes.borrow();
+        let (viewport_start, viewport_size) = axes.viewport(self.pixel_ratio);
+
+        self.pipelines.render().axis_lines().render(
+            self.buffers.shared().matrices(),
+            self.buffers.axes().config(),
+            self.buffers.shared().axes(),
+            self.buffers.axes().lines(),
+            viewport_start,
+            viewport_size,
+            &self.device,
+            render_pass,
+        );
+    }
+
+    fn render_selections(&self, render_pass: &webgpu::RenderPassEncoder) {
+        if self.active_label_idx.is_none() {
+            return;
+        }
+        let active_label_idx = self.active_label_idx.unwrap();
+
+        let axes = self.axes.borrow();
+        let (viewport_start, viewport_size) = axes.viewport(self.pixel_ratio);
+
+        let render = |label| {
+            self.pipelines.render().selections().render(
+                self.buffers.shared().matrices(),
+                self.buffers.selections().config(),
+                self.buffers.shared().axes(),
+                self.buffers.selections().lines(label),
+                self.buffers.shared().label_colors(),
+                self.buffers.curves().sample_texture(label),
+                viewport_start,
+                viewport_size,
+                &self.device,
+                render_pass,
+            );
+        };
+
+        // Draw the active label last, such that it remains on top.
+        if self.show_all_selections {
+            for (i, label) in self.labels.iter().enumerate() {
+                if i == active_label_idx || !label.visible {
+                    continue;
+                }
+                render(i)
+            }
+        }
+        if self.labels[active_label_idx].visible {
+            render(active_label_idx)
+        }
+    }
+
+    fn render_curve_segments(&self, render_pass: &webgpu::RenderPassEncoder) {
+        if self.active_label_idx.is_none() {
+            return;
+        }
+        let active_label_idx = self.active_label_idx.unwrap();
+
+        let axes = self.axes.borrow();
+        let (viewport_start, viewport_size) = axes.viewport(self.pixel_ratio);
+        let (min_curve_t, _) = axes.curve_t_range();
+
+        let render = |label| {
+            self.pipelines.render().curve_segments().render(
+                label,
+                active_label_idx,
+                min_curve_t,
+                self.buffers.shared().matrices(),
+                self.buffers.shared().axes(),
+                self.buffers.curves().lines(label),
+                self.buffers.shared().label_colors(),
+                viewport_start,
+                viewport_size,
+                &self.device,
+                render_pass,
+            );
+        };
+
+        for (i, label) in self.labels.iter().enumerate() {
+            if i == active_label_idx || !label.visible {
+                continue;
+            }
+            render(i)
+        }
+        if self.labels[active_label_idx].visible {
+            render(active_label_idx)
+        }
+    }
+
+    fn render_curves(&self, render_pass: &webgpu::RenderPassEncoder) {
+        let active_label_idx = match self.active_label_idx {
+            Some(x) if self.labels[x].visible => x,
+            _ => return,
+        };
+
+        let axes = self.axes.borrow();
+        let (viewport_start, viewport_size) = axes.viewport(self.pixel_ratio);
+
+        self.pipelines.render().curve_lines().render(
+            self.buffers.shared().matrices(),
+            self.buffers.curves().config(),
+            self.buffers.shared().axes(),
+            self.buffers.curves().lines(active_label_idx),
+            viewport_start,
+            viewport_size,
+            &self.device,
+            render_pass,
+        );
+    }
+
+    fn render_curve_comparison(&self, render_pass: &webgpu::RenderPassEncoder) {
+        let Some((label_a, label_b)) = &self.curve_comparison else {
+            return;
+        };
+        let position = |id: &str| self.labels.iter().position(|l| l.id == id && l.visible);
+        let (Some(label_a), Some(label_b)) = (position(label_a), position(label_b)) else {
+            return;
+        };
+
+        let axes = self.axes.borrow();
+        let (viewport_start, viewport_size) = axes.viewport(self.pixel_ratio);
+
+        self.pipelines.render().curve_comparison().render(
+            (label_a, label_b),
+            axes.curve_t_range(),
+            self.buffers.shared().matrices(),
+            self.buffers.shared().axes(),
+            self.buffers.shared().label_colors(),
+            (
+                self.buffers.curves().sample_texture(label_a),
+                self.buffers.curves().sample_texture(label_b),
+            ),
+            viewport_start,
+            viewport_size,
+            &self.device,
+            render_pass,
+        );
+    }
+
+    fn render_color_bar(&self, render_pass: &webgpu::RenderPassEncoder) {
+        if !self.color_bar.is_visible() {
+            return;
+        }
+
+        let (viewport_start, viewport_size) = self.color_bar.bar_viewport(self.pixel_ratio);
+        if viewport_size.0 < 1.0 || viewport_size.1 < 1.0 {
+            return;
+        }
+
+        self.pipelines.render().color_bar().render(
+            self.buffers.shared().color_scale(),
+            self.buffers.shared().color_scale_bounds(),
+            viewport_start,
             viewport_size,
             &self.device,
             render_pass,
@@ -597,7 +1420,7 @@ fn render_ticks(&self) {
         for ax in guard.visible_axes() {
             let world_mapper = ax.space_transformer();
             let (ticks_start, ticks_end) = ax.ticks_range(false);
-            for (t, tick) in ax.ticks() {
+            for (t, tick) in ax.ticks().iter() {
                 let position = ticks_start.lerp(ticks_end, *t);
                 let position = position.transform(&world_mapper);
                 let position = position.transform(&screen_mapper);
@@ -608,7 +1431,7 @@ fn render_ticks(&self) {
 
             if ax.is_expanded() {
                 let (ticks_start_exp, ticks_end_exp) = ax.ticks_range(true);
-                for (t, tick) in ax.ticks() {
+                for (t, tick) in ax.ticks().iter() {
                     let position = ticks_start_exp.lerp(ticks_end_exp, *t);
                     let position = position.transform(&world_mapper);
                     let position = position.transform(&screen_mapper);
@@ -619,11 +1442,17 @@ fn render_ticks(&self) {
             }
         }
 
+        self.context_2d.restore();
+    }
+
+    fn render_color_bar_ticks(&self) {
         if !self.color_bar.is_visible() {
-            self.context_2d.restore();
             return;
         }
 
+        self.context_2d.save();
+        self.context_2d.set_text_align("right");
+
         let (ticks_start, ticks_end) = self.color_bar.ticks_range();
         for (t, tick) in self.color_bar.ticks() {
             let position = ticks_start.lerp(ticks_end, *t);
@@ -634,18 +1463,244 @@ fn render_ticks(&self) {
         self.context_2d.restore();
     }
 
+    /// Restricts the following drawing operations to the plot area, while the
+    /// view is zoomed, such that the elements of the axes outside of the view
+    /// are hidden.
+    fn clip_to_view(&self) {
+        if !self.axes.borrow().is_view_zoomed() {
+            return;
+        }
+
+        let plot_area = self.color_bar.plot_area();
+        let (start_x, start_y) = plot_area.start().extract();
+        let (end_x, end_y) = plot_area.end().extract();
+        self.context_2d.begin_path();
+        self.context_2d.rect(
+            start_x as f64,
+            start_y as f64,
+            (end_x - start_x) as f64,
+            (end_y - start_y) as f64,
+        );
+        self.context_2d.clip();
+    }
+
+    fn render_axis_region_labels(&self) {
+        self.context_2d.save();
+        self.context_2d.set_text_align("left");
+        self.context_2d.set_text_baseline("top");
+
+        let guard = self.axes.borrow();
+        let screen_mapper = guard.space_transformer();
+
+        for region in self.axis_regions.values() {
+            let Some(label) = region.label.as_deref().filter(|l| !l.is_empty()) else {
+                continue;
+            };
+            let Some(ax) = guard.axis(&region.axis).filter(|ax| !ax.is_hidden()) else {
+                continue;
+            };
+
+            // Label the top of the visible part of the region.
+            let (min, max) = ax.visible_data_range();
+            let (start, end) = region.range;
+            if end < min || start > max {
+                continue;
+            }
+            let Some(position) = ax.value_position(end.min(max)) else {
+                continue;
+            };
+
+            let position = position.transform(&ax.space_transformer());
+            let position = position.transform(&screen_mapper);
+            let (x, y) = position.extract();
+
+            self.context_2d
+                .fill_text(label, x as f64 + 6.0, y as f64)
+                .unwrap();
+        }
+
+        self.context_2d.restore();
+    }
+
+    fn render_label_means(&self) {
+        if self.label_mean_mode == wasm_bridge::LabelMeanMode::Hidden {
+            return;
+        }
+
+        let guard = self.axes.borrow();
+        let screen_mapper = guard.space_transformer();
+        let visible_axes = guard.visible_axes().collect::<Vec<_>>();
+
+        self.context_2d.save();
+        self.context_2d.set_line_width(2.5);
+        self.context_2d.set_line_join("round");
+
+        for label in self.labels.iter().filter(|l| l.visible) {
+            let Some(means) = self.label_means.get(&label.id) else {
+                continue;
+            };
+
+            // The polyline is interrupted at the axes whose mean lies outside
+            // of the visible range, while the band is clipped to it.
+            let mut runs = vec![vec![]];
+            for ax in &visible_axes {
+                let points = means.get(&ax.key()).and_then(|mean| {
+                    let (min, max) = ax.data_range();
+                    let (visible_min, visible_max) = ax.visible_data_range();
+                    let (start, end) = ax.axis_line_range();
+                    let to_screen = |normalized: f32| {
+                        let t = min.lerp(max, normalized).inv_lerp(visible_min, visible_max);
+                        let position = start.lerp(end, t.clamp(0.0, 1.0));
+                        let position = position.transform(&ax.space_transformer());
+                        let (x, y) = position.transform(&screen_mapper).extract();
+                        (t, x as f64, y as f64)
+                    };
+
+                    let (t, x, y) = to_screen(mean.mean);
+                    if !(0.0..=1.0).contains(&t) {
+                        return None;
+                    }
+                    let (_, _, low) = to_screen(mean.mean - mean.deviation);
+                    let (_, _, high) = to_screen(mean.mean + mean.deviation);
+                    Some((x, y, low, high))
+                });
+                match points {
+                    Some(points) => runs.last_mut().unwrap().push(points),
+                    None => runs.push(vec![]),
+                }
+            }
+
+            let (color, _) = label.colors(&self.label_groups);
+            let [r, g, b] = color
+                .transform::<SRgb>()
+                .to_f32()
+                .map(|v| (v.clamp(0.0, 1.0) * 255.0).round());
+            self.context_2d
+                .set_fill_style(&format!("rgb({r} {g} {b} / 0.2)").into());
+            self.context_2d
+                .set_stroke_style(&format!("rgb({r} {g} {b})").into());
+
+            for run in runs.iter().filter(|run| run.len() > 1) {
+                if self.label_mean_mode == wasm_bridge::LabelMeanMode::MeanWithDeviation {
+                    self.context_2d.begin_path();
+                    for &(x, _, _, high) in run {
+                        self.context_2d.line_to(x, high);
+                    }
+                    for &(x, _, low, _) in run.iter().rev() {
+                        self.context_2d.line_to(x, low);
+                    }
+                    self.context_2d.close_path();
+                    self.context_2d.fill();
+                }
+
+                self.context_2d.begin_path();
+                for &(x, y, _, _) in run {
+                    self.context_2d.line_to(x, y);
+                }
+                self.context_2d.stroke();
+            }
+        }
+
+        self.context_2d.restore();
+    }
+
+    /// Returns the position of the anchor of an annotation on the screen,
+    /// if it lies inside of the visible range of a visible axis.
+    fn annotation_position(
+        &self,
+        anchor: &wasm_bridge::AnnotationAnchor,
+    ) -> Option<Position<ScreenSpace>> {
+        let guard = self.axes.borrow();
+        let ax = guard.axis(anchor.axis()).filter(|ax| !ax.is_hidden())?;
+        let value = match anchor {
+            wasm_bridge::AnnotationAnchor::Value { value, .. } => *value,
+            wasm_bridge::AnnotationAnchor::Datum { index, .. } => *ax.data().get(*index)?,
+        };
+
+        let position = ax.value_position(value)?;
+        let position = position.transform(&ax.space_transformer());
+        Some(position.transform(&guard.space_transformer()))
+    }
+
+    fn render_annotations(&self) {
+        if self.annotations.is_empty() {
+            return;
+        }
+
+        self.context_2d.save();
+        self.context_2d.set_fill_style(&"rgb(60 60 60)".into());
+        self.context_2d.set_stroke_style(&"rgb(60 60 60)".into());
+        self.context_2d.set_line_width(1.5);
+        self.context_2d.set_text_baseline("middle");
+
+        for annotation in self.annotations.values() {
+            let Some(anchor) = self.annotation_position(&annotation.anchor) else {
+                continue;
+            };
+            let (x, y) = anchor.extract();
+            let (x, y) = (x as f64, y as f64);
+            let (offset_x, offset_y) = annotation.offset;
+            let (text_x, text_y) = (x + offset_x as f64, y + offset_y as f64);
+
+            match annotation.kind {
+                wasm_bridge::AnnotationKind::Text => {}
+                wasm_bridge::AnnotationKind::Arrow => {
+                    const HEAD_LENGTH: f64 = 8.0;
+                    const HEAD_ANGLE: f64 = std::f64::consts::FRAC_PI_6;
+                    let angle = (y - text_y).atan2(x - text_x);
+
+                    self.context_2d.begin_path();
+                    self.context_2d.move_to(text_x, text_y);
+                    self.context_2d.line_to(x, y);
+                    self.context_2d.move_to(
+                        x - HEAD_LENGTH * (angle - HEAD_ANGLE).cos(),
+                        y - HEAD_LENGTH * (angle - HEAD_ANGLE).sin(),
+                    );
+                    self.context_2d.line_to(x, y);
+                    self.context_2d.line_to(
+                        x - HEAD_LENGTH * (angle + HEAD_ANGLE).cos(),
+                        y - HEAD_LENGTH * (angle + HEAD_ANGLE).sin(),
+                    );
+                    self.context_2d.stroke();
+                }
+                wasm_bridge::AnnotationKind::Marker => {
+                    self.context_2d.begin_path();
+                    self.context_2d
+                        .arc(x, y, 4.0, 0.0, std::f64::consts::TAU)
+                        .unwrap();
+                    self.context_2d.fill();
+                }
+            }
+
+            if annotation.text.is_empty() {
+                continue;
+            }
+
+            // Grow the text away from the anchor.
+            self.context_2d
+                .set_text_align(if offset_x < 0.0 { "right" } else { "left" });
+            self.context_2d
+                .fill_text(&annotation.text, text_x, text_y)
+                .unwrap();
+        }
+
+        self.context_2d.restore();
+    }
+
     fn render_control_points(&self) {
         let active_label_idx = match self.active_label_idx {
-            Some(x) => x,
-            None => return,
+            Some(x) if self.labels[x].visible => x,
+            _ => return,
         };
 
         self.context_2d.save();
-        self.context_2d.set_fill_style(&"rgb(178 178 178)".into());
-        self.context_2d.set_stroke_style(&"rgb(120 120 120)".into());
+        self.context_2d
+            .set_fill_style(&self.control_points.fill.as_str().into());
+        self.context_2d
+            .set_stroke_style(&self.control_points.stroke.as_str().into());
 
+        let radius = self.control_points_radius();
         let guard = self.axes.borrow();
-        let radius = guard.control_points_radius().extract::<f32>() as f64;
         let screen_mapper = guard.space_transformer();
 
         for ax in guard.visible_axes() {
@@ -795,6 +1850,277 @@ fn render_control_points(&self) {
         self.context_2d.restore();
     }
 
+    fn render_hover_highlight(&self) {
+        let (active_label_idx, element) = match (self.active_label_idx, &self.hovered_element) {
+            (Some(label), Some(element)) => (label, element),
+            _ => return,
+        };
+
+        self.context_2d.save();
+        self.context_2d
+            .set_fill_style(&self.control_points.hover_fill.as_str().into());
+        self.context_2d
+            .set_stroke_style(&self.control_points.hover_stroke.as_str().into());
+        self.context_2d.set_line_cap("round");
+
+        let radius = self.control_points_radius();
+        let guard = self.axes.borrow();
+        let screen_mapper = guard.space_transformer();
+
+        match element {
+            axis::Element::Group { axis, group_idx } => {
+                let range = axis
+                    .borrow_selection_curve_builder(active_label_idx)
+                    .get_group_range(*group_idx);
+                if let Some([start, end]) = range {
+                    let world_mapper = axis.space_transformer();
+                    let (axis_start, axis_end) = axis.axis_line_range();
+                    let (x0, y0) = axis_start
+                        .lerp(axis_end, start.clamp(0.0, 1.0))
+                        .transform(&world_mapper)
+                        .transform(&screen_mapper)
+                        .extract();
+                    let (x1, y1) = axis_start
+                        .lerp(axis_end, end.clamp(0.0, 1.0))
+                        .transform(&world_mapper)
+                        .transform(&screen_mapper)
+                        .extract();
+
+                    self.context_2d.set_line_width(radius * 2.0);
+                    self.context_2d.begin_path();
+                    self.context_2d.move_to(x0 as f64, y0 as f64);
+                    self.context_2d.line_to(x1 as f64, y1 as f64);
+                    self.context_2d.stroke();
+                }
+            }
+            axis::Element::Brush {
+                axis,
+                selection_idx,
+            } => {
+                let control_points = axis
+                    .borrow_selection_curve_builder(active_label_idx)
+                    .get_selection_control_points();
+                if let Some((rank, control_points)) = control_points.get(*selection_idx) {
+                    let world_mapper = axis.space_transformer();
+                    let (axis_start, axis_end) = axis.axis_line_range();
+                    let rank_offset = axis.selection_offset_at_rank(*rank);
+                    let start = control_points.first().unwrap().clamp(0.0, 1.0);
+                    let end = control_points.last().unwrap().clamp(0.0, 1.0);
+                    let (x0, y0) = (axis_start.lerp(axis_end, start) + rank_offset)
+                        .transform(&world_mapper)
+                        .transform(&screen_mapper)
+                        .extract();
+                    let (x1, y1) = (axis_start.lerp(axis_end, end) + rank_offset)
+                        .transform(&world_mapper)
+                        .transform(&screen_mapper)
+                        .extract();
+
+                    self.context_2d.set_line_width(radius * 2.0);
+                    self.context_2d.begin_path();
+                    self.context_2d.move_to(x0 as f64, y0 as f64);
+                    self.context_2d.line_to(x1 as f64, y1 as f64);
+                    self.context_2d.stroke();
+                }
+            }
+            axis::Element::AxisControlPoint { .. } | axis::Element::CurveControlPoint { .. } => {
+                let options = &self.control_points;
+                let (fill, stroke) = (&options.hover_fill, &options.hover_stroke);
+                self.render_control_point_highlight(element, active_label_idx, fill, stroke);
+            }
+            _ => {}
+        }
+
+        self.context_2d.restore();
+    }
+
+    fn render_active_control_point(&self) {
+        let element = self
+            .active_action
+            .as_ref()
+            .and_then(|action| action.control_point());
+        let (active_label_idx, element) = match (self.active_label_idx, element) {
+            (Some(label), Some(element)) => (label, element),
+            _ => return,
+        };
+
+        let options = &self.control_points;
+        let (fill, stroke) = (&options.active_fill, &options.active_stroke);
+        self.render_control_point_highlight(&element, active_label_idx, fill, stroke);
+    }
+
+    /// Draws a control point enlarged by the highlight scale.
+    fn render_control_point_highlight(
+        &self,
+        element: &axis::Element,
+        active_label_idx: usize,
+        fill: &str,
+        stroke: &str,
+    ) {
+        let guard = self.axes.borrow();
+        let screen_mapper = guard.space_transformer();
+
+        let position = match element {
+            axis::Element::AxisControlPoint {
+                axis,
+                selection_idx,
+                control_point_idx,
+            } => {
+                let control_points = axis
+                    .borrow_selection_curve_builder(active_label_idx)
+                    .get_selection_control_points();
+                let point = control_points
+                    .get(*selection_idx)
+                    .and_then(|(rank, points)| Some((*rank, *points.get(*control_point_idx)?)));
+                point.map(|(rank, point)| {
+                    let world_mapper = axis.space_transformer();
+                    let (axis_start, axis_end) = axis.axis_line_range();
+                    let rank_offset = axis.selection_offset_at_rank(rank);
+                    (axis_start.lerp(axis_end, point) + rank_offset)
+                        .transform(&world_mapper)
+                        .transform(&screen_mapper)
+                })
+            }
+            axis::Element::CurveControlPoint {
+                axis,
+                selection_idx,
+                control_point_idx,
+            } => {
+                let point = axis
+                    .borrow_selection_curve_builder(active_label_idx)
+                    .selections()
+                    .get(*selection_idx)
+                    .and_then(|selection| selection.control_points().get(*control_point_idx))
+                    .copied();
+                point.map(|(axis_value, curve_value)| {
+                    let world_mapper = axis.space_transformer();
+                    let (axis_start, axis_end) = axis.axis_line_range();
+                    let curve_offset = axis.curve_offset_at_curve_value(curve_value);
+                    (axis_start.lerp(axis_end, axis_value) + curve_offset)
+                        .transform(&world_mapper)
+                        .transform(&screen_mapper)
+                })
+            }
+            _ => None,
+        };
+        let (x, y) = match position {
+            Some(position) => position.extract(),
+            None => return,
+        };
+
+        let radius = self.control_points_radius() * self.control_points.highlight_scale as f64;
+        self.context_2d.save();
+        self.context_2d.set_fill_style(&fill.into());
+        self.context_2d.set_stroke_style(&stroke.into());
+        self.context_2d.begin_path();
+        self.context_2d
+            .arc(x as f64, y as f64, radius, 0.0, std::f64::consts::TAU)
+            .unwrap();
+        self.context_2d.fill();
+        self.context_2d.stroke();
+        self.context_2d.restore();
+    }
+
+    /// Returns the radius of the drawn control points in screen space,
+    /// enlarged for the type of the last pointer.
+    fn control_points_radius(&self) -> f64 {
+        let radius = self.axes.borrow().control_points_radius().extract::<f32>();
+        (radius * self.pointer_scale) as f64
+    }
+
+    fn render_insertion_marker(&self) {
+        let (start, end) = match self
+            .active_action
+            .as_ref()
+            .and_then(|action| action.insertion_marker())
+        {
+            Some(x) => x,
+            None => return,
+        };
+
+        let guard = self.axes.borrow();
+        let screen_mapper = guard.space_transformer();
+        let (start_x, start_y) = start.transform(&screen_mapper).extract();
+        let (end_x, end_y) = end.transform(&screen_mapper).extract();
+
+        self.context_2d.save();
+        self.context_2d.set_stroke_style(&"rgb(120 120 120)".into());
+        self.context_2d.set_line_width(2.0);
+        let stroke = js_sys::Array::from_iter([js_sys::Number::from(4.0f64), 4.0f64.into()]);
+        self.context_2d.set_line_dash(&stroke.into()).unwrap();
+
+        self.context_2d.begin_path();
+        self.context_2d.move_to(start_x as f64, start_y as f64);
+        self.context_2d.line_to(end_x as f64, end_y as f64);
+        self.context_2d.stroke();
+
+        self.context_2d.restore();
+    }
+
+    fn render_removal_marker(&self) {
+        let bounding_box = match self
+            .active_action
+            .as_ref()
+            .and_then(|action| action.removal_marker())
+        {
+            Some(x) => x,
+            None => return,
+        };
+
+        let guard = self.axes.borrow();
+        let bounding_box = bounding_box.transform(&guard.space_transformer());
+        let x = bounding_box.start().x;
+        let y = bounding_box.end().y;
+        let (w, h) = bounding_box.size().extract();
+        let (x, y, w, h) = (x as f64, y as f64, w as f64, h as f64);
+
+        self.context_2d.save();
+        self.context_2d
+            .set_fill_style(&"rgb(220 50 50 / 0.15)".into());
+        self.context_2d.set_stroke_style(&"rgb(220 50 50)".into());
+        self.context_2d.set_line_width(2.0);
+        self.context_2d.fill_rect(x, y, w, h);
+        self.context_2d.stroke_rect(x, y, w, h);
+
+        // Cross out the axis.
+        self.context_2d.begin_path();
+        self.context_2d.move_to(x, y);
+        self.context_2d.line_to(x + w, y + h);
+        self.context_2d.move_to(x + w, y);
+        self.context_2d.line_to(x, y + h);
+        self.context_2d.stroke();
+
+        self.context_2d.restore();
+    }
+
+    fn render_curve_readout(&self) {
+        const OFFSET: f64 = 12.0;
+        const PADDING: f64 = 3.0;
+
+        let Some((position, text)) = &self.staging_data.curve_readout else {
+            return;
+        };
+        let (x, y) = position.extract();
+        let (x, y) = (x as f64 + OFFSET, y as f64 - OFFSET);
+
+        self.context_2d.save();
+        self.context_2d.set_text_baseline("middle");
+        let metrics = self.context_2d.measure_text(text).unwrap();
+        let width = metrics.width();
+        let height = metrics.actual_bounding_box_ascent() + metrics.actual_bounding_box_descent();
+
+        self.context_2d
+            .set_fill_style(&"rgb(255 255 255 / 0.8)".into());
+        self.context_2d.fill_rect(
+            x - PADDING,
+            y - (height / 2.0) - PADDING,
+            width + (2.0 * PADDING),
+            height + (2.0 * PADDING),
+        );
+        self.context_2d.set_fill_style(&"rgb(60 60 60)".into());
+        self.context_2d.fill_text(text, x, y).unwrap();
+        self.context_2d.restore();
+    }
+
     fn render_color_bar_label(&self) {
         self.context_2d.save();
         self.context_2d.set_text_align("center");
@@ -819,13 +2145,80 @@ fn render_color_bar_label(&self) {
         self.context_2d.restore();
     }
 
+    fn render_interaction_trace(&self) {
+        const LINE_HEIGHT: f64 = 16.0;
+        const PADDING: f64 = 4.0;
+
+        if !self.debug.show_interaction_trace {
+            return;
+        }
+
+        self.context_2d.save();
+        self.context_2d
+            .set_stroke_style(&"rgb(255 0 0 / 0.6)".into());
+        self.context_2d.set_line_width(2.0);
+        self.context_2d.begin_path();
+        for (i, position) in self.staging_data.pointer_trail.iter().enumerate() {
+            let (x, y) = position.extract();
+            if i == 0 {
+                self.context_2d.move_to(x as f64, y as f64);
+            } else {
+                self.context_2d.line_to(x as f64, y as f64);
+            }
+        }
+        self.context_2d.stroke();
+
+        let element = self
+            .staging_data
+            .pointer_trail
+            .back()
+            .and_then(|&position| {
+                self.axes.borrow().element_at_position(
+                    position,
+                    self.active_label_idx,
+                    self.pointer_scale,
+                )
+            });
+        let element = match element {
+            Some(element) => element.describe(),
+            None => "none".into(),
+        };
+        let action = match &self.active_action {
+            Some(action) => action.describe(),
+            None => "none".into(),
+        };
+        let lines = [format!("element: {element}"), format!("action: {action}")];
+
+        self.context_2d.set_font("12px monospace");
+        self.context_2d.set_text_baseline("top");
+        let width = lines
+            .iter()
+            .map(|line| self.context_2d.measure_text(line).unwrap().width())
+            .fold(0.0, f64::max);
+        self.context_2d
+            .set_fill_style(&"rgb(255 255 255 / 0.8)".into());
+        self.context_2d.fill_rect(
+            0.0,
+            0.0,
+            width + (2.0 * PADDING),
+            (lines.len() as f64 * LINE_HEIGHT) + (2.0 * PADDING),
+        );
+        self.context_2d.set_fill_style(&"rgb(60 60 60)".into());
+        for (i, line) in lines.iter().enumerate() {
+            let y = PADDING + (i as f64 * LINE_HEIGHT);
+            self.context_2d.fill_text(line, PADDING, y).unwrap();
+        }
+        self.context_2d.restore();
+    }
+
     fn render_bounding_boxes(&self) {
         if self.debug.none_is_active() {
             return;
         }
 
         let axes = self.axes.borrow();
-        let ((x, y), (w, h)) = axes.viewport(self.pixel_ratio);
+        // The 2D context is already scaled by the pixel ratio.
+        let ((x, y), (w, h)) = axes.viewport(1.0);
         self.context_2d
             .stroke_rect(x as f64, y as f64, w as f64, h as f64);
 
@@ -896,7 +2289,7 @@ fn render_bounding_boxes(&self) {
         if self.color_bar.is_visible() {
             let bounding_box = self.color_bar.bounding_box();
             let x = bounding_box.start().x;
-            let y = bounding_box.end().y;
+            let y = bounding_box.start().y;
             let (w, h) = bounding_box.size().extract();
             self.context_2d
                 .stroke_rect(x as f64, y as f64, w as f64, h as f64)
@@ -904,6 +2297,19 @@ fn render_bounding_boxes(&self) {
     }
 
     async fn render(&mut self, completion: Sender<()>) {
+        // Defer all work until the canvas becomes visible again.
+        if !self.visible {
+            completion
+                .send(())
+                .await
+                .expect("the channel should be open");
+            return;
+        }
+
+        self.tick_action();
+        self.tick_axis_expansion();
+        self.tick_view();
+        self.tick_live_data();
         let (redraw, resample) = self.handle_events();
         if !redraw {
             completion
@@ -912,61 +2318,132 @@ async fn render(&mut self, completion: Sender<()>) {
                 .expect("the channel should be open");
             return;
         }
+        self.update_panic_snapshot();
+
+        // Switching the blending space or the antialiasing requires recompiling
+        // the render pipelines.
+        self.update_output_format().await;
 
+        push_validation_scope(&self.device);
         let command_encoder = self
             .device
             .create_command_encoder(webgpu::CommandEncoderDescriptor { label: None });
 
-        // Update the probability curves and probabilities.
+        // Update the probability curves and probabilities. The labels which were
+        // skipped during an interaction are resampled once it has finished.
+        let resample = resample
+            || (self.active_action.is_none()
+                && std::mem::take(&mut self.staging_data.deferred_resample));
         if resample {
             let changed = self.update_probabilities(&command_encoder);
+            for &label_idx in changed.iter() {
+                self.labels[label_idx].num_selected = None;
+            }
+            self.staging_data.group_probabilities_update |= !changed.is_empty();
+            self.staging_data.label_means_update |= !changed.is_empty();
+            self.staging_data.density_update |= !changed.is_empty();
+            self.staging_data.histogram_update |= !changed.is_empty();
             self.staging_data
                 .updated_probabilities
                 .extend(changed.into_vec().into_iter());
         };
 
+        // Count the rows of each ribbon, now that the probabilities are up to date.
+        if self.flow_ribbons {
+            self.aggregate_flows(&command_encoder);
+        }
+
+        // Estimate the densities, now that the data and probabilities are up to date.
+        if self.density_options.is_some() && std::mem::take(&mut self.staging_data.density_update) {
+            // Without data points, the curves are sampled from the histograms instead.
+            if self.axes.borrow().num_data_points() == 0 {
+                self.update_histogram_densities();
+            } else {
+                let probabilities = if let Some(active_label_idx) = self.active_label_idx {
+                    self.buffers.data().probabilities(active_label_idx).clone()
+                } else {
+                    buffers::ProbabilitiesBuffer::empty(&self.device)
+                };
+                self.pipelines.compute().density_estimation().dispatch(
+                    self.buffers.densities(),
+                    self.buffers.data().data(),
+                    &probabilities,
+                    &self.device,
+                    &command_encoder,
+                );
+            }
+        }
+
+        // Bin the data, now that the data and probabilities are up to date.
+        if self.histogram_options.is_some()
+            && std::mem::take(&mut self.staging_data.histogram_update)
+        {
+            // Without data points, the bins are resampled from the axis histograms instead.
+            if self.axes.borrow().num_data_points() == 0 {
+                self.update_axis_histogram_bins();
+            } else {
+                let probabilities = if let Some(active_label_idx) = self.active_label_idx {
+                    self.buffers.data().probabilities(active_label_idx).clone()
+                } else {
+                    buffers::ProbabilitiesBuffer::empty(&self.device)
+                };
+                self.pipelines.compute().histogram_binning().dispatch(
+                    self.buffers.histograms(),
+                    self.buffers.data().data(),
+                    &probabilities,
+                    &self.device,
+                    &command_encoder,
+                );
+            }
+        }
+
         // Draw the main view into the framebuffer.
         if self.canvas_gpu.width() != 0 && self.canvas_gpu.height() != 0 {
             let texture_view =
                 webgpu::Texture::from_raw(self.context_gpu.get_current_texture()).create_view(None);
-            let msaa_texture_view = self.render_texture.view();
-            let depth_texture_view = self.depth_texture.view();
+            self.encode_main_view(&command_encoder, texture_view);
+        }
 
-            let render_pass_descriptor = webgpu::RenderPassDescriptor {
-                label: Some("render pass".into()),
-                color_attachments: [webgpu::RenderPassColorAttachments {
-                    clear_value: Some(self.background_color.to_f32_with_alpha()),
-                    load_op: webgpu::RenderPassLoadOp::Clear,
-                    store_op: webgpu::RenderPassStoreOp::Store,
-                    resolve_target: Some(texture_view.clone()),
-                    view: msaa_texture_view.clone(),
-                }],
-                depth_stencil_attachment: Some(webgpu::RenderPassDepthStencilAttachment {
-                    view: depth_texture_view,
-                    depth_clear_value: Some(1.0),
-                    depth_load_op: Some(webgpu::RenderPassLoadOp::Clear),
-                    depth_read_only: Some(false),
-                    depth_store_op: Some(webgpu::RenderPassStoreOp::Store),
-                    stencil_clear_value: None,
-                    stencil_load_op: None,
-                    stencil_read_only: None,
-                    stencil_store_op: None,
-                }),
-                max_draw_count: None,
-            };
-            let render_pass = command_encoder.begin_render_pass(render_pass_descriptor);
+        self.device.queue().submit(&[command_encoder.finish(None)]);
+        if let Some(diagnostic) = pop_validation_scope(&self.device, "frame").await {
+            self.report_diagnostic(diagnostic);
+        }
 
-            self.render_data(&render_pass);
-            self.render_axes(&render_pass);
-            self.render_selections(&render_pass);
-            self.render_curve_segments(&render_pass);
-            self.render_curves(&render_pass);
-            self.render_color_bar(&render_pass);
+        // Fit the visible ranges, now that the probabilities are up to date.
+        if self.active_action.is_none() {
+            if let Some(axes) = self.staging_data.range_fit.take() {
+                self.fit_visible_ranges(axes).await;
+            }
+        }
 
-            render_pass.end();
+        // Capture the snapshots, now that the probabilities are up to date.
+        if self.active_action.is_none() && !self.staging_data.label_freezes.is_empty() {
+            self.freeze_labels().await;
         }
 
-        self.device.queue().submit(&[command_encoder.finish(None)]);
+        // Aggregate the probabilities of the group, now that they are up to date.
+        if self.active_action.is_none()
+            && std::mem::take(&mut self.staging_data.group_probabilities_update)
+        {
+            self.update_group_probabilities().await;
+        }
+
+        // Reduce the means of the labels, now that the probabilities are up to date.
+        if self.active_action.is_none() && std::mem::take(&mut self.staging_data.label_means_update)
+        {
+            self.update_label_means().await;
+        }
+
+        // Compute the outlier scores, now that the data buffer is up to date.
+        if self.active_action.is_none()
+            && std::mem::take(&mut self.staging_data.outlier_scores_update)
+        {
+            self.update_outlier_scores().await;
+        }
+
+        if let Some(buffer) = self.debug.log_buffer {
+            logging::debug(&self.inspect_buffer(buffer).await);
+        }
 
         // Draw the text and ui control elements.
         self.context_2d.clear_rect(
@@ -975,13 +2452,26 @@ async fn render(&mut self, completion: Sender<()>) {
             self.canvas_2d.width() as f64,
             self.canvas_2d.height() as f64,
         );
+        self.context_2d.save();
+        self.clip_to_view();
         self.render_labels();
         self.render_min_max_labels();
         self.render_ticks();
+        self.render_axis_region_labels();
+        self.render_label_means();
+        self.render_annotations();
         self.render_control_points();
+        self.render_hover_highlight();
+        self.render_active_control_point();
+        self.render_insertion_marker();
+        self.render_removal_marker();
+        self.context_2d.restore();
+        self.render_color_bar_ticks();
         self.render_color_bar_label();
+        self.render_curve_readout();
 
         self.render_bounding_boxes();
+        self.render_interaction_trace();
 
         self.notify_changes().await;
 
@@ -992,94 +2482,691 @@ async fn render(&mut self, completion: Sender<()>) {
     }
 }
 
-// Event handling
+// Main view
 impl Renderer {
-    fn handle_events(&mut self) -> (bool, bool) {
-        if self.events.is_empty() {
-            return (false, false);
-        }
-
-        let mut resample = false;
-        let events = std::mem::take(&mut self.events);
-        for events in events {
-            if events.is_empty() {
-                continue;
+    fn configure_canvas(
+        context: &web_sys::GpuCanvasContext,
+        device: &webgpu::Device,
+        output: buffers::OutputFormat,
+        alpha_mode: wasm_bridge::CanvasAlphaMode,
+    ) {
+        let alpha_mode = match alpha_mode {
+            wasm_bridge::CanvasAlphaMode::Opaque => web_sys::GpuCanvasAlphaMode::Opaque,
+            wasm_bridge::CanvasAlphaMode::Premultiplied => {
+                web_sys::GpuCanvasAlphaMode::Premultiplied
             }
-            self.handled_events.signal(events);
+        };
+        let mut configuration =
+            web_sys::GpuCanvasConfiguration::new(device.raw(), output.format.into());
+        configuration.alpha_mode(alpha_mode);
 
-            // External events.
-            if events.signaled(event::Event::RESIZE) {
-                let (width, height, device_pixel_ratio) = self.staging_data.resize.pop().unwrap();
-                self.resize_drawing_area(width, height, device_pixel_ratio);
-            }
+        // The color space of the canvas is not exposed by `web_sys`.
+        let color_space = if output.wide_gamut {
+            "display-p3"
+        } else {
+            "srgb"
+        };
+        js_sys::Reflect::set(&configuration, &"colorSpace".into(), &color_space.into()).unwrap();
+        context.configure(&configuration);
+    }
 
-            if events.signaled(event::Event::TRANSACTION_COMMIT) {
-                let transaction = self.staging_data.transactions.pop().unwrap();
-                self.handle_transaction(transaction);
+    fn set_canvas_alpha_mode(&mut self, mode: wasm_bridge::CanvasAlphaMode) {
+        self.canvas_alpha_mode = mode;
+        Self::configure_canvas(
+            &self.context_gpu,
+            &self.device,
+            self.render_texture.output(),
+            mode,
+        );
+    }
+
+    fn set_background_layer(&mut self, layer: Option<wasm_bridge::BackgroundLayer>) {
+        let Some(layer) = layer else {
+            self.background_layer = None;
+            return;
+        };
+
+        let (gradient, direction) = match &layer.source {
+            wasm_bridge::BackgroundSource::Image {
+                width,
+                height,
+                pixels,
+            } => {
+                self.buffers
+                    .background_mut()
+                    .set_image(&self.device, *width, *height, pixels);
+                (0, [0.0, 0.0])
             }
+            wasm_bridge::BackgroundSource::Gradient { scale, angle } => {
+                let color_space = scale.color_space;
+                let scale = Self::resolve_color_scale(color_space, scale.scale.clone());
+                let color_scale_elements = self.create_color_scale_elements(&scale);
+
+                let encoder =
+                    self.device
+                        .create_command_encoder(webgpu::CommandEncoderDescriptor {
+                            label: Some("background gradient command encoder".into()),
+                        });
+                self.pipelines.compute().color_scale_sampling().dispatch(
+                    color_space,
+                    self.buffers.background_mut().gradient_mut(),
+                    &color_scale_elements,
+                    &self.device,
+                    &encoder,
+                );
+                self.device.queue().submit(&[encoder.finish(None)]);
 
-            // Internal events.
-            let update_axes_buffer = events.signaled_any(&[
-                event::Event::AXIS_STATE_CHANGE,
-                event::Event::AXIS_POSITION_CHANGE,
-                event::Event::SELECTIONS_CHANGE,
-            ]);
-            if update_axes_buffer {
-                self.update_axes_buffer();
+                let angle = angle.to_radians();
+                (1, [angle.cos(), angle.sin()])
             }
+        };
 
-            let update_selection_lines_buffer = events.signaled_any(&[
-                event::Event::AXIS_STATE_CHANGE,
-                event::Event::SELECTIONS_CHANGE,
-            ]);
-            if update_selection_lines_buffer {
-                self.update_selection_lines_buffer();
+        let config = buffers::BackgroundConfig {
+            opacity: layer.opacity,
+            gradient,
+            direction: wgsl::Vec2(direction),
+        };
+        self.buffers
+            .background_mut()
+            .update_config(&self.device, &config);
+        self.background_layer = Some(layer);
+    }
+
+    /// Computes the region of the canvas covered by the background layer.
+    fn background_viewport(
+        &self,
+        layer: &wasm_bridge::BackgroundLayer,
+    ) -> ((f32, f32), (f32, f32)) {
+        let canvas_width = self.canvas_gpu.width() as f32;
+        let canvas_height = self.canvas_gpu.height() as f32;
+
+        let (width, height) = match &layer.source {
+            wasm_bridge::BackgroundSource::Image { width, height, .. }
+                if layer.placement != wasm_bridge::BackgroundPlacement::Stretch =>
+            {
+                (*width as f32, *height as f32)
             }
+            _ => return ((0.0, 0.0), (canvas_width, canvas_height)),
+        };
 
-            let update_data_lines_buffer = events.signaled_any(&[
-                event::Event::AXIS_STATE_CHANGE,
-                event::Event::AXIS_ORDER_CHANGE,
-            ]);
-            if update_data_lines_buffer {
-                self.update_data_lines_buffer();
+        // Shrink the image, such that it fits into the canvas.
+        let scale = layer.scale * self.pixel_ratio;
+        let fit = (canvas_width / (width * scale))
+            .min(canvas_height / (height * scale))
+            .min(1.0);
+        let width = (width * scale * fit).floor();
+        let height = (height * scale * fit).floor();
+
+        let free_x = canvas_width - width;
+        let free_y = canvas_height - height;
+        let start = match layer.placement {
+            wasm_bridge::BackgroundPlacement::Stretch => (0.0, 0.0),
+            wasm_bridge::BackgroundPlacement::Center => {
+                ((free_x / 2.0).floor(), (free_y / 2.0).floor())
             }
+            wasm_bridge::BackgroundPlacement::TopLeft => (0.0, 0.0),
+            wasm_bridge::BackgroundPlacement::TopRight => (free_x, 0.0),
+            wasm_bridge::BackgroundPlacement::BottomLeft => (0.0, free_y),
+            wasm_bridge::BackgroundPlacement::BottomRight => (free_x, free_y),
+        };
+        (start, (width, height))
+    }
 
-            resample |= events.signaled_any(&[
-                event::Event::TRANSACTION_COMMIT,
-                event::Event::SELECTIONS_CHANGE,
-            ]);
+    /// Returns the output format matching the current blending space and
+    /// antialiasing.
+    fn output_format(&self) -> buffers::OutputFormat {
+        let samples = if self.antialiasing.is_multisampled() {
+            buffers::RenderTexture::MSAA_SAMPLES
+        } else {
+            1
+        };
+        buffers::OutputFormat {
+            linear: self.linear_blending && !self.debug.disable_linear_blending,
+            samples,
+            analytic_antialiasing: self.antialiasing.is_analytic(),
+            ..self.render_texture.output()
         }
-
-        (true, resample)
     }
-}
 
-// Callback events
-impl Renderer {
-    async fn notify_changes(&mut self) {
-        if self.active_action.is_some() {
+    async fn update_output_format(&mut self) {
+        let output = self.output_format();
+        if output == self.render_texture.output() {
             return;
         }
 
-        let events = std::mem::take(&mut self.handled_events);
-        if events.is_empty() {
-            return;
-        }
+        self.pipelines.set_output(&self.device, output).await;
+        self.render_texture.set_output(&self.device, output);
+        self.depth_texture.set_samples(&self.device, output.samples);
+    }
 
-        let plot_diff = js_sys::Array::new();
+    fn background_clear_value(&self) -> [f32; 4] {
+        let output = self.render_texture.output();
+        let color = if output.wide_gamut {
+            // Display P3 shares the transfer function of sRGB.
+            let p3 = self
+                .background_color
+                .transform::<Xyz>()
+                .transform::<colors::DisplayP3>();
+            ColorTransparent::<SRgb>::from_f32_with_alpha(p3.to_f32_with_alpha())
+        } else {
+            self.background_color
+        };
 
-        if events.signaled(event::Event::AXIS_ORDER_CHANGE) {
-            plot_diff.push(&self.create_axis_order_diff().into());
-        }
+        let [r, g, b, a] = if output.linear {
+            color.transform::<SRgbLinear>().to_f32_with_alpha()
+        } else {
+            color.to_f32_with_alpha()
+        };
 
-        if events.signaled(event::Event::SELECTIONS_CHANGE) {
-            plot_diff.push(&self.create_brushes_diff().into());
+        // The canvas expects premultiplied colors.
+        match self.canvas_alpha_mode {
+            wasm_bridge::CanvasAlphaMode::Opaque => [r, g, b, 1.0],
+            wasm_bridge::CanvasAlphaMode::Premultiplied => [r * a, g * a, b * a, a],
         }
+    }
 
-        if events.signaled(event::Event::SELECTIONS_CHANGE) {
-            plot_diff.push(&self.create_probabilities_diff().await.into());
-            self.staging_data.updated_probabilities.clear();
-            self.staging_data.last_labels = self.labels.iter().map(|l| l.id.clone()).collect();
+    fn encode_main_view(&self, encoder: &webgpu::CommandEncoder, target: webgpu::TextureView) {
+        let depth_texture_view = self.depth_texture.view();
+
+        // With linear blending, the view is resolved into an intermediate
+        // texture, which is then encoded into the target.
+        let linear_texture_view = self.render_texture.resolve_view();
+        let resolve_target = linear_texture_view
+            .clone()
+            .unwrap_or_else(|| target.clone());
+
+        // Without multisampling, the view is rendered into the resolve target directly.
+        let (view, resolve_target) = if self.render_texture.output().is_multisampled() {
+            (self.render_texture.view(), Some(resolve_target))
+        } else {
+            (resolve_target, None)
+        };
+
+        let render_pass_descriptor = webgpu::RenderPassDescriptor {
+            label: Some("render pass".into()),
+            color_attachments: [webgpu::RenderPassColorAttachments {
+                clear_value: Some(self.background_clear_value()),
+                load_op: webgpu::RenderPassLoadOp::Clear,
+                store_op: webgpu::RenderPassStoreOp::Store,
+                resolve_target,
+                view,
+            }],
+            depth_stencil_attachment: Some(webgpu::RenderPassDepthStencilAttachment {
+                view: depth_texture_view,
+                depth_clear_value: Some(1.0),
+                depth_load_op: Some(webgpu::RenderPassLoadOp::Clear),
+                depth_read_only: Some(false),
+                depth_store_op: Some(webgpu::RenderPassStoreOp::Store),
+                stencil_clear_value: None,
+                stencil_load_op: None,
+                stencil_read_only: None,
+                stencil_store_op: None,
+            }),
+            max_draw_count: None,
+        };
+        let render_pass = encoder.begin_render_pass(render_pass_descriptor);
+
+        self.render_background(&render_pass);
+
+        // The plot area collapses when the canvas is smaller than the color
+        // bar, e.g. inside of a folded panel, so there is nothing to draw.
+        let (_, plot_viewport_size) = self.axes.borrow().viewport(self.pixel_ratio);
+        if plot_viewport_size.0 >= 1.0 && plot_viewport_size.1 >= 1.0 {
+            self.render_axis_regions(&render_pass);
+            self.render_reference_data(&render_pass);
+            self.render_flow_ribbons(&render_pass);
+            self.render_snapshots(&render_pass);
+            self.render_data(&render_pass);
+            self.render_histograms(&render_pass);
+            self.render_density_curves(&render_pass);
+            self.render_axes(&render_pass);
+            self.render_selections(&render_pass);
+            self.render_curve_segments(&render_pass);
+            self.render_curves(&render_pass);
+            self.render_curve_comparison(&render_pass);
+        }
+        self.render_color_bar(&render_pass);
+
+        render_pass.end();
+
+        if let Some(linear_texture_view) = linear_texture_view {
+            let render_pass = encoder.begin_render_pass(webgpu::RenderPassDescriptor {
+                label: Some("linear resolve render pass".into()),
+                color_attachments: [webgpu::RenderPassColorAttachments {
+                    clear_value: None,
+                    load_op: webgpu::RenderPassLoadOp::Clear,
+                    store_op: webgpu::RenderPassStoreOp::Store,
+                    resolve_target: None,
+                    view: target,
+                }],
+                depth_stencil_attachment: None,
+                max_draw_count: None,
+            });
+            self.pipelines.render().linear_resolve().render(
+                linear_texture_view,
+                &self.device,
+                &render_pass,
+            );
+            render_pass.end();
+        }
+    }
+
+    /// Reads a buffer back from the GPU and formats its entries with the
+    /// layout of the corresponding host struct.
+    async fn inspect_buffer(&self, buffer: wasm_bridge::DebugBuffer) -> String {
+        use wasm_bridge::DebugBuffer;
+
+        fn format<T: std::fmt::Debug>(buffer: DebugBuffer, entries: &[T]) -> String {
+            format!("{buffer:?} ({} entries): {entries:#?}", entries.len())
+        }
+
+        match (buffer, self.active_label_idx) {
+            (DebugBuffer::Axes, _) => {
+                let raw = self.buffers.shared().axes().buffer();
+                let entries = buffers::read_buffer::<buffers::Axis>(&self.device, raw).await;
+                format(buffer, &entries)
+            }
+            (DebugBuffer::DataLines, _) => {
+                let raw = self.buffers.data().lines().buffer();
+                let entries = buffers::read_buffer::<buffers::DataLine>(&self.device, raw).await;
+                format(buffer, &entries)
+            }
+            (DebugBuffer::Selections, Some(active_label_idx)) => {
+                let raw = self.buffers.selections().lines(active_label_idx).buffer();
+                let entries =
+                    buffers::read_buffer::<buffers::SelectionLineInfo>(&self.device, raw).await;
+                format(buffer, &entries)
+            }
+            (DebugBuffer::Probabilities, Some(active_label_idx)) => {
+                let raw = self.buffers.data().probabilities(active_label_idx).buffer();
+                let entries = buffers::read_buffer::<f32>(&self.device, raw).await;
+                format(buffer, &entries)
+            }
+            (DebugBuffer::Selections | DebugBuffer::Probabilities, None) => {
+                format!("{buffer:?}: no active label")
+            }
+        }
+    }
+
+    /// Renders the main view into an offscreen texture and reads back its pixels.
+    ///
+    /// Returns an object of the form `{ width, height, data }`, where `data`
+    /// contains the pixels in the `RGBA` format, with one byte per channel,
+    /// without the text overlay.
+    async fn capture(&self) -> js_sys::Object {
+        let width = self.canvas_gpu.width();
+        let height = self.canvas_gpu.height();
+
+        let obj = js_sys::Object::new();
+        js_sys::Reflect::set(&obj, &"width".into(), &width.into()).unwrap();
+        js_sys::Reflect::set(&obj, &"height".into(), &height.into()).unwrap();
+        if width == 0 || height == 0 {
+            let data = js_sys::Uint8ClampedArray::new_with_length(0);
+            js_sys::Reflect::set(&obj, &"data".into(), &data.into()).unwrap();
+            return obj;
+        }
+
+        let format = self.render_texture.output().format;
+        let texture = self
+            .device
+            .create_texture(webgpu::TextureDescriptor::<2, 0> {
+                label: Some(Cow::Borrowed("capture texture")),
+                dimension: Some(webgpu::TextureDimension::D2),
+                format,
+                mip_level_count: None,
+                sample_count: None,
+                size: [width as usize, height as usize],
+                usage: webgpu::TextureUsage::RENDER_ATTACHMENT | webgpu::TextureUsage::COPY_SRC,
+                view_formats: None,
+            });
+
+        // Wide-gamut canvases store the channels as half precision floats.
+        let bytes_per_pixel = match format {
+            webgpu::TextureFormat::Rgba16float => 8,
+            _ => 4,
+        };
+
+        // Rows of the copy must be aligned to 256 bytes.
+        let row_size = width * bytes_per_pixel;
+        let bytes_per_row = (row_size + 255) & !255;
+        let staging_buffer = self.device.create_buffer(webgpu::BufferDescriptor {
+            label: Some(Cow::Borrowed("capture staging buffer")),
+            size: (bytes_per_row * height) as usize,
+            usage: webgpu::BufferUsage::MAP_READ | webgpu::BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
+        let encoder = self
+            .device
+            .create_command_encoder(webgpu::CommandEncoderDescriptor { label: None });
+        self.encode_main_view(&encoder, texture.create_view(None));
+        encoder.copy_texture_to_buffer(
+            webgpu::ImageCopyTexture {
+                texture: texture.clone(),
+                aspect: None,
+                mip_level: None,
+                origin: None,
+            },
+            webgpu::ImageCopyBuffer {
+                buffer: staging_buffer.clone(),
+                layout: webgpu::ImageDataLayout {
+                    bytes_per_row: Some(bytes_per_row),
+                    ..Default::default()
+                },
+            },
+            [width, height],
+        );
+        self.device.queue().submit(&[encoder.finish(None)]);
+
+        staging_buffer.map_async(webgpu::MapMode::READ).await;
+        let mapped = js_sys::Uint8Array::new(&staging_buffer.mapped_range()).to_vec();
+        staging_buffer.unmap();
+        staging_buffer.destroy();
+
+        let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+        for row in mapped.chunks_exact(bytes_per_row as usize) {
+            for pixel in row[..row_size as usize].chunks_exact(bytes_per_pixel as usize) {
+                match format {
+                    webgpu::TextureFormat::Rgba16float => {
+                        pixels.extend(pixel.chunks_exact(2).map(|channel| {
+                            let value = f16_to_f32(u16::from_le_bytes([channel[0], channel[1]]));
+                            (value.clamp(0.0, 1.0) * 255.0).round() as u8
+                        }));
+                    }
+                    webgpu::TextureFormat::Bgra8Unorm => {
+                        pixels.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+                    }
+                    _ => pixels.extend_from_slice(pixel),
+                }
+            }
+        }
+
+        let data = js_sys::Uint8ClampedArray::from(&pixels[..]);
+        js_sys::Reflect::set(&obj, &"data".into(), &data.into()).unwrap();
+        obj
+    }
+}
+
+// Event handling
+impl Renderer {
+    /// Records the state sent with the report of a panic.
+    fn update_panic_snapshot(&self) {
+        let axes = self.axes.borrow();
+        let snapshot = api::StateSnapshot {
+            num_axes: axes.axes().count(),
+            num_visible_axes: axes.num_visible_axes(),
+            num_data_points: axes.num_data_points(),
+            labels: self.labels.iter().map(|label| label.id.clone()).collect(),
+            active_label: self
+                .active_label_idx
+                .map(|label_idx| self.labels[label_idx].id.clone()),
+            last_events: self
+                .staging_data
+                .last_events
+                .iter()
+                .map(|&name| name.into())
+                .collect(),
+        };
+        self.panic_reporter.set_snapshot(snapshot);
+    }
+
+    /// Logs the pending events and the pointer inputs received since the
+    /// last frame, together with the interaction state.
+    fn log_event_trace(&mut self) {
+        let events = self
+            .events
+            .iter()
+            .fold(event::Event::NONE, |acc, &events| acc | events);
+        let pointer = std::mem::take(&mut self.staging_data.pointer_trace);
+        if events.is_empty() && pointer.is_empty() {
+            return;
+        }
+
+        let names = events
+            .names()
+            .into_iter()
+            .map(JsValue::from)
+            .collect::<js_sys::Array>();
+        let pointer = pointer
+            .into_iter()
+            .map(|(kind, position)| {
+                let (x, y) = position.extract();
+                js_sys::Array::of3(&kind.into(), &x.into(), &y.into())
+            })
+            .collect::<js_sys::Array>();
+        let action = self.active_action.as_ref().map(|action| action.describe());
+        let hovered = self
+            .hovered_element
+            .as_ref()
+            .map(|element| element.describe());
+
+        let trace = js_sys::Object::new();
+        js_sys::Reflect::set(&trace, &"events".into(), &names).unwrap();
+        js_sys::Reflect::set(&trace, &"pointer".into(), &pointer).unwrap();
+        js_sys::Reflect::set(&trace, &"action".into(), &action.into()).unwrap();
+        js_sys::Reflect::set(&trace, &"hovered".into(), &hovered.into()).unwrap();
+        logging::log(wasm_bridge::LogLevel::Debug, "ppc frame", &trace);
+    }
+
+    fn handle_events(&mut self) -> (bool, bool) {
+        if self.debug.log_events {
+            self.log_event_trace();
+        }
+
+        if self.events.is_empty() {
+            return (false, false);
+        }
+
+        // Merge the pending events, such that each buffer is updated at most once per frame.
+        let events = std::mem::take(&mut self.events)
+            .into_iter()
+            .fold(event::Event::NONE, |acc, events| acc | events);
+        if events.is_empty() {
+            return (true, false);
+        }
+        self.handled_events.signal(events);
+
+        let last_events = &mut self.staging_data.last_events;
+        last_events.extend(events.names());
+        while last_events.len() > PANIC_SNAPSHOT_EVENTS {
+            last_events.pop_front();
+        }
+
+        // External events.
+        if events.signaled(event::Event::RESIZE) {
+            // Only the most recent size is relevant.
+            if let Some((width, height, device_pixel_ratio)) = self.staging_data.resize.take() {
+                self.resize_drawing_area(width, height, device_pixel_ratio);
+            }
+        }
+
+        if events.signaled(event::Event::TRANSACTION_COMMIT) {
+            // Apply all pending transactions in the order they were committed.
+            while let Some(transaction) = self.staging_data.transactions.pop_front() {
+                self.handle_transaction(transaction);
+            }
+        }
+
+        // Internal events.
+        let mut resample = false;
+        if events.signaled(event::Event::AXIS_VISIBILITY_CHANGE) {
+            self.update_data();
+            self.staging_data.label_means_update = true;
+            resample = true;
+        }
+
+        if events.signaled(event::Event::VIEW_CHANGE) {
+            // The widths of the lines are given in world units, which scale with the view.
+            self.update_matrix_buffer();
+            self.update_axes_config_buffer();
+            self.update_data_config_buffer();
+            self.update_curves_config_buffer();
+            self.update_selections_config_buffer();
+        }
+
+        let update_axes_buffer = events.signaled_any(&[
+            event::Event::AXIS_STATE_CHANGE,
+            event::Event::AXIS_POSITION_CHANGE,
+            event::Event::SELECTIONS_CHANGE,
+            event::Event::VIEW_CHANGE,
+        ]);
+        if update_axes_buffer {
+            self.update_axes_buffer();
+        }
+
+        let update_axis_regions_buffer = events.signaled_any(&[
+            event::Event::TRANSACTION_COMMIT,
+            event::Event::AXIS_STATE_CHANGE,
+            event::Event::AXIS_POSITION_CHANGE,
+            event::Event::AXIS_ORDER_CHANGE,
+        ]);
+        if update_axis_regions_buffer {
+            self.update_axis_regions_buffer();
+        }
+
+        let update_flow_buffers = events.signaled_any(&[
+            event::Event::TRANSACTION_COMMIT,
+            event::Event::AXIS_STATE_CHANGE,
+            event::Event::AXIS_ORDER_CHANGE,
+            event::Event::AXIS_VISIBILITY_CHANGE,
+        ]);
+        if update_flow_buffers {
+            self.update_flow_buffers();
+        }
+
+        let update_selection_lines_buffer = events.signaled_any(&[
+            event::Event::AXIS_STATE_CHANGE,
+            event::Event::SELECTIONS_CHANGE,
+        ]);
+        if update_selection_lines_buffer {
+            self.update_selection_lines_buffer();
+        }
+
+        let update_data_lines_buffer = events.signaled_any(&[
+            event::Event::AXIS_STATE_CHANGE,
+            event::Event::AXIS_ORDER_CHANGE,
+        ]);
+        if update_data_lines_buffer {
+            self.update_data_lines_buffer();
+            self.update_reference_lines_buffer();
+            self.update_snapshot_lines_buffers();
+        }
+
+        resample |= events.signaled_any(&[
+            event::Event::TRANSACTION_COMMIT,
+            event::Event::SELECTIONS_CHANGE,
+        ]);
+
+        (true, resample)
+    }
+}
+
+// Callback events
+impl Renderer {
+    async fn notify_changes(&mut self) {
+        if self.active_action.is_some() {
+            return;
+        }
+
+        let events = std::mem::take(&mut self.handled_events);
+        let logs = logging::take_mirrored();
+        if events.is_empty() && logs.is_empty() {
+            return;
+        }
+
+        let plot_diff = js_sys::Array::new();
+
+        if events.signaled(event::Event::AXIS_ORDER_CHANGE) {
+            plot_diff.push(&self.create_axis_order_diff().to_js());
+            if let Some(diff) = self.create_axis_order_operation_diff() {
+                plot_diff.push(&diff.to_js());
+            }
+        }
+
+        if events.signaled(event::Event::AXIS_VISIBILITY_CHANGE) {
+            plot_diff.push(&self.create_hidden_axes_diff().to_js());
+        }
+
+        if events.signaled(event::Event::LABEL_VISIBILITY_CHANGE) {
+            plot_diff.push(&self.create_hidden_labels_diff().to_js());
+        }
+
+        if events.signaled(event::Event::AXIS_RANGE_EDIT_REQUEST) {
+            let request = self.staging_data.range_edit_request.take();
+            if let Some(diff) = request.and_then(|axis| self.create_axis_range_edit_diff(&axis)) {
+                plot_diff.push(&diff.to_js());
+            }
+        }
+
+        if events.signaled(event::Event::SELECTIONS_CHANGE) {
+            self.record_label_history();
+            let modifiers = self.staging_data.action_modifiers.take();
+            plot_diff.push(&self.create_brushes_diff(modifiers).to_js());
+            for diff in self.create_brush_operation_diffs() {
+                plot_diff.push(&diff.to_js());
+            }
+        }
+
+        if events.signaled(event::Event::SNAPSHOTS_CHANGE) {
+            plot_diff.push(&self.create_snapshots_diff().to_js());
+        }
+
+        if events.signaled(event::Event::SELECTIONS_CHANGE) {
+            plot_diff.push(&self.create_probabilities_diff().await.to_js());
+            self.staging_data.updated_probabilities.clear();
+            self.staging_data.last_labels = self.labels.iter().map(|l| l.id.clone()).collect();
+        }
+
+        // The selected counts may change without a selection change, e.g.,
+        // when the data is updated.
+        let counts_changed = self.refresh_selected_counts().await;
+        let description_changed = counts_changed
+            || events.signaled_any(&[
+                event::Event::TRANSACTION_COMMIT,
+                event::Event::AXIS_STATE_CHANGE,
+                event::Event::AXIS_ORDER_CHANGE,
+                event::Event::AXIS_VISIBILITY_CHANGE,
+                event::Event::SELECTIONS_CHANGE,
+            ]);
+        if description_changed {
+            let description = self.create_description();
+            if description.text != self.staging_data.last_description {
+                self.staging_data.last_description = description.text.clone();
+                plot_diff.push(&api::PlotDiff::Description { value: description }.to_js());
+            }
+        }
+
+        let layout_changed = events.signaled_any(&[
+            event::Event::RESIZE,
+            event::Event::TRANSACTION_COMMIT,
+            event::Event::AXIS_STATE_CHANGE,
+            event::Event::AXIS_POSITION_CHANGE,
+            event::Event::AXIS_ORDER_CHANGE,
+            event::Event::AXIS_VISIBILITY_CHANGE,
+            event::Event::VIEW_CHANGE,
+        ]);
+        if layout_changed {
+            let layout = self.create_layout();
+            if self.staging_data.last_layout.as_ref() != Some(&layout) {
+                self.staging_data.last_layout = Some(layout.clone());
+                plot_diff.push(&api::PlotDiff::Layout { value: layout }.to_js());
+            }
+        }
+
+        if events.signaled(event::Event::HEADER_POINTER) {
+            for value in std::mem::take(&mut self.staging_data.header_pointer_events) {
+                plot_diff.push(&api::PlotDiff::HeaderPointer { value }.to_js());
+            }
+        }
+
+        if events.signaled(event::Event::DIAGNOSTIC) {
+            for diagnostic in std::mem::take(&mut self.staging_data.diagnostics) {
+                plot_diff.push(&diagnostic.into_diff().to_js());
+            }
+        }
+
+        for diff in logs {
+            plot_diff.push(&diff.to_js());
         }
 
         if plot_diff.length() != 0 {
@@ -1088,87 +3175,559 @@ async fn notify_changes(&mut self) {
         }
     }
 
-    fn create_axis_order_diff(&self) -> js_sys::Object {
+    fn create_layout(&self) -> api::LayoutValue {
         let guard = self.axes.borrow();
-        let order = js_sys::Array::new();
-        for ax in guard.visible_axes() {
-            order.push(&(*ax.key()).into());
+        let screen_mapper = guard.space_transformer();
+
+        let axes = guard
+            .visible_axes()
+            .map(|ax| {
+                let world_mapper = ax.space_transformer();
+                let (line_start, line_end) = ax.axis_line_range();
+                let ticks = ax
+                    .ticks()
+                    .iter()
+                    .map(|(t, tick)| {
+                        let position = line_start.lerp(line_end, *t);
+                        let position = position.transform(&world_mapper);
+                        let position = position.transform(&screen_mapper);
+                        api::TickLayout {
+                            label: tick.to_string(),
+                            y: position.y,
+                        }
+                    })
+                    .collect();
+
+                let line_start = line_start.transform(&world_mapper);
+                let line_start = line_start.transform(&screen_mapper);
+                let line_end = line_end.transform(&world_mapper);
+                let line_end = line_end.transform(&screen_mapper);
+
+                let label_box = ax
+                    .label_bounding_box()
+                    .transform(&world_mapper)
+                    .transform(&screen_mapper);
+                let (width, height) = label_box.size().extract();
+                let header_box = self.header_box(&ax);
+
+                api::AxisLayout {
+                    key: ax.key().to_string(),
+                    x: line_start.x,
+                    line_range: (line_start.y, line_end.y),
+                    ticks,
+                    label_box: api::LayoutBox {
+                        x: label_box.start().x,
+                        y: label_box.end().y,
+                        width,
+                        height,
+                    },
+                    header_box,
+                }
+            })
+            .collect();
+
+        api::LayoutValue { axes }
+    }
+
+    /// Returns the screen rectangle of the header slot of an axis.
+    fn header_box(&self, axis: &axis::Axis) -> Option<api::LayoutBox> {
+        let guard = self.axes.borrow();
+        let bounding_box = axis
+            .header_bounding_box()?
+            .transform(&axis.space_transformer())
+            .transform(&guard.space_transformer());
+        let (width, height) = bounding_box.size().extract();
+
+        Some(api::LayoutBox {
+            x: bounding_box.start().x,
+            y: bounding_box.end().y,
+            width,
+            height,
+        })
+    }
+
+    fn create_axis_order_diff(&self) -> api::PlotDiff {
+        let guard = self.axes.borrow();
+        let order = guard
+            .visible_axes()
+            .map(|ax| ax.key().to_string())
+            .collect();
+        api::PlotDiff::AxisOrder { value: order }
+    }
+
+    fn create_hidden_axes_diff(&self) -> api::PlotDiff {
+        let guard = self.axes.borrow();
+        let hidden = guard
+            .axes()
+            .filter(|ax| ax.is_hidden())
+            .map(|ax| ax.key().to_string())
+            .collect();
+        api::PlotDiff::HiddenAxes { value: hidden }
+    }
+
+    fn create_hidden_labels_diff(&self) -> api::PlotDiff {
+        let hidden = self
+            .labels
+            .iter()
+            .filter(|l| !l.visible)
+            .map(|l| l.id.clone())
+            .collect();
+        api::PlotDiff::HiddenLabels { value: hidden }
+    }
+
+    fn create_axis_range_edit_diff(&self, axis: &str) -> Option<api::PlotDiff> {
+        let guard = self.axes.borrow();
+        let axis = guard.axis(axis)?;
+        Some(api::PlotDiff::AxisRangeEditRequested {
+            axis: axis.key().to_string(),
+            range: axis.data_range(),
+            visible_range: axis.visible_data_range(),
+        })
+    }
+
+    fn create_brushes_diff(&self, modifiers: Option<api::PointerModifiers>) -> api::PlotDiff {
+        api::PlotDiff::Brushes {
+            value: self.current_brushes(),
+            modifiers,
+        }
+    }
+
+    /// Reports the local changes of the brushes as operations, if the
+    /// operation sync is enabled.
+    fn create_brush_operation_diffs(&mut self) -> Vec<api::PlotDiff> {
+        if self.sync.is_none() {
+            return Vec::new();
+        }
+
+        let brushes = self.current_brushes();
+        let sync = self
+            .sync
+            .as_mut()
+            .expect("operation sync should be enabled");
+        let guard = self.axes.borrow();
+        let labels = &self.labels;
+        let exists = |label: &str, axis: &str| {
+            labels.iter().any(|l| l.id == label) && guard.axis(axis).is_some()
+        };
+
+        sync.diff_brushes(&brushes, exists)
+            .into_iter()
+            .map(|value| api::PlotDiff::Operation { value })
+            .collect()
+    }
+
+    /// Reports a local change of the axis order as an operation, if the
+    /// operation sync is enabled.
+    fn create_axis_order_operation_diff(&mut self) -> Option<api::PlotDiff> {
+        let sync = self.sync.as_mut()?;
+        let guard = self.axes.borrow();
+        let order = guard
+            .visible_axes()
+            .map(|ax| ax.key().to_string())
+            .collect();
+        sync.diff_axis_order(order)
+            .map(|value| api::PlotDiff::Operation { value })
+    }
+
+    /// Returns the brushes of all labels.
+    fn current_brushes(&self) -> api::BrushesSpec {
+        let mut brushes = api::BrushesSpec::new();
+        for (label_idx, label) in self.labels.iter().enumerate() {
+            let label_brushes = self.label_brushes(label_idx);
+            if !label_brushes.is_empty() {
+                brushes.insert(label.id.clone(), label_brushes);
+            }
         }
+        brushes
+    }
+
+    /// Returns the brushes of a label, keyed by the axes.
+    fn label_brushes(&self, label_idx: usize) -> BTreeMap<String, Vec<api::BrushSpec>> {
+        let mut label_brushes = BTreeMap::new();
+
+        let guard = self.axes.borrow();
+        for ax in guard.axes() {
+            let mut axis_brushes = Vec::new();
+
+            let (data_start, data_end) = ax.data_range();
+            let curve = ax.borrow_selection_curve_builder(label_idx);
+            for selection in curve.selections() {
+                let control_points = selection
+                    .control_points()
+                    .iter()
+                    .map(|&(x, y)| (data_start.lerp(data_end, x), y))
+                    .collect::<Vec<_>>();
+
+                if !control_points.is_empty() {
+                    axis_brushes.push(api::BrushSpec {
+                        control_points,
+                        main_segment_idx: selection.primary_segment_idx(),
+                    });
+                }
+            }
+
+            if !axis_brushes.is_empty() {
+                label_brushes.insert(ax.key().to_string(), axis_brushes);
+            }
+        }
+
+        label_brushes
+    }
+
+    /// Appends the brushes of each label to its edit history, if they changed
+    /// since the last step.
+    fn record_label_history(&mut self) {
+        let timestamp = js_sys::Date::now();
+        for label_idx in 0..self.labels.len() {
+            let brushes = self.label_brushes(label_idx);
+            let history = &mut self.labels[label_idx].history;
+            let step = match history.last() {
+                Some(last) if last.brushes == brushes => continue,
+                Some(last) => last.step + 1,
+                None => 0,
+            };
+
+            history.push(api::LabelHistoryStep {
+                step,
+                timestamp,
+                brushes,
+            });
+            if history.len() > MAX_LABEL_HISTORY_STEPS {
+                history.remove(0);
+            }
+        }
+    }
+
+    fn create_label_history(&self, label: &str) -> api::LabelHistoryValue {
+        let steps = self
+            .labels
+            .iter()
+            .find(|l| l.id == label)
+            .map(|l| l.history.clone())
+            .unwrap_or_default();
+
+        api::LabelHistoryValue {
+            label: label.to_string(),
+            steps,
+        }
+    }
+
+    fn report_diagnostic(&mut self, diagnostic: diagnostics::Diagnostic) {
+        if diagnostic.is_error() {
+            logging::error(&diagnostic.to_string());
+        } else {
+            logging::warn(&diagnostic.to_string());
+        }
+        self.staging_data.diagnostics.push(diagnostic);
+        self.handled_events.signal(event::Event::DIAGNOSTIC);
+    }
+
+    /// Counts the selected data points of the labels whose probabilities
+    /// changed since they were last counted.
+    ///
+    /// Returns whether any label was recounted.
+    async fn refresh_selected_counts(&mut self) -> bool {
+        let mut refreshed = false;
+        for label_idx in 0..self.labels.len() {
+            if self.labels[label_idx].num_selected.is_none() {
+                let (_, attribution) = self
+                    .extract_label_attribution_and_probability(label_idx)
+                    .await;
+                self.labels[label_idx].num_selected = Some(attribution.len());
+                refreshed = true;
+            }
+        }
+        refreshed
+    }
+
+    fn create_description(&self) -> api::DescriptionValue {
+        let guard = self.axes.borrow();
+
+        let mut text = String::new();
+        let visible = guard.visible_axes().collect::<Vec<_>>();
+        text.push_str(&format!(
+            "Parallel coordinates plot with {} data points and {} visible axes",
+            guard.num_data_points(),
+            visible.len()
+        ));
+
+        let mut axes = Vec::new();
+        for (i, ax) in visible.iter().enumerate() {
+            let (min, max) = ax.visible_data_range();
+            text.push_str(if i == 0 { ": " } else { ", " });
+            text.push_str(&format!(
+                "{} (from {} to {}",
+                ax.label(),
+                ax.min_label(),
+                ax.max_label()
+            ));
+            if ax.is_expanded() {
+                text.push_str(", expanded");
+            }
+            text.push(')');
+
+            axes.push(api::AxisDescription {
+                key: ax.key().to_string(),
+                label: ax.label().to_string(),
+                min,
+                max,
+                expanded: ax.is_expanded(),
+            });
+        }
+        text.push('.');
+
+        let hidden = guard
+            .axes()
+            .filter(|ax| ax.is_hidden())
+            .map(|ax| ax.label())
+            .collect::<Vec<_>>();
+        if !hidden.is_empty() {
+            text.push_str(&format!(" Hidden axes: {}.", hidden.join(", ")));
+        }
+
+        let mut labels = Vec::new();
+        for (label_idx, label) in self.labels.iter().enumerate() {
+            let num_selections = visible
+                .iter()
+                .map(|ax| {
+                    ax.borrow_selection_curve_builder(label_idx)
+                        .selections()
+                        .len()
+                })
+                .sum::<usize>();
+            text.push_str(&format!(
+                " Label {}: {} selections, {} of {} data points selected.",
+                label.id,
+                num_selections,
+                label.num_selected.unwrap_or(0),
+                guard.num_data_points()
+            ));
+
+            labels.push(api::LabelDescription {
+                id: label.id.clone(),
+                num_selections,
+                num_selected: label.num_selected.unwrap_or(0),
+            });
+        }
+
+        let mut annotations = Vec::new();
+        for (id, annotation) in &self.annotations {
+            text.push_str(&format!(
+                " Annotation {} on axis {}: {}.",
+                id,
+                annotation.anchor.axis(),
+                annotation.text
+            ));
+
+            annotations.push(api::AnnotationDescription {
+                id: id.clone(),
+                annotation: annotation.into(),
+            });
+        }
+
+        api::DescriptionValue {
+            text,
+            axes,
+            labels,
+            annotations,
+        }
+    }
+
+    fn create_plot_state(&self) -> api::PlotStateValue {
+        let guard = self.axes.borrow();
+        let axes = guard
+            .axes()
+            .map(|ax| {
+                let state = api::AxisStateValue {
+                    label: ax.label().to_string(),
+                    range: ax.data_range(),
+                    visible_range: ax.visible_data_range(),
+                    hidden: ax.is_hidden(),
+                };
+                (ax.key().to_string(), state)
+            })
+            .collect();
+        let order = guard
+            .visible_axes()
+            .map(|ax| ax.key().to_string())
+            .collect();
+        drop(guard);
+
+        let labels = self
+            .labels
+            .iter()
+            .map(|label| {
+                let (color, _) = label.colors(&self.label_groups);
+                let state = api::LabelStateValue {
+                    color: color.to_f32(),
+                    group: label.group.clone(),
+                    visible: label.visible,
+                    selection_bounds: label.selection_bounds,
+                };
+                (label.id.clone(), state)
+            })
+            .collect();
+
+        let (mode, constant, attribute) = match &self.data_color_mode {
+            wasm_bridge::DataColorMode::Constant(x) => ("constant", Some(*x), None),
+            wasm_bridge::DataColorMode::Attribute(x) => ("attribute", None, Some(x.clone())),
+            wasm_bridge::DataColorMode::AttributeDensity(x) => {
+                ("attribute_density", None, Some(x.clone()))
+            }
+            wasm_bridge::DataColorMode::Probability => ("probability", None, None),
+            wasm_bridge::DataColorMode::GroupProbability(x) => {
+                ("group_probability", None, Some(x.clone()))
+            }
+        };
+        let coloring = api::ColoringStateValue {
+            mode: mode.to_string(),
+            constant,
+            attribute,
+            background: self.background_color.to_f32_with_alpha(),
+            brush: self.brush_color.to_f32(),
+            unselected: self.unselected_color.to_f32_with_alpha(),
+        };
+
+        api::PlotStateValue {
+            axes,
+            order,
+            brushes: self.current_brushes(),
+            labels,
+            coloring,
+        }
+    }
+
+    /// Returns the values of the data points on all axes, or `None` for the
+    /// indices outside of the data.
+    fn query_data(&self, indices: &[usize]) -> Vec<Option<api::DatumValue>> {
+        let guard = self.axes.borrow();
+        let num_data_points = guard.num_data_points();
+        indices
+            .iter()
+            .map(|&index| {
+                if index >= num_data_points {
+                    return None;
+                }
+
+                let values = guard
+                    .axes()
+                    .map(|ax| {
+                        let value = ax.data()[index];
+                        (ax.key().to_string(), (!value.is_nan()).then_some(value))
+                    })
+                    .collect();
+                Some(values)
+            })
+            .collect()
+    }
+
+    /// Returns the indices of all data points, sorted by the probability of
+    /// the active label in descending order.
+    async fn sort_by_probability(&self) -> Box<[u64]> {
+        let Some(label_idx) = self.active_label_idx else {
+            return Box::new([]);
+        };
+
+        let num_data_points = self.axes.borrow().num_data_points();
+        if num_data_points == 0 {
+            return Box::new([]);
+        }
+
+        let sort = buffers::SortBuffer::new(&self.device, num_data_points);
+        let encoder = self
+            .device
+            .create_command_encoder(webgpu::CommandEncoderDescriptor { label: None });
+        self.pipelines.compute().sort().dispatch(
+            &sort,
+            self.buffers.data().probabilities(label_idx).buffer(),
+            true,
+            &self.device,
+            &encoder,
+        );
+        sort.copy_for_readback(&encoder);
+        self.device.queue().submit(&[encoder.finish(None)]);
 
-        let obj = js_sys::Object::new();
-        js_sys::Reflect::set(&obj, &"type".into(), &"axis_order".into()).unwrap();
-        js_sys::Reflect::set(&obj, &"value".into(), &order.into()).unwrap();
-        obj
+        sort.read()
+            .await
+            .iter()
+            .map(|pair| pair.value as u64)
+            .collect()
     }
 
-    fn create_brushes_diff(&self) -> js_sys::Object {
-        let brushes = js_sys::Object::new();
-
+    fn create_curves_export(&self) -> api::CurvesValue {
         let guard = self.axes.borrow();
-        for (label_idx, label) in self.labels.iter().enumerate() {
-            let label_brushes = js_sys::Object::new();
-            for ax in guard.axes() {
-                let brushes = js_sys::Array::new();
 
-                let (data_start, data_end) = ax.data_range();
-                let curve = ax.borrow_selection_curve_builder(label_idx);
-                for selection in curve.selections() {
-                    let control_points = js_sys::Array::new();
-
-                    let main_segment_idx = selection.primary_segment_idx();
-                    for &(x, y) in selection.control_points() {
-                        let x = data_start.lerp(data_end, x);
-                        let control_point = js_sys::Array::from_iter([
-                            &wasm_bindgen::JsValue::from(x),
-                            &wasm_bindgen::JsValue::from(y),
-                        ]);
-                        control_points.push(&control_point.into());
-                    }
+        let labels = self
+            .labels
+            .iter()
+            .enumerate()
+            .map(|(label_idx, label)| {
+                let axes = guard
+                    .visible_axes()
+                    .map(|ax| {
+                        let curve = ax.borrow_selection_curve(label_idx);
+                        let segments = curve
+                            .spline()
+                            .segments()
+                            .iter()
+                            .map(|s| api::CurveSegmentDescription {
+                                bounds: s.bounds.into(),
+                                t_range: s.t_range.into(),
+                                coefficients: s.coefficients,
+                            })
+                            .collect();
+
+                        api::AxisCurveDescription {
+                            key: ax.key().to_string(),
+                            data_range: ax.data_range(),
+                            segments,
+                        }
+                    })
+                    .collect();
 
-                    if control_points.length() != 0 {
-                        let brush = js_sys::Object::new();
-                        js_sys::Reflect::set(
-                            &brush,
-                            &"controlPoints".into(),
-                            &control_points.into(),
-                        )
-                        .unwrap();
-                        js_sys::Reflect::set(
-                            &brush,
-                            &"mainSegmentIdx".into(),
-                            &main_segment_idx.into(),
-                        )
-                        .unwrap();
-                        brushes.push(&brush.into());
-                    }
+                api::LabelCurvesDescription {
+                    id: label.id.clone(),
+                    axes,
                 }
+            })
+            .collect();
 
-                if brushes.length() != 0 {
-                    js_sys::Reflect::set(&label_brushes, &(*ax.key()).into(), &brushes.into())
-                        .unwrap();
-                }
-            }
+        api::CurvesValue { labels }
+    }
+
+    fn create_snapshots_diff(&mut self) -> api::PlotDiff {
+        let indices_diff = js_sys::Object::new();
+        let mut removals = Vec::new();
 
-            if js_sys::Object::entries(&label_brushes).length() != 0 {
-                js_sys::Reflect::set(&brushes, &(*label.id).into(), &label_brushes.into()).unwrap();
+        for id in std::mem::take(&mut self.staging_data.updated_snapshots) {
+            let label = self.labels.iter().find(|l| l.id == id);
+            if let Some(snapshot) = label.and_then(|l| l.snapshot.as_ref()) {
+                let indices = js_sys::BigUint64Array::from(&**snapshot);
+                js_sys::Reflect::set(&indices_diff, &id.into(), &indices.into()).unwrap();
+            } else {
+                removals.push(id);
             }
         }
 
-        let obj = js_sys::Object::new();
-        js_sys::Reflect::set(&obj, &"type".into(), &"brushes".into()).unwrap();
-        js_sys::Reflect::set(&obj, &"value".into(), &brushes.into()).unwrap();
-        obj
+        api::PlotDiff::Snapshots {
+            value: api::SnapshotsValue {
+                indices: indices_diff,
+                removals,
+            },
+        }
     }
 
-    async fn create_probabilities_diff(&self) -> js_sys::Object {
+    async fn create_probabilities_diff(&mut self) -> api::PlotDiff {
         let prob_diff = js_sys::Object::new();
         let indices_diff = js_sys::Object::new();
-        let removals = js_sys::Array::new();
+        let mut removals = Vec::new();
 
-        for &changed_label in &self.staging_data.updated_probabilities {
+        let updated_probabilities = Vec::from_iter(self.staging_data.updated_probabilities.clone());
+        for changed_label in updated_probabilities {
             let (prob, attr) = self
                 .extract_label_attribution_and_probability(changed_label)
                 .await;
+            self.labels[changed_label].num_selected = Some(attr.len());
 
             let prob = js_sys::Float32Array::from(&*prob);
             let attr = js_sys::BigUint64Array::from(&*attr);
@@ -1180,19 +3739,86 @@ async fn create_probabilities_diff(&self) -> js_sys::Object {
 
         for label in &self.staging_data.last_labels {
             if !self.labels.iter().any(|l| &l.id == label) {
-                removals.push(&label.into());
+                removals.push(label.clone());
             }
         }
 
-        let diff = js_sys::Object::new();
-        js_sys::Reflect::set(&diff, &"probabilities".into(), &prob_diff.into()).unwrap();
-        js_sys::Reflect::set(&diff, &"indices".into(), &indices_diff.into()).unwrap();
-        js_sys::Reflect::set(&diff, &"removals".into(), &removals.into()).unwrap();
+        api::PlotDiff::Probabilities {
+            value: api::ProbabilitiesValue {
+                probabilities: prob_diff,
+                indices: indices_diff,
+                removals,
+                agreements: self.compute_label_agreements().await,
+            },
+        }
+    }
 
-        let obj = js_sys::Object::new();
-        js_sys::Reflect::set(&obj, &"type".into(), &"probabilities".into()).unwrap();
-        js_sys::Reflect::set(&obj, &"value".into(), &diff.into()).unwrap();
-        obj
+    async fn compute_label_agreements(&self) -> Option<Vec<api::LabelAgreement>> {
+        let num_data_points = self.axes.borrow().num_data_points();
+        if !self.label_agreement_metrics || self.labels.len() < 2 || num_data_points == 0 {
+            return None;
+        }
+
+        // Reduce the sums of all pairs of labels in a single submission.
+        let num_workgroups = preprocessor::num_workgroups(self.device.limits(), num_data_points);
+        let encoder = self
+            .device
+            .create_command_encoder(webgpu::CommandEncoderDescriptor { label: None });
+        let mut pairs = Vec::new();
+        for a in 0..self.labels.len() {
+            for b in a + 1..self.labels.len() {
+                let config = buffers::LabelAgreementConfig {
+                    selection_bounds_a: wgsl::Vec2(self.labels[a].selection_bounds.into()),
+                    selection_bounds_b: wgsl::Vec2(self.labels[b].selection_bounds.into()),
+                    num_datums: num_data_points as u32,
+                };
+                let agreement =
+                    buffers::LabelAgreementBuffer::new(&self.device, &config, num_workgroups);
+                self.pipelines
+                    .compute()
+                    .label_agreement_reduction()
+                    .dispatch(
+                        &agreement,
+                        (
+                            self.buffers.data().probabilities(a),
+                            self.buffers.data().probabilities(b),
+                        ),
+                        &self.device,
+                        &encoder,
+                    );
+                agreement.copy_for_readback(&encoder);
+                pairs.push((a, b, agreement));
+            }
+        }
+        self.device.queue().submit(&[encoder.finish(None)]);
+
+        let n = num_data_points as f64;
+        let mut agreements = Vec::with_capacity(pairs.len());
+        for (a, b, agreement) in pairs {
+            let sums = agreement.read().await;
+
+            let jaccard = if sums.num_union > 0.0 {
+                sums.num_intersection / sums.num_union
+            } else {
+                1.0
+            };
+
+            let covariance = n * sums.sum_ab - sums.sum_a * sums.sum_b;
+            let variance_a = n * sums.sum_aa - sums.sum_a * sums.sum_a;
+            let variance_b = n * sums.sum_bb - sums.sum_b * sums.sum_b;
+            let correlation = (variance_a > 0.0 && variance_b > 0.0).then(|| {
+                let correlation = covariance / (variance_a * variance_b).sqrt();
+                correlation.clamp(-1.0, 1.0) as f32
+            });
+
+            agreements.push(api::LabelAgreement {
+                labels: (self.labels[a].id.clone(), self.labels[b].id.clone()),
+                jaccard: jaccard as f32,
+                correlation,
+            });
+        }
+
+        Some(agreements)
     }
 }
 
@@ -1213,20 +3839,232 @@ fn add_axis(&mut self, axis: wasm_bridge::AxisDef) {
             axis.range,
             axis.visible_range,
             axis.ticks,
+            axis.histogram,
             self.labels.len(),
         );
     }
 
+    /// Buffers the points appended by a transaction. The axes are padded
+    /// with missing values, such that each transaction appends whole rows.
+    fn stage_appended_points(&mut self, appends: BTreeMap<String, Vec<f32>>) {
+        if appends.values().all(|points| points.is_empty()) {
+            return;
+        }
+
+        // Queued points beyond the maximum of the live mode would be evicted
+        // immediately, e.g. while the data is frozen.
+        let max_points = self.live_options.and_then(|options| options.max_points);
+        let staging_data = &mut self.staging_data;
+        staging_data.num_appended_points = queue_appended_rows(
+            &mut staging_data.appended_points,
+            staging_data.num_appended_points,
+            appends,
+            max_points,
+        );
+        self.schedule_live_update();
+    }
+
+    /// Appends the buffered points to the axes, evicting the oldest data
+    /// points beyond the maximum of the live mode. The brushes are retained.
+    ///
+    /// Returns whether the data of the axes changed.
+    fn apply_appended_points(&mut self) -> bool {
+        let num_rows = std::mem::take(&mut self.staging_data.num_appended_points);
+        let mut appended = std::mem::take(&mut self.staging_data.appended_points);
+        if num_rows == 0 {
+            return false;
+        }
+
+        let mut guard = self.axes.borrow_mut();
+        if guard.axes().next().is_none() {
+            return false;
+        }
+
+        let max_points = self
+            .live_options
+            .and_then(|options| options.max_points)
+            .unwrap_or(usize::MAX);
+        let num_evicted = (guard.num_data_points() + num_rows).saturating_sub(max_points);
+
+        let data = guard
+            .axes()
+            .map(|ax| {
+                let rows = appended.remove(&*ax.key()).unwrap_or_default();
+                let points = append_rows(&ax.data(), rows, num_rows, num_evicted);
+                (ax.key(), points)
+            })
+            .collect::<Vec<_>>();
+        guard.replace_data(data);
+        drop(guard);
+
+        // The snapshots reference the data points by their index.
+        if num_evicted != 0 {
+            for label in &mut self.labels {
+                let Some(snapshot) = &label.snapshot else {
+                    continue;
+                };
+                label.snapshot = Some(shift_snapshot(snapshot, num_evicted));
+                self.staging_data.updated_snapshots.insert(label.id.clone());
+            }
+        }
+
+        true
+    }
+
+    /// Replaces the synthetic axis containing the cluster of each data point.
+    fn set_clusters(&mut self, options: Option<wasm_bridge::ClusterOptions>) {
+        if self.axes.borrow().axis(CLUSTER_AXIS_KEY).is_some() {
+            self.remove_axis(CLUSTER_AXIS_KEY.into());
+        }
+
+        let Some(options) = options else {
+            let colored_by_clusters = matches!(
+                &self.data_color_mode,
+                wasm_bridge::DataColorMode::Attribute(key) if key == CLUSTER_AXIS_KEY
+            );
+            if colored_by_clusters {
+                self.set_data_color_mode(DEFAULT_DATA_COLOR_MODE());
+            }
+            return;
+        };
+
+        // The clusters are computed from the values of the visible axes,
+        // stored row by row.
+        let guard = self.axes.borrow();
+        let num_data_points = guard.num_data_points();
+        let axes = guard.visible_axes().collect::<Vec<_>>();
+        let dims = axes.len();
+        if dims == 0 {
+            return;
+        }
+
+        let mut points = vec![f32::NAN; num_data_points * dims];
+        for (dim, ax) in axes.iter().enumerate() {
+            let values = ax.data_normalized();
+            for (point, &value) in points.chunks_exact_mut(dims).zip(values.iter()) {
+                point[dim] = value;
+            }
+        }
+        drop(axes);
+        drop(guard);
+
+        let clusters = cluster::k_means(&points, dims, options.k, options.max_iterations);
+        let ticks = (0..options.k)
+            .map(|cluster| (cluster as f32, Some(Rc::from((cluster + 1).to_string()))))
+            .collect();
+        self.add_axis(wasm_bridge::AxisDef {
+            key: CLUSTER_AXIS_KEY.into(),
+            label: "Cluster".into(),
+            points: clusters.iter().map(|&cluster| cluster as f32).collect(),
+            range: Some((-0.5, options.k as f32 - 0.5)),
+            visible_range: None,
+            ticks: Some(ticks),
+            histogram: None,
+        });
+    }
+
+    /// Replaces the synthetic axis containing the outlier score of each data
+    /// point. Until the scores are computed on the GPU, all data points have
+    /// a score of `0`.
+    fn set_outlier_scores(&mut self, options: Option<wasm_bridge::OutlierOptions>) {
+        if self.axes.borrow().axis(OUTLIER_AXIS_KEY).is_some() {
+            self.remove_axis(OUTLIER_AXIS_KEY.into());
+        }
+
+        self.outlier_options = options;
+        if self.outlier_options.is_none() {
+            let colored_by_scores = matches!(
+                &self.data_color_mode,
+                wasm_bridge::DataColorMode::Attribute(key) if key == OUTLIER_AXIS_KEY
+            );
+            if colored_by_scores {
+                self.set_data_color_mode(DEFAULT_DATA_COLOR_MODE());
+            }
+            return;
+        }
+
+        let num_data_points = self.axes.borrow().num_data_points();
+        self.add_outlier_axis(vec![0.0; num_data_points].into());
+        self.staging_data.outlier_scores_update = true;
+    }
+
+    fn add_outlier_axis(&mut self, scores: Box<[f32]>) {
+        self.add_axis(wasm_bridge::AxisDef {
+            key: OUTLIER_AXIS_KEY.into(),
+            label: "Outlier score".into(),
+            points: scores,
+            range: Some(OUTLIER_AXIS_RANGE),
+            visible_range: None,
+            ticks: None,
+            histogram: None,
+        });
+    }
+
+    /// Creates the label of the outliers, if it doesn't exist yet, and selects
+    /// the data points whose score reaches the threshold.
+    fn add_outlier_label(&mut self) {
+        let Some(wasm_bridge::OutlierOptions {
+            label: Some(label), ..
+        }) = &self.outlier_options
+        else {
+            return;
+        };
+
+        if !self.labels.iter().any(|l| l.id == *label) {
+            self.add_label(label.clone(), None, None, selection::EasingType::Linear);
+        }
+        self.import_outlier_curve();
+    }
+
+    /// Imports a step curve on the axis of the outlier scores into the label
+    /// of the outliers. The label may have been removed in the meantime.
+    fn import_outlier_curve(&mut self) {
+        let Some(wasm_bridge::OutlierOptions {
+            threshold,
+            label: Some(label),
+        }) = &self.outlier_options
+        else {
+            return;
+        };
+        let Some(label_idx) = self.labels.iter().position(|l| l.id == *label) else {
+            return;
+        };
+        let guard = self.axes.borrow();
+        let Some(axis) = guard.axis(OUTLIER_AXIS_KEY) else {
+            return;
+        };
+
+        let threshold = threshold.inv_lerp(OUTLIER_AXIS_RANGE.0, OUTLIER_AXIS_RANGE.1);
+        let mut spline = spline::Spline::new([0.0, 1.0]);
+        spline.insert_segment(spline::SplineSegment::new_constant(
+            1.0,
+            [threshold, 1.0],
+            None,
+        ));
+
+        *axis.borrow_selection_curve_builder_mut(label_idx) =
+            selection::SelectionCurveBuilder::new();
+        axis.borrow_selection_curve_mut(label_idx)
+            .import_curve(spline);
+        drop(guard);
+
+        self.update_selection_lines_buffer();
+    }
+
     fn update_data(&mut self) {
         let guard = self.axes.borrow();
         for axis in guard.visible_axes() {
             for (label_idx, label_info) in self.labels.iter().enumerate() {
+                let mut curve = axis.borrow_selection_curve_mut(label_idx);
+                if curve.is_imported() {
+                    continue;
+                }
+
                 let curve_builder = axis.borrow_selection_curve_builder(label_idx);
-                let curve = curve_builder.build(
+                curve.set_curve(curve_builder.build(
                     axis.visible_data_range_normalized().into(),
                     label_info.easing,
-                );
-                axis.borrow_selection_curve_mut(label_idx).set_curve(curve);
+                ));
             }
         }
 
@@ -1247,6 +4085,8 @@ fn update_data(&mut self) {
         self.update_axes_buffer();
         self.update_axes_lines_buffer();
         self.update_data_lines_buffer();
+        self.update_reference_lines_buffer();
+        self.update_snapshot_lines_buffers();
         self.update_data_buffer();
         self.update_color_values_buffer();
 
@@ -1264,6 +4104,8 @@ fn set_axes_order(&mut self, order: wasm_bridge::AxisOrder) {
 
             self.update_axes_buffer();
             self.update_data_lines_buffer();
+            self.update_reference_lines_buffer();
+            self.update_snapshot_lines_buffers();
         }
     }
 
@@ -1329,6 +4171,232 @@ fn set_brushes(
         self.update_selection_lines_buffer();
     }
 
+    fn edit_brush(&mut self, edit: wasm_bridge::BrushEdit) {
+        let wasm_bridge::BrushEdit {
+            label,
+            axis,
+            index,
+            kind,
+        } = edit;
+
+        let label_idx = self
+            .labels
+            .iter()
+            .position(|l| l.id == label)
+            .expect("label should exist");
+        let guard = self.axes.borrow();
+        let axis = guard.axis(&axis).expect("axis should exist");
+        let (data_start, data_end) = axis.data_range();
+
+        // Edit the brush in the same way as the pointer interactions, i.e.,
+        // by removing it from the builder and reinserting the modified brush.
+        let mut curve_builder = axis.borrow_selection_curve_builder_mut(label_idx);
+        let mut selection = curve_builder.remove_selection(index);
+        match kind {
+            wasm_bridge::BrushEditKind::Nudge { delta } => {
+                selection.offset(delta / (data_end - data_start));
+            }
+            wasm_bridge::BrushEditKind::SetBounds { lower, upper } => {
+                let lower = lower.inv_lerp(data_start, data_end);
+                let upper = upper.inv_lerp(data_start, data_end);
+                selection.set_selection_range([lower, upper]);
+            }
+        }
+        curve_builder.insert_selection(selection, index);
+
+        let datums_range = axis.visible_data_range_normalized().into();
+        let easing_type = self.labels[label_idx].easing;
+        axis.borrow_selection_curve_mut(label_idx)
+            .set_curve(curve_builder.build(datums_range, easing_type));
+        drop(curve_builder);
+        drop(guard);
+
+        self.update_selection_lines_buffer();
+    }
+
+    /// Restores the brushes of a label to a step of its edit history. The
+    /// brushes on axes which no longer exist are skipped.
+    fn revert_label(&mut self, label: &str, step: usize) {
+        let label_idx = self
+            .labels
+            .iter()
+            .position(|l| l.id == label)
+            .expect("label should exist");
+        let brushes = self.labels[label_idx]
+            .history
+            .iter()
+            .find(|s| s.step == step)
+            .map(|s| s.brushes.clone())
+            .expect("step should exist");
+
+        let guard = self.axes.borrow();
+        for ax in guard.axes() {
+            let mut curve_builder = ax.borrow_selection_curve_builder_mut(label_idx);
+            *curve_builder = selection::SelectionCurveBuilder::new();
+
+            let mut curve = ax.borrow_selection_curve_mut(label_idx);
+            curve.set_curve(None);
+        }
+
+        for (ax, brushes) in brushes {
+            if let Some(ax) = guard.axis(&ax) {
+                self.replace_axis_brushes(label_idx, &ax, brushes);
+            }
+        }
+        drop(guard);
+
+        self.update_selection_lines_buffer();
+    }
+
+    /// Replaces the brushes of a label on an axis.
+    fn replace_axis_brushes(
+        &self,
+        label_idx: usize,
+        ax: &axis::Axis,
+        brushes: Vec<api::BrushSpec>,
+    ) {
+        let (data_start, data_end) = ax.data_range();
+
+        let mut curve_builder = selection::SelectionCurveBuilder::new();
+        for brush in brushes {
+            let api::BrushSpec {
+                control_points,
+                main_segment_idx,
+            } = brush;
+
+            let control_points = control_points
+                .into_iter()
+                .map(|(x, y)| (x.inv_lerp(data_start, data_end), y))
+                .collect();
+            let selection =
+                selection::Selection::from_control_points(control_points, main_segment_idx);
+            curve_builder.add_selection(selection);
+        }
+
+        let normalized_range = ax.visible_data_range_normalized();
+        let easing_type = self.labels[label_idx].easing;
+        let spline = curve_builder.build(normalized_range.into(), easing_type);
+
+        *ax.borrow_selection_curve_builder_mut(label_idx) = curve_builder;
+        ax.borrow_selection_curve_mut(label_idx).set_curve(spline);
+    }
+
+    /// Applies an operation of a remote client. Changes of labels or axes
+    /// which don't exist locally are only recorded in the replica.
+    fn apply_remote_operation(&mut self, operation: api::SyncOperation) {
+        let sync = self
+            .sync
+            .as_mut()
+            .expect("operation sync should be enabled");
+        let Some(change) = sync.apply(operation) else {
+            return;
+        };
+
+        match change {
+            sync::RemoteChange::Brushes {
+                label,
+                axis,
+                brushes,
+            } => {
+                let Some(label_idx) = self.labels.iter().position(|l| l.id == label) else {
+                    return;
+                };
+                let guard = self.axes.borrow();
+                let Some(ax) = guard.axis(&axis) else {
+                    return;
+                };
+                self.replace_axis_brushes(label_idx, &ax, brushes);
+                drop(guard);
+
+                let applied = self
+                    .label_brushes(label_idx)
+                    .remove(&axis)
+                    .unwrap_or_default();
+                if let Some(sync) = &mut self.sync {
+                    sync.track_brushes(&label, &axis, applied);
+                }
+
+                self.update_selection_lines_buffer();
+                self.handled_events.signal(event::Event::SELECTIONS_CHANGE);
+            }
+            sync::RemoteChange::AxisOrder { order } => {
+                let guard = self.axes.borrow();
+                let mut seen = BTreeSet::new();
+                let order = order
+                    .into_iter()
+                    .filter(|ax| guard.axis(ax).is_some() && seen.insert(ax.clone()))
+                    .collect::<Vec<_>>();
+                drop(guard);
+
+                if let Some(sync) = &mut self.sync {
+                    sync.track_axis_order(order.clone());
+                }
+
+                self.set_axes_order(wasm_bridge::AxisOrder::Custom {
+                    order: order.into_boxed_slice(),
+                });
+                self.update_data();
+                self.handled_events.signal(event::Event::AXIS_ORDER_CHANGE);
+            }
+        }
+    }
+
+    fn import_curve(&mut self, import: wasm_bridge::CurveImport) {
+        let wasm_bridge::CurveImport { label, axis, curve } = import;
+
+        let label_idx = self
+            .labels
+            .iter()
+            .position(|l| l.id == label)
+            .expect("label should exist");
+        let guard = self.axes.borrow();
+        let axis = guard.axis(&axis).expect("axis should exist");
+
+        // Like the curves built from the brushes, the imported curve spans
+        // the normalized data range of the axis.
+        let mut spline = spline::Spline::new([0.0, 1.0]);
+        match curve {
+            wasm_bridge::ProbabilityCurve::Samples(samples) => {
+                let step = 1.0 / (samples.len() - 1) as f32;
+                for (i, values) in samples.windows(2).enumerate() {
+                    let start = [i as f32 * step, values[0]];
+                    let end = [(i + 1) as f32 * step, values[1]];
+                    spline.insert_segment(spline::SplineSegment::new_linear(start, end, None));
+                }
+            }
+            wasm_bridge::ProbabilityCurve::Segments {
+                bounds,
+                t_ranges,
+                coefficients,
+            } => {
+                let segments = bounds
+                    .chunks_exact(2)
+                    .zip(t_ranges.chunks_exact(2))
+                    .zip(coefficients.chunks_exact(4));
+                for ((bounds, t_range), coefficients) in segments {
+                    spline.insert_segment(spline::SplineSegment {
+                        bounds: [bounds[0], bounds[1]],
+                        t_range: [t_range[0], t_range[1]],
+                        coefficients: [
+                            coefficients[0],
+                            coefficients[1],
+                            coefficients[2],
+                            coefficients[3],
+                        ],
+                    });
+                }
+            }
+        }
+
+        *axis.borrow_selection_curve_builder_mut(label_idx) =
+            selection::SelectionCurveBuilder::new();
+        axis.borrow_selection_curve_mut(label_idx)
+            .import_curve(spline);
+        drop(guard);
+
+        self.update_selection_lines_buffer();
+    }
+
     fn set_background_color(&mut self, color: ColorQuery<'_>) {
         let color = color.resolve_with_alpha::<SRgb>();
         self.background_color = color;
@@ -1356,7 +4424,15 @@ fn set_color_scale(
         color_space: wasm_bridge::ColorSpace,
         scale: ColorScaleDescriptor<'_>,
     ) {
-        let scale = match color_space {
+        let scale = Self::resolve_color_scale(color_space, scale);
+        self.update_color_scale_texture(color_space, scale);
+    }
+
+    fn resolve_color_scale(
+        color_space: wasm_bridge::ColorSpace,
+        scale: ColorScaleDescriptor<'_>,
+    ) -> color_scale::ColorScale<colors::UnknownColorSpace> {
+        match color_space {
             wasm_bridge::ColorSpace::SRgb => scale
                 .to_color_scale::<SRgbLinear>()
                 .transform::<colors::UnknownColorSpace>(),
@@ -1369,9 +4445,84 @@ fn set_color_scale(
             wasm_bridge::ColorSpace::CieLch => scale
                 .to_color_scale::<colors::CieLch>()
                 .transform::<colors::UnknownColorSpace>(),
-        };
+            wasm_bridge::ColorSpace::Oklab => scale
+                .to_color_scale::<colors::Oklab>()
+                .transform::<colors::UnknownColorSpace>(),
+            wasm_bridge::ColorSpace::Oklch => scale
+                .to_color_scale::<colors::Oklch>()
+                .transform::<colors::UnknownColorSpace>(),
+        }
+    }
 
-        self.update_color_scale_texture(color_space, scale);
+    /// Samples a color scale, without applying it to the plot.
+    ///
+    /// Returns the samples in the `RGBA` format, with one byte per channel.
+    async fn preview_color_scale(
+        &self,
+        color_scale: wasm_bridge::ColorScale,
+    ) -> js_sys::Uint8Array {
+        let wasm_bridge::ColorScale { color_space, scale } = color_scale;
+        let scale = Self::resolve_color_scale(color_space, scale);
+        let color_scale_elements = self.create_color_scale_elements(&scale);
+
+        let mut texture = buffers::ColorScaleTexture::new(&self.device);
+        let resolution = texture.texture().width();
+
+        // Rows of the copy must be aligned to 256 bytes.
+        let row_size = resolution * std::mem::size_of::<[f32; 4]>() as u32;
+        let bytes_per_row = (row_size + 255) & !255;
+        let staging_buffer = self.device.create_buffer(webgpu::BufferDescriptor {
+            label: Some(Cow::Borrowed("color scale preview staging buffer")),
+            size: bytes_per_row as usize,
+            usage: webgpu::BufferUsage::MAP_READ | webgpu::BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+
+        let encoder = self
+            .device
+            .create_command_encoder(webgpu::CommandEncoderDescriptor {
+                label: Some("color scale preview command encoder".into()),
+            });
+        self.pipelines.compute().color_scale_sampling().dispatch(
+            color_space,
+            &mut texture,
+            &color_scale_elements,
+            &self.device,
+            &encoder,
+        );
+        encoder.copy_texture_to_buffer(
+            webgpu::ImageCopyTexture {
+                texture: texture.texture().clone(),
+                aspect: None,
+                mip_level: None,
+                origin: None,
+            },
+            webgpu::ImageCopyBuffer {
+                buffer: staging_buffer.clone(),
+                layout: webgpu::ImageDataLayout {
+                    bytes_per_row: Some(bytes_per_row),
+                    ..Default::default()
+                },
+            },
+            [resolution, 1],
+        );
+        self.device.queue().submit(&[encoder.finish(None)]);
+
+        staging_buffer.map_async(webgpu::MapMode::READ).await;
+        let samples = unsafe { staging_buffer.get_mapped_range::<f32>() };
+        staging_buffer.unmap();
+        staging_buffer.destroy();
+
+        // The samples are stored in the XYZ color space.
+        let pixels = samples[..(resolution as usize * 4)]
+            .chunks_exact(4)
+            .flat_map(|sample| {
+                let color = ColorOpaque::<Xyz>::from_f32([sample[0], sample[1], sample[2]]);
+                let [r, g, b] = color.transform::<SRgb>().to_f32();
+                [r, g, b, sample[3]].map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8)
+            })
+            .collect::<Vec<_>>();
+        js_sys::Uint8Array::from(&pixels[..])
     }
 
     fn set_data_color_mode(&mut self, coloring: wasm_bridge::DataColorMode) {
@@ -1397,58 +4548,86 @@ fn set_data_color_mode(&mut self, coloring: wasm_bridge::DataColorMode) {
                     self.color_bar.set_to_label_probability("");
                 }
             }
+            wasm_bridge::DataColorMode::GroupProbability(group) => {
+                self.color_bar.set_to_group_probability(group);
+            }
         }
-
-        let width = self.canvas_gpu.width() as f32 / self.pixel_ratio;
-        let height = self.canvas_gpu.height() as f32 / self.pixel_ratio;
-        if self.color_bar.is_visible() {
-            let bounding_box = self.color_bar.bounding_box();
-            let world_end_x = bounding_box.start().x;
-
-            let guard = self.axes.borrow();
-            guard.set_view_bounding_box(Aabb::new(
-                Position::zero(),
-                Position::new((world_end_x, height)),
-            ));
-            drop(guard);
-        } else {
-            let guard = self.axes.borrow();
-            guard
-                .set_view_bounding_box(Aabb::new(Position::zero(), Position::new((width, height))));
-            drop(guard);
-        }
+
+        self.update_plot_area();
 
         self.update_color_values_buffer();
         self.update_data_config_buffer();
         self.update_color_scale_bounds_buffer();
     }
 
-    fn set_color_bar_visibility(&mut self, visible: bool) {
-        let width = self.canvas_gpu.width() as f32 / self.pixel_ratio;
-        let height = self.canvas_gpu.height() as f32 / self.pixel_ratio;
+    fn set_all_selections_visibility(&mut self, visible: bool) {
+        self.show_all_selections = visible;
+        self.update_selection_lines_buffer();
+    }
 
+    fn set_color_bar_visibility(&mut self, visible: bool) {
         self.color_bar.set_visible(visible);
-        if self.color_bar.is_visible() {
-            let bounding_box = self.color_bar.bounding_box();
-            let world_end_x = bounding_box.start().x;
+        self.update_plot_area();
+    }
 
-            let guard = self.axes.borrow();
-            guard.set_view_bounding_box(Aabb::new(
-                Position::zero(),
-                Position::new((world_end_x, height)),
-            ));
-            drop(guard);
-        } else {
-            let guard = self.axes.borrow();
-            guard
-                .set_view_bounding_box(Aabb::new(Position::zero(), Position::new((width, height))));
-            drop(guard);
+    fn set_color_bar_placement(&mut self, placement: color_bar::ColorBarPlacement) {
+        self.color_bar.set_placement(placement);
+        self.update_plot_area();
+        self.update_color_scale_bounds_buffer();
+    }
+
+    fn set_color_bar_options(&mut self, options: wasm_bridge::ColorBarOptions) {
+        self.color_bar.set_options(options);
+        self.update_plot_area();
+    }
+
+    /// Fits the axes into the area of the screen not covered by the color bar.
+    fn update_plot_area(&mut self) {
+        let guard = self.axes.borrow();
+        guard.set_plot_area(self.color_bar.plot_area());
+        drop(guard);
+
+        // The snapping depends on the size of the viewport.
+        if self.pixel_snapping {
+            self.update_axes_config_buffer();
+            self.update_selections_config_buffer();
+        }
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        if visible && !self.visible {
+            self.events.push(event::Event::REDRAW);
+        }
+        self.visible = visible;
+    }
+
+    fn set_frame_options(&mut self, options: wasm_bridge::FrameOptions) {
+        // Stop the previous scheduler before starting a new one.
+        self.draw_scheduler = None;
+        if options.schedule_draws {
+            let sender = self.event_sender.clone().unwrap();
+            self.draw_scheduler = Some(scheduler::DrawScheduler::new(sender, options.max_fps));
         }
     }
 
+    #[cfg(feature = "dev-tools")]
+    async fn reload_shader(&mut self, name: &str, source: &str) -> Result<(), String> {
+        let output = self.render_texture.output();
+        self.pipelines
+            .render_mut()
+            .reload(&self.device, output, name, source)
+            .await?;
+        self.events.push(event::Event::REDRAW);
+        Ok(())
+    }
+
     fn resize_drawing_area(&mut self, width: u32, height: u32, device_pixel_ratio: f32) {
-        let scaled_width = (width as f32 * device_pixel_ratio) as u32;
-        let scaled_height = (height as f32 * device_pixel_ratio) as u32;
+        let device_pixel_ratio = coordinates::valid_pixel_ratio(device_pixel_ratio);
+
+        // Both canvases must share the same rounded size, otherwise fractional
+        // ratios shift the text against the GPU rendering by a pixel.
+        let (scaled_width, scaled_height) =
+            coordinates::device_pixel_size((width, height), device_pixel_ratio);
 
         self.pixel_ratio = device_pixel_ratio;
         self.canvas_gpu.set_width(scaled_width);
@@ -1456,34 +4635,18 @@ fn resize_drawing_area(&mut self, width: u32, height: u32, device_pixel_ratio: f
 
         self.canvas_2d.set_width(scaled_width);
         self.canvas_2d.set_height(scaled_height);
+        let ratio = device_pixel_ratio as f64;
         self.context_2d
-            .scale(device_pixel_ratio as f64, device_pixel_ratio as f64)
+            .set_transform(ratio, 0.0, 0.0, ratio, 0.0, 0.0)
             .unwrap();
 
         self.render_texture
-            .resize(&self.device, width, height, device_pixel_ratio);
+            .resize(&self.device, scaled_width, scaled_height);
         self.depth_texture
-            .resize(&self.device, width, height, device_pixel_ratio);
+            .resize(&self.device, scaled_width, scaled_height);
 
         self.color_bar.set_screen_size(width as f32, height as f32);
-        if self.color_bar.is_visible() {
-            let bounding_box = self.color_bar.bounding_box();
-            let world_end_x = bounding_box.start().x;
-
-            let guard = self.axes.borrow();
-            guard.set_view_bounding_box(Aabb::new(
-                Position::zero(),
-                Position::new((world_end_x, height as f32)),
-            ));
-            drop(guard);
-        } else {
-            let guard = self.axes.borrow();
-            guard.set_view_bounding_box(Aabb::new(
-                Position::zero(),
-                Position::new((width as f32, height as f32)),
-            ));
-            drop(guard);
-        }
+        self.update_plot_area();
 
         self.update_axes_config_buffer();
         self.update_data_config_buffer();
@@ -1504,6 +4667,7 @@ fn add_label(
             panic!("id already exists");
         }
 
+        let inherits_group_color = color.is_none();
         let (color, color_dimmed) = if let Some(color) = color {
             let c = color.resolve();
             let c2 = LabelColorGenerator::dim(c);
@@ -1521,6 +4685,16 @@ fn add_label(
             easing: easing_type,
             color,
             color_dimmed,
+            num_selected: None,
+            snapshot: None,
+            group: None,
+            inherits_group_color,
+            visible: true,
+            history: vec![api::LabelHistoryStep {
+                step: 0,
+                timestamp: js_sys::Date::now(),
+                brushes: BTreeMap::new(),
+            }],
         };
 
         self.labels.push(label);
@@ -1543,6 +4717,7 @@ fn add_label(
         self.update_selection_lines_buffer();
         self.update_label_colors_buffer();
         self.update_color_scale_bounds_buffer();
+        self.update_axes_lines_buffer();
     }
 
     fn remove_label(&mut self, id: String) {
@@ -1552,7 +4727,23 @@ fn remove_label(&mut self, id: String) {
             .position(|l| l.id == id)
             .expect("no label with a matching id found");
 
-        self.labels.remove(label_idx);
+        let label = self.labels.remove(label_idx);
+        if label.snapshot.is_some() {
+            self.staging_data.updated_snapshots.insert(label.id.clone());
+            self.handled_events.signal(event::Event::SNAPSHOTS_CHANGE);
+        }
+        if label.group.is_some() {
+            self.staging_data.group_probabilities_update = true;
+        }
+        if !label.visible {
+            self.handled_events
+                .signal(event::Event::LABEL_VISIBILITY_CHANGE);
+        }
+        if let Some((label_a, label_b)) = &self.curve_comparison {
+            if *label_a == label.id || *label_b == label.id {
+                self.curve_comparison = None;
+            }
+        }
         self.buffers.data_mut().remove_label(label_idx);
         self.buffers.curves_mut().remove_label(label_idx);
         self.buffers.selections_mut().remove_label(label_idx);
@@ -1592,6 +4783,7 @@ fn remove_label(&mut self, id: String) {
         self.update_selection_lines_buffer();
         self.update_label_colors_buffer();
         self.update_color_scale_bounds_buffer();
+        self.update_axes_lines_buffer();
     }
 
     fn change_active_label(&mut self, id: Option<String>) {
@@ -1618,6 +4810,8 @@ fn change_active_label(&mut self, id: Option<String>) {
         self.update_selection_lines_buffer();
         self.update_data_config_buffer();
         self.update_color_scale_bounds_buffer();
+        self.update_axes_config_buffer();
+        self.update_axes_lines_buffer();
     }
 
     fn change_label_color(&mut self, id: &str, color: Option<ColorQuery<'_>>) {
@@ -1627,6 +4821,7 @@ fn change_label_color(&mut self, id: &str, color: Option<ColorQuery<'_>>) {
             .position(|l| l.id == id)
             .expect("no label with a matching id found");
 
+        let inherits_group_color = color.is_none();
         let (color, color_dimmed) = if let Some(color) = color {
             let c = color.resolve();
             let c2 = LabelColorGenerator::dim(c);
@@ -1637,6 +4832,7 @@ fn change_label_color(&mut self, id: &str, color: Option<ColorQuery<'_>>) {
 
         self.labels[label_idx].color = color;
         self.labels[label_idx].color_dimmed = color_dimmed;
+        self.labels[label_idx].inherits_group_color = inherits_group_color;
 
         self.update_selections_config_buffer();
         self.update_label_colors_buffer();
@@ -1658,6 +4854,7 @@ fn change_label_selection_bounds(&mut self, id: &str, selection_bounds: Option<(
             if label_idx == active_label_idx {
                 self.update_data_config_buffer();
                 self.update_color_scale_bounds_buffer();
+                self.update_axes_lines_buffer();
             }
         }
     }
@@ -1673,15 +4870,104 @@ fn change_label_easing(&mut self, id: &str, easing: selection::EasingType) {
 
         let axes = self.axes.borrow();
         for axis in axes.visible_axes() {
+            let mut curve = axis.borrow_selection_curve_mut(label_idx);
+            if curve.is_imported() {
+                continue;
+            }
+
             let curve_builder = axis.borrow_selection_curve_builder(label_idx);
-            let curve = curve_builder.build(axis.visible_data_range_normalized().into(), easing);
-            axis.borrow_selection_curve_mut(label_idx).set_curve(curve);
+            let range = axis.visible_data_range_normalized().into();
+            curve.set_curve(curve_builder.build(range, easing));
         }
         drop(axes);
 
         self.update_selection_lines_buffer();
     }
 
+    fn change_label_visibility(&mut self, id: &str, visible: bool) {
+        let label = self
+            .labels
+            .iter_mut()
+            .find(|l| l.id == id)
+            .expect("no label with a matching id found");
+
+        if label.visible != visible {
+            label.visible = visible;
+            self.handled_events
+                .signal(event::Event::LABEL_VISIBILITY_CHANGE);
+        }
+    }
+
+    fn discard_label_snapshot(&mut self, id: &str) {
+        let label = self
+            .labels
+            .iter_mut()
+            .find(|l| l.id == id)
+            .expect("no label with a matching id found");
+
+        if label.snapshot.take().is_some() {
+            self.staging_data.updated_snapshots.insert(label.id.clone());
+            self.handled_events.signal(event::Event::SNAPSHOTS_CHANGE);
+        }
+    }
+
+    fn add_label_group(&mut self, id: String, color: Option<ColorQuery<'_>>) {
+        if self.label_groups.iter().any(|g| g.id == id) {
+            panic!("id already exists");
+        }
+
+        let (color, color_dimmed) = if let Some(color) = color {
+            let c = color.resolve();
+            let c2 = LabelColorGenerator::dim(c);
+            (c, c2)
+        } else {
+            self.label_color_generator.next()
+        };
+
+        self.label_groups.push(LabelGroupInfo {
+            id,
+            color,
+            color_dimmed,
+        });
+    }
+
+    fn remove_label_group(&mut self, id: &str) {
+        let group_idx = self
+            .label_groups
+            .iter()
+            .position(|g| g.id == id)
+            .expect("no label group with a matching id found");
+        self.label_groups.remove(group_idx);
+
+        for label in &mut self.labels {
+            if label.group.as_deref() == Some(id) {
+                label.group = None;
+            }
+        }
+
+        let colored_by_group = matches!(
+            &self.data_color_mode,
+            wasm_bridge::DataColorMode::GroupProbability(group) if group == id
+        );
+        if colored_by_group {
+            self.set_data_color_mode(DEFAULT_DATA_COLOR_MODE());
+        }
+
+        self.update_label_colors_buffer();
+    }
+
+    fn set_label_group(&mut self, id: &str, group: Option<String>) {
+        let label = self
+            .labels
+            .iter_mut()
+            .find(|l| l.id == id)
+            .expect("no label with a matching id found");
+        label.group = group;
+
+        self.staging_data.group_probabilities_update = true;
+        self.update_label_colors_buffer();
+    }
+
     fn change_interaction_mode(&mut self, mode: wasm_bridge::InteractionMode) {
         self.finish_action();
         self.interaction_mode = mode;
@@ -1698,48 +4984,241 @@ fn change_interaction_mode(&mut self, mode: wasm_bridge::InteractionMode) {
 
     fn change_debug_options(&mut self, options: wasm_bridge::DebugOptions) {
         self.debug = options;
+        logging::configure(options.log_level, options.mirror_logs);
+        if !options.show_interaction_trace {
+            self.staging_data.pointer_trail.clear();
+        }
+        if !options.log_events {
+            self.staging_data.pointer_trace.clear();
+        }
+    }
+
+    fn change_hit_test_options(&mut self, options: wasm_bridge::HitTestOptions) {
+        self.hit_test = options;
+
+        let mut guard = self.axes.borrow_mut();
+        guard.set_hit_test_radii(axis::HitTestRadii {
+            control_point: options.control_point_radius,
+            axis_line: options.axis_line_radius,
+            selection_edge: options.selection_edge_radius,
+        });
+    }
+
+    fn change_control_point_options(&mut self, options: wasm_bridge::ControlPointOptions) {
+        self.axes
+            .borrow_mut()
+            .set_control_point_radius(options.radius);
+        self.control_points = options;
+        self.update_plot_area();
+    }
+
+    fn change_accessibility_options(&mut self, options: wasm_bridge::AccessibilityOptions) {
+        self.accessibility = options;
+
+        self.update_label_colors_buffer();
+        self.update_axes_config_buffer();
+        self.update_data_config_buffer();
+        self.update_curves_config_buffer();
+        self.update_selections_config_buffer();
+    }
+
+    fn change_locale_options(&mut self, options: wasm_bridge::LocaleOptions) {
+        self.axes
+            .borrow_mut()
+            .set_number_format(options.number_format());
+        self.color_bar.set_number_format(options.number_format());
+        self.update_plot_area();
     }
 
-    fn validate_transaction(&self, transaction: &wasm_bridge::StateTransaction) -> bool {
+    fn validate_transaction(
+        &self,
+        transaction: &wasm_bridge::StateTransaction,
+    ) -> Result<(), diagnostics::Diagnostic> {
+        use diagnostics::{Diagnostic, Warning};
+
         let wasm_bridge::StateTransaction {
             axis_removals,
             axis_additions,
+            axis_point_changes,
+            axis_point_appends,
+            visible_range_changes,
+            range_fit,
+            axis_jitter_changes,
+            category_order_optimizations,
+            reference_clear,
+            reference_points,
             order_change,
+            view_change,
+            colors_change,
+            color_bar_placement_change,
+            color_bar_options_change,
+            axis_header_height_change,
+            curve_area_width_change,
+            background_layer_change,
+            annotation_removals,
+            annotation_additions,
+            axis_region_removals,
+            axis_region_additions,
+            clusters_change,
+            outlier_scores_change,
+            density_curves_change,
+            histograms_change,
+            live_mode_change,
+            live_freeze_change,
             label_removals,
             label_additions,
             label_updates,
             active_label_change,
+            label_freezes,
+            snapshot_removals,
+            label_group_removals,
+            label_group_additions,
+            label_group_changes,
+            curve_comparison_change,
             brushes_change,
+            brush_edits,
+            curve_imports,
+            label_reverts,
+            operation_sync_change,
+            remote_operations,
             ..
         } = transaction;
 
         for axis in axis_removals {
             let guard = self.axes.borrow();
             if guard.axis(axis).is_none() {
-                web_sys::console::warn_1(&"Transaction removes a nonexistent axis.".into());
-                return false;
+                return Err(Diagnostic::new(Warning::RemoveNonexistentAxis).with("axis", axis));
             }
         }
         for (axis, axis_def) in axis_additions {
             let guard = self.axes.borrow();
             if guard.axis(axis).is_some() && !axis_removals.contains(axis) {
-                web_sys::console::warn_1(&"Transaction adds a duplicate axis.".into());
-                return false;
+                return Err(Diagnostic::new(Warning::AddDuplicateAxis).with("axis", axis));
             }
 
-            let wasm_bridge::AxisDef {
-                key,
-                label,
-                points,
-                range,
-                visible_range,
-                ticks,
-            } = axis_def;
+            let max_points = self.remaining_num_data_points(axis_removals);
+            validate_axis_def(axis, axis_def, max_points)?;
+        }
+        for (axis, points) in axis_point_changes {
+            let guard = self.axes.borrow();
+            if guard.axis(axis).is_none() || axis_removals.contains(axis) {
+                return Err(Diagnostic::new(Warning::SetPointsOfNonexistentAxis).with("axis", axis));
+            }
+            if points.iter().any(|p| p.is_infinite()) {
+                return Err(Diagnostic::new(Warning::InfiniteAxisPoint).with("axis", axis));
+            }
+            if points.len() > guard.num_data_points() {
+                return Err(Diagnostic::new(Warning::MismatchedPointCount)
+                    .with("axis", axis)
+                    .with("expected", guard.num_data_points().to_string())
+                    .with("actual", points.len().to_string()));
+            }
+        }
+        for (axis, points) in axis_point_appends {
+            let guard = self.axes.borrow();
+            if guard.axis(axis).is_none() || axis_removals.contains(axis) {
+                return Err(Diagnostic::new(Warning::AppendToNonexistentAxis).with("axis", axis));
+            }
+            if points.iter().any(|p| p.is_infinite()) {
+                return Err(Diagnostic::new(Warning::InfiniteAxisPoint).with("axis", axis));
+            }
+        }
+        for (axis, &(min, max)) in visible_range_changes {
+            // The visible range of an added axis is part of its definition.
+            let guard = self.axes.borrow();
+            let Some(ax) = guard.axis(axis).filter(|_| !axis_removals.contains(axis)) else {
+                return Err(
+                    Diagnostic::new(Warning::VisibleRangeOfNonexistentAxis).with("axis", axis)
+                );
+            };
+
+            if !(min.is_finite() && max.is_finite() && min < max) {
+                return Err(Diagnostic::new(Warning::InvalidVisibleRange).with("axis", axis));
+            }
+            let (range_min, range_max) = ax.data_range();
+            if min < range_min || max > range_max {
+                return Err(Diagnostic::new(Warning::VisibleRangeOutsideRange).with("axis", axis));
+            }
+        }
+        for (axis, &amplitude) in axis_jitter_changes {
+            let guard = self.axes.borrow();
+            let exists = (guard.axis(axis).is_some() && !axis_removals.contains(axis))
+                || axis_additions.contains_key(axis);
+            if !exists {
+                return Err(Diagnostic::new(Warning::JitterOfNonexistentAxis).with("axis", axis));
+            }
+            if !(0.0..=MAX_AXIS_JITTER).contains(&amplitude) {
+                return Err(Diagnostic::new(Warning::InvalidAxisJitter).with("axis", axis));
+            }
+        }
+        for axis in category_order_optimizations {
+            let guard = self.axes.borrow();
+            let Some(ax) = guard.axis(axis).filter(|_| !axis_removals.contains(axis)) else {
+                return Err(
+                    Diagnostic::new(Warning::CategoryOrderOfNonexistentAxis).with("axis", axis)
+                );
+            };
+            if Self::axis_categories(&ax).is_none() {
+                return Err(
+                    Diagnostic::new(Warning::CategoryOrderOfNonCategoricalAxis).with("axis", axis)
+                );
+            }
+        }
+        if let Some(wasm_bridge::FitAxes::Axes(axes)) = range_fit {
+            let guard = self.axes.borrow();
+            for axis in axes {
+                if !((guard.axis(axis).is_some() && !axis_removals.contains(axis))
+                    || axis_additions.contains_key(axis))
+                {
+                    return Err(
+                        Diagnostic::new(Warning::VisibleRangeOfNonexistentAxis).with("axis", axis)
+                    );
+                }
+            }
+        }
+        // The reference points of all axes must match, including the ones of
+        // the axes which are not replaced by the transaction.
+        let mut num_reference_points = None;
+        if !reference_clear {
+            num_reference_points = self
+                .reference_data
+                .iter()
+                .find(|(axis, _)| !reference_points.contains_key(*axis))
+                .map(|(_, points)| points.len());
+        }
+        for (axis, points) in reference_points {
+            if points.iter().any(|p| p.is_infinite()) {
+                return Err(Diagnostic::new(Warning::InfiniteReferencePoint).with("axis", axis));
+            }
+
+            let expected = *num_reference_points.get_or_insert(points.len());
+            if points.len() != expected {
+                return Err(Diagnostic::new(Warning::MismatchedReferencePointCount)
+                    .with("axis", axis)
+                    .with("expected", expected.to_string())
+                    .with("actual", points.len().to_string()));
+            }
+        }
+        if let Some(wasm_bridge::ViewChange { target, .. }) = view_change {
+            let keys = match target {
+                wasm_bridge::ViewTarget::Default => &[][..],
+                wasm_bridge::ViewTarget::Axes(keys) => &keys[..],
+                wasm_bridge::ViewTarget::Axis(key) => std::slice::from_ref(key),
+            };
+            let guard = self.axes.borrow();
+            for axis in keys {
+                if !((guard.axis(axis).is_some() && !axis_removals.contains(axis))
+                    || axis_additions.contains_key(axis))
+                {
+                    return Err(Diagnostic::new(Warning::ViewOfNonexistentAxis).with("axis", axis));
+                }
+            }
         }
         if let Some(wasm_bridge::AxisOrder::Custom { order }) = order_change {
             if BTreeSet::from_iter(order.iter()).len() != order.len() {
-                web_sys::console::warn_1(&"Transaction axis order contains duplicates.".into());
-                return false;
+                return Err(
+                    Diagnostic::new(Warning::DuplicateAxisInOrder).with("order", order.join(","))
+                );
             }
 
             let guard = self.axes.borrow();
@@ -1747,26 +5226,37 @@ fn validate_transaction(&self, transaction: &wasm_bridge::StateTransaction) -> b
                 (guard.axis(key).is_some() && !axis_removals.contains(key))
                     || axis_additions.contains_key(key)
             };
-            if order.iter().any(|ax| !contains_axis(ax)) {
-                web_sys::console::warn_1(
-                    &"Transaction axis order contains nonexistent axes.".into(),
-                );
-                return false;
+            if let Some(axis) = order.iter().find(|ax| !contains_axis(ax)) {
+                return Err(Diagnostic::new(Warning::NonexistentAxisInOrder).with("axis", axis));
             }
         }
         for label in label_removals {
             if !self.labels.iter().any(|l| l.id == *label) {
-                web_sys::console::warn_1(&"Transaction removes a nonexistent label.".into());
-                return false;
+                return Err(Diagnostic::new(Warning::RemoveNonexistentLabel).with("label", label));
             }
         }
         for label in label_additions.keys() {
             if self.labels.iter().any(|l| l.id == *label) {
-                web_sys::console::warn_1(&"Transaction adds a duplicate label.".into());
-                return false;
+                return Err(Diagnostic::new(Warning::AddDuplicateLabel).with("label", label));
+            }
+        }
+        let modified_labels = label_updates
+            .keys()
+            .chain(label_freezes)
+            .chain(snapshot_removals)
+            .chain(label_group_changes.keys());
+        for label in modified_labels {
+            let mut available_labels = self
+                .labels
+                .iter()
+                .map(|l| &l.id)
+                .filter(|l| !label_removals.contains(*l))
+                .chain(label_additions.keys());
+            if !available_labels.any(|l| l == label) {
+                return Err(Diagnostic::new(Warning::ModifyNonexistentLabel).with("label", label));
             }
         }
-        for label in label_updates.keys() {
+        if let Some(Some(label)) = active_label_change {
             let mut available_labels = self
                 .labels
                 .iter()
@@ -1774,22 +5264,232 @@ fn validate_transaction(&self, transaction: &wasm_bridge::StateTransaction) -> b
                 .filter(|l| !label_removals.contains(*l))
                 .chain(label_additions.keys());
             if !available_labels.any(|l| l == label) {
-                web_sys::console::warn_1(&"Transaction modifies a nonexistent label.".into());
-                return false;
+                return Err(Diagnostic::new(Warning::ActivateNonexistentLabel).with("label", label));
+            }
+        }
+        for group in label_group_removals {
+            if !self.label_groups.iter().any(|g| g.id == *group) {
+                return Err(
+                    Diagnostic::new(Warning::RemoveNonexistentLabelGroup).with("group", group)
+                );
+            }
+        }
+        for group in label_group_additions.keys() {
+            if self.label_groups.iter().any(|g| g.id == *group) {
+                return Err(Diagnostic::new(Warning::AddDuplicateLabelGroup).with("group", group));
+            }
+        }
+        let group_exists = |group: &String| {
+            (self.label_groups.iter().any(|g| g.id == *group)
+                && !label_group_removals.contains(group))
+                || label_group_additions.contains_key(group)
+        };
+        for (label, group) in label_group_changes {
+            if let Some(group) = group.as_ref().filter(|group| !group_exists(group)) {
+                return Err(Diagnostic::new(Warning::AssignNonexistentLabelGroup)
+                    .with("label", label)
+                    .with("group", group));
+            }
+        }
+        if let Some(wasm_bridge::Colors {
+            color_mode: Some(wasm_bridge::DataColorMode::GroupProbability(group)),
+            ..
+        }) = colors_change
+        {
+            if !group_exists(group) {
+                return Err(
+                    Diagnostic::new(Warning::ColorByNonexistentLabelGroup).with("group", group)
+                );
+            }
+        }
+
+        if let Some(Some(options)) = clusters_change {
+            if !(2..=MAX_CLUSTERS).contains(&options.k) || options.max_iterations == 0 {
+                return Err(Diagnostic::new(Warning::InvalidClusterOptions));
+            }
+        }
+        if let Some(wasm_bridge::Colors {
+            color_mode: Some(wasm_bridge::DataColorMode::Attribute(axis)),
+            ..
+        }) = colors_change
+        {
+            let has_clusters = match clusters_change {
+                Some(options) => options.is_some(),
+                None => self.axes.borrow().axis(CLUSTER_AXIS_KEY).is_some(),
+            };
+            if axis == CLUSTER_AXIS_KEY && !has_clusters {
+                return Err(Diagnostic::new(Warning::ColorByMissingClusters));
+            }
+        }
+
+        if let Some(Some(options)) = outlier_scores_change {
+            let valid = options.threshold > 0.0 && options.threshold < MAX_OUTLIER_SCORE;
+            if !valid {
+                return Err(Diagnostic::new(Warning::InvalidOutlierOptions));
+            }
+        }
+        if let Some(wasm_bridge::Colors {
+            color_mode: Some(wasm_bridge::DataColorMode::Attribute(axis)),
+            ..
+        }) = colors_change
+        {
+            let has_scores = match outlier_scores_change {
+                Some(options) => options.is_some(),
+                None => self.outlier_options.is_some(),
+            };
+            if axis == OUTLIER_AXIS_KEY && !has_scores {
+                return Err(Diagnostic::new(Warning::ColorByMissingOutlierScores));
+            }
+        }
+
+        if let Some(Some(options)) = density_curves_change {
+            let valid = options.bandwidth > 0.0 && options.bandwidth <= 1.0;
+            if !valid {
+                return Err(Diagnostic::new(Warning::InvalidDensityOptions));
+            }
+        }
+        if let Some(Some(options)) = histograms_change {
+            let valid = (1..=buffers::HistogramConfig::MAX_BINS).contains(&options.num_bins);
+            if !valid {
+                return Err(Diagnostic::new(Warning::InvalidHistogramOptions));
+            }
+        }
+        if let Some(Some(options)) = live_mode_change {
+            let valid = options.interval.is_finite()
+                && options.interval >= 0.0
+                && options.max_points != Some(0);
+            if !valid {
+                return Err(Diagnostic::new(Warning::InvalidLiveOptions));
+            }
+        }
+        if let Some(true) = live_freeze_change {
+            let live = match live_mode_change {
+                Some(options) => options.is_some(),
+                None => self.live_options.is_some(),
+            };
+            if !live {
+                return Err(Diagnostic::new(Warning::FreezeWithoutLiveMode));
+            }
+        }
+
+        if let Some(color_bar::ColorBarPlacement::Floating { x, y }) = color_bar_placement_change {
+            if !(x.is_finite() && y.is_finite()) {
+                return Err(Diagnostic::new(Warning::InvalidColorBarPosition));
+            }
+        }
+
+        if let Some(options) = color_bar_options_change {
+            let valid_count = options.tick_count.unwrap_or(2) >= 2;
+            let valid_positions = match &options.tick_positions {
+                Some(positions) => {
+                    positions.len() >= 2 && positions.iter().all(|t| (0.0..=1.0).contains(t))
+                }
+                None => true,
+            };
+            if !(valid_count && valid_positions) {
+                return Err(Diagnostic::new(Warning::InvalidColorBarTicks));
+            }
+        }
+
+        if let Some(height) = axis_header_height_change {
+            if !(0.0..=MAX_AXIS_HEADER_HEIGHT).contains(height) {
+                return Err(Diagnostic::new(Warning::InvalidAxisHeaderHeight));
+            }
+        }
+
+        if let Some(width) = *curve_area_width_change {
+            let valid = width > 0.0 && width <= MAX_CURVE_AREA_WIDTH;
+            if !valid {
+                return Err(Diagnostic::new(Warning::InvalidCurveAreaWidth));
+            }
+        }
+
+        if let Some(Some(layer)) = background_layer_change {
+            let valid_opacity = (0.0..=1.0).contains(&layer.opacity);
+            let valid_scale = layer.scale.is_finite() && layer.scale > 0.0;
+            if !(valid_opacity && valid_scale) {
+                return Err(Diagnostic::new(Warning::InvalidBackgroundLayer));
+            }
+        }
+
+        for id in axis_region_removals {
+            if !self.axis_regions.contains_key(id) {
+                return Err(
+                    Diagnostic::new(Warning::RemoveNonexistentAxisRegion).with("region", id)
+                );
+            }
+        }
+        for (id, region) in axis_region_additions {
+            if self.axis_regions.contains_key(id) && !axis_region_removals.contains(id) {
+                return Err(Diagnostic::new(Warning::AddDuplicateAxisRegion).with("region", id));
+            }
+
+            let axis = &region.axis;
+            let guard = self.axes.borrow();
+            if !((guard.axis(axis).is_some() && !axis_removals.contains(axis))
+                || axis_additions.contains_key(axis))
+            {
+                return Err(Diagnostic::new(Warning::AxisRegionOfNonexistentAxis)
+                    .with("region", id)
+                    .with("axis", axis));
+            }
+
+            let (start, end) = region.range;
+            if !(start.is_finite() && end.is_finite() && start < end) {
+                return Err(Diagnostic::new(Warning::InvalidAxisRegionRange).with("region", id));
+            }
+        }
+
+        for id in annotation_removals {
+            if !self.annotations.contains_key(id) {
+                return Err(
+                    Diagnostic::new(Warning::RemoveNonexistentAnnotation).with("annotation", id)
+                );
+            }
+        }
+        for (id, annotation) in annotation_additions {
+            if self.annotations.contains_key(id) && !annotation_removals.contains(id) {
+                return Err(Diagnostic::new(Warning::AddDuplicateAnnotation).with("annotation", id));
+            }
+
+            let axis = annotation.anchor.axis();
+            let num_data_points = match axis_additions.get(axis) {
+                Some(axis_def) => axis_def.points.len(),
+                None => {
+                    let guard = self.axes.borrow();
+                    if guard.axis(axis).is_none() || axis_removals.contains(axis) {
+                        return Err(Diagnostic::new(Warning::AnnotateNonexistentAxis)
+                            .with("annotation", id)
+                            .with("axis", axis));
+                    }
+                    guard.num_data_points()
+                }
+            };
+
+            let valid_anchor = match &annotation.anchor {
+                wasm_bridge::AnnotationAnchor::Value { value, .. } => value.is_finite(),
+                wasm_bridge::AnnotationAnchor::Datum { index, .. } => *index < num_data_points,
+            };
+            if !valid_anchor {
+                return Err(
+                    Diagnostic::new(Warning::InvalidAnnotationAnchor).with("annotation", id)
+                );
             }
         }
-        if let Some(Some(label)) = active_label_change {
-            let mut available_labels = self
-                .labels
-                .iter()
-                .map(|l| &l.id)
-                .filter(|l| !label_removals.contains(*l))
-                .chain(label_additions.keys());
-            if !available_labels.any(|l| l == label) {
-                web_sys::console::warn_1(
-                    &"Transaction sets the active label to a nonexistent label.".into(),
-                );
-                return false;
+
+        if let Some(Some((label_a, label_b))) = curve_comparison_change {
+            for label in [label_a, label_b] {
+                let mut available_labels = self
+                    .labels
+                    .iter()
+                    .map(|l| &l.id)
+                    .filter(|l| !label_removals.contains(*l))
+                    .chain(label_additions.keys());
+                if !available_labels.any(|l| l == label) {
+                    return Err(
+                        Diagnostic::new(Warning::CompareNonexistentLabel).with("label", label)
+                    );
+                }
             }
         }
 
@@ -1803,47 +5503,43 @@ fn validate_transaction(&self, transaction: &wasm_bridge::StateTransaction) -> b
                     .filter(|l| !label_removals.contains(*l))
                     .chain(label_additions.keys());
                 if !available_labels.any(|l| l == label) {
-                    web_sys::console::warn_1(
-                        &"Transaction specifies the brushes of a nonexistent label.".into(),
+                    return Err(
+                        Diagnostic::new(Warning::BrushesOfNonexistentLabel).with("label", label)
                     );
-                    return false;
                 }
 
                 for (axis, brushes) in label_brushes {
                     if !((guard.axis(axis).is_some() && !axis_removals.contains(axis))
                         || axis_additions.contains_key(axis))
                     {
-                        web_sys::console::warn_1(
-                            &"Transaction specifies the brushes of a nonexistent axis.".into(),
-                        );
-                        return false;
+                        return Err(Diagnostic::new(Warning::BrushesOfNonexistentAxis)
+                            .with("label", label)
+                            .with("axis", axis));
                     }
 
-                    for brush in brushes {
+                    for (brush_idx, brush) in brushes.iter().enumerate() {
+                        let diagnostic = |warning| {
+                            Diagnostic::new(warning)
+                                .with("label", label)
+                                .with("axis", axis)
+                                .with("brush", brush_idx.to_string())
+                        };
+
                         if brush.control_points.len() < 2 {
-                            web_sys::console::warn_1(
-                                &"A brush must contain at least two control points".into(),
-                            );
-                            return false;
+                            return Err(diagnostic(Warning::TooFewBrushControlPoints));
                         }
 
                         if brush.main_segment_idx >= brush.control_points.len() - 1 {
-                            web_sys::console::warn_1(&"Main brush segment is out of bounds".into());
-                            return false;
+                            return Err(diagnostic(Warning::MainBrushSegmentOutOfBounds));
                         }
 
                         let mut last_x = brush.control_points.first().unwrap_or(&(0.0, 0.0)).0;
                         for &(x, y) in &brush.control_points {
                             if !x.is_finite() || !(0.0..=1.0).contains(&y) {
-                                web_sys::console::warn_1(&"Invalid brush control point".into());
-                                return false;
+                                return Err(diagnostic(Warning::InvalidBrushControlPoint));
                             }
                             if last_x > x {
-                                web_sys::console::warn_1(
-                                    &"Brush control points must be ordered by increasing x value"
-                                        .into(),
-                                );
-                                return false;
+                                return Err(diagnostic(Warning::UnorderedBrushControlPoints));
                             }
                             last_x = x;
                         }
@@ -1852,32 +5548,259 @@ fn validate_transaction(&self, transaction: &wasm_bridge::StateTransaction) -> b
             }
         }
 
-        true
+        for edit in brush_edits {
+            let wasm_bridge::BrushEdit {
+                label,
+                axis,
+                index,
+                kind,
+            } = edit;
+            let diagnostic = |warning| {
+                Diagnostic::new(warning)
+                    .with("label", label)
+                    .with("axis", axis)
+                    .with("brush", index.to_string())
+            };
+
+            // The brushes are edited after they have been replaced by the
+            // transaction, if it contains new brushes.
+            let num_brushes = if let Some(brushes) = brushes_change {
+                brushes
+                    .get(label)
+                    .and_then(|brushes| brushes.get(axis))
+                    .map_or(0, |brushes| brushes.len())
+            } else {
+                let guard = self.axes.borrow();
+                let label_idx = self
+                    .labels
+                    .iter()
+                    .position(|l| l.id == *label)
+                    .filter(|_| !label_removals.contains(label));
+                let axis = guard.axis(axis).filter(|_| !axis_removals.contains(axis));
+                match (label_idx, axis) {
+                    (Some(label_idx), Some(axis)) => axis
+                        .borrow_selection_curve_builder(label_idx)
+                        .selections()
+                        .len(),
+                    _ => 0,
+                }
+            };
+            if *index >= num_brushes {
+                return Err(diagnostic(Warning::EditNonexistentBrush));
+            }
+
+            let is_valid = match *kind {
+                wasm_bridge::BrushEditKind::Nudge { delta } => delta.is_finite(),
+                wasm_bridge::BrushEditKind::SetBounds { lower, upper } => {
+                    lower.is_finite() && upper.is_finite() && lower <= upper
+                }
+            };
+            if !is_valid {
+                return Err(diagnostic(Warning::InvalidBrushEdit));
+            }
+        }
+
+        let sync_enabled = match operation_sync_change {
+            Some(client) => client.is_some(),
+            None => self.sync.is_some(),
+        };
+        if !remote_operations.is_empty() && !sync_enabled {
+            return Err(Diagnostic::new(Warning::RemoteOperationWithoutSync));
+        }
+
+        for (label, &step) in label_reverts {
+            let label_info = self
+                .labels
+                .iter()
+                .find(|l| l.id == *label)
+                .filter(|_| !label_removals.contains(label));
+            let Some(label_info) = label_info else {
+                return Err(Diagnostic::new(Warning::RevertOfNonexistentLabel).with("label", label));
+            };
+            if !label_info.history.iter().any(|s| s.step == step) {
+                return Err(Diagnostic::new(Warning::RevertToNonexistentStep)
+                    .with("label", label)
+                    .with("step", step.to_string()));
+            }
+        }
+
+        for import in curve_imports {
+            let wasm_bridge::CurveImport { label, axis, curve } = import;
+            let diagnostic = |warning| {
+                Diagnostic::new(warning)
+                    .with("label", label)
+                    .with("axis", axis)
+            };
+
+            let mut available_labels = self
+                .labels
+                .iter()
+                .map(|l| &l.id)
+                .filter(|l| !label_removals.contains(*l))
+                .chain(label_additions.keys());
+            if !available_labels.any(|l| l == label) {
+                return Err(diagnostic(Warning::ImportCurveOfNonexistentLabel));
+            }
+
+            let axis_exists = (self.axes.borrow().axis(axis).is_some()
+                && !axis_removals.contains(axis))
+                || axis_additions.contains_key(axis);
+            if !axis_exists {
+                return Err(diagnostic(Warning::ImportCurveOfNonexistentAxis));
+            }
+
+            match curve {
+                wasm_bridge::ProbabilityCurve::Samples(samples) => {
+                    let in_range = samples.iter().all(|s| (0.0..=1.0).contains(s));
+                    if samples.len() < 2 || !in_range {
+                        return Err(diagnostic(Warning::InvalidCurveSamples));
+                    }
+                }
+                wasm_bridge::ProbabilityCurve::Segments {
+                    bounds,
+                    t_ranges,
+                    coefficients,
+                } => {
+                    let num_segments = bounds.len() / 2;
+                    let is_valid = num_segments != 0
+                        && bounds.len() == 2 * num_segments
+                        && t_ranges.len() == 2 * num_segments
+                        && coefficients.len() == 4 * num_segments
+                        && coefficients.iter().all(|c| c.is_finite())
+                        && t_ranges
+                            .chunks_exact(2)
+                            .all(|t| 0.0 <= t[0] && t[0] < t[1] && t[1] <= 1.0)
+                        && bounds.first().is_some_and(|&start| start >= 0.0)
+                        && bounds.last().is_some_and(|&end| end <= 1.0)
+                        && bounds.windows(2).all(|b| b[0] <= b[1])
+                        && bounds.chunks_exact(2).all(|b| b[0] < b[1]);
+                    if !is_valid {
+                        return Err(diagnostic(Warning::InvalidCurveSegments));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of data points of the axes that are not removed,
+    /// or `None` if no axis remains.
+    fn remaining_num_data_points(&self, axis_removals: &BTreeSet<String>) -> Option<usize> {
+        let guard = self.axes.borrow();
+        let remaining_axes = guard.axes().any(|ax| !axis_removals.contains(&*ax.key()));
+        remaining_axes.then(|| guard.num_data_points())
+    }
+
+    /// Pads the data points of the added and replaced axes with missing
+    /// values, such that all axes of the plot contain the same number of points.
+    fn pad_ragged_axes(&self, transaction: &mut wasm_bridge::StateTransaction) {
+        let wasm_bridge::StateTransaction {
+            axis_removals,
+            axis_additions,
+            axis_point_changes,
+            ..
+        } = transaction;
+
+        let num_data_points = ragged_num_data_points(
+            self.remaining_num_data_points(axis_removals),
+            axis_additions.values().map(|def| def.points.len()),
+        );
+
+        for axis in axis_additions.values_mut() {
+            pad_missing_points(&mut axis.points, num_data_points);
+        }
+
+        for points in axis_point_changes.values_mut() {
+            pad_missing_points(points, num_data_points);
+        }
     }
 
-    fn handle_transaction(&mut self, transaction: wasm_bridge::StateTransaction) -> bool {
-        if !self.validate_transaction(&transaction) {
-            web_sys::console::warn_1(&"Could not validate the transaction, rolling back.".into());
+    fn handle_transaction(&mut self, mut transaction: wasm_bridge::StateTransaction) -> bool {
+        if logging::enabled(wasm_bridge::LogLevel::Trace) {
+            logging::trace(&format!("{transaction:?}"));
+        }
+
+        if let Err(diagnostic) = self.validate_transaction(&transaction) {
+            self.report_diagnostic(diagnostic);
             return false;
         }
+        self.pad_ragged_axes(&mut transaction);
+
+        // The hovered element may be invalidated by the transaction.
+        self.set_hovered_element(None);
 
         let wasm_bridge::StateTransaction {
             axis_removals,
             axis_additions,
+            axis_point_changes,
+            axis_point_appends,
+            visible_range_changes,
+            range_fit,
+            axis_jitter_changes,
+            category_order_optimizations,
+            reference_clear,
+            reference_points,
             order_change,
+            view_change,
             colors_change,
             color_bar_visibility_change,
+            color_bar_placement_change,
+            color_bar_options_change,
+            axis_header_height_change,
+            curve_area_width_change,
+            all_selections_visibility_change,
+            label_agreement_metrics_change,
+            linear_blending_change,
+            pixel_snapping_change,
+            antialiasing_change,
+            data_line_join_change,
+            canvas_alpha_mode_change,
+            background_layer_change,
+            annotation_removals,
+            annotation_additions,
+            axis_region_removals,
+            axis_region_additions,
+            flow_ribbons_change,
+            label_mean_mode_change,
+            clusters_change,
+            outlier_scores_change,
+            density_curves_change,
+            histograms_change,
+            live_mode_change,
+            live_freeze_change,
             label_removals,
             label_additions,
             label_updates,
             active_label_change,
+            label_freezes,
+            snapshot_removals,
+            label_group_removals,
+            label_group_additions,
+            label_group_changes,
+            curve_comparison_change,
             brushes_change,
+            brush_edits,
+            curve_imports,
+            label_reverts,
+            operation_sync_change,
+            remote_operations,
             interaction_mode_change,
             debug_options_change,
+            hit_test_options_change,
+            control_point_options_change,
+            accessibility_options_change,
+            locale_options_change,
         } = transaction;
 
         let mut data_update = false;
 
+        // The outlier scores are recomputed whenever the data of the axes changes.
+        let data_changed = !axis_removals.is_empty()
+            || !axis_additions.is_empty()
+            || !axis_point_changes.is_empty()
+            || !axis_point_appends.is_empty();
+
         if !axis_removals.is_empty() {
             self.handled_events.signal_many(&[
                 event::Event::AXIS_STATE_CHANGE,
@@ -1904,12 +5827,111 @@ fn handle_transaction(&mut self, transaction: wasm_bridge::StateTransaction) ->
             self.add_axis(axis);
         }
 
+        // The curves of all labels are rebuilt by the data update, so that
+        // their probabilities are recomputed.
+        if !axis_point_changes.is_empty() {
+            data_update = true;
+            self.handled_events.signal_many(&[
+                event::Event::AXIS_STATE_CHANGE,
+                event::Event::SELECTIONS_CHANGE,
+            ]);
+        }
+        for (axis, points) in axis_point_changes {
+            self.axes.borrow().set_axis_data(&axis, points);
+        }
+
+        // Without the live mode, the appended points are applied immediately.
+        // Resuming the frozen data applies all queued points at once.
+        if let Some(options) = live_mode_change {
+            self.live_frozen &= options.is_some();
+            self.live_options = options;
+        }
+        if let Some(frozen) = live_freeze_change {
+            self.live_frozen = frozen;
+        }
+        self.stage_appended_points(axis_point_appends);
+        let apply_appended = self.live_options.is_none() || live_freeze_change == Some(false);
+        if apply_appended && self.apply_appended_points() {
+            self.staging_data.last_live_update = js_sys::Date::now();
+            data_update = true;
+            self.handled_events.signal_many(&[
+                event::Event::AXIS_STATE_CHANGE,
+                event::Event::SELECTIONS_CHANGE,
+            ]);
+        }
+
+        if let Some(options) = clusters_change {
+            data_update = true;
+            self.handled_events.signal_many(&[
+                event::Event::AXIS_STATE_CHANGE,
+                event::Event::AXIS_POSITION_CHANGE,
+                event::Event::AXIS_ORDER_CHANGE,
+                event::Event::SELECTIONS_CHANGE,
+            ]);
+            self.set_clusters(options);
+        }
+
+        let outlier_label_change = outlier_scores_change.is_some();
+        if let Some(options) = outlier_scores_change {
+            data_update = true;
+            self.handled_events.signal_many(&[
+                event::Event::AXIS_STATE_CHANGE,
+                event::Event::AXIS_POSITION_CHANGE,
+                event::Event::AXIS_ORDER_CHANGE,
+                event::Event::SELECTIONS_CHANGE,
+            ]);
+            self.set_outlier_scores(options);
+        } else if data_changed && self.outlier_options.is_some() {
+            self.staging_data.outlier_scores_update = true;
+        }
+
+        if !visible_range_changes.is_empty() {
+            data_update = true;
+            self.handled_events.signal(event::Event::AXIS_STATE_CHANGE);
+        }
+        for (axis, range) in visible_range_changes {
+            self.axes.borrow().set_visible_range(&axis, range);
+        }
+
+        if !axis_jitter_changes.is_empty() {
+            data_update = true;
+        }
+        for (axis, amplitude) in axis_jitter_changes {
+            self.axes.borrow().set_jitter(&axis, amplitude);
+        }
+
+        if reference_clear || !reference_points.is_empty() {
+            if reference_clear {
+                self.reference_data.clear();
+            }
+            self.reference_data.extend(reference_points);
+            self.update_reference_lines_buffer();
+        }
+
         if let Some(order) = order_change {
             data_update = true;
             self.handled_events.signal(event::Event::AXIS_ORDER_CHANGE);
             self.set_axes_order(order);
         }
 
+        // The view is moved once the axes of the transaction are in place.
+        if let Some(change) = view_change {
+            self.staging_data.view_change = Some(change);
+        }
+
+        if !category_order_optimizations.is_empty() {
+            data_update = true;
+            self.handled_events.signal(event::Event::AXIS_STATE_CHANGE);
+        }
+        for axis in category_order_optimizations {
+            self.optimize_category_order(&axis);
+        }
+
+        if let Some(options) = locale_options_change {
+            data_update = true;
+            self.change_locale_options(options);
+        }
+
         if let Some(colors) = colors_change {
             let wasm_bridge::Colors {
                 background,
@@ -1947,6 +5969,27 @@ fn handle_transaction(&mut self, transaction: wasm_bridge::StateTransaction) ->
         if let Some(visibility) = color_bar_visibility_change {
             self.set_color_bar_visibility(visibility);
         }
+        if let Some(placement) = color_bar_placement_change {
+            self.set_color_bar_placement(placement);
+        }
+        if let Some(options) = color_bar_options_change {
+            self.set_color_bar_options(options);
+        }
+
+        if let Some(height) = axis_header_height_change {
+            self.axes.borrow_mut().set_header_height(height);
+            self.handled_events
+                .signal(event::Event::AXIS_POSITION_CHANGE);
+            self.update_axes_buffer();
+            self.update_selection_lines_buffer();
+        }
+
+        if let Some(width) = curve_area_width_change {
+            self.axes.borrow_mut().set_curve_area_width(width);
+            self.handled_events
+                .signal(event::Event::AXIS_POSITION_CHANGE);
+            self.update_axes_buffer();
+        }
 
         if !label_removals.is_empty() {
             self.handled_events.signal(event::Event::SELECTIONS_CHANGE);
@@ -1955,52 +5998,219 @@ fn handle_transaction(&mut self, transaction: wasm_bridge::StateTransaction) ->
             self.remove_label(label);
         }
 
-        if !label_additions.is_empty() {
+        if !label_additions.is_empty() {
+            self.handled_events.signal(event::Event::SELECTIONS_CHANGE);
+        }
+        for (_, label) in label_additions {
+            let wasm_bridge::Label {
+                id,
+                color,
+                selection_bounds,
+                easing,
+                ..
+            } = label;
+            self.add_label(
+                id,
+                color,
+                selection_bounds,
+                easing.unwrap_or(selection::EasingType::Linear),
+            );
+        }
+
+        // The label of the outliers is created after the other labels, as it
+        // may be added by the same transaction.
+        if outlier_label_change {
+            self.handled_events.signal(event::Event::SELECTIONS_CHANGE);
+            self.add_outlier_label();
+        }
+
+        if !label_updates.is_empty() {
+            self.handled_events.signal(event::Event::SELECTIONS_CHANGE);
+        }
+        for (_, update) in label_updates {
+            let wasm_bridge::Label {
+                id,
+                color,
+                selection_bounds,
+                easing,
+                visible,
+            } = update;
+            if let Some(color) = color {
+                self.change_label_color(&id, Some(color));
+            }
+            if let Some(selection_bounds) = selection_bounds {
+                self.change_label_selection_bounds(&id, Some(selection_bounds));
+            }
+            if let Some(easing) = easing {
+                self.change_label_easing(&id, easing);
+            }
+            if let Some(visible) = visible {
+                self.change_label_visibility(&id, visible);
+            }
+        }
+
+        if let Some(active_label) = active_label_change {
+            self.change_active_label(active_label);
+        }
+
+        for group in label_group_removals {
+            self.remove_label_group(&group);
+        }
+        for (_, group) in label_group_additions {
+            let wasm_bridge::LabelGroup { id, color } = group;
+            self.add_label_group(id, color);
+        }
+        for (label, group) in label_group_changes {
+            self.set_label_group(&label, group);
+        }
+
+        if let Some(labels) = curve_comparison_change {
+            self.curve_comparison = labels;
+        }
+
+        for label in snapshot_removals {
+            self.discard_label_snapshot(&label);
+        }
+
+        // The snapshots are captured once the probabilities have been updated.
+        self.staging_data.label_freezes.extend(label_freezes);
+
+        if let Some(visibility) = all_selections_visibility_change {
+            self.set_all_selections_visibility(visibility);
+        }
+
+        if let Some(enabled) = label_agreement_metrics_change {
+            self.label_agreement_metrics = enabled;
+            self.handled_events.signal(event::Event::SELECTIONS_CHANGE);
+        }
+
+        if let Some(enabled) = linear_blending_change {
+            self.linear_blending = enabled;
+        }
+
+        if let Some(enabled) = pixel_snapping_change {
+            self.pixel_snapping = enabled;
+            self.update_axes_config_buffer();
+            self.update_selections_config_buffer();
+        }
+
+        if let Some(mode) = antialiasing_change {
+            self.antialiasing = mode;
+        }
+
+        if let Some(join) = data_line_join_change {
+            self.data_line_join = join;
+            self.update_data_config_buffer();
+        }
+
+        if let Some(mode) = canvas_alpha_mode_change {
+            self.set_canvas_alpha_mode(mode);
+        }
+
+        if let Some(layer) = background_layer_change {
+            self.set_background_layer(layer);
+        }
+
+        for id in annotation_removals {
+            self.annotations.remove(&id);
+        }
+        self.annotations.extend(annotation_additions);
+
+        for id in axis_region_removals {
+            self.axis_regions.remove(&id);
+        }
+        for (id, region) in axis_region_additions {
+            let wasm_bridge::AxisRegion {
+                axis,
+                range,
+                label,
+                color,
+            } = region;
+            let color = color
+                .map(|color| color.resolve_with_alpha())
+                .unwrap_or_else(DEFAULT_AXIS_REGION_COLOR);
+            self.axis_regions.insert(
+                id,
+                AxisRegionInfo {
+                    axis,
+                    range,
+                    label,
+                    color,
+                },
+            );
+        }
+
+        if let Some(enabled) = flow_ribbons_change {
+            self.flow_ribbons = enabled;
+        }
+
+        if let Some(mode) = label_mean_mode_change {
+            self.label_mean_mode = mode;
+            self.staging_data.label_means_update = true;
+        }
+
+        if let Some(options) = density_curves_change {
+            self.density_options = options;
+            self.update_density_buffers();
+        }
+
+        if let Some(options) = histograms_change {
+            self.histogram_options = options;
+            self.update_histogram_buffers();
+        }
+
+        if let Some(brushes) = brushes_change {
+            self.set_brushes(brushes);
+            self.handled_events.signal(event::Event::SELECTIONS_CHANGE);
+        }
+
+        if !brush_edits.is_empty() {
+            self.handled_events.signal(event::Event::SELECTIONS_CHANGE);
+        }
+        for edit in brush_edits {
+            self.edit_brush(edit);
+        }
+
+        if !curve_imports.is_empty() {
             self.handled_events.signal(event::Event::SELECTIONS_CHANGE);
         }
-        for (_, label) in label_additions {
-            let wasm_bridge::Label {
-                id,
-                color,
-                selection_bounds,
-                easing,
-            } = label;
-            self.add_label(
-                id,
-                color,
-                selection_bounds,
-                easing.unwrap_or(selection::EasingType::Linear),
-            );
+        for import in curve_imports {
+            self.import_curve(import);
         }
 
-        if !label_updates.is_empty() {
+        if !label_reverts.is_empty() {
             self.handled_events.signal(event::Event::SELECTIONS_CHANGE);
         }
-        for (_, update) in label_updates {
-            let wasm_bridge::Label {
-                id,
-                color,
-                selection_bounds,
-                easing,
-            } = update;
-            if let Some(color) = color {
-                self.change_label_color(&id, Some(color));
-            }
-            if let Some(selection_bounds) = selection_bounds {
-                self.change_label_selection_bounds(&id, Some(selection_bounds));
-            }
-            if let Some(easing) = easing {
-                self.change_label_easing(&id, easing);
-            }
+        for (label, step) in label_reverts {
+            self.revert_label(&label, step);
         }
 
-        if let Some(active_label) = active_label_change {
-            self.change_active_label(active_label);
+        if let Some(client) = operation_sync_change {
+            self.sync = client.map(|client| {
+                let guard = self.axes.borrow();
+                let order = guard
+                    .visible_axes()
+                    .map(|ax| ax.key().to_string())
+                    .collect();
+                sync::SyncState::new(client, self.current_brushes(), order)
+            });
+        }
+        for operation in remote_operations {
+            self.apply_remote_operation(operation);
         }
 
-        if let Some(brushes) = brushes_change {
-            self.set_brushes(brushes);
-            self.handled_events.signal(event::Event::SELECTIONS_CHANGE);
+        // The ranges are fitted once the probabilities have been updated.
+        if let Some(fit) = range_fit {
+            use wasm_bridge::FitAxes;
+            let pending = self.staging_data.range_fit.take();
+            self.staging_data.range_fit = Some(match (pending, fit) {
+                (None, fit) => fit,
+                (Some(FitAxes::Axes(mut pending)), FitAxes::Axes(axes)) => {
+                    pending.extend(axes);
+                    FitAxes::Axes(pending)
+                }
+                _ => FitAxes::All,
+            });
         }
 
         if let Some(mode) = interaction_mode_change {
@@ -2011,38 +6221,153 @@ fn handle_transaction(&mut self, transaction: wasm_bridge::StateTransaction) ->
             self.change_debug_options(options);
         }
 
+        if let Some(options) = hit_test_options_change {
+            self.change_hit_test_options(options);
+        }
+
+        if let Some(options) = control_point_options_change {
+            self.change_control_point_options(options);
+        }
+
+        if let Some(options) = accessibility_options_change {
+            self.change_accessibility_options(options);
+        }
+
         true
     }
 
-    fn pointer_down(&mut self, event: web_sys::PointerEvent) {
-        if !event.is_primary() || event.button() != 0 {
+    fn pointer_down(&mut self, event: wasm_bridge::PointerInput) {
+        if !event.is_primary() {
+            return;
+        }
+        self.trace_pointer("down", &event);
+        if self.forward_header_pointer(&event, api::HeaderPointerKind::Down) {
+            return;
+        }
+        if event.button() != 0 {
             return;
         }
 
         self.create_action(event);
     }
 
-    fn pointer_up(&mut self, event: web_sys::PointerEvent) {
-        if !event.is_primary() || (event.button() != 0 && event.button() != -1) {
+    fn pointer_up(&mut self, event: wasm_bridge::PointerInput) {
+        if !event.is_primary() {
+            return;
+        }
+        self.trace_pointer("up", &event);
+        if self.forward_header_pointer(&event, api::HeaderPointerKind::Up) {
+            return;
+        }
+        if event.button() != 0 && event.button() != -1 {
             return;
         }
 
         self.finish_action();
     }
 
-    fn pointer_move(&mut self, event: web_sys::PointerEvent) {
+    fn pointer_move(&mut self, event: wasm_bridge::PointerInput) {
         if !event.is_primary() {
             return;
         }
+        self.trace_pointer("move", &event);
+        if self.forward_header_pointer(&event, api::HeaderPointerKind::Move) {
+            return;
+        }
 
+        self.update_curve_readout(&event);
         self.update_action(event);
     }
+
+    /// Records the pointer input for the interaction trace of the debug options.
+    fn trace_pointer(&mut self, kind: &'static str, event: &wasm_bridge::PointerInput) {
+        let position =
+            Position::<ScreenSpace>::new((event.offset_x() as f32, event.offset_y() as f32));
+
+        if self.debug.show_interaction_trace {
+            let trail = &mut self.staging_data.pointer_trail;
+            if trail.len() == POINTER_TRAIL_LENGTH {
+                trail.pop_front();
+            }
+            trail.push_back(position);
+            self.events.push(event::Event::REDRAW);
+        }
+
+        if self.debug.log_events {
+            self.staging_data.pointer_trace.push((kind, position));
+        }
+    }
+
+    /// Shows the probability of the active label at the hovered value, while
+    /// the pointer lies inside of the curve area of an expanded axis.
+    fn update_curve_readout(&mut self, event: &wasm_bridge::PointerInput) {
+        let position =
+            Position::<ScreenSpace>::new((event.offset_x() as f32, event.offset_y() as f32));
+        let readout = self
+            .active_label_idx
+            .filter(|&label_idx| self.labels[label_idx].visible)
+            .and_then(|label_idx| {
+                let (axis, axis_value) = self.axes.borrow().curve_area_at_position(position)?;
+                let curve = axis.borrow_selection_curve(label_idx);
+                let probability = curve.spline().evaluate(axis_value);
+                Some((position, format!("{probability:.2}")))
+            });
+
+        if readout.is_some() || self.staging_data.curve_readout.is_some() {
+            self.staging_data.curve_readout = readout;
+            self.events.push(event::Event::REDRAW);
+        }
+    }
+
+    /// Forwards the pointer event to the host application, if it lies inside
+    /// of the header slot of an axis and no action is active.
+    fn forward_header_pointer(
+        &mut self,
+        event: &wasm_bridge::PointerInput,
+        kind: api::HeaderPointerKind,
+    ) -> bool {
+        if self.active_action.is_some() {
+            return false;
+        }
+
+        let position =
+            Position::<ScreenSpace>::new((event.offset_x() as f32, event.offset_y() as f32));
+        let Some(axis) = self.axes.borrow().header_at_position(position) else {
+            return false;
+        };
+        let Some(header_box) = self.header_box(&axis) else {
+            return false;
+        };
+
+        if kind == api::HeaderPointerKind::Move {
+            self.canvas_2d
+                .style()
+                .set_property("cursor", "default")
+                .unwrap();
+            self.set_hovered_element(None);
+        }
+
+        let (x, y) = position.extract();
+        let value = api::HeaderPointerValue {
+            axis: axis.key().to_string(),
+            kind,
+            position: (x - header_box.x, y - header_box.y),
+            button: event.button(),
+            modifiers: api::PointerModifiers::of(event),
+        };
+        self.staging_data.header_pointer_events.push(value);
+        self.handled_events.signal(event::Event::HEADER_POINTER);
+        self.events.push(event::Event::REDRAW);
+        true
+    }
 }
 
 // Actions
 impl Renderer {
-    fn create_action(&mut self, event: web_sys::PointerEvent) {
+    fn create_action(&mut self, event: wasm_bridge::PointerInput) {
         self.finish_action();
+        self.set_hovered_element(None);
+        self.pointer_scale = self.hit_test_scale(&event);
 
         if self.interaction_mode == wasm_bridge::InteractionMode::Disabled {
             return;
@@ -2051,6 +6376,13 @@ fn create_action(&mut self, event: web_sys::PointerEvent) {
         let position =
             Position::<ScreenSpace>::new((event.offset_x() as f32, event.offset_y() as f32));
 
+        // The floating color bar lies above the axes.
+        if self.is_floating_color_bar_at(position) {
+            let start_position = self.color_bar.bounding_box().start();
+            self.active_action = Some(action::Action::new_move_color_bar(event, start_position));
+            return;
+        }
+
         use wasm_bridge::InteractionMode;
         let enable_reorder = !matches!(self.interaction_mode, InteractionMode::Disabled);
         let enable_modification = matches!(
@@ -2059,7 +6391,8 @@ fn create_action(&mut self, event: web_sys::PointerEvent) {
         );
 
         let axes = self.axes.borrow();
-        let element = axes.element_at_position(position, self.active_label_idx);
+        let element =
+            axes.element_at_position(position, self.active_label_idx, self.hit_test_scale(&event));
         if let Some(element) = element {
             match element {
                 axis::Element::Label { axis } if enable_reorder => {
@@ -2070,6 +6403,16 @@ fn create_action(&mut self, event: web_sys::PointerEvent) {
                         self.interaction_mode,
                     ))
                 }
+                axis::Element::MinLabel { axis } | axis::Element::MaxLabel { axis }
+                    if enable_modification =>
+                {
+                    // The range is edited by the host application, which
+                    // responds with a transaction.
+                    self.staging_data.range_edit_request = Some(axis.key().to_string());
+                    self.handled_events
+                        .signal(event::Event::AXIS_RANGE_EDIT_REQUEST);
+                    self.events.push(event::Event::REDRAW);
+                }
                 axis::Element::Group { axis, group_idx } if enable_modification => {
                     if let Some(active_label_idx) = self.active_label_idx {
                         self.active_action = Some(action::Action::new_select_group(
@@ -2133,18 +6476,48 @@ fn create_action(&mut self, event: web_sys::PointerEvent) {
                         ))
                     }
                 }
+                axis::Element::HistogramBin { axis, bin } if enable_modification => {
+                    let layout = axes.histogram_layout();
+                    if let (Some(active_label_idx), Some(layout)) = (self.active_label_idx, layout)
+                    {
+                        self.active_action = Some(action::Action::new_create_histogram_brush(
+                            axis,
+                            bin,
+                            layout.num_bins,
+                            active_label_idx,
+                            self.labels[active_label_idx].easing,
+                        ))
+                    }
+                }
                 _ => {}
             }
         }
     }
 
-    fn update_action(&mut self, event: web_sys::PointerEvent) {
+    fn update_action(&mut self, event: wasm_bridge::PointerInput) {
+        self.pointer_scale = self.hit_test_scale(&event);
         if let Some(action) = &mut self.active_action {
             self.events.push(action.update(event));
+            if let Some(position) = action.color_bar_position() {
+                let (x, y) = position.extract();
+                self.color_bar
+                    .set_placement(color_bar::ColorBarPlacement::Floating { x, y });
+            }
         } else {
             let position =
                 Position::<ScreenSpace>::new((event.offset_x() as f32, event.offset_y() as f32));
 
+            if self.interaction_mode != wasm_bridge::InteractionMode::Disabled
+                && self.is_floating_color_bar_at(position)
+            {
+                self.canvas_2d
+                    .style()
+                    .set_property("cursor", "move")
+                    .unwrap();
+                self.set_hovered_element(None);
+                return;
+            }
+
             use wasm_bridge::InteractionMode;
             let enable_reorder = !matches!(self.interaction_mode, InteractionMode::Disabled);
             let enable_modification = matches!(
@@ -2153,13 +6526,25 @@ fn update_action(&mut self, event: web_sys::PointerEvent) {
             );
 
             let axes = self.axes.borrow();
-            let element = axes.element_at_position(position, self.active_label_idx);
+            let element = axes.element_at_position(
+                position,
+                self.active_label_idx,
+                self.hit_test_scale(&event),
+            );
             match element {
                 Some(axis::Element::Label { .. }) if enable_reorder => self
                     .canvas_2d
                     .style()
                     .set_property("cursor", "ew-resize")
                     .unwrap(),
+                Some(axis::Element::MinLabel { .. } | axis::Element::MaxLabel { .. })
+                    if enable_modification =>
+                {
+                    self.canvas_2d
+                        .style()
+                        .set_property("cursor", "pointer")
+                        .unwrap()
+                }
                 Some(axis::Element::Group { .. }) if enable_modification => self
                     .canvas_2d
                     .style()
@@ -2185,79 +6570,470 @@ fn update_action(&mut self, event: web_sys::PointerEvent) {
                     .style()
                     .set_property("cursor", "crosshair")
                     .unwrap(),
+                Some(axis::Element::HistogramBin { .. }) if enable_modification => self
+                    .canvas_2d
+                    .style()
+                    .set_property("cursor", "pointer")
+                    .unwrap(),
                 _ => self
                     .canvas_2d
                     .style()
                     .set_property("cursor", "default")
                     .unwrap(),
             }
+
+            let hovered_element = match element {
+                Some(
+                    axis::Element::Group { .. }
+                    | axis::Element::Brush { .. }
+                    | axis::Element::AxisControlPoint { .. }
+                    | axis::Element::CurveControlPoint { .. },
+                ) if enable_modification => element,
+                _ => None,
+            };
+            drop(axes);
+            self.set_hovered_element(hovered_element);
+        }
+    }
+
+    fn is_floating_color_bar_at(&self, position: Position<ScreenSpace>) -> bool {
+        self.color_bar.is_visible()
+            && self.color_bar.is_floating()
+            && self.color_bar.bounding_box().contains_point(&position)
+    }
+
+    fn set_hovered_element(&mut self, element: Option<axis::Element>) {
+        if self.hovered_element != element {
+            self.hovered_element = element;
+            self.events.push(event::Event::HOVER_CHANGE);
+        }
+    }
+
+    fn hit_test_scale(&self, event: &wasm_bridge::PointerInput) -> f32 {
+        match event.pointer_type().as_str() {
+            "touch" => self.hit_test.touch_scale,
+            "pen" => self.hit_test.pen_scale,
+            _ => 1.0,
+        }
+    }
+
+    fn tick_action(&mut self) {
+        if let Some(action) = &mut self.active_action {
+            let events = action.tick(self.accessibility.reduced_motion);
+            if events.has_events() {
+                self.events.push(events);
+            }
+        }
+    }
+
+    /// Applies the appended points at the cadence of the live mode.
+    ///
+    /// The points are held back while the data is frozen, or while the user
+    /// is interacting with the plot, such that the brushes are edited on a
+    /// stable snapshot of the data.
+    fn tick_live_data(&mut self) {
+        self.live_update = None;
+        let Some(options) = self.live_options else {
+            return;
+        };
+        if self.live_frozen || self.active_action.is_some() {
+            self.schedule_live_update();
+            return;
+        }
+
+        let now = js_sys::Date::now();
+        if now - self.staging_data.last_live_update < options.interval {
+            self.schedule_live_update();
+            return;
+        }
+
+        if self.apply_appended_points() {
+            self.staging_data.last_live_update = now;
+            self.update_data();
+            if self.outlier_options.is_some() {
+                self.staging_data.outlier_scores_update = true;
+            }
+            self.events.push(event::Event::AXIS_STATE_CHANGE);
+            self.events.push(event::Event::SELECTIONS_CHANGE);
+        }
+    }
+
+    /// Requests a draw once the queued points are due at the cadence of the
+    /// live mode, such that they are applied without further draws of the
+    /// embedding page. Frozen points wait for the data to be resumed.
+    fn schedule_live_update(&mut self) {
+        let Some(options) = self.live_options else {
+            return;
+        };
+        if self.live_frozen
+            || self.live_update.is_some()
+            || self.draw_scheduler.is_some()
+            || self.staging_data.num_appended_points == 0
+        {
+            return;
+        }
+
+        let Some(sender) = self.event_sender.clone() else {
+            return;
+        };
+        let elapsed = js_sys::Date::now() - self.staging_data.last_live_update;
+        let delay = (options.interval - elapsed).max(0.0);
+        self.live_update = Some(scheduler::DelayedDraw::new(sender, delay));
+    }
+
+    fn tick_axis_expansion(&mut self) {
+        let step = if self.accessibility.reduced_motion {
+            1.0
+        } else {
+            AXIS_EXPANSION_STEP
+        };
+
+        if self.axes.borrow().tick_expansions(step) {
+            self.events.push(event::Event::AXIS_POSITION_CHANGE);
+        }
+    }
+
+    /// Moves the view towards the window requested by the last view change.
+    fn tick_view(&mut self) {
+        let Some(change) = &self.staging_data.view_change else {
+            return;
+        };
+
+        let guard = self.axes.borrow();
+        let target = match &change.target {
+            wasm_bridge::ViewTarget::Default => Some(guard.default_view_window()),
+            wasm_bridge::ViewTarget::Axes(keys) => {
+                guard.framing_window(keys.iter().map(|key| key.as_str()))
+            }
+            wasm_bridge::ViewTarget::Axis(key) => guard.centered_window(key),
+        };
+        // The axes may have been hidden since the change was requested.
+        let Some(target) = target else {
+            self.staging_data.view_change = None;
+            return;
+        };
+
+        let step = if !change.animate || self.accessibility.reduced_motion {
+            1.0
+        } else {
+            VIEW_ANIMATION_STEP
+        };
+        let window = step_view_window(guard.view_window(), target, step);
+        if window == target {
+            self.staging_data.view_change = None;
         }
+        guard.set_view_window(Some(window));
+        self.events.push(event::Event::VIEW_CHANGE);
     }
 
     fn finish_action(&mut self) {
         if let Some(action) = self.active_action.take() {
+            self.staging_data.action_modifiers = Some(action.modifiers());
             self.events.push(action.finish());
+            if self.staging_data.deferred_resample {
+                self.events.push(event::Event::REDRAW);
+            }
         }
     }
 }
 
 // Shared buffers
 impl Renderer {
+    /// Returns the size of the viewport in device pixels, to which the axis
+    /// geometry is snapped, or zero if the snapping is disabled.
+    fn snap_resolution(&self) -> wgsl::Vec2<f32> {
+        if !self.pixel_snapping {
+            return wgsl::Vec2([0.0, 0.0]);
+        }
+
+        let (_, (width, height)) = self.axes.borrow().viewport(self.pixel_ratio);
+        wgsl::Vec2([width, height])
+    }
+
+    fn line_width_scale(&self) -> f32 {
+        if self.accessibility.high_contrast {
+            HIGH_CONTRAST_LINE_SCALE
+        } else {
+            1.0
+        }
+    }
+
     fn update_matrix_buffer(&mut self) {
         let guard = self.axes.borrow();
-        self.buffers.shared_mut().matrices_mut().update(
-            &self.device,
-            &buffers::Matrices::new(guard.num_visible_axes()),
+        let matrices = if guard.is_view_zoomed() {
+            buffers::Matrices::with_view(guard.view_window())
+        } else {
+            buffers::Matrices::new(guard.num_visible_axes())
+        };
+        self.buffers
+            .shared_mut()
+            .matrices_mut()
+            .update(&self.device, &matrices);
+    }
+
+    fn update_axes_buffer(&mut self) {
+        let guard = self.axes.borrow();
+        let mut axes = Vec::new();
+        axes.resize_with(guard.visible_axes().len(), MaybeUninit::uninit);
+
+        for ax in guard.visible_axes() {
+            let range = ax.axis_line_range();
+            let range = (
+                range.0.transform(&ax.space_transformer()),
+                range.1.transform(&ax.space_transformer()),
+            );
+            let range = [
+                range.0.extract::<(f32, f32)>().1,
+                range.1.extract::<(f32, f32)>().1,
+            ];
+
+            let extends = ax
+                .expanded_extends(self.active_label_idx)
+                .transform(&ax.space_transformer());
+            let extends = [extends.start().x, extends.end().x];
+
+            axes[ax.axis_index().unwrap()].write(buffers::Axis {
+                expanded_val: ax.expansion(),
+                center_x: ax.world_offset(),
+                position_x: wgsl::Vec2(extends),
+                range_y: wgsl::Vec2(range),
+            });
+        }
+        self.buffers
+            .shared_mut()
+            .axes_mut()
+            .update(&self.device, &axes);
+    }
+
+    fn update_axis_regions_buffer(&mut self) {
+        let guard = self.axes.borrow();
+        let (half_width, _) = guard.selections_line_size();
+        let half_width = half_width.0 * 2.0;
+
+        let regions = self
+            .axis_regions
+            .values()
+            .filter_map(|region| {
+                let ax = guard.axis(&region.axis)?;
+                let axis_index = ax.axis_index()?;
+
+                // The regions are clipped to the visible range of the axis.
+                let (min, max) = ax.visible_data_range();
+                let (start, end) = region.range;
+                let range = [
+                    start.inv_lerp(min, max).clamp(0.0, 1.0),
+                    end.inv_lerp(min, max).clamp(0.0, 1.0),
+                ];
+                if range[0] >= range[1] {
+                    return None;
+                }
+
+                Some(buffers::AxisRegion {
+                    axis: axis_index as u32,
+                    half_width,
+                    range: wgsl::Vec2(range),
+                    color: wgsl::Vec4(region.color.to_f32_with_alpha()),
+                })
+            })
+            .collect::<Vec<_>>();
+        self.buffers
+            .axes_mut()
+            .regions_mut()
+            .update(&self.device, &regions);
+    }
+
+    /// Returns the sorted distinct values of a categorical axis, or `None`
+    /// if the axis has too many distinct values.
+    fn axis_categories(ax: &axis::Axis) -> Option<Vec<f32>> {
+        let mut categories = Vec::new();
+        for &value in ax.data().iter().filter(|v| v.is_finite()) {
+            if let Err(idx) = categories.binary_search_by(|c: &f32| c.total_cmp(&value)) {
+                if categories.len() == buffers::MAX_FLOW_CATEGORIES {
+                    return None;
+                }
+                categories.insert(idx, value);
+            }
+        }
+
+        (!categories.is_empty()).then_some(categories)
+    }
+
+    /// Reorders the categories of an axis, such that the lines to its
+    /// neighboring axes cross as little as possible.
+    fn optimize_category_order(&mut self, key: &str) {
+        let guard = self.axes.borrow();
+        let axis = guard.axis(key).expect("axis should exist");
+        let Some(categories) = Self::axis_categories(&axis) else {
+            return;
+        };
+
+        let assignments = axis
+            .data()
+            .iter()
+            .map(|value| categories.binary_search_by(|c| c.total_cmp(value)).ok())
+            .collect::<Vec<_>>();
+        let neighbors = [axis.left_neighbor(), axis.right_neighbor()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        let neighbor_values = neighbors
+            .iter()
+            .map(|neighbor| neighbor.data_normalized())
+            .collect::<Vec<_>>();
+        let neighbor_values = neighbor_values.iter().map(|v| &**v).collect::<Vec<_>>();
+
+        let order = optimizer::minimize_category_crossings(
+            categories.len(),
+            &assignments,
+            &neighbor_values,
         );
+        let order = order.iter().map(|&i| categories[i]).collect::<Vec<_>>();
+        guard.set_category_order(key, &order);
     }
 
-    fn update_axes_buffer(&mut self) {
-        let guard = self.axes.borrow();
-        let mut axes = Vec::new();
-        axes.resize_with(guard.visible_axes().len(), MaybeUninit::uninit);
+    fn update_flow_buffers(&mut self) {
+        if !self.flow_ribbons {
+            self.buffers.flows_mut().set_pairs(&self.device, &[], &[]);
+            return;
+        }
 
-        for ax in guard.visible_axes() {
-            let range = ax.axis_line_range();
-            let range = (
-                range.0.transform(&ax.space_transformer()),
-                range.1.transform(&ax.space_transformer()),
-            );
-            let range = [
-                range.0.extract::<(f32, f32)>().1,
-                range.1.extract::<(f32, f32)>().1,
-            ];
+        let guard = self.axes.borrow();
+        let visible_axes = guard.visible_axes().collect::<Vec<_>>();
 
-            let extends = ax
-                .expanded_extends(self.active_label_idx)
-                .transform(&ax.space_transformer());
-            let extends = [extends.start().x, extends.end().x];
+        let mut pairs = Vec::new();
+        let mut categories = Vec::new();
+        for axes in visible_axes.windows(2) {
+            let (a, b) = (&axes[0], &axes[1]);
+            let (Some(axis_a), Some(axis_b)) = (a.axis_index(), b.axis_index()) else {
+                continue;
+            };
+            let (Some(categories_a), Some(categories_b)) =
+                (Self::axis_categories(a), Self::axis_categories(b))
+            else {
+                continue;
+            };
 
-            axes[ax.axis_index().unwrap()].write(buffers::Axis {
-                expanded_val: if ax.is_expanded() { 1.0 } else { 0.0 },
-                center_x: ax.world_offset(),
-                position_x: wgsl::Vec2(extends),
-                range_y: wgsl::Vec2(range),
+            // The categories are placed at their positions inside of the visible range.
+            let positions = |ax: &axis::Axis, values: &[f32]| {
+                let (min, max) = ax.visible_data_range();
+                let mut positions = [0.0; buffers::MAX_FLOW_CATEGORIES];
+                for (position, &value) in positions.iter_mut().zip(values) {
+                    *position = if min == max {
+                        0.5
+                    } else {
+                        ax.category_position(value).inv_lerp(min, max)
+                    };
+                }
+                positions
+            };
+            let positions_a = positions(a, &categories_a);
+            let positions_b = positions(b, &categories_b);
+
+            // The ribbons of a category may not overlap the neighboring categories.
+            let min_gap = positions_a[..categories_a.len()]
+                .windows(2)
+                .chain(positions_b[..categories_b.len()].windows(2))
+                .map(|w| (w[1] - w[0]).abs())
+                .fold(f32::INFINITY, f32::min);
+            let scale = (min_gap * 0.9).min(0.5);
+
+            pairs.push(buffers::FlowPair {
+                axis_a: axis_a as u32,
+                axis_b: axis_b as u32,
+                num_categories_a: categories_a.len() as u32,
+                num_categories_b: categories_b.len() as u32,
+                scale,
+                positions_a,
+                positions_b,
             });
+
+            let category = |categories: &[f32], value: f32| {
+                categories.binary_search_by(|c| c.total_cmp(&value)).ok()
+            };
+            let (data_a, data_b) = (a.data(), b.data());
+            categories.extend(
+                data_a
+                    .iter()
+                    .zip(data_b.iter())
+                    .map(|(&value_a, &value_b)| {
+                        let category_a = category(&categories_a, value_a);
+                        let category_b = category(&categories_b, value_b);
+                        match (category_a, category_b) {
+                            (Some(a), Some(b)) => (a * buffers::MAX_FLOW_CATEGORIES + b) as u32,
+                            _ => buffers::FlowPair::MISSING_CATEGORY,
+                        }
+                    }),
+            );
         }
+        drop(guard);
+
         self.buffers
-            .shared_mut()
-            .axes_mut()
-            .update(&self.device, &axes);
+            .flows_mut()
+            .set_pairs(&self.device, &pairs, &categories);
+    }
+
+    /// Counts the rows taking each pair of categories of the adjacent axes.
+    ///
+    /// With an active label, only its selected rows are counted.
+    fn aggregate_flows(&mut self, encoder: &webgpu::CommandEncoder) {
+        let num_data_points = self.axes.borrow().num_data_points();
+        let (config, probabilities) = if let Some(active_label_idx) = self.active_label_idx {
+            let label = &self.labels[active_label_idx];
+            let (color, _) = label.colors(&self.label_groups);
+            let config = buffers::FlowConfig {
+                color: wgsl::Vec4(color.with_alpha(0.35).to_f32_with_alpha()),
+                selection_bounds: wgsl::Vec2(label.selection_bounds.into()),
+                num_datums: num_data_points as u32,
+                use_selection: 1,
+            };
+            let probabilities = self.buffers.data().probabilities(active_label_idx).clone();
+            (config, probabilities)
+        } else {
+            let config = buffers::FlowConfig {
+                color: wgsl::Vec4(DEFAULT_FLOW_COLOR().to_f32_with_alpha()),
+                selection_bounds: wgsl::Vec2([0.0, 1.0]),
+                num_datums: num_data_points as u32,
+                use_selection: 0,
+            };
+            (config, buffers::ProbabilitiesBuffer::empty(&self.device))
+        };
+
+        self.buffers
+            .flows_mut()
+            .update_config(&self.device, &config);
+        self.pipelines.compute().flow_aggregation().dispatch(
+            self.buffers.flows(),
+            &probabilities,
+            num_data_points,
+            &self.device,
+            encoder,
+        );
     }
 
     fn update_label_colors_buffer(&mut self) {
+        let alpha = if self.accessibility.high_contrast {
+            1.0
+        } else {
+            0.5
+        };
         let colors = self
             .labels
             .iter()
-            .map(|l| buffers::LabelColor {
-                color_high: wgsl::Vec4(l.color.with_alpha(0.5).to_f32_with_alpha()),
-                color_low: wgsl::Vec4(l.color_dimmed.with_alpha(0.5).to_f32_with_alpha()),
+            .map(|l| {
+                let (color, color_dimmed) = l.colors(&self.label_groups);
+                buffers::LabelColor {
+                    color_high: wgsl::Vec4(color.with_alpha(alpha).to_f32_with_alpha()),
+                    color_low: wgsl::Vec4(color_dimmed.with_alpha(alpha).to_f32_with_alpha()),
+                }
             })
             .collect::<Vec<_>>();
         self.buffers
             .shared_mut()
             .label_colors_mut()
             .update(&self.device, &colors);
+
+        self.update_snapshot_config_buffers();
+        self.update_axes_config_buffer();
     }
 
     fn update_color_scale_texture(
@@ -2265,17 +7041,7 @@ fn update_color_scale_texture(
         color_space: wasm_bridge::ColorSpace,
         scale: color_scale::ColorScale<colors::UnknownColorSpace>,
     ) {
-        let color_scale_elements = scale
-            .get_scale()
-            .iter()
-            .copied()
-            .map(|(t, c)| buffers::ColorScaleElement {
-                t,
-                color: wgsl::Vec4(c.to_f32_with_alpha()),
-            })
-            .collect::<Vec<_>>();
-        let color_scale_elements =
-            buffers::ColorScaleElementBuffer::new(&self.device, &color_scale_elements);
+        let color_scale_elements = self.create_color_scale_elements(&scale);
 
         let encoder = self
             .device
@@ -2292,24 +7058,39 @@ fn update_color_scale_texture(
         self.device.queue().submit(&[encoder.finish(None)]);
     }
 
+    fn create_color_scale_elements(
+        &self,
+        scale: &color_scale::ColorScale<colors::UnknownColorSpace>,
+    ) -> buffers::ColorScaleElementBuffer {
+        let color_scale_elements = scale
+            .get_scale()
+            .iter()
+            .copied()
+            .map(|(t, c)| buffers::ColorScaleElement {
+                t,
+                color: wgsl::Vec4(c.to_f32_with_alpha()),
+            })
+            .collect::<Vec<_>>();
+        buffers::ColorScaleElementBuffer::new(&self.device, &color_scale_elements)
+    }
+
     fn update_color_scale_bounds_buffer(&mut self) {
-        if let Some(active_label_idx) = self.active_label_idx {
-            let color_mode = self.color_bar.color_mode();
-            let bounds = match color_mode {
-                color_bar::ColorBarColorMode::Color => buffers::ColorScaleBounds {
-                    start: 0.0,
-                    end: 1.0,
-                },
-                color_bar::ColorBarColorMode::Probability => buffers::ColorScaleBounds {
-                    start: self.labels[active_label_idx].selection_bounds.0,
-                    end: self.labels[active_label_idx].selection_bounds.1,
-                },
-            };
-            self.buffers
-                .shared_mut()
-                .color_scale_bounds_mut()
-                .update(&self.device, &bounds);
-        }
+        let (start, end) = match self.color_bar.color_mode() {
+            color_bar::ColorBarColorMode::Color => (0.0, 1.0),
+            color_bar::ColorBarColorMode::Probability => match self.active_label_idx {
+                Some(active_label_idx) => self.labels[active_label_idx].selection_bounds,
+                None => return,
+            },
+        };
+        let bounds = buffers::ColorScaleBounds {
+            start,
+            end,
+            horizontal: self.color_bar.is_horizontal() as u32,
+        };
+        self.buffers
+            .shared_mut()
+            .color_scale_bounds_mut()
+            .update(&self.device, &bounds);
     }
 }
 
@@ -2318,11 +7099,25 @@ impl Renderer {
     fn update_axes_config_buffer(&mut self) {
         let guard = self.axes.borrow();
         let (width, height) = guard.axis_line_size();
+        let scale = self.line_width_scale();
+        let snap_resolution = self.snap_resolution();
+        let color = if self.accessibility.high_contrast {
+            [0.0, 0.0, 0.0]
+        } else {
+            [0.8, 0.8, 0.8]
+        };
+        // The markers of the selection bounds share the color of the active label.
+        let marker_color = self.active_label_idx.map_or(color, |label_idx| {
+            let (label_color, _) = self.labels[label_idx].colors(&self.label_groups);
+            label_color.transform::<SRgb>().to_f32()
+        });
         self.buffers.axes_mut().config_mut().update(
             &self.device,
             &buffers::AxesConfig {
-                line_width: wgsl::Vec2([width.0, height.0]),
-                color: wgsl::Vec3([0.8, 0.8, 0.8]),
+                line_width: wgsl::Vec2([width.0 * scale, height.0 * scale]),
+                snap_resolution,
+                color: wgsl::Vec3(color),
+                marker_color: wgsl::Vec3(marker_color),
             },
         );
     }
@@ -2344,16 +7139,19 @@ fn update_axes_lines_buffer(&mut self) {
                 axis: index as u32,
                 axis_position: buffers::AxisLineInfo::CENTER,
                 min_expanded_val: 0.0,
+                marker: 0,
             });
             lines.push(MaybeUninit::new(buffers::AxisLineInfo {
                 axis: index as u32,
                 axis_position: buffers::AxisLineInfo::LEFT,
                 min_expanded_val: 1.0,
+                marker: 0,
             }));
             lines.push(MaybeUninit::new(buffers::AxisLineInfo {
                 axis: index as u32,
                 axis_position: buffers::AxisLineInfo::RIGHT,
                 min_expanded_val: 1.0,
+                marker: 0,
             }));
 
             for t in [0.0, 0.25, 0.5, 0.75, 1.0] {
@@ -2362,10 +7160,29 @@ fn update_axes_lines_buffer(&mut self) {
                     axis: index as u32,
                     axis_position,
                     min_expanded_val: 1.0,
+                    marker: 0,
                 }));
             }
         }
 
+        // The selection bounds of the active label are marked in the curve
+        // area of the expanded axes, on top of the other lines.
+        if let Some(label_idx) = self.active_label_idx {
+            let (lower, upper) = self.labels[label_idx].selection_bounds;
+            for ax in guard.visible_axes() {
+                let index = ax.axis_index().unwrap();
+                for t in [lower, upper] {
+                    let axis_position = curve_t_min.lerp(curve_t_max, t);
+                    lines.push(MaybeUninit::new(buffers::AxisLineInfo {
+                        axis: index as u32,
+                        axis_position,
+                        min_expanded_val: 1.0,
+                        marker: 1,
+                    }));
+                }
+            }
+        }
+
         self.buffers
             .axes_mut()
             .lines_mut()
@@ -2403,24 +7220,225 @@ fn update_data_config_buffer(&mut self) {
                 buffers::DataLineConfig::ORDER_SELECTED_PROBABILITY_INVERTED
             }
         };
+        let line_join = match self.data_line_join {
+            wasm_bridge::DataLineJoin::Butt => buffers::DataLineConfig::LINE_JOIN_BUTT,
+            wasm_bridge::DataLineJoin::Round => buffers::DataLineConfig::LINE_JOIN_ROUND,
+            wasm_bridge::DataLineJoin::Miter => buffers::DataLineConfig::LINE_JOIN_MITER,
+        };
         let (width, height) = guard.data_line_size();
+        let scale = self.line_width_scale();
         self.buffers.data_mut().config_mut().update(
             &self.device,
             &buffers::DataLineConfig {
-                line_width: wgsl::Vec2([width.0, height.0]),
+                line_width: wgsl::Vec2([width.0 * scale, height.0 * scale]),
                 selection_bounds: wgsl::Vec2(selection_bounds.into()),
                 color_probabilities,
                 render_order,
+                line_join,
                 unselected_color: wgsl::Vec4(self.unselected_color.to_f32_with_alpha()),
             },
         );
+        self.buffers.data_mut().reference_config_mut().update(
+            &self.device,
+            &buffers::ReferenceConfig {
+                line_width: wgsl::Vec2([width.0 * scale, height.0 * scale]),
+                color: wgsl::Vec4(DEFAULT_REFERENCE_COLOR().to_f32_with_alpha()),
+            },
+        );
+        drop(guard);
+
+        self.update_snapshot_config_buffers();
+        self.update_density_buffers();
+        self.update_histogram_buffers();
+    }
+
+    /// Updates the config and visible ranges of the density curves, and
+    /// schedules a new estimation of the densities.
+    fn update_density_buffers(&mut self) {
+        let Some(options) = self.density_options else {
+            return;
+        };
+
+        let guard = self.axes.borrow();
+        let num_data_points = guard.num_data_points();
+        let mut visible_ranges = vec![wgsl::Vec2([0.0, 1.0]); guard.num_visible_axes()];
+        for axis in guard.visible_axes() {
+            let axis_idx = axis
+                .axis_index()
+                .expect("all visible axes should have an index");
+            visible_ranges[axis_idx] = wgsl::Vec2(axis.visible_data_range_normalized().into());
+        }
+        let (width, height) = guard.data_line_size();
+        drop(guard);
+
+        let color = DEFAULT_DENSITY_COLOR();
+        let (selected_color, selection_bounds, use_selection) = match self.active_label_idx {
+            Some(active_label_idx) if options.selected => {
+                let label = &self.labels[active_label_idx];
+                let (color, _) = label.colors(&self.label_groups);
+                (color.with_alpha(0.8), label.selection_bounds, 1)
+            }
+            _ => (color, (1.0, 1.0), 0),
+        };
+
+        let scale = self.line_width_scale();
+        let config = buffers::DensityConfig {
+            color: wgsl::Vec4(color.to_f32_with_alpha()),
+            selected_color: wgsl::Vec4(selected_color.to_f32_with_alpha()),
+            line_width: wgsl::Vec2([width.0 * scale, height.0 * scale]),
+            selection_bounds: wgsl::Vec2(selection_bounds.into()),
+            num_datums: num_data_points as u32,
+            num_axes: visible_ranges.len() as u32,
+            bandwidth: options.bandwidth,
+            width: 0.3,
+            use_selection,
+        };
+        let densities = self.buffers.densities_mut();
+        densities.update_config(&self.device, &config);
+        densities.set_visible_ranges(&self.device, &visible_ranges);
+        self.staging_data.density_update = true;
+    }
+
+    /// Samples the histograms of the visible axes over their visible ranges,
+    /// in place of the estimated densities. Axes without a histogram, and
+    /// the curves of the selected data points, remain empty.
+    fn update_histogram_densities(&self) {
+        let num_samples = buffers::DensityConfig::NUM_SAMPLES as usize;
+        let num_axes = self.buffers.densities().num_axes();
+        let mut densities = vec![0.0; 2 * num_axes * num_samples];
+
+        let guard = self.axes.borrow();
+        for axis in guard.visible_axes() {
+            let Some(histogram) = axis.histogram() else {
+                continue;
+            };
+            let axis_idx = axis
+                .axis_index()
+                .expect("all visible axes should have an index");
+            let offset = axis_idx * num_samples;
+            let Some(samples) = densities.get_mut(offset..offset + num_samples) else {
+                continue;
+            };
+
+            let (start, end) = axis.visible_data_range_normalized();
+            for (i, sample) in samples.iter_mut().enumerate() {
+                let t = start.lerp(end, i as f32 / (num_samples - 1) as f32);
+                let bin = (t * histogram.len() as f32) as usize;
+                *sample = histogram[bin.min(histogram.len() - 1)];
+            }
+        }
+        drop(guard);
+
+        self.buffers
+            .densities()
+            .set_densities(&self.device, &densities);
+    }
+
+    /// Updates the config and visible ranges of the histograms, and
+    /// schedules a new binning of the data.
+    fn update_histogram_buffers(&mut self) {
+        // The bars of the histograms can be brushed, so their geometry is
+        // shared with the hit testing.
+        let layout = self.histogram_options.map(|options| axis::HistogramLayout {
+            num_bins: options.num_bins as usize,
+            width: HISTOGRAM_WIDTH,
+        });
+        self.axes.borrow_mut().set_histogram_layout(layout);
+
+        let Some(options) = self.histogram_options else {
+            return;
+        };
+
+        let guard = self.axes.borrow();
+        let num_data_points = guard.num_data_points();
+        let mut visible_ranges = vec![wgsl::Vec2([0.0, 1.0]); guard.num_visible_axes()];
+        for axis in guard.visible_axes() {
+            let axis_idx = axis
+                .axis_index()
+                .expect("all visible axes should have an index");
+            visible_ranges[axis_idx] = wgsl::Vec2(axis.visible_data_range_normalized().into());
+        }
+        drop(guard);
+
+        // The selected bars are only drawn for the active label.
+        let color = DEFAULT_HISTOGRAM_COLOR();
+        let (selected_color, use_selection) = match self.active_label_idx {
+            Some(active_label_idx) => {
+                let (color, _) = self.labels[active_label_idx].colors(&self.label_groups);
+                (color.with_alpha(0.6), 1)
+            }
+            None => (color, 0),
+        };
+
+        let config = buffers::HistogramConfig {
+            color: wgsl::Vec4(color.to_f32_with_alpha()),
+            selected_color: wgsl::Vec4(selected_color.to_f32_with_alpha()),
+            num_datums: num_data_points as u32,
+            num_axes: visible_ranges.len() as u32,
+            num_bins: options.num_bins,
+            width: HISTOGRAM_WIDTH,
+            use_selection,
+        };
+        let histograms = self.buffers.histograms_mut();
+        histograms.update_config(&self.device, &config);
+        histograms.set_visible_ranges(&self.device, &visible_ranges, options.num_bins as usize);
+        self.staging_data.histogram_update = true;
+    }
+
+    /// Resamples the histograms provided with the visible axes into the bins
+    /// of all data points, in place of binning the data. Axes without a
+    /// histogram, and the bins of the selected data points, remain empty.
+    fn update_axis_histogram_bins(&self) {
+        let histograms = self.buffers.histograms();
+        let num_bins = histograms.num_bins();
+        let mut bins = vec![0.0; 2 * histograms.num_axes() * num_bins];
+
+        let guard = self.axes.borrow();
+        for axis in guard.visible_axes() {
+            let Some(histogram) = axis.histogram() else {
+                continue;
+            };
+            let axis_idx = axis
+                .axis_index()
+                .expect("all visible axes should have an index");
+            let offset = axis_idx * num_bins;
+            let Some(axis_bins) = bins.get_mut(offset..offset + num_bins) else {
+                continue;
+            };
+
+            let range = axis.visible_data_range_normalized();
+            axis_bins.copy_from_slice(&resample_histogram(histogram, range, num_bins));
+        }
+        drop(guard);
+
+        histograms.set_bins(&self.device, &bins);
+    }
+
+    fn update_snapshot_config_buffers(&mut self) {
+        let guard = self.axes.borrow();
+        let (width, height) = guard.data_line_size();
+        let scale = self.line_width_scale();
+        for (label_idx, label) in self.labels.iter().enumerate() {
+            let (_, color_dimmed) = label.colors(&self.label_groups);
+            self.buffers
+                .data_mut()
+                .snapshot_config_mut(label_idx)
+                .update(
+                    &self.device,
+                    &buffers::ReferenceConfig {
+                        line_width: wgsl::Vec2([width.0 * scale, height.0 * scale]),
+                        color: wgsl::Vec4(color_dimmed.with_alpha(0.5).to_f32_with_alpha()),
+                    },
+                );
+        }
     }
 
     fn update_data_lines_buffer(&mut self) {
         let axes = self.axes.borrow();
 
-        // Compute the curves.
-        let mut curves = vec![Vec::new(); axes.num_data_points()];
+        // Compute the curves. Missing values are kept as `NaN`, while curves
+        // with values outside of the requested range are removed.
+        let mut curves = vec![Some(Vec::new()); axes.num_data_points()];
         let mut axis_indices = Vec::new();
         for axis in axes.visible_axes() {
             let axis_idx = axis
@@ -2432,49 +7450,122 @@ fn update_data_lines_buffer(&mut self) {
             let range = start..=end;
 
             for (i, data_point) in axis.data_normalized().iter().enumerate() {
-                if range.contains(data_point) {
-                    curves[i].push(*data_point);
+                let Some(curve) = &mut curves[i] else {
+                    continue;
+                };
+
+                if data_point.is_nan() {
+                    curve.push(f32::NAN);
+                } else if range.contains(data_point) {
+                    curve.push(axis.jitter_value(i, *data_point));
                 } else {
-                    curves[i].push(f32::NAN);
+                    curves[i] = None;
                 }
             }
         }
 
-        // Filter curves with values outside of the requested range.
+        // Write the curves into a buffer.
         let curves = curves
             .into_iter()
-            .filter(|c| !c.iter().any(|d| d.is_nan()))
-            .collect::<Vec<_>>();
+            .enumerate()
+            .filter_map(|(i, c)| c.map(|c| (i, c)));
+        let lines = curve_segments(curves, &axis_indices);
 
-        // Write the curves into a buffer.
-        let num_curve_segments = axes.num_visible_axes().saturating_sub(1);
-        let num_lines = num_curve_segments * curves.len();
-
-        let mut lines = Vec::with_capacity(num_lines);
-        for (i, curve) in curves.into_iter().enumerate() {
-            for (values, indices) in curve.windows(2).zip(axis_indices.windows(2)) {
-                let curve_idx = i as u32;
-                let start_axis = indices[0] as u32;
-                let end_axis = indices[1] as u32;
-                let start_value = values[0];
-                let end_value = values[1];
-
-                lines.push(buffers::DataLine {
-                    curve_idx,
-                    start_axis,
-                    start_value,
-                    end_axis,
-                    end_value,
+        self.buffers
+            .data_mut()
+            .lines_mut()
+            .update(&self.device, &lines)
+    }
+
+    fn update_reference_lines_buffer(&mut self) {
+        let axes = self.axes.borrow();
+
+        // Normalize the reference points with the ranges of the visible axes.
+        let num_points = self.reference_data.values().next().map_or(0, |p| p.len());
+        let mut curves = vec![Vec::new(); num_points];
+        let mut axis_indices = Vec::new();
+        for axis in axes.visible_axes() {
+            let axis_idx = axis
+                .axis_index()
+                .expect("all visible axes must have an axis index");
+            axis_indices.push(axis_idx);
+
+            let (min, max) = axis.data_range();
+            let (start, end) = axis.visible_data_range_normalized();
+            let range = start..=end;
+
+            let points = self.reference_data.get(&*axis.key());
+            for (i, curve) in curves.iter_mut().enumerate() {
+                let point = points.map_or(f32::NAN, |p| {
+                    (axis.category_position(p[i]) - min) / (max - min)
                 });
+                if range.contains(&point) {
+                    curve.push(point);
+                } else {
+                    curve.push(f32::NAN);
+                }
             }
         }
 
+        // Unlike the data lines, the segments of the reference curves are
+        // drawn even if the curve lies outside of the range of other axes.
+        let lines = curve_segments(curves.into_iter().enumerate(), &axis_indices);
+
         self.buffers
             .data_mut()
-            .lines_mut()
+            .reference_lines_mut()
             .update(&self.device, &lines)
     }
 
+    fn update_snapshot_lines_buffers(&mut self) {
+        let axes = self.axes.borrow();
+
+        // Like the data lines, the curves of the snapshots are only drawn
+        // if all their values are contained in the visible ranges.
+        let visible_axes = axes.visible_axes().collect::<Vec<_>>();
+        let data = visible_axes
+            .iter()
+            .map(|axis| {
+                let axis_idx = axis
+                    .axis_index()
+                    .expect("all visible axes must have an axis index");
+                let (start, end) = axis.visible_data_range_normalized();
+                (axis_idx as u32, start..=end, axis)
+            })
+            .collect::<Vec<_>>();
+
+        for (label_idx, label) in self.labels.iter().enumerate() {
+            let mut lines = Vec::new();
+            for &i in label.snapshot.as_deref().unwrap_or_default() {
+                let i = i as usize;
+                let is_visible = data.iter().all(|(_, range, axis)| {
+                    let values = axis.data_normalized();
+                    values.get(i).is_some_and(|v| range.contains(v))
+                });
+                if !is_visible {
+                    continue;
+                }
+
+                for window in data.windows(2) {
+                    let (start_axis, _, start) = &window[0];
+                    let (end_axis, _, end) = &window[1];
+                    lines.push(buffers::DataLine {
+                        curve_idx: i as u32,
+                        start_axis: *start_axis,
+                        start_value: start.jitter_value(i, start.data_normalized()[i]),
+                        end_axis: *end_axis,
+                        end_value: end.jitter_value(i, end.data_normalized()[i]),
+                    });
+                }
+            }
+
+            self.buffers
+                .data_mut()
+                .snapshot_lines_mut(label_idx)
+                .update(&self.device, &lines);
+        }
+    }
+
     fn update_color_values_buffer(&mut self) {
         let axes = self.axes.borrow();
         let num_data_points = axes.num_data_points();
@@ -2498,7 +7589,7 @@ fn update_color_values_buffer(&mut self) {
                 self.buffers
                     .data()
                     .color_values()
-                    .update(&self.device, values);
+                    .update(&self.device, &values);
             }
             wasm_bridge::DataColorMode::AttributeDensity(key) => {
                 let axis = axes.axis(key).expect("unknown attribute");
@@ -2506,9 +7597,12 @@ fn update_color_values_buffer(&mut self) {
                 self.buffers
                     .data()
                     .color_values()
-                    .update(&self.device, values);
+                    .update(&self.device, &values);
             }
             wasm_bridge::DataColorMode::Probability => {}
+            wasm_bridge::DataColorMode::GroupProbability(_) => {
+                self.staging_data.group_probabilities_update = true;
+            }
         }
     }
 
@@ -2527,6 +7621,7 @@ fn update_data_buffer(&mut self) {
         }
 
         for axis in axes.visible_axes() {
+            // Missing values are kept as `NaN`, which the shaders test for.
             let data = axis.data_normalized();
             let axis_idx = axis
                 .axis_index()
@@ -2534,7 +7629,7 @@ fn update_data_buffer(&mut self) {
             self.buffers
                 .data()
                 .data()
-                .update(&self.device, data, axis_idx);
+                .update(&self.device, &data, axis_idx);
         }
     }
 }
@@ -2544,10 +7639,11 @@ impl Renderer {
     fn update_curves_config_buffer(&mut self) {
         let guard = self.axes.borrow();
         let (width, height) = guard.curve_line_size();
+        let scale = self.line_width_scale();
         self.buffers.curves_mut().config_mut().update(
             &self.device,
             &buffers::CurvesConfig {
-                line_width: wgsl::Vec2([width.0, height.0]),
+                line_width: wgsl::Vec2([width.0 * scale, height.0 * scale]),
                 color: wgsl::Vec3([1.0, 0.8, 0.8]),
             },
         );
@@ -2559,10 +7655,14 @@ impl Renderer {
     fn update_selections_config_buffer(&mut self) {
         let guard = self.axes.borrow();
         let (width, height) = guard.selections_line_size();
+        let scale = self.line_width_scale();
+        let snap_resolution = self.snap_resolution();
         self.buffers.selections_mut().config_mut().update(
             &self.device,
             &buffers::SelectionConfig {
-                line_width: wgsl::Vec2([width.0, height.0]),
+                line_width: wgsl::Vec2([width.0 * scale, height.0 * scale]),
+                snap_resolution,
+                pattern: self.accessibility.high_contrast as u32,
                 high_color: wgsl::Vec3(self.brush_color.to_f32()),
                 low_color: wgsl::Vec3([0.0; 3]),
             },
@@ -2576,6 +7676,10 @@ fn update_selection_lines_buffer(&mut self) {
         let active_label_idx = self.active_label_idx.unwrap();
 
         let guard = self.axes.borrow();
+        let preview = self
+            .active_action
+            .as_ref()
+            .and_then(|action| action.preview_selection());
 
         let mut segments = Vec::new();
         for axis in guard.visible_axes() {
@@ -2600,6 +7704,7 @@ fn update_selection_lines_buffer(&mut self) {
                         use_left: 0,
                         offset_x,
                         color_idx: active_label_idx as u32,
+                        style: buffers::SelectionLineInfo::STYLE_DEFAULT,
                         range: wgsl::Vec2(range),
                     });
                 }
@@ -2611,6 +7716,7 @@ fn update_selection_lines_buffer(&mut self) {
                         use_left: 1,
                         offset_x: 0.0,
                         color_idx: 0,
+                        style: buffers::SelectionLineInfo::STYLE_DEFAULT,
                         range: wgsl::Vec2(*range),
                     });
                 }
@@ -2618,71 +7724,130 @@ fn update_selection_lines_buffer(&mut self) {
                 for range in curve_builder.get_group_ranges_between(data_range).iter() {
                     segments.push(buffers::SelectionLineInfo {
                         axis: axis_index as u32,
-                        use_color: 0,
-                        use_left: 0,
+                        use_color: 0,
+                        use_left: 0,
+                        offset_x: 0.0,
+                        color_idx: 0,
+                        style: buffers::SelectionLineInfo::STYLE_DEFAULT,
+                        range: wgsl::Vec2(*range),
+                    });
+                }
+            }
+
+            let axis_preview = preview.as_ref().filter(|(a, _)| Rc::ptr_eq(a, &axis));
+            if let Some((_, [start, end])) = axis_preview {
+                let [min, max]: [f32; 2] = data_range;
+                segments.push(buffers::SelectionLineInfo {
+                    axis: axis_index as u32,
+                    use_color: 1,
+                    use_left: is_expanded as u32,
+                    offset_x: 0.0,
+                    color_idx: active_label_idx as u32,
+                    style: buffers::SelectionLineInfo::STYLE_PREVIEW,
+                    range: wgsl::Vec2([start.clamp(min, max), end.clamp(min, max)]),
+                });
+            }
+        }
+        self.buffers
+            .selections_mut()
+            .lines_mut(active_label_idx)
+            .update(&self.device, &segments);
+
+        if !self.show_all_selections {
+            return;
+        }
+
+        // The brushes of the inactive labels are drawn without the curve gradient.
+        for label_idx in 0..self.labels.len() {
+            if label_idx == active_label_idx {
+                continue;
+            }
+
+            let mut segments = Vec::new();
+            for axis in guard.visible_axes() {
+                let axis_index = axis
+                    .axis_index()
+                    .expect("all visible axes must have an index");
+                let data_range = axis.visible_data_range_normalized().into();
+                let curve_builder = axis.borrow_selection_curve_builder(label_idx);
+                for range in curve_builder.get_group_ranges_between(data_range).iter() {
+                    segments.push(buffers::SelectionLineInfo {
+                        axis: axis_index as u32,
+                        use_color: 1,
+                        use_left: axis.is_expanded() as u32,
                         offset_x: 0.0,
-                        color_idx: 0,
+                        color_idx: label_idx as u32,
+                        style: buffers::SelectionLineInfo::STYLE_DIMMED,
                         range: wgsl::Vec2(*range),
                     });
                 }
             }
+            self.buffers
+                .selections_mut()
+                .lines_mut(label_idx)
+                .update(&self.device, &segments);
         }
-        self.buffers
-            .selections_mut()
-            .lines_mut(active_label_idx)
-            .update(&self.device, &segments);
     }
 }
 
 // Probability
 impl Renderer {
+    /// Samples the changed probability curves of the label.
+    ///
+    /// Returns the indices of the axes whose curve changed, or `None` if no
+    /// curve needs to be reapplied.
     fn sample_probability_curve(
         &mut self,
         encoder: &webgpu::CommandEncoder,
         label_idx: usize,
-    ) -> bool {
+    ) -> Option<Box<[u32]>> {
         let axes = self.axes.borrow();
         self.buffers
             .curves_mut()
             .sample_texture_mut(label_idx)
             .set_num_curves(&self.device, axes.num_visible_axes());
 
-        let mut changed = axes.num_visible_axes() == 0;
+        // Pack the splines of all changed axes into one buffer, such that they
+        // can be sampled with a single dispatch.
+        let mut segments = Vec::new();
+        let mut ranges = Vec::new();
         for axis in axes.visible_axes() {
             let mut selection_curve = axis.borrow_selection_curve_mut(label_idx);
             let spline = match selection_curve.get_changed_curve() {
                 Some(s) => s,
                 None => continue,
             };
-            changed = true;
 
             let axis_idx = axis
                 .axis_index()
                 .expect("all visible axes must have an index");
-            let probability_texture = self.buffers.curves().sample_texture(label_idx);
 
-            let spline_segments = spline
-                .segments()
-                .iter()
-                .map(|s| buffers::SplineSegment {
-                    coefficients: wgsl::Vec4(s.coefficients),
-                    bounds: wgsl::Vec2(s.bounds),
-                    t_range: wgsl::Vec2(s.t_range),
-                })
-                .collect::<Vec<_>>();
-            let spline_segments =
-                buffers::SplineSegmentsBuffer::new(&self.device, &spline_segments);
+            let segments_start = segments.len() as u32;
+            segments.extend(spline.segments().iter().map(|s| buffers::SplineSegment {
+                coefficients: wgsl::Vec4(s.coefficients),
+                bounds: wgsl::Vec2(s.bounds),
+                t_range: wgsl::Vec2(s.t_range),
+            }));
+            ranges.push(buffers::SplineRange {
+                axis_idx: axis_idx as u32,
+                segments_start,
+                segments_end: segments.len() as u32,
+            });
+        }
 
-            self.pipelines.compute().curve_spline_sampling().dispatch(
-                axis_idx,
-                probability_texture,
-                &spline_segments,
-                &self.device,
-                encoder,
-            );
+        if ranges.is_empty() {
+            return (axes.num_visible_axes() == 0).then(|| Box::new([]) as Box<[_]>);
         }
 
-        changed
+        let spline_segments = buffers::SplineSegmentsBuffer::new(&self.device, &segments, &ranges);
+        self.pipelines.compute().curve_spline_sampling().dispatch(
+            self.buffers.curves().sample_texture(label_idx),
+            &spline_segments,
+            &self.device,
+            encoder,
+        );
+
+        Some(ranges.iter().map(|r| r.axis_idx).collect())
     }
 
     fn create_probability_curve_lines(
@@ -2727,7 +7892,7 @@ fn create_probability_curve_lines(
             layout: self.pipelines.compute().create_curves.0.clone(),
         });
 
-        let num_workgroups = ((num_lines + 63) / 64) as u32;
+        let num_workgroups = preprocessor::num_workgroups(self.device.limits(), num_lines);
 
         let pass = encoder.begin_compute_pass(None);
         pass.set_pipeline(&self.pipelines.compute().create_curves.1);
@@ -2736,21 +7901,48 @@ fn create_probability_curve_lines(
         pass.end();
     }
 
-    fn apply_probability_curves(&mut self, encoder: &webgpu::CommandEncoder, label_idx: usize) {
+    fn apply_probability_curves(
+        &mut self,
+        encoder: &webgpu::CommandEncoder,
+        label_idx: usize,
+        changed_axes: &[u32],
+    ) {
         let axes = self.axes.borrow();
         let num_data_points = axes.num_data_points();
         let num_visible_axes = axes.num_visible_axes();
 
-        // Ensure that the buffer is large enough.
+        // Ensure that the buffers are large enough.
         self.buffers
             .data_mut()
             .probabilities_mut(label_idx)
             .set_len(&self.device, num_data_points);
+        let reallocated = self
+            .buffers
+            .data_mut()
+            .memberships_mut(label_idx)
+            .set_len(&self.device, num_data_points * num_visible_axes);
 
         if num_data_points == 0 || num_visible_axes == 0 {
             return;
         }
 
+        // The memberships of the unchanged axes are still valid, unless the
+        // buffer was just allocated.
+        let changed_axes = if reallocated {
+            (0..num_visible_axes as u32).collect::<Vec<_>>()
+        } else {
+            changed_axes.to_vec()
+        };
+        let changed_axes_buffer = self.device.create_buffer(webgpu::BufferDescriptor {
+            label: Some(Cow::Borrowed("changed axes")),
+            size: std::mem::size_of_val(&*changed_axes),
+            usage: webgpu::BufferUsage::STORAGE | webgpu::BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+        self.device
+            .queue()
+            .write_buffer(&changed_axes_buffer, 0, &changed_axes);
+
         let num_data_points_buffer = self.device.create_buffer(webgpu::BufferDescriptor {
             label: Some(Cow::Borrowed("num data points")),
             size: std::mem::size_of::<u32>(),
@@ -2764,12 +7956,7 @@ fn apply_probability_curves(&mut self, encoder: &webgpu::CommandEncoder, label_i
         );
 
         let curve_samples = self.buffers.curves().sample_texture(label_idx).array_view();
-        let output_buffer = self.device.create_buffer(webgpu::BufferDescriptor {
-            label: Some(Cow::Borrowed("curve application output")),
-            size: std::mem::size_of::<u32>() * self.buffers.data().data().len(),
-            usage: webgpu::BufferUsage::STORAGE,
-            mapped_at_creation: None,
-        });
+        let output_buffer = self.buffers.data().memberships(label_idx).buffer().clone();
 
         // First we apply the curves to each value.
         let bind_group = self.device.create_bind_group(webgpu::BindGroupDescriptor {
@@ -2803,6 +7990,14 @@ fn apply_probability_curves(&mut self, encoder: &webgpu::CommandEncoder, label_i
                         size: None,
                     }),
                 },
+                webgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: webgpu::BindGroupEntryResource::Buffer(webgpu::BufferBinding {
+                        buffer: changed_axes_buffer,
+                        offset: None,
+                        size: None,
+                    }),
+                },
             ],
             layout: self
                 .pipelines
@@ -2812,7 +8007,7 @@ fn apply_probability_curves(&mut self, encoder: &webgpu::CommandEncoder, label_i
                 .clone(),
         });
 
-        let num_workgroups = ((self.buffers.data().data().len() + 63) / 64) as u32;
+        let num_workgroups = preprocessor::num_workgroups(self.device.limits(), num_data_points);
 
         let pass = encoder.begin_compute_pass(None);
         pass.set_pipeline(
@@ -2823,7 +8018,7 @@ fn apply_probability_curves(&mut self, encoder: &webgpu::CommandEncoder, label_i
                 .apply_curve_pipeline,
         );
         pass.set_bind_group(0, &bind_group);
-        pass.dispatch_workgroups(&[num_workgroups]);
+        pass.dispatch_workgroups(&[num_workgroups, changed_axes.len() as u32]);
         pass.end();
 
         // Then we reduce the value to a single one per curve.
@@ -2868,7 +8063,7 @@ fn apply_probability_curves(&mut self, encoder: &webgpu::CommandEncoder, label_i
                 .clone(),
         });
 
-        let num_workgroups = ((num_data_points + 63) / 64) as u32;
+        let num_workgroups = preprocessor::num_workgroups(self.device.limits(), num_data_points);
 
         let pass = encoder.begin_compute_pass(None);
         pass.set_pipeline(&self.pipelines.compute().compute_probability.reduce_pipeline);
@@ -2877,70 +8072,599 @@ fn apply_probability_curves(&mut self, encoder: &webgpu::CommandEncoder, label_i
         pass.end();
     }
 
+    /// Fits the visible ranges of the `axes` to the data points selected by
+    /// the active label. The plot is redrawn with the new ranges in the next
+    /// frame.
+    async fn fit_visible_ranges(&mut self, axes: wasm_bridge::FitAxes) {
+        let Some(active_label_idx) = self.active_label_idx else {
+            return;
+        };
+
+        let (num_data_points, num_visible_axes) = {
+            let guard = self.axes.borrow();
+            (guard.num_data_points(), guard.num_visible_axes())
+        };
+
+        if num_data_points == 0 || num_visible_axes == 0 {
+            return;
+        }
+
+        let ranges = buffers::SelectedRangesBuffer::new(
+            &self.device,
+            &buffers::SelectedRangeConfig {
+                selection_bounds: wgsl::Vec2(self.labels[active_label_idx].selection_bounds.into()),
+                num_datums: num_data_points as u32,
+                num_axes: num_visible_axes as u32,
+            },
+        );
+
+        let encoder = self
+            .device
+            .create_command_encoder(webgpu::CommandEncoderDescriptor { label: None });
+        self.pipelines
+            .compute()
+            .selected_range_reduction()
+            .dispatch(
+                &ranges,
+                self.buffers.data().data(),
+                self.buffers.data().probabilities(active_label_idx),
+                num_data_points,
+                &self.device,
+                &encoder,
+            );
+        ranges.copy_for_readback(&encoder);
+        self.device.queue().submit(&[encoder.finish(None)]);
+        let ranges = ranges.read().await;
+
+        let guard = self.axes.borrow();
+        for axis in guard.visible_axes() {
+            let key = axis.key();
+            if let wasm_bridge::FitAxes::Axes(axes) = &axes {
+                if !axes.contains(&*key) {
+                    continue;
+                }
+            }
+
+            // Axes without at least two distinct selected values keep their range.
+            let axis_idx = axis
+                .axis_index()
+                .expect("all visible axes should have an index");
+            let Some((min, max)) = ranges[axis_idx].filter(|(min, max)| min < max) else {
+                continue;
+            };
+
+            let (data_start, data_end) = axis.data_range();
+            let range = (
+                data_start.lerp(data_end, min),
+                data_start.lerp(data_end, max),
+            );
+            guard.set_visible_range(&key, range);
+        }
+        drop(guard);
+
+        self.update_data();
+        self.handled_events.signal(event::Event::AXIS_STATE_CHANGE);
+        self.staging_data.deferred_resample = true;
+        self.events.push(event::Event::REDRAW);
+    }
+
+    async fn freeze_labels(&mut self) {
+        for id in std::mem::take(&mut self.staging_data.label_freezes) {
+            // The label may have been removed in the meantime.
+            let Some(label_idx) = self.labels.iter().position(|l| l.id == id) else {
+                continue;
+            };
+
+            let selected = self.extract_label_attribution(label_idx).await;
+            self.labels[label_idx].snapshot = Some(selected);
+            self.staging_data.updated_snapshots.insert(id);
+
+            // Following brushes of the label start from an empty selection.
+            let guard = self.axes.borrow();
+            for axis in guard.axes() {
+                *axis.borrow_selection_curve_builder_mut(label_idx) =
+                    selection::SelectionCurveBuilder::new();
+                axis.borrow_selection_curve_mut(label_idx).set_curve(None);
+            }
+        }
+
+        self.update_snapshot_lines_buffers();
+        self.handled_events.signal(event::Event::SNAPSHOTS_CHANGE);
+        self.events.push(event::Event::SELECTIONS_CHANGE);
+    }
+
+    /// Reduces the weighted means of the values selected by each label.
+    async fn update_label_means(&mut self) {
+        self.label_means.clear();
+        if self.label_mean_mode == wasm_bridge::LabelMeanMode::Hidden {
+            return;
+        }
+
+        let (num_data_points, num_visible_axes, axes) = {
+            let guard = self.axes.borrow();
+            let axes = guard
+                .visible_axes()
+                .map(|ax| (ax.axis_index(), ax.key()))
+                .collect::<Vec<_>>();
+            (guard.num_data_points(), guard.num_visible_axes(), axes)
+        };
+
+        if num_data_points == 0 || num_visible_axes == 0 {
+            return;
+        }
+
+        // Reduce the sums of all labels in a single submission.
+        let num_workgroups = preprocessor::num_workgroups(self.device.limits(), num_data_points);
+        let encoder = self
+            .device
+            .create_command_encoder(webgpu::CommandEncoderDescriptor { label: None });
+        let mut reductions = Vec::with_capacity(self.labels.len());
+        for (label_idx, label) in self.labels.iter().enumerate() {
+            let config = buffers::LabelMeanConfig {
+                selection_bounds: wgsl::Vec2(label.selection_bounds.into()),
+                num_datums: num_data_points as u32,
+                num_axes: num_visible_axes as u32,
+            };
+            let means = buffers::LabelMeansBuffer::new(&self.device, &config, num_workgroups);
+            self.pipelines.compute().label_mean_reduction().dispatch(
+                &means,
+                self.buffers.data().data(),
+                self.buffers.data().probabilities(label_idx),
+                &self.device,
+                &encoder,
+            );
+            means.copy_for_readback(&encoder);
+            reductions.push((label.id.clone(), means));
+        }
+        self.device.queue().submit(&[encoder.finish(None)]);
+
+        for (id, means) in reductions {
+            let means = means.read().await;
+            let means = axes
+                .iter()
+                .filter_map(|(axis_idx, key)| {
+                    let mean = (*means.get((*axis_idx)?)?)?;
+                    Some((key.clone(), mean))
+                })
+                .collect();
+            self.label_means.insert(id, means);
+        }
+    }
+
+    async fn update_outlier_scores(&mut self) {
+        if self.outlier_options.is_none() {
+            return;
+        }
+
+        // The scores are the largest z-score of the values of the visible axes,
+        // where the synthetic axes are skipped.
+        let (num_data_points, num_visible_axes, statistics) = {
+            let guard = self.axes.borrow();
+            let num_data_points = guard.num_data_points();
+            let num_visible_axes = guard.num_visible_axes();
+            let mut statistics = vec![buffers::AxisStatistics::default(); num_visible_axes];
+            for axis in guard.visible_axes() {
+                if matches!(&*axis.key(), CLUSTER_AXIS_KEY | OUTLIER_AXIS_KEY) {
+                    continue;
+                }
+
+                let (count, sum, sum_squares) = axis
+                    .data_normalized()
+                    .iter()
+                    .filter(|v| !v.is_nan())
+                    .fold((0usize, 0.0f64, 0.0f64), |(count, sum, sum_squares), &v| {
+                        let v = v as f64;
+                        (count + 1, sum + v, sum_squares + v * v)
+                    });
+                if count == 0 {
+                    continue;
+                }
+
+                let mean = sum / count as f64;
+                let variance = (sum_squares / count as f64 - mean * mean).max(0.0);
+                let axis_idx = axis
+                    .axis_index()
+                    .expect("all visible axes should have an index");
+                statistics[axis_idx] = buffers::AxisStatistics {
+                    mean: mean as f32,
+                    deviation: variance.sqrt() as f32,
+                };
+            }
+            (num_data_points, num_visible_axes, statistics)
+        };
+
+        if num_data_points == 0 || num_visible_axes == 0 {
+            return;
+        }
+
+        let config = buffers::OutlierConfig {
+            num_datums: num_data_points as u32,
+            num_axes: num_visible_axes as u32,
+        };
+        let num_workgroups = preprocessor::num_workgroups(self.device.limits(), num_data_points);
+        let scores =
+            buffers::OutlierScoresBuffer::new(&self.device, &config, &statistics, num_workgroups);
+        let encoder = self
+            .device
+            .create_command_encoder(webgpu::CommandEncoderDescriptor { label: None });
+        self.pipelines.compute().outlier_scores().dispatch(
+            &scores,
+            self.buffers.data().data(),
+            &self.device,
+            &encoder,
+        );
+        scores.copy_for_readback(&encoder);
+        self.device.queue().submit(&[encoder.finish(None)]);
+
+        // The scores are clamped to the fixed range of their axis, such that
+        // the curve of the label of the outliers remains valid.
+        let scores = scores
+            .read()
+            .await
+            .iter()
+            .map(|score| score.min(MAX_OUTLIER_SCORE))
+            .collect();
+        self.remove_axis(OUTLIER_AXIS_KEY.into());
+        self.add_outlier_axis(scores);
+        self.import_outlier_curve();
+
+        self.update_data();
+        self.events.push(
+            event::Event::AXIS_STATE_CHANGE
+                | event::Event::AXIS_POSITION_CHANGE
+                | event::Event::AXIS_ORDER_CHANGE
+                | event::Event::SELECTIONS_CHANGE,
+        );
+    }
+
+    async fn update_group_probabilities(&self) {
+        let wasm_bridge::DataColorMode::GroupProbability(group) = &self.data_color_mode else {
+            return;
+        };
+
+        let num_data_points = self.axes.borrow().num_data_points();
+        let mut values = vec![0.0; num_data_points];
+        for (label_idx, label) in self.labels.iter().enumerate() {
+            if label.group.as_ref() != Some(group) {
+                continue;
+            }
+
+            let probabilities = self.extract_label_probability(label_idx).await;
+            for (value, &probability) in values.iter_mut().zip(probabilities.iter()) {
+                *value = f32::max(*value, probability);
+            }
+        }
+
+        self.buffers
+            .data()
+            .color_values()
+            .update(&self.device, &values);
+    }
+
     async fn extract_label_attribution_and_probability(
         &self,
         label_idx: usize,
     ) -> (Box<[f32]>, Box<[u64]>) {
-        {
-            let axes = self.axes.borrow();
-            if axes.num_data_points() == 0 {
-                return (Box::new([]), Box::new([]));
-            }
+        let attribution = self.extract_label_attribution(label_idx).await;
+        let probabilities = self.extract_label_probability(label_idx).await;
+        (probabilities, attribution)
+    }
+
+    async fn extract_label_probability(&self, label_idx: usize) -> Box<[f32]> {
+        if self.axes.borrow().num_data_points() == 0 {
+            return Box::new([]);
         }
 
-        // Create a temporary staging buffer for mapping the computed probability.
+        // The probabilities were copied into the readback buffer in the same
+        // submission as their computation.
+        let readback_buffer = self
+            .buffers
+            .data()
+            .probabilities_readback(label_idx)
+            .front();
+        if readback_buffer.size() == 0 {
+            return Box::new([]);
+        }
+
+        readback_buffer.map_async(webgpu::MapMode::READ).await;
+        let probabilities = unsafe { readback_buffer.get_mapped_range::<f32>() };
+        readback_buffer.unmap();
+        probabilities
+    }
+
+    /// Returns the indices of the data points selected by the label.
+    ///
+    /// The indices are compacted on the GPU, such that only the selected
+    /// indices are read back, instead of filtering all probabilities.
+    async fn extract_label_attribution(&self, label_idx: usize) -> Box<[u64]> {
+        let num_data_points = self.axes.borrow().num_data_points();
+        let probabilities = self.buffers.data().probabilities(label_idx);
+        if num_data_points == 0 || probabilities.buffer().size() == 0 {
+            return Box::new([]);
+        }
+
+        let config = buffers::CompactionConfig {
+            selection_bounds: wgsl::Vec2(self.labels[label_idx].selection_bounds.into()),
+            num_datums: num_data_points as u32,
+        };
+        let compaction = buffers::CompactionBuffer::new(&self.device, &config);
         let encoder = self
             .device
             .create_command_encoder(webgpu::CommandEncoderDescriptor { label: None });
-        let staging_buffer = self.device.create_buffer(webgpu::BufferDescriptor {
-            label: Some(Cow::Borrowed("probability staging buffer")),
-            size: self.buffers.data().probabilities(label_idx).size(),
-            usage: webgpu::BufferUsage::MAP_READ | webgpu::BufferUsage::COPY_DST,
-            mapped_at_creation: None,
-        });
-        encoder.copy_buffer_to_buffer(
-            self.buffers.data().probabilities(label_idx).buffer(),
-            0,
-            &staging_buffer,
-            0,
-            staging_buffer.size(),
+        let compute = self.pipelines.compute();
+        compute.selection_compaction().dispatch(
+            &compaction,
+            probabilities,
+            compute.scan(),
+            &self.device,
+            &encoder,
         );
+        compaction.copy_count_for_readback(&encoder);
         self.device.queue().submit(&[encoder.finish(None)]);
 
-        // Read the computed probabilities.
-        staging_buffer.map_async(webgpu::MapMode::READ).await;
-        let selection_range = (self.labels[label_idx].selection_bounds.0)
-            ..=(self.labels[label_idx].selection_bounds.1);
-        let probabilities = unsafe { staging_buffer.get_mapped_range::<f32>() };
-        let attribution = probabilities
+        compaction
+            .read(&self.device)
+            .await
             .iter()
-            .enumerate()
-            .filter(|(_, p)| selection_range.contains(p))
-            .map(|(i, _)| i as u64)
-            .collect::<Box<[_]>>();
-
-        (probabilities, attribution)
+            .map(|&i| i as u64)
+            .collect()
     }
 
     fn update_probabilities(&mut self, encoder: &webgpu::CommandEncoder) -> Box<[usize]> {
         let mut changed = Vec::new();
         for i in 0..self.labels.len() {
-            let curve_changed = self.sample_probability_curve(encoder, i);
+            // Interactions only modify the active label, so the curves of the
+            // remaining labels are left dirty until the interaction finishes.
+            if self.active_action.is_some() && self.active_label_idx != Some(i) {
+                self.staging_data.deferred_resample = true;
+                continue;
+            }
+
+            let changed_axes = self.sample_probability_curve(encoder, i);
 
             let threshold_changed = std::mem::replace(&mut self.labels[i].threshold_changed, false);
-            if !curve_changed {
-                if threshold_changed {
-                    changed.push(i);
-                }
+            let changed_axes = match changed_axes {
+                Some(changed_axes) => changed_axes,
+                None => {
+                    if threshold_changed {
+                        changed.push(i);
+                    }
 
-                continue;
-            }
+                    continue;
+                }
+            };
 
             changed.push(i);
             self.create_probability_curve_lines(encoder, i);
-            self.apply_probability_curves(encoder, i);
+            self.apply_probability_curves(encoder, i, &changed_axes);
+            self.buffers
+                .data_mut()
+                .copy_probabilities_for_readback(&self.device, encoder, i);
         }
 
         changed.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn axis_def(points: &[f32]) -> wasm_bridge::AxisDef {
+        wasm_bridge::AxisDef {
+            key: "a".into(),
+            label: "A".into(),
+            points: points.into(),
+            range: None,
+            visible_range: None,
+            ticks: None,
+            histogram: None,
+        }
+    }
+
+    fn axis_warning(warning: diagnostics::Warning) -> diagnostics::Diagnostic {
+        diagnostics::Diagnostic::new(warning).with("axis", "a")
+    }
+
+    #[test]
+    fn valid_axis_definitions_are_accepted() {
+        let mut def = axis_def(&[1.0, f32::NAN, 3.0]);
+        assert_eq!(validate_axis_def("a", &def, Some(3)), Ok(()));
+        assert_eq!(validate_axis_def("a", &def, None), Ok(()));
+
+        def.range = Some((0.0, 4.0));
+        def.visible_range = Some((1.0, 2.0));
+        def.ticks = Some(vec![(0.0, None), (4.0, None)]);
+        assert_eq!(validate_axis_def("a", &def, Some(3)), Ok(()));
+    }
+
+    #[test]
+    fn axis_definitions_with_invalid_points_are_rejected() {
+        use diagnostics::{Diagnostic, Warning};
+
+        let def = axis_def(&[1.0, f32::INFINITY]);
+        let expected = axis_warning(Warning::InfiniteAxisPoint);
+        assert_eq!(validate_axis_def("a", &def, None), Err(expected));
+
+        let def = axis_def(&[1.0, 2.0, 3.0]);
+        let expected = Diagnostic::new(Warning::MismatchedPointCount)
+            .with("axis", "a")
+            .with("expected", "2")
+            .with("actual", "3");
+        assert_eq!(validate_axis_def("a", &def, Some(2)), Err(expected));
+    }
+
+    #[test]
+    fn axis_definitions_with_invalid_ranges_are_rejected() {
+        use diagnostics::{Diagnostic, Warning};
+
+        let mut def = axis_def(&[1.0, 3.0]);
+        def.range = Some((2.0, 2.0));
+        let expected = axis_warning(Warning::InvalidAxisRange);
+        assert_eq!(validate_axis_def("a", &def, None), Err(expected));
+
+        def.range = Some((2.0, 4.0));
+        let expected = axis_warning(Warning::AxisRangeExcludesData);
+        assert_eq!(validate_axis_def("a", &def, None), Err(expected));
+
+        def.range = None;
+        def.visible_range = Some((f32::NAN, 2.0));
+        let expected = axis_warning(Warning::InvalidVisibleRange);
+        assert_eq!(validate_axis_def("a", &def, None), Err(expected));
+
+        def.visible_range = Some((0.0, 2.0));
+        let expected = axis_warning(Warning::VisibleRangeOutsideRange);
+        assert_eq!(validate_axis_def("a", &def, None), Err(expected));
+
+        def.visible_range = None;
+        def.ticks = Some(vec![(1.0, None), (3.5, None)]);
+        let expected = Diagnostic::new(Warning::TickOutsideRange)
+            .with("axis", "a")
+            .with("tick", "3.5");
+        assert_eq!(validate_axis_def("a", &def, None), Err(expected));
+    }
+
+    #[test]
+    fn ragged_axes_are_padded_with_missing_values() {
+        let mut points: Box<[f32]> = Box::new([1.0, 2.0]);
+        pad_missing_points(&mut points, 4);
+        assert_eq!(points.len(), 4);
+        assert_eq!(points[..2], [1.0, 2.0]);
+        assert!(points[2..].iter().all(|p| p.is_nan()));
+    }
+
+    #[test]
+    fn ragged_curves_are_drawn_up_to_the_missing_values() {
+        let line = |curve_idx, start_axis, start_value, end_axis, end_value| buffers::DataLine {
+            curve_idx,
+            start_axis,
+            start_value,
+            end_axis,
+            end_value,
+        };
+
+        let curves = vec![
+            (0, vec![0.1, 0.2, 0.3]),
+            (1, vec![0.4, f32::NAN, 0.6]),
+            (3, vec![0.7, 0.8, f32::NAN]),
+        ];
+        let lines = curve_segments(curves, &[2, 0, 1]);
+        assert_eq!(
+            lines,
+            [
+                line(0, 2, 0.1, 0, 0.2),
+                line(0, 0, 0.2, 1, 0.3),
+                line(3, 2, 0.7, 0, 0.8),
+            ]
+        );
+    }
+
+    #[test]
+    fn complete_axes_are_not_padded() {
+        let mut points: Box<[f32]> = Box::new([1.0, 2.0, 3.0]);
+        pad_missing_points(&mut points, 3);
+        assert_eq!(*points, [1.0, 2.0, 3.0]);
+
+        let mut points: Box<[f32]> = Box::new([]);
+        pad_missing_points(&mut points, 0);
+        assert!(points.is_empty());
+    }
+
+    #[test]
+    fn existing_axes_dictate_the_number_of_data_points() {
+        assert_eq!(ragged_num_data_points(Some(3), [5, 1].into_iter()), 3);
+        assert_eq!(ragged_num_data_points(Some(0), [5].into_iter()), 0);
+        assert_eq!(ragged_num_data_points(None, [2, 5, 1].into_iter()), 5);
+        assert_eq!(ragged_num_data_points(None, std::iter::empty()), 0);
+    }
+
+    #[test]
+    fn view_window_steps_towards_the_target() {
+        assert_eq!(step_view_window((0.0, 4.0), (1.0, 2.0), 0.5), (0.5, 3.0));
+        assert_eq!(step_view_window((0.0, 4.0), (1.0, 2.0), 1.0), (1.0, 2.0));
+        assert_eq!(step_view_window((0.0, 4.0), (1.0, 2.0), 0.0), (0.0, 4.0));
+    }
+
+    #[test]
+    fn view_window_snaps_to_a_close_target() {
+        let target = (1.0, 2.0);
+        let mut window = (0.0, 4.0);
+        for _ in 0..100 {
+            window = step_view_window(window, target, VIEW_ANIMATION_STEP);
+        }
+        assert_eq!(window, target);
+    }
+
+    #[test]
+    fn histograms_are_resampled_over_the_visible_range() {
+        let histogram = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(resample_histogram(&histogram, (0.0, 1.0), 4), histogram);
+        assert_eq!(resample_histogram(&histogram, (0.0, 1.0), 2), [3.0, 7.0]);
+        assert_eq!(resample_histogram(&histogram, (0.5, 1.0), 2), [3.0, 4.0]);
+        assert_eq!(
+            resample_histogram(&histogram, (0.0, 1.0), 8)[..3],
+            [0.5, 0.5, 1.0]
+        );
+    }
+
+    #[test]
+    fn histograms_are_empty_outside_of_the_axis() {
+        let histogram = [1.0, 2.0];
+        assert_eq!(
+            resample_histogram(&histogram, (-1.0, 1.0), 4),
+            [0.0, 0.0, 1.0, 2.0]
+        );
+        assert_eq!(resample_histogram(&[], (0.0, 1.0), 3), [0.0; 3]);
+    }
+
+    #[test]
+    fn appended_rows_are_queued_as_whole_rows() {
+        let mut columns = BTreeMap::new();
+        let appends = BTreeMap::from([("a".to_string(), vec![1.0, 2.0])]);
+        let num_queued = queue_appended_rows(&mut columns, 0, appends, None);
+        assert_eq!(num_queued, 2);
+
+        let appends = BTreeMap::from([("b".to_string(), vec![3.0])]);
+        let num_queued = queue_appended_rows(&mut columns, num_queued, appends, None);
+        assert_eq!(num_queued, 3);
+        assert_eq!(columns["a"][..2], [1.0, 2.0]);
+        assert!(columns["a"][2].is_nan());
+        assert!(columns["b"][..2].iter().all(|p| p.is_nan()));
+        assert_eq!(columns["b"][2], 3.0);
+    }
+
+    #[test]
+    fn queued_rows_evict_the_oldest_rows_first() {
+        let mut columns = BTreeMap::new();
+        let appends = BTreeMap::from([("a".to_string(), vec![1.0, 2.0, 3.0])]);
+        let num_queued = queue_appended_rows(&mut columns, 0, appends, Some(4));
+        let appends = BTreeMap::from([("a".to_string(), vec![4.0, 5.0])]);
+        let num_queued = queue_appended_rows(&mut columns, num_queued, appends, Some(4));
+        assert_eq!(num_queued, 4);
+        assert_eq!(columns["a"], [2.0, 3.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn appended_rows_evict_the_oldest_points_first() {
+        let points = [1.0, 2.0, 3.0];
+        assert_eq!(
+            *append_rows(&points, vec![4.0, 5.0], 2, 0),
+            [1.0, 2.0, 3.0, 4.0, 5.0]
+        );
+        assert_eq!(*append_rows(&points, vec![4.0, 5.0], 2, 2), [3.0, 4.0, 5.0]);
+        assert_eq!(*append_rows(&points, vec![4.0, 5.0], 2, 10), []);
+
+        let points = append_rows(&points, vec![], 1, 1);
+        assert_eq!(points[..2], [2.0, 3.0]);
+        assert!(points[2].is_nan());
+    }
+
+    #[test]
+    fn snapshots_are_shifted_past_the_evicted_points() {
+        let snapshot = [0, 1, 4, 7];
+        assert_eq!(*shift_snapshot(&snapshot, 0), [0, 1, 4, 7]);
+        assert_eq!(*shift_snapshot(&snapshot, 2), [2, 5]);
+        assert_eq!(*shift_snapshot(&snapshot, 4), [0, 3]);
+        assert!(shift_snapshot(&snapshot, 8).is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+//! Leveled logging of the renderer.
+//!
+//! Messages below the level selected in the debug options are discarded.
+//! The remaining ones are written to the console and, if requested, kept
+//! until they are forwarded to the callback of the renderer.
+use std::cell::RefCell;
+
+use wasm_bindgen::JsValue;
+
+use crate::{api::PlotDiff, wasm_bridge::LogLevel};
+
+/// Maximum number of messages kept for the callback between two frames.
+const MAX_MIRRORED_MESSAGES: usize = 256;
+
+/// Console functions logging a message without and with additional data.
+type ConsoleSink = (fn(&JsValue), fn(&JsValue, &JsValue));
+
+#[derive(Debug, Default)]
+struct Logger {
+    level: LogLevel,
+    mirror: bool,
+    mirrored: Vec<(LogLevel, String)>,
+}
+
+thread_local! {
+    static LOGGER: RefCell<Logger> = RefCell::new(Logger::default());
+}
+
+/// Sets the minimum level of the logged messages, and whether they are
+/// forwarded to the callback.
+pub fn configure(level: LogLevel, mirror: bool) {
+    LOGGER.with(|logger| {
+        let mut logger = logger.borrow_mut();
+        logger.level = level;
+        logger.mirror = mirror;
+        if !mirror {
+            logger.mirrored.clear();
+        }
+    });
+}
+
+/// Returns whether messages of the level are logged.
+pub fn enabled(level: LogLevel) -> bool {
+    LOGGER.with(|logger| level >= logger.borrow().level)
+}
+
+/// Logs a message, with optional structured data shown next to it in the
+/// console.
+pub fn log(level: LogLevel, message: &str, data: &JsValue) {
+    if !enabled(level) {
+        return;
+    }
+
+    let (log_1, log_2): ConsoleSink = match level {
+        LogLevel::Trace => (web_sys::console::debug_1, web_sys::console::debug_2),
+        LogLevel::Debug => (web_sys::console::log_1, web_sys::console::log_2),
+        LogLevel::Warn => (web_sys::console::warn_1, web_sys::console::warn_2),
+        LogLevel::Critical | LogLevel::Off => {
+            (web_sys::console::error_1, web_sys::console::error_2)
+        }
+    };
+    if data.is_undefined() {
+        log_1(&message.into());
+    } else {
+        log_2(&message.into(), data);
+    }
+
+    LOGGER.with(|logger| {
+        let mut logger = logger.borrow_mut();
+        if logger.mirror && logger.mirrored.len() < MAX_MIRRORED_MESSAGES {
+            logger.mirrored.push((level, message.to_string()));
+        }
+    });
+}
+
+pub fn trace(message: &str) {
+    log(LogLevel::Trace, message, &JsValue::UNDEFINED);
+}
+
+pub fn debug(message: &str) {
+    log(LogLevel::Debug, message, &JsValue::UNDEFINED);
+}
+
+pub fn warn(message: &str) {
+    log(LogLevel::Warn, message, &JsValue::UNDEFINED);
+}
+
+pub fn error(message: &str) {
+    log(LogLevel::Critical, message, &JsValue::UNDEFINED);
+}
+
+/// Takes the messages to forward to the callback.
+pub fn take_mirrored() -> Vec<PlotDiff> {
+    let mirrored = LOGGER.with(|logger| std::mem::take(&mut logger.borrow_mut().mirrored));
+    mirrored
+        .into_iter()
+        .map(|(level, message)| PlotDiff::Log {
+            level: level.name().into(),
+            message,
+        })
+        .collect()
+}
@@ -0,0 +1,94 @@
+//! Heuristics for untangling the lines of the plot.
+
+/// Counts the crossings between the lines of two categories, if the category
+/// `below` is placed directly below the category `above`.
+///
+/// Both slices contain the sorted positions of the lines on a neighboring axis.
+fn count_crossings(below: &[f32], above: &[f32]) -> usize {
+    // A line of the lower category crosses all lines of the upper category
+    // which end below it.
+    let mut crossings = 0;
+    let mut end = 0;
+    for &position in below {
+        while end < above.len() && above[end] < position {
+            end += 1;
+        }
+        crossings += end;
+    }
+    crossings
+}
+
+/// Orders the categories of an axis, such that the lines to its neighboring
+/// axes cross as little as possible.
+///
+/// `categories` contains the category of each line, or `None` if its value
+/// is missing, while `neighbors` contains the normalized values of the lines
+/// on each neighboring axis, where missing values are `NaN`. The categories
+/// are first ordered by the barycenter of their lines on the neighbors, and
+/// then refined by swapping adjacent categories, while this reduces the
+/// crossings. Returns the categories from the bottom to the top of the axis.
+pub fn minimize_category_crossings(
+    num_categories: usize,
+    categories: &[Option<usize>],
+    neighbors: &[&[f32]],
+) -> Box<[usize]> {
+    let mut positions = vec![vec![Vec::new(); neighbors.len()]; num_categories];
+    for (i, &category) in categories.iter().enumerate() {
+        let Some(category) = category else {
+            continue;
+        };
+
+        for (neighbor, values) in neighbors.iter().enumerate() {
+            if let Some(&value) = values.get(i).filter(|v| !v.is_nan()) {
+                positions[category][neighbor].push(value);
+            }
+        }
+    }
+    for lines in positions.iter_mut().flatten() {
+        lines.sort_by(f32::total_cmp);
+    }
+
+    // Categories without any lines are kept in the center.
+    let barycenters = positions
+        .iter()
+        .map(|lines| {
+            let (sum, count) = lines
+                .iter()
+                .flatten()
+                .fold((0.0, 0), |(sum, count), &v| (sum + v, count + 1));
+            if count == 0 {
+                0.5
+            } else {
+                sum / count as f32
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut order = (0..num_categories).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| barycenters[a].total_cmp(&barycenters[b]));
+
+    // Swapping two adjacent categories only affects the crossings among
+    // their lines, so each swap strictly reduces the total crossings.
+    let crossings = |below: usize, above: usize| {
+        positions[below]
+            .iter()
+            .zip(&positions[above])
+            .map(|(below, above)| count_crossings(below, above))
+            .sum::<usize>()
+    };
+    loop {
+        let mut swapped = false;
+        for i in 1..order.len() {
+            let (below, above) = (order[i - 1], order[i]);
+            if crossings(above, below) < crossings(below, above) {
+                order.swap(i - 1, i);
+                swapped = true;
+            }
+        }
+
+        if !swapped {
+            break;
+        }
+    }
+
+    order.into()
+}
@@ -0,0 +1,90 @@
+//! Reporting of panics to the callbacks of the renderers.
+//!
+//! A panic leaves the module in an unusable state, such that all renderers
+//! stop working. The hook therefore forwards the panic to the callback of
+//! each live renderer, together with the last state snapshot recorded by it.
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+    sync::Once,
+};
+
+use wasm_bindgen::JsValue;
+
+use crate::api::{PlotDiff, StateSnapshot};
+
+#[derive(Debug)]
+struct Reporter {
+    callback: js_sys::Function,
+    snapshot: RefCell<StateSnapshot>,
+}
+
+thread_local! {
+    static REPORTERS: RefCell<Vec<Weak<Reporter>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Installs the panic hook, which logs the panic to the console and
+/// forwards it to the registered callbacks.
+pub fn set_hook() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        std::panic::set_hook(Box::new(|info| {
+            console_error_panic_hook::hook(info);
+            report(&info.to_string());
+        }))
+    });
+}
+
+fn report(message: &str) {
+    // The registry may be borrowed if the panic originates from it.
+    let reporters = REPORTERS.with(|reporters| match reporters.try_borrow() {
+        Ok(reporters) => reporters.iter().filter_map(Weak::upgrade).collect(),
+        Err(_) => Vec::new(),
+    });
+
+    for reporter in reporters {
+        let snapshot = match reporter.snapshot.try_borrow() {
+            Ok(snapshot) => snapshot.clone(),
+            Err(_) => StateSnapshot::default(),
+        };
+        let diff = PlotDiff::Panic {
+            message: message.into(),
+            snapshot,
+        };
+        let plot_diff = js_sys::Array::of1(&diff.to_js());
+
+        // The panic is already logged, so a failing callback is ignored.
+        let _ = reporter.callback.call1(&JsValue::null(), &plot_diff);
+    }
+}
+
+/// Registration of a callback for the panic reports.
+///
+/// The callback is unregistered once the reporter is dropped.
+#[derive(Debug)]
+pub struct PanicReporter {
+    reporter: Rc<Reporter>,
+}
+
+impl PanicReporter {
+    pub fn register(callback: js_sys::Function) -> Self {
+        set_hook();
+
+        let reporter = Rc::new(Reporter {
+            callback,
+            snapshot: Default::default(),
+        });
+        REPORTERS.with(|reporters| {
+            let mut reporters = reporters.borrow_mut();
+            reporters.retain(|reporter| reporter.strong_count() != 0);
+            reporters.push(Rc::downgrade(&reporter));
+        });
+
+        Self { reporter }
+    }
+
+    /// Replaces the snapshot sent with a panic report.
+    pub fn set_snapshot(&self, snapshot: StateSnapshot) {
+        *self.reporter.snapshot.borrow_mut() = snapshot;
+    }
+}
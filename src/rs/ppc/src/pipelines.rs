@@ -1,7 +1,54 @@
-use crate::buffers;
+use std::future::Future;
+
 use crate::webgpu::*;
+use crate::{buffers, preprocessor};
+
+/// Number of pipelines compiled by [`Pipelines::new`].
+const NUM_PIPELINES: usize = 29;
+
+/// Reports the fraction of compiled pipelines.
+pub struct Progress<'a> {
+    compiled: usize,
+    callback: &'a mut dyn FnMut(f32),
+}
+
+impl<'a> Progress<'a> {
+    pub fn new(callback: &'a mut dyn FnMut(f32)) -> Self {
+        Self {
+            compiled: 0,
+            callback,
+        }
+    }
 
-const NUM_SAMPLES: u32 = 4;
+    async fn track<T>(&mut self, pipeline: impl Future<Output = T>) -> T {
+        let pipeline = pipeline.await;
+        self.compiled += 1;
+        (self.callback)(self.compiled as f32 / NUM_PIPELINES as f32);
+        pipeline
+    }
+}
+
+/// Area of the render target drawn by a pipeline, in device pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub start: (f32, f32),
+    pub size: (f32, f32),
+}
+
+/// Sets the viewport of the render pass, and restricts the rasterization to it.
+///
+/// The lines are expanded in the vertex shaders, and may extend past the clip
+/// volume, which the scissor rect clips consistently on all devices.
+fn set_viewport(
+    render_pass: &RenderPassEncoder,
+    viewport_start: (f32, f32),
+    viewport_size: (f32, f32),
+) {
+    let (x, y) = viewport_start;
+    let (width, height) = viewport_size;
+    render_pass.set_viewport(x, y, width, height, 0.0, 1.0);
+    render_pass.set_scissor_rect(x as u32, y as u32, width as u32, height as u32);
+}
 
 pub struct Pipelines {
     render_pipelines: RenderPipelines,
@@ -9,9 +56,13 @@ pub struct Pipelines {
 }
 
 impl Pipelines {
-    pub async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
-        let render_pipelines = RenderPipelines::new(device, presentation_format).await;
-        let compute_pipelines = ComputePipelines::new(device).await;
+    pub async fn new(
+        device: &Device,
+        output: buffers::OutputFormat,
+        progress: &mut Progress<'_>,
+    ) -> Self {
+        let render_pipelines = RenderPipelines::new(device, output, progress).await;
+        let compute_pipelines = ComputePipelines::new(device, progress).await;
 
         Self {
             render_pipelines,
@@ -26,29 +77,97 @@ pub fn render(&self) -> &RenderPipelines {
     pub fn compute(&self) -> &ComputePipelines {
         &self.compute_pipelines
     }
+
+    /// Recreates the render pipelines for the `output` format.
+    pub async fn set_output(&mut self, device: &Device, output: buffers::OutputFormat) {
+        let mut callback = |_: f32| {};
+        let mut progress = Progress::new(&mut callback);
+        self.render_pipelines = RenderPipelines::new(device, output, &mut progress).await;
+    }
+
+    #[cfg(feature = "dev-tools")]
+    pub fn render_mut(&mut self) -> &mut RenderPipelines {
+        &mut self.render_pipelines
+    }
 }
 
 pub struct RenderPipelines {
+    background: BackgroundRenderPipeline,
+    axis_regions: AxisRegionsRenderPipeline,
     axis_lines: AxisLinesRenderPipeline,
     data_lines: DataLinesRenderPipeline,
+    reference_lines: ReferenceLinesRenderPipeline,
+    flow_ribbons: FlowRibbonsRenderPipeline,
     curve_lines: CurveLinesRenderPipeline,
+    density_curves: DensityCurvesRenderPipeline,
+    histogram_bars: HistogramBarsRenderPipeline,
     selections: SelectionsRenderPipeline,
     curve_segments: CurveSegmentsRenderPipeline,
+    curve_comparison: CurveComparisonRenderPipeline,
     color_bar: ColorBarRenderPipeline,
+    linear_resolve: LinearResolveRenderPipeline,
 }
 
 impl RenderPipelines {
-    pub async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
+    pub async fn new(
+        device: &Device,
+        output: buffers::OutputFormat,
+        progress: &mut Progress<'_>,
+    ) -> Self {
         Self {
-            axis_lines: AxisLinesRenderPipeline::new(device, presentation_format).await,
-            data_lines: DataLinesRenderPipeline::new(device, presentation_format).await,
-            curve_lines: CurveLinesRenderPipeline::new(device, presentation_format).await,
-            selections: SelectionsRenderPipeline::new(device, presentation_format).await,
-            curve_segments: CurveSegmentsRenderPipeline::new(device, presentation_format).await,
-            color_bar: ColorBarRenderPipeline::new(device, presentation_format).await,
+            background: progress
+                .track(BackgroundRenderPipeline::new(device, output))
+                .await,
+            axis_regions: progress
+                .track(AxisRegionsRenderPipeline::new(device, output))
+                .await,
+            axis_lines: progress
+                .track(AxisLinesRenderPipeline::new(device, output))
+                .await,
+            data_lines: progress
+                .track(DataLinesRenderPipeline::new(device, output))
+                .await,
+            reference_lines: progress
+                .track(ReferenceLinesRenderPipeline::new(device, output))
+                .await,
+            flow_ribbons: progress
+                .track(FlowRibbonsRenderPipeline::new(device, output))
+                .await,
+            curve_lines: progress
+                .track(CurveLinesRenderPipeline::new(device, output))
+                .await,
+            density_curves: progress
+                .track(DensityCurvesRenderPipeline::new(device, output))
+                .await,
+            histogram_bars: progress
+                .track(HistogramBarsRenderPipeline::new(device, output))
+                .await,
+            selections: progress
+                .track(SelectionsRenderPipeline::new(device, output))
+                .await,
+            curve_segments: progress
+                .track(CurveSegmentsRenderPipeline::new(device, output))
+                .await,
+            curve_comparison: progress
+                .track(CurveComparisonRenderPipeline::new(device, output))
+                .await,
+            color_bar: progress
+                .track(ColorBarRenderPipeline::new(device, output))
+                .await,
+            linear_resolve: progress
+                .track(LinearResolveRenderPipeline::new(device, output.encoded()))
+                .await,
         }
     }
 
+    pub fn background(&self) -> &BackgroundRenderPipeline {
+        &self.background
+    }
+
+    pub fn axis_regions(&self) -> &AxisRegionsRenderPipeline {
+        &self.axis_regions
+    }
+
     pub fn axis_lines(&self) -> &AxisLinesRenderPipeline {
         &self.axis_lines
     }
@@ -57,10 +176,26 @@ pub fn data_lines(&self) -> &DataLinesRenderPipeline {
         &self.data_lines
     }
 
+    pub fn reference_lines(&self) -> &ReferenceLinesRenderPipeline {
+        &self.reference_lines
+    }
+
+    pub fn flow_ribbons(&self) -> &FlowRibbonsRenderPipeline {
+        &self.flow_ribbons
+    }
+
     pub fn curve_lines(&self) -> &CurveLinesRenderPipeline {
         &self.curve_lines
     }
 
+    pub fn density_curves(&self) -> &DensityCurvesRenderPipeline {
+        &self.density_curves
+    }
+
+    pub fn histogram_bars(&self) -> &HistogramBarsRenderPipeline {
+        &self.histogram_bars
+    }
+
     pub fn selections(&self) -> &SelectionsRenderPipeline {
         &self.selections
     }
@@ -69,29 +204,153 @@ pub fn curve_segments(&self) -> &CurveSegmentsRenderPipeline {
         &self.curve_segments
     }
 
+    pub fn curve_comparison(&self) -> &CurveComparisonRenderPipeline {
+        &self.curve_comparison
+    }
+
     pub fn color_bar(&self) -> &ColorBarRenderPipeline {
         &self.color_bar
     }
+
+    pub fn linear_resolve(&self) -> &LinearResolveRenderPipeline {
+        &self.linear_resolve
+    }
 }
 
-pub struct AxisLinesRenderPipeline {
+#[cfg(feature = "dev-tools")]
+impl RenderPipelines {
+    /// Recreates the pipeline `name` from the WGSL `source`.
+    ///
+    /// The previous pipeline is kept if the shader contains errors.
+    pub async fn reload(
+        &mut self,
+        device: &Device,
+        output: buffers::OutputFormat,
+        name: &str,
+        source: &str,
+    ) -> Result<(), String> {
+        const NAMES: [&str; 13] = [
+            "background",
+            "axis_regions",
+            "axis_lines",
+            "data_lines",
+            "reference_lines",
+            "flow_ribbons",
+            "curve_lines",
+            "density_curves",
+            "histogram_bars",
+            "selections",
+            "curve_segments",
+            "curve_comparison",
+            "color_bar",
+        ];
+        if !NAMES.contains(&name) {
+            return Err(format!("unknown render pipeline '{name}'"));
+        }
+
+        let source = &preprocessor::preprocess_render(source, device.limits(), output)?;
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some(name.into()),
+            code: source.into(),
+        });
+        let info = shader_module
+            .compilation_info()
+            .await
+            .map_err(|e| format!("{e:?}"))?;
+        let errors = info
+            .messages()
+            .iter()
+            .map(wasm_bindgen::JsCast::unchecked_into::<web_sys::GpuCompilationMessage>)
+            .filter(|m| m.type_() == web_sys::GpuCompilationMessageType::Error)
+            .map(|m| format!("{}:{}: {}", m.line_num(), m.line_pos(), m.message()))
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(errors.join("\n"));
+        }
+
+        match name {
+            "background" => {
+                self.background =
+                    BackgroundRenderPipeline::from_source(device, output, source).await;
+            }
+            "axis_regions" => {
+                self.axis_regions =
+                    AxisRegionsRenderPipeline::from_source(device, output, source).await;
+            }
+            "axis_lines" => {
+                self.axis_lines =
+                    AxisLinesRenderPipeline::from_source(device, output, source).await;
+            }
+            "data_lines" => {
+                self.data_lines =
+                    DataLinesRenderPipeline::from_source(device, output, source).await;
+            }
+            "reference_lines" => {
+                self.reference_lines =
+                    ReferenceLinesRenderPipeline::from_source(device, output, source).await;
+            }
+            "flow_ribbons" => {
+                self.flow_ribbons =
+                    FlowRibbonsRenderPipeline::from_source(device, output, source).await;
+            }
+            "curve_lines" => {
+                self.curve_lines =
+                    CurveLinesRenderPipeline::from_source(device, output, source).await;
+            }
+            "density_curves" => {
+                self.density_curves =
+                    DensityCurvesRenderPipeline::from_source(device, output, source).await;
+            }
+            "histogram_bars" => {
+                self.histogram_bars =
+                    HistogramBarsRenderPipeline::from_source(device, output, source).await;
+            }
+            "selections" => {
+                self.selections =
+                    SelectionsRenderPipeline::from_source(device, output, source).await;
+            }
+            "curve_segments" => {
+                self.curve_segments =
+                    CurveSegmentsRenderPipeline::from_source(device, output, source).await;
+            }
+            "curve_comparison" => {
+                self.curve_comparison =
+                    CurveComparisonRenderPipeline::from_source(device, output, source).await;
+            }
+            "color_bar" => {
+                self.color_bar = ColorBarRenderPipeline::from_source(device, output, source).await;
+            }
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+}
+
+pub struct BackgroundRenderPipeline {
     layout: BindGroupLayout,
     pipeline: RenderPipeline,
+    sampler: Sampler,
 }
 
-impl AxisLinesRenderPipeline {
-    async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
+impl BackgroundRenderPipeline {
+    async fn new(device: &Device, output: buffers::OutputFormat) -> Self {
+        let code = include_str!("./shaders/background.wgsl");
+        Self::from_source(device, output, code).await
+    }
+
+    async fn from_source(device: &Device, output: buffers::OutputFormat, code: &str) -> Self {
         let shader_module = device.create_shader_module(ShaderModuleDescriptor {
-            label: Some("axis lines shader".into()),
-            code: include_str!("./shaders/axis_lines.wgsl").into(),
+            label: Some("background shader".into()),
+            code: preprocessor::bundled_render(code, device.limits(), output).into(),
         });
 
         let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
-            label: Some("axis lines render pipeline bind group layout".into()),
+            label: Some("background render pipeline bind group layout".into()),
             entries: [
                 BindGroupLayoutEntry {
                     binding: 0,
-                    visibility: ShaderStage::VERTEX,
+                    visibility: ShaderStage::FRAGMENT,
                     resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
                         r#type: Some(BufferBindingType::Uniform),
                         ..Default::default()
@@ -99,34 +358,49 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                 },
                 BindGroupLayoutEntry {
                     binding: 1,
-                    visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
-                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
-                        r#type: Some(BufferBindingType::Uniform),
-                        ..Default::default()
+                    visibility: ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Texture(TextureBindingLayout {
+                        multisampled: None,
+                        sample_type: Some(TextureSampleType::Float),
+                        view_dimension: Some(TextureViewDimension::D2),
                     }),
                 },
                 BindGroupLayoutEntry {
                     binding: 2,
-                    visibility: ShaderStage::VERTEX,
-                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
-                        r#type: Some(BufferBindingType::ReadOnlyStorage),
-                        ..Default::default()
+                    visibility: ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Sampler(SamplerBindingLayout {
+                        r#type: Some(SamplerBindingType::Filtering),
                     }),
                 },
                 BindGroupLayoutEntry {
                     binding: 3,
-                    visibility: ShaderStage::VERTEX,
-                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
-                        r#type: Some(BufferBindingType::ReadOnlyStorage),
-                        ..Default::default()
+                    visibility: ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Texture(TextureBindingLayout {
+                        multisampled: None,
+                        sample_type: Some(TextureSampleType::UnfilterableFloat),
+                        view_dimension: Some(TextureViewDimension::D2),
                     }),
                 },
             ],
         });
 
+        let sampler = device.create_sampler(SamplerDescriptor {
+            label: Some("background image sampler".into()),
+            address_mode_u: Some(AddressMode::ClampToEdge),
+            address_mode_v: Some(AddressMode::ClampToEdge),
+            address_mode_w: None,
+            compare: None,
+            lod_max_clamp: None,
+            lod_min_clamp: None,
+            mag_filter: Some(FilterMode::Linear),
+            max_anisotropy: None,
+            min_filter: Some(FilterMode::Linear),
+            mipmap_filter: None,
+        });
+
         let pipeline = device
             .create_render_pipeline_async(RenderPipelineDescriptor {
-                label: Some("axis lines render pipeline".into()),
+                label: Some("background render pipeline".into()),
                 layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
                     PipelineLayoutDescriptor {
                         label: None,
@@ -149,7 +423,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                     entry_point: "fragment_main",
                     module: shader_module,
                     targets: [FragmentStateTarget {
-                        format: presentation_format,
+                        format: output.target_format(),
                         blend: Some(FragmentStateBlend {
                             alpha: FragmentStateBlendEntry {
                                 dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
@@ -167,7 +441,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                 }),
                 multisample: Some(MultisampleState {
                     alpha_to_coverage_enabled: None,
-                    count: Some(NUM_SAMPLES),
+                    count: Some(output.samples),
                     mask: None,
                 }),
                 primitive: Some(PrimitiveState {
@@ -180,89 +454,74 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
             })
             .await;
 
-        Self { layout, pipeline }
+        Self {
+            layout,
+            pipeline,
+            sampler,
+        }
     }
 
-    #[allow(clippy::too_many_arguments)]
     pub fn render(
         &self,
-        matrices: &buffers::MatricesBuffer,
-        config: &buffers::AxesConfigBuffer,
-        axes: &buffers::AxesBuffer,
-        axis_lines: &buffers::AxisLinesBuffer,
+        background: &buffers::BackgroundBuffers,
         viewport_start: (f32, f32),
         viewport_size: (f32, f32),
         device: &Device,
         render_pass: &RenderPassEncoder,
     ) {
-        let num_lines = axis_lines.len();
-        if num_lines == 0 {
-            return;
-        }
-
         let bind_group = device.create_bind_group(BindGroupDescriptor {
-            label: Some("axis lines bind group".into()),
+            label: Some("background bind group".into()),
             entries: [
                 BindGroupEntry {
                     binding: 0,
                     resource: BindGroupEntryResource::Buffer(BufferBinding {
-                        buffer: matrices.buffer().clone(),
+                        buffer: background.config().clone(),
                         offset: None,
                         size: None,
                     }),
                 },
                 BindGroupEntry {
                     binding: 1,
-                    resource: BindGroupEntryResource::Buffer(BufferBinding {
-                        buffer: config.buffer().clone(),
-                        offset: None,
-                        size: None,
-                    }),
+                    resource: BindGroupEntryResource::TextureView(background.image_view()),
                 },
                 BindGroupEntry {
                     binding: 2,
-                    resource: BindGroupEntryResource::Buffer(BufferBinding {
-                        buffer: axes.buffer().clone(),
-                        offset: None,
-                        size: None,
-                    }),
+                    resource: BindGroupEntryResource::Sampler(self.sampler.clone()),
                 },
                 BindGroupEntry {
                     binding: 3,
-                    resource: BindGroupEntryResource::Buffer(BufferBinding {
-                        buffer: axis_lines.buffer().clone(),
-                        offset: None,
-                        size: None,
-                    }),
+                    resource: BindGroupEntryResource::TextureView(background.gradient().view()),
                 },
             ],
             layout: self.layout.clone(),
         });
 
-        let (x, y) = viewport_start;
-        let (width, height) = viewport_size;
-
         render_pass.set_pipeline(&self.pipeline);
         render_pass.set_bind_group(0, &bind_group);
-        render_pass.set_viewport(x, y, width, height, 0.0, 1.0);
-        render_pass.draw_with_instance_count(6, num_lines);
+        set_viewport(render_pass, viewport_start, viewport_size);
+        render_pass.draw(6);
     }
 }
 
-pub struct DataLinesRenderPipeline {
+pub struct AxisRegionsRenderPipeline {
     layout: BindGroupLayout,
     pipeline: RenderPipeline,
 }
 
-impl DataLinesRenderPipeline {
-    async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
+impl AxisRegionsRenderPipeline {
+    async fn new(device: &Device, output: buffers::OutputFormat) -> Self {
+        let code = include_str!("./shaders/axis_regions.wgsl");
+        Self::from_source(device, output, code).await
+    }
+
+    async fn from_source(device: &Device, output: buffers::OutputFormat, code: &str) -> Self {
         let shader_module = device.create_shader_module(ShaderModuleDescriptor {
-            label: Some("data lines shader".into()),
-            code: include_str!("./shaders/data_lines.wgsl").into(),
+            label: Some("axis regions shader".into()),
+            code: preprocessor::bundled_render(code, device.limits(), output).into(),
         });
 
         let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
-            label: Some("data lines render pipeline bind group layout".into()),
+            label: Some("axis regions render pipeline bind group layout".into()),
             entries: [
                 BindGroupLayoutEntry {
                     binding: 0,
@@ -274,22 +533,6 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                 },
                 BindGroupLayoutEntry {
                     binding: 1,
-                    visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
-                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
-                        r#type: Some(BufferBindingType::Uniform),
-                        ..Default::default()
-                    }),
-                },
-                BindGroupLayoutEntry {
-                    binding: 2,
-                    visibility: ShaderStage::VERTEX,
-                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
-                        r#type: Some(BufferBindingType::ReadOnlyStorage),
-                        ..Default::default()
-                    }),
-                },
-                BindGroupLayoutEntry {
-                    binding: 3,
                     visibility: ShaderStage::VERTEX,
                     resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
                         r#type: Some(BufferBindingType::ReadOnlyStorage),
@@ -297,36 +540,19 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                     }),
                 },
                 BindGroupLayoutEntry {
-                    binding: 4,
-                    visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
-                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
-                        r#type: Some(BufferBindingType::ReadOnlyStorage),
-                        ..Default::default()
-                    }),
-                },
-                BindGroupLayoutEntry {
-                    binding: 5,
+                    binding: 2,
                     visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
                     resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
                         r#type: Some(BufferBindingType::ReadOnlyStorage),
                         ..Default::default()
                     }),
                 },
-                BindGroupLayoutEntry {
-                    binding: 6,
-                    visibility: ShaderStage::FRAGMENT,
-                    resource: BindGroupLayoutEntryResource::Texture(TextureBindingLayout {
-                        multisampled: None,
-                        sample_type: Some(TextureSampleType::UnfilterableFloat),
-                        view_dimension: Some(TextureViewDimension::D2),
-                    }),
-                },
             ],
         });
 
         let pipeline = device
             .create_render_pipeline_async(RenderPipelineDescriptor {
-                label: Some("data lines render pipeline".into()),
+                label: Some("axis regions render pipeline".into()),
                 layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
                     PipelineLayoutDescriptor {
                         label: None,
@@ -337,8 +563,8 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                     depth_bias: None,
                     depth_bias_clamp: None,
                     depth_bias_slope_scale: None,
-                    depth_compare: CompareFunction::LessEqual,
-                    depth_write_enabled: true,
+                    depth_compare: CompareFunction::Always,
+                    depth_write_enabled: false,
                     format: buffers::DepthTexture::DEPTH_FORMAT,
                 }),
                 vertex: VertexState {
@@ -349,7 +575,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                     entry_point: "fragment_main",
                     module: shader_module,
                     targets: [FragmentStateTarget {
-                        format: presentation_format,
+                        format: output.target_format(),
                         blend: Some(FragmentStateBlend {
                             alpha: FragmentStateBlendEntry {
                                 dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
@@ -367,7 +593,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                 }),
                 multisample: Some(MultisampleState {
                     alpha_to_coverage_enabled: None,
-                    count: Some(NUM_SAMPLES),
+                    count: Some(output.samples),
                     mask: None,
                 }),
                 primitive: Some(PrimitiveState {
@@ -383,28 +609,22 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
         Self { layout, pipeline }
     }
 
-    #[allow(clippy::too_many_arguments)]
     pub fn render(
         &self,
         matrices: &buffers::MatricesBuffer,
-        config: &buffers::DataConfigBuffer,
         axes: &buffers::AxesBuffer,
-        data_lines: &buffers::DataLinesBuffer,
-        color_values: &buffers::ColorValuesBuffer,
-        probabilities: &buffers::ProbabilitiesBuffer,
-        color_scale: &buffers::ColorScaleTexture,
-        viewport_start: (f32, f32),
-        viewport_size: (f32, f32),
+        regions: &buffers::AxisRegionsBuffer,
+        viewport: Viewport,
         device: &Device,
         render_pass: &RenderPassEncoder,
     ) {
-        let num_lines = data_lines.len();
-        if num_lines == 0 {
+        let num_regions = regions.len();
+        if num_regions == 0 {
             return;
         }
 
         let bind_group = device.create_bind_group(BindGroupDescriptor {
-            label: Some("data lines bind group".into()),
+            label: Some("axis regions bind group".into()),
             entries: [
                 BindGroupEntry {
                     binding: 0,
@@ -416,14 +636,6 @@ pub fn render(
                 },
                 BindGroupEntry {
                     binding: 1,
-                    resource: BindGroupEntryResource::Buffer(BufferBinding {
-                        buffer: config.buffer().clone(),
-                        offset: None,
-                        size: None,
-                    }),
-                },
-                BindGroupEntry {
-                    binding: 2,
                     resource: BindGroupEntryResource::Buffer(BufferBinding {
                         buffer: axes.buffer().clone(),
                         offset: None,
@@ -431,61 +643,43 @@ pub fn render(
                     }),
                 },
                 BindGroupEntry {
-                    binding: 3,
-                    resource: BindGroupEntryResource::Buffer(BufferBinding {
-                        buffer: data_lines.buffer().clone(),
-                        offset: None,
-                        size: None,
-                    }),
-                },
-                BindGroupEntry {
-                    binding: 4,
-                    resource: BindGroupEntryResource::Buffer(BufferBinding {
-                        buffer: color_values.buffer().clone(),
-                        offset: None,
-                        size: None,
-                    }),
-                },
-                BindGroupEntry {
-                    binding: 5,
+                    binding: 2,
                     resource: BindGroupEntryResource::Buffer(BufferBinding {
-                        buffer: probabilities.buffer().clone(),
+                        buffer: regions.buffer().clone(),
                         offset: None,
                         size: None,
                     }),
                 },
-                BindGroupEntry {
-                    binding: 6,
-                    resource: BindGroupEntryResource::TextureView(color_scale.view()),
-                },
             ],
             layout: self.layout.clone(),
         });
 
-        let (x, y) = viewport_start;
-        let (width, height) = viewport_size;
-
         render_pass.set_pipeline(&self.pipeline);
         render_pass.set_bind_group(0, &bind_group);
-        render_pass.set_viewport(x, y, width, height, 0.0, 1.0);
-        render_pass.draw_with_instance_count(6, num_lines);
+        set_viewport(render_pass, viewport.start, viewport.size);
+        render_pass.draw_with_instance_count(6, num_regions);
     }
 }
 
-pub struct CurveLinesRenderPipeline {
+pub struct AxisLinesRenderPipeline {
     layout: BindGroupLayout,
     pipeline: RenderPipeline,
 }
 
-impl CurveLinesRenderPipeline {
-    async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
+impl AxisLinesRenderPipeline {
+    async fn new(device: &Device, output: buffers::OutputFormat) -> Self {
+        let code = include_str!("./shaders/axis_lines.wgsl");
+        Self::from_source(device, output, code).await
+    }
+
+    async fn from_source(device: &Device, output: buffers::OutputFormat, code: &str) -> Self {
         let shader_module = device.create_shader_module(ShaderModuleDescriptor {
-            label: Some("curve lines shader".into()),
-            code: include_str!("./shaders/curve_lines.wgsl").into(),
+            label: Some("axis lines shader".into()),
+            code: preprocessor::bundled_render(code, device.limits(), output).into(),
         });
 
         let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
-            label: Some("curve lines render pipeline bind group layout".into()),
+            label: Some("axis lines render pipeline bind group layout".into()),
             entries: [
                 BindGroupLayoutEntry {
                     binding: 0,
@@ -524,7 +718,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
 
         let pipeline = device
             .create_render_pipeline_async(RenderPipelineDescriptor {
-                label: Some("curve lines render pipeline".into()),
+                label: Some("axis lines render pipeline".into()),
                 layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
                     PipelineLayoutDescriptor {
                         label: None,
@@ -547,7 +741,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                     entry_point: "fragment_main",
                     module: shader_module,
                     targets: [FragmentStateTarget {
-                        format: presentation_format,
+                        format: output.target_format(),
                         blend: Some(FragmentStateBlend {
                             alpha: FragmentStateBlendEntry {
                                 dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
@@ -565,7 +759,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                 }),
                 multisample: Some(MultisampleState {
                     alpha_to_coverage_enabled: None,
-                    count: Some(NUM_SAMPLES),
+                    count: Some(output.samples),
                     mask: None,
                 }),
                 primitive: Some(PrimitiveState {
@@ -585,21 +779,21 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
     pub fn render(
         &self,
         matrices: &buffers::MatricesBuffer,
-        config: &buffers::CurvesConfigBuffer,
+        config: &buffers::AxesConfigBuffer,
         axes: &buffers::AxesBuffer,
-        curve_lines: &buffers::CurveLinesInfoBuffer,
+        axis_lines: &buffers::AxisLinesBuffer,
         viewport_start: (f32, f32),
         viewport_size: (f32, f32),
         device: &Device,
         render_pass: &RenderPassEncoder,
     ) {
-        let num_lines = curve_lines.len();
+        let num_lines = axis_lines.len();
         if num_lines == 0 {
             return;
         }
 
         let bind_group = device.create_bind_group(BindGroupDescriptor {
-            label: Some("curve lines bind group".into()),
+            label: Some("axis lines bind group".into()),
             entries: [
                 BindGroupEntry {
                     binding: 0,
@@ -628,7 +822,7 @@ pub fn render(
                 BindGroupEntry {
                     binding: 3,
                     resource: BindGroupEntryResource::Buffer(BufferBinding {
-                        buffer: curve_lines.buffer().clone(),
+                        buffer: axis_lines.buffer().clone(),
                         offset: None,
                         size: None,
                     }),
@@ -637,30 +831,32 @@ pub fn render(
             layout: self.layout.clone(),
         });
 
-        let (x, y) = viewport_start;
-        let (width, height) = viewport_size;
-
         render_pass.set_pipeline(&self.pipeline);
         render_pass.set_bind_group(0, &bind_group);
-        render_pass.set_viewport(x, y, width, height, 0.0, 1.0);
+        set_viewport(render_pass, viewport_start, viewport_size);
         render_pass.draw_with_instance_count(6, num_lines);
     }
 }
 
-pub struct SelectionsRenderPipeline {
+pub struct DataLinesRenderPipeline {
     layout: BindGroupLayout,
     pipeline: RenderPipeline,
 }
 
-impl SelectionsRenderPipeline {
-    async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
+impl DataLinesRenderPipeline {
+    async fn new(device: &Device, output: buffers::OutputFormat) -> Self {
+        let code = include_str!("./shaders/data_lines.wgsl");
+        Self::from_source(device, output, code).await
+    }
+
+    async fn from_source(device: &Device, output: buffers::OutputFormat, code: &str) -> Self {
         let shader_module = device.create_shader_module(ShaderModuleDescriptor {
-            label: Some("selections shader".into()),
-            code: include_str!("./shaders/selections.wgsl").into(),
+            label: Some("data lines shader".into()),
+            code: preprocessor::bundled_render(code, device.limits(), output).into(),
         });
 
         let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
-            label: Some("selections render pipeline bind group layout".into()),
+            label: Some("data lines render pipeline bind group layout".into()),
             entries: [
                 BindGroupLayoutEntry {
                     binding: 0,
@@ -688,7 +884,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                 },
                 BindGroupLayoutEntry {
                     binding: 3,
-                    visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
+                    visibility: ShaderStage::VERTEX,
                     resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
                         r#type: Some(BufferBindingType::ReadOnlyStorage),
                         ..Default::default()
@@ -696,7 +892,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                 },
                 BindGroupLayoutEntry {
                     binding: 4,
-                    visibility: ShaderStage::FRAGMENT,
+                    visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
                     resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
                         r#type: Some(BufferBindingType::ReadOnlyStorage),
                         ..Default::default()
@@ -704,11 +900,19 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                 },
                 BindGroupLayoutEntry {
                     binding: 5,
+                    visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 6,
                     visibility: ShaderStage::FRAGMENT,
                     resource: BindGroupLayoutEntryResource::Texture(TextureBindingLayout {
                         multisampled: None,
                         sample_type: Some(TextureSampleType::UnfilterableFloat),
-                        view_dimension: Some(TextureViewDimension::D2Array),
+                        view_dimension: Some(TextureViewDimension::D2),
                     }),
                 },
             ],
@@ -716,7 +920,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
 
         let pipeline = device
             .create_render_pipeline_async(RenderPipelineDescriptor {
-                label: Some("selections render pipeline".into()),
+                label: Some("data lines render pipeline".into()),
                 layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
                     PipelineLayoutDescriptor {
                         label: None,
@@ -727,8 +931,8 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                     depth_bias: None,
                     depth_bias_clamp: None,
                     depth_bias_slope_scale: None,
-                    depth_compare: CompareFunction::Always,
-                    depth_write_enabled: false,
+                    depth_compare: CompareFunction::LessEqual,
+                    depth_write_enabled: true,
                     format: buffers::DepthTexture::DEPTH_FORMAT,
                 }),
                 vertex: VertexState {
@@ -739,7 +943,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                     entry_point: "fragment_main",
                     module: shader_module,
                     targets: [FragmentStateTarget {
-                        format: presentation_format,
+                        format: output.target_format(),
                         blend: Some(FragmentStateBlend {
                             alpha: FragmentStateBlendEntry {
                                 dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
@@ -757,7 +961,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                 }),
                 multisample: Some(MultisampleState {
                     alpha_to_coverage_enabled: None,
-                    count: Some(NUM_SAMPLES),
+                    count: Some(output.samples),
                     mask: None,
                 }),
                 primitive: Some(PrimitiveState {
@@ -777,23 +981,24 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
     pub fn render(
         &self,
         matrices: &buffers::MatricesBuffer,
-        config: &buffers::SelectionsConfigBuffer,
+        config: &buffers::DataConfigBuffer,
         axes: &buffers::AxesBuffer,
-        selection_infos: &buffers::SelectionLinesBuffer,
-        colors: &buffers::LabelColorBuffer,
-        probability_samples: &buffers::ProbabilitySampleTexture,
+        data_lines: &buffers::DataLinesBuffer,
+        color_values: &buffers::ColorValuesBuffer,
+        probabilities: &buffers::ProbabilitiesBuffer,
+        color_scale: &buffers::ColorScaleTexture,
         viewport_start: (f32, f32),
         viewport_size: (f32, f32),
         device: &Device,
         render_pass: &RenderPassEncoder,
     ) {
-        let num_selections = selection_infos.len();
-        if num_selections == 0 {
+        let num_lines = data_lines.len();
+        if num_lines == 0 {
             return;
         }
 
         let bind_group = device.create_bind_group(BindGroupDescriptor {
-            label: Some("selections bind group".into()),
+            label: Some("data lines bind group".into()),
             entries: [
                 BindGroupEntry {
                     binding: 0,
@@ -822,7 +1027,7 @@ pub fn render(
                 BindGroupEntry {
                     binding: 3,
                     resource: BindGroupEntryResource::Buffer(BufferBinding {
-                        buffer: selection_infos.buffer().clone(),
+                        buffer: data_lines.buffer().clone(),
                         offset: None,
                         size: None,
                     }),
@@ -830,43 +1035,53 @@ pub fn render(
                 BindGroupEntry {
                     binding: 4,
                     resource: BindGroupEntryResource::Buffer(BufferBinding {
-                        buffer: colors.buffer().clone(),
+                        buffer: color_values.buffer().clone(),
                         offset: None,
                         size: None,
                     }),
                 },
                 BindGroupEntry {
                     binding: 5,
-                    resource: BindGroupEntryResource::TextureView(probability_samples.array_view()),
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: probabilities.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 6,
+                    resource: BindGroupEntryResource::TextureView(color_scale.view()),
                 },
             ],
             layout: self.layout.clone(),
         });
 
-        let (x, y) = viewport_start;
-        let (width, height) = viewport_size;
-
         render_pass.set_pipeline(&self.pipeline);
         render_pass.set_bind_group(0, &bind_group);
-        render_pass.set_viewport(x, y, width, height, 0.0, 1.0);
-        render_pass.draw_with_instance_count(6, num_selections);
+        set_viewport(render_pass, viewport_start, viewport_size);
+        render_pass.draw_with_instance_count(6, num_lines);
     }
 }
 
-pub struct CurveSegmentsRenderPipeline {
+pub struct ReferenceLinesRenderPipeline {
     layout: BindGroupLayout,
     pipeline: RenderPipeline,
 }
 
-impl CurveSegmentsRenderPipeline {
-    async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
+impl ReferenceLinesRenderPipeline {
+    async fn new(device: &Device, output: buffers::OutputFormat) -> Self {
+        let code = include_str!("./shaders/reference_lines.wgsl");
+        Self::from_source(device, output, code).await
+    }
+
+    async fn from_source(device: &Device, output: buffers::OutputFormat, code: &str) -> Self {
         let shader_module = device.create_shader_module(ShaderModuleDescriptor {
-            label: Some("curve segments shader".into()),
-            code: include_str!("./shaders/curve_segments.wgsl").into(),
+            label: Some("reference lines shader".into()),
+            code: preprocessor::bundled_render(code, device.limits(), output).into(),
         });
 
         let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
-            label: Some("curve segments render pipeline bind group layout".into()),
+            label: Some("reference lines render pipeline bind group layout".into()),
             entries: [
                 BindGroupLayoutEntry {
                     binding: 0,
@@ -900,20 +1115,14 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                         ..Default::default()
                     }),
                 },
-                BindGroupLayoutEntry {
-                    binding: 4,
-                    visibility: ShaderStage::FRAGMENT,
-                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
-                        r#type: Some(BufferBindingType::ReadOnlyStorage),
-                        ..Default::default()
-                    }),
-                },
             ],
         });
 
+        // The reference and snapshot lines are drawn before, and therefore
+        // behind, the data lines, so they must not write to the depth buffer.
         let pipeline = device
             .create_render_pipeline_async(RenderPipelineDescriptor {
-                label: Some("curve segments render pipeline".into()),
+                label: Some("reference lines render pipeline".into()),
                 layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
                     PipelineLayoutDescriptor {
                         label: None,
@@ -936,7 +1145,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                     entry_point: "fragment_main",
                     module: shader_module,
                     targets: [FragmentStateTarget {
-                        format: presentation_format,
+                        format: output.target_format(),
                         blend: Some(FragmentStateBlend {
                             alpha: FragmentStateBlendEntry {
                                 dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
@@ -954,7 +1163,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                 }),
                 multisample: Some(MultisampleState {
                     alpha_to_coverage_enabled: None,
-                    count: Some(NUM_SAMPLES),
+                    count: Some(output.samples),
                     mask: None,
                 }),
                 primitive: Some(PrimitiveState {
@@ -973,34 +1182,22 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
     #[allow(clippy::too_many_arguments)]
     pub fn render(
         &self,
-        label_idx: usize,
-        active_label_idx: usize,
-        min_curve_t: f32,
         matrices: &buffers::MatricesBuffer,
+        config: &buffers::ReferenceConfigBuffer,
         axes: &buffers::AxesBuffer,
-        curve_lines: &buffers::CurveLinesInfoBuffer,
-        label_colors: &buffers::LabelColorBuffer,
+        reference_lines: &buffers::DataLinesBuffer,
         viewport_start: (f32, f32),
         viewport_size: (f32, f32),
         device: &Device,
         render_pass: &RenderPassEncoder,
     ) {
-        let num_lines = curve_lines.len();
+        let num_lines = reference_lines.len();
         if num_lines == 0 {
             return;
         }
 
-        let config = buffers::CurveSegmentConfigBuffer::new(
-            device,
-            buffers::CurveSegmentConfig {
-                label: label_idx as u32,
-                active_label: active_label_idx as u32,
-                min_curve_t,
-            },
-        );
-
         let bind_group = device.create_bind_group(BindGroupDescriptor {
-            label: Some("curve segments bind group".into()),
+            label: Some("reference lines bind group".into()),
             entries: [
                 BindGroupEntry {
                     binding: 0,
@@ -1029,15 +1226,7 @@ pub fn render(
                 BindGroupEntry {
                     binding: 3,
                     resource: BindGroupEntryResource::Buffer(BufferBinding {
-                        buffer: curve_lines.buffer().clone(),
-                        offset: None,
-                        size: None,
-                    }),
-                },
-                BindGroupEntry {
-                    binding: 4,
-                    resource: BindGroupEntryResource::Buffer(BufferBinding {
-                        buffer: label_colors.buffer().clone(),
+                        buffer: reference_lines.buffer().clone(),
                         offset: None,
                         size: None,
                     }),
@@ -1046,47 +1235,71 @@ pub fn render(
             layout: self.layout.clone(),
         });
 
-        let (x, y) = viewport_start;
-        let (width, height) = viewport_size;
-
         render_pass.set_pipeline(&self.pipeline);
         render_pass.set_bind_group(0, &bind_group);
-        render_pass.set_viewport(x, y, width, height, 0.0, 1.0);
+        set_viewport(render_pass, viewport_start, viewport_size);
         render_pass.draw_with_instance_count(6, num_lines);
     }
 }
 
-pub struct ColorBarRenderPipeline {
+pub struct FlowRibbonsRenderPipeline {
     layout: BindGroupLayout,
     pipeline: RenderPipeline,
 }
 
-impl ColorBarRenderPipeline {
-    async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
+impl FlowRibbonsRenderPipeline {
+    async fn new(device: &Device, output: buffers::OutputFormat) -> Self {
+        let code = include_str!("./shaders/flow_ribbons.wgsl");
+        Self::from_source(device, output, code).await
+    }
+
+    async fn from_source(device: &Device, output: buffers::OutputFormat, code: &str) -> Self {
         let shader_module = device.create_shader_module(ShaderModuleDescriptor {
-            label: Some("color bar shader".into()),
-            code: include_str!("./shaders/color_bar.wgsl").into(),
+            label: Some("flow ribbons shader".into()),
+            code: preprocessor::bundled_render(code, device.limits(), output).into(),
         });
 
         let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
-            label: Some("color bar rendering bind group layout".into()),
+            label: Some("flow ribbons render pipeline bind group layout".into()),
             entries: [
                 BindGroupLayoutEntry {
                     binding: 0,
-                    visibility: ShaderStage::FRAGMENT,
-                    resource: BindGroupLayoutEntryResource::Texture(TextureBindingLayout {
-                        multisampled: None,
-                        sample_type: Some(TextureSampleType::UnfilterableFloat),
-                        view_dimension: Some(TextureViewDimension::D2),
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::Uniform),
+                        ..Default::default()
                     }),
                 },
                 BindGroupLayoutEntry {
                     binding: 1,
-                    visibility: ShaderStage::FRAGMENT,
+                    visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
                     resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
-                        has_dynamic_offset: None,
-                        min_binding_size: None,
                         r#type: Some(BufferBindingType::Uniform),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
                     }),
                 },
             ],
@@ -1094,7 +1307,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
 
         let pipeline = device
             .create_render_pipeline_async(RenderPipelineDescriptor {
-                label: Some("color bar render pipeline".into()),
+                label: Some("flow ribbons render pipeline".into()),
                 layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
                     PipelineLayoutDescriptor {
                         label: None,
@@ -1117,7 +1330,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                     entry_point: "fragment_main",
                     module: shader_module,
                     targets: [FragmentStateTarget {
-                        format: presentation_format,
+                        format: output.target_format(),
                         blend: Some(FragmentStateBlend {
                             alpha: FragmentStateBlendEntry {
                                 dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
@@ -1135,7 +1348,7 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
                 }),
                 multisample: Some(MultisampleState {
                     alpha_to_coverage_enabled: None,
-                    count: Some(NUM_SAMPLES),
+                    count: Some(output.samples),
                     mask: None,
                 }),
                 primitive: Some(PrimitiveState {
@@ -1151,27 +1364,60 @@ async fn new(device: &Device, presentation_format: TextureFormat) -> Self {
         Self { layout, pipeline }
     }
 
-    #[allow(clippy::too_many_arguments)]
+    /// Draws one ribbon for each pair of categories of the adjacent axes.
     pub fn render(
         &self,
-        color_scale: &buffers::ColorScaleTexture,
-        color_scale_bounds: &buffers::ColorScaleBoundsBuffer,
-        viewport_start: (f32, f32),
-        viewport_size: (f32, f32),
+        matrices: &buffers::MatricesBuffer,
+        axes: &buffers::AxesBuffer,
+        flows: &buffers::FlowBuffers,
+        viewport: Viewport,
         device: &Device,
         render_pass: &RenderPassEncoder,
     ) {
+        let num_pairs = flows.num_pairs();
+        if num_pairs == 0 {
+            return;
+        }
+
         let bind_group = device.create_bind_group(BindGroupDescriptor {
-            label: Some("color bar bind group".into()),
+            label: Some("flow ribbons bind group".into()),
             entries: [
                 BindGroupEntry {
                     binding: 0,
-                    resource: BindGroupEntryResource::TextureView(color_scale.view()),
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: matrices.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
                 },
                 BindGroupEntry {
                     binding: 1,
                     resource: BindGroupEntryResource::Buffer(BufferBinding {
-                        buffer: color_scale_bounds.buffer().clone(),
+                        buffer: flows.config().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: axes.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: flows.pairs().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: flows.counts().clone(),
                         offset: None,
                         size: None,
                     }),
@@ -1180,105 +1426,1595 @@ pub fn render(
             layout: self.layout.clone(),
         });
 
-        let (x, y) = viewport_start;
-        let (width, height) = viewport_size;
+        let num_vertices = 6 * buffers::FlowPair::NUM_SEGMENTS as usize;
+        let num_ribbons = num_pairs * buffers::MAX_FLOW_CATEGORIES * buffers::MAX_FLOW_CATEGORIES;
 
         render_pass.set_pipeline(&self.pipeline);
         render_pass.set_bind_group(0, &bind_group);
-        render_pass.set_viewport(x, y, width, height, 0.0, 1.0);
-        render_pass.draw(6);
+        set_viewport(render_pass, viewport.start, viewport.size);
+        render_pass.draw_with_instance_count(num_vertices, num_ribbons);
     }
 }
 
-pub struct ComputePipelines {
-    pub create_curves: (BindGroupLayout, ComputePipeline),
-    pub compute_probability: ProbabilityComputationPipeline,
-    pub transform_color_scale: (BindGroupLayout, ComputePipeline),
-    curve_spline_sampling: ProbabilityCurveSplineSamplingComputePipeline,
-    //
-    //
-    color_scale_sampling: ColorScaleSamplingComputePipeline,
-}
-
-pub struct ProbabilityComputationPipeline {
-    pub apply_curve_bind_layout: BindGroupLayout,
-    pub apply_curve_pipeline: ComputePipeline,
-    pub reduce_bind_layout: BindGroupLayout,
-    pub reduce_pipeline: ComputePipeline,
+pub struct CurveLinesRenderPipeline {
+    layout: BindGroupLayout,
+    pipeline: RenderPipeline,
 }
 
-impl ComputePipelines {
-    pub async fn new(device: &Device) -> Self {
-        let create_curves = Self::init_curve_creation_pipeline(device).await;
-        let compute_probability = Self::init_probability_computation_pipeline(device).await;
-        let transform_color_scale = Self::init_color_scale_transformation_pipeline(device).await;
-
-        Self {
-            create_curves,
-            compute_probability,
-            transform_color_scale,
-            curve_spline_sampling: ProbabilityCurveSplineSamplingComputePipeline::new(device).await,
-            color_scale_sampling: ColorScaleSamplingComputePipeline::new(device).await,
-        }
-    }
-
-    pub fn curve_spline_sampling(&self) -> &ProbabilityCurveSplineSamplingComputePipeline {
-        &self.curve_spline_sampling
+impl CurveLinesRenderPipeline {
+    async fn new(device: &Device, output: buffers::OutputFormat) -> Self {
+        let code = include_str!("./shaders/curve_lines.wgsl");
+        Self::from_source(device, output, code).await
     }
 
-    pub fn color_scale_sampling(&self) -> &ColorScaleSamplingComputePipeline {
-        &self.color_scale_sampling
-    }
+    async fn from_source(device: &Device, output: buffers::OutputFormat, code: &str) -> Self {
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("curve lines shader".into()),
+            code: preprocessor::bundled_render(code, device.limits(), output).into(),
+        });
 
-    async fn init_curve_creation_pipeline(device: &Device) -> (BindGroupLayout, ComputePipeline) {
-        let bind_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
-            label: Some("curve creation bind group layout".into()),
+        let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("curve lines render pipeline bind group layout".into()),
             entries: [
                 BindGroupLayoutEntry {
                     binding: 0,
-                    visibility: ShaderStage::COMPUTE,
+                    visibility: ShaderStage::VERTEX,
                     resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
-                        has_dynamic_offset: None,
-                        min_binding_size: None,
-                        r#type: Some(BufferBindingType::Storage),
+                        r#type: Some(BufferBindingType::Uniform),
+                        ..Default::default()
                     }),
                 },
                 BindGroupLayoutEntry {
                     binding: 1,
-                    visibility: ShaderStage::COMPUTE,
-                    resource: BindGroupLayoutEntryResource::Texture(TextureBindingLayout {
-                        multisampled: None,
-                        sample_type: Some(TextureSampleType::UnfilterableFloat),
-                        view_dimension: Some(TextureViewDimension::D2Array),
+                    visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::Uniform),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
                     }),
                 },
             ],
         });
 
         let pipeline = device
-            .create_compute_pipeline_async(ComputePipelineDescriptor {
-                label: Some("curve creation compute pipeline".into()),
+            .create_render_pipeline_async(RenderPipelineDescriptor {
+                label: Some("curve lines render pipeline".into()),
                 layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
                     PipelineLayoutDescriptor {
-                        label: Some("curve creation pipeline layout".into()),
-                        layouts: [bind_layout.clone()],
+                        label: None,
+                        layouts: [layout.clone()],
                     },
                 )),
-                compute: ProgrammableStage {
-                    entry_point: "main",
-                    module: device.create_shader_module(ShaderModuleDescriptor {
-                        label: Some("curve creation compute shader".into()),
-                        code: include_str!("./shaders/create_curves.comp.wgsl").into(),
-                    }),
-                },
-            })
-            .await;
-
-        (bind_layout, pipeline)
-    }
-
-    async fn init_probability_computation_pipeline(
-        device: &Device,
+                depth_stencil: Some(DepthStencilState {
+                    depth_bias: None,
+                    depth_bias_clamp: None,
+                    depth_bias_slope_scale: None,
+                    depth_compare: CompareFunction::Always,
+                    depth_write_enabled: false,
+                    format: buffers::DepthTexture::DEPTH_FORMAT,
+                }),
+                vertex: VertexState {
+                    entry_point: "vertex_main",
+                    module: shader_module.clone(),
+                },
+                fragment: Some(FragmentState {
+                    entry_point: "fragment_main",
+                    module: shader_module,
+                    targets: [FragmentStateTarget {
+                        format: output.target_format(),
+                        blend: Some(FragmentStateBlend {
+                            alpha: FragmentStateBlendEntry {
+                                dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
+                                operation: Some(BlendOperation::Add),
+                                src_factor: Some(BlendFactor::One),
+                            },
+                            color: FragmentStateBlendEntry {
+                                dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
+                                operation: Some(BlendOperation::Add),
+                                src_factor: Some(BlendFactor::One),
+                            },
+                        }),
+                        write_mask: None,
+                    }],
+                }),
+                multisample: Some(MultisampleState {
+                    alpha_to_coverage_enabled: None,
+                    count: Some(output.samples),
+                    mask: None,
+                }),
+                primitive: Some(PrimitiveState {
+                    cull_mode: None,
+                    front_face: None,
+                    strip_index_format: None,
+                    topology: Some(PrimitiveTopology::TriangleList),
+                    unclipped_depth: None,
+                }),
+            })
+            .await;
+
+        Self { layout, pipeline }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &self,
+        matrices: &buffers::MatricesBuffer,
+        config: &buffers::CurvesConfigBuffer,
+        axes: &buffers::AxesBuffer,
+        curve_lines: &buffers::CurveLinesInfoBuffer,
+        viewport_start: (f32, f32),
+        viewport_size: (f32, f32),
+        device: &Device,
+        render_pass: &RenderPassEncoder,
+    ) {
+        let num_lines = curve_lines.len();
+        if num_lines == 0 {
+            return;
+        }
+
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("curve lines bind group".into()),
+            entries: [
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: matrices.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: config.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: axes.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: curve_lines.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+            ],
+            layout: self.layout.clone(),
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group);
+        set_viewport(render_pass, viewport_start, viewport_size);
+        render_pass.draw_with_instance_count(6, num_lines);
+    }
+}
+
+pub struct DensityCurvesRenderPipeline {
+    layout: BindGroupLayout,
+    pipeline: RenderPipeline,
+}
+
+impl DensityCurvesRenderPipeline {
+    async fn new(device: &Device, output: buffers::OutputFormat) -> Self {
+        let code = include_str!("./shaders/density_curves.wgsl");
+        Self::from_source(device, output, code).await
+    }
+
+    async fn from_source(device: &Device, output: buffers::OutputFormat, code: &str) -> Self {
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("density curves shader".into()),
+            code: preprocessor::bundled_render(code, device.limits(), output).into(),
+        });
+
+        let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("density curves render pipeline bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::Uniform),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::Uniform),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
+                    }),
+                },
+            ],
+        });
+
+        let pipeline = device
+            .create_render_pipeline_async(RenderPipelineDescriptor {
+                label: Some("density curves render pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: None,
+                        layouts: [layout.clone()],
+                    },
+                )),
+                depth_stencil: Some(DepthStencilState {
+                    depth_bias: None,
+                    depth_bias_clamp: None,
+                    depth_bias_slope_scale: None,
+                    depth_compare: CompareFunction::Always,
+                    depth_write_enabled: false,
+                    format: buffers::DepthTexture::DEPTH_FORMAT,
+                }),
+                vertex: VertexState {
+                    entry_point: "vertex_main",
+                    module: shader_module.clone(),
+                },
+                fragment: Some(FragmentState {
+                    entry_point: "fragment_main",
+                    module: shader_module,
+                    targets: [FragmentStateTarget {
+                        format: output.target_format(),
+                        blend: Some(FragmentStateBlend {
+                            alpha: FragmentStateBlendEntry {
+                                dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
+                                operation: Some(BlendOperation::Add),
+                                src_factor: Some(BlendFactor::One),
+                            },
+                            color: FragmentStateBlendEntry {
+                                dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
+                                operation: Some(BlendOperation::Add),
+                                src_factor: Some(BlendFactor::One),
+                            },
+                        }),
+                        write_mask: None,
+                    }],
+                }),
+                multisample: Some(MultisampleState {
+                    alpha_to_coverage_enabled: None,
+                    count: Some(output.samples),
+                    mask: None,
+                }),
+                primitive: Some(PrimitiveState {
+                    cull_mode: None,
+                    front_face: None,
+                    strip_index_format: None,
+                    topology: Some(PrimitiveTopology::TriangleList),
+                    unclipped_depth: None,
+                }),
+            })
+            .await;
+
+        Self { layout, pipeline }
+    }
+
+    /// Draws the density curves of the visible axes.
+    pub fn render(
+        &self,
+        matrices: &buffers::MatricesBuffer,
+        axes: &buffers::AxesBuffer,
+        densities: &buffers::DensityBuffers,
+        viewport: Viewport,
+        device: &Device,
+        render_pass: &RenderPassEncoder,
+    ) {
+        let num_axes = densities.num_axes();
+        if num_axes == 0 {
+            return;
+        }
+
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("density curves bind group".into()),
+            entries: [
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: matrices.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: densities.config().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: axes.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: densities.densities().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+            ],
+            layout: self.layout.clone(),
+        });
+
+        // Two curves per axis, one for all datums and one for the selected datums.
+        let num_segments = buffers::DensityConfig::NUM_SAMPLES as usize - 1;
+        let num_lines = 2 * num_axes * num_segments;
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group);
+        set_viewport(render_pass, viewport.start, viewport.size);
+        render_pass.draw_with_instance_count(6, num_lines);
+    }
+}
+
+pub struct HistogramBarsRenderPipeline {
+    layout: BindGroupLayout,
+    pipeline: RenderPipeline,
+}
+
+impl HistogramBarsRenderPipeline {
+    async fn new(device: &Device, output: buffers::OutputFormat) -> Self {
+        let code = include_str!("./shaders/histogram_bars.wgsl");
+        Self::from_source(device, output, code).await
+    }
+
+    async fn from_source(device: &Device, output: buffers::OutputFormat, code: &str) -> Self {
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("histogram bars shader".into()),
+            code: preprocessor::bundled_render(code, device.limits(), output).into(),
+        });
+
+        let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("histogram bars render pipeline bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::Uniform),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::Uniform),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
+                    }),
+                },
+            ],
+        });
+
+        let pipeline = device
+            .create_render_pipeline_async(RenderPipelineDescriptor {
+                label: Some("histogram bars render pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: None,
+                        layouts: [layout.clone()],
+                    },
+                )),
+                depth_stencil: Some(DepthStencilState {
+                    depth_bias: None,
+                    depth_bias_clamp: None,
+                    depth_bias_slope_scale: None,
+                    depth_compare: CompareFunction::Always,
+                    depth_write_enabled: false,
+                    format: buffers::DepthTexture::DEPTH_FORMAT,
+                }),
+                vertex: VertexState {
+                    entry_point: "vertex_main",
+                    module: shader_module.clone(),
+                },
+                fragment: Some(FragmentState {
+                    entry_point: "fragment_main",
+                    module: shader_module,
+                    targets: [FragmentStateTarget {
+                        format: output.target_format(),
+                        blend: Some(FragmentStateBlend {
+                            alpha: FragmentStateBlendEntry {
+                                dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
+                                operation: Some(BlendOperation::Add),
+                                src_factor: Some(BlendFactor::One),
+                            },
+                            color: FragmentStateBlendEntry {
+                                dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
+                                operation: Some(BlendOperation::Add),
+                                src_factor: Some(BlendFactor::One),
+                            },
+                        }),
+                        write_mask: None,
+                    }],
+                }),
+                multisample: Some(MultisampleState {
+                    alpha_to_coverage_enabled: None,
+                    count: Some(output.samples),
+                    mask: None,
+                }),
+                primitive: Some(PrimitiveState {
+                    cull_mode: None,
+                    front_face: None,
+                    strip_index_format: None,
+                    topology: Some(PrimitiveTopology::TriangleList),
+                    unclipped_depth: None,
+                }),
+            })
+            .await;
+
+        Self { layout, pipeline }
+    }
+
+    /// Draws the histogram bars of the visible axes.
+    pub fn render(
+        &self,
+        matrices: &buffers::MatricesBuffer,
+        axes: &buffers::AxesBuffer,
+        histograms: &buffers::HistogramBuffers,
+        viewport: Viewport,
+        device: &Device,
+        render_pass: &RenderPassEncoder,
+    ) {
+        let num_axes = histograms.num_axes();
+        if num_axes == 0 {
+            return;
+        }
+
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("histogram bars bind group".into()),
+            entries: [
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: matrices.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: histograms.config().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: axes.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: histograms.bins().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+            ],
+            layout: self.layout.clone(),
+        });
+
+        // Two histograms per axis, one for all datums and one for the selected datums.
+        let num_bars = 2 * num_axes * histograms.num_bins();
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group);
+        set_viewport(render_pass, viewport.start, viewport.size);
+        render_pass.draw_with_instance_count(6, num_bars);
+    }
+}
+
+pub struct SelectionsRenderPipeline {
+    layout: BindGroupLayout,
+    pipeline: RenderPipeline,
+}
+
+impl SelectionsRenderPipeline {
+    async fn new(device: &Device, output: buffers::OutputFormat) -> Self {
+        let code = include_str!("./shaders/selections.wgsl");
+        Self::from_source(device, output, code).await
+    }
+
+    async fn from_source(device: &Device, output: buffers::OutputFormat, code: &str) -> Self {
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("selections shader".into()),
+            code: preprocessor::bundled_render(code, device.limits(), output).into(),
+        });
+
+        let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("selections render pipeline bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::Uniform),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::Uniform),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 5,
+                    visibility: ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Texture(TextureBindingLayout {
+                        multisampled: None,
+                        sample_type: Some(TextureSampleType::UnfilterableFloat),
+                        view_dimension: Some(TextureViewDimension::D2Array),
+                    }),
+                },
+            ],
+        });
+
+        let pipeline = device
+            .create_render_pipeline_async(RenderPipelineDescriptor {
+                label: Some("selections render pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: None,
+                        layouts: [layout.clone()],
+                    },
+                )),
+                depth_stencil: Some(DepthStencilState {
+                    depth_bias: None,
+                    depth_bias_clamp: None,
+                    depth_bias_slope_scale: None,
+                    depth_compare: CompareFunction::Always,
+                    depth_write_enabled: false,
+                    format: buffers::DepthTexture::DEPTH_FORMAT,
+                }),
+                vertex: VertexState {
+                    entry_point: "vertex_main",
+                    module: shader_module.clone(),
+                },
+                fragment: Some(FragmentState {
+                    entry_point: "fragment_main",
+                    module: shader_module,
+                    targets: [FragmentStateTarget {
+                        format: output.target_format(),
+                        blend: Some(FragmentStateBlend {
+                            alpha: FragmentStateBlendEntry {
+                                dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
+                                operation: Some(BlendOperation::Add),
+                                src_factor: Some(BlendFactor::One),
+                            },
+                            color: FragmentStateBlendEntry {
+                                dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
+                                operation: Some(BlendOperation::Add),
+                                src_factor: Some(BlendFactor::One),
+                            },
+                        }),
+                        write_mask: None,
+                    }],
+                }),
+                multisample: Some(MultisampleState {
+                    alpha_to_coverage_enabled: None,
+                    count: Some(output.samples),
+                    mask: None,
+                }),
+                primitive: Some(PrimitiveState {
+                    cull_mode: None,
+                    front_face: None,
+                    strip_index_format: None,
+                    topology: Some(PrimitiveTopology::TriangleList),
+                    unclipped_depth: None,
+                }),
+            })
+            .await;
+
+        Self { layout, pipeline }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &self,
+        matrices: &buffers::MatricesBuffer,
+        config: &buffers::SelectionsConfigBuffer,
+        axes: &buffers::AxesBuffer,
+        selection_infos: &buffers::SelectionLinesBuffer,
+        colors: &buffers::LabelColorBuffer,
+        probability_samples: &buffers::ProbabilitySampleTexture,
+        viewport_start: (f32, f32),
+        viewport_size: (f32, f32),
+        device: &Device,
+        render_pass: &RenderPassEncoder,
+    ) {
+        let num_selections = selection_infos.len();
+        if num_selections == 0 {
+            return;
+        }
+
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("selections bind group".into()),
+            entries: [
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: matrices.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: config.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: axes.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: selection_infos.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: colors.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 5,
+                    resource: BindGroupEntryResource::TextureView(probability_samples.array_view()),
+                },
+            ],
+            layout: self.layout.clone(),
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group);
+        set_viewport(render_pass, viewport_start, viewport_size);
+        render_pass.draw_with_instance_count(6, num_selections);
+    }
+}
+
+pub struct CurveSegmentsRenderPipeline {
+    layout: BindGroupLayout,
+    pipeline: RenderPipeline,
+}
+
+impl CurveSegmentsRenderPipeline {
+    async fn new(device: &Device, output: buffers::OutputFormat) -> Self {
+        let code = include_str!("./shaders/curve_segments.wgsl");
+        Self::from_source(device, output, code).await
+    }
+
+    async fn from_source(device: &Device, output: buffers::OutputFormat, code: &str) -> Self {
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("curve segments shader".into()),
+            code: preprocessor::bundled_render(code, device.limits(), output).into(),
+        });
+
+        let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("curve segments render pipeline bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::Uniform),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::Uniform),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
+                    }),
+                },
+            ],
+        });
+
+        let pipeline = device
+            .create_render_pipeline_async(RenderPipelineDescriptor {
+                label: Some("curve segments render pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: None,
+                        layouts: [layout.clone()],
+                    },
+                )),
+                depth_stencil: Some(DepthStencilState {
+                    depth_bias: None,
+                    depth_bias_clamp: None,
+                    depth_bias_slope_scale: None,
+                    depth_compare: CompareFunction::Always,
+                    depth_write_enabled: false,
+                    format: buffers::DepthTexture::DEPTH_FORMAT,
+                }),
+                vertex: VertexState {
+                    entry_point: "vertex_main",
+                    module: shader_module.clone(),
+                },
+                fragment: Some(FragmentState {
+                    entry_point: "fragment_main",
+                    module: shader_module,
+                    targets: [FragmentStateTarget {
+                        format: output.target_format(),
+                        blend: Some(FragmentStateBlend {
+                            alpha: FragmentStateBlendEntry {
+                                dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
+                                operation: Some(BlendOperation::Add),
+                                src_factor: Some(BlendFactor::One),
+                            },
+                            color: FragmentStateBlendEntry {
+                                dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
+                                operation: Some(BlendOperation::Add),
+                                src_factor: Some(BlendFactor::One),
+                            },
+                        }),
+                        write_mask: None,
+                    }],
+                }),
+                multisample: Some(MultisampleState {
+                    alpha_to_coverage_enabled: None,
+                    count: Some(output.samples),
+                    mask: None,
+                }),
+                primitive: Some(PrimitiveState {
+                    cull_mode: None,
+                    front_face: None,
+                    strip_index_format: None,
+                    topology: Some(PrimitiveTopology::TriangleList),
+                    unclipped_depth: None,
+                }),
+            })
+            .await;
+
+        Self { layout, pipeline }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &self,
+        label_idx: usize,
+        active_label_idx: usize,
+        min_curve_t: f32,
+        matrices: &buffers::MatricesBuffer,
+        axes: &buffers::AxesBuffer,
+        curve_lines: &buffers::CurveLinesInfoBuffer,
+        label_colors: &buffers::LabelColorBuffer,
+        viewport_start: (f32, f32),
+        viewport_size: (f32, f32),
+        device: &Device,
+        render_pass: &RenderPassEncoder,
+    ) {
+        let num_lines = curve_lines.len();
+        if num_lines == 0 {
+            return;
+        }
+
+        let config = buffers::CurveSegmentConfigBuffer::new(
+            device,
+            buffers::CurveSegmentConfig {
+                label: label_idx as u32,
+                active_label: active_label_idx as u32,
+                min_curve_t,
+            },
+        );
+
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("curve segments bind group".into()),
+            entries: [
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: matrices.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: config.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: axes.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: curve_lines.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: label_colors.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+            ],
+            layout: self.layout.clone(),
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group);
+        set_viewport(render_pass, viewport_start, viewport_size);
+        render_pass.draw_with_instance_count(6, num_lines);
+    }
+}
+
+pub struct CurveComparisonRenderPipeline {
+    layout: BindGroupLayout,
+    pipeline: RenderPipeline,
+}
+
+impl CurveComparisonRenderPipeline {
+    async fn new(device: &Device, output: buffers::OutputFormat) -> Self {
+        let code = include_str!("./shaders/curve_comparison.wgsl");
+        Self::from_source(device, output, code).await
+    }
+
+    async fn from_source(device: &Device, output: buffers::OutputFormat, code: &str) -> Self {
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("curve comparison shader".into()),
+            code: preprocessor::bundled_render(code, device.limits(), output).into(),
+        });
+
+        let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("curve comparison render pipeline bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::VERTEX,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::Uniform),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::Uniform),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                        ..Default::default()
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Texture(TextureBindingLayout {
+                        multisampled: None,
+                        sample_type: Some(TextureSampleType::UnfilterableFloat),
+                        view_dimension: Some(TextureViewDimension::D2Array),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 5,
+                    visibility: ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Texture(TextureBindingLayout {
+                        multisampled: None,
+                        sample_type: Some(TextureSampleType::UnfilterableFloat),
+                        view_dimension: Some(TextureViewDimension::D2Array),
+                    }),
+                },
+            ],
+        });
+
+        let pipeline = device
+            .create_render_pipeline_async(RenderPipelineDescriptor {
+                label: Some("curve comparison render pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: None,
+                        layouts: [layout.clone()],
+                    },
+                )),
+                depth_stencil: Some(DepthStencilState {
+                    depth_bias: None,
+                    depth_bias_clamp: None,
+                    depth_bias_slope_scale: None,
+                    depth_compare: CompareFunction::Always,
+                    depth_write_enabled: false,
+                    format: buffers::DepthTexture::DEPTH_FORMAT,
+                }),
+                vertex: VertexState {
+                    entry_point: "vertex_main",
+                    module: shader_module.clone(),
+                },
+                fragment: Some(FragmentState {
+                    entry_point: "fragment_main",
+                    module: shader_module,
+                    targets: [FragmentStateTarget {
+                        format: output.target_format(),
+                        blend: Some(FragmentStateBlend {
+                            alpha: FragmentStateBlendEntry {
+                                dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
+                                operation: Some(BlendOperation::Add),
+                                src_factor: Some(BlendFactor::One),
+                            },
+                            color: FragmentStateBlendEntry {
+                                dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
+                                operation: Some(BlendOperation::Add),
+                                src_factor: Some(BlendFactor::One),
+                            },
+                        }),
+                        write_mask: None,
+                    }],
+                }),
+                multisample: Some(MultisampleState {
+                    alpha_to_coverage_enabled: None,
+                    count: Some(output.samples),
+                    mask: None,
+                }),
+                primitive: Some(PrimitiveState {
+                    cull_mode: None,
+                    front_face: None,
+                    strip_index_format: None,
+                    topology: Some(PrimitiveTopology::TriangleList),
+                    unclipped_depth: None,
+                }),
+            })
+            .await;
+
+        Self { layout, pipeline }
+    }
+
+    /// Shades the area between the probability curves of the labels `labels`
+    /// on the expanded axes.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &self,
+        labels: (usize, usize),
+        curve_t_range: (f32, f32),
+        matrices: &buffers::MatricesBuffer,
+        axes: &buffers::AxesBuffer,
+        label_colors: &buffers::LabelColorBuffer,
+        samples: (
+            &buffers::ProbabilitySampleTexture,
+            &buffers::ProbabilitySampleTexture,
+        ),
+        viewport_start: (f32, f32),
+        viewport_size: (f32, f32),
+        device: &Device,
+        render_pass: &RenderPassEncoder,
+    ) {
+        let num_axes = axes.len();
+        if num_axes == 0 {
+            return;
+        }
+
+        let (min_curve_t, max_curve_t) = curve_t_range;
+        let config = buffers::CurveComparisonConfigBuffer::new(
+            device,
+            buffers::CurveComparisonConfig {
+                label_a: labels.0 as u32,
+                label_b: labels.1 as u32,
+                curve_t_range: crate::wgsl::Vec2([min_curve_t, max_curve_t]),
+            },
+        );
+
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("curve comparison bind group".into()),
+            entries: [
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: matrices.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: config.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: axes.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: label_colors.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: BindGroupEntryResource::TextureView(samples.0.array_view()),
+                },
+                BindGroupEntry {
+                    binding: 5,
+                    resource: BindGroupEntryResource::TextureView(samples.1.array_view()),
+                },
+            ],
+            layout: self.layout.clone(),
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group);
+        set_viewport(render_pass, viewport_start, viewport_size);
+        render_pass.draw_with_instance_count(6, num_axes);
+    }
+}
+
+pub struct ColorBarRenderPipeline {
+    layout: BindGroupLayout,
+    pipeline: RenderPipeline,
+}
+
+impl ColorBarRenderPipeline {
+    async fn new(device: &Device, output: buffers::OutputFormat) -> Self {
+        let code = include_str!("./shaders/color_bar.wgsl");
+        Self::from_source(device, output, code).await
+    }
+
+    async fn from_source(device: &Device, output: buffers::OutputFormat, code: &str) -> Self {
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("color bar shader".into()),
+            code: preprocessor::bundled_render(code, device.limits(), output).into(),
+        });
+
+        let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("color bar rendering bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Texture(TextureBindingLayout {
+                        multisampled: None,
+                        sample_type: Some(TextureSampleType::UnfilterableFloat),
+                        view_dimension: Some(TextureViewDimension::D2),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::FRAGMENT,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Uniform),
+                    }),
+                },
+            ],
+        });
+
+        let pipeline = device
+            .create_render_pipeline_async(RenderPipelineDescriptor {
+                label: Some("color bar render pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: None,
+                        layouts: [layout.clone()],
+                    },
+                )),
+                depth_stencil: Some(DepthStencilState {
+                    depth_bias: None,
+                    depth_bias_clamp: None,
+                    depth_bias_slope_scale: None,
+                    depth_compare: CompareFunction::Always,
+                    depth_write_enabled: false,
+                    format: buffers::DepthTexture::DEPTH_FORMAT,
+                }),
+                vertex: VertexState {
+                    entry_point: "vertex_main",
+                    module: shader_module.clone(),
+                },
+                fragment: Some(FragmentState {
+                    entry_point: "fragment_main",
+                    module: shader_module,
+                    targets: [FragmentStateTarget {
+                        format: output.target_format(),
+                        blend: Some(FragmentStateBlend {
+                            alpha: FragmentStateBlendEntry {
+                                dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
+                                operation: Some(BlendOperation::Add),
+                                src_factor: Some(BlendFactor::One),
+                            },
+                            color: FragmentStateBlendEntry {
+                                dst_factor: Some(BlendFactor::OneMinusSrcAlpha),
+                                operation: Some(BlendOperation::Add),
+                                src_factor: Some(BlendFactor::One),
+                            },
+                        }),
+                        write_mask: None,
+                    }],
+                }),
+                multisample: Some(MultisampleState {
+                    alpha_to_coverage_enabled: None,
+                    count: Some(output.samples),
+                    mask: None,
+                }),
+                primitive: Some(PrimitiveState {
+                    cull_mode: None,
+                    front_face: None,
+                    strip_index_format: None,
+                    topology: Some(PrimitiveTopology::TriangleList),
+                    unclipped_depth: None,
+                }),
+            })
+            .await;
+
+        Self { layout, pipeline }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &self,
+        color_scale: &buffers::ColorScaleTexture,
+        color_scale_bounds: &buffers::ColorScaleBoundsBuffer,
+        viewport_start: (f32, f32),
+        viewport_size: (f32, f32),
+        device: &Device,
+        render_pass: &RenderPassEncoder,
+    ) {
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("color bar bind group".into()),
+            entries: [
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindGroupEntryResource::TextureView(color_scale.view()),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: color_scale_bounds.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+            ],
+            layout: self.layout.clone(),
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group);
+        set_viewport(render_pass, viewport_start, viewport_size);
+        render_pass.draw(6);
+    }
+}
+
+/// Encodes the linearly blended colors into the canvas.
+pub struct LinearResolveRenderPipeline {
+    layout: BindGroupLayout,
+    pipeline: RenderPipeline,
+}
+
+impl LinearResolveRenderPipeline {
+    async fn new(device: &Device, output: buffers::OutputFormat) -> Self {
+        let code = include_str!("./shaders/linear_resolve.wgsl");
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("linear resolve shader".into()),
+            code: preprocessor::bundled_render(code, device.limits(), output).into(),
+        });
+
+        let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("linear resolve bind group layout".into()),
+            entries: [BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStage::FRAGMENT,
+                resource: BindGroupLayoutEntryResource::Texture(TextureBindingLayout {
+                    multisampled: None,
+                    sample_type: Some(TextureSampleType::UnfilterableFloat),
+                    view_dimension: Some(TextureViewDimension::D2),
+                }),
+            }],
+        });
+
+        let pipeline = device
+            .create_render_pipeline_async(RenderPipelineDescriptor {
+                label: Some("linear resolve render pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: None,
+                        layouts: [layout.clone()],
+                    },
+                )),
+                depth_stencil: None,
+                vertex: VertexState {
+                    entry_point: "vertex_main",
+                    module: shader_module.clone(),
+                },
+                fragment: Some(FragmentState {
+                    entry_point: "fragment_main",
+                    module: shader_module,
+                    targets: [FragmentStateTarget {
+                        format: output.target_format(),
+                        blend: None,
+                        write_mask: None,
+                    }],
+                }),
+                multisample: None,
+                primitive: Some(PrimitiveState {
+                    cull_mode: None,
+                    front_face: None,
+                    strip_index_format: None,
+                    topology: Some(PrimitiveTopology::TriangleList),
+                    unclipped_depth: None,
+                }),
+            })
+            .await;
+
+        Self { layout, pipeline }
+    }
+
+    pub fn render(
+        &self,
+        linear_texture: TextureView,
+        device: &Device,
+        render_pass: &RenderPassEncoder,
+    ) {
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("linear resolve bind group".into()),
+            entries: [BindGroupEntry {
+                binding: 0,
+                resource: BindGroupEntryResource::TextureView(linear_texture),
+            }],
+            layout: self.layout.clone(),
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group);
+        render_pass.draw(3);
+    }
+}
+
+pub struct ComputePipelines {
+    pub create_curves: (BindGroupLayout, ComputePipeline),
+    pub compute_probability: ProbabilityComputationPipeline,
+    pub transform_color_scale: (BindGroupLayout, ComputePipeline),
+    curve_spline_sampling: ProbabilityCurveSplineSamplingComputePipeline,
+    selected_range_reduction: SelectedRangeReductionComputePipeline,
+    label_agreement_reduction: LabelAgreementReductionComputePipeline,
+    label_mean_reduction: LabelMeanReductionComputePipeline,
+    outlier_scores: OutlierScoresComputePipeline,
+    sort: SortComputePipeline,
+    scan: ScanComputePipeline,
+    selection_compaction: SelectionCompactionComputePipeline,
+    flow_aggregation: FlowAggregationComputePipeline,
+    density_estimation: DensityEstimationComputePipeline,
+    histogram_binning: HistogramBinningComputePipeline,
+    //
+    //
+    color_scale_sampling: ColorScaleSamplingComputePipeline,
+}
+
+pub struct ProbabilityComputationPipeline {
+    pub apply_curve_bind_layout: BindGroupLayout,
+    pub apply_curve_pipeline: ComputePipeline,
+    pub reduce_bind_layout: BindGroupLayout,
+    pub reduce_pipeline: ComputePipeline,
+}
+
+impl ComputePipelines {
+    pub async fn new(device: &Device, progress: &mut Progress<'_>) -> Self {
+        let create_curves = progress
+            .track(Self::init_curve_creation_pipeline(device))
+            .await;
+        let compute_probability = progress
+            .track(Self::init_probability_computation_pipeline(device))
+            .await;
+        let transform_color_scale = progress
+            .track(Self::init_color_scale_transformation_pipeline(device))
+            .await;
+        let curve_spline_sampling = progress
+            .track(ProbabilityCurveSplineSamplingComputePipeline::new(device))
+            .await;
+        let selected_range_reduction = progress
+            .track(SelectedRangeReductionComputePipeline::new(device))
+            .await;
+        let label_agreement_reduction = progress
+            .track(LabelAgreementReductionComputePipeline::new(device))
+            .await;
+        let label_mean_reduction = progress
+            .track(LabelMeanReductionComputePipeline::new(device))
+            .await;
+        let outlier_scores = progress
+            .track(OutlierScoresComputePipeline::new(device))
+            .await;
+        let sort = progress.track(SortComputePipeline::new(device)).await;
+        let scan = progress.track(ScanComputePipeline::new(device)).await;
+        let selection_compaction = progress
+            .track(SelectionCompactionComputePipeline::new(device))
+            .await;
+        let flow_aggregation = progress
+            .track(FlowAggregationComputePipeline::new(device))
+            .await;
+        let density_estimation = progress
+            .track(DensityEstimationComputePipeline::new(device))
+            .await;
+        let histogram_binning = progress
+            .track(HistogramBinningComputePipeline::new(device))
+            .await;
+        let color_scale_sampling = progress
+            .track(ColorScaleSamplingComputePipeline::new(device))
+            .await;
+
+        Self {
+            create_curves,
+            compute_probability,
+            transform_color_scale,
+            curve_spline_sampling,
+            selected_range_reduction,
+            label_agreement_reduction,
+            label_mean_reduction,
+            outlier_scores,
+            sort,
+            scan,
+            selection_compaction,
+            flow_aggregation,
+            density_estimation,
+            histogram_binning,
+            color_scale_sampling,
+        }
+    }
+
+    pub fn curve_spline_sampling(&self) -> &ProbabilityCurveSplineSamplingComputePipeline {
+        &self.curve_spline_sampling
+    }
+
+    pub fn selected_range_reduction(&self) -> &SelectedRangeReductionComputePipeline {
+        &self.selected_range_reduction
+    }
+
+    pub fn label_agreement_reduction(&self) -> &LabelAgreementReductionComputePipeline {
+        &self.label_agreement_reduction
+    }
+
+    pub fn label_mean_reduction(&self) -> &LabelMeanReductionComputePipeline {
+        &self.label_mean_reduction
+    }
+
+    pub fn outlier_scores(&self) -> &OutlierScoresComputePipeline {
+        &self.outlier_scores
+    }
+
+    pub fn sort(&self) -> &SortComputePipeline {
+        &self.sort
+    }
+
+    pub fn scan(&self) -> &ScanComputePipeline {
+        &self.scan
+    }
+
+    pub fn selection_compaction(&self) -> &SelectionCompactionComputePipeline {
+        &self.selection_compaction
+    }
+
+    pub fn flow_aggregation(&self) -> &FlowAggregationComputePipeline {
+        &self.flow_aggregation
+    }
+
+    pub fn density_estimation(&self) -> &DensityEstimationComputePipeline {
+        &self.density_estimation
+    }
+
+    pub fn histogram_binning(&self) -> &HistogramBinningComputePipeline {
+        &self.histogram_binning
+    }
+
+    pub fn color_scale_sampling(&self) -> &ColorScaleSamplingComputePipeline {
+        &self.color_scale_sampling
+    }
+
+    async fn init_curve_creation_pipeline(device: &Device) -> (BindGroupLayout, ComputePipeline) {
+        let bind_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("curve creation bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Storage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Texture(TextureBindingLayout {
+                        multisampled: None,
+                        sample_type: Some(TextureSampleType::UnfilterableFloat),
+                        view_dimension: Some(TextureViewDimension::D2Array),
+                    }),
+                },
+            ],
+        });
+
+        let pipeline = device
+            .create_compute_pipeline_async(ComputePipelineDescriptor {
+                label: Some("curve creation compute pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: Some("curve creation pipeline layout".into()),
+                        layouts: [bind_layout.clone()],
+                    },
+                )),
+                compute: ProgrammableStage {
+                    entry_point: "main",
+                    module: device.create_shader_module(ShaderModuleDescriptor {
+                        label: Some("curve creation compute shader".into()),
+                        code: preprocessor::bundled(
+                            include_str!("./shaders/create_curves.comp.wgsl"),
+                            device.limits(),
+                        )
+                        .into(),
+                    }),
+                },
+            })
+            .await;
+
+        (bind_layout, pipeline)
+    }
+
+    async fn init_probability_computation_pipeline(
+        device: &Device,
     ) -> ProbabilityComputationPipeline {
         let application_bind_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
             label: Some("curve application bind group layout".into()),
@@ -1295,15 +3031,1635 @@ async fn init_probability_computation_pipeline(
                 BindGroupLayoutEntry {
                     binding: 1,
                     visibility: ShaderStage::COMPUTE,
-                    resource: BindGroupLayoutEntryResource::Texture(TextureBindingLayout {
-                        multisampled: None,
-                        sample_type: Some(TextureSampleType::UnfilterableFloat),
-                        view_dimension: Some(TextureViewDimension::D2Array),
-                    }),
-                },
-                BindGroupLayoutEntry {
-                    binding: 2,
-                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Texture(TextureBindingLayout {
+                        multisampled: None,
+                        sample_type: Some(TextureSampleType::UnfilterableFloat),
+                        view_dimension: Some(TextureViewDimension::D2Array),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Uniform),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+            ],
+        });
+
+        let application_pipeline = device
+            .create_compute_pipeline_async(ComputePipelineDescriptor {
+                label: Some("curve application compute pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: Some("curve application pipeline layout".into()),
+                        layouts: [application_bind_layout.clone()],
+                    },
+                )),
+                compute: ProgrammableStage {
+                    entry_point: "main",
+                    module: device.create_shader_module(ShaderModuleDescriptor {
+                        label: Some("curve application compute shader".into()),
+                        code: preprocessor::bundled(
+                            include_str!("./shaders/apply_curves.comp.wgsl"),
+                            device.limits(),
+                        )
+                        .into(),
+                    }),
+                },
+            })
+            .await;
+
+        let reduction_bind_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("curve application reduction bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Storage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Uniform),
+                    }),
+                },
+            ],
+        });
+
+        let reduction_pipeline = device
+            .create_compute_pipeline_async(ComputePipelineDescriptor {
+                label: Some("curve application reduction compute pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: Some("curve application reduction pipeline layout".into()),
+                        layouts: [reduction_bind_layout.clone()],
+                    },
+                )),
+                compute: ProgrammableStage {
+                    entry_point: "main",
+                    module: device.create_shader_module(ShaderModuleDescriptor {
+                        label: Some("curve application reduction compute shader".into()),
+                        code: preprocessor::bundled(
+                            include_str!("./shaders/reduce_probability.comp.wgsl"),
+                            device.limits(),
+                        )
+                        .into(),
+                    }),
+                },
+            })
+            .await;
+
+        ProbabilityComputationPipeline {
+            apply_curve_bind_layout: application_bind_layout,
+            apply_curve_pipeline: application_pipeline,
+            reduce_bind_layout: reduction_bind_layout,
+            reduce_pipeline: reduction_pipeline,
+        }
+    }
+
+    async fn init_color_scale_transformation_pipeline(
+        device: &Device,
+    ) -> (BindGroupLayout, ComputePipeline) {
+        let bind_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("color scale transformation bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Texture(TextureBindingLayout {
+                        multisampled: None,
+                        sample_type: Some(TextureSampleType::UnfilterableFloat),
+                        view_dimension: Some(TextureViewDimension::D2),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::StorageTexture(
+                        StorageTextureBindingLayout {
+                            access: Some(StorageTextureAccess::WriteOnly),
+                            format: TextureFormat::Rgba32float,
+                            view_dimension: Some(TextureViewDimension::D2),
+                        },
+                    ),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Uniform),
+                    }),
+                },
+            ],
+        });
+
+        let pipeline = device
+            .create_compute_pipeline_async(ComputePipelineDescriptor {
+                label: Some("color scale transformation compute pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: Some("color scale transformation pipeline layout".into()),
+                        layouts: [bind_layout.clone()],
+                    },
+                )),
+                compute: ProgrammableStage {
+                    entry_point: "main",
+                    module: device.create_shader_module(ShaderModuleDescriptor {
+                        label: Some("color scale transformation compute shader".into()),
+                        code: preprocessor::bundled(
+                            include_str!("./shaders/color_scale/transform_color_scale.comp.wgsl"),
+                            device.limits(),
+                        )
+                        .into(),
+                    }),
+                },
+            })
+            .await;
+
+        (bind_layout, pipeline)
+    }
+}
+
+pub struct ProbabilityCurveSplineSamplingComputePipeline {
+    layout: BindGroupLayout,
+    pipeline: ComputePipeline,
+}
+
+impl ProbabilityCurveSplineSamplingComputePipeline {
+    async fn new(device: &Device) -> Self {
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("probability curve sampling compute shader".into()),
+            code: preprocessor::bundled(
+                include_str!("./shaders/probability_curve/sample_spline.comp.wgsl"),
+                device.limits(),
+            )
+            .into(),
+        });
+
+        let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("probability curve spline sampling bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::StorageTexture(
+                        StorageTextureBindingLayout {
+                            access: Some(StorageTextureAccess::WriteOnly),
+                            format: TextureFormat::R32float,
+                            view_dimension: Some(TextureViewDimension::D2Array),
+                        },
+                    ),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+            ],
+        });
+
+        let pipeline = device
+            .create_compute_pipeline_async(ComputePipelineDescriptor {
+                label: Some("probability curve spline sampling compute pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: Some("curve sampling pipeline layout".into()),
+                        layouts: [layout.clone()],
+                    },
+                )),
+                compute: ProgrammableStage {
+                    entry_point: "main",
+                    module: shader_module,
+                },
+            })
+            .await;
+
+        Self { layout, pipeline }
+    }
+
+    /// Samples the splines of all axes contained in `spline_segments` with a single dispatch.
+    pub fn dispatch(
+        &self,
+        probability_texture: &buffers::ProbabilitySampleTexture,
+        spline_segments: &buffers::SplineSegmentsBuffer,
+        device: &Device,
+        encoder: &CommandEncoder,
+    ) {
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("probability curve spline sampling bind group".into()),
+            entries: [
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindGroupEntryResource::TextureView(probability_texture.array_view()),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: spline_segments.segments().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: spline_segments.ranges().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+            ],
+            layout: self.layout.clone(),
+        });
+
+        let num_workgroups = preprocessor::num_workgroups(
+            device.limits(),
+            buffers::ProbabilitySampleTexture::PROBABILITY_CURVE_RESOLUTION,
+        );
+
+        let pass = encoder.begin_compute_pass(None);
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group);
+        pass.dispatch_workgroups(&[num_workgroups, spline_segments.num_ranges() as u32]);
+        pass.end();
+    }
+}
+
+pub struct SelectedRangeReductionComputePipeline {
+    layout: BindGroupLayout,
+    pipeline: ComputePipeline,
+}
+
+impl SelectedRangeReductionComputePipeline {
+    async fn new(device: &Device) -> Self {
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("selected range reduction compute shader".into()),
+            code: preprocessor::bundled(
+                include_str!("./shaders/reduce_selected_range.comp.wgsl"),
+                device.limits(),
+            )
+            .into(),
+        });
+
+        let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("selected range reduction bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Storage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Uniform),
+                    }),
+                },
+            ],
+        });
+
+        let pipeline = device
+            .create_compute_pipeline_async(ComputePipelineDescriptor {
+                label: Some("selected range reduction compute pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: Some("selected range reduction pipeline layout".into()),
+                        layouts: [layout.clone()],
+                    },
+                )),
+                compute: ProgrammableStage {
+                    entry_point: "main",
+                    module: shader_module,
+                },
+            })
+            .await;
+
+        Self { layout, pipeline }
+    }
+
+    /// Reduces the ranges of the values of each axis, whose data point has a
+    /// probability inside of the selection bounds.
+    pub fn dispatch(
+        &self,
+        ranges: &buffers::SelectedRangesBuffer,
+        data: &buffers::DataBuffer,
+        probabilities: &buffers::ProbabilitiesBuffer,
+        num_data_points: usize,
+        device: &Device,
+        encoder: &CommandEncoder,
+    ) {
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("selected range reduction bind group".into()),
+            entries: [
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: ranges.ranges().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: data.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: probabilities.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: ranges.config().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+            ],
+            layout: self.layout.clone(),
+        });
+
+        let num_workgroups = preprocessor::num_workgroups(device.limits(), num_data_points);
+
+        let pass = encoder.begin_compute_pass(None);
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group);
+        pass.dispatch_workgroups(&[num_workgroups, ranges.num_axes() as u32]);
+        pass.end();
+    }
+}
+
+pub struct LabelAgreementReductionComputePipeline {
+    layout: BindGroupLayout,
+    pipeline: ComputePipeline,
+}
+
+impl LabelAgreementReductionComputePipeline {
+    async fn new(device: &Device) -> Self {
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("label agreement reduction compute shader".into()),
+            code: preprocessor::bundled(
+                include_str!("./shaders/reduce_label_agreement.comp.wgsl"),
+                device.limits(),
+            )
+            .into(),
+        });
+
+        let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("label agreement reduction bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Storage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Uniform),
+                    }),
+                },
+            ],
+        });
+
+        let pipeline = device
+            .create_compute_pipeline_async(ComputePipelineDescriptor {
+                label: Some("label agreement reduction compute pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: Some("label agreement reduction pipeline layout".into()),
+                        layouts: [layout.clone()],
+                    },
+                )),
+                compute: ProgrammableStage {
+                    entry_point: "main",
+                    module: shader_module,
+                },
+            })
+            .await;
+
+        Self { layout, pipeline }
+    }
+
+    /// Reduces the sums required for computing the agreement of the
+    /// probabilities of two labels.
+    pub fn dispatch(
+        &self,
+        agreement: &buffers::LabelAgreementBuffer,
+        probabilities: (&buffers::ProbabilitiesBuffer, &buffers::ProbabilitiesBuffer),
+        device: &Device,
+        encoder: &CommandEncoder,
+    ) {
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("label agreement reduction bind group".into()),
+            entries: [
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: agreement.partial_sums().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: probabilities.0.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: probabilities.1.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: agreement.config().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+            ],
+            layout: self.layout.clone(),
+        });
+
+        let pass = encoder.begin_compute_pass(None);
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group);
+        pass.dispatch_workgroups(&[agreement.num_workgroups()]);
+        pass.end();
+    }
+}
+
+pub struct LabelMeanReductionComputePipeline {
+    layout: BindGroupLayout,
+    pipeline: ComputePipeline,
+}
+
+impl LabelMeanReductionComputePipeline {
+    async fn new(device: &Device) -> Self {
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("label mean reduction compute shader".into()),
+            code: preprocessor::bundled(
+                include_str!("./shaders/reduce_label_mean.comp.wgsl"),
+                device.limits(),
+            )
+            .into(),
+        });
+
+        let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("label mean reduction bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Storage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Uniform),
+                    }),
+                },
+            ],
+        });
+
+        let pipeline = device
+            .create_compute_pipeline_async(ComputePipelineDescriptor {
+                label: Some("label mean reduction compute pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: Some("label mean reduction pipeline layout".into()),
+                        layouts: [layout.clone()],
+                    },
+                )),
+                compute: ProgrammableStage {
+                    entry_point: "main",
+                    module: shader_module,
+                },
+            })
+            .await;
+
+        Self { layout, pipeline }
+    }
+
+    /// Reduces the weighted sums of the values of each axis, whose data point
+    /// has a probability inside of the selection bounds.
+    pub fn dispatch(
+        &self,
+        means: &buffers::LabelMeansBuffer,
+        data: &buffers::DataBuffer,
+        probabilities: &buffers::ProbabilitiesBuffer,
+        device: &Device,
+        encoder: &CommandEncoder,
+    ) {
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("label mean reduction bind group".into()),
+            entries: [
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: means.partial_sums().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: data.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: probabilities.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: means.config().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+            ],
+            layout: self.layout.clone(),
+        });
+
+        let pass = encoder.begin_compute_pass(None);
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group);
+        pass.dispatch_workgroups(&[means.num_workgroups(), means.num_axes() as u32]);
+        pass.end();
+    }
+}
+
+pub struct OutlierScoresComputePipeline {
+    layout: BindGroupLayout,
+    pipeline: ComputePipeline,
+}
+
+impl OutlierScoresComputePipeline {
+    async fn new(device: &Device) -> Self {
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("outlier scores compute shader".into()),
+            code: preprocessor::bundled(
+                include_str!("./shaders/outlier_scores.comp.wgsl"),
+                device.limits(),
+            )
+            .into(),
+        });
+
+        let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("outlier scores bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Storage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Uniform),
+                    }),
+                },
+            ],
+        });
+
+        let pipeline = device
+            .create_compute_pipeline_async(ComputePipelineDescriptor {
+                label: Some("outlier scores compute pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: Some("outlier scores pipeline layout".into()),
+                        layouts: [layout.clone()],
+                    },
+                )),
+                compute: ProgrammableStage {
+                    entry_point: "main",
+                    module: shader_module,
+                },
+            })
+            .await;
+
+        Self { layout, pipeline }
+    }
+
+    /// Computes the outlier score of each data point from the statistics
+    /// of the axes.
+    pub fn dispatch(
+        &self,
+        scores: &buffers::OutlierScoresBuffer,
+        data: &buffers::DataBuffer,
+        device: &Device,
+        encoder: &CommandEncoder,
+    ) {
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("outlier scores bind group".into()),
+            entries: [
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: scores.scores().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: data.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: scores.statistics().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: scores.config().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+            ],
+            layout: self.layout.clone(),
+        });
+
+        let pass = encoder.begin_compute_pass(None);
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group);
+        pass.dispatch_workgroups(&[scores.num_workgroups()]);
+        pass.end();
+    }
+}
+
+pub struct SortComputePipeline {
+    init_layout: BindGroupLayout,
+    init_pipeline: ComputePipeline,
+    sort_layout: BindGroupLayout,
+    sort_pipeline: ComputePipeline,
+}
+
+impl SortComputePipeline {
+    async fn new(device: &Device) -> Self {
+        let init_shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("sort initialization shader module".into()),
+            code: preprocessor::bundled(
+                include_str!("./shaders/sort/init_sort_pairs.comp.wgsl"),
+                device.limits(),
+            )
+            .into(),
+        });
+
+        let sort_shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("bitonic sort shader module".into()),
+            code: preprocessor::bundled(
+                include_str!("./shaders/sort/bitonic_sort.comp.wgsl"),
+                device.limits(),
+            )
+            .into(),
+        });
+
+        let init_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("sort initialization bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Storage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Uniform),
+                    }),
+                },
+            ],
+        });
+
+        let sort_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("bitonic sort bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Storage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Uniform),
+                    }),
+                },
+            ],
+        });
+
+        let init_pipeline = device
+            .create_compute_pipeline_async(ComputePipelineDescriptor {
+                label: Some("sort initialization compute pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: Some("sort initialization pipeline layout".into()),
+                        layouts: [init_layout.clone()],
+                    },
+                )),
+                compute: ProgrammableStage {
+                    entry_point: "main",
+                    module: init_shader_module,
+                },
+            })
+            .await;
+
+        let sort_pipeline = device
+            .create_compute_pipeline_async(ComputePipelineDescriptor {
+                label: Some("bitonic sort compute pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: Some("bitonic sort pipeline layout".into()),
+                        layouts: [sort_layout.clone()],
+                    },
+                )),
+                compute: ProgrammableStage {
+                    entry_point: "main",
+                    module: sort_shader_module,
+                },
+            })
+            .await;
+
+        Self {
+            init_layout,
+            init_pipeline,
+            sort_layout,
+            sort_pipeline,
+        }
+    }
+
+    /// Pairs each of the `keys` with its index, and sorts the pairs by their
+    /// key, in descending order if `descending` is set. Pairs with equal keys
+    /// remain ordered by their index.
+    pub fn dispatch(
+        &self,
+        sort: &buffers::SortBuffer,
+        keys: &Buffer,
+        descending: bool,
+        device: &Device,
+        encoder: &CommandEncoder,
+    ) {
+        let num_pairs = sort.num_pairs();
+        let num_workgroups = preprocessor::num_workgroups(device.limits(), num_pairs);
+
+        // The configs of all steps are stored in a single buffer, where the
+        // first one is used for the initialization.
+        let mut configs = vec![(0, 0)];
+        let mut block_size = 2;
+        while block_size <= num_pairs {
+            let mut distance = block_size / 2;
+            while distance > 0 {
+                configs.push((block_size, distance));
+                distance /= 2;
+            }
+            block_size *= 2;
+        }
+
+        let config_buffer = device.create_buffer(BufferDescriptor {
+            label: Some("sort config buffer".into()),
+            size: configs.len() * buffers::SortConfig::STRIDE,
+            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+            mapped_at_creation: None,
+        });
+        for (step, &(block_size, distance)) in configs.iter().enumerate() {
+            let config = buffers::SortConfig {
+                num_keys: sort.num_keys() as u32,
+                num_pairs: num_pairs as u32,
+                block_size: block_size as u32,
+                distance: distance as u32,
+                descending: descending as u32,
+            };
+            device.queue().write_buffer_single(
+                &config_buffer,
+                (step * buffers::SortConfig::STRIDE) as u32,
+                &config,
+            );
+        }
+        let config_binding = |step: usize| {
+            BindGroupEntryResource::Buffer(BufferBinding {
+                buffer: config_buffer.clone(),
+                offset: Some(step * buffers::SortConfig::STRIDE),
+                size: Some(std::mem::size_of::<buffers::SortConfig>()),
+            })
+        };
+
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("sort initialization bind group".into()),
+            entries: [
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: sort.pairs().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: keys.clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: config_binding(0),
+                },
+            ],
+            layout: self.init_layout.clone(),
+        });
+
+        let pass = encoder.begin_compute_pass(None);
+        pass.set_pipeline(&self.init_pipeline);
+        pass.set_bind_group(0, &bind_group);
+        pass.dispatch_workgroups(&[num_workgroups]);
+
+        // Each step depends on the results of the previous one, which are
+        // visible to the following dispatches of the same pass.
+        pass.set_pipeline(&self.sort_pipeline);
+        for step in 1..configs.len() {
+            let bind_group = device.create_bind_group(BindGroupDescriptor {
+                label: Some("bitonic sort bind group".into()),
+                entries: [
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindGroupEntryResource::Buffer(BufferBinding {
+                            buffer: sort.pairs().clone(),
+                            offset: None,
+                            size: None,
+                        }),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: config_binding(step),
+                    },
+                ],
+                layout: self.sort_layout.clone(),
+            });
+            pass.set_bind_group(0, &bind_group);
+            pass.dispatch_workgroups(&[num_workgroups]);
+        }
+        pass.end();
+    }
+}
+
+pub struct ScanComputePipeline {
+    scan_layout: BindGroupLayout,
+    scan_pipeline: ComputePipeline,
+    offsets_layout: BindGroupLayout,
+    offsets_pipeline: ComputePipeline,
+}
+
+impl ScanComputePipeline {
+    async fn new(device: &Device) -> Self {
+        let scan_shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("block scan shader module".into()),
+            code: preprocessor::bundled(
+                include_str!("./shaders/scan/scan_blocks.comp.wgsl"),
+                device.limits(),
+            )
+            .into(),
+        });
+
+        let offsets_shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("block offsets shader module".into()),
+            code: preprocessor::bundled(
+                include_str!("./shaders/scan/add_block_offsets.comp.wgsl"),
+                device.limits(),
+            )
+            .into(),
+        });
+
+        let scan_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("block scan bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Storage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Storage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Uniform),
+                    }),
+                },
+            ],
+        });
+
+        let offsets_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("block offsets bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Storage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Uniform),
+                    }),
+                },
+            ],
+        });
+
+        let scan_pipeline = device
+            .create_compute_pipeline_async(ComputePipelineDescriptor {
+                label: Some("block scan compute pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: Some("block scan pipeline layout".into()),
+                        layouts: [scan_layout.clone()],
+                    },
+                )),
+                compute: ProgrammableStage {
+                    entry_point: "main",
+                    module: scan_shader_module,
+                },
+            })
+            .await;
+
+        let offsets_pipeline = device
+            .create_compute_pipeline_async(ComputePipelineDescriptor {
+                label: Some("block offsets compute pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: Some("block offsets pipeline layout".into()),
+                        layouts: [offsets_layout.clone()],
+                    },
+                )),
+                compute: ProgrammableStage {
+                    entry_point: "main",
+                    module: offsets_shader_module,
+                },
+            })
+            .await;
+
+        Self {
+            scan_layout,
+            scan_pipeline,
+            offsets_layout,
+            offsets_pipeline,
+        }
+    }
+
+    /// Replaces the first `num_values` values of the buffer with their
+    /// exclusive prefix sum.
+    pub fn dispatch(
+        &self,
+        values: &Buffer,
+        num_values: usize,
+        device: &Device,
+        encoder: &CommandEncoder,
+    ) {
+        if num_values == 0 {
+            return;
+        }
+
+        // Each level contains the sums of the blocks of the previous level,
+        // until all values fit into a single block.
+        let mut levels = vec![(values.clone(), num_values)];
+        loop {
+            let (_, num_level_values) = levels[levels.len() - 1];
+            let num_blocks = preprocessor::num_workgroups(device.limits(), num_level_values);
+            let block_sums = device.create_buffer(BufferDescriptor {
+                label: Some("scan block sums buffer".into()),
+                size: num_blocks as usize * std::mem::size_of::<u32>(),
+                usage: BufferUsage::STORAGE,
+                mapped_at_creation: None,
+            });
+            levels.push((block_sums, num_blocks as usize));
+            if num_blocks == 1 {
+                break;
+            }
+        }
+
+        let configs = levels
+            .iter()
+            .map(|(_, num_values)| {
+                let config = buffers::ScanConfig {
+                    num_values: *num_values as u32,
+                };
+                let buffer = device.create_buffer(BufferDescriptor {
+                    label: Some("scan config buffer".into()),
+                    size: std::mem::size_of::<buffers::ScanConfig>(),
+                    usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+                    mapped_at_creation: None,
+                });
+                device.queue().write_buffer_single(&buffer, 0, &config);
+                buffer
+            })
+            .collect::<Vec<_>>();
+
+        let bind_group = |label: &str, layout: &BindGroupLayout, level: usize| {
+            let binding = |buffer: &Buffer| {
+                BindGroupEntryResource::Buffer(BufferBinding {
+                    buffer: buffer.clone(),
+                    offset: None,
+                    size: None,
+                })
+            };
+            device.create_bind_group(BindGroupDescriptor {
+                label: Some(label.into()),
+                entries: [
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: binding(&levels[level].0),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: binding(&levels[level + 1].0),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: binding(&configs[level]),
+                    },
+                ],
+                layout: layout.clone(),
+            })
+        };
+
+        // Scan the blocks of each level, and then offset them by the scanned
+        // sums of the next level, starting from the topmost level.
+        let num_levels = levels.len() - 1;
+        let pass = encoder.begin_compute_pass(None);
+        pass.set_pipeline(&self.scan_pipeline);
+        for level in 0..num_levels {
+            pass.set_bind_group(
+                0,
+                &bind_group("block scan bind group", &self.scan_layout, level),
+            );
+            pass.dispatch_workgroups(&[levels[level + 1].1 as u32]);
+        }
+        pass.set_pipeline(&self.offsets_pipeline);
+        for level in (0..num_levels - 1).rev() {
+            pass.set_bind_group(
+                0,
+                &bind_group("block offsets bind group", &self.offsets_layout, level),
+            );
+            pass.dispatch_workgroups(&[levels[level + 1].1 as u32]);
+        }
+        pass.end();
+    }
+}
+
+pub struct SelectionCompactionComputePipeline {
+    layout: BindGroupLayout,
+    flag_pipeline: ComputePipeline,
+    scatter_pipeline: ComputePipeline,
+}
+
+impl SelectionCompactionComputePipeline {
+    async fn new(device: &Device) -> Self {
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("selection compaction shader module".into()),
+            code: preprocessor::bundled(
+                include_str!("./shaders/scan/compact_selection.comp.wgsl"),
+                device.limits(),
+            )
+            .into(),
+        });
+
+        let storage_entry = |binding, r#type| BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStage::COMPUTE,
+            resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                has_dynamic_offset: None,
+                min_binding_size: None,
+                r#type: Some(r#type),
+            }),
+        };
+        let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("selection compaction bind group layout".into()),
+            entries: [
+                storage_entry(0, BufferBindingType::Storage),
+                storage_entry(1, BufferBindingType::Storage),
+                storage_entry(2, BufferBindingType::Storage),
+                storage_entry(3, BufferBindingType::ReadOnlyStorage),
+                storage_entry(4, BufferBindingType::Uniform),
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(PipelineLayoutDescriptor {
+            label: Some("selection compaction pipeline layout".into()),
+            layouts: [layout.clone()],
+        });
+
+        let flag_pipeline = device
+            .create_compute_pipeline_async(ComputePipelineDescriptor {
+                label: Some("selection flag compute pipeline".into()),
+                layout: PipelineLayoutType::Layout(pipeline_layout.clone()),
+                compute: ProgrammableStage {
+                    entry_point: "flag",
+                    module: shader_module.clone(),
+                },
+            })
+            .await;
+
+        let scatter_pipeline = device
+            .create_compute_pipeline_async(ComputePipelineDescriptor {
+                label: Some("selection scatter compute pipeline".into()),
+                layout: PipelineLayoutType::Layout(pipeline_layout),
+                compute: ProgrammableStage {
+                    entry_point: "scatter",
+                    module: shader_module,
+                },
+            })
+            .await;
+
+        Self {
+            layout,
+            flag_pipeline,
+            scatter_pipeline,
+        }
+    }
+
+    /// Compacts the indices of the data points whose probability lies inside
+    /// of the selection bounds, using the `scan` pipeline for computing their
+    /// positions.
+    pub fn dispatch(
+        &self,
+        compaction: &buffers::CompactionBuffer,
+        probabilities: &buffers::ProbabilitiesBuffer,
+        scan: &ScanComputePipeline,
+        device: &Device,
+        encoder: &CommandEncoder,
+    ) {
+        let binding = |buffer: &Buffer| {
+            BindGroupEntryResource::Buffer(BufferBinding {
+                buffer: buffer.clone(),
+                offset: None,
+                size: None,
+            })
+        };
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("selection compaction bind group".into()),
+            entries: [
+                BindGroupEntry {
+                    binding: 0,
+                    resource: binding(compaction.flags()),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: binding(compaction.offsets()),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: binding(compaction.indices()),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: binding(probabilities.buffer()),
+                },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: binding(compaction.config()),
+                },
+            ],
+            layout: self.layout.clone(),
+        });
+        let num_workgroups = preprocessor::num_workgroups(device.limits(), compaction.num_datums());
+
+        let pass = encoder.begin_compute_pass(None);
+        pass.set_pipeline(&self.flag_pipeline);
+        pass.set_bind_group(0, &bind_group);
+        pass.dispatch_workgroups(&[num_workgroups]);
+        pass.end();
+
+        scan.dispatch(
+            compaction.offsets(),
+            compaction.num_datums(),
+            device,
+            encoder,
+        );
+
+        let pass = encoder.begin_compute_pass(None);
+        pass.set_pipeline(&self.scatter_pipeline);
+        pass.set_bind_group(0, &bind_group);
+        pass.dispatch_workgroups(&[num_workgroups]);
+        pass.end();
+    }
+}
+
+pub struct FlowAggregationComputePipeline {
+    layout: BindGroupLayout,
+    pipeline: ComputePipeline,
+}
+
+impl FlowAggregationComputePipeline {
+    async fn new(device: &Device) -> Self {
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("flow aggregation compute shader".into()),
+            code: preprocessor::bundled(
+                include_str!("./shaders/aggregate_flows.comp.wgsl"),
+                device.limits(),
+            )
+            .into(),
+        });
+
+        let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("flow aggregation bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Storage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Uniform),
+                    }),
+                },
+            ],
+        });
+
+        let pipeline = device
+            .create_compute_pipeline_async(ComputePipelineDescriptor {
+                label: Some("flow aggregation compute pipeline".into()),
+                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
+                    PipelineLayoutDescriptor {
+                        label: Some("flow aggregation pipeline layout".into()),
+                        layouts: [layout.clone()],
+                    },
+                )),
+                compute: ProgrammableStage {
+                    entry_point: "main",
+                    module: shader_module,
+                },
+            })
+            .await;
+
+        Self { layout, pipeline }
+    }
+
+    /// Counts the datums taking each pair of categories of the adjacent axes.
+    ///
+    /// Only the datums with a probability inside of the selection bounds
+    /// are counted, if enabled in the config.
+    pub fn dispatch(
+        &self,
+        flows: &buffers::FlowBuffers,
+        probabilities: &buffers::ProbabilitiesBuffer,
+        num_data_points: usize,
+        device: &Device,
+        encoder: &CommandEncoder,
+    ) {
+        let num_pairs = flows.num_pairs();
+        if num_pairs == 0 {
+            return;
+        }
+
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("flow aggregation bind group".into()),
+            entries: [
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: flows.counts().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: flows.categories().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: probabilities.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: flows.config().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+            ],
+            layout: self.layout.clone(),
+        });
+
+        let num_workgroups = preprocessor::num_workgroups(device.limits(), num_data_points);
+
+        encoder.clear_buffer(flows.counts());
+        let pass = encoder.begin_compute_pass(None);
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group);
+        pass.dispatch_workgroups(&[num_workgroups, num_pairs as u32]);
+        pass.end();
+    }
+}
+
+pub struct DensityEstimationComputePipeline {
+    layout: BindGroupLayout,
+    pipeline: ComputePipeline,
+}
+
+impl DensityEstimationComputePipeline {
+    async fn new(device: &Device) -> Self {
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("density estimation compute shader".into()),
+            code: preprocessor::bundled(
+                include_str!("./shaders/density/estimate_density.comp.wgsl"),
+                device.limits(),
+            )
+            .into(),
+        });
+
+        let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            label: Some("density estimation bind group layout".into()),
+            entries: [
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Storage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStage::COMPUTE,
                     resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
                         has_dynamic_offset: None,
                         min_binding_size: None,
@@ -1311,50 +4667,16 @@ async fn init_probability_computation_pipeline(
                     }),
                 },
                 BindGroupLayoutEntry {
-                    binding: 3,
-                    visibility: ShaderStage::COMPUTE,
-                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
-                        has_dynamic_offset: None,
-                        min_binding_size: None,
-                        r#type: Some(BufferBindingType::Uniform),
-                    }),
-                },
-            ],
-        });
-
-        let application_pipeline = device
-            .create_compute_pipeline_async(ComputePipelineDescriptor {
-                label: Some("curve application compute pipeline".into()),
-                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
-                    PipelineLayoutDescriptor {
-                        label: Some("curve application pipeline layout".into()),
-                        layouts: [application_bind_layout.clone()],
-                    },
-                )),
-                compute: ProgrammableStage {
-                    entry_point: "main",
-                    module: device.create_shader_module(ShaderModuleDescriptor {
-                        label: Some("curve application compute shader".into()),
-                        code: include_str!("./shaders/apply_curves.comp.wgsl").into(),
-                    }),
-                },
-            })
-            .await;
-
-        let reduction_bind_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
-            label: Some("curve application reduction bind group layout".into()),
-            entries: [
-                BindGroupLayoutEntry {
-                    binding: 0,
+                    binding: 2,
                     visibility: ShaderStage::COMPUTE,
                     resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
                         has_dynamic_offset: None,
                         min_binding_size: None,
-                        r#type: Some(BufferBindingType::Storage),
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
                     }),
                 },
                 BindGroupLayoutEntry {
-                    binding: 1,
+                    binding: 3,
                     visibility: ShaderStage::COMPUTE,
                     resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
                         has_dynamic_offset: None,
@@ -1363,7 +4685,7 @@ async fn init_probability_computation_pipeline(
                     }),
                 },
                 BindGroupLayoutEntry {
-                    binding: 2,
+                    binding: 4,
                     visibility: ShaderStage::COMPUTE,
                     resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
                         has_dynamic_offset: None,
@@ -1374,120 +4696,129 @@ async fn init_probability_computation_pipeline(
             ],
         });
 
-        let reduction_pipeline = device
+        let pipeline = device
             .create_compute_pipeline_async(ComputePipelineDescriptor {
-                label: Some("curve application reduction compute pipeline".into()),
+                label: Some("density estimation compute pipeline".into()),
                 layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
                     PipelineLayoutDescriptor {
-                        label: Some("curve application reduction pipeline layout".into()),
-                        layouts: [reduction_bind_layout.clone()],
+                        label: Some("density estimation pipeline layout".into()),
+                        layouts: [layout.clone()],
                     },
                 )),
                 compute: ProgrammableStage {
                     entry_point: "main",
-                    module: device.create_shader_module(ShaderModuleDescriptor {
-                        label: Some("curve application reduction compute shader".into()),
-                        code: include_str!("./shaders/reduce_probability.comp.wgsl").into(),
-                    }),
+                    module: shader_module,
                 },
             })
             .await;
 
-        ProbabilityComputationPipeline {
-            apply_curve_bind_layout: application_bind_layout,
-            apply_curve_pipeline: application_pipeline,
-            reduce_bind_layout: reduction_bind_layout,
-            reduce_pipeline: reduction_pipeline,
-        }
+        Self { layout, pipeline }
     }
 
-    async fn init_color_scale_transformation_pipeline(
+    /// Estimates the densities of the visible axes with a gaussian kernel.
+    ///
+    /// The densities of the datums with a probability inside of the selection
+    /// bounds are only estimated, if enabled in the config.
+    pub fn dispatch(
+        &self,
+        densities: &buffers::DensityBuffers,
+        data: &buffers::DataBuffer,
+        probabilities: &buffers::ProbabilitiesBuffer,
         device: &Device,
-    ) -> (BindGroupLayout, ComputePipeline) {
-        let bind_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
-            label: Some("color scale transformation bind group layout".into()),
+        encoder: &CommandEncoder,
+    ) {
+        let num_axes = densities.num_axes();
+        if num_axes == 0 {
+            return;
+        }
+
+        let bind_group = device.create_bind_group(BindGroupDescriptor {
+            label: Some("density estimation bind group".into()),
             entries: [
-                BindGroupLayoutEntry {
+                BindGroupEntry {
                     binding: 0,
-                    visibility: ShaderStage::COMPUTE,
-                    resource: BindGroupLayoutEntryResource::Texture(TextureBindingLayout {
-                        multisampled: None,
-                        sample_type: Some(TextureSampleType::UnfilterableFloat),
-                        view_dimension: Some(TextureViewDimension::D2),
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: densities.densities().clone(),
+                        offset: None,
+                        size: None,
                     }),
                 },
-                BindGroupLayoutEntry {
+                BindGroupEntry {
                     binding: 1,
-                    visibility: ShaderStage::COMPUTE,
-                    resource: BindGroupLayoutEntryResource::StorageTexture(
-                        StorageTextureBindingLayout {
-                            access: Some(StorageTextureAccess::WriteOnly),
-                            format: TextureFormat::Rgba32float,
-                            view_dimension: Some(TextureViewDimension::D2),
-                        },
-                    ),
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: data.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
                 },
-                BindGroupLayoutEntry {
+                BindGroupEntry {
                     binding: 2,
-                    visibility: ShaderStage::COMPUTE,
-                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
-                        has_dynamic_offset: None,
-                        min_binding_size: None,
-                        r#type: Some(BufferBindingType::Uniform),
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: probabilities.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: densities.visible_ranges().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: densities.config().clone(),
+                        offset: None,
+                        size: None,
                     }),
                 },
             ],
+            layout: self.layout.clone(),
         });
 
-        let pipeline = device
-            .create_compute_pipeline_async(ComputePipelineDescriptor {
-                label: Some("color scale transformation compute pipeline".into()),
-                layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
-                    PipelineLayoutDescriptor {
-                        label: Some("color scale transformation pipeline layout".into()),
-                        layouts: [bind_layout.clone()],
-                    },
-                )),
-                compute: ProgrammableStage {
-                    entry_point: "main",
-                    module: device.create_shader_module(ShaderModuleDescriptor {
-                        label: Some("color scale transformation compute shader".into()),
-                        code: include_str!("./shaders/color_scale/transform_color_scale.comp.wgsl")
-                            .into(),
-                    }),
-                },
-            })
-            .await;
+        let num_workgroups = preprocessor::num_workgroups(
+            device.limits(),
+            buffers::DensityConfig::NUM_SAMPLES as usize,
+        );
 
-        (bind_layout, pipeline)
+        let pass = encoder.begin_compute_pass(None);
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group);
+        pass.dispatch_workgroups(&[num_workgroups, num_axes as u32]);
+        pass.end();
     }
 }
 
-pub struct ProbabilityCurveSplineSamplingComputePipeline {
+pub struct HistogramBinningComputePipeline {
     layout: BindGroupLayout,
     pipeline: ComputePipeline,
 }
 
-impl ProbabilityCurveSplineSamplingComputePipeline {
+impl HistogramBinningComputePipeline {
     async fn new(device: &Device) -> Self {
         let shader_module = device.create_shader_module(ShaderModuleDescriptor {
-            label: Some("probability curve sampling compute shader".into()),
-            code: include_str!("./shaders/probability_curve/sample_spline.comp.wgsl").into(),
+            label: Some("histogram binning compute shader".into()),
+            code: preprocessor::bundled(
+                include_str!("./shaders/histogram/bin_values.comp.wgsl"),
+                device.limits(),
+            )
+            .into(),
         });
 
         let layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
-            label: Some("probability curve spline sampling bind group layout".into()),
+            label: Some("histogram binning bind group layout".into()),
             entries: [
                 BindGroupLayoutEntry {
                     binding: 0,
                     visibility: ShaderStage::COMPUTE,
-                    resource: BindGroupLayoutEntryResource::StorageTexture(
-                        StorageTextureBindingLayout {
-                            access: Some(StorageTextureAccess::WriteOnly),
-                            format: TextureFormat::R32float,
-                            view_dimension: Some(TextureViewDimension::D2),
-                        },
-                    ),
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Storage),
+                    }),
                 },
                 BindGroupLayoutEntry {
                     binding: 1,
@@ -1498,15 +4829,42 @@ async fn new(device: &Device) -> Self {
                         r#type: Some(BufferBindingType::ReadOnlyStorage),
                     }),
                 },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::ReadOnlyStorage),
+                    }),
+                },
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStage::COMPUTE,
+                    resource: BindGroupLayoutEntryResource::Buffer(BufferBindingLayout {
+                        has_dynamic_offset: None,
+                        min_binding_size: None,
+                        r#type: Some(BufferBindingType::Uniform),
+                    }),
+                },
             ],
         });
 
         let pipeline = device
             .create_compute_pipeline_async(ComputePipelineDescriptor {
-                label: Some("probability curve spline sampling compute pipeline".into()),
+                label: Some("histogram binning compute pipeline".into()),
                 layout: PipelineLayoutType::Layout(device.create_pipeline_layout(
                     PipelineLayoutDescriptor {
-                        label: Some("curve sampling pipeline layout".into()),
+                        label: Some("histogram binning pipeline layout".into()),
                         layouts: [layout.clone()],
                     },
                 )),
@@ -1520,27 +4878,62 @@ async fn new(device: &Device) -> Self {
         Self { layout, pipeline }
     }
 
+    /// Counts the data points in each bin of the visible axes.
+    ///
+    /// The counts weighted by the probabilities are only computed, if enabled
+    /// in the config.
     pub fn dispatch(
         &self,
-        axis_idx: usize,
-        probability_texture: &buffers::ProbabilitySampleTexture,
-        spline_segments: &buffers::SplineSegmentsBuffer,
+        histograms: &buffers::HistogramBuffers,
+        data: &buffers::DataBuffer,
+        probabilities: &buffers::ProbabilitiesBuffer,
         device: &Device,
         encoder: &CommandEncoder,
     ) {
+        let num_axes = histograms.num_axes();
+        if num_axes == 0 {
+            return;
+        }
+
         let bind_group = device.create_bind_group(BindGroupDescriptor {
-            label: Some("probability curve spline sampling bind group".into()),
+            label: Some("histogram binning bind group".into()),
             entries: [
                 BindGroupEntry {
                     binding: 0,
-                    resource: BindGroupEntryResource::TextureView(
-                        probability_texture.axis_view(axis_idx),
-                    ),
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: histograms.bins().clone(),
+                        offset: None,
+                        size: None,
+                    }),
                 },
                 BindGroupEntry {
                     binding: 1,
                     resource: BindGroupEntryResource::Buffer(BufferBinding {
-                        buffer: spline_segments.buffer().clone(),
+                        buffer: data.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: probabilities.buffer().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: histograms.visible_ranges().clone(),
+                        offset: None,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: BindGroupEntryResource::Buffer(BufferBinding {
+                        buffer: histograms.config().clone(),
                         offset: None,
                         size: None,
                     }),
@@ -1549,13 +4942,12 @@ pub fn dispatch(
             layout: self.layout.clone(),
         });
 
-        const NUM_WORKGROUPS: u32 =
-            ((buffers::ProbabilitySampleTexture::PROBABILITY_CURVE_RESOLUTION + 63) / 64) as u32;
+        let num_workgroups = preprocessor::num_workgroups(device.limits(), histograms.num_bins());
 
         let pass = encoder.begin_compute_pass(None);
         pass.set_pipeline(&self.pipeline);
         pass.set_bind_group(0, &bind_group);
-        pass.dispatch_workgroups(&[NUM_WORKGROUPS]);
+        pass.dispatch_workgroups(&[num_workgroups, num_axes as u32]);
         pass.end();
     }
 }
@@ -1571,12 +4963,20 @@ impl ColorScaleSamplingComputePipeline {
     async fn new(device: &Device) -> Self {
         let sampling_shader_module = device.create_shader_module(ShaderModuleDescriptor {
             label: Some("color scale sampling shader module".into()),
-            code: include_str!("./shaders/color_scale/sample_color_scale.comp.wgsl").into(),
+            code: preprocessor::bundled(
+                include_str!("./shaders/color_scale/sample_color_scale.comp.wgsl"),
+                device.limits(),
+            )
+            .into(),
         });
 
         let transformation_shader_module = device.create_shader_module(ShaderModuleDescriptor {
             label: Some("color scale transformation shader module".into()),
-            code: include_str!("./shaders/color_scale/transform_color_scale.comp.wgsl").into(),
+            code: preprocessor::bundled(
+                include_str!("./shaders/color_scale/transform_color_scale.comp.wgsl"),
+                device.limits(),
+            )
+            .into(),
         });
 
         let sampling_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
@@ -1688,8 +5088,10 @@ pub fn dispatch(
         device: &Device,
         encoder: &CommandEncoder,
     ) {
-        const NUM_WORKGROUPS: u32 =
-            ((buffers::ColorScaleTexture::COLOR_SCALE_RESOLUTION + 63) / 64) as u32;
+        let num_workgroups = preprocessor::num_workgroups(
+            device.limits(),
+            buffers::ColorScaleTexture::COLOR_SCALE_RESOLUTION,
+        );
 
         let color_scale_view = color_scale.view();
         let bind_group = device.create_bind_group(BindGroupDescriptor {
@@ -1714,7 +5116,7 @@ pub fn dispatch(
         let pass = encoder.begin_compute_pass(None);
         pass.set_pipeline(&self.sampling_pipeline);
         pass.set_bind_group(0, &bind_group);
-        pass.dispatch_workgroups(&[NUM_WORKGROUPS]);
+        pass.dispatch_workgroups(&[num_workgroups]);
         pass.end();
 
         // We don't need to transform the color space, since it is already correct.
@@ -1728,6 +5130,8 @@ pub fn dispatch(
             crate::wasm_bridge::ColorSpace::Xyz => 1,
             crate::wasm_bridge::ColorSpace::CieLab => 2,
             crate::wasm_bridge::ColorSpace::CieLch => 3,
+            crate::wasm_bridge::ColorSpace::Oklab => 4,
+            crate::wasm_bridge::ColorSpace::Oklch => 5,
         };
         let color_space_buffer = device.create_buffer(BufferDescriptor {
             label: Some("color space buffer".into()),
@@ -1765,7 +5169,7 @@ pub fn dispatch(
         let pass = encoder.begin_compute_pass(None);
         pass.set_pipeline(&self.transformation_pipeline);
         pass.set_bind_group(0, &bind_group);
-        pass.dispatch_workgroups(&[NUM_WORKGROUPS]);
+        pass.dispatch_workgroups(&[num_workgroups]);
         pass.end();
 
         *color_scale = tmp_color_scale;
@@ -0,0 +1,270 @@
+//! Preprocessing of the bundled WGSL shaders.
+//!
+//! Constants and struct layouts which are shared with the host are defined
+//! once on the Rust side, and are included into the shaders with an
+//! `#include <name>` directive on a line of its own.
+use std::borrow::Cow;
+
+use crate::{buffers, colors, webgpu::Limits, wgsl::WgslStruct};
+
+/// Preferred number of invocations of each compute workgroup.
+const PREFERRED_WORKGROUP_SIZE: u32 = 256;
+
+/// Number of invocations of each compute workgroup supported by all devices.
+const MIN_WORKGROUP_SIZE: u32 = 64;
+
+/// Returns the number of invocations of each compute workgroup.
+///
+/// Picks the largest power of two up to [`PREFERRED_WORKGROUP_SIZE`] which
+/// is supported by a device with the given `limits`.
+pub fn workgroup_size(limits: Limits) -> u32 {
+    let max_size = PREFERRED_WORKGROUP_SIZE
+        .min(limits.max_compute_workgroup_size_x)
+        .min(limits.max_compute_invocations_per_workgroup);
+    if max_size < MIN_WORKGROUP_SIZE {
+        MIN_WORKGROUP_SIZE
+    } else {
+        1 << max_size.ilog2()
+    }
+}
+
+/// Returns the number of workgroups required to process `num_items` items.
+pub fn num_workgroups(limits: Limits, num_items: usize) -> u32 {
+    let workgroup_size = workgroup_size(limits) as usize;
+    num_items.div_ceil(workgroup_size) as u32
+}
+
+fn constants(limits: Limits) -> String {
+    use buffers::{DataLineConfig as Config, FlowPair, SelectionLineInfo as Selection};
+
+    let constants = [
+        ("WORKGROUP_SIZE", workgroup_size(limits)),
+        ("ORDER_UNORDERED", Config::ORDER_UNORDERED),
+        ("ORDER_PROBABILITY", Config::ORDER_PROBABILITY),
+        (
+            "ORDER_PROBABILITY_INVERTED",
+            Config::ORDER_PROBABILITY_INVERTED,
+        ),
+        ("ORDER_SELECTED_UNORDERED", Config::ORDER_SELECTED_UNORDERED),
+        (
+            "ORDER_SELECTED_PROBABILITY",
+            Config::ORDER_SELECTED_PROBABILITY,
+        ),
+        (
+            "ORDER_SELECTED_PROBABILITY_INVERTED",
+            Config::ORDER_SELECTED_PROBABILITY_INVERTED,
+        ),
+        ("LINE_JOIN_BUTT", Config::LINE_JOIN_BUTT),
+        ("LINE_JOIN_ROUND", Config::LINE_JOIN_ROUND),
+        ("LINE_JOIN_MITER", Config::LINE_JOIN_MITER),
+        ("SELECTION_STYLE_DEFAULT", Selection::STYLE_DEFAULT),
+        ("SELECTION_STYLE_DIMMED", Selection::STYLE_DIMMED),
+        ("SELECTION_STYLE_PREVIEW", Selection::STYLE_PREVIEW),
+        ("MAX_FLOW_CATEGORIES", buffers::MAX_FLOW_CATEGORIES as u32),
+        ("FLOW_MISSING_CATEGORY", FlowPair::MISSING_CATEGORY),
+        ("FLOW_RIBBON_SEGMENTS", FlowPair::NUM_SEGMENTS),
+        ("SORT_INVALID_VALUE", buffers::SortPair::INVALID_VALUE),
+        ("DENSITY_CURVE_SAMPLES", buffers::DensityConfig::NUM_SAMPLES),
+    ];
+
+    constants
+        .iter()
+        .map(|(name, value)| format!("const {name}: u32 = {value}u;\n"))
+        .collect()
+}
+
+/// Transfer function of the `sRGB` color space.
+const ENCODE_OUTPUT: &str = "
+fn encode_output(color: vec3<f32>) -> vec3<f32> {
+    let a = 12.92 * color;
+    let b = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+    let c = step(vec3<f32>(0.0031308), color);
+    return mix(a, b, c);
+}
+";
+
+/// Colors are blended in linear space, and encoded when they are resolved.
+const ENCODE_OUTPUT_LINEAR: &str = "
+fn encode_output(color: vec3<f32>) -> vec3<f32> {
+    return color;
+}
+";
+
+/// Converts colors specified in `sRGB` to the output colors.
+const SRGB_TO_OUTPUT: &str = "
+fn srgb_to_output(color: vec3<f32>) -> vec3<f32> {
+    let a = color / 12.92;
+    let b = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+    let c = step(vec3<f32>(0.04045), color);
+    let linear_srgb = mix(a, b, c);
+    return encode_output(XYZ_SRGB_CONVERSION_MATRIX * (SRGB_XYZ_CONVERSION_MATRIX * linear_srgb));
+}
+";
+
+/// Moves the vertical lines to the centers of the device pixels, which
+/// avoids shimmering of thin lines at fractional positions.
+///
+/// The resolution is the size of the viewport in device pixels, and disables
+/// the snapping if it is zero.
+const PIXEL_SNAPPING: &str = "
+fn snap_to_pixel(position: vec4<f32>, resolution: vec2<f32>) -> vec4<f32> {
+    if resolution.x == 0.0 {
+        return position;
+    }
+
+    let pixel = (position.x / position.w * 0.5 + 0.5) * resolution.x;
+    let snapped = ((floor(pixel) + 0.5) / resolution.x * 2.0 - 1.0) * position.w;
+    return vec4<f32>(snapped, position.yzw);
+}
+";
+
+/// Test for the missing values of the data, which are stored as `NaN`.
+///
+/// The bits are compared directly, as the comparison operators may assume
+/// that their operands are not `NaN`.
+const MISSING_VALUES: &str = "
+fn is_missing(value: f32) -> bool {
+    return (bitcast<u32>(value) & 0x7fffffffu) > 0x7f800000u;
+}
+";
+
+/// Coverage of the lines, which fades out their outer half.
+const FEATHERED_LINE_COVERAGE: &str = "
+const FEATHER: f32 = 0.5;
+const ONE_MINUS_FEATHER: f32 = 1.0 - FEATHER;
+
+fn line_coverage(normal: vec2<f32>) -> f32 {
+    let distance = length(normal);
+    if distance <= ONE_MINUS_FEATHER {
+        return 1.0;
+    } else if distance <= 1.0 {
+        let t = (distance - FEATHER) / ONE_MINUS_FEATHER;
+        return mix(1.0, 0.0, t);
+    }
+
+    return 0.0;
+}
+";
+
+/// Coverage of the lines, which fades out the last device pixel before their
+/// edges, independent of the width of the lines.
+const ANALYTIC_LINE_COVERAGE: &str = "
+fn line_coverage(normal: vec2<f32>) -> f32 {
+    let distance = length(normal);
+    let pixel_size = max(fwidth(distance), 0.0001);
+    return clamp((1.0 - distance) / pixel_size, 0.0, 1.0);
+}
+";
+
+fn matrix_constant(name: &str, m: [[f32; 3]; 3]) -> String {
+    // WGSL matrices are constructed from their columns.
+    let columns = (0..3)
+        .map(|c| {
+            format!(
+                "    vec3<f32>({:?}, {:?}, {:?}),\n",
+                m[0][c], m[1][c], m[2][c]
+            )
+        })
+        .collect::<String>();
+    format!("const {name} = mat3x3<f32>(\n{columns});\n")
+}
+
+/// Returns the conversions from `XYZ` and `sRGB` to the output colors.
+///
+/// Wide-gamut canvases use the `display-p3` color space, which shares the
+/// transfer function of `sRGB`, so only the primaries differ.
+fn color_conversion(output: buffers::OutputFormat) -> String {
+    let xyz_to_output = if output.wide_gamut {
+        colors::XYZ_TO_DISPLAY_P3_LINEAR_MATRIX
+    } else {
+        colors::XYZ_TO_SRGB_LINEAR_MATRIX
+    };
+    let encode_output = if output.linear {
+        ENCODE_OUTPUT_LINEAR
+    } else {
+        ENCODE_OUTPUT
+    };
+
+    let mut conversion = matrix_constant("XYZ_SRGB_CONVERSION_MATRIX", xyz_to_output);
+    conversion += &matrix_constant(
+        "SRGB_XYZ_CONVERSION_MATRIX",
+        colors::SRGB_LINEAR_TO_XYZ_MATRIX,
+    );
+    conversion += encode_output;
+    conversion += SRGB_TO_OUTPUT;
+    conversion
+}
+
+/// Returns the definitions of the include `name`.
+fn include(name: &str, limits: Limits, output: buffers::OutputFormat) -> Option<Cow<'static, str>> {
+    let include = match name {
+        "constants" => constants(limits).into(),
+        "color_conversion" => color_conversion(output).into(),
+        "missing_values" => MISSING_VALUES.into(),
+        "pixel_snapping" => PIXEL_SNAPPING.into(),
+        "line_coverage" if output.analytic_antialiasing => ANALYTIC_LINE_COVERAGE.into(),
+        "line_coverage" => FEATHERED_LINE_COVERAGE.into(),
+        "matrices" => buffers::Matrices::DEFINITION.into(),
+        "axes" => buffers::Axis::DEFINITION.into(),
+        "label_color" => buffers::LabelColor::DEFINITION.into(),
+        "curve_line_info" => buffers::CurveLineInfo::DEFINITION.into(),
+        "draw_indirect_args" => buffers::DrawIndirectArgs::DEFINITION.into(),
+        _ => return None,
+    };
+
+    Some(include)
+}
+
+/// Resolves the `#include` directives of the shader `source`.
+///
+/// The included constants are specialized to a device with the given
+/// `limits`. Returns an error if an unknown name is included.
+pub fn preprocess(source: &str, limits: Limits) -> Result<String, String> {
+    let output = buffers::OutputFormat {
+        format: crate::webgpu::TextureFormat::Bgra8Unorm,
+        wide_gamut: false,
+        linear: false,
+        samples: buffers::RenderTexture::MSAA_SAMPLES,
+        analytic_antialiasing: false,
+    };
+    preprocess_render(source, limits, output)
+}
+
+/// Resolves the `#include` directives of the render shader `source`.
+///
+/// The color conversions are specialized to the `output` of the pipeline.
+pub fn preprocess_render(
+    source: &str,
+    limits: Limits,
+    output: buffers::OutputFormat,
+) -> Result<String, String> {
+    let mut processed = String::with_capacity(source.len());
+    for line in source.lines() {
+        match line.trim().strip_prefix("#include") {
+            Some(name) => {
+                let name = name.trim();
+                let name = name.strip_prefix('<').unwrap_or(name);
+                let name = name.strip_suffix('>').unwrap_or(name);
+                match include(name, limits, output) {
+                    Some(include) => processed.push_str(include.trim()),
+                    None => return Err(format!("unknown shader include <{name}>")),
+                }
+            }
+            None => processed.push_str(line),
+        }
+        processed.push('\n');
+    }
+
+    Ok(processed)
+}
+
+/// Resolves the `#include` directives of a shader bundled with the crate.
+pub fn bundled(source: &str, limits: Limits) -> String {
+    preprocess(source, limits).expect("the bundled shaders should only include known names")
+}
+
+/// Resolves the `#include` directives of a render shader bundled with the crate.
+pub fn bundled_render(source: &str, limits: Limits, output: buffers::OutputFormat) -> String {
+    preprocess_render(source, limits, output)
+        .expect("the bundled shaders should only include known names")
+}
@@ -0,0 +1,125 @@
+//! Scheduling of draws driven by `requestAnimationFrame` and timers.
+use std::{cell::Cell, rc::Rc};
+
+use async_channel::Sender;
+
+use crate::wasm_bridge::Event;
+
+/// Spawns a [`Event::Draw`] on each animation frame, limited to a maximum rate.
+///
+/// The scheduler waits for each draw to complete before requesting the next
+/// one, and stops once it is dropped.
+#[derive(Debug)]
+pub struct DrawScheduler {
+    stopped: Rc<Cell<bool>>,
+}
+
+impl DrawScheduler {
+    pub fn new(sender: Sender<Event>, max_fps: Option<f32>) -> Self {
+        let stopped = Rc::new(Cell::new(false));
+        let interval = max_fps
+            .filter(|fps| fps.is_finite() && *fps > 0.0)
+            .map_or(0.0, |fps| 1000.0 / fps as f64);
+
+        let flag = stopped.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let mut last_frame = f64::NEG_INFINITY;
+            loop {
+                let time = next_animation_frame().await;
+                if flag.get() {
+                    break;
+                }
+
+                let elapsed = time - last_frame;
+                if elapsed < interval {
+                    continue;
+                }
+
+                // Carry over the excess time, such that the frame rate
+                // does not drift below the requested rate.
+                last_frame = if interval > 0.0 && elapsed.is_finite() {
+                    time - (elapsed % interval)
+                } else {
+                    time
+                };
+
+                let (sx, rx) = async_channel::bounded(1);
+                if sender.send(Event::Draw { completion: sx }).await.is_err() {
+                    break;
+                }
+                if rx.recv().await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self { stopped }
+    }
+}
+
+impl Drop for DrawScheduler {
+    fn drop(&mut self) {
+        self.stopped.set(true);
+    }
+}
+
+/// Spawns a single [`Event::Draw`] after a delay, unless it is dropped before.
+#[derive(Debug)]
+pub struct DelayedDraw {
+    cancelled: Rc<Cell<bool>>,
+}
+
+impl DelayedDraw {
+    pub fn new(sender: Sender<Event>, delay: f64) -> Self {
+        let cancelled = Rc::new(Cell::new(false));
+
+        let flag = cancelled.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            timeout(delay).await;
+            if flag.get() {
+                return;
+            }
+
+            let (sx, rx) = async_channel::bounded(1);
+            if sender.send(Event::Draw { completion: sx }).await.is_ok() {
+                let _ = rx.recv().await;
+            }
+        });
+
+        Self { cancelled }
+    }
+}
+
+impl Drop for DelayedDraw {
+    fn drop(&mut self) {
+        self.cancelled.set(true);
+    }
+}
+
+/// Waits for the given number of milliseconds.
+pub async fn timeout(delay: f64) {
+    let timer = js_sys::Promise::new(&mut |resolve, _| {
+        web_sys::window()
+            .unwrap()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, delay.ceil() as i32)
+            .unwrap();
+    });
+
+    wasm_bindgen_futures::JsFuture::from(timer).await.unwrap();
+}
+
+/// Waits for the next animation frame and returns its timestamp.
+pub async fn next_animation_frame() -> f64 {
+    let frame = js_sys::Promise::new(&mut |resolve, _| {
+        web_sys::window()
+            .unwrap()
+            .request_animation_frame(&resolve)
+            .unwrap();
+    });
+
+    wasm_bindgen_futures::JsFuture::from(frame)
+        .await
+        .unwrap()
+        .as_f64()
+        .unwrap_or_else(js_sys::Date::now)
+}
@@ -9,6 +9,7 @@
 pub struct SelectionCurve {
     range: [f32; 2],
     is_dirty: bool,
+    is_imported: bool,
     spline: Spline,
 }
 
@@ -24,6 +25,7 @@ impl SelectionCurve {
         Self {
             range,
             is_dirty: true,
+            is_imported: false,
             spline,
         }
     }
@@ -38,6 +40,34 @@ pub fn set_curve(&mut self, spline: Option<Spline>) {
         }
 
         self.is_dirty = true;
+        self.is_imported = false;
+    }
+
+    /// Sets a curve that was not built from selections. The curve is kept
+    /// until it is replaced with [`SelectionCurve::set_curve`].
+    pub fn import_curve(&mut self, mut spline: Spline) {
+        spline.set_range(self.range);
+        self.spline = spline;
+        self.is_dirty = true;
+        self.is_imported = true;
+    }
+
+    pub fn is_imported(&self) -> bool {
+        self.is_imported
+    }
+
+    pub fn set_range(&mut self, range: [f32; 2]) {
+        if range[0] >= range[1] {
+            panic!("invalid selection curve range")
+        }
+
+        self.range = range;
+        self.spline.set_range(range);
+        self.is_dirty = true;
+    }
+
+    pub fn spline(&self) -> &Spline {
+        &self.spline
     }
 
     pub fn get_changed_curve(&mut self) -> Option<&Spline> {
@@ -150,6 +180,12 @@ pub fn get_group_containing(&self, value: f32) -> Option<usize> {
             .next()
     }
 
+    pub fn get_group_range(&self, group_idx: usize) -> Option<[f32; 2]> {
+        self.selection_groups
+            .get(group_idx)
+            .map(|group| group.range)
+    }
+
     pub fn get_group_ranges_between(&self, [min, max]: [f32; 2]) -> Box<[[f32; 2]]> {
         let range = min..=max;
         let mut groups = Vec::new();
@@ -498,6 +534,30 @@ pub fn offset(&mut self, offset: f32) {
         }
     }
 
+    /// Moves and scales the control points, such that the selection spans
+    /// the range `[start, end]`.
+    pub fn set_selection_range(&mut self, [start, end]: [f32; 2]) {
+        let [old_start, old_end] = self.selection_range();
+        let factor = if old_start < old_end {
+            (end - start) / (old_end - old_start)
+        } else {
+            0.0
+        };
+        for (x, _) in &mut self.control_points {
+            *x = start + (*x - old_start) * factor;
+        }
+
+        let last = self.control_points.len() - 1;
+        self.control_points[last].0 = end;
+    }
+
+    /// Scales the distance of each control point to the `center`.
+    pub fn scale(&mut self, center: f32, factor: f32) {
+        for (x, _) in &mut self.control_points {
+            *x = center + (*x - center) * factor;
+        }
+    }
+
     pub fn to_spline_segments(
         &self,
         [min, max]: [f32; 2],
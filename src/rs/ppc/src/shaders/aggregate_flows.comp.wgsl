@@ -0,0 +1,46 @@
+#include <constants>
+
+struct Config {
+    color: vec4<f32>,
+    selection_bounds: vec2<f32>,
+    num_datums: u32,
+    use_selection: u32,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> counts: array<atomic<u32>>;
+
+@group(0) @binding(1)
+var<storage, read> categories: array<u32>;
+
+@group(0) @binding(2)
+var<storage, read> probabilities: array<f32>;
+
+@group(0) @binding(3)
+var<uniform> config: Config;
+
+// Counts the datums taking each pair of categories, one axis pair per workgroup row.
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let datum = global_id.x;
+    let pair = global_id.y;
+    if datum >= config.num_datums {
+        return;
+    }
+
+    // Only the selected datums of the active label are counted.
+    if config.use_selection != 0u {
+        let probability = probabilities[datum];
+        if probability < config.selection_bounds.x || probability > config.selection_bounds.y {
+            return;
+        }
+    }
+
+    let category_pair = categories[pair * config.num_datums + datum];
+    if category_pair == FLOW_MISSING_CATEGORY {
+        return;
+    }
+
+    let pair_offset = pair * MAX_FLOW_CATEGORIES * MAX_FLOW_CATEGORIES;
+    atomicAdd(&counts[pair_offset + category_pair], 1u);
+}
@@ -1,3 +1,6 @@
+#include <constants>
+#include <missing_values>
+
 @group(0) @binding(0)
 var<storage, read_write> output: array<f32>;
 
@@ -10,24 +13,36 @@ var<storage, read> data: array<f32>;
 @group(0) @binding(3)
 var<uniform> num_datums: u32;
 
-@compute @workgroup_size(64)
+@group(0) @binding(4)
+var<storage, read> changed_axes: array<u32>;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(
     @builtin(global_invocation_id) global_id: vec3<u32>
 ) {
-    if global_id.x >= arrayLength(&data) {
+    if global_id.x >= num_datums || global_id.y >= arrayLength(&changed_axes) {
+        return;
+    }
+
+    // Each row of workgroups recomputes the column of one changed axis.
+    let axis_idx = changed_axes[global_id.y];
+    let idx = axis_idx * num_datums + global_id.x;
+    let value = data[idx];
+
+    // Missing values don't constrain the probability of the data point.
+    if is_missing(value) {
+        output[idx] = 1.0;
         return;
     }
 
-    let value = data[global_id.x];
     let texture_idx = value * f32(textureDimensions(probability_curves).x - 1);
     let lower_texel_pos = i32(floor(texture_idx));
     let upper_texel_pos = i32(ceil(texture_idx));
     let t = fract(texture_idx);
 
-    let axis_idx = i32(global_id.x / num_datums);
-    let lower_texel = textureLoad(probability_curves, vec2<i32>(lower_texel_pos, 0), axis_idx, 0).r;
-    let upper_texel = textureLoad(probability_curves, vec2<i32>(upper_texel_pos, 0), axis_idx, 0).r;
+    let lower_texel = textureLoad(probability_curves, vec2<i32>(lower_texel_pos, 0), i32(axis_idx), 0).r;
+    let upper_texel = textureLoad(probability_curves, vec2<i32>(upper_texel_pos, 0), i32(axis_idx), 0).r;
 
     let curve_value = mix(lower_texel, upper_texel, t);
-    output[global_id.x] = curve_value;
+    output[idx] = curve_value;
 }
\ No newline at end of file
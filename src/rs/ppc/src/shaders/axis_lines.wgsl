@@ -1,24 +1,23 @@
 struct Config {
     line_width: vec2<f32>,
+    snap_resolution: vec2<f32>,
     color: vec3<f32>,
+    marker_color: vec3<f32>,
 }
 
-struct Matrices {
-    mv_matrix: mat4x4<f32>,
-    p_matrix: mat4x4<f32>,
-}
+#include <matrices>
 
-struct Axes {
-    expanded_val: f32,
-    center_x: f32,
-    position_x: vec2<f32>,
-    range_y: vec2<f32>,
-}
+#include <axes>
+
+#include <color_conversion>
+
+#include <pixel_snapping>
 
 struct AxisLineInfo {
     axis: u32,
     axis_position: f32,
     min_expanded_val: f32,
+    marker: u32,
 }
 
 const AXIS_LEFT: u32 = 0u;
@@ -41,10 +40,10 @@ struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) normal: vec2<f32>,
     @location(1) @interpolate(flat) discard_line: u32,
+    @location(2) @interpolate(flat) marker: u32,
 }
 
-const FEATHER: f32 = 0.5;
-const ONE_MINUS_FEATHER: f32 = 1.0 - FEATHER;
+#include <line_coverage>
 
 @vertex
 fn vertex_main(
@@ -88,33 +87,28 @@ fn vertex_main(
     let vertex_normal = rotation_matrix * VERTEX_NORMALS_BUFFER[index];
     let vertex_pos = select(line_start, line_end, vec2<bool>(index <= 1u));
 
-    let line_width = select(config.line_width, config.line_width * 2.0, line.axis_position == 0);
+    let wide_line = line.axis_position == 0 || line.marker != 0u;
+    let line_width = select(config.line_width, config.line_width * 2.0, wide_line);
     let delta = matrices.mv_matrix * vec4<f32>(vertex_normal * line_width, 0.0, 0.0);
     let pos = matrices.mv_matrix * vec4<f32>(vertex_pos, 0.0, 1.0);
-    let offset_position = matrices.p_matrix * (pos + delta);
+    let center = snap_to_pixel(matrices.p_matrix * pos, config.snap_resolution);
+    let offset_position = center + matrices.p_matrix * delta;
 
-    return VertexOutput(offset_position, vertex_normal, select(0u, 1u, discard_line));
+    return VertexOutput(offset_position, vertex_normal, select(0u, 1u, discard_line), line.marker);
 }
 
 @fragment
 fn fragment_main(
     @location(0) normal: vec2<f32>,
     @location(1) @interpolate(flat) discard_line: u32,
+    @location(2) @interpolate(flat) marker: u32,
 ) -> @location(0) vec4<f32> {
     if discard_line != 0u {
         discard;
     }
 
-    let distance = length(normal);
-    var alpha = 0.0;
-
-    if distance <= ONE_MINUS_FEATHER {
-        alpha = 1.0;
-    } else if distance <= 1.0 {
-        let t = (distance - FEATHER) / ONE_MINUS_FEATHER;
-        alpha = mix(1.0, 0.0, t);
-    }
+    let alpha = line_coverage(normal);
 
-    let color = config.color;
+    let color = srgb_to_output(select(config.color, config.marker_color, marker != 0u));
     return vec4<f32>(color * alpha, alpha);
 }
\ No newline at end of file
@@ -0,0 +1,64 @@
+#include <matrices>
+#include <axes>
+
+struct AxisRegion {
+    axis: u32,
+    half_width: f32,
+    range: vec2<f32>,
+    color: vec4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> matrices: Matrices;
+
+@group(0) @binding(1)
+var<storage, read> axes: array<Axes>;
+
+@group(0) @binding(2)
+var<storage, read> regions: array<AxisRegion>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) @interpolate(flat) instance_idx: u32,
+}
+
+#include <color_conversion>
+
+fn xyz_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let linear_srgb = XYZ_SRGB_CONVERSION_MATRIX * color.xyz;
+    return encode_output(linear_srgb);
+}
+
+@vertex
+fn vertex_main(
+    @builtin(vertex_index) vertex_idx: u32,
+    @builtin(instance_index) instance_idx: u32,
+) -> VertexOutput {
+    var INDEX_BUFFER = array<u32, 6>(0u, 1u, 2u, 1u, 3u, 2u);
+    var VERTEX_BUFFER = array<vec2<f32>, 4>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 1.0),
+    );
+
+    let vertex = VERTEX_BUFFER[INDEX_BUFFER[vertex_idx]];
+    let region = regions[instance_idx];
+    let axis = axes[region.axis];
+
+    // Expanded axes are covered up to the extends of their curves.
+    let left = mix(axis.center_x - region.half_width, axis.position_x.x, axis.expanded_val);
+    let right = mix(axis.center_x + region.half_width, axis.position_x.y, axis.expanded_val);
+    let bottom = mix(axis.range_y.x, axis.range_y.y, region.range.x);
+    let top = mix(axis.range_y.x, axis.range_y.y, region.range.y);
+
+    let vertex_pos = vec2<f32>(mix(left, right, vertex.x), mix(bottom, top, vertex.y));
+    let pos = matrices.mv_matrix * vec4<f32>(vertex_pos, 0.0, 1.0);
+    return VertexOutput(matrices.p_matrix * pos, instance_idx);
+}
+
+@fragment
+fn fragment_main(@location(0) @interpolate(flat) instance_idx: u32) -> @location(0) vec4<f32> {
+    let color = regions[instance_idx].color;
+    return vec4<f32>(xyz_to_srgb(color.rgb) * color.a, color.a);
+}
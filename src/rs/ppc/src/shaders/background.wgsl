@@ -0,0 +1,75 @@
+struct Config {
+    opacity: f32,
+    gradient: u32,
+    direction: vec2<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> config: Config;
+
+@group(0) @binding(1)
+var image: texture_2d<f32>;
+
+@group(0) @binding(2)
+var image_sampler: sampler;
+
+@group(0) @binding(3)
+var gradient: texture_2d<f32>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+#include <color_conversion>
+
+@vertex
+fn vertex_main(@builtin(vertex_index) vertex_idx: u32) -> VertexOutput {
+    var INDEX_BUFFER = array<u32, 6>(0u, 1u, 2u, 1u, 3u, 2u);
+    var VERTEX_BUFFER = array<vec2<f32>, 4>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(-1.0, 1.0),
+        vec2<f32>(1.0, 1.0),
+    );
+
+    let vertex = VERTEX_BUFFER[INDEX_BUFFER[vertex_idx]];
+
+    // The texture coordinates start at the top-left corner.
+    let uv = vec2<f32>(vertex.x + 1.0, 1.0 - vertex.y) * 0.5;
+    return VertexOutput(vec4<f32>(vertex, 0.0, 1.0), uv);
+}
+
+fn sample_gradient(uv: vec2<f32>) -> vec4<f32> {
+    // Projects the position onto the direction, such that the gradient
+    // spans the two opposite corners of the layer.
+    let direction = config.direction;
+    let extent = abs(direction.x) + abs(direction.y);
+    let t = clamp(dot(uv - 0.5, direction) / extent + 0.5, 0.0, 1.0);
+
+    let num_samples = textureDimensions(gradient).x;
+    let sample_position = t * f32(num_samples - 1u);
+    let sample_1 = textureLoad(gradient, vec2(i32(floor(sample_position)), 0), 0);
+    let sample_2 = textureLoad(gradient, vec2(i32(ceil(sample_position)), 0), 0);
+    let color = mix(sample_1, sample_2, fract(sample_position));
+
+    return vec4<f32>(encode_output(XYZ_SRGB_CONVERSION_MATRIX * color.rgb), color.a);
+}
+
+fn sample_image(uv: vec2<f32>) -> vec4<f32> {
+    let color = textureSample(image, image_sampler, uv);
+    return vec4<f32>(srgb_to_output(color.rgb), color.a);
+}
+
+@fragment
+fn fragment_main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    var color: vec4<f32>;
+    if config.gradient != 0u {
+        color = sample_gradient(uv);
+    } else {
+        color = sample_image(uv);
+    }
+
+    let alpha = color.a * config.opacity;
+    return vec4<f32>(color.rgb * alpha, alpha);
+}
\ No newline at end of file
@@ -1,6 +1,7 @@
 struct ColorBarBounds {
     start: f32,
     end: f32,
+    horizontal: u32,
 }
 
 @group(0) @binding(0)
@@ -23,19 +24,11 @@ const OUT_OF_BOUNDS_FACTOR = 0.4;
 //     vec2<f32>(1.0, 1.0),
 // );
 
-const XYZ_SRGB_CONVERSION_MATRIX = mat3x3<f32>(
-    vec3<f32>(3.240812398895283, -0.9692430170086407, 0.055638398436112804),
-    vec3<f32>(-1.5373084456298136, 1.8759663029085742, -0.20400746093241362),
-    vec3<f32>(-0.4985865229069666, 0.04155503085668564, 1.0571295702861434),
-);
+#include <color_conversion>
 
 fn xyz_to_srgb(color: vec3<f32>) -> vec3<f32> {
     let linear_srgb = XYZ_SRGB_CONVERSION_MATRIX * color.xyz;
-    let a = 12.92 * linear_srgb;
-    let b = 1.055 * pow(linear_srgb, vec3<f32>(1.0 / 2.4)) - 0.055;
-    let c = step(vec3<f32>(0.0031308), linear_srgb);
-    let srgb = mix(a, b, c);
-    return srgb;
+    return encode_output(linear_srgb);
 }
 
 @vertex
@@ -52,7 +45,9 @@ fn vertex_main(@builtin(vertex_index) vertex_idx: u32) -> VertexOutput {
     let vertex = VERTEX_BUFFER[index];
 
     let position = vec4<f32>(vertex, 0.0, 1.0);
-    let color_scale_value = select(0.0, 1.0, index >= 2u);
+    // Vertical bars grow upwards, horizontal bars to the right.
+    let is_end = select(index >= 2u, index % 2u == 1u, color_scale_bounds.horizontal != 0u);
+    let color_scale_value = select(0.0, 1.0, is_end);
     return VertexOutput(position, color_scale_value);
 }
 
@@ -1,3 +1,5 @@
+#include <constants>
+
 struct ScaleElement {
     t: f32,
     // padding: 12 bytes
@@ -10,7 +12,7 @@ var color_scale: texture_storage_2d<rgba32float, write>;
 @group(0) @binding(1)
 var<storage> scale: array<ScaleElement>;
 
-@compute @workgroup_size(64)
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(
     @builtin(global_invocation_id) global_id: vec3<u32>
 ) {
@@ -1,3 +1,5 @@
+#include <constants>
+
 @group(0) @binding(0)
 var color_scale: texture_2d<f32>;
 
@@ -8,10 +10,12 @@ var color_scale_transformed: texture_storage_2d<rgba32float, write>;
 // 1 = Xyz
 // 2 = CieLab
 // 3 = CieLch
+// 4 = Oklab
+// 5 = Oklch
 @group(0) @binding(2)
 var<uniform> color_space: u32;
 
-@compute @workgroup_size(64)
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(
     @builtin(global_invocation_id) global_id: vec3<u32>
 ) {
@@ -32,9 +36,13 @@ fn main(
         transformed = cie_lab_to_xyz(sample);
     } else if color_space == 3u {
         transformed = cie_lch_to_xyz(sample);
+    } else if color_space == 4u {
+        transformed = oklab_to_xyz(sample);
+    } else if color_space == 5u {
+        transformed = oklch_to_xyz(sample);
     }
 
-    textureStore(color_scale_transformed, sample_idx, sample);
+    textureStore(color_scale_transformed, sample_idx, transformed);
 }
 
 const SRGB_XYZ_CONVERSION_MATRIX = mat3x3<f32>(
@@ -91,3 +99,36 @@ fn cie_lch_to_xyz(lcha: vec4<f32>) -> vec4<f32> {
 
     return cie_lab_to_xyz(laba);
 }
+
+const OKLAB_LMS_CONVERSION_MATRIX = mat3x3<f32>(
+    vec3<f32>(1.0, 1.0, 1.0),
+    vec3<f32>(0.3963377774, -0.1055613458, -0.0894841775),
+    vec3<f32>(0.2158037573, -0.0638541728, -1.2914855480),
+);
+
+const LMS_XYZ_CONVERSION_MATRIX = mat3x3<f32>(
+    vec3<f32>(1.2270138511, -0.0405801784, -0.0763812845),
+    vec3<f32>(-0.5577999807, 1.1122568696, -0.4214819784),
+    vec3<f32>(0.2812561490, -0.0716766787, 1.5861632204),
+);
+
+fn oklab_to_xyz(laba: vec4<f32>) -> vec4<f32> {
+    let lms = pow(OKLAB_LMS_CONVERSION_MATRIX * laba.rgb, vec3(3.0));
+    let xyz = LMS_XYZ_CONVERSION_MATRIX * lms;
+
+    return vec4(xyz, laba.a);
+}
+
+fn oklch_to_xyz(lcha: vec4<f32>) -> vec4<f32> {
+    let l = lcha.r;
+    let c = lcha.g;
+    let h = lcha.b;
+
+    let h_rad = radians(h);
+
+    let a = c * cos(h_rad);
+    let b = c * sin(h_rad);
+    let laba = vec4<f32>(l, a, b, lcha.a);
+
+    return oklab_to_xyz(laba);
+}
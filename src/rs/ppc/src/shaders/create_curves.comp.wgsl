@@ -1,8 +1,6 @@
-struct CurveLineInfo {
-    x_t_values: vec2<f32>,
-    y_t_values: vec2<f32>,
-    axis: u32,
-}
+#include <constants>
+
+#include <curve_line_info>
 
 @group(0) @binding(0)
 var<storage, read_write> output: array<CurveLineInfo>;
@@ -10,7 +8,7 @@ var<storage, read_write> output: array<CurveLineInfo>;
 @group(0) @binding(1)
 var probability_curves: texture_2d_array<f32>;
 
-@compute @workgroup_size(64)
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(
     @builtin(global_invocation_id) global_id: vec3<u32>
 ) {
@@ -0,0 +1,117 @@
+#include <matrices>
+
+struct Config {
+    label_a: u32,
+    label_b: u32,
+    curve_t_range: vec2<f32>,
+}
+
+#include <axes>
+
+#include <label_color>
+
+@group(0) @binding(0)
+var<uniform> matrices: Matrices;
+
+@group(0) @binding(1)
+var<uniform> config: Config;
+
+@group(0) @binding(2)
+var<storage, read> axes: array<Axes>;
+
+@group(0) @binding(3)
+var<storage, read> colors: array<LabelColor>;
+
+@group(0) @binding(4)
+var curves_a: texture_2d_array<f32>;
+
+@group(0) @binding(5)
+var curves_b: texture_2d_array<f32>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) curve_t: vec2<f32>,
+    @location(1) @interpolate(flat) axis_idx: u32,
+}
+
+// Minimal difference between the probabilities, for the curves to be considered divergent.
+const MIN_DIVERGENCE: f32 = 0.01;
+const HATCH_WIDTH: f32 = 6.0;
+
+#include <color_conversion>
+
+fn xyz_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let linear_srgb = XYZ_SRGB_CONVERSION_MATRIX * color.xyz;
+    return encode_output(linear_srgb);
+}
+
+fn sample_curve(curves: texture_2d_array<f32>, axis_idx: u32, curve_pos: f32) -> f32 {
+    let num_samples = textureDimensions(curves).x;
+    let sample_position = curve_pos * f32(num_samples - 1u);
+    let sample_1_pos = i32(floor(sample_position));
+    let sample_2_pos = i32(ceil(sample_position));
+    let t = fract(sample_position);
+
+    let sample_1 = textureLoad(curves, vec2(sample_1_pos, 0), i32(axis_idx), 0).r;
+    let sample_2 = textureLoad(curves, vec2(sample_2_pos, 0), i32(axis_idx), 0).r;
+    return mix(sample_1, sample_2, t);
+}
+
+@vertex
+fn vertex_main(
+    @builtin(vertex_index) vertex_idx: u32,
+    @builtin(instance_index) instance_idx: u32,
+) -> VertexOutput {
+    var INDEX_BUFFER = array<u32, 6>(0u, 1u, 2u, 1u, 3u, 2u);
+
+    let index = INDEX_BUFFER[vertex_idx];
+    let axis = axes[instance_idx];
+
+    // Covers the area of the axis in which the curves are drawn.
+    let left_vertex = index % 2u == 0u;
+    let top_vertex = index > 1u;
+    let curve_t = vec2<f32>(
+        select(config.curve_t_range.x, config.curve_t_range.y, left_vertex),
+        select(0.0, 1.0, top_vertex),
+    );
+
+    let x = mix(axis.center_x, axis.position_x.x, curve_t.x);
+    let y = mix(axis.range_y.x, axis.range_y.y, curve_t.y);
+    let pos = matrices.p_matrix * matrices.mv_matrix * vec4<f32>(x, y, 0.0, 1.0);
+
+    return VertexOutput(pos, curve_t, instance_idx);
+}
+
+@fragment
+fn fragment_main(
+    @builtin(position) position: vec4<f32>,
+    @location(0) curve_t: vec2<f32>,
+    @location(1) @interpolate(flat) axis_idx: u32,
+) -> @location(0) vec4<f32> {
+    let num_curves = min(textureNumLayers(curves_a), textureNumLayers(curves_b));
+    if axis_idx >= num_curves || axes[axis_idx].expanded_val < curve_t.x {
+        discard;
+    }
+
+    let probability_a = sample_curve(curves_a, axis_idx, curve_t.y);
+    let probability_b = sample_curve(curves_b, axis_idx, curve_t.y);
+    if abs(probability_a - probability_b) < MIN_DIVERGENCE {
+        discard;
+    }
+
+    // Shade the area enclosed by the two curves.
+    let x_a = mix(config.curve_t_range.x, config.curve_t_range.y, probability_a);
+    let x_b = mix(config.curve_t_range.x, config.curve_t_range.y, probability_b);
+    if curve_t.x < min(x_a, x_b) || curve_t.x > max(x_a, x_b) {
+        discard;
+    }
+
+    // The area is drawn in the color of the label with the higher probability,
+    // and hatched, such that the underlying curves remain visible.
+    let label = select(config.label_b, config.label_a, probability_a > probability_b);
+    let hatched = fract((position.x + position.y) / (2.0 * HATCH_WIDTH)) < 0.5;
+    let alpha = select(0.15, 0.5, hatched);
+
+    let color = xyz_to_srgb(colors[label].color_high.rgb);
+    return vec4<f32>(color * alpha, alpha);
+}
@@ -3,23 +3,13 @@ struct Config {
     color: vec3<f32>,
 }
 
-struct Matrices {
-    mv_matrix: mat4x4<f32>,
-    p_matrix: mat4x4<f32>,
-}
+#include <matrices>
 
-struct Axes {
-    expanded_val: f32,
-    center_x: f32,
-    position_x: vec2<f32>,
-    range_y: vec2<f32>,
-}
+#include <axes>
 
-struct CurveLineInfo {
-    x_t_values: vec2<f32>,
-    y_t_values: vec2<f32>,
-    axis: u32,
-}
+#include <curve_line_info>
+
+#include <color_conversion>
 
 @group(0) @binding(0)
 var<uniform> matrices: Matrices;
@@ -39,8 +29,7 @@ struct VertexOutput {
     @location(1) @interpolate(flat) discard_line: u32,
 }
 
-const FEATHER: f32 = 0.5;
-const ONE_MINUS_FEATHER: f32 = 1.0 - FEATHER;
+#include <line_coverage>
 
 @vertex
 fn vertex_main(
@@ -96,16 +85,8 @@ fn fragment_main(
         discard;
     }
 
-    let distance = length(normal);
-    var alpha = 0.0;
-
-    if distance <= ONE_MINUS_FEATHER {
-        alpha = 1.0;
-    } else if distance <= 1.0 {
-        let t = (distance - FEATHER) / ONE_MINUS_FEATHER;
-        alpha = mix(1.0, 0.0, t);
-    }
+    let alpha = line_coverage(normal);
 
-    let color = config.color;
+    let color = srgb_to_output(config.color);
     return vec4<f32>(color * alpha, alpha);
 }
\ No newline at end of file
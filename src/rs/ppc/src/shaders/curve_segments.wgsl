@@ -1,7 +1,4 @@
-struct Matrices {
-    mv_matrix: mat4x4<f32>,
-    p_matrix: mat4x4<f32>,
-}
+#include <matrices>
 
 struct Config {
     label: u32,
@@ -9,23 +6,11 @@ struct Config {
     min_curve_t: f32,
 }
 
-struct Axes {
-    expanded_val: f32,
-    center_x: f32,
-    position_x: vec2<f32>,
-    range_y: vec2<f32>,
-}
+#include <axes>
 
-struct LabelColor {
-    color_high: vec4<f32>,
-    color_low: vec4<f32>,
-}
+#include <label_color>
 
-struct CurveLineInfo {
-    x_t_values: vec2<f32>,
-    y_t_values: vec2<f32>,
-    axis: u32,
-}
+#include <curve_line_info>
 
 @group(0) @binding(0)
 var<uniform> matrices: Matrices;
@@ -47,19 +32,11 @@ struct VertexOutput {
     @location(0) @interpolate(flat) discard_segment: u32,
 }
 
-const XYZ_SRGB_CONVERSION_MATRIX = mat3x3<f32>(
-    vec3<f32>(3.240812398895283, -0.9692430170086407, 0.055638398436112804),
-    vec3<f32>(-1.5373084456298136, 1.8759663029085742, -0.20400746093241362),
-    vec3<f32>(-0.4985865229069666, 0.04155503085668564, 1.0571295702861434),
-);
+#include <color_conversion>
 
 fn xyz_to_srgb(color: vec3<f32>) -> vec3<f32> {
     let linear_srgb = XYZ_SRGB_CONVERSION_MATRIX * color.xyz;
-    let a = 12.92 * linear_srgb;
-    let b = 1.055 * pow(linear_srgb, vec3<f32>(1.0 / 2.4)) - 0.055;
-    let c = step(vec3<f32>(0.0031308), linear_srgb);
-    let srgb = mix(a, b, c);
-    return srgb;
+    return encode_output(linear_srgb);
 }
 
 @vertex
@@ -1,22 +1,17 @@
-struct Matrices {
-    mv_matrix: mat4x4<f32>,
-    p_matrix: mat4x4<f32>,
-}
+#include <constants>
+
+#include <matrices>
 
 struct Config {
     line_width: vec2<f32>,
     selection_bounds: vec2<f32>,
     color_probabilities: u32,
     render_order: u32,
+    line_join: u32,
     unselected_color: vec4<f32>,
 }
 
-struct Axes {
-    expanded_val: f32,
-    center_x: f32,
-    position_x: vec2<f32>,
-    range_y: vec2<f32>,
-}
+#include <axes>
 
 struct DataLine {
     curve_idx: u32,
@@ -52,36 +47,65 @@ struct VertexOutput {
     @location(0) normal: vec2<f32>,
     @location(1) @interpolate(flat) discard_value: u32,
     @location(2) @interpolate(flat) instance_idx: u32,
+    @location(3) @interpolate(flat) half_length: f32,
 }
 
-const FEATHER: f32 = 0.5;
-const ONE_MINUS_FEATHER: f32 = 1.0 - FEATHER;
-
-fn get_line_alpha(normal: vec2<f32>) -> f32 {
-    let distance = length(normal);
-    if distance <= ONE_MINUS_FEATHER {
-        return 1.0;
-    } else if distance <= 1.0 {
-        let t = (distance - FEATHER) / ONE_MINUS_FEATHER;
-        return mix(1.0, 0.0, t);
-    }
+// Maximum length of a miter, relative to the width of the line.
+const MITER_LIMIT: f32 = 4.0;
 
-    return 0.0;
-}
+#include <line_coverage>
 
-const XYZ_SRGB_CONVERSION_MATRIX = mat3x3<f32>(
-    vec3<f32>(3.240812398895283, -0.9692430170086407, 0.055638398436112804),
-    vec3<f32>(-1.5373084456298136, 1.8759663029085742, -0.20400746093241362),
-    vec3<f32>(-0.4985865229069666, 0.04155503085668564, 1.0571295702861434),
-);
+#include <color_conversion>
 
 fn xyz_to_srgb(color: vec3<f32>) -> vec3<f32> {
     let linear_srgb = XYZ_SRGB_CONVERSION_MATRIX * color.xyz;
-    let a = 12.92 * linear_srgb;
-    let b = 1.055 * pow(linear_srgb, vec3<f32>(1.0 / 2.4)) - 0.055;
-    let c = step(vec3<f32>(0.0031308), linear_srgb);
-    let srgb = mix(a, b, c);
-    return srgb;
+    return encode_output(linear_srgb);
+}
+
+struct LinePoints {
+    start: vec2<f32>,
+    end: vec2<f32>,
+}
+
+fn line_points(value: DataLine) -> LinePoints {
+    let start_axis = axes[value.start_axis];
+    let end_axis = axes[value.end_axis];
+
+    let start_x = mix(start_axis.center_x, start_axis.position_x.y, start_axis.expanded_val);
+    let end_x = mix(end_axis.center_x, end_axis.position_x.x, end_axis.expanded_val);
+
+    let line_start = vec2<f32>(start_x, mix(start_axis.range_y.x, start_axis.range_y.y, value.start_value));
+    let line_end = vec2<f32>(end_x, mix(end_axis.range_y.x, end_axis.range_y.y, value.end_value));
+    return LinePoints(line_start, line_end);
+}
+
+// Returns the direction of the line in units of its width, in which the line is isotropic.
+fn line_direction(points: LinePoints) -> vec2<f32> {
+    return normalize((points.end - points.start) / config.line_width);
+}
+
+// Returns the direction of the segment of the same curve, which shares the
+// start or end vertex with the segment, or `direction` if there is none.
+//
+// The segments of a curve are stored consecutively, and only share their
+// vertices at the collapsed axes.
+fn adjacent_direction(instance_idx: u32, at_end: bool, direction: vec2<f32>) -> vec2<f32> {
+    if (!at_end && instance_idx == 0u) || (at_end && instance_idx + 1u >= arrayLength(&values)) {
+        return direction;
+    }
+
+    let value = values[instance_idx];
+    let adjacent = values[select(instance_idx - 1u, instance_idx + 1u, at_end)];
+    let shared_axis = select(value.start_axis, value.end_axis, at_end);
+    let adjacent_axis = select(adjacent.end_axis, adjacent.start_axis, at_end);
+    let is_joined = adjacent.curve_idx == value.curve_idx
+        && adjacent_axis == shared_axis
+        && axes[shared_axis].expanded_val == 0.0;
+    if !is_joined {
+        return direction;
+    }
+
+    return line_direction(line_points(adjacent));
 }
 
 @vertex
@@ -102,14 +126,9 @@ fn vertex_main(
     let color_value = color_values[value.curve_idx];
     let probability = probabilities[value.curve_idx];
 
-    let start_axis = axes[value.start_axis];
-    let end_axis = axes[value.end_axis];
-
-    let start_x = mix(start_axis.center_x, start_axis.position_x.y, start_axis.expanded_val);
-    let end_x = mix(end_axis.center_x, end_axis.position_x.x, end_axis.expanded_val);
-
-    let line_start = vec2<f32>(start_x, mix(start_axis.range_y.x, start_axis.range_y.y, value.start_value));
-    let line_end = vec2<f32>(end_x, mix(end_axis.range_y.x, end_axis.range_y.y, value.end_value));
+    let points = line_points(value);
+    let line_start = points.start;
+    let line_end = points.end;
 
     let discard_value = 0u;
 
@@ -126,34 +145,61 @@ fn vertex_main(
     let vertex_normal = rotation_matrix * VERTEX_NORMALS_BUFFER[index];
     let vertex_pos = select(line_start, line_end, vec2<bool>(index <= 1u));
 
-    let delta = matrices.mv_matrix * vec4<f32>(vertex_normal * config.line_width, 0.0, 0.0);
+    // The normal contains the distance along the line, which is only used by
+    // the round joins, and the distance across it.
+    let at_end = index <= 1u;
+    let across = VERTEX_NORMALS_BUFFER[index].y;
+    var normal = vec2<f32>(0.0, across);
+    var half_length = 0.0;
+    var offset = vertex_normal * config.line_width;
+
+    if config.line_join != LINE_JOIN_BUTT {
+        let direction = line_direction(points);
+        let perpendicular = vec2<f32>(-direction.y, direction.x);
+
+        if config.line_join == LINE_JOIN_ROUND {
+            // Extend both ends by a round cap, which covers the gap to the adjacent segments.
+            let along = select(-1.0, 1.0, at_end);
+            half_length = length((line_end - line_start) / config.line_width) * 0.5;
+            normal.x = along * (half_length + 1.0);
+            offset = ((perpendicular * across) + (direction * along)) * config.line_width;
+        } else {
+            let adjacent = adjacent_direction(instance_idx, at_end, direction);
+            let miter_sum = perpendicular + vec2<f32>(-adjacent.y, adjacent.x);
+            let miter = select(perpendicular, normalize(miter_sum), dot(miter_sum, miter_sum) > 0.0001);
+            let miter_length = min(1.0 / max(dot(miter, perpendicular), 0.0001), MITER_LIMIT);
+            offset = miter * (across * miter_length) * config.line_width;
+        }
+    }
+
+    let delta = matrices.mv_matrix * vec4<f32>(offset, 0.0, 0.0);
     let pos = matrices.mv_matrix * vec4<f32>(vertex_pos, 0.0, 1.0);
     var offset_position = matrices.p_matrix * (pos + delta);
 
     let order_by = select(color_value, probability, config.color_probabilities == 1u);
     switch config.render_order {
-        case 0u, default {
+        case ORDER_UNORDERED, default {
             offset_position.z = 0.0;
         }
-        case 1u {
+        case ORDER_PROBABILITY {
             offset_position.z = 1.0 - probability;
         }
-        case 2u {
+        case ORDER_PROBABILITY_INVERTED {
             offset_position.z = probability;
         }
-        case 3u {
+        case ORDER_SELECTED_UNORDERED {
             let sample_in_bounds_0 = config.selection_bounds.x <= probability;
             let sample_in_bounds_1 = probability <= config.selection_bounds.y;
             let sample_in_bounds = sample_in_bounds_0 && sample_in_bounds_1;
             offset_position.z = select(1.0, 0.0, sample_in_bounds);
         }
-        case 4u {
+        case ORDER_SELECTED_PROBABILITY {
             let sample_in_bounds_0 = config.selection_bounds.x <= probability;
             let sample_in_bounds_1 = probability <= config.selection_bounds.y;
             let sample_in_bounds = sample_in_bounds_0 && sample_in_bounds_1;
             offset_position.z = select(1.0, 1.0 - (order_by * 0.5), sample_in_bounds);
         }
-        case 5u {
+        case ORDER_SELECTED_PROBABILITY_INVERTED {
             let sample_in_bounds_0 = config.selection_bounds.x <= probability;
             let sample_in_bounds_1 = probability <= config.selection_bounds.y;
             let sample_in_bounds = sample_in_bounds_0 && sample_in_bounds_1;
@@ -161,20 +207,23 @@ fn vertex_main(
         }
     }
 
-    return VertexOutput(offset_position, vertex_normal, discard_value, value.curve_idx);
+    return VertexOutput(offset_position, normal, discard_value, value.curve_idx, half_length);
 }
 
 @fragment
 fn fragment_main(
     @location(0) normal: vec2<f32>,
     @location(1) @interpolate(flat) discard_value: u32,
-    @location(2) @interpolate(flat) instance_idx: u32
+    @location(2) @interpolate(flat) instance_idx: u32,
+    @location(3) @interpolate(flat) half_length: f32,
 ) -> @location(0) vec4<f32> {
     if discard_value != 0u {
         discard;
     }
 
-    let alpha = get_line_alpha(normal);
+    // Only the round caps extend beyond the vertices of the line.
+    let beyond = max(abs(normal.x) - half_length, 0.0);
+    let alpha = line_coverage(vec2<f32>(beyond, normal.y));
 
     let color_value = color_values[instance_idx];
     let probability = probabilities[instance_idx];
@@ -0,0 +1,68 @@
+#include <constants>
+#include <missing_values>
+
+struct Config {
+    color: vec4<f32>,
+    selected_color: vec4<f32>,
+    line_width: vec2<f32>,
+    selection_bounds: vec2<f32>,
+    num_datums: u32,
+    num_axes: u32,
+    bandwidth: f32,
+    width: f32,
+    use_selection: u32,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> densities: array<f32>;
+
+@group(0) @binding(1)
+var<storage, read> data: array<f32>;
+
+@group(0) @binding(2)
+var<storage, read> probabilities: array<f32>;
+
+@group(0) @binding(3)
+var<storage, read> visible_ranges: array<vec2<f32>>;
+
+@group(0) @binding(4)
+var<uniform> config: Config;
+
+// Estimates the density at one sample of the visible range of an axis, one axis per
+// workgroup row. The densities of all datums precede the densities of the selected datums.
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let sample = global_id.x;
+    let axis = global_id.y;
+    if sample >= DENSITY_CURVE_SAMPLES || axis >= config.num_axes {
+        return;
+    }
+
+    // The bandwidth is relative to the visible range.
+    let range = visible_ranges[axis];
+    let position = mix(range.x, range.y, f32(sample) / f32(DENSITY_CURVE_SAMPLES - 1u));
+    let bandwidth = config.bandwidth * (range.y - range.x);
+
+    var density = 0.0;
+    var selected_density = 0.0;
+    for (var i = 0u; i < config.num_datums; i++) {
+        let value = data[axis * config.num_datums + i];
+        if is_missing(value) {
+            continue;
+        }
+
+        let z = (value - position) / bandwidth;
+        let weight = exp(-0.5 * z * z);
+        density += weight;
+
+        if config.use_selection != 0u {
+            let probability = probabilities[i];
+            let selected = probability >= config.selection_bounds.x
+                && probability <= config.selection_bounds.y;
+            selected_density += select(0.0, weight, selected);
+        }
+    }
+
+    densities[axis * DENSITY_CURVE_SAMPLES + sample] = density;
+    densities[(config.num_axes + axis) * DENSITY_CURVE_SAMPLES + sample] = selected_density;
+}
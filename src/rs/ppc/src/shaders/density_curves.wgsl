@@ -0,0 +1,120 @@
+#include <constants>
+#include <matrices>
+#include <axes>
+
+struct Config {
+    color: vec4<f32>,
+    selected_color: vec4<f32>,
+    line_width: vec2<f32>,
+    selection_bounds: vec2<f32>,
+    num_datums: u32,
+    num_axes: u32,
+    bandwidth: f32,
+    width: f32,
+    use_selection: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> matrices: Matrices;
+
+@group(0) @binding(1)
+var<uniform> config: Config;
+
+@group(0) @binding(2)
+var<storage, read> axes: array<Axes>;
+
+@group(0) @binding(3)
+var<storage, read> densities: array<f32>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) normal: vec2<f32>,
+    @location(1) @interpolate(flat) color: vec4<f32>,
+}
+
+#include <color_conversion>
+
+fn xyz_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let linear_srgb = XYZ_SRGB_CONVERSION_MATRIX * color.xyz;
+    return encode_output(linear_srgb);
+}
+
+#include <line_coverage>
+
+@vertex
+fn vertex_main(
+    @builtin(vertex_index) vertex_idx: u32,
+    @builtin(instance_index) instance_idx: u32,
+) -> VertexOutput {
+    var INDEX_BUFFER = array<u32, 6>(0u, 1u, 2u, 1u, 3u, 2u);
+    var VERTEX_NORMALS_BUFFER = array<vec2<f32>, 4>(
+        vec2<f32>(0.0, -1.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(0.0, -1.0),
+        vec2<f32>(0.0, 1.0),
+    );
+
+    // Each instance draws one segment of a curve. The curves of all datums
+    // precede the curves of the selected datums.
+    let num_segments = DENSITY_CURVE_SAMPLES - 1u;
+    let curve_idx = instance_idx / num_segments;
+    let segment = instance_idx % num_segments;
+    let axis_idx = curve_idx % config.num_axes;
+    let selected = curve_idx >= config.num_axes;
+
+    // Both curves are scaled by the maximum density of all datums, such
+    // that the selected curve shows the share of the selected datums.
+    var max_density = 0.0;
+    for (var i = 0u; i < DENSITY_CURVE_SAMPLES; i++) {
+        max_density = max(max_density, densities[axis_idx * DENSITY_CURVE_SAMPLES + i]);
+    }
+
+    // Skipped curves collapse into degenerate triangles, which are not rasterized.
+    if max_density == 0.0 || (selected && config.use_selection == 0u) {
+        return VertexOutput(vec4<f32>(0.0, 0.0, 0.0, 1.0), vec2<f32>(0.0), vec4<f32>(0.0));
+    }
+
+    let offset = curve_idx * DENSITY_CURVE_SAMPLES + segment;
+    let values = vec2<f32>(densities[offset], densities[offset + 1u]) / max_density;
+
+    // The curves extend to the left of the axis line.
+    let axis = axes[axis_idx];
+    let x_positions = vec2<f32>(axis.center_x) - values * config.width;
+    let y_t_values = vec2<f32>(f32(segment), f32(segment + 1u)) / f32(num_segments);
+    let y_positions = mix(vec2(axis.range_y.x), vec2(axis.range_y.y), y_t_values);
+
+    let line_start = vec2<f32>(x_positions.x, y_positions.x);
+    let line_end = vec2<f32>(x_positions.y, y_positions.y);
+
+    let line_vector = normalize(line_end - line_start);
+    let line_unit_cos = line_vector.x;
+    let line_unit_sin = line_vector.y;
+
+    let rotation_matrix = mat2x2<f32>(
+        line_unit_cos,
+        line_unit_sin,    // column 1: [cos theta, sin theta]
+        -line_unit_sin,
+        line_unit_cos,   // column 2: [-sin theta, cos theta]
+    );
+    let index = INDEX_BUFFER[vertex_idx];
+    let vertex_normal = rotation_matrix * VERTEX_NORMALS_BUFFER[index];
+    let vertex_pos = select(line_start, line_end, vec2<bool>(index <= 1u));
+
+    let delta = matrices.mv_matrix * vec4<f32>(vertex_normal * config.line_width, 0.0, 0.0);
+    let pos = matrices.mv_matrix * vec4<f32>(vertex_pos, 0.0, 1.0);
+    let offset_position = matrices.p_matrix * (pos + delta);
+
+    let color = select(config.color, config.selected_color, selected);
+    return VertexOutput(offset_position, vertex_normal, color);
+}
+
+@fragment
+fn fragment_main(
+    @location(0) normal: vec2<f32>,
+    @location(1) @interpolate(flat) color: vec4<f32>,
+) -> @location(0) vec4<f32> {
+    var alpha = line_coverage(normal);
+
+    alpha *= color.a;
+    return vec4<f32>(xyz_to_srgb(color.rgb) * alpha, alpha);
+}
@@ -0,0 +1,116 @@
+#include <constants>
+#include <matrices>
+#include <axes>
+
+struct Config {
+    color: vec4<f32>,
+    selection_bounds: vec2<f32>,
+    num_datums: u32,
+    use_selection: u32,
+}
+
+struct FlowPair {
+    axis_a: u32,
+    axis_b: u32,
+    num_categories_a: u32,
+    num_categories_b: u32,
+    scale: f32,
+    positions_a: array<f32, MAX_FLOW_CATEGORIES>,
+    positions_b: array<f32, MAX_FLOW_CATEGORIES>,
+}
+
+@group(0) @binding(0)
+var<uniform> matrices: Matrices;
+
+@group(0) @binding(1)
+var<uniform> config: Config;
+
+@group(0) @binding(2)
+var<storage, read> axes: array<Axes>;
+
+@group(0) @binding(3)
+var<storage, read> pairs: array<FlowPair>;
+
+@group(0) @binding(4)
+var<storage, read> counts: array<u32>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+}
+
+#include <color_conversion>
+
+fn xyz_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let linear_srgb = XYZ_SRGB_CONVERSION_MATRIX * color.xyz;
+    return encode_output(linear_srgb);
+}
+
+fn flow_count(pair: u32, category_a: u32, category_b: u32) -> f32 {
+    let idx = (pair * MAX_FLOW_CATEGORIES + category_a) * MAX_FLOW_CATEGORIES + category_b;
+    return f32(counts[idx]);
+}
+
+@vertex
+fn vertex_main(
+    @builtin(vertex_index) vertex_idx: u32,
+    @builtin(instance_index) instance_idx: u32,
+) -> VertexOutput {
+    var INDEX_BUFFER = array<u32, 6>(0u, 1u, 2u, 1u, 3u, 2u);
+    var VERTEX_BUFFER = array<vec2<f32>, 4>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 1.0),
+    );
+
+    // Each instance draws the ribbon of one pair of categories.
+    let pair_idx = instance_idx / (MAX_FLOW_CATEGORIES * MAX_FLOW_CATEGORIES);
+    let category_a = (instance_idx / MAX_FLOW_CATEGORIES) % MAX_FLOW_CATEGORIES;
+    let category_b = instance_idx % MAX_FLOW_CATEGORIES;
+    let pair = pairs[pair_idx];
+
+    // Empty ribbons collapse into degenerate triangles, which are not rasterized.
+    let count = flow_count(pair_idx, category_a, category_b);
+    if count == 0.0 || category_a >= pair.num_categories_a || category_b >= pair.num_categories_b {
+        return VertexOutput(vec4<f32>(0.0, 0.0, 0.0, 1.0));
+    }
+
+    // The ribbons of a category are stacked in the order of the categories of the other axis.
+    var total_a = 0.0;
+    var total_b = 0.0;
+    var offset_a = 0.0;
+    var offset_b = 0.0;
+    for (var k = 0u; k < MAX_FLOW_CATEGORIES; k++) {
+        let count_a = flow_count(pair_idx, category_a, k);
+        let count_b = flow_count(pair_idx, k, category_b);
+        total_a += count_a;
+        total_b += count_b;
+        offset_a += select(0.0, count_a, k < category_b);
+        offset_b += select(0.0, count_b, k < category_a);
+    }
+
+    let scale = pair.scale / f32(max(config.num_datums, 1u));
+    let start_a = pair.positions_a[category_a] + (offset_a - total_a * 0.5) * scale;
+    let start_b = pair.positions_b[category_b] + (offset_b - total_b * 0.5) * scale;
+    let height = count * scale;
+
+    let segment = vertex_idx / 6u;
+    let vertex = VERTEX_BUFFER[INDEX_BUFFER[vertex_idx % 6u]];
+    let s = (f32(segment) + vertex.x) / f32(FLOW_RIBBON_SEGMENTS);
+    let t = smoothstep(0.0, 1.0, s);
+
+    let axis_a = axes[pair.axis_a];
+    let axis_b = axes[pair.axis_b];
+    let y_a = mix(axis_a.range_y.x, axis_a.range_y.y, start_a + vertex.y * height);
+    let y_b = mix(axis_b.range_y.x, axis_b.range_y.y, start_b + vertex.y * height);
+    let vertex_pos = vec2<f32>(mix(axis_a.center_x, axis_b.center_x, s), mix(y_a, y_b, t));
+
+    let pos = matrices.mv_matrix * vec4<f32>(vertex_pos, 0.0, 1.0);
+    return VertexOutput(matrices.p_matrix * pos);
+}
+
+@fragment
+fn fragment_main() -> @location(0) vec4<f32> {
+    let color = xyz_to_srgb(config.color.rgb);
+    return vec4<f32>(color * config.color.a, config.color.a);
+}
@@ -0,0 +1,74 @@
+#include <constants>
+#include <missing_values>
+
+struct Config {
+    color: vec4<f32>,
+    selected_color: vec4<f32>,
+    num_datums: u32,
+    num_axes: u32,
+    num_bins: u32,
+    width: f32,
+    use_selection: u32,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> bins: array<f32>;
+
+@group(0) @binding(1)
+var<storage, read> data: array<f32>;
+
+@group(0) @binding(2)
+var<storage, read> probabilities: array<f32>;
+
+@group(0) @binding(3)
+var<storage, read> visible_ranges: array<vec2<f32>>;
+
+@group(0) @binding(4)
+var<uniform> config: Config;
+
+// Returns the bin of the visible range containing the value, or `-1` if the
+// value is missing or lies outside of the visible range.
+fn bin_index(value: f32, range: vec2<f32>) -> i32 {
+    if is_missing(value) || range.y <= range.x {
+        return -1;
+    }
+
+    let t = (value - range.x) / (range.y - range.x);
+    if t < 0.0 || t > 1.0 {
+        return -1;
+    }
+    return i32(min(u32(t * f32(config.num_bins)), config.num_bins - 1u));
+}
+
+// Counts the datums of one bin of an axis, one axis per workgroup row. The counts of all
+// datums precede the counts of the selected datums.
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let bin = i32(global_id.x);
+    let axis = global_id.y;
+    if global_id.x >= config.num_bins || axis >= config.num_axes {
+        return;
+    }
+
+    let range = visible_ranges[axis];
+    var count = 0.0;
+    for (var i = 0u; i < config.num_datums; i++) {
+        if bin_index(data[axis * config.num_datums + i], range) == bin {
+            count += 1.0;
+        }
+    }
+
+    // The second pass shares the binning, but weights each datum by its
+    // probability of being selected by the active label.
+    var selected_count = 0.0;
+    if config.use_selection != 0u {
+        for (var i = 0u; i < config.num_datums; i++) {
+            if bin_index(data[axis * config.num_datums + i], range) == bin {
+                selected_count += probabilities[i];
+            }
+        }
+    }
+
+    bins[axis * config.num_bins + global_id.x] = count;
+    bins[(config.num_axes + axis) * config.num_bins + global_id.x] = selected_count;
+}
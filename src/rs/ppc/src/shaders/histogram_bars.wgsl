@@ -0,0 +1,87 @@
+#include <constants>
+#include <matrices>
+#include <axes>
+
+struct Config {
+    color: vec4<f32>,
+    selected_color: vec4<f32>,
+    num_datums: u32,
+    num_axes: u32,
+    num_bins: u32,
+    width: f32,
+    use_selection: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> matrices: Matrices;
+
+@group(0) @binding(1)
+var<uniform> config: Config;
+
+@group(0) @binding(2)
+var<storage, read> axes: array<Axes>;
+
+@group(0) @binding(3)
+var<storage, read> bins: array<f32>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) @interpolate(flat) color: vec4<f32>,
+}
+
+#include <color_conversion>
+
+fn xyz_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let linear_srgb = XYZ_SRGB_CONVERSION_MATRIX * color.xyz;
+    return encode_output(linear_srgb);
+}
+
+@vertex
+fn vertex_main(
+    @builtin(vertex_index) vertex_idx: u32,
+    @builtin(instance_index) instance_idx: u32,
+) -> VertexOutput {
+    var INDEX_BUFFER = array<u32, 6>(0u, 1u, 2u, 1u, 3u, 2u);
+    var VERTEX_OFFSETS_BUFFER = array<vec2<f32>, 4>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 1.0),
+    );
+
+    // Each instance draws one bar of a histogram. The histograms of all
+    // datums precede the histograms of the selected datums.
+    let histogram_idx = instance_idx / config.num_bins;
+    let bin = instance_idx % config.num_bins;
+    let axis_idx = histogram_idx % config.num_axes;
+    let selected = histogram_idx >= config.num_axes;
+
+    // Both histograms are scaled by the largest bin of all datums, such
+    // that the selected bars show the share of the selected datums.
+    var max_count = 0.0;
+    for (var i = 0u; i < config.num_bins; i++) {
+        max_count = max(max_count, bins[axis_idx * config.num_bins + i]);
+    }
+
+    // Skipped bars collapse into degenerate triangles, which are not rasterized.
+    let count = bins[histogram_idx * config.num_bins + bin];
+    if max_count == 0.0 || count == 0.0 || (selected && config.use_selection == 0u) {
+        return VertexOutput(vec4<f32>(0.0, 0.0, 0.0, 1.0), vec4<f32>(0.0));
+    }
+
+    // The bars extend to the right of the axis line.
+    let axis = axes[axis_idx];
+    let offset = VERTEX_OFFSETS_BUFFER[INDEX_BUFFER[vertex_idx]];
+    let x = axis.center_x + offset.x * (count / max_count) * config.width;
+    let y_t = (f32(bin) + offset.y) / f32(config.num_bins);
+    let y = mix(axis.range_y.x, axis.range_y.y, y_t);
+
+    let position = matrices.p_matrix * matrices.mv_matrix * vec4<f32>(x, y, 0.0, 1.0);
+    let color = select(config.color, config.selected_color, selected);
+    return VertexOutput(position, color);
+}
+
+@fragment
+fn fragment_main(@location(0) @interpolate(flat) color: vec4<f32>) -> @location(0) vec4<f32> {
+    return vec4<f32>(xyz_to_srgb(color.rgb) * color.a, color.a);
+}
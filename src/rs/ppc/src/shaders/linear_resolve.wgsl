@@ -0,0 +1,24 @@
+#include <color_conversion>
+
+@group(0) @binding(0)
+var linear_texture: texture_2d<f32>;
+
+@vertex
+fn vertex_main(@builtin(vertex_index) vertex_idx: u32) -> @builtin(position) vec4<f32> {
+    // Covers the whole viewport with a single triangle.
+    let uv = vec2<f32>(f32((vertex_idx << 1u) & 2u), f32(vertex_idx & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fragment_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let color = textureLoad(linear_texture, vec2<i32>(position.xy), 0);
+    if color.a == 0.0 {
+        return vec4<f32>(0.0);
+    }
+
+    // The colors are premultiplied, but the transfer function must be
+    // applied to the straight colors.
+    let encoded = encode_output(color.rgb / color.a);
+    return vec4<f32>(encoded * color.a, color.a);
+}
\ No newline at end of file
@@ -0,0 +1,47 @@
+#include <constants>
+#include <missing_values>
+
+struct Config {
+    num_datums: u32,
+    num_axes: u32,
+}
+
+// Mean and standard deviation of the normalized values of an axis.
+struct AxisStatistics {
+    mean: f32,
+    deviation: f32,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> scores: array<f32>;
+
+@group(0) @binding(1)
+var<storage, read> data: array<f32>;
+
+@group(0) @binding(2)
+var<storage, read> statistics: array<AxisStatistics>;
+
+@group(0) @binding(3)
+var<uniform> config: Config;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let datum_idx = global_id.x;
+    if datum_idx >= config.num_datums {
+        return;
+    }
+
+    // The score of a data point is its largest absolute z-score. Missing values
+    // and axes without a deviation are skipped.
+    var score = 0.0;
+    for (var axis_idx = 0u; axis_idx < config.num_axes; axis_idx++) {
+        let axis = statistics[axis_idx];
+        let value = data[axis_idx * config.num_datums + datum_idx];
+        if axis.deviation <= 0.0 || is_missing(value) {
+            continue;
+        }
+
+        score = max(score, abs(value - axis.mean) / axis.deviation);
+    }
+    scores[datum_idx] = score;
+}
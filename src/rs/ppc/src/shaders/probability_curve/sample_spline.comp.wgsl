@@ -1,31 +1,45 @@
+#include <constants>
+
 struct SplineSegment {
     coefficients: vec4<f32>,
     bounds: vec2<f32>,
     t_range: vec2<f32>,
 }
 
+struct SplineRange {
+    axis_idx: u32,
+    segments_start: u32,
+    segments_end: u32,
+}
+
 @group(0) @binding(0)
-var probability_curves: texture_storage_2d<r32float, write>;
+var probability_curves: texture_storage_2d_array<r32float, write>;
 
 @group(0) @binding(1)
 var<storage> spline: array<SplineSegment>;
 
-@compute @workgroup_size(64)
+@group(0) @binding(2)
+var<storage> ranges: array<SplineRange>;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(
     @builtin(global_invocation_id) global_id: vec3<u32>
 ) {
     let num_samples = u32(textureDimensions(probability_curves).x);
 
-    if global_id.x >= num_samples {
+    if global_id.x >= num_samples || global_id.y >= arrayLength(&ranges) {
         return;
     }
 
+    // Each row of workgroups samples the spline of one axis.
+    let range = ranges[global_id.y];
+
     // The position will always be in [0, 1].
     let arc_position = f32(global_id.x) / f32(num_samples);
 
     // Search for the segment that contains the position.
-    var idx = 0u;
-    while idx < arrayLength(&spline) {
+    var idx = range.segments_start;
+    while idx < range.segments_end {
         if spline[idx].bounds.x <= arc_position && spline[idx].bounds.y >= arc_position {
             break;
         }
@@ -34,7 +48,7 @@ fn main(
     }
 
     // Check that we found the right segment.
-    let segment = spline[idx];
+    let segment = spline[min(idx, range.segments_end - 1u)];
 
     let t_min = segment.t_range.x;
     let t_max = segment.t_range.y;
@@ -50,5 +64,5 @@ fn main(
 
     let texture_idx = vec2<i32>(i32(global_id.x), 0);
     let sample = vec4<f32>(value, 0.0, 0.0, 1.0);
-    textureStore(probability_curves, texture_idx, sample);
+    textureStore(probability_curves, texture_idx, i32(range.axis_idx), sample);
 }
\ No newline at end of file
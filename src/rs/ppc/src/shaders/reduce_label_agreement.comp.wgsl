@@ -0,0 +1,89 @@
+#include <constants>
+
+struct Config {
+    selection_bounds_a: vec2<f32>,
+    selection_bounds_b: vec2<f32>,
+    num_datums: u32,
+}
+
+// Partial sums of a workgroup, required for computing the agreement of two labels.
+struct AgreementSums {
+    num_intersection: f32,
+    num_union: f32,
+    sum_a: f32,
+    sum_b: f32,
+    sum_ab: f32,
+    sum_aa: f32,
+    sum_bb: f32,
+    padding: f32,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> partial_sums: array<AgreementSums>;
+
+@group(0) @binding(1)
+var<storage, read> probabilities_a: array<f32>;
+
+@group(0) @binding(2)
+var<storage, read> probabilities_b: array<f32>;
+
+@group(0) @binding(3)
+var<uniform> config: Config;
+
+var<workgroup> workgroup_sums: array<AgreementSums, WORKGROUP_SIZE>;
+
+fn add_sums(lhs: AgreementSums, rhs: AgreementSums) -> AgreementSums {
+    return AgreementSums(
+        lhs.num_intersection + rhs.num_intersection,
+        lhs.num_union + rhs.num_union,
+        lhs.sum_a + rhs.sum_a,
+        lhs.sum_b + rhs.sum_b,
+        lhs.sum_ab + rhs.sum_ab,
+        lhs.sum_aa + rhs.sum_aa,
+        lhs.sum_bb + rhs.sum_bb,
+        0.0,
+    );
+}
+
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(local_invocation_index) local_idx: u32,
+    @builtin(workgroup_id) workgroup_id: vec3<u32>,
+) {
+    var sums = AgreementSums(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+    if global_id.x < config.num_datums {
+        let a = probabilities_a[global_id.x];
+        let b = probabilities_b[global_id.x];
+        let selected_a = a >= config.selection_bounds_a.x && a <= config.selection_bounds_a.y;
+        let selected_b = b >= config.selection_bounds_b.x && b <= config.selection_bounds_b.y;
+
+        sums = AgreementSums(
+            select(0.0, 1.0, selected_a && selected_b),
+            select(0.0, 1.0, selected_a || selected_b),
+            a,
+            b,
+            a * b,
+            a * a,
+            b * b,
+            0.0,
+        );
+    }
+    workgroup_sums[local_idx] = sums;
+    workgroupBarrier();
+
+    // The workgroup size is a power of two, so the sums can be reduced pairwise.
+    for (var stride = WORKGROUP_SIZE / 2u; stride > 0u; stride /= 2u) {
+        if local_idx < stride {
+            workgroup_sums[local_idx] = add_sums(
+                workgroup_sums[local_idx],
+                workgroup_sums[local_idx + stride],
+            );
+        }
+        workgroupBarrier();
+    }
+
+    if local_idx == 0u {
+        partial_sums[workgroup_id.x] = workgroup_sums[0];
+    }
+}
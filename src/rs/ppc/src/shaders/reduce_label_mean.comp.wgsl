@@ -0,0 +1,80 @@
+#include <constants>
+#include <missing_values>
+
+struct Config {
+    selection_bounds: vec2<f32>,
+    num_datums: u32,
+    num_axes: u32,
+}
+
+// Partial sums of a workgroup, required for computing the weighted mean and
+// standard deviation of an axis.
+struct MeanSums {
+    weight: f32,
+    sum: f32,
+    sum_squares: f32,
+    padding: f32,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> partial_sums: array<MeanSums>;
+
+@group(0) @binding(1)
+var<storage, read> data: array<f32>;
+
+@group(0) @binding(2)
+var<storage, read> probabilities: array<f32>;
+
+@group(0) @binding(3)
+var<uniform> config: Config;
+
+var<workgroup> workgroup_sums: array<MeanSums, WORKGROUP_SIZE>;
+
+fn add_sums(lhs: MeanSums, rhs: MeanSums) -> MeanSums {
+    return MeanSums(
+        lhs.weight + rhs.weight,
+        lhs.sum + rhs.sum,
+        lhs.sum_squares + rhs.sum_squares,
+        0.0,
+    );
+}
+
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(local_invocation_index) local_idx: u32,
+    @builtin(workgroup_id) workgroup_id: vec3<u32>,
+    @builtin(num_workgroups) num_workgroups: vec3<u32>,
+) {
+    // Each row of workgroups reduces the column of one axis. The selected
+    // values are weighted by their probability, while missing values are
+    // skipped.
+    let axis_idx = global_id.y;
+    var sums = MeanSums(0.0, 0.0, 0.0, 0.0);
+    if global_id.x < config.num_datums && axis_idx < config.num_axes {
+        let value = data[axis_idx * config.num_datums + global_id.x];
+        let probability = probabilities[global_id.x];
+        let is_selected = probability >= config.selection_bounds.x
+            && probability <= config.selection_bounds.y;
+        if is_selected && !is_missing(value) {
+            sums = MeanSums(probability, probability * value, probability * value * value, 0.0);
+        }
+    }
+    workgroup_sums[local_idx] = sums;
+    workgroupBarrier();
+
+    // The workgroup size is a power of two, so the sums can be reduced pairwise.
+    for (var stride = WORKGROUP_SIZE / 2u; stride > 0u; stride /= 2u) {
+        if local_idx < stride {
+            workgroup_sums[local_idx] = add_sums(
+                workgroup_sums[local_idx],
+                workgroup_sums[local_idx + stride],
+            );
+        }
+        workgroupBarrier();
+    }
+
+    if local_idx == 0u && axis_idx < config.num_axes {
+        partial_sums[axis_idx * num_workgroups.x + workgroup_id.x] = workgroup_sums[0];
+    }
+}
@@ -1,3 +1,5 @@
+#include <constants>
+
 @group(0) @binding(0)
 var<storage, read_write> output: array<f32>;
 
@@ -7,7 +9,7 @@ var<storage, read> input: array<f32>;
 @group(0) @binding(2)
 var<uniform> num_datums: u32;
 
-@compute @workgroup_size(64)
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(
     @builtin(global_invocation_id) global_id: vec3<u32>
 ) {
@@ -0,0 +1,57 @@
+#include <constants>
+#include <missing_values>
+
+struct Config {
+    selection_bounds: vec2<f32>,
+    num_datums: u32,
+    num_axes: u32,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> ranges: array<atomic<u32>>;
+
+@group(0) @binding(1)
+var<storage, read> data: array<f32>;
+
+@group(0) @binding(2)
+var<storage, read> probabilities: array<f32>;
+
+@group(0) @binding(3)
+var<uniform> config: Config;
+
+var<workgroup> workgroup_min: atomic<u32>;
+var<workgroup> workgroup_max: atomic<u32>;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(local_invocation_index) local_idx: u32,
+) {
+    if local_idx == 0u {
+        atomicStore(&workgroup_min, 0xFFFFFFFFu);
+        atomicStore(&workgroup_max, 0u);
+    }
+    workgroupBarrier();
+
+    // Each row of workgroups reduces the column of one axis. The normalized
+    // values are positive, so their bit patterns are ordered like the values.
+    // Missing values are skipped.
+    let axis_idx = global_id.y;
+    if global_id.x < config.num_datums && axis_idx < config.num_axes {
+        let value = data[axis_idx * config.num_datums + global_id.x];
+        let probability = probabilities[global_id.x];
+        let is_selected = probability >= config.selection_bounds.x
+            && probability <= config.selection_bounds.y;
+        if is_selected && !is_missing(value) {
+            atomicMin(&workgroup_min, bitcast<u32>(value));
+            atomicMax(&workgroup_max, bitcast<u32>(value));
+        }
+    }
+    workgroupBarrier();
+
+    // Only one invocation of each workgroup accesses the global ranges.
+    if local_idx == 0u && axis_idx < config.num_axes {
+        atomicMin(&ranges[2u * axis_idx], atomicLoad(&workgroup_min));
+        atomicMax(&ranges[2u * axis_idx + 1u], atomicLoad(&workgroup_max));
+    }
+}
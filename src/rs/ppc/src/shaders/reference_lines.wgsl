@@ -0,0 +1,97 @@
+#include <matrices>
+
+struct Config {
+    line_width: vec2<f32>,
+    color: vec4<f32>,
+}
+
+#include <axes>
+
+struct DataLine {
+    curve_idx: u32,
+    start_axis: u32,
+    start_value: f32,
+    end_axis: u32,
+    end_value: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> matrices: Matrices;
+
+@group(0) @binding(1)
+var<uniform> config: Config;
+
+@group(0) @binding(2)
+var<storage, read> axes: array<Axes>;
+
+@group(0) @binding(3)
+var<storage, read> values: array<DataLine>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) normal: vec2<f32>,
+}
+
+#include <line_coverage>
+
+#include <color_conversion>
+
+fn xyz_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let linear_srgb = XYZ_SRGB_CONVERSION_MATRIX * color.xyz;
+    return encode_output(linear_srgb);
+}
+
+@vertex
+fn vertex_main(
+    @builtin(vertex_index) vertex_idx: u32,
+    @builtin(instance_index) instance_idx: u32,
+) -> VertexOutput {
+    var INDEX_BUFFER = array<u32, 6>(0u, 1u, 2u, 1u, 3u, 2u);
+    var VERTEX_NORMALS_BUFFER = array<vec2<f32>, 4>(
+        vec2<f32>(0.0, -1.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(0.0, -1.0),
+        vec2<f32>(0.0, 1.0),
+    );
+
+    let index = INDEX_BUFFER[vertex_idx];
+    let value = values[instance_idx];
+
+    let start_axis = axes[value.start_axis];
+    let end_axis = axes[value.end_axis];
+
+    let start_x = mix(start_axis.center_x, start_axis.position_x.y, start_axis.expanded_val);
+    let end_x = mix(end_axis.center_x, end_axis.position_x.x, end_axis.expanded_val);
+
+    let line_start = vec2<f32>(start_x, mix(start_axis.range_y.x, start_axis.range_y.y, value.start_value));
+    let line_end = vec2<f32>(end_x, mix(end_axis.range_y.x, end_axis.range_y.y, value.end_value));
+
+    let line_vector = normalize(line_end - line_start);
+    let line_unit_cos = line_vector.x;
+    let line_unit_sin = line_vector.y;
+
+    let rotation_matrix = mat2x2<f32>(
+        line_unit_cos,
+        line_unit_sin,    // column 1: [cos theta, sin theta]
+        -line_unit_sin,
+        line_unit_cos,   // column 2: [-sin theta, cos theta]
+    );
+    let vertex_normal = rotation_matrix * VERTEX_NORMALS_BUFFER[index];
+    let vertex_pos = select(line_start, line_end, vec2<bool>(index <= 1u));
+
+    let delta = matrices.mv_matrix * vec4<f32>(vertex_normal * config.line_width, 0.0, 0.0);
+    let pos = matrices.mv_matrix * vec4<f32>(vertex_pos, 0.0, 1.0);
+    let offset_position = matrices.p_matrix * (pos + delta);
+
+    return VertexOutput(offset_position, vertex_normal);
+}
+
+@fragment
+fn fragment_main(@location(0) normal: vec2<f32>) -> @location(0) vec4<f32> {
+    let alpha = line_coverage(normal);
+
+    let color_alpha = config.color.a;
+    let color_srgb = xyz_to_srgb(config.color.rgb);
+
+    return vec4<f32>(color_srgb * alpha * color_alpha, alpha * color_alpha);
+}
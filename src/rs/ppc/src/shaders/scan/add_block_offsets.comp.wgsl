@@ -0,0 +1,26 @@
+#include <constants>
+
+struct Config {
+    num_values: u32,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> values: array<u32>;
+
+@group(0) @binding(1)
+var<storage, read> block_offsets: array<u32>;
+
+@group(0) @binding(2)
+var<uniform> config: Config;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(workgroup_id) workgroup_id: vec3<u32>,
+) {
+    // Offsets the scan of each block by the sum of all previous blocks.
+    let idx = global_id.x;
+    if idx < config.num_values {
+        values[idx] += block_offsets[workgroup_id.x];
+    }
+}
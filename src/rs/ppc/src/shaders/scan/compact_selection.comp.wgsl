@@ -0,0 +1,57 @@
+#include <constants>
+
+struct Config {
+    selection_bounds: vec2<f32>,
+    num_datums: u32,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> flags: array<u32>;
+
+@group(0) @binding(1)
+var<storage, read_write> offsets: array<u32>;
+
+@group(0) @binding(2)
+var<storage, read_write> indices: array<u32>;
+
+@group(0) @binding(3)
+var<storage, read> probabilities: array<f32>;
+
+@group(0) @binding(4)
+var<uniform> config: Config;
+
+// Marks the data points whose probability lies inside of the selection
+// bounds. The offsets are initialized with the flags, and are scanned in
+// place afterwards.
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn flag(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx >= config.num_datums {
+        return;
+    }
+
+    let probability = probabilities[idx];
+    let is_selected = probability >= config.selection_bounds.x
+        && probability <= config.selection_bounds.y;
+    let selected = select(0u, 1u, is_selected);
+    flags[idx] = selected;
+    offsets[idx] = selected;
+}
+
+// Writes the index of each selected data point to its scanned offset. The
+// number of selected data points is stored in front of the indices.
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn scatter(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx >= config.num_datums {
+        return;
+    }
+
+    let offset = offsets[idx];
+    if flags[idx] != 0u {
+        indices[offset + 1u] = idx;
+    }
+    if idx == config.num_datums - 1u {
+        indices[0] = offset + flags[idx];
+    }
+}
@@ -0,0 +1,64 @@
+#include <constants>
+
+struct Config {
+    num_values: u32,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> values: array<u32>;
+
+@group(0) @binding(1)
+var<storage, read_write> block_sums: array<u32>;
+
+@group(0) @binding(2)
+var<uniform> config: Config;
+
+var<workgroup> block: array<u32, WORKGROUP_SIZE>;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(local_invocation_index) local_idx: u32,
+    @builtin(workgroup_id) workgroup_id: vec3<u32>,
+) {
+    // Each workgroup computes the exclusive scan of its block of values, where
+    // the values past the end are treated as zero.
+    let idx = global_id.x;
+    if idx < config.num_values {
+        block[local_idx] = values[idx];
+    } else {
+        block[local_idx] = 0u;
+    }
+    workgroupBarrier();
+
+    // Up-sweep: builds a tree of partial sums, with the sum of the block at
+    // the root.
+    for (var stride = 1u; stride < WORKGROUP_SIZE; stride *= 2u) {
+        let i = (local_idx + 1u) * stride * 2u - 1u;
+        if i < WORKGROUP_SIZE {
+            block[i] += block[i - stride];
+        }
+        workgroupBarrier();
+    }
+
+    if local_idx == 0u {
+        block_sums[workgroup_id.x] = block[WORKGROUP_SIZE - 1u];
+        block[WORKGROUP_SIZE - 1u] = 0u;
+    }
+    workgroupBarrier();
+
+    // Down-sweep: distributes the partial sums back down the tree.
+    for (var stride = WORKGROUP_SIZE / 2u; stride > 0u; stride /= 2u) {
+        let i = (local_idx + 1u) * stride * 2u - 1u;
+        if i < WORKGROUP_SIZE {
+            let left = block[i - stride];
+            block[i - stride] = block[i];
+            block[i] += left;
+        }
+        workgroupBarrier();
+    }
+
+    if idx < config.num_values {
+        values[idx] = block[local_idx];
+    }
+}
@@ -1,20 +1,15 @@
-struct Matrices {
-    mv_matrix: mat4x4<f32>,
-    p_matrix: mat4x4<f32>,
-}
+#include <constants>
+#include <matrices>
 
 struct Config {
     line_width: vec2<f32>,
+    snap_resolution: vec2<f32>,
+    pattern: u32,
     high_color: vec3<f32>,
     low_color: vec3<f32>,
 }
 
-struct Axes {
-    expanded_val: f32,
-    center_x: f32,
-    position_x: vec2<f32>,
-    range_y: vec2<f32>,
-}
+#include <axes>
 
 struct SelectionLineInfo {
     axis: u32,
@@ -22,13 +17,11 @@ struct SelectionLineInfo {
     use_left: u32,
     offset_x: f32,
     color_idx: u32,
+    style: u32,
     range: vec2<f32>,
 }
 
-struct LabelColor {
-    color_high: vec4<f32>,
-    color_low: vec4<f32>,
-}
+#include <label_color>
 
 @group(0) @binding(0)
 var<uniform> matrices: Matrices;
@@ -55,34 +48,17 @@ struct VertexOutput {
     @location(2) @interpolate(flat) instance_idx: u32,
 }
 
-const FEATHER: f32 = 0.5;
-const ONE_MINUS_FEATHER: f32 = 1.0 - FEATHER;
+const PATTERN_STRIPES: f32 = 40.0;
 
-fn get_line_alpha(normal: vec2<f32>) -> f32 {
-    let distance = length(normal);
-    if distance <= ONE_MINUS_FEATHER {
-        return 1.0;
-    } else if distance <= 1.0 {
-        let t = (distance - FEATHER) / ONE_MINUS_FEATHER;
-        return mix(1.0, 0.0, t);
-    }
+#include <line_coverage>
 
-    return 0.0;
-}
+#include <color_conversion>
 
-const XYZ_SRGB_CONVERSION_MATRIX = mat3x3<f32>(
-    vec3<f32>(3.240812398895283, -0.9692430170086407, 0.055638398436112804),
-    vec3<f32>(-1.5373084456298136, 1.8759663029085742, -0.20400746093241362),
-    vec3<f32>(-0.4985865229069666, 0.04155503085668564, 1.0571295702861434),
-);
+#include <pixel_snapping>
 
 fn xyz_to_srgb(color: vec3<f32>) -> vec3<f32> {
     let linear_srgb = XYZ_SRGB_CONVERSION_MATRIX * color.xyz;
-    let a = 12.92 * linear_srgb;
-    let b = 1.055 * pow(linear_srgb, vec3<f32>(1.0 / 2.4)) - 0.055;
-    let c = step(vec3<f32>(0.0031308), linear_srgb);
-    let srgb = mix(a, b, c);
-    return srgb;
+    return encode_output(linear_srgb);
 }
 
 @vertex
@@ -123,7 +99,8 @@ fn vertex_main(
 
     let delta = matrices.mv_matrix * vec4<f32>(vertex_normal * config.line_width, 0.0, 0.0);
     let pos = matrices.mv_matrix * vec4<f32>(vertex_pos, 0.0, 1.0);
-    let offset_position = matrices.p_matrix * (pos + delta);
+    let center = snap_to_pixel(matrices.p_matrix * pos, config.snap_resolution);
+    let offset_position = center + matrices.p_matrix * delta;
     let curve_pos = select(selection.range.x, selection.range.y, index <= 1u);
 
     return VertexOutput(offset_position, vertex_normal, curve_pos, instance_idx);
@@ -135,7 +112,7 @@ fn fragment_main(
     @location(1) curve_pos: f32,
     @location(2) @interpolate(flat) instance_idx: u32
 ) -> @location(0) vec4<f32> {
-    let alpha = get_line_alpha(normal);
+    var alpha = line_coverage(normal);
 
     let selection = selections[instance_idx];
 
@@ -150,6 +127,25 @@ fn fragment_main(
     let sample_2 = textureLoad(probability_curves, vec2(sample_2_pos, 0), texture_array_index, 0).r;
     let sample = mix(sample_1, sample_2, t);
 
+    // Dim every other stripe, so that the selections are distinguishable without color.
+    if config.pattern != 0u && fract(curve_pos * PATTERN_STRIPES) < 0.5 {
+        alpha *= 0.35;
+    }
+
+    // Selections of the inactive labels are drawn in their dimmed colors.
+    if selection.style == SELECTION_STYLE_DIMMED {
+        alpha *= 0.6;
+        let color = xyz_to_srgb(colors[selection.color_idx].color_low.rgb);
+        return vec4<f32>(color * alpha, alpha);
+    }
+
+    // The brush which is being created is translucent, until it is committed.
+    if selection.style == SELECTION_STYLE_PREVIEW {
+        alpha *= 0.4;
+        let color = xyz_to_srgb(colors[selection.color_idx].color_high.rgb);
+        return vec4<f32>(color * alpha, alpha);
+    }
+
     if selection.use_color != 0u {
         let color_xyz = colors[selection.color_idx].color_high;
         let color = xyz_to_srgb(color_xyz.rgb);
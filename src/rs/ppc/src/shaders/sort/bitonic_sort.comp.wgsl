@@ -0,0 +1,58 @@
+#include <constants>
+
+struct Config {
+    num_keys: u32,
+    num_pairs: u32,
+    block_size: u32,
+    distance: u32,
+    descending: u32,
+}
+
+struct SortPair {
+    key: f32,
+    value: u32,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> pairs: array<SortPair>;
+
+@group(0) @binding(1)
+var<uniform> config: Config;
+
+// Returns whether `a` is ordered before `b`. The padding is always ordered
+// last, while pairs with equal keys are ordered by their value.
+fn ordered_before(a: SortPair, b: SortPair) -> bool {
+    if a.value == SORT_INVALID_VALUE {
+        return false;
+    }
+    if b.value == SORT_INVALID_VALUE {
+        return true;
+    }
+
+    if a.key != b.key {
+        return select(a.key < b.key, a.key > b.key, config.descending != 0u);
+    }
+    return a.value < b.value;
+}
+
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    // Each invocation compares the pair at its index with its partner at the
+    // given distance, where only the first of the two performs the swap.
+    let idx = global_id.x;
+    let partner_idx = idx ^ config.distance;
+    if idx >= config.num_pairs || partner_idx <= idx {
+        return;
+    }
+
+    // The blocks are alternately sorted in ascending and descending order,
+    // such that each two neighboring blocks form a bitonic sequence.
+    let a = pairs[idx];
+    let b = pairs[partner_idx];
+    let ascending = (idx & config.block_size) == 0u;
+    let swap = select(ordered_before(a, b), ordered_before(b, a), ascending);
+    if swap {
+        pairs[idx] = b;
+        pairs[partner_idx] = a;
+    }
+}
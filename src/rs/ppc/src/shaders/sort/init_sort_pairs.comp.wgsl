@@ -0,0 +1,39 @@
+#include <constants>
+
+struct Config {
+    num_keys: u32,
+    num_pairs: u32,
+    block_size: u32,
+    distance: u32,
+    descending: u32,
+}
+
+struct SortPair {
+    key: f32,
+    value: u32,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> pairs: array<SortPair>;
+
+@group(0) @binding(1)
+var<storage, read> keys: array<f32>;
+
+@group(0) @binding(2)
+var<uniform> config: Config;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx >= config.num_pairs {
+        return;
+    }
+
+    // The pairs are padded to a power of two, where the padding is marked
+    // with an invalid value and is sorted to the end.
+    if idx < config.num_keys {
+        pairs[idx] = SortPair(keys[idx], idx);
+    } else {
+        pairs[idx] = SortPair(0.0, SORT_INVALID_VALUE);
+    }
+}
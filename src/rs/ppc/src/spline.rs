@@ -1,6 +1,7 @@
 use crate::lerp::{InverseLerp, Lerp};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct Spline {
     range: [f32; 2],
     segments: Vec<SplineSegment>,
@@ -28,6 +29,27 @@ pub fn segments(&self) -> &[SplineSegment] {
         &self.segments
     }
 
+    /// Evaluates the spline at a position, like the sampling of the
+    /// probability curves on the GPU.
+    pub fn evaluate(&self, position: f32) -> f32 {
+        let segment = self
+            .segments
+            .iter()
+            .find(|s| !s.is_empty() && s.bounds[0] <= position && s.bounds[1] >= position)
+            .or(self.segments.last());
+        let Some(segment) = segment else {
+            return 0.0;
+        };
+
+        let [t_min, t_max] = segment.t_range;
+        let t = t_min.lerp(
+            t_max,
+            position.inv_lerp(segment.bounds[0], segment.bounds[1]),
+        );
+        let [a, b, c, d] = segment.coefficients;
+        (d + t * (c + t * (b + t * a))).clamp(0.0, 1.0)
+    }
+
     pub fn set_range(&mut self, range: [f32; 2]) {
         if range[0] < self.range[0] {
             self.segments.insert(
@@ -136,7 +158,7 @@ pub fn insert_segment(&mut self, segment: SplineSegment) {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct SplineSegment {
     pub bounds: [f32; 2],
     pub t_range: [f32; 2],
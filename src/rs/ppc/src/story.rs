@@ -0,0 +1,224 @@
+//! Scripted playback of transactions, e.g. for guided walkthroughs of a dataset.
+use std::{cell::Cell, collections::BTreeMap, rc::Rc};
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    api::{self, StoryEasingSpec, StorySpec, TransactionSpec},
+    lerp::Lerp,
+    scheduler::next_animation_frame,
+    wasm_bridge::EventQueue,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlaybackState {
+    Playing,
+    Paused,
+    Canceled,
+    Finished,
+}
+
+/// Handle to a story played back by [`play_story`].
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct StoryPlayback {
+    state: Rc<Cell<PlaybackState>>,
+    step: Rc<Cell<usize>>,
+}
+
+#[wasm_bindgen]
+impl StoryPlayback {
+    /// Pauses the playback, including the delays and transitions.
+    pub fn pause(&self) {
+        if self.state.get() == PlaybackState::Playing {
+            self.state.set(PlaybackState::Paused);
+        }
+    }
+
+    /// Resumes a paused playback.
+    pub fn resume(&self) {
+        if self.state.get() == PlaybackState::Paused {
+            self.state.set(PlaybackState::Playing);
+        }
+    }
+
+    /// Stops the playback, retaining the steps applied up until now.
+    pub fn cancel(&self) {
+        if self.state.get() != PlaybackState::Finished {
+            self.state.set(PlaybackState::Canceled);
+        }
+    }
+
+    /// Index of the current step.
+    #[wasm_bindgen(getter)]
+    pub fn step(&self) -> u32 {
+        self.step.get() as u32
+    }
+
+    /// Returns whether the playback is paused.
+    #[wasm_bindgen(getter)]
+    pub fn paused(&self) -> bool {
+        self.state.get() == PlaybackState::Paused
+    }
+
+    /// Returns whether the playback finished or was canceled.
+    #[wasm_bindgen(getter)]
+    pub fn done(&self) -> bool {
+        matches!(
+            self.state.get(),
+            PlaybackState::Canceled | PlaybackState::Finished
+        )
+    }
+}
+
+/// Clock advancing only while the playback is not paused.
+struct StoryClock {
+    state: Rc<Cell<PlaybackState>>,
+    last_frame: f64,
+    time: f64,
+}
+
+impl StoryClock {
+    /// Waits for the next frame and returns the playback time since the
+    /// last reset, or `None` if the playback was canceled.
+    async fn tick(&mut self) -> Option<f64> {
+        let frame = next_animation_frame().await;
+        let elapsed = (frame - self.last_frame).max(0.0);
+        self.last_frame = frame;
+
+        match self.state.get() {
+            PlaybackState::Playing => self.time += elapsed,
+            PlaybackState::Paused => {}
+            PlaybackState::Canceled | PlaybackState::Finished => return None,
+        }
+
+        Some(self.time)
+    }
+
+    /// Waits until the playback time reaches `time`.
+    async fn wait(&mut self, time: f64) -> Option<()> {
+        while self.tick().await? < time {}
+        Some(())
+    }
+
+    fn reset(&mut self) {
+        self.time = 0.0;
+    }
+}
+
+fn ease(t: f32, easing: StoryEasingSpec) -> f32 {
+    match easing {
+        StoryEasingSpec::Linear => t,
+        StoryEasingSpec::EaseIn => t * t,
+        StoryEasingSpec::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+        StoryEasingSpec::EaseInOut => t * t * (3.0 - 2.0 * t),
+    }
+}
+
+/// Plays back a story on the plot of the `queue`.
+///
+/// Each step waits for its delay before committing its transaction. Steps
+/// with a duration transition the visible ranges from the ranges previously
+/// set by the story, while the remaining changes are applied immediately.
+#[wasm_bindgen(js_name = playStory)]
+pub fn play_story(queue: &EventQueue, story: StorySpec) -> StoryPlayback {
+    let state = Rc::new(Cell::new(PlaybackState::Playing));
+    let step = Rc::new(Cell::new(0));
+    let playback = StoryPlayback {
+        state: state.clone(),
+        step: step.clone(),
+    };
+
+    let queue = EventQueue {
+        sender: queue.sender.clone(),
+    };
+    wasm_bindgen_futures::spawn_local(async move {
+        let mut clock = StoryClock {
+            state: state.clone(),
+            last_frame: f64::INFINITY,
+            time: 0.0,
+        };
+        let commit = |spec: TransactionSpec| {
+            if queue.sender.is_closed() {
+                state.set(PlaybackState::Canceled);
+            } else {
+                queue.commit_transaction(api::transaction_from_spec(spec));
+            }
+        };
+
+        let mut ranges = BTreeMap::<String, (f32, f32)>::new();
+        for (i, story_step) in story.steps.into_iter().enumerate() {
+            step.set(i);
+            clock.reset();
+            if clock.wait(story_step.delay).await.is_none() {
+                return;
+            }
+
+            let mut transaction = story_step.transaction;
+            for axis in &transaction.remove_axes {
+                ranges.remove(axis);
+            }
+            for (key, axis) in &transaction.add_axes {
+                if let Some(range) = axis.visible_range {
+                    ranges.insert(key.clone(), range);
+                }
+            }
+
+            // Only the ranges with a known start are transitioned.
+            let transitions = if story_step.duration > 0.0 {
+                let targets = std::mem::take(&mut transaction.visible_ranges);
+                let (animated, immediate) = targets
+                    .into_iter()
+                    .partition::<BTreeMap<_, _>, _>(|(axis, _)| ranges.contains_key(axis));
+                transaction.visible_ranges = immediate;
+                animated
+                    .into_iter()
+                    .map(|(axis, target)| {
+                        let start = ranges[&axis];
+                        (axis, start, target)
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                Vec::new()
+            };
+            ranges.extend(transaction.visible_ranges.clone());
+            commit(transaction);
+
+            if transitions.is_empty() {
+                continue;
+            }
+
+            clock.reset();
+            loop {
+                let Some(time) = clock.tick().await else {
+                    return;
+                };
+
+                let t = (time / story_step.duration).min(1.0) as f32;
+                let t = ease(t, story_step.easing);
+                let visible_ranges = transitions
+                    .iter()
+                    .map(|(axis, start, end)| {
+                        let range = (start.0.lerp(end.0, t), start.1.lerp(end.1, t));
+                        (axis.clone(), range)
+                    })
+                    .collect::<BTreeMap<_, _>>();
+                ranges.extend(visible_ranges.clone());
+                commit(TransactionSpec {
+                    visible_ranges,
+                    ..Default::default()
+                });
+
+                if time >= story_step.duration {
+                    break;
+                }
+            }
+        }
+
+        if state.get() != PlaybackState::Canceled {
+            state.set(PlaybackState::Finished);
+        }
+    });
+
+    playback
+}
@@ -0,0 +1,298 @@
+//! Operation-based synchronization of the plot between multiple clients.
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::api::{BrushSpec, BrushesSpec, SyncOperation, SyncOperationKind};
+
+/// Change of the local state caused by a remote operation.
+#[derive(Debug, Clone)]
+pub enum RemoteChange {
+    Brushes {
+        label: String,
+        axis: String,
+        brushes: Vec<BrushSpec>,
+    },
+    AxisOrder {
+        order: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct TrackedBrush {
+    id: String,
+    brush: BrushSpec,
+}
+
+/// Lamport timestamp of an operation, ordered by the clock and the client.
+type Stamp = (u32, String);
+
+/// Replica of the synchronized state of the plot.
+///
+/// Each brush is identified by the operation which created it. Conflicting
+/// operations are ordered by their Lamport timestamps, such that all clients
+/// converge to the same state, independent of the order in which they
+/// receive the operations:
+///
+/// - The most recent creation or move of a brush wins.
+/// - Deleting a brush wins over all moves of it.
+/// - The most recent reorder of the axes wins.
+#[derive(Debug)]
+pub struct SyncState {
+    client: String,
+    clock: u32,
+    brushes: BTreeMap<(String, String), Vec<TrackedBrush>>,
+    stamps: BTreeMap<String, Stamp>,
+    deleted: BTreeSet<String>,
+    axis_order: Vec<String>,
+    axis_order_stamp: Option<Stamp>,
+}
+
+impl SyncState {
+    /// Constructs a new replica, starting from the current state of the plot.
+    ///
+    /// The clients are expected to start from the same state, such that the
+    /// initial brushes are assigned the same ids.
+    pub fn new(client: String, brushes: BrushesSpec, axis_order: Vec<String>) -> Self {
+        let brushes = brushes
+            .into_iter()
+            .flat_map(|(label, axes)| {
+                axes.into_iter().map(move |(axis, brushes)| {
+                    let brushes = brushes
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, brush)| TrackedBrush {
+                            id: format!("initial:{label}:{axis}:{i}"),
+                            brush,
+                        })
+                        .collect();
+                    ((label.clone(), axis), brushes)
+                })
+            })
+            .collect();
+
+        Self {
+            client,
+            clock: 0,
+            brushes,
+            stamps: BTreeMap::new(),
+            deleted: BTreeSet::new(),
+            axis_order,
+            axis_order_stamp: None,
+        }
+    }
+
+    fn next_stamp(&mut self) -> Stamp {
+        self.clock += 1;
+        (self.clock, self.client.clone())
+    }
+
+    fn operation(&mut self, kind: SyncOperationKind) -> SyncOperation {
+        let (clock, client) = self.next_stamp();
+        SyncOperation {
+            client,
+            clock,
+            kind,
+        }
+    }
+
+    /// Compares the brushes with the replica and returns the operations
+    /// required to transform the replica into them.
+    ///
+    /// Brushes of labels or axes which don't exist locally, as determined by
+    /// `exists`, are skipped, as they may have been created by a remote client.
+    pub fn diff_brushes(
+        &mut self,
+        current: &BrushesSpec,
+        exists: impl Fn(&str, &str) -> bool,
+    ) -> Vec<SyncOperation> {
+        let keys = current
+            .iter()
+            .flat_map(|(label, axes)| axes.keys().map(|axis| (label.clone(), axis.clone())))
+            .chain(self.brushes.keys().cloned())
+            .collect::<BTreeSet<_>>();
+
+        let mut operations = Vec::new();
+        for (label, axis) in keys {
+            if !exists(&label, &axis) {
+                continue;
+            }
+
+            let new = current
+                .get(&label)
+                .and_then(|axes| axes.get(&axis))
+                .cloned()
+                .unwrap_or_default();
+            let key = (label, axis);
+            let mut unmatched_old = self.brushes.remove(&key).unwrap_or_default();
+            let (label, axis) = key;
+
+            // Unchanged brushes keep their id, regardless of their position.
+            let mut tracked = Vec::new();
+            let mut unmatched_new = Vec::new();
+            for brush in new {
+                match unmatched_old.iter().position(|b| b.brush == brush) {
+                    Some(i) => tracked.push(unmatched_old.remove(i)),
+                    None => unmatched_new.push(brush),
+                }
+            }
+
+            let mut unmatched_old = unmatched_old.into_iter();
+            for brush in unmatched_new {
+                let (id, kind) = match unmatched_old.next() {
+                    Some(old) => {
+                        let kind = SyncOperationKind::MoveBrush {
+                            label: label.clone(),
+                            axis: axis.clone(),
+                            id: old.id.clone(),
+                            brush: brush.clone(),
+                        };
+                        (old.id, kind)
+                    }
+                    None => {
+                        let id = format!("{}:{}", self.client, self.clock + 1);
+                        let kind = SyncOperationKind::CreateBrush {
+                            label: label.clone(),
+                            axis: axis.clone(),
+                            id: id.clone(),
+                            brush: brush.clone(),
+                        };
+                        (id, kind)
+                    }
+                };
+
+                let operation = self.operation(kind);
+                self.stamps
+                    .insert(id.clone(), (operation.clock, operation.client.clone()));
+                tracked.push(TrackedBrush { id, brush });
+                operations.push(operation);
+            }
+            for old in unmatched_old {
+                self.stamps.remove(&old.id);
+                self.deleted.insert(old.id.clone());
+                operations.push(self.operation(SyncOperationKind::DeleteBrush {
+                    label: label.clone(),
+                    axis: axis.clone(),
+                    id: old.id,
+                }));
+            }
+
+            if !tracked.is_empty() {
+                self.brushes.insert((label, axis), tracked);
+            }
+        }
+
+        operations
+    }
+
+    /// Compares the order of the axes with the replica and returns the
+    /// operation required to transform the replica into it.
+    pub fn diff_axis_order(&mut self, order: Vec<String>) -> Option<SyncOperation> {
+        if self.axis_order == order {
+            return None;
+        }
+
+        self.axis_order = order.clone();
+        let operation = self.operation(SyncOperationKind::ReorderAxes { order });
+        self.axis_order_stamp = Some((operation.clock, operation.client.clone()));
+        Some(operation)
+    }
+
+    /// Applies an operation of a remote client to the replica.
+    ///
+    /// Returns the resulting change of the local state, or `None` if the
+    /// operation lost a conflict or was already applied.
+    pub fn apply(&mut self, operation: SyncOperation) -> Option<RemoteChange> {
+        let SyncOperation {
+            client,
+            clock,
+            kind,
+        } = operation;
+        self.clock = self.clock.max(clock);
+        let stamp = (clock, client);
+
+        match kind {
+            SyncOperationKind::CreateBrush {
+                label,
+                axis,
+                id,
+                brush,
+            }
+            | SyncOperationKind::MoveBrush {
+                label,
+                axis,
+                id,
+                brush,
+            } => {
+                let outdated = self.stamps.get(&id).is_some_and(|s| *s >= stamp);
+                if outdated || self.deleted.contains(&id) {
+                    return None;
+                }
+                self.stamps.insert(id.clone(), stamp);
+
+                let key = (label, axis);
+                let brushes = self.brushes.entry(key.clone()).or_default();
+                match brushes.iter_mut().find(|b| b.id == id) {
+                    Some(tracked) => tracked.brush = brush,
+                    None => brushes.push(TrackedBrush { id, brush }),
+                }
+
+                let brushes = brushes.iter().map(|b| b.brush.clone()).collect();
+                let (label, axis) = key;
+                Some(RemoteChange::Brushes {
+                    label,
+                    axis,
+                    brushes,
+                })
+            }
+            SyncOperationKind::DeleteBrush { label, axis, id } => {
+                if !self.deleted.insert(id.clone()) {
+                    return None;
+                }
+                self.stamps.remove(&id);
+
+                let key = (label, axis);
+                let mut tracked = self.brushes.remove(&key).unwrap_or_default();
+                tracked.retain(|b| b.id != id);
+                let brushes = tracked.iter().map(|b| b.brush.clone()).collect();
+                if !tracked.is_empty() {
+                    self.brushes.insert(key.clone(), tracked);
+                }
+
+                let (label, axis) = key;
+                Some(RemoteChange::Brushes {
+                    label,
+                    axis,
+                    brushes,
+                })
+            }
+            SyncOperationKind::ReorderAxes { order } => {
+                if self.axis_order_stamp.as_ref().is_some_and(|s| *s >= stamp) {
+                    return None;
+                }
+
+                self.axis_order_stamp = Some(stamp);
+                self.axis_order = order.clone();
+                Some(RemoteChange::AxisOrder { order })
+            }
+        }
+    }
+
+    /// Replaces the brushes of the replica with the brushes applied to the
+    /// plot, retaining their ids.
+    ///
+    /// Applying a remote change may alter the brushes slightly, e.g. due to
+    /// rounding, which would otherwise be reported as local moves.
+    pub fn track_brushes(&mut self, label: &str, axis: &str, brushes: Vec<BrushSpec>) {
+        let key = (label.to_string(), axis.to_string());
+        if let Some(tracked) = self.brushes.get_mut(&key) {
+            for (tracked, brush) in tracked.iter_mut().zip(brushes) {
+                tracked.brush = brush;
+            }
+        }
+    }
+
+    /// Replaces the axis order of the replica with the order applied to the
+    /// plot, which skips the unknown axes.
+    pub fn track_axis_order(&mut self, order: Vec<String>) {
+        self.axis_order = order;
+    }
+}
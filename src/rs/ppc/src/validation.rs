@@ -0,0 +1,558 @@
+//! Validation of the bundled WGSL shaders against the Rust buffer layouts.
+//!
+//! The shaders are parsed and validated with `naga`, and the layout of each
+//! struct shared with the host is compared to the layout of the
+//! corresponding [`HostSharable`](crate::wgsl::HostSharable) Rust struct.
+//! A mismatch would otherwise only manifest as corrupted rendering.
+use std::mem::MaybeUninit;
+
+use crate::{buffers, preprocessor, webgpu::Limits};
+
+/// Pairs a bundled shader with its path relative to the `shaders` directory.
+macro_rules! shader {
+    ($path:literal) => {
+        ($path, include_str!(concat!("./shaders/", $path)))
+    };
+}
+
+/// Bundled shaders, indexed by their path.
+const SHADERS: [(&str, &str); 32] = [
+    shader!("background.wgsl"),
+    shader!("axis_regions.wgsl"),
+    shader!("axis_lines.wgsl"),
+    shader!("data_lines.wgsl"),
+    shader!("reference_lines.wgsl"),
+    shader!("flow_ribbons.wgsl"),
+    shader!("curve_lines.wgsl"),
+    shader!("density_curves.wgsl"),
+    shader!("histogram_bars.wgsl"),
+    shader!("selections.wgsl"),
+    shader!("curve_segments.wgsl"),
+    shader!("curve_comparison.wgsl"),
+    shader!("color_bar.wgsl"),
+    shader!("linear_resolve.wgsl"),
+    shader!("create_curves.comp.wgsl"),
+    shader!("apply_curves.comp.wgsl"),
+    shader!("reduce_probability.comp.wgsl"),
+    shader!("reduce_selected_range.comp.wgsl"),
+    shader!("reduce_label_agreement.comp.wgsl"),
+    shader!("reduce_label_mean.comp.wgsl"),
+    shader!("outlier_scores.comp.wgsl"),
+    shader!("sort/init_sort_pairs.comp.wgsl"),
+    shader!("sort/bitonic_sort.comp.wgsl"),
+    shader!("scan/scan_blocks.comp.wgsl"),
+    shader!("scan/add_block_offsets.comp.wgsl"),
+    shader!("scan/compact_selection.comp.wgsl"),
+    shader!("aggregate_flows.comp.wgsl"),
+    shader!("density/estimate_density.comp.wgsl"),
+    shader!("histogram/bin_values.comp.wgsl"),
+    shader!("color_scale/sample_color_scale.comp.wgsl"),
+    shader!("color_scale/transform_color_scale.comp.wgsl"),
+    shader!("probability_curve/sample_spline.comp.wgsl"),
+];
+
+/// Layout of a Rust struct shared with the shaders.
+struct HostLayout {
+    size: usize,
+    offsets: Vec<(&'static str, usize)>,
+}
+
+/// Computes the [`HostLayout`] of a struct from the listed fields.
+macro_rules! host_layout {
+    ($ty:ty { $($field:ident),* $(,)? }) => {{
+        let value = MaybeUninit::<$ty>::uninit();
+        let base = value.as_ptr();
+        HostLayout {
+            size: std::mem::size_of::<$ty>(),
+            offsets: vec![$((
+                stringify!($field),
+                // Safety: Only the address of the field is computed, it is never read.
+                unsafe { std::ptr::addr_of!((*base).$field) as usize - base as usize },
+            )),*],
+        }
+    }};
+}
+
+/// Expected layouts, indexed by the shader and the name of the WGSL struct.
+fn host_layouts() -> Vec<(&'static str, &'static str, HostLayout)> {
+    vec![
+        (
+            "axis_lines.wgsl",
+            "Matrices",
+            host_layout!(buffers::Matrices {
+                mv_matrix,
+                p_matrix
+            }),
+        ),
+        (
+            "axis_lines.wgsl",
+            "Axes",
+            host_layout!(buffers::Axis {
+                expanded_val,
+                center_x,
+                position_x,
+                range_y
+            }),
+        ),
+        (
+            "axis_lines.wgsl",
+            "Config",
+            host_layout!(buffers::AxesConfig {
+                line_width,
+                snap_resolution,
+                color,
+                marker_color
+            }),
+        ),
+        (
+            "axis_lines.wgsl",
+            "AxisLineInfo",
+            host_layout!(buffers::AxisLineInfo {
+                axis,
+                axis_position,
+                min_expanded_val,
+                marker
+            }),
+        ),
+        (
+            "data_lines.wgsl",
+            "Config",
+            host_layout!(buffers::DataLineConfig {
+                line_width,
+                selection_bounds,
+                color_probabilities,
+                render_order,
+                line_join,
+                unselected_color
+            }),
+        ),
+        (
+            "data_lines.wgsl",
+            "DataLine",
+            host_layout!(buffers::DataLine {
+                curve_idx,
+                start_axis,
+                start_value,
+                end_axis,
+                end_value
+            }),
+        ),
+        (
+            "reference_lines.wgsl",
+            "Config",
+            host_layout!(buffers::ReferenceConfig { line_width, color }),
+        ),
+        (
+            "reference_lines.wgsl",
+            "DataLine",
+            host_layout!(buffers::DataLine {
+                curve_idx,
+                start_axis,
+                start_value,
+                end_axis,
+                end_value
+            }),
+        ),
+        (
+            "curve_lines.wgsl",
+            "Config",
+            host_layout!(buffers::CurvesConfig { line_width, color }),
+        ),
+        (
+            "curve_lines.wgsl",
+            "CurveLineInfo",
+            host_layout!(buffers::CurveLineInfo {
+                x_t_values,
+                y_t_values,
+                axis
+            }),
+        ),
+        (
+            "curve_segments.wgsl",
+            "Config",
+            host_layout!(buffers::CurveSegmentConfig {
+                label,
+                active_label,
+                min_curve_t
+            }),
+        ),
+        (
+            "curve_segments.wgsl",
+            "LabelColor",
+            host_layout!(buffers::LabelColor {
+                color_high,
+                color_low
+            }),
+        ),
+        (
+            "curve_comparison.wgsl",
+            "Config",
+            host_layout!(buffers::CurveComparisonConfig {
+                label_a,
+                label_b,
+                curve_t_range
+            }),
+        ),
+        (
+            "selections.wgsl",
+            "Config",
+            host_layout!(buffers::SelectionConfig {
+                line_width,
+                snap_resolution,
+                pattern,
+                high_color,
+                low_color
+            }),
+        ),
+        (
+            "selections.wgsl",
+            "SelectionLineInfo",
+            host_layout!(buffers::SelectionLineInfo {
+                axis,
+                use_color,
+                use_left,
+                offset_x,
+                color_idx,
+                style,
+                range
+            }),
+        ),
+        (
+            "axis_regions.wgsl",
+            "AxisRegion",
+            host_layout!(buffers::AxisRegion {
+                axis,
+                half_width,
+                range,
+                color
+            }),
+        ),
+        (
+            "flow_ribbons.wgsl",
+            "Config",
+            host_layout!(buffers::FlowConfig {
+                color,
+                selection_bounds,
+                num_datums,
+                use_selection
+            }),
+        ),
+        (
+            "flow_ribbons.wgsl",
+            "FlowPair",
+            host_layout!(buffers::FlowPair {
+                axis_a,
+                axis_b,
+                num_categories_a,
+                num_categories_b,
+                scale,
+                positions_a,
+                positions_b
+            }),
+        ),
+        (
+            "background.wgsl",
+            "Config",
+            host_layout!(buffers::BackgroundConfig {
+                opacity,
+                gradient,
+                direction
+            }),
+        ),
+        (
+            "color_bar.wgsl",
+            "ColorBarBounds",
+            host_layout!(buffers::ColorScaleBounds {
+                start,
+                end,
+                horizontal
+            }),
+        ),
+        (
+            "reduce_selected_range.comp.wgsl",
+            "Config",
+            host_layout!(buffers::SelectedRangeConfig {
+                selection_bounds,
+                num_datums,
+                num_axes
+            }),
+        ),
+        (
+            "reduce_label_agreement.comp.wgsl",
+            "Config",
+            host_layout!(buffers::LabelAgreementConfig {
+                selection_bounds_a,
+                selection_bounds_b,
+                num_datums
+            }),
+        ),
+        (
+            "reduce_label_mean.comp.wgsl",
+            "Config",
+            host_layout!(buffers::LabelMeanConfig {
+                selection_bounds,
+                num_datums,
+                num_axes
+            }),
+        ),
+        (
+            "outlier_scores.comp.wgsl",
+            "Config",
+            host_layout!(buffers::OutlierConfig {
+                num_datums,
+                num_axes
+            }),
+        ),
+        (
+            "outlier_scores.comp.wgsl",
+            "AxisStatistics",
+            host_layout!(buffers::AxisStatistics { mean, deviation }),
+        ),
+        (
+            "sort/init_sort_pairs.comp.wgsl",
+            "Config",
+            host_layout!(buffers::SortConfig {
+                num_keys,
+                num_pairs,
+                block_size,
+                distance,
+                descending
+            }),
+        ),
+        (
+            "sort/init_sort_pairs.comp.wgsl",
+            "SortPair",
+            host_layout!(buffers::SortPair { key, value }),
+        ),
+        (
+            "sort/bitonic_sort.comp.wgsl",
+            "Config",
+            host_layout!(buffers::SortConfig {
+                num_keys,
+                num_pairs,
+                block_size,
+                distance,
+                descending
+            }),
+        ),
+        (
+            "sort/bitonic_sort.comp.wgsl",
+            "SortPair",
+            host_layout!(buffers::SortPair { key, value }),
+        ),
+        (
+            "scan/scan_blocks.comp.wgsl",
+            "Config",
+            host_layout!(buffers::ScanConfig { num_values }),
+        ),
+        (
+            "scan/add_block_offsets.comp.wgsl",
+            "Config",
+            host_layout!(buffers::ScanConfig { num_values }),
+        ),
+        (
+            "scan/compact_selection.comp.wgsl",
+            "Config",
+            host_layout!(buffers::CompactionConfig {
+                selection_bounds,
+                num_datums
+            }),
+        ),
+        (
+            "aggregate_flows.comp.wgsl",
+            "Config",
+            host_layout!(buffers::FlowConfig {
+                color,
+                selection_bounds,
+                num_datums,
+                use_selection
+            }),
+        ),
+        (
+            "density/estimate_density.comp.wgsl",
+            "Config",
+            host_layout!(buffers::DensityConfig {
+                color,
+                selected_color,
+                line_width,
+                selection_bounds,
+                num_datums,
+                num_axes,
+                bandwidth,
+                width,
+                use_selection
+            }),
+        ),
+        (
+            "density_curves.wgsl",
+            "Config",
+            host_layout!(buffers::DensityConfig {
+                color,
+                selected_color,
+                line_width,
+                selection_bounds,
+                num_datums,
+                num_axes,
+                bandwidth,
+                width,
+                use_selection
+            }),
+        ),
+        (
+            "histogram/bin_values.comp.wgsl",
+            "Config",
+            host_layout!(buffers::HistogramConfig {
+                color,
+                selected_color,
+                num_datums,
+                num_axes,
+                num_bins,
+                width,
+                use_selection
+            }),
+        ),
+        (
+            "histogram_bars.wgsl",
+            "Config",
+            host_layout!(buffers::HistogramConfig {
+                color,
+                selected_color,
+                num_datums,
+                num_axes,
+                num_bins,
+                width,
+                use_selection
+            }),
+        ),
+        (
+            "color_scale/sample_color_scale.comp.wgsl",
+            "ScaleElement",
+            host_layout!(buffers::ColorScaleElement { t, color }),
+        ),
+        (
+            "probability_curve/sample_spline.comp.wgsl",
+            "SplineSegment",
+            host_layout!(buffers::SplineSegment {
+                coefficients,
+                bounds,
+                t_range
+            }),
+        ),
+        (
+            "probability_curve/sample_spline.comp.wgsl",
+            "SplineRange",
+            host_layout!(buffers::SplineRange {
+                axis_idx,
+                segments_start,
+                segments_end
+            }),
+        ),
+    ]
+}
+
+/// Parses and validates the shader `source`.
+fn parse(name: &str, source: &str) -> Result<naga::Module, String> {
+    let source =
+        preprocessor::preprocess(source, Limits::default()).map_err(|e| format!("{name}: {e}"))?;
+    let module = naga::front::wgsl::parse_str(&source)
+        .map_err(|e| format!("{name}: {}", e.emit_to_string(&source)))?;
+
+    let mut validator = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    );
+    validator
+        .validate(&module)
+        .map_err(|e| format!("{name}: {e:?}"))?;
+
+    Ok(module)
+}
+
+/// Compares the WGSL struct `name` of the `module` with the `expected` layout.
+fn compare_layout(module: &naga::Module, name: &str, expected: &HostLayout) -> Vec<String> {
+    let Some((_, ty)) = module
+        .types
+        .iter()
+        .find(|(_, ty)| ty.name.as_deref() == Some(name))
+    else {
+        return vec![format!("struct `{name}` is not defined")];
+    };
+    let naga::TypeInner::Struct { members, span } = &ty.inner else {
+        return vec![format!("`{name}` is not a struct")];
+    };
+
+    let mut errors = Vec::new();
+    if *span as usize != expected.size {
+        errors.push(format!(
+            "struct `{name}` has a size of {span} bytes, expected {} bytes",
+            expected.size
+        ));
+    }
+
+    if members.len() != expected.offsets.len() {
+        errors.push(format!(
+            "struct `{name}` has {} members, expected {}",
+            members.len(),
+            expected.offsets.len()
+        ));
+    }
+
+    for (member, (field, offset)) in members.iter().zip(&expected.offsets) {
+        let member_name = member.name.as_deref().unwrap_or_default();
+        if member_name != *field {
+            errors.push(format!(
+                "member `{name}.{member_name}` does not match the field `{field}`"
+            ));
+        } else if member.offset as usize != *offset {
+            errors.push(format!(
+                "member `{name}.{field}` has an offset of {} bytes, expected {offset} bytes",
+                member.offset
+            ));
+        }
+    }
+
+    errors
+}
+
+/// Validates the bundled shaders, and checks that the layouts of the shared
+/// structs match their Rust counterparts.
+///
+/// Returns a description of all mismatches found.
+fn validate_layouts(layouts: Vec<(&'static str, &'static str, HostLayout)>) -> Result<(), String> {
+    let mut errors = Vec::new();
+    for (shader, source) in SHADERS {
+        let module = match parse(shader, source) {
+            Ok(module) => module,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        };
+
+        for (_, name, layout) in layouts.iter().filter(|(s, _, _)| *s == shader) {
+            errors.extend(
+                compare_layout(&module, name, layout)
+                    .into_iter()
+                    .map(|e| format!("{shader}: {e}")),
+            );
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shaders_match_the_host_layouts() {
+        if let Err(e) = validate_layouts(host_layouts()) {
+            panic!("the shaders do not match the buffer layouts:\n{e}");
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! Tracking of the visibility of the canvas.
+use std::{cell::Cell, rc::Rc};
+
+use async_channel::Sender;
+use wasm_bindgen::{closure::Closure, prelude::*};
+
+use crate::wasm_bridge::Event;
+
+/// Observes whether the canvas is visible to the user.
+///
+/// The canvas is considered hidden if it is scrolled out of the viewport,
+/// or if the document itself is hidden, e.g. in a background tab. Changes
+/// are forwarded to the renderer as [`Event::SetVisible`] events.
+#[derive(Debug)]
+pub struct VisibilityObserver {
+    document: web_sys::Document,
+    intersection_observer: web_sys::IntersectionObserver,
+    _intersection_listener: Closure<dyn FnMut(js_sys::Array)>,
+    visibility_listener: Closure<dyn FnMut()>,
+}
+
+#[derive(Debug)]
+struct VisibilityState {
+    intersecting: Cell<bool>,
+    document_visible: Cell<bool>,
+    visible: Cell<bool>,
+    sender: Sender<Event>,
+}
+
+impl VisibilityState {
+    fn update(&self) {
+        let visible = self.intersecting.get() && self.document_visible.get();
+        if self.visible.replace(visible) != visible {
+            // The channel may already be closed during the shutdown.
+            let _ = self.sender.try_send(Event::SetVisible { visible });
+        }
+    }
+}
+
+impl VisibilityObserver {
+    pub fn new(element: &web_sys::Element, sender: Sender<Event>) -> Self {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let state = Rc::new(VisibilityState {
+            intersecting: Cell::new(true),
+            document_visible: Cell::new(!document.hidden()),
+            visible: Cell::new(true),
+            sender,
+        });
+        state.update();
+
+        let intersection_listener = {
+            let state = state.clone();
+            Closure::<dyn FnMut(js_sys::Array)>::new(move |entries: js_sys::Array| {
+                if let Some(entry) = entries.iter().last() {
+                    let entry = entry.unchecked_into::<web_sys::IntersectionObserverEntry>();
+                    state.intersecting.set(entry.is_intersecting());
+                    state.update();
+                }
+            })
+        };
+        let intersection_observer =
+            web_sys::IntersectionObserver::new(intersection_listener.as_ref().unchecked_ref())
+                .unwrap();
+        intersection_observer.observe(element);
+
+        let visibility_listener = {
+            let state = state.clone();
+            let document = document.clone();
+            Closure::<dyn FnMut()>::new(move || {
+                state.document_visible.set(!document.hidden());
+                state.update();
+            })
+        };
+        document
+            .add_event_listener_with_callback(
+                "visibilitychange",
+                visibility_listener.as_ref().unchecked_ref(),
+            )
+            .unwrap();
+
+        Self {
+            document,
+            intersection_observer,
+            _intersection_listener: intersection_listener,
+            visibility_listener,
+        }
+    }
+}
+
+impl Drop for VisibilityObserver {
+    fn drop(&mut self) {
+        self.intersection_observer.disconnect();
+        self.document
+            .remove_event_listener_with_callback(
+                "visibilitychange",
+                self.visibility_listener.as_ref().unchecked_ref(),
+            )
+            .unwrap();
+    }
+}
@@ -8,9 +8,9 @@
 use wasm_bindgen::prelude::*;
 
 use crate::{
-    color_scale,
+    api, color_bar, color_scale,
     colors::{self, Color},
-    selection,
+    logging, selection,
 };
 
 #[wasm_bindgen]
@@ -21,7 +21,7 @@ pub enum PowerProfile {
     High,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[wasm_bindgen]
 pub struct AxisDef {
     pub(crate) key: Box<str>,
@@ -30,6 +30,7 @@ pub struct AxisDef {
     pub(crate) range: Option<(f32, f32)>,
     pub(crate) visible_range: Option<(f32, f32)>,
     pub(crate) ticks: Option<Vec<(f32, Option<Rc<str>>)>>,
+    pub(crate) histogram: Option<Box<[f32]>>,
 }
 
 #[wasm_bindgen]
@@ -68,8 +69,20 @@ pub fn new(
             range: range.map(|v| (v[0], v[1])),
             visible_range: visible_range.map(|v| (v[0], v[1])),
             ticks,
+            histogram: None,
         }
     }
+
+    /// Sets the distribution summary of the axis, as the number of data
+    /// points in equally sized bins spanning the range of the axis.
+    ///
+    /// Together with an explicit range, the axis may be defined without any
+    /// data points. While the plot contains no data points, the histogram
+    /// is drawn in place of the estimated density curve of the axis.
+    #[wasm_bindgen(js_name = withHistogram)]
+    pub fn with_histogram(&mut self, bins: Box<[f32]>) {
+        self.histogram = Some(bins);
+    }
 }
 
 #[wasm_bindgen]
@@ -126,6 +139,8 @@ pub enum ColorSpace {
     Xyz,
     CieLab,
     CieLch,
+    Oklab,
+    Oklch,
 }
 
 #[wasm_bindgen]
@@ -137,6 +152,8 @@ pub fn new(color_space: &str) -> Self {
             "xyz" => ColorSpace::Xyz,
             "cie_lab" => ColorSpace::CieLab,
             "cie_lch" => ColorSpace::CieLch,
+            "oklab" => ColorSpace::Oklab,
+            "oklch" => ColorSpace::Oklch,
             _ => panic!("unknown color space {color_space:?}"),
         };
 
@@ -162,6 +179,40 @@ pub fn with_sample(&mut self, sample: Option<f32>, color: ColorDescription) {
     }
 }
 
+impl ColorScaleDescription {
+    /// Converts the description into a color scale.
+    pub(crate) fn into_color_scale(self) -> ColorScale {
+        let ColorScaleDescription {
+            color_space,
+            gradient,
+        } = self;
+        let gradient = gradient
+            .into_iter()
+            .map(|(t, color)| {
+                let ColorDescription {
+                    color_space,
+                    values,
+                    alpha,
+                } = color;
+
+                let color = match color_space {
+                    ColorSpace::SRgb => colors::ColorQuery::SRgb(values, alpha),
+                    ColorSpace::Xyz => colors::ColorQuery::Xyz(values, alpha),
+                    ColorSpace::CieLab => colors::ColorQuery::Lab(values, alpha),
+                    ColorSpace::CieLch => colors::ColorQuery::Lch(values, alpha),
+                    ColorSpace::Oklab => colors::ColorQuery::Oklab(values, alpha),
+                    ColorSpace::Oklch => colors::ColorQuery::Oklch(values, alpha),
+                };
+
+                (t, color)
+            })
+            .collect::<Vec<_>>();
+
+        let scale = color_scale::ColorScaleDescriptor::Gradient(gradient);
+        ColorScale { color_space, scale }
+    }
+}
+
 #[wasm_bindgen]
 impl ColorDescription {
     #[wasm_bindgen(constructor)]
@@ -173,6 +224,8 @@ pub fn new(color_space: &str, values: &[f32]) -> Self {
             "xyz" => ColorSpace::Xyz,
             "cie_lab" => ColorSpace::CieLab,
             "cie_lch" => ColorSpace::CieLch,
+            "oklab" => ColorSpace::Oklab,
+            "oklch" => ColorSpace::Oklch,
             _ => panic!("unknown color space {color_space:?}"),
         };
 
@@ -216,12 +269,36 @@ pub enum DrawOrder {
     SelectedDecreasing,
 }
 
+/// Join of the segments of the data lines at the collapsed axes.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DataLineJoin {
+    /// The segments end at the axes, which leaves gaps at sharp vertices of
+    /// thick lines.
+    Butt,
+    /// The segments are extended by round caps.
+    Round,
+    /// The segments are extended to a sharp corner, up to a length of four
+    /// times the line width.
+    Miter,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorBarEdge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub enum DataColorMode {
     Constant(f32),
     Attribute(String),
     AttributeDensity(String),
     Probability,
+    GroupProbability(String),
 }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
@@ -240,6 +317,58 @@ pub struct Label {
     pub color: Option<colors::ColorQuery<'static>>,
     pub selection_bounds: Option<(f32, f32)>,
     pub easing: Option<selection::EasingType>,
+    pub visible: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct LabelGroup {
+    pub id: String,
+    pub color: Option<colors::ColorQuery<'static>>,
+}
+
+/// A static shaded band on an axis.
+///
+/// Unlike a brush, a region does not select any data.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct AxisRegion {
+    pub axis: String,
+    pub range: (f32, f32),
+    pub label: Option<String>,
+    pub color: Option<colors::ColorQuery<'static>>,
+}
+
+/// Options of the k-means clustering of the data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ClusterOptions {
+    pub k: usize,
+    pub max_iterations: usize,
+}
+
+/// Options of the outlier scores of the data.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct OutlierOptions {
+    pub threshold: f32,
+    pub label: Option<String>,
+}
+
+/// Options of the live mode for streaming data.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct LiveOptions {
+    pub interval: f64,
+    pub max_points: Option<usize>,
+}
+
+/// Options of the density curves of the axes.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct DensityOptions {
+    pub bandwidth: f32,
+    pub selected: bool,
+}
+
+/// Options of the histograms of the axes.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct HistogramOptions {
+    pub num_bins: u32,
 }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
@@ -260,18 +389,74 @@ pub struct LabelEasingUpdate {
     pub easing: selection::EasingType,
 }
 
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct LabelVisibilityUpdate {
+    pub id: String,
+    pub visible: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct LabelVisibleAxesUpdate {
     pub id: String,
     pub visible_axes: Vec<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub enum FitAxes {
+    All,
+    Axes(BTreeSet<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub enum ViewTarget {
+    Default,
+    Axes(Vec<String>),
+    Axis(String),
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct ViewChange {
+    pub target: ViewTarget,
+    pub animate: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct Brush {
     pub control_points: Vec<(f32, f32)>,
     pub main_segment_idx: usize,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum BrushEditKind {
+    Nudge { delta: f32 },
+    SetBounds { lower: f32, upper: f32 },
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct BrushEdit {
+    pub label: String,
+    pub axis: String,
+    pub index: usize,
+    pub kind: BrushEditKind,
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub enum ProbabilityCurve {
+    Samples(Box<[f32]>),
+    Segments {
+        bounds: Box<[f32]>,
+        t_ranges: Box<[f32]>,
+        coefficients: Box<[f32]>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct CurveImport {
+    pub label: String,
+    pub axis: String,
+    pub curve: ProbabilityCurve,
+}
+
 #[wasm_bindgen]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum InteractionMode {
@@ -282,8 +467,49 @@ pub enum InteractionMode {
     Full,
 }
 
+/// Minimum severity of the messages logged by the renderer.
 #[wasm_bindgen]
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    #[default]
+    Warn,
+    /// Errors, e.g. GPU validation errors. Not named `Error`, as the name
+    /// collides with the error type of the conversions generated for `Wasm`.
+    Critical,
+    /// Disables the logging.
+    Off,
+}
+
+impl LogLevel {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            LogLevel::Trace => "trace",
+            LogLevel::Debug => "debug",
+            LogLevel::Warn => "warn",
+            LogLevel::Critical => "critical",
+            LogLevel::Off => "off",
+        }
+    }
+}
+
+/// GPU buffers whose content can be inspected for debugging.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugBuffer {
+    /// The axes, as a list of `Axis`.
+    Axes,
+    /// The data lines, as a list of `DataLine`.
+    DataLines,
+    /// The brushes of the active label, as a list of `SelectionLineInfo`.
+    Selections,
+    /// The probabilities of the active label.
+    Probabilities,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Default, Clone, Copy)]
 pub struct DebugOptions {
     #[wasm_bindgen(js_name = showAxisBoundingBox)]
     pub show_axis_bounding_box: bool,
@@ -297,6 +523,27 @@ pub struct DebugOptions {
     pub show_selections_bounding_box: bool,
     #[wasm_bindgen(js_name = showColorBarBoundingBox)]
     pub show_color_bar_bounding_box: bool,
+    /// Blends in the presentation format even if linear blending is enabled,
+    /// to compare the two blending spaces.
+    #[wasm_bindgen(js_name = disableLinearBlending)]
+    pub disable_linear_blending: bool,
+    /// Minimum level of the logged messages.
+    #[wasm_bindgen(js_name = logLevel)]
+    pub log_level: LogLevel,
+    /// Forwards the logged messages to the callback of the renderer.
+    #[wasm_bindgen(js_name = mirrorLogs)]
+    pub mirror_logs: bool,
+    /// Logs the content of a buffer after each draw, at the debug level.
+    #[wasm_bindgen(js_name = logBuffer)]
+    pub log_buffer: Option<DebugBuffer>,
+    /// Draws the recent pointer trail, the element under the pointer and
+    /// the state of the active action.
+    #[wasm_bindgen(js_name = showInteractionTrace)]
+    pub show_interaction_trace: bool,
+    /// Logs the events and pointer inputs handled in each frame, at the debug
+    /// level.
+    #[wasm_bindgen(js_name = logEvents)]
+    pub log_events: bool,
 }
 
 #[wasm_bindgen]
@@ -322,160 +569,855 @@ pub fn none_is_active(&self) -> bool {
     }
 }
 
-#[derive(Debug)]
-enum StateTransactionOperation {
-    AddAxis {
-        axis: AxisDef,
-    },
-    RemoveAxis {
-        axis: String,
-    },
-    SetAxisOrder {
-        order: AxisOrder,
-    },
-    SetBackgroundColor {
-        color: colors::ColorQuery<'static>,
-    },
-    SetBrushColor {
-        color: colors::ColorQuery<'static>,
-    },
-    SetUnselectedColor {
-        color: colors::ColorQuery<'static>,
-    },
-    SetDrawOrder {
-        order: DrawOrder,
-    },
-    SetColorScale {
-        color_scale: ColorScale,
-    },
-    SetDataColorMode {
-        color_mode: DataColorMode,
-    },
-    SetColorBarVisibility {
-        visibility: bool,
-    },
-    AddLabel {
-        label: Label,
-    },
-    RemoveLabel {
-        label: String,
-    },
-    SetLabelColor {
-        update: LabelColorUpdate,
-    },
-    SetLabelSelectionBounds {
-        update: LabelBoundsUpdate,
-    },
-    SetLabelEasing {
-        update: LabelEasingUpdate,
-    },
-    SwitchActiveLabel {
-        id: Option<String>,
-    },
-    SetBrushes {
-        brushes: BTreeMap<String, BTreeMap<String, Vec<Brush>>>,
-    },
-    SetInteractionMode {
-        mode: InteractionMode,
-    },
-    SetDebugOptions {
-        options: DebugOptions,
-    },
+#[wasm_bindgen]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AccessibilityOptions {
+    #[wasm_bindgen(js_name = highContrast)]
+    pub high_contrast: bool,
+    #[wasm_bindgen(js_name = reducedMotion)]
+    pub reduced_motion: bool,
 }
 
 #[wasm_bindgen]
-#[derive(Debug, Default)]
-pub struct StateTransactionBuilder {
-    operations: Vec<StateTransactionOperation>,
+impl AccessibilityOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        AccessibilityOptions::default()
+    }
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocaleOptions {
+    pub locale: Option<String>,
+    #[wasm_bindgen(js_name = useGrouping)]
+    pub use_grouping: bool,
+    #[wasm_bindgen(js_name = maximumFractionDigits)]
+    pub maximum_fraction_digits: Option<u32>,
 }
 
 #[wasm_bindgen]
-impl StateTransactionBuilder {
+impl LocaleOptions {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
-        Default::default()
+        LocaleOptions::default()
     }
+}
 
-    #[wasm_bindgen(js_name = addAxis)]
-    pub fn add_axis(&mut self, axis: AxisDef) {
-        self.operations
-            .push(StateTransactionOperation::AddAxis { axis });
+impl LocaleOptions {
+    /// Constructs the number format described by the options.
+    pub(crate) fn number_format(&self) -> js_sys::Intl::NumberFormat {
+        let locales = match &self.locale {
+            Some(locale) => js_sys::Array::of1(&locale.into()),
+            None => js_sys::Array::new(),
+        };
+
+        let options = js_sys::Object::new();
+        js_sys::Reflect::set(&options, &"useGrouping".into(), &self.use_grouping.into()).unwrap();
+        if let Some(digits) = self.maximum_fraction_digits {
+            js_sys::Reflect::set(&options, &"maximumFractionDigits".into(), &digits.into())
+                .unwrap();
+        }
+
+        js_sys::Intl::NumberFormat::new(&locales, &options)
     }
+}
 
-    #[wasm_bindgen(js_name = removeAxis)]
-    pub fn remove_axis(&mut self, axis: String) {
-        self.operations
-            .push(StateTransactionOperation::RemoveAxis { axis });
+impl Default for LocaleOptions {
+    fn default() -> Self {
+        Self {
+            locale: None,
+            use_grouping: true,
+            maximum_fraction_digits: None,
+        }
     }
+}
 
-    #[wasm_bindgen(js_name = setAxisOrder)]
-    pub fn set_axis_order(&mut self, order: js_sys::Array) {
-        let order = if order.is_truthy() {
-            let order = order.into_iter().map(|x| x.as_string().unwrap()).collect();
-            AxisOrder::Custom { order }
-        } else {
-            AxisOrder::Automatic
-        };
+/// Format of the tick labels of the color bar.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorBarTickFormat {
+    /// Percentages for probabilities and densities, values otherwise.
+    Automatic,
+    /// Values, formatted with the number format of the locale.
+    Number,
+    /// Percentages of the color scale.
+    Percent,
+}
 
-        self.operations
-            .push(StateTransactionOperation::SetAxisOrder { order });
-    }
+/// Compositing of the plot with the page.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CanvasAlphaMode {
+    /// The plot is opaque, ignoring the alpha of the background color.
+    Opaque,
+    /// The plot is composited over the page, using its premultiplied alpha.
+    Premultiplied,
+}
 
-    #[wasm_bindgen(js_name = setDefaultColor)]
-    pub fn set_default_color(&mut self, element: Element) {
-        let color = match element {
-            Element::Background => {
-                let color = crate::DEFAULT_BACKGROUND_COLOR();
-                colors::ColorQuery::SRgb(color.to_f32(), Some(color.alpha))
-            }
-            Element::Brush => {
-                let color = crate::DEFAULT_BRUSH_COLOR();
-                colors::ColorQuery::Xyz(color.to_f32(), None)
-            }
-            Element::Unselected => {
-                let color = crate::DEFAULT_UNSELECTED_COLOR();
-                colors::ColorQuery::Xyz(color.to_f32(), Some(color.alpha))
-            }
-        };
-        let event = match element {
-            Element::Background => StateTransactionOperation::SetBackgroundColor { color },
-            Element::Brush => StateTransactionOperation::SetBrushColor { color },
-            Element::Unselected => StateTransactionOperation::SetUnselectedColor { color },
-        };
+/// Antialiasing of the rendered lines.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Antialiasing {
+    /// The plot is multisampled, and the edges of the lines are feathered.
+    Multisample,
+    /// The plot is multisampled, and the edges of the lines are covered
+    /// analytically, which smoothes thin lines on low density displays.
+    AnalyticMultisample,
+    /// The edges of the lines are covered analytically, without multisampling,
+    /// which is the cheapest option on slow devices.
+    Analytic,
+}
 
-        self.operations.push(event);
+impl Antialiasing {
+    pub(crate) fn is_multisampled(self) -> bool {
+        matches!(self, Self::Multisample | Self::AnalyticMultisample)
     }
 
-    #[wasm_bindgen(js_name = setDefaultDrawOrder)]
-    pub fn set_default_draw_order(&mut self) {
-        self.set_draw_order(crate::DEFAULT_DRAW_ORDER);
+    pub(crate) fn is_analytic(self) -> bool {
+        matches!(self, Self::AnalyticMultisample | Self::Analytic)
     }
+}
 
-    #[wasm_bindgen(js_name = setColorNamed)]
-    pub fn set_color_named(&mut self, element: Element, color: &str) {
-        let color = colors::ColorQuery::Named(color.to_string().into());
-        let event = match element {
-            Element::Background => StateTransactionOperation::SetBackgroundColor { color },
-            Element::Brush => StateTransactionOperation::SetBrushColor { color },
-            Element::Unselected => StateTransactionOperation::SetUnselectedColor { color },
-        };
-
-        self.operations.push(event);
-    }
+/// Display of the weighted mean of the values selected by each label.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LabelMeanMode {
+    /// The means are not drawn.
+    Hidden,
+    /// Draws the mean polyline of each label.
+    Mean,
+    /// Draws the mean polyline of each label, inside of a band spanning
+    /// one standard deviation around the mean.
+    MeanWithDeviation,
+}
 
-    #[wasm_bindgen(js_name = setColorValue)]
-    pub fn set_color_value(&mut self, element: Element, color: ColorDescription) {
-        let ColorDescription {
-            color_space,
-            values,
-            alpha,
-        } = color;
+/// Placement of the background layer on the canvas.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BackgroundPlacement {
+    /// Stretches the layer over the whole canvas.
+    Stretch,
+    Center,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum BackgroundSource {
+    /// Pixels in the `RGBA` format, with one byte per channel.
+    Image {
+        width: u32,
+        height: u32,
+        pixels: Vec<u8>,
+    },
+    /// Gradient along the direction `angle`, in degrees clockwise from the
+    /// positive x axis.
+    Gradient { scale: ColorScale, angle: f32 },
+}
+
+/// An image or a gradient drawn behind the data, e.g., a logo or watermark.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackgroundLayer {
+    pub(crate) source: BackgroundSource,
+    pub(crate) opacity: f32,
+    pub(crate) placement: BackgroundPlacement,
+    pub(crate) scale: f32,
+}
+
+#[wasm_bindgen]
+impl BackgroundLayer {
+    /// Constructs a layer from the pixels of an image, e.g., the `data` of
+    /// an `ImageData`, in the `RGBA` format with one byte per channel.
+    #[wasm_bindgen(js_name = fromImage)]
+    pub fn from_image(width: u32, height: u32, pixels: Vec<u8>) -> Self {
+        assert_eq!(pixels.len(), (width * height * 4) as usize);
+        Self {
+            source: BackgroundSource::Image {
+                width,
+                height,
+                pixels,
+            },
+            opacity: 1.0,
+            placement: BackgroundPlacement::Center,
+            scale: 1.0,
+        }
+    }
+
+    /// Constructs a layer from a color gradient, which always covers the
+    /// whole canvas.
+    #[wasm_bindgen(js_name = fromGradient)]
+    pub fn from_gradient(scale: ColorScaleDescription, angle: f32) -> Self {
+        Self {
+            source: BackgroundSource::Gradient {
+                scale: scale.into_color_scale(),
+                angle,
+            },
+            opacity: 1.0,
+            placement: BackgroundPlacement::Stretch,
+            scale: 1.0,
+        }
+    }
+
+    #[wasm_bindgen(js_name = setOpacity)]
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity;
+    }
+
+    #[wasm_bindgen(js_name = setPlacement)]
+    pub fn set_placement(&mut self, placement: BackgroundPlacement) {
+        self.placement = placement;
+    }
+
+    /// Sets the scale of an image, relative to its size in CSS pixels.
+    #[wasm_bindgen(js_name = setScale)]
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+}
+
+/// Shape of an annotation.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AnnotationKind {
+    /// Text placed next to the anchor.
+    Text,
+    /// Text with an arrow pointing to the anchor.
+    Arrow,
+    /// Text with a circular marker at the anchor.
+    Marker,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum AnnotationAnchor {
+    /// A value on an axis.
+    Value { axis: String, value: f32 },
+    /// The value of a datum on an axis.
+    Datum { axis: String, index: usize },
+}
+
+impl AnnotationAnchor {
+    pub(crate) fn axis(&self) -> &str {
+        match self {
+            AnnotationAnchor::Value { axis, .. } | AnnotationAnchor::Datum { axis, .. } => axis,
+        }
+    }
+}
+
+/// A note anchored to the data coordinates of the plot.
+///
+/// Annotations follow their anchor when the axes are reordered, resized
+/// or their visible range changes, and are hidden while the anchor lies
+/// outside of the visible range of its axis.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub(crate) kind: AnnotationKind,
+    pub(crate) anchor: AnnotationAnchor,
+    pub(crate) text: String,
+    pub(crate) offset: (f32, f32),
+}
+
+#[wasm_bindgen]
+impl Annotation {
+    /// Constructs an annotation anchored to a value on an axis.
+    #[wasm_bindgen(js_name = atValue)]
+    pub fn at_value(kind: AnnotationKind, axis: String, value: f32, text: String) -> Self {
+        Self::new(kind, AnnotationAnchor::Value { axis, value }, text)
+    }
+
+    /// Constructs an annotation anchored to the value of the datum with the
+    /// index `index` on an axis.
+    #[wasm_bindgen(js_name = atDatum)]
+    pub fn at_datum(kind: AnnotationKind, axis: String, index: usize, text: String) -> Self {
+        Self::new(kind, AnnotationAnchor::Datum { axis, index }, text)
+    }
+
+    /// Sets the offset of the text from the anchor, in CSS pixels.
+    #[wasm_bindgen(js_name = setOffset)]
+    pub fn set_offset(&mut self, x: f32, y: f32) {
+        self.offset = (x, y);
+    }
+}
+
+impl Annotation {
+    pub(crate) fn new(kind: AnnotationKind, anchor: AnnotationAnchor, text: String) -> Self {
+        let offset = match kind {
+            AnnotationKind::Text | AnnotationKind::Marker => (8.0, -8.0),
+            AnnotationKind::Arrow => (40.0, -40.0),
+        };
+
+        Self {
+            kind,
+            anchor,
+            text,
+            offset,
+        }
+    }
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorBarOptions {
+    /// Number of evenly spaced ticks.
+    #[wasm_bindgen(js_name = tickCount)]
+    pub tick_count: Option<u32>,
+    /// Positions of the ticks on the color scale, in the range `[0, 1]`.
+    /// Takes precedence over the tick count.
+    #[wasm_bindgen(js_name = tickPositions)]
+    pub tick_positions: Option<Vec<f32>>,
+    #[wasm_bindgen(js_name = tickFormat)]
+    pub tick_format: ColorBarTickFormat,
+    /// Unit appended to the label of the color bar.
+    pub unit: Option<String>,
+}
+
+#[wasm_bindgen]
+impl ColorBarOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        ColorBarOptions::default()
+    }
+}
+
+impl Default for ColorBarOptions {
+    fn default() -> Self {
+        Self {
+            tick_count: None,
+            tick_positions: None,
+            tick_format: ColorBarTickFormat::Automatic,
+            unit: None,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HitTestOptions {
+    #[wasm_bindgen(js_name = controlPointRadius)]
+    pub control_point_radius: f32,
+    #[wasm_bindgen(js_name = axisLineRadius)]
+    pub axis_line_radius: f32,
+    #[wasm_bindgen(js_name = selectionEdgeRadius)]
+    pub selection_edge_radius: f32,
+    #[wasm_bindgen(js_name = touchScale)]
+    pub touch_scale: f32,
+    #[wasm_bindgen(js_name = penScale)]
+    pub pen_scale: f32,
+}
+
+#[wasm_bindgen]
+impl HitTestOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        HitTestOptions::default()
+    }
+}
+
+impl Default for HitTestOptions {
+    fn default() -> Self {
+        let radii = crate::axis::HitTestRadii::default();
+        Self {
+            control_point_radius: radii.control_point,
+            axis_line_radius: radii.axis_line,
+            selection_edge_radius: radii.selection_edge,
+            touch_scale: 2.0,
+            pen_scale: 1.5,
+        }
+    }
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlPointOptions {
+    pub radius: f32,
+    pub fill: String,
+    pub stroke: String,
+    #[wasm_bindgen(js_name = hoverFill)]
+    pub hover_fill: String,
+    #[wasm_bindgen(js_name = hoverStroke)]
+    pub hover_stroke: String,
+    #[wasm_bindgen(js_name = activeFill)]
+    pub active_fill: String,
+    #[wasm_bindgen(js_name = activeStroke)]
+    pub active_stroke: String,
+    #[wasm_bindgen(js_name = highlightScale)]
+    pub highlight_scale: f32,
+}
+
+#[wasm_bindgen]
+impl ControlPointOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        ControlPointOptions::default()
+    }
+}
+
+impl Default for ControlPointOptions {
+    fn default() -> Self {
+        Self {
+            radius: crate::axis::CONTROL_POINTS_RADIUS_REM,
+            fill: "rgb(178 178 178)".into(),
+            stroke: "rgb(120 120 120)".into(),
+            hover_fill: "rgb(178 178 178 / 0.5)".into(),
+            hover_stroke: "rgb(120 120 120 / 0.5)".into(),
+            active_fill: "rgb(120 120 120)".into(),
+            active_stroke: "rgb(60 60 60)".into(),
+            highlight_scale: 1.5,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct FrameOptions {
+    #[wasm_bindgen(js_name = scheduleDraws)]
+    pub schedule_draws: bool,
+    #[wasm_bindgen(js_name = maxFps)]
+    pub max_fps: Option<f32>,
+}
+
+#[wasm_bindgen]
+impl FrameOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+#[derive(Debug)]
+enum StateTransactionOperation {
+    AddAxis {
+        axis: AxisDef,
+    },
+    RemoveAxis {
+        axis: String,
+    },
+    SetAxisPoints {
+        axis: String,
+        points: Box<[f32]>,
+    },
+    AppendAxisPoints {
+        axis: String,
+        points: Box<[f32]>,
+    },
+    SetVisibleRange {
+        axis: String,
+        range: (f32, f32),
+    },
+    FitVisibleRange {
+        axis: Option<String>,
+    },
+    SetAxisJitter {
+        axis: String,
+        amplitude: f32,
+    },
+    OptimizeCategoryOrder {
+        axis: String,
+    },
+    SetReferencePoints {
+        axis: String,
+        points: Box<[f32]>,
+    },
+    ClearReferenceData,
+    SetAxisOrder {
+        order: AxisOrder,
+    },
+    SetView {
+        change: ViewChange,
+    },
+    SetBackgroundColor {
+        color: colors::ColorQuery<'static>,
+    },
+    SetBrushColor {
+        color: colors::ColorQuery<'static>,
+    },
+    SetUnselectedColor {
+        color: colors::ColorQuery<'static>,
+    },
+    SetDrawOrder {
+        order: DrawOrder,
+    },
+    SetColorScale {
+        color_scale: ColorScale,
+    },
+    SetDataColorMode {
+        color_mode: DataColorMode,
+    },
+    SetColorBarVisibility {
+        visibility: bool,
+    },
+    SetColorBarPlacement {
+        placement: color_bar::ColorBarPlacement,
+    },
+    SetColorBarOptions {
+        options: ColorBarOptions,
+    },
+    SetAxisHeaderHeight {
+        height: f32,
+    },
+    SetCurveAreaWidth {
+        width: f32,
+    },
+    SetAllSelectionsVisibility {
+        visibility: bool,
+    },
+    SetLabelAgreementMetrics {
+        enabled: bool,
+    },
+    SetLinearBlending {
+        enabled: bool,
+    },
+    SetPixelSnapping {
+        enabled: bool,
+    },
+    SetAntialiasing {
+        mode: Antialiasing,
+    },
+    SetDataLineJoin {
+        join: DataLineJoin,
+    },
+    SetCanvasAlphaMode {
+        mode: CanvasAlphaMode,
+    },
+    SetBackgroundLayer {
+        layer: Option<BackgroundLayer>,
+    },
+    AddAnnotation {
+        id: String,
+        annotation: Annotation,
+    },
+    RemoveAnnotation {
+        id: String,
+    },
+    AddAxisRegion {
+        id: String,
+        region: AxisRegion,
+    },
+    RemoveAxisRegion {
+        id: String,
+    },
+    SetFlowRibbons {
+        enabled: bool,
+    },
+    SetLabelMeanMode {
+        mode: LabelMeanMode,
+    },
+    SetClusters {
+        options: Option<ClusterOptions>,
+    },
+    SetOutlierScores {
+        options: Option<OutlierOptions>,
+    },
+    SetDensityCurves {
+        options: Option<DensityOptions>,
+    },
+    SetHistograms {
+        options: Option<HistogramOptions>,
+    },
+    SetLiveMode {
+        options: Option<LiveOptions>,
+    },
+    SetLiveDataFrozen {
+        frozen: bool,
+    },
+    AddLabel {
+        label: Label,
+    },
+    RemoveLabel {
+        label: String,
+    },
+    SetLabelColor {
+        update: LabelColorUpdate,
+    },
+    SetLabelSelectionBounds {
+        update: LabelBoundsUpdate,
+    },
+    SetLabelEasing {
+        update: LabelEasingUpdate,
+    },
+    SetLabelVisibility {
+        update: LabelVisibilityUpdate,
+    },
+    SwitchActiveLabel {
+        id: Option<String>,
+    },
+    FreezeLabel {
+        label: String,
+    },
+    DiscardLabelSnapshot {
+        label: String,
+    },
+    AddLabelGroup {
+        group: LabelGroup,
+    },
+    SetCurveComparison {
+        labels: Option<(String, String)>,
+    },
+    RemoveLabelGroup {
+        group: String,
+    },
+    SetLabelGroup {
+        label: String,
+        group: Option<String>,
+    },
+    SetBrushes {
+        brushes: BTreeMap<String, BTreeMap<String, Vec<Brush>>>,
+    },
+    EditBrush {
+        edit: BrushEdit,
+    },
+    ImportCurve {
+        import: CurveImport,
+    },
+    RevertLabel {
+        label: String,
+        step: usize,
+    },
+    SetOperationSync {
+        client: Option<String>,
+    },
+    ApplyRemoteOperation {
+        operation: api::SyncOperation,
+    },
+    SetInteractionMode {
+        mode: InteractionMode,
+    },
+    SetDebugOptions {
+        options: DebugOptions,
+    },
+    SetHitTestOptions {
+        options: HitTestOptions,
+    },
+    SetControlPointOptions {
+        options: ControlPointOptions,
+    },
+    SetAccessibilityOptions {
+        options: AccessibilityOptions,
+    },
+    SetLocaleOptions {
+        options: LocaleOptions,
+    },
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Default)]
+pub struct StateTransactionBuilder {
+    operations: Vec<StateTransactionOperation>,
+}
+
+#[wasm_bindgen]
+impl StateTransactionBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    #[wasm_bindgen(js_name = addAxis)]
+    pub fn add_axis(&mut self, axis: AxisDef) {
+        self.operations
+            .push(StateTransactionOperation::AddAxis { axis });
+    }
+
+    #[wasm_bindgen(js_name = removeAxis)]
+    pub fn remove_axis(&mut self, axis: String) {
+        self.operations
+            .push(StateTransactionOperation::RemoveAxis { axis });
+    }
+
+    /// Replaces the data points of an existing axis.
+    ///
+    /// Unlike removing and adding the axis, the brushes of the axis are
+    /// retained. The range of the axis is recomputed from the new points.
+    #[wasm_bindgen(js_name = setAxisPoints)]
+    pub fn set_axis_points(&mut self, axis: String, points: Box<[f32]>) {
+        self.operations
+            .push(StateTransactionOperation::SetAxisPoints { axis, points });
+    }
+
+    /// Appends data points to an existing axis.
+    ///
+    /// The axes which are not appended to by the transaction are padded with
+    /// missing values, such that all axes contain the same number of points.
+    /// While the live mode is enabled, the points are applied at its cadence.
+    #[wasm_bindgen(js_name = appendAxisPoints)]
+    pub fn append_axis_points(&mut self, axis: String, points: Box<[f32]>) {
+        self.operations
+            .push(StateTransactionOperation::AppendAxisPoints { axis, points });
+    }
+
+    /// Sets the visible range of an existing axis.
+    #[wasm_bindgen(js_name = setVisibleRange)]
+    pub fn set_visible_range(&mut self, axis: String, min: f32, max: f32) {
+        self.operations
+            .push(StateTransactionOperation::SetVisibleRange {
+                axis,
+                range: (min, max),
+            });
+    }
+
+    /// Fits the visible range of an axis, or of all axes if `axis` is
+    /// missing, to the data points selected by the active label.
+    #[wasm_bindgen(js_name = fitVisibleRangeToSelection)]
+    pub fn fit_visible_range_to_selection(&mut self, axis: Option<String>) {
+        self.operations
+            .push(StateTransactionOperation::FitVisibleRange { axis });
+    }
+
+    /// Sets the amplitude of the jitter of the lines on an axis.
+    ///
+    /// Each line is offset by a deterministic pseudo random amount of up to
+    /// `amplitude`, in normalized units of the axis, such that lines with
+    /// identical values fan out. The reported values are not affected. An
+    /// amplitude of `0` disables the jitter.
+    #[wasm_bindgen(js_name = setAxisJitter)]
+    pub fn set_axis_jitter(&mut self, axis: String, amplitude: f32) {
+        self.operations
+            .push(StateTransactionOperation::SetAxisJitter { axis, amplitude });
+    }
+
+    /// Reorders the categories of an axis, such that the lines to its
+    /// neighboring axes cross as little as possible.
+    ///
+    /// Only applies to axes with few distinct values. The categories are
+    /// placed at the positions of the sorted categories, and the ticks are
+    /// replaced by one tick per category. The values of the data points
+    /// remain unchanged.
+    #[wasm_bindgen(js_name = optimizeCategoryOrder)]
+    pub fn optimize_category_order(&mut self, axis: String) {
+        self.operations
+            .push(StateTransactionOperation::OptimizeCategoryOrder { axis });
+    }
+
+    /// Sets the points of the reference dataset on an axis.
+    ///
+    /// The reference dataset is drawn in a muted color behind the data of
+    /// the plot, and is never brushed. Its points are normalized with the
+    /// range of the axis of the same key.
+    #[wasm_bindgen(js_name = setReferencePoints)]
+    pub fn set_reference_points(&mut self, axis: String, points: Box<[f32]>) {
+        self.operations
+            .push(StateTransactionOperation::SetReferencePoints { axis, points });
+    }
+
+    /// Removes the points of the reference dataset from all axes.
+    #[wasm_bindgen(js_name = clearReferenceData)]
+    pub fn clear_reference_data(&mut self) {
+        self.operations
+            .push(StateTransactionOperation::ClearReferenceData);
+    }
+
+    #[wasm_bindgen(js_name = setAxisOrder)]
+    pub fn set_axis_order(&mut self, order: js_sys::Array) {
+        let order = if order.is_truthy() {
+            let order = order.into_iter().map(|x| x.as_string().unwrap()).collect();
+            AxisOrder::Custom { order }
+        } else {
+            AxisOrder::Automatic
+        };
+
+        self.operations
+            .push(StateTransactionOperation::SetAxisOrder { order });
+    }
+
+    /// Zooms and pans the view, such that the axes in `keys` fill the plot.
+    ///
+    /// If `animate` is set, the view is moved over multiple frames, unless
+    /// reduced motion is requested.
+    #[wasm_bindgen(js_name = fitToAxes)]
+    pub fn fit_to_axes(&mut self, keys: js_sys::Array, animate: Option<bool>) {
+        let keys = keys.into_iter().map(|x| x.as_string().unwrap()).collect();
+        self.operations.push(StateTransactionOperation::SetView {
+            change: ViewChange {
+                target: ViewTarget::Axes(keys),
+                animate: animate.unwrap_or(false),
+            },
+        });
+    }
+
+    /// Pans the view, such that the axis `key` is in its center, keeping the
+    /// current zoom level.
+    #[wasm_bindgen(js_name = centerOnAxis)]
+    pub fn center_on_axis(&mut self, key: String, animate: Option<bool>) {
+        self.operations.push(StateTransactionOperation::SetView {
+            change: ViewChange {
+                target: ViewTarget::Axis(key),
+                animate: animate.unwrap_or(false),
+            },
+        });
+    }
+
+    /// Resets the view, such that all visible axes fit the plot.
+    #[wasm_bindgen(js_name = resetView)]
+    pub fn reset_view(&mut self, animate: Option<bool>) {
+        self.operations.push(StateTransactionOperation::SetView {
+            change: ViewChange {
+                target: ViewTarget::Default,
+                animate: animate.unwrap_or(false),
+            },
+        });
+    }
+
+    #[wasm_bindgen(js_name = setDefaultColor)]
+    pub fn set_default_color(&mut self, element: Element) {
+        let color = match element {
+            Element::Background => {
+                let color = crate::DEFAULT_BACKGROUND_COLOR();
+                colors::ColorQuery::SRgb(color.to_f32(), Some(color.alpha))
+            }
+            Element::Brush => {
+                let color = crate::DEFAULT_BRUSH_COLOR();
+                colors::ColorQuery::Xyz(color.to_f32(), None)
+            }
+            Element::Unselected => {
+                let color = crate::DEFAULT_UNSELECTED_COLOR();
+                colors::ColorQuery::Xyz(color.to_f32(), Some(color.alpha))
+            }
+        };
+        let event = match element {
+            Element::Background => StateTransactionOperation::SetBackgroundColor { color },
+            Element::Brush => StateTransactionOperation::SetBrushColor { color },
+            Element::Unselected => StateTransactionOperation::SetUnselectedColor { color },
+        };
+
+        self.operations.push(event);
+    }
+
+    #[wasm_bindgen(js_name = setDefaultDrawOrder)]
+    pub fn set_default_draw_order(&mut self) {
+        self.set_draw_order(crate::DEFAULT_DRAW_ORDER);
+    }
+
+    #[wasm_bindgen(js_name = setColorNamed)]
+    pub fn set_color_named(&mut self, element: Element, color: &str) {
+        let color = colors::ColorQuery::Named(color.to_string().into());
+        let event = match element {
+            Element::Background => StateTransactionOperation::SetBackgroundColor { color },
+            Element::Brush => StateTransactionOperation::SetBrushColor { color },
+            Element::Unselected => StateTransactionOperation::SetUnselectedColor { color },
+        };
+
+        self.operations.push(event);
+    }
+
+    #[wasm_bindgen(js_name = setColorValue)]
+    pub fn set_color_value(&mut self, element: Element, color: ColorDescription) {
+        let ColorDescription {
+            color_space,
+            values,
+            alpha,
+        } = color;
 
         let color = match color_space {
             ColorSpace::SRgb => colors::ColorQuery::SRgb(values, alpha),
             ColorSpace::Xyz => colors::ColorQuery::Xyz(values, alpha),
             ColorSpace::CieLab => colors::ColorQuery::Lab(values, alpha),
             ColorSpace::CieLch => colors::ColorQuery::Lch(values, alpha),
+            ColorSpace::Oklab => colors::ColorQuery::Oklab(values, alpha),
+            ColorSpace::Oklch => colors::ColorQuery::Oklch(values, alpha),
         };
         let event = match element {
             Element::Background => StateTransactionOperation::SetBackgroundColor { color },
@@ -483,143 +1425,499 @@ pub fn set_color_value(&mut self, element: Element, color: ColorDescription) {
             Element::Unselected => StateTransactionOperation::SetUnselectedColor { color },
         };
 
-        self.operations.push(event);
+        self.operations.push(event);
+    }
+
+    #[wasm_bindgen(js_name = setDrawOrder)]
+    pub fn set_draw_order(&mut self, order: DrawOrder) {
+        self.operations
+            .push(StateTransactionOperation::SetDrawOrder { order });
+    }
+
+    #[wasm_bindgen(js_name = setDefaultColorScaleColor)]
+    pub fn set_default_color_scale_color(&mut self) {
+        let scale = crate::DEFAULT_COLOR_SCALE();
+
+        let color_scale = ColorScale {
+            color_space: ColorSpace::SRgb,
+            scale,
+        };
+        self.operations
+            .push(StateTransactionOperation::SetColorScale { color_scale });
+    }
+
+    #[wasm_bindgen(js_name = setColorScaleNamed)]
+    pub fn set_color_scale_named(&mut self, name: &str) {
+        let scale = color_scale::ColorScaleDescriptor::Named(name.to_string().into());
+
+        let color_scale = ColorScale {
+            color_space: ColorSpace::Xyz,
+            scale,
+        };
+        self.operations
+            .push(StateTransactionOperation::SetColorScale { color_scale });
+    }
+
+    #[wasm_bindgen(js_name = setColorScaleConstant)]
+    pub fn set_color_scale_constant(&mut self, color: ColorDescription) {
+        let ColorDescription {
+            color_space,
+            values,
+            alpha,
+        } = color;
+
+        let color = match color_space {
+            ColorSpace::SRgb => colors::ColorQuery::SRgb(values, alpha),
+            ColorSpace::Xyz => colors::ColorQuery::Xyz(values, alpha),
+            ColorSpace::CieLab => colors::ColorQuery::Lab(values, alpha),
+            ColorSpace::CieLch => colors::ColorQuery::Lch(values, alpha),
+            ColorSpace::Oklab => colors::ColorQuery::Oklab(values, alpha),
+            ColorSpace::Oklch => colors::ColorQuery::Oklch(values, alpha),
+        };
+        let scale = color_scale::ColorScaleDescriptor::Constant(color);
+
+        let color_scale = ColorScale {
+            color_space: ColorSpace::SRgb,
+            scale,
+        };
+        self.operations
+            .push(StateTransactionOperation::SetColorScale { color_scale });
+    }
+
+    #[wasm_bindgen(js_name = setColorScaleGradient)]
+    pub fn set_color_scale_gradient(&mut self, scale: ColorScaleDescription) {
+        let color_scale = scale.into_color_scale();
+        self.operations
+            .push(StateTransactionOperation::SetColorScale { color_scale });
+    }
+
+    #[wasm_bindgen(js_name = setDefaultSelectedDataColorMode)]
+    pub fn set_default_selected_data_color_mode(&mut self) {
+        self.operations
+            .push(StateTransactionOperation::SetDataColorMode {
+                color_mode: crate::DEFAULT_DATA_COLOR_MODE(),
+            });
+    }
+
+    #[wasm_bindgen(js_name = setSelectedDataColorModeConstant)]
+    pub fn set_selected_data_color_mode_constant(&mut self, value: f32) {
+        if !(0.0..=1.0).contains(&value) {
+            panic!("constant must lie in the interval [0, 1], got '{value}'");
+        }
+
+        self.operations
+            .push(StateTransactionOperation::SetDataColorMode {
+                color_mode: DataColorMode::Constant(value),
+            });
+    }
+
+    #[wasm_bindgen(js_name = setSelectedDataColorModeAttribute)]
+    pub fn set_selected_data_color_mode_attribute(&mut self, id: &str) {
+        self.operations
+            .push(StateTransactionOperation::SetDataColorMode {
+                color_mode: DataColorMode::Attribute(id.into()),
+            });
+    }
+
+    #[wasm_bindgen(js_name = setSelectedDataColorModeAttributeDensity)]
+    pub fn set_selected_data_color_mode_attribute_density(&mut self, id: &str) {
+        self.operations
+            .push(StateTransactionOperation::SetDataColorMode {
+                color_mode: DataColorMode::AttributeDensity(id.into()),
+            });
+    }
+
+    #[wasm_bindgen(js_name = setSelectedDataColorModeProbability)]
+    pub fn set_selected_data_color_mode_probability(&mut self) {
+        self.operations
+            .push(StateTransactionOperation::SetDataColorMode {
+                color_mode: DataColorMode::Probability,
+            });
+    }
+
+    /// Colors the data by the cluster computed with [`Self::compute_clusters`].
+    #[wasm_bindgen(js_name = setSelectedDataColorModeClusters)]
+    pub fn set_selected_data_color_mode_clusters(&mut self) {
+        self.operations
+            .push(StateTransactionOperation::SetDataColorMode {
+                color_mode: DataColorMode::Attribute(crate::CLUSTER_AXIS_KEY.into()),
+            });
+    }
+
+    /// Colors the data by the outlier scores computed with
+    /// [`Self::compute_outlier_scores`].
+    #[wasm_bindgen(js_name = setSelectedDataColorModeOutlierScores)]
+    pub fn set_selected_data_color_mode_outlier_scores(&mut self) {
+        self.operations
+            .push(StateTransactionOperation::SetDataColorMode {
+                color_mode: DataColorMode::Attribute(crate::OUTLIER_AXIS_KEY.into()),
+            });
+    }
+
+    /// Colors the data by the maximum probability of the labels of a group.
+    #[wasm_bindgen(js_name = setSelectedDataColorModeGroupProbability)]
+    pub fn set_selected_data_color_mode_group_probability(&mut self, group: &str) {
+        self.operations
+            .push(StateTransactionOperation::SetDataColorMode {
+                color_mode: DataColorMode::GroupProbability(group.into()),
+            });
+    }
+
+    #[wasm_bindgen(js_name = setColorBarVisibility)]
+    pub fn set_color_bar_visibility(&mut self, visibility: bool) {
+        self.operations
+            .push(StateTransactionOperation::SetColorBarVisibility { visibility });
+    }
+
+    /// Attaches the color bar to an edge of the plot.
+    #[wasm_bindgen(js_name = setColorBarEdge)]
+    pub fn set_color_bar_edge(&mut self, edge: ColorBarEdge) {
+        let placement = match edge {
+            ColorBarEdge::Left => color_bar::ColorBarPlacement::Left,
+            ColorBarEdge::Right => color_bar::ColorBarPlacement::Right,
+            ColorBarEdge::Top => color_bar::ColorBarPlacement::Top,
+            ColorBarEdge::Bottom => color_bar::ColorBarPlacement::Bottom,
+        };
+        self.operations
+            .push(StateTransactionOperation::SetColorBarPlacement { placement });
+    }
+
+    /// Lets the color bar float above the plot, with its top-left corner
+    /// at the given position, in CSS pixels. A floating color bar can be
+    /// dragged by the user.
+    #[wasm_bindgen(js_name = setColorBarFloating)]
+    pub fn set_color_bar_floating(&mut self, x: f32, y: f32) {
+        self.operations
+            .push(StateTransactionOperation::SetColorBarPlacement {
+                placement: color_bar::ColorBarPlacement::Floating { x, y },
+            });
+    }
+
+    /// Sets the ticks and the unit of the color bar.
+    #[wasm_bindgen(js_name = setColorBarOptions)]
+    pub fn set_color_bar_options(&mut self, options: ColorBarOptions) {
+        self.operations
+            .push(StateTransactionOperation::SetColorBarOptions { options });
+    }
+
+    /// Reserves a header slot of `height` rem above each axis.
+    ///
+    /// The slots are left empty, such that the host application can place its
+    /// own controls inside of them. Their rectangles are reported through the
+    /// `layout` diff, and the pointer events inside of them are forwarded
+    /// through the `header_pointer` diff, instead of interacting with the plot.
+    /// A height of `0` removes the slots.
+    #[wasm_bindgen(js_name = setAxisHeaderHeight)]
+    pub fn set_axis_header_height(&mut self, height: f32) {
+        self.operations
+            .push(StateTransactionOperation::SetAxisHeaderHeight { height });
+    }
+
+    /// Sets the width of the area showing the probability curves of an
+    /// expanded axis, relative to the distance between two axes.
+    ///
+    /// The width must lie in `(0, 0.5]`, and defaults to `0.4`.
+    #[wasm_bindgen(js_name = setCurveAreaWidth)]
+    pub fn set_curve_area_width(&mut self, width: f32) {
+        self.operations
+            .push(StateTransactionOperation::SetCurveAreaWidth { width });
+    }
+
+    /// Shows the selections of all labels, instead of only the active one.
+    #[wasm_bindgen(js_name = setAllSelectionsVisibility)]
+    pub fn set_all_selections_visibility(&mut self, visibility: bool) {
+        self.operations
+            .push(StateTransactionOperation::SetAllSelectionsVisibility { visibility });
+    }
+
+    /// Includes the agreement of each pair of labels in the probabilities
+    /// diff, i.e., the Jaccard index of their selections and the correlation
+    /// of their probabilities.
+    #[wasm_bindgen(js_name = setLabelAgreementMetrics)]
+    pub fn set_label_agreement_metrics(&mut self, enabled: bool) {
+        self.operations
+            .push(StateTransactionOperation::SetLabelAgreementMetrics { enabled });
+    }
+
+    /// Blends the colors in linear space instead of the presentation format,
+    /// which avoids darkening many overlapping translucent lines.
+    #[wasm_bindgen(js_name = setLinearBlending)]
+    pub fn set_linear_blending(&mut self, enabled: bool) {
+        self.operations
+            .push(StateTransactionOperation::SetLinearBlending { enabled });
+    }
+
+    /// Snaps the axis and selection lines to the centers of the device
+    /// pixels, such that thin lines don't shimmer at fractional positions.
+    /// The data lines are not snapped.
+    #[wasm_bindgen(js_name = setPixelSnapping)]
+    pub fn set_pixel_snapping(&mut self, enabled: bool) {
+        self.operations
+            .push(StateTransactionOperation::SetPixelSnapping { enabled });
+    }
+
+    #[wasm_bindgen(js_name = setAntialiasing)]
+    pub fn set_antialiasing(&mut self, mode: Antialiasing) {
+        self.operations
+            .push(StateTransactionOperation::SetAntialiasing { mode });
+    }
+
+    #[wasm_bindgen(js_name = setDataLineJoin)]
+    pub fn set_data_line_join(&mut self, join: DataLineJoin) {
+        self.operations
+            .push(StateTransactionOperation::SetDataLineJoin { join });
+    }
+
+    #[wasm_bindgen(js_name = setCanvasAlphaMode)]
+    pub fn set_canvas_alpha_mode(&mut self, mode: CanvasAlphaMode) {
+        self.operations
+            .push(StateTransactionOperation::SetCanvasAlphaMode { mode });
+    }
+
+    #[wasm_bindgen(js_name = setBackgroundLayer)]
+    pub fn set_background_layer(&mut self, layer: BackgroundLayer) {
+        self.operations
+            .push(StateTransactionOperation::SetBackgroundLayer { layer: Some(layer) });
+    }
+
+    #[wasm_bindgen(js_name = clearBackgroundLayer)]
+    pub fn clear_background_layer(&mut self) {
+        self.operations
+            .push(StateTransactionOperation::SetBackgroundLayer { layer: None });
+    }
+
+    #[wasm_bindgen(js_name = addAnnotation)]
+    pub fn add_annotation(&mut self, id: String, annotation: Annotation) {
+        self.operations
+            .push(StateTransactionOperation::AddAnnotation { id, annotation });
+    }
+
+    #[wasm_bindgen(js_name = removeAnnotation)]
+    pub fn remove_annotation(&mut self, id: String) {
+        self.operations
+            .push(StateTransactionOperation::RemoveAnnotation { id });
+    }
+
+    /// Adds a shaded band spanning the values `[start, end]` of an axis,
+    /// e.g., to highlight a safe operating range.
+    ///
+    /// The region is drawn behind the data and the selections, and is
+    /// unaffected by brushing.
+    #[wasm_bindgen(js_name = addAxisRegion)]
+    pub fn add_axis_region(
+        &mut self,
+        id: String,
+        axis: String,
+        start: f32,
+        end: f32,
+        label: Option<String>,
+        color: Option<ColorDescription>,
+    ) {
+        let color = color.map(|color| {
+            let ColorDescription {
+                color_space,
+                values,
+                alpha,
+            } = color;
+
+            match color_space {
+                ColorSpace::SRgb => colors::ColorQuery::SRgb(values, alpha),
+                ColorSpace::Xyz => colors::ColorQuery::Xyz(values, alpha),
+                ColorSpace::CieLab => colors::ColorQuery::Lab(values, alpha),
+                ColorSpace::CieLch => colors::ColorQuery::Lch(values, alpha),
+                ColorSpace::Oklab => colors::ColorQuery::Oklab(values, alpha),
+                ColorSpace::Oklch => colors::ColorQuery::Oklch(values, alpha),
+            }
+        });
+
+        let region = AxisRegion {
+            axis,
+            range: (start, end),
+            label,
+            color,
+        };
+        self.operations
+            .push(StateTransactionOperation::AddAxisRegion { id, region });
+    }
+
+    #[wasm_bindgen(js_name = removeAxisRegion)]
+    pub fn remove_axis_region(&mut self, id: String) {
+        self.operations
+            .push(StateTransactionOperation::RemoveAxisRegion { id });
     }
 
-    #[wasm_bindgen(js_name = setDrawOrder)]
-    pub fn set_draw_order(&mut self, order: DrawOrder) {
+    /// Draws ribbons between adjacent categorical axes, whose widths are
+    /// proportional to the number of selected rows taking each pair of
+    /// categories, like in an alluvial diagram.
+    ///
+    /// Only axes with at most 16 distinct values are considered categorical.
+    #[wasm_bindgen(js_name = setFlowRibbons)]
+    pub fn set_flow_ribbons(&mut self, enabled: bool) {
         self.operations
-            .push(StateTransactionOperation::SetDrawOrder { order });
+            .push(StateTransactionOperation::SetFlowRibbons { enabled });
     }
 
-    #[wasm_bindgen(js_name = setDefaultColorScaleColor)]
-    pub fn set_default_color_scale_color(&mut self) {
-        let scale = crate::DEFAULT_COLOR_SCALE();
+    /// Draws the mean polyline of the selected rows of each label on top of
+    /// the data, where each row is weighted by its probability.
+    #[wasm_bindgen(js_name = setLabelMeanMode)]
+    pub fn set_label_mean_mode(&mut self, mode: LabelMeanMode) {
+        self.operations
+            .push(StateTransactionOperation::SetLabelMeanMode { mode });
+    }
 
-        let color_scale = ColorScale {
-            color_space: ColorSpace::SRgb,
-            scale,
+    /// Partitions the data into `k` clusters with the k-means algorithm,
+    /// using the normalized values of the visible axes.
+    ///
+    /// The clusters are added as the synthetic axis `__clusters`, which can
+    /// also be used for coloring the data.
+    #[wasm_bindgen(js_name = computeClusters)]
+    pub fn compute_clusters(&mut self, k: usize, max_iterations: Option<usize>) {
+        let options = ClusterOptions {
+            k,
+            max_iterations: max_iterations.unwrap_or(32),
         };
         self.operations
-            .push(StateTransactionOperation::SetColorScale { color_scale });
+            .push(StateTransactionOperation::SetClusters {
+                options: Some(options),
+            });
     }
 
-    #[wasm_bindgen(js_name = setColorScaleNamed)]
-    pub fn set_color_scale_named(&mut self, name: &str) {
-        let scale = color_scale::ColorScaleDescriptor::Named(name.to_string().into());
+    /// Removes the clusters, together with their synthetic axis.
+    #[wasm_bindgen(js_name = clearClusters)]
+    pub fn clear_clusters(&mut self) {
+        self.operations
+            .push(StateTransactionOperation::SetClusters { options: None });
+    }
 
-        let color_scale = ColorScale {
-            color_space: ColorSpace::Xyz,
-            scale,
+    /// Computes the outlier score of each data point, i.e. the largest
+    /// absolute z-score of its values on the visible axes.
+    ///
+    /// The scores are added as the synthetic axis `__outlier_scores`, and are
+    /// recomputed whenever the data of the axes changes. If a label is given,
+    /// it is created if necessary, and selects the data points whose score is
+    /// at least the threshold, which defaults to `3`.
+    #[wasm_bindgen(js_name = computeOutlierScores)]
+    pub fn compute_outlier_scores(&mut self, threshold: Option<f32>, label: Option<String>) {
+        let options = OutlierOptions {
+            threshold: threshold.unwrap_or(3.0),
+            label,
         };
         self.operations
-            .push(StateTransactionOperation::SetColorScale { color_scale });
+            .push(StateTransactionOperation::SetOutlierScores {
+                options: Some(options),
+            });
     }
 
-    #[wasm_bindgen(js_name = setColorScaleConstant)]
-    pub fn set_color_scale_constant(&mut self, color: ColorDescription) {
-        let ColorDescription {
-            color_space,
-            values,
-            alpha,
-        } = color;
-
-        let color = match color_space {
-            ColorSpace::SRgb => colors::ColorQuery::SRgb(values, alpha),
-            ColorSpace::Xyz => colors::ColorQuery::Xyz(values, alpha),
-            ColorSpace::CieLab => colors::ColorQuery::Lab(values, alpha),
-            ColorSpace::CieLch => colors::ColorQuery::Lch(values, alpha),
-        };
-        let scale = color_scale::ColorScaleDescriptor::Constant(color);
+    /// Removes the outlier scores, together with their synthetic axis. The
+    /// label of the outliers is kept, but no longer selects any data point.
+    #[wasm_bindgen(js_name = clearOutlierScores)]
+    pub fn clear_outlier_scores(&mut self) {
+        self.operations
+            .push(StateTransactionOperation::SetOutlierScores { options: None });
+    }
 
-        let color_scale = ColorScale {
-            color_space: ColorSpace::SRgb,
-            scale,
+    /// Draws a kernel density estimate of the data next to each visible axis.
+    ///
+    /// The bandwidth of the gaussian kernel is given relative to the visible
+    /// range of the axis, and defaults to `0.05`. If `selected` is enabled,
+    /// which is the default, a second curve shows the density of the data
+    /// points selected by the active label.
+    #[wasm_bindgen(js_name = showDensityCurves)]
+    pub fn show_density_curves(&mut self, bandwidth: Option<f32>, selected: Option<bool>) {
+        let options = DensityOptions {
+            bandwidth: bandwidth.unwrap_or(0.05),
+            selected: selected.unwrap_or(true),
         };
         self.operations
-            .push(StateTransactionOperation::SetColorScale { color_scale });
+            .push(StateTransactionOperation::SetDensityCurves {
+                options: Some(options),
+            });
     }
 
-    #[wasm_bindgen(js_name = setColorScaleGradient)]
-    pub fn set_color_scale_gradient(&mut self, scale: ColorScaleDescription) {
-        let ColorScaleDescription {
-            color_space,
-            gradient,
-        } = scale;
-        let gradient = gradient
-            .into_iter()
-            .map(|(t, color)| {
-                let ColorDescription {
-                    color_space,
-                    values,
-                    alpha,
-                } = color;
-
-                let color = match color_space {
-                    ColorSpace::SRgb => colors::ColorQuery::SRgb(values, alpha),
-                    ColorSpace::Xyz => colors::ColorQuery::Xyz(values, alpha),
-                    ColorSpace::CieLab => colors::ColorQuery::Lab(values, alpha),
-                    ColorSpace::CieLch => colors::ColorQuery::Lch(values, alpha),
-                };
-
-                (t, color)
-            })
-            .collect::<Vec<_>>();
-
-        let scale = color_scale::ColorScaleDescriptor::Gradient(gradient);
-        let color_scale = ColorScale { color_space, scale };
+    /// Hides the density curves of the axes.
+    #[wasm_bindgen(js_name = hideDensityCurves)]
+    pub fn hide_density_curves(&mut self) {
         self.operations
-            .push(StateTransactionOperation::SetColorScale { color_scale });
+            .push(StateTransactionOperation::SetDensityCurves { options: None });
     }
 
-    #[wasm_bindgen(js_name = setDefaultSelectedDataColorMode)]
-    pub fn set_default_selected_data_color_mode(&mut self) {
+    /// Draws a histogram of the data next to each visible axis.
+    ///
+    /// The visible range of each axis is split into `num_bins` bins, which
+    /// defaults to `16`. The bars of the data points selected by the active
+    /// label, weighted by their probability, are drawn over the bars of all
+    /// data points, such that the share of the selection is visible per bin.
+    /// Clicking or dragging over the bars creates a brush spanning the bins.
+    #[wasm_bindgen(js_name = showHistograms)]
+    pub fn show_histograms(&mut self, num_bins: Option<u32>) {
+        let options = HistogramOptions {
+            num_bins: num_bins.unwrap_or(16),
+        };
         self.operations
-            .push(StateTransactionOperation::SetDataColorMode {
-                color_mode: crate::DEFAULT_DATA_COLOR_MODE(),
+            .push(StateTransactionOperation::SetHistograms {
+                options: Some(options),
             });
     }
 
-    #[wasm_bindgen(js_name = setSelectedDataColorModeConstant)]
-    pub fn set_selected_data_color_mode_constant(&mut self, value: f32) {
-        if !(0.0..=1.0).contains(&value) {
-            panic!("constant must lie in the interval [0, 1], got '{value}'");
-        }
+    /// Hides the histograms of the axes.
+    #[wasm_bindgen(js_name = hideHistograms)]
+    pub fn hide_histograms(&mut self) {
+        self.operations
+            .push(StateTransactionOperation::SetHistograms { options: None });
+    }
 
+    /// Enables the live mode for streaming data.
+    ///
+    /// The points appended with `appendAxisPoints` are buffered and applied
+    /// at most once every `interval` milliseconds. If `max_points` is set, the
+    /// oldest data points are evicted once the plot contains more points.
+    /// The buffered points are applied without waiting for a draw request.
+    #[wasm_bindgen(js_name = enableLiveMode)]
+    pub fn enable_live_mode(&mut self, interval: f64, max_points: Option<usize>) {
+        let options = LiveOptions {
+            interval,
+            max_points,
+        };
         self.operations
-            .push(StateTransactionOperation::SetDataColorMode {
-                color_mode: DataColorMode::Constant(value),
+            .push(StateTransactionOperation::SetLiveMode {
+                options: Some(options),
             });
     }
 
-    #[wasm_bindgen(js_name = setSelectedDataColorModeAttribute)]
-    pub fn set_selected_data_color_mode_attribute(&mut self, id: &str) {
+    /// Disables the live mode, applying the buffered points immediately.
+    #[wasm_bindgen(js_name = disableLiveMode)]
+    pub fn disable_live_mode(&mut self) {
         self.operations
-            .push(StateTransactionOperation::SetDataColorMode {
-                color_mode: DataColorMode::Attribute(id.into()),
-            });
+            .push(StateTransactionOperation::SetLiveMode { options: None });
     }
 
-    #[wasm_bindgen(js_name = setSelectedDataColorModeAttributeDensity)]
-    pub fn set_selected_data_color_mode_attribute_density(&mut self, id: &str) {
+    /// Stops applying the appended points of the live mode, such that the
+    /// user can brush a stable snapshot of the data. The points are queued
+    /// until the data is resumed.
+    #[wasm_bindgen(js_name = freezeLiveData)]
+    pub fn freeze_live_data(&mut self) {
         self.operations
-            .push(StateTransactionOperation::SetDataColorMode {
-                color_mode: DataColorMode::AttributeDensity(id.into()),
-            });
+            .push(StateTransactionOperation::SetLiveDataFrozen { frozen: true });
     }
 
-    #[wasm_bindgen(js_name = setSelectedDataColorModeProbability)]
-    pub fn set_selected_data_color_mode_probability(&mut self) {
+    /// Resumes the live mode, applying all queued points at once.
+    #[wasm_bindgen(js_name = resumeLiveData)]
+    pub fn resume_live_data(&mut self) {
         self.operations
-            .push(StateTransactionOperation::SetDataColorMode {
-                color_mode: DataColorMode::Probability,
-            });
+            .push(StateTransactionOperation::SetLiveDataFrozen { frozen: false });
     }
 
-    #[wasm_bindgen(js_name = setColorBarVisibility)]
-    pub fn set_color_bar_visibility(&mut self, visibility: bool) {
+    /// Clears the plot to a fully transparent background, such that the
+    /// page is visible behind the plot and its text overlay.
+    #[wasm_bindgen(js_name = setTransparentBackground)]
+    pub fn set_transparent_background(&mut self) {
+        let color = colors::ColorQuery::SRgb([0.0; 3], Some(0.0));
         self.operations
-            .push(StateTransactionOperation::SetColorBarVisibility { visibility });
+            .push(StateTransactionOperation::SetBackgroundColor { color });
+        self.operations
+            .push(StateTransactionOperation::SetCanvasAlphaMode {
+                mode: CanvasAlphaMode::Premultiplied,
+            });
     }
 
     #[wasm_bindgen(js_name = addLabel)]
@@ -644,6 +1942,8 @@ pub fn add_label(
                 ColorSpace::Xyz => colors::ColorQuery::Xyz(values, alpha),
                 ColorSpace::CieLab => colors::ColorQuery::Lab(values, alpha),
                 ColorSpace::CieLch => colors::ColorQuery::Lch(values, alpha),
+                ColorSpace::Oklab => colors::ColorQuery::Oklab(values, alpha),
+                ColorSpace::Oklch => colors::ColorQuery::Oklch(values, alpha),
             }
         });
         let selection_bounds = if has_selection_bounds {
@@ -660,7 +1960,7 @@ pub fn add_label(
             Some("out") => selection::EasingType::EaseOut,
             Some("inout") => selection::EasingType::EaseInOut,
             _ => {
-                web_sys::console::warn_1(&format!("unknown easing {easing_type:?}").into());
+                logging::warn(&format!("unknown easing {easing_type:?}"));
                 selection::EasingType::Linear
             }
         };
@@ -670,6 +1970,7 @@ pub fn add_label(
             color,
             selection_bounds,
             easing: Some(easing),
+            visible: None,
         };
         self.operations
             .push(StateTransactionOperation::AddLabel { label });
@@ -694,6 +1995,8 @@ pub fn set_label_color(&mut self, label: String, color: ColorDescription) {
             ColorSpace::Xyz => colors::ColorQuery::Xyz(values, alpha),
             ColorSpace::CieLab => colors::ColorQuery::Lab(values, alpha),
             ColorSpace::CieLch => colors::ColorQuery::Lch(values, alpha),
+            ColorSpace::Oklab => colors::ColorQuery::Oklab(values, alpha),
+            ColorSpace::Oklch => colors::ColorQuery::Oklch(values, alpha),
         };
 
         let update = LabelColorUpdate { label, color };
@@ -729,7 +2032,7 @@ pub fn set_label_easing(&mut self, id: String, easing_type: Option<String>) {
             Some("out") => selection::EasingType::EaseOut,
             Some("inout") => selection::EasingType::EaseInOut,
             _ => {
-                web_sys::console::warn_1(&format!("unknown easing {easing_type:?}").into());
+                logging::warn(&format!("unknown easing {easing_type:?}"));
                 selection::EasingType::Linear
             }
         };
@@ -739,12 +2042,218 @@ pub fn set_label_easing(&mut self, id: String, easing_type: Option<String>) {
             .push(StateTransactionOperation::SetLabelEasing { update });
     }
 
+    /// Shows or hides the selections and curves of a label, retaining its state.
+    #[wasm_bindgen(js_name = setLabelVisibility)]
+    pub fn set_label_visibility(&mut self, id: String, visible: bool) {
+        let update = LabelVisibilityUpdate { id, visible };
+        self.operations
+            .push(StateTransactionOperation::SetLabelVisibility { update });
+    }
+
     #[wasm_bindgen(js_name = switchActiveLabel)]
     pub fn switch_active_label(&mut self, id: Option<String>) {
         self.operations
             .push(StateTransactionOperation::SwitchActiveLabel { id });
     }
 
+    /// Captures the data points currently selected by a label as a frozen
+    /// snapshot, and removes the brushes of the label.
+    ///
+    /// The snapshot is drawn in the dimmed color of the label, and replaces
+    /// any previous snapshot of the label.
+    #[wasm_bindgen(js_name = freezeLabelSelection)]
+    pub fn freeze_label_selection(&mut self, label: String) {
+        self.operations
+            .push(StateTransactionOperation::FreezeLabel { label });
+    }
+
+    /// Discards the frozen snapshot of a label.
+    #[wasm_bindgen(js_name = discardLabelSnapshot)]
+    pub fn discard_label_snapshot(&mut self, label: String) {
+        self.operations
+            .push(StateTransactionOperation::DiscardLabelSnapshot { label });
+    }
+
+    /// Adds a group of labels.
+    ///
+    /// The labels of the group are drawn in the color of the group, unless
+    /// they were assigned a color of their own.
+    #[wasm_bindgen(js_name = addLabelGroup)]
+    pub fn add_label_group(&mut self, id: String, color: Option<ColorDescription>) {
+        let color = color.map(|color| {
+            let ColorDescription {
+                color_space,
+                values,
+                alpha,
+            } = color;
+
+            match color_space {
+                ColorSpace::SRgb => colors::ColorQuery::SRgb(values, alpha),
+                ColorSpace::Xyz => colors::ColorQuery::Xyz(values, alpha),
+                ColorSpace::CieLab => colors::ColorQuery::Lab(values, alpha),
+                ColorSpace::CieLch => colors::ColorQuery::Lch(values, alpha),
+                ColorSpace::Oklab => colors::ColorQuery::Oklab(values, alpha),
+                ColorSpace::Oklch => colors::ColorQuery::Oklch(values, alpha),
+            }
+        });
+
+        let group = LabelGroup { id, color };
+        self.operations
+            .push(StateTransactionOperation::AddLabelGroup { group });
+    }
+
+    /// Removes a group of labels, retaining its labels.
+    #[wasm_bindgen(js_name = removeLabelGroup)]
+    pub fn remove_label_group(&mut self, group: String) {
+        self.operations
+            .push(StateTransactionOperation::RemoveLabelGroup { group });
+    }
+
+    /// Assigns a label to a group, or removes it from its group.
+    #[wasm_bindgen(js_name = setLabelGroup)]
+    pub fn set_label_group(&mut self, label: String, group: Option<String>) {
+        self.operations
+            .push(StateTransactionOperation::SetLabelGroup { label, group });
+    }
+
+    /// Overlays the probability curves of two labels on the expanded axes,
+    /// shading the areas where they diverge.
+    #[wasm_bindgen(js_name = compareLabelCurves)]
+    pub fn compare_label_curves(&mut self, label_a: String, label_b: String) {
+        self.operations
+            .push(StateTransactionOperation::SetCurveComparison {
+                labels: Some((label_a, label_b)),
+            });
+    }
+
+    /// Stops comparing the probability curves of two labels.
+    #[wasm_bindgen(js_name = clearLabelCurveComparison)]
+    pub fn clear_label_curve_comparison(&mut self) {
+        self.operations
+            .push(StateTransactionOperation::SetCurveComparison { labels: None });
+    }
+
+    /// Moves a brush by `delta`, in the units of the axis.
+    #[wasm_bindgen(js_name = nudgeBrush)]
+    pub fn nudge_brush(&mut self, label: String, axis: String, index: usize, delta: f32) {
+        self.operations.push(StateTransactionOperation::EditBrush {
+            edit: BrushEdit {
+                label,
+                axis,
+                index,
+                kind: BrushEditKind::Nudge { delta },
+            },
+        });
+    }
+
+    /// Moves and scales a brush, such that it spans the range `[lower, upper]`,
+    /// in the units of the axis.
+    #[wasm_bindgen(js_name = setBrushBounds)]
+    pub fn set_brush_bounds(
+        &mut self,
+        label: String,
+        axis: String,
+        index: usize,
+        lower: f32,
+        upper: f32,
+    ) {
+        self.operations.push(StateTransactionOperation::EditBrush {
+            edit: BrushEdit {
+                label,
+                axis,
+                index,
+                kind: BrushEditKind::SetBounds { lower, upper },
+            },
+        });
+    }
+
+    /// Replaces the probability curve of a label on an axis with samples,
+    /// evenly spaced over the data range of the axis.
+    ///
+    /// The curve bypasses the brushes of the label, which are removed from
+    /// the axis, and is kept until the axis is brushed again.
+    #[wasm_bindgen(js_name = importCurveSamples)]
+    pub fn import_curve_samples(&mut self, label: String, axis: String, samples: Box<[f32]>) {
+        self.operations
+            .push(StateTransactionOperation::ImportCurve {
+                import: CurveImport {
+                    label,
+                    axis,
+                    curve: ProbabilityCurve::Samples(samples),
+                },
+            });
+    }
+
+    /// Replaces the probability curve of a label on an axis with spline
+    /// segments, in the format returned by `exportCurves`.
+    ///
+    /// The segments are flattened, i.e., each segment consists of two values
+    /// of `bounds`, two values of `t_ranges` and four `coefficients`.
+    #[wasm_bindgen(js_name = importCurveSegments)]
+    pub fn import_curve_segments(
+        &mut self,
+        label: String,
+        axis: String,
+        bounds: Box<[f32]>,
+        t_ranges: Box<[f32]>,
+        coefficients: Box<[f32]>,
+    ) {
+        self.operations
+            .push(StateTransactionOperation::ImportCurve {
+                import: CurveImport {
+                    label,
+                    axis,
+                    curve: ProbabilityCurve::Segments {
+                        bounds,
+                        t_ranges,
+                        coefficients,
+                    },
+                },
+            });
+    }
+
+    /// Restores the brushes of a label to a step of its edit history, as
+    /// returned by `labelHistory`.
+    ///
+    /// The brushes of the other labels are kept, and the revert is recorded
+    /// as a new step of the history.
+    #[wasm_bindgen(js_name = revertLabelTo)]
+    pub fn revert_label_to(&mut self, label: String, step: usize) {
+        self.operations
+            .push(StateTransactionOperation::RevertLabel { label, step });
+    }
+
+    /// Starts reporting the changes of the brushes and of the axis order as
+    /// compact operations, which can be applied to the plots of other clients
+    /// with `applyRemoteOperation`.
+    ///
+    /// The `client` id must be unique among the synchronized clients, which
+    /// are expected to start from the same state.
+    #[wasm_bindgen(js_name = enableOperationSync)]
+    pub fn enable_operation_sync(&mut self, client: String) {
+        self.operations
+            .push(StateTransactionOperation::SetOperationSync {
+                client: Some(client),
+            });
+    }
+
+    #[wasm_bindgen(js_name = disableOperationSync)]
+    pub fn disable_operation_sync(&mut self) {
+        self.operations
+            .push(StateTransactionOperation::SetOperationSync { client: None });
+    }
+
+    /// Applies an operation reported by another client.
+    ///
+    /// Conflicting operations are resolved by their timestamps, such that all
+    /// clients converge to the same state, independent of the order in which
+    /// the operations are applied.
+    #[wasm_bindgen(js_name = applyRemoteOperation)]
+    pub fn apply_remote_operation(&mut self, operation: api::SyncOperation) {
+        self.operations
+            .push(StateTransactionOperation::ApplyRemoteOperation { operation });
+    }
+
     #[wasm_bindgen(js_name = setBrushes)]
     pub fn set_brushes(&mut self, brushes: &js_sys::Object) {
         let mut brush_map = BTreeMap::default();
@@ -805,8 +2314,7 @@ pub fn set_brushes(&mut self, brushes: &js_sys::Object) {
             }
         }
 
-        self.operations
-            .push(StateTransactionOperation::SetBrushes { brushes: brush_map });
+        self.set_brush_map(brush_map);
     }
 
     #[wasm_bindgen(js_name = setInteractionMode)]
@@ -821,20 +2329,97 @@ pub fn set_debug_options(&mut self, options: DebugOptions) {
             .push(StateTransactionOperation::SetDebugOptions { options })
     }
 
+    #[wasm_bindgen(js_name = setHitTestOptions)]
+    pub fn set_hit_test_options(&mut self, options: HitTestOptions) {
+        self.operations
+            .push(StateTransactionOperation::SetHitTestOptions { options })
+    }
+
+    /// Sets the radius and the colors of the control points of the brushes.
+    ///
+    /// The radius is given in `rem` and is enlarged for touch and pen input
+    /// by the same scale as the hit test radii.
+    #[wasm_bindgen(js_name = setControlPointOptions)]
+    pub fn set_control_point_options(&mut self, options: ControlPointOptions) {
+        self.operations
+            .push(StateTransactionOperation::SetControlPointOptions { options })
+    }
+
+    #[wasm_bindgen(js_name = setAccessibilityOptions)]
+    pub fn set_accessibility_options(&mut self, options: AccessibilityOptions) {
+        self.operations
+            .push(StateTransactionOperation::SetAccessibilityOptions { options })
+    }
+
+    #[wasm_bindgen(js_name = setLocaleOptions)]
+    pub fn set_locale_options(&mut self, options: LocaleOptions) {
+        self.operations
+            .push(StateTransactionOperation::SetLocaleOptions { options })
+    }
+
     pub fn build(self) -> StateTransaction {
         let mut axis_removals: BTreeSet<String> = Default::default();
         let mut axis_additions: BTreeMap<String, AxisDef> = Default::default();
+        let mut axis_point_changes: BTreeMap<String, Box<[f32]>> = Default::default();
+        let mut axis_point_appends: BTreeMap<String, Vec<f32>> = Default::default();
+        let mut visible_range_changes: BTreeMap<String, (f32, f32)> = Default::default();
+        let mut range_fit: Option<FitAxes> = Default::default();
+        let mut axis_jitter_changes: BTreeMap<String, f32> = Default::default();
+        let mut category_order_optimizations: BTreeSet<String> = Default::default();
+        let mut reference_clear: bool = Default::default();
+        let mut reference_points: BTreeMap<String, Box<[f32]>> = Default::default();
         let mut order_change: Option<AxisOrder> = Default::default();
+        let mut view_change: Option<ViewChange> = Default::default();
         let mut colors_change: Option<Colors> = Default::default();
         let mut color_bar_visibility_change: Option<bool> = Default::default();
+        let mut color_bar_placement_change: Option<color_bar::ColorBarPlacement> =
+            Default::default();
+        let mut color_bar_options_change: Option<ColorBarOptions> = Default::default();
+        let mut axis_header_height_change: Option<f32> = Default::default();
+        let mut curve_area_width_change: Option<f32> = Default::default();
+        let mut all_selections_visibility_change: Option<bool> = Default::default();
+        let mut label_agreement_metrics_change: Option<bool> = Default::default();
+        let mut linear_blending_change: Option<bool> = Default::default();
+        let mut pixel_snapping_change: Option<bool> = Default::default();
+        let mut antialiasing_change: Option<Antialiasing> = Default::default();
+        let mut data_line_join_change: Option<DataLineJoin> = Default::default();
+        let mut canvas_alpha_mode_change: Option<CanvasAlphaMode> = Default::default();
+        let mut background_layer_change: Option<Option<BackgroundLayer>> = Default::default();
+        let mut annotation_removals: BTreeSet<String> = Default::default();
+        let mut annotation_additions: BTreeMap<String, Annotation> = Default::default();
+        let mut axis_region_removals: BTreeSet<String> = Default::default();
+        let mut axis_region_additions: BTreeMap<String, AxisRegion> = Default::default();
+        let mut flow_ribbons_change: Option<bool> = Default::default();
+        let mut label_mean_mode_change: Option<LabelMeanMode> = Default::default();
+        let mut clusters_change: Option<Option<ClusterOptions>> = Default::default();
+        let mut outlier_scores_change: Option<Option<OutlierOptions>> = Default::default();
+        let mut density_curves_change: Option<Option<DensityOptions>> = Default::default();
+        let mut histograms_change: Option<Option<HistogramOptions>> = Default::default();
+        let mut live_mode_change: Option<Option<LiveOptions>> = Default::default();
+        let mut live_freeze_change: Option<bool> = Default::default();
         let mut label_removals: BTreeSet<String> = Default::default();
         let mut label_additions: BTreeMap<String, Label> = Default::default();
         let mut label_updates: BTreeMap<String, Label> = Default::default();
         let mut active_label_change: Option<Option<String>> = Default::default();
+        let mut label_freezes: BTreeSet<String> = Default::default();
+        let mut snapshot_removals: BTreeSet<String> = Default::default();
+        let mut label_group_removals: BTreeSet<String> = Default::default();
+        let mut label_group_additions: BTreeMap<String, LabelGroup> = Default::default();
+        let mut label_group_changes: BTreeMap<String, Option<String>> = Default::default();
+        let mut curve_comparison_change: Option<Option<(String, String)>> = Default::default();
         let mut brushes_change: Option<BTreeMap<String, BTreeMap<String, Vec<Brush>>>> =
             Default::default();
+        let mut brush_edits: Vec<BrushEdit> = Default::default();
+        let mut curve_imports: Vec<CurveImport> = Default::default();
+        let mut label_reverts: BTreeMap<String, usize> = Default::default();
+        let mut operation_sync_change: Option<Option<String>> = Default::default();
+        let mut remote_operations: Vec<api::SyncOperation> = Default::default();
         let mut interaction_mode_change: Option<InteractionMode> = Default::default();
         let mut debug_options_change: Option<DebugOptions> = Default::default();
+        let mut hit_test_options_change: Option<HitTestOptions> = Default::default();
+        let mut control_point_options_change: Option<ControlPointOptions> = Default::default();
+        let mut accessibility_options_change: Option<AccessibilityOptions> = Default::default();
+        let mut locale_options_change: Option<LocaleOptions> = Default::default();
 
         for op in self.operations {
             match op {
@@ -844,9 +2429,44 @@ pub fn build(self) -> StateTransaction {
                 StateTransactionOperation::AddAxis { axis } => {
                     axis_additions.insert(axis.key.clone().into(), axis);
                 }
+                StateTransactionOperation::SetAxisPoints { axis, points } => {
+                    axis_point_changes.insert(axis, points);
+                }
+                StateTransactionOperation::AppendAxisPoints { axis, points } => {
+                    axis_point_appends.entry(axis).or_default().extend(&*points);
+                }
+                StateTransactionOperation::SetVisibleRange { axis, range } => {
+                    visible_range_changes.insert(axis, range);
+                }
+                StateTransactionOperation::FitVisibleRange { axis } => {
+                    range_fit = match (range_fit.take(), axis) {
+                        (Some(FitAxes::All), _) | (_, None) => Some(FitAxes::All),
+                        (Some(FitAxes::Axes(mut axes)), Some(axis)) => {
+                            axes.insert(axis);
+                            Some(FitAxes::Axes(axes))
+                        }
+                        (None, Some(axis)) => Some(FitAxes::Axes(BTreeSet::from([axis]))),
+                    };
+                }
+                StateTransactionOperation::SetAxisJitter { axis, amplitude } => {
+                    axis_jitter_changes.insert(axis, amplitude);
+                }
+                StateTransactionOperation::OptimizeCategoryOrder { axis } => {
+                    category_order_optimizations.insert(axis);
+                }
+                StateTransactionOperation::SetReferencePoints { axis, points } => {
+                    reference_points.insert(axis, points);
+                }
+                StateTransactionOperation::ClearReferenceData => {
+                    reference_clear = true;
+                    reference_points.clear();
+                }
                 StateTransactionOperation::SetAxisOrder { order } => {
                     order_change = Some(order);
                 }
+                StateTransactionOperation::SetView { change } => {
+                    view_change = Some(change);
+                }
                 StateTransactionOperation::SetBackgroundColor { color } => {
                     let c = colors_change.get_or_insert(Colors {
                         background: None,
@@ -916,6 +2536,78 @@ pub fn build(self) -> StateTransaction {
                 StateTransactionOperation::SetColorBarVisibility { visibility } => {
                     color_bar_visibility_change = Some(visibility);
                 }
+                StateTransactionOperation::SetColorBarPlacement { placement } => {
+                    color_bar_placement_change = Some(placement);
+                }
+                StateTransactionOperation::SetColorBarOptions { options } => {
+                    color_bar_options_change = Some(options);
+                }
+                StateTransactionOperation::SetAxisHeaderHeight { height } => {
+                    axis_header_height_change = Some(height);
+                }
+                StateTransactionOperation::SetCurveAreaWidth { width } => {
+                    curve_area_width_change = Some(width);
+                }
+                StateTransactionOperation::SetAllSelectionsVisibility { visibility } => {
+                    all_selections_visibility_change = Some(visibility);
+                }
+                StateTransactionOperation::SetLabelAgreementMetrics { enabled } => {
+                    label_agreement_metrics_change = Some(enabled);
+                }
+                StateTransactionOperation::SetLinearBlending { enabled } => {
+                    linear_blending_change = Some(enabled);
+                }
+                StateTransactionOperation::SetPixelSnapping { enabled } => {
+                    pixel_snapping_change = Some(enabled);
+                }
+                StateTransactionOperation::SetAntialiasing { mode } => {
+                    antialiasing_change = Some(mode);
+                }
+                StateTransactionOperation::SetDataLineJoin { join } => {
+                    data_line_join_change = Some(join);
+                }
+                StateTransactionOperation::SetCanvasAlphaMode { mode } => {
+                    canvas_alpha_mode_change = Some(mode);
+                }
+                StateTransactionOperation::SetBackgroundLayer { layer } => {
+                    background_layer_change = Some(layer);
+                }
+                StateTransactionOperation::AddAnnotation { id, annotation } => {
+                    annotation_additions.insert(id, annotation);
+                }
+                StateTransactionOperation::RemoveAnnotation { id } => {
+                    let _ = annotation_removals.insert(id);
+                }
+                StateTransactionOperation::AddAxisRegion { id, region } => {
+                    axis_region_additions.insert(id, region);
+                }
+                StateTransactionOperation::RemoveAxisRegion { id } => {
+                    let _ = axis_region_removals.insert(id);
+                }
+                StateTransactionOperation::SetFlowRibbons { enabled } => {
+                    flow_ribbons_change = Some(enabled);
+                }
+                StateTransactionOperation::SetLabelMeanMode { mode } => {
+                    label_mean_mode_change = Some(mode);
+                }
+                StateTransactionOperation::SetClusters { options } => {
+                    clusters_change = Some(options);
+                }
+                StateTransactionOperation::SetOutlierScores { options } => {
+                    outlier_scores_change = Some(options);
+                }
+                StateTransactionOperation::SetDensityCurves { options } => {
+                    density_curves_change = Some(options);
+                }
+                StateTransactionOperation::SetHistograms { options } => {
+                    histograms_change = Some(options);
+                }
+                StateTransactionOperation::SetLiveMode { options } => {
+                    live_mode_change = Some(options);
+                }
+                StateTransactionOperation::SetLiveDataFrozen { frozen } => {
+                    live_freeze_change = Some(frozen);
+                }
                 StateTransactionOperation::AddLabel { label } => {
                     label_additions.insert(label.id.clone(), label);
                 }
@@ -928,32 +2620,83 @@ pub fn build(self) -> StateTransaction {
                         color: None,
                         selection_bounds: None,
                         easing: None,
+                        visible: None,
                     });
                     label.color = Some(update.color)
                 }
-                StateTransactionOperation::SetLabelSelectionBounds { update } => {
+                StateTransactionOperation::SetLabelSelectionBounds { update } => {
+                    let label = label_updates.entry(update.id.clone()).or_insert(Label {
+                        id: update.id,
+                        color: None,
+                        selection_bounds: None,
+                        easing: None,
+                        visible: None,
+                    });
+                    label.selection_bounds = Some(update.selection_bounds);
+                }
+                StateTransactionOperation::SetLabelEasing { update } => {
                     let label = label_updates.entry(update.id.clone()).or_insert(Label {
                         id: update.id,
                         color: None,
                         selection_bounds: None,
                         easing: None,
+                        visible: None,
                     });
-                    label.selection_bounds = Some(update.selection_bounds);
+                    label.easing = Some(update.easing);
                 }
-                StateTransactionOperation::SetLabelEasing { update } => {
+                StateTransactionOperation::SetLabelVisibility { update } => {
                     let label = label_updates.entry(update.id.clone()).or_insert(Label {
                         id: update.id,
                         color: None,
                         selection_bounds: None,
                         easing: None,
+                        visible: None,
                     });
-                    label.easing = Some(update.easing);
+                    label.visible = Some(update.visible);
                 }
                 StateTransactionOperation::SwitchActiveLabel { id } => {
                     active_label_change = Some(id);
                 }
+                StateTransactionOperation::FreezeLabel { label } => {
+                    snapshot_removals.remove(&label);
+                    label_freezes.insert(label);
+                }
+                StateTransactionOperation::DiscardLabelSnapshot { label } => {
+                    label_freezes.remove(&label);
+                    snapshot_removals.insert(label);
+                }
+                StateTransactionOperation::AddLabelGroup { group } => {
+                    label_group_additions.insert(group.id.clone(), group);
+                }
+                StateTransactionOperation::RemoveLabelGroup { group } => {
+                    let _ = label_group_removals.insert(group);
+                }
+                StateTransactionOperation::SetLabelGroup { label, group } => {
+                    label_group_changes.insert(label, group);
+                }
+                StateTransactionOperation::SetCurveComparison { labels } => {
+                    curve_comparison_change = Some(labels);
+                }
                 StateTransactionOperation::SetBrushes { brushes } => {
                     brushes_change = Some(brushes);
+                    brush_edits.clear();
+                    curve_imports.clear();
+                    label_reverts.clear();
+                }
+                StateTransactionOperation::EditBrush { edit } => {
+                    brush_edits.push(edit);
+                }
+                StateTransactionOperation::ImportCurve { import } => {
+                    curve_imports.push(import);
+                }
+                StateTransactionOperation::RevertLabel { label, step } => {
+                    label_reverts.insert(label, step);
+                }
+                StateTransactionOperation::SetOperationSync { client } => {
+                    operation_sync_change = Some(client);
+                }
+                StateTransactionOperation::ApplyRemoteOperation { operation } => {
+                    remote_operations.push(operation);
                 }
                 StateTransactionOperation::SetInteractionMode { mode } => {
                     interaction_mode_change = Some(mode);
@@ -961,41 +2704,166 @@ pub fn build(self) -> StateTransaction {
                 StateTransactionOperation::SetDebugOptions { options } => {
                     debug_options_change = Some(options);
                 }
+                StateTransactionOperation::SetHitTestOptions { options } => {
+                    hit_test_options_change = Some(options);
+                }
+                StateTransactionOperation::SetControlPointOptions { options } => {
+                    control_point_options_change = Some(options);
+                }
+                StateTransactionOperation::SetAccessibilityOptions { options } => {
+                    accessibility_options_change = Some(options);
+                }
+                StateTransactionOperation::SetLocaleOptions { options } => {
+                    locale_options_change = Some(options);
+                }
             }
         }
 
         StateTransaction {
             axis_removals,
             axis_additions,
+            axis_point_changes,
+            axis_point_appends,
+            visible_range_changes,
+            range_fit,
+            axis_jitter_changes,
+            category_order_optimizations,
+            reference_clear,
+            reference_points,
             order_change,
+            view_change,
             colors_change,
             color_bar_visibility_change,
+            color_bar_placement_change,
+            color_bar_options_change,
+            axis_header_height_change,
+            curve_area_width_change,
+            all_selections_visibility_change,
+            label_agreement_metrics_change,
+            linear_blending_change,
+            pixel_snapping_change,
+            antialiasing_change,
+            data_line_join_change,
+            canvas_alpha_mode_change,
+            background_layer_change,
+            annotation_removals,
+            annotation_additions,
+            axis_region_removals,
+            axis_region_additions,
+            flow_ribbons_change,
+            label_mean_mode_change,
+            clusters_change,
+            outlier_scores_change,
+            density_curves_change,
+            histograms_change,
+            live_mode_change,
+            live_freeze_change,
             label_removals,
             label_additions,
             label_updates,
             active_label_change,
+            label_freezes,
+            snapshot_removals,
+            label_group_removals,
+            label_group_additions,
+            label_group_changes,
+            curve_comparison_change,
             brushes_change,
+            brush_edits,
+            curve_imports,
+            label_reverts,
+            operation_sync_change,
+            remote_operations,
             interaction_mode_change,
             debug_options_change,
+            hit_test_options_change,
+            control_point_options_change,
+            accessibility_options_change,
+            locale_options_change,
         }
     }
 }
 
+impl StateTransactionBuilder {
+    pub(crate) fn set_custom_axis_order(&mut self, order: Box<[String]>) {
+        self.operations
+            .push(StateTransactionOperation::SetAxisOrder {
+                order: AxisOrder::Custom { order },
+            });
+    }
+
+    pub(crate) fn set_brush_map(
+        &mut self,
+        brushes: BTreeMap<String, BTreeMap<String, Vec<Brush>>>,
+    ) {
+        self.operations
+            .push(StateTransactionOperation::SetBrushes { brushes });
+    }
+}
+
 #[wasm_bindgen]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct StateTransaction {
     pub(crate) axis_removals: BTreeSet<String>,
     pub(crate) axis_additions: BTreeMap<String, AxisDef>,
+    pub(crate) axis_point_changes: BTreeMap<String, Box<[f32]>>,
+    pub(crate) axis_point_appends: BTreeMap<String, Vec<f32>>,
+    pub(crate) visible_range_changes: BTreeMap<String, (f32, f32)>,
+    pub(crate) range_fit: Option<FitAxes>,
+    pub(crate) axis_jitter_changes: BTreeMap<String, f32>,
+    pub(crate) category_order_optimizations: BTreeSet<String>,
+    pub(crate) reference_clear: bool,
+    pub(crate) reference_points: BTreeMap<String, Box<[f32]>>,
     pub(crate) order_change: Option<AxisOrder>,
+    pub(crate) view_change: Option<ViewChange>,
     pub(crate) colors_change: Option<Colors>,
     pub(crate) color_bar_visibility_change: Option<bool>,
+    pub(crate) color_bar_placement_change: Option<color_bar::ColorBarPlacement>,
+    pub(crate) color_bar_options_change: Option<ColorBarOptions>,
+    pub(crate) axis_header_height_change: Option<f32>,
+    pub(crate) curve_area_width_change: Option<f32>,
+    pub(crate) all_selections_visibility_change: Option<bool>,
+    pub(crate) label_agreement_metrics_change: Option<bool>,
+    pub(crate) linear_blending_change: Option<bool>,
+    pub(crate) pixel_snapping_change: Option<bool>,
+    pub(crate) antialiasing_change: Option<Antialiasing>,
+    pub(crate) data_line_join_change: Option<DataLineJoin>,
+    pub(crate) canvas_alpha_mode_change: Option<CanvasAlphaMode>,
+    pub(crate) background_layer_change: Option<Option<BackgroundLayer>>,
+    pub(crate) annotation_removals: BTreeSet<String>,
+    pub(crate) annotation_additions: BTreeMap<String, Annotation>,
+    pub(crate) axis_region_removals: BTreeSet<String>,
+    pub(crate) axis_region_additions: BTreeMap<String, AxisRegion>,
+    pub(crate) flow_ribbons_change: Option<bool>,
+    pub(crate) label_mean_mode_change: Option<LabelMeanMode>,
+    pub(crate) clusters_change: Option<Option<ClusterOptions>>,
+    pub(crate) outlier_scores_change: Option<Option<OutlierOptions>>,
+    pub(crate) density_curves_change: Option<Option<DensityOptions>>,
+    pub(crate) histograms_change: Option<Option<HistogramOptions>>,
+    pub(crate) live_mode_change: Option<Option<LiveOptions>>,
+    pub(crate) live_freeze_change: Option<bool>,
     pub(crate) label_removals: BTreeSet<String>,
     pub(crate) label_additions: BTreeMap<String, Label>,
     pub(crate) label_updates: BTreeMap<String, Label>,
     pub(crate) active_label_change: Option<Option<String>>,
+    pub(crate) label_freezes: BTreeSet<String>,
+    pub(crate) snapshot_removals: BTreeSet<String>,
+    pub(crate) label_group_removals: BTreeSet<String>,
+    pub(crate) label_group_additions: BTreeMap<String, LabelGroup>,
+    pub(crate) label_group_changes: BTreeMap<String, Option<String>>,
+    pub(crate) curve_comparison_change: Option<Option<(String, String)>>,
     pub(crate) brushes_change: Option<BTreeMap<String, BTreeMap<String, Vec<Brush>>>>,
+    pub(crate) brush_edits: Vec<BrushEdit>,
+    pub(crate) curve_imports: Vec<CurveImport>,
+    pub(crate) label_reverts: BTreeMap<String, usize>,
+    pub(crate) operation_sync_change: Option<Option<String>>,
+    pub(crate) remote_operations: Vec<api::SyncOperation>,
     pub(crate) interaction_mode_change: Option<InteractionMode>,
     pub(crate) debug_options_change: Option<DebugOptions>,
+    pub(crate) hit_test_options_change: Option<HitTestOptions>,
+    pub(crate) control_point_options_change: Option<ControlPointOptions>,
+    pub(crate) accessibility_options_change: Option<AccessibilityOptions>,
+    pub(crate) locale_options_change: Option<LocaleOptions>,
 }
 
 #[wasm_bindgen]
@@ -1008,15 +2876,63 @@ pub fn log(&self) {
     pub fn is_empty(&self) -> bool {
         self.axis_removals.is_empty()
             && self.axis_additions.is_empty()
+            && self.axis_point_changes.is_empty()
+            && self.axis_point_appends.is_empty()
+            && self.visible_range_changes.is_empty()
+            && self.range_fit.is_none()
+            && self.axis_jitter_changes.is_empty()
+            && self.category_order_optimizations.is_empty()
+            && !self.reference_clear
+            && self.reference_points.is_empty()
             && self.order_change.is_none()
+            && self.view_change.is_none()
             && self.colors_change.is_none()
             && self.color_bar_visibility_change.is_none()
+            && self.color_bar_placement_change.is_none()
+            && self.color_bar_options_change.is_none()
+            && self.axis_header_height_change.is_none()
+            && self.curve_area_width_change.is_none()
+            && self.all_selections_visibility_change.is_none()
+            && self.label_agreement_metrics_change.is_none()
+            && self.linear_blending_change.is_none()
+            && self.pixel_snapping_change.is_none()
+            && self.antialiasing_change.is_none()
+            && self.data_line_join_change.is_none()
+            && self.canvas_alpha_mode_change.is_none()
+            && self.background_layer_change.is_none()
+            && self.annotation_removals.is_empty()
+            && self.annotation_additions.is_empty()
+            && self.axis_region_removals.is_empty()
+            && self.axis_region_additions.is_empty()
+            && self.flow_ribbons_change.is_none()
+            && self.label_mean_mode_change.is_none()
+            && self.clusters_change.is_none()
+            && self.outlier_scores_change.is_none()
+            && self.density_curves_change.is_none()
+            && self.histograms_change.is_none()
+            && self.live_mode_change.is_none()
+            && self.live_freeze_change.is_none()
             && self.label_removals.is_empty()
             && self.label_additions.is_empty()
             && self.label_updates.is_empty()
             && self.active_label_change.is_none()
+            && self.label_freezes.is_empty()
+            && self.snapshot_removals.is_empty()
+            && self.label_group_removals.is_empty()
+            && self.label_group_additions.is_empty()
+            && self.label_group_changes.is_empty()
+            && self.curve_comparison_change.is_none()
+            && self.brush_edits.is_empty()
+            && self.curve_imports.is_empty()
+            && self.label_reverts.is_empty()
+            && self.operation_sync_change.is_none()
+            && self.remote_operations.is_empty()
             && self.interaction_mode_change.is_none()
             && self.debug_options_change.is_none()
+            && self.hit_test_options_change.is_none()
+            && self.control_point_options_change.is_none()
+            && self.accessibility_options_change.is_none()
+            && self.locale_options_change.is_none()
     }
 }
 
@@ -1033,17 +2949,339 @@ pub enum Event {
     Draw {
         completion: Sender<()>,
     },
+    Describe {
+        completion: Sender<api::DescriptionObject>,
+    },
+    ExportCurves {
+        completion: Sender<api::CurvesObject>,
+    },
+    QueryLabelHistory {
+        label: String,
+        completion: Sender<api::LabelHistoryObject>,
+    },
+    QueryStateFingerprint {
+        completion: Sender<String>,
+    },
+    QueryData {
+        indices: Box<[usize]>,
+        completion: Sender<api::DataObject>,
+    },
+    SortByProbability {
+        completion: Sender<js_sys::BigUint64Array>,
+    },
+    Capture {
+        completion: Sender<js_sys::Object>,
+    },
+    PreviewColorScale {
+        color_scale: ColorScale,
+        completion: Sender<js_sys::Uint8Array>,
+    },
+    PointerDown {
+        event: PointerInput,
+    },
+    PointerUp {
+        event: PointerInput,
+    },
+    PointerMove {
+        event: PointerInput,
+    },
+    SetVisible {
+        visible: bool,
+    },
+    SetFrameOptions {
+        options: FrameOptions,
+    },
+    StartRecording,
+    StopRecording {
+        completion: Sender<EventLog>,
+    },
+    InspectBuffer {
+        buffer: DebugBuffer,
+        completion: Sender<String>,
+    },
+    #[cfg(feature = "dev-tools")]
+    ReloadShader {
+        name: String,
+        source: String,
+        completion: Sender<Result<(), String>>,
+    },
+}
+
+/// Pointer input forwarded to the renderer.
+///
+/// Mirrors the subset of a [`web_sys::PointerEvent`] used by the renderer,
+/// such that it can be recorded and replayed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointerInput {
+    offset_x: i32,
+    offset_y: i32,
+    movement_x: i32,
+    movement_y: i32,
+    button: i16,
+    is_primary: bool,
+    pointer_type: String,
+    shift_key: bool,
+    ctrl_key: bool,
+    alt_key: bool,
+}
+
+impl PointerInput {
+    pub fn offset_x(&self) -> i32 {
+        self.offset_x
+    }
+
+    pub fn offset_y(&self) -> i32 {
+        self.offset_y
+    }
+
+    pub fn movement_x(&self) -> i32 {
+        self.movement_x
+    }
+
+    pub fn movement_y(&self) -> i32 {
+        self.movement_y
+    }
+
+    pub fn button(&self) -> i16 {
+        self.button
+    }
+
+    pub fn is_primary(&self) -> bool {
+        self.is_primary
+    }
+
+    pub fn pointer_type(&self) -> String {
+        self.pointer_type.clone()
+    }
+
+    pub fn shift_key(&self) -> bool {
+        self.shift_key
+    }
+
+    pub fn ctrl_key(&self) -> bool {
+        self.ctrl_key
+    }
+
+    pub fn alt_key(&self) -> bool {
+        self.alt_key
+    }
+
+    fn to_js(&self) -> js_sys::Object {
+        let obj = js_sys::Object::new();
+        js_sys::Reflect::set(&obj, &"offsetX".into(), &self.offset_x.into()).unwrap();
+        js_sys::Reflect::set(&obj, &"offsetY".into(), &self.offset_y.into()).unwrap();
+        js_sys::Reflect::set(&obj, &"movementX".into(), &self.movement_x.into()).unwrap();
+        js_sys::Reflect::set(&obj, &"movementY".into(), &self.movement_y.into()).unwrap();
+        js_sys::Reflect::set(&obj, &"button".into(), &self.button.into()).unwrap();
+        js_sys::Reflect::set(&obj, &"isPrimary".into(), &self.is_primary.into()).unwrap();
+        js_sys::Reflect::set(
+            &obj,
+            &"pointerType".into(),
+            &self.pointer_type.as_str().into(),
+        )
+        .unwrap();
+        js_sys::Reflect::set(&obj, &"shiftKey".into(), &self.shift_key.into()).unwrap();
+        js_sys::Reflect::set(&obj, &"ctrlKey".into(), &self.ctrl_key.into()).unwrap();
+        js_sys::Reflect::set(&obj, &"altKey".into(), &self.alt_key.into()).unwrap();
+        obj
+    }
+
+    fn from_js(obj: &JsValue) -> Self {
+        let get = |key: &str| js_sys::Reflect::get(obj, &key.into()).unwrap();
+        let number = |key: &str| get(key).as_f64().unwrap_or(0.0);
+        let boolean = |key: &str| get(key).as_bool().unwrap_or(false);
+
+        Self {
+            offset_x: number("offsetX") as i32,
+            offset_y: number("offsetY") as i32,
+            movement_x: number("movementX") as i32,
+            movement_y: number("movementY") as i32,
+            button: number("button") as i16,
+            is_primary: boolean("isPrimary"),
+            pointer_type: get("pointerType").as_string().unwrap_or_default(),
+            shift_key: boolean("shiftKey"),
+            ctrl_key: boolean("ctrlKey"),
+            alt_key: boolean("altKey"),
+        }
+    }
+}
+
+impl From<web_sys::PointerEvent> for PointerInput {
+    fn from(event: web_sys::PointerEvent) -> Self {
+        Self {
+            offset_x: event.offset_x(),
+            offset_y: event.offset_y(),
+            movement_x: event.movement_x(),
+            movement_y: event.movement_y(),
+            button: event.button(),
+            is_primary: event.is_primary(),
+            pointer_type: event.pointer_type(),
+            shift_key: event.shift_key(),
+            ctrl_key: event.ctrl_key(),
+            alt_key: event.alt_key(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum RecordedEventKind {
+    Resize {
+        width: u32,
+        height: u32,
+        device_pixel_ratio: f32,
+    },
+    CommitTransaction {
+        transaction: Box<StateTransaction>,
+    },
+    Draw,
     PointerDown {
-        event: web_sys::PointerEvent,
+        event: PointerInput,
     },
     PointerUp {
-        event: web_sys::PointerEvent,
+        event: PointerInput,
     },
     PointerMove {
-        event: web_sys::PointerEvent,
+        event: PointerInput,
     },
 }
 
+#[derive(Debug, Clone)]
+pub(crate) struct RecordedEvent {
+    /// Time in milliseconds since the start of the recording.
+    pub(crate) time: f64,
+    pub(crate) kind: RecordedEventKind,
+}
+
+impl RecordedEvent {
+    /// Returns the recordable part of an event.
+    pub(crate) fn from_event(time: f64, event: &Event) -> Option<Self> {
+        let kind = match event {
+            Event::Resize {
+                width,
+                height,
+                device_pixel_ratio,
+            } => RecordedEventKind::Resize {
+                width: *width,
+                height: *height,
+                device_pixel_ratio: *device_pixel_ratio,
+            },
+            Event::CommitTransaction { transaction } => RecordedEventKind::CommitTransaction {
+                transaction: Box::new(transaction.clone()),
+            },
+            Event::Draw { .. } => RecordedEventKind::Draw,
+            Event::PointerDown { event } => RecordedEventKind::PointerDown {
+                event: event.clone(),
+            },
+            Event::PointerUp { event } => RecordedEventKind::PointerUp {
+                event: event.clone(),
+            },
+            Event::PointerMove { event } => RecordedEventKind::PointerMove {
+                event: event.clone(),
+            },
+            _ => return None,
+        };
+
+        Some(Self { time, kind })
+    }
+}
+
+/// A log of recorded events.
+///
+/// Transactions are stored in their parsed form. They are therefore only
+/// replayable from the log itself, whereas the serialized form contains a
+/// debug representation of them.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct EventLog {
+    pub(crate) events: Vec<RecordedEvent>,
+}
+
+#[wasm_bindgen]
+impl EventLog {
+    /// Number of recorded events.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Serializes the log into an array of plain objects.
+    #[wasm_bindgen(js_name = toJSON)]
+    pub fn to_json(&self) -> js_sys::Array {
+        let array = js_sys::Array::new();
+        for event in &self.events {
+            let (ty, obj) = match &event.kind {
+                RecordedEventKind::Resize {
+                    width,
+                    height,
+                    device_pixel_ratio,
+                } => {
+                    let obj = js_sys::Object::new();
+                    js_sys::Reflect::set(&obj, &"width".into(), &(*width).into()).unwrap();
+                    js_sys::Reflect::set(&obj, &"height".into(), &(*height).into()).unwrap();
+                    js_sys::Reflect::set(
+                        &obj,
+                        &"devicePixelRatio".into(),
+                        &(*device_pixel_ratio).into(),
+                    )
+                    .unwrap();
+                    ("resize", obj)
+                }
+                RecordedEventKind::CommitTransaction { transaction } => {
+                    let obj = js_sys::Object::new();
+                    js_sys::Reflect::set(&obj, &"debug".into(), &format!("{transaction:?}").into())
+                        .unwrap();
+                    ("transaction", obj)
+                }
+                RecordedEventKind::Draw => ("draw", js_sys::Object::new()),
+                RecordedEventKind::PointerDown { event } => ("pointer_down", event.to_js()),
+                RecordedEventKind::PointerUp { event } => ("pointer_up", event.to_js()),
+                RecordedEventKind::PointerMove { event } => ("pointer_move", event.to_js()),
+            };
+
+            js_sys::Reflect::set(&obj, &"type".into(), &ty.into()).unwrap();
+            js_sys::Reflect::set(&obj, &"time".into(), &event.time.into()).unwrap();
+            array.push(&obj.into());
+        }
+
+        array
+    }
+
+    /// Deserializes a log produced by [`EventLog::to_json`].
+    ///
+    /// Transactions can not be restored from their debug representation
+    /// and are skipped.
+    #[wasm_bindgen(js_name = fromJSON)]
+    pub fn from_json(entries: js_sys::Array) -> Self {
+        let mut events = Vec::new();
+        for entry in entries.iter() {
+            let get = |key: &str| js_sys::Reflect::get(&entry, &key.into()).unwrap();
+            let time = get("time").as_f64().unwrap_or(0.0);
+            let kind = match get("type").as_string().as_deref() {
+                Some("resize") => RecordedEventKind::Resize {
+                    width: get("width").as_f64().unwrap_or(0.0) as u32,
+                    height: get("height").as_f64().unwrap_or(0.0) as u32,
+                    device_pixel_ratio: get("devicePixelRatio").as_f64().unwrap_or(1.0) as f32,
+                },
+                Some("draw") => RecordedEventKind::Draw,
+                Some("pointer_down") => RecordedEventKind::PointerDown {
+                    event: PointerInput::from_js(&entry),
+                },
+                Some("pointer_up") => RecordedEventKind::PointerUp {
+                    event: PointerInput::from_js(&entry),
+                },
+                Some("pointer_move") => RecordedEventKind::PointerMove {
+                    event: PointerInput::from_js(&entry),
+                },
+                _ => continue,
+            };
+
+            events.push(RecordedEvent { time, kind });
+        }
+
+        Self { events }
+    }
+}
+
 /// An event queue to interact with the renderer.
 #[wasm_bindgen]
 pub struct EventQueue {
@@ -1074,7 +3312,9 @@ pub fn resize(&self, width: u32, height: u32, device_pixel_ratio: f32) {
     #[wasm_bindgen(js_name = pointerDown)]
     pub fn pointer_down(&self, event: web_sys::PointerEvent) {
         self.sender
-            .send_blocking(Event::PointerDown { event })
+            .send_blocking(Event::PointerDown {
+                event: event.into(),
+            })
             .expect("the channel should be open");
     }
 
@@ -1082,7 +3322,9 @@ pub fn pointer_down(&self, event: web_sys::PointerEvent) {
     #[wasm_bindgen(js_name = pointerUp)]
     pub fn pointer_up(&self, event: web_sys::PointerEvent) {
         self.sender
-            .send_blocking(Event::PointerUp { event })
+            .send_blocking(Event::PointerUp {
+                event: event.into(),
+            })
             .expect("the channel should be open");
     }
 
@@ -1090,7 +3332,96 @@ pub fn pointer_up(&self, event: web_sys::PointerEvent) {
     #[wasm_bindgen(js_name = pointerMove)]
     pub fn pointer_move(&self, event: web_sys::PointerEvent) {
         self.sender
-            .send_blocking(Event::PointerMove { event })
+            .send_blocking(Event::PointerMove {
+                event: event.into(),
+            })
+            .expect("the channel should be open");
+    }
+
+    /// Starts recording all events received by the renderer.
+    #[wasm_bindgen(js_name = startRecording)]
+    pub fn start_recording(&self) {
+        self.sender
+            .send_blocking(Event::StartRecording)
+            .expect("the channel should be open");
+    }
+
+    /// Stops the recording and returns the recorded events.
+    #[wasm_bindgen(js_name = stopRecording)]
+    pub async fn stop_recording(&self) -> EventLog {
+        let (sx, rx) = async_channel::bounded(1);
+
+        // Spawn the event.
+        self.sender
+            .send(Event::StopRecording { completion: sx })
+            .await
+            .expect("the channel should be open when trying to send a message");
+
+        // Wait for the log.
+        rx.recv().await.expect("the channel should be open")
+    }
+
+    /// Feeds the events of the log back to the renderer.
+    ///
+    /// The events are replayed in their recorded order, independently of
+    /// their timestamps. Each recorded `draw` waits for the frame to
+    /// complete, such that the replay is deterministic.
+    pub fn replay(&self, log: &EventLog) -> js_sys::Promise {
+        let sender = self.sender.clone();
+        let events = log.events.clone();
+
+        wasm_bindgen_futures::future_to_promise(async move {
+            for event in events {
+                let event = match event.kind {
+                    RecordedEventKind::Resize {
+                        width,
+                        height,
+                        device_pixel_ratio,
+                    } => Event::Resize {
+                        width,
+                        height,
+                        device_pixel_ratio,
+                    },
+                    RecordedEventKind::CommitTransaction { transaction } => {
+                        Event::CommitTransaction {
+                            transaction: *transaction,
+                        }
+                    }
+                    RecordedEventKind::Draw => {
+                        let (sx, rx) = async_channel::bounded(1);
+                        sender
+                            .send(Event::Draw { completion: sx })
+                            .await
+                            .expect("the channel should be open when trying to send a message");
+
+                        // Wait for the frame to complete.
+                        rx.recv().await.expect("the channel should be open");
+                        continue;
+                    }
+                    RecordedEventKind::PointerDown { event } => Event::PointerDown { event },
+                    RecordedEventKind::PointerUp { event } => Event::PointerUp { event },
+                    RecordedEventKind::PointerMove { event } => Event::PointerMove { event },
+                };
+
+                sender
+                    .send(event)
+                    .await
+                    .expect("the channel should be open when trying to send a message");
+            }
+
+            Ok(JsValue::UNDEFINED)
+        })
+    }
+
+    /// Sets how the draws of the renderer are scheduled.
+    ///
+    /// If `scheduleDraws` is enabled, the renderer draws on each animation
+    /// frame, at most `maxFps` times per second, and the host is not required
+    /// to spawn `draw` events.
+    #[wasm_bindgen(js_name = setFrameOptions)]
+    pub fn set_frame_options(&self, options: FrameOptions) {
+        self.sender
+            .send_blocking(Event::SetFrameOptions { options })
             .expect("the channel should be open");
     }
 
@@ -1118,4 +3449,205 @@ pub async fn draw(&self) {
         // Wait for the event to complete.
         rx.recv().await.expect("the channel should be open");
     }
+
+    /// Renders the current plot into an offscreen texture and returns its pixels.
+    pub async fn capture(&self) -> js_sys::Object {
+        let (sx, rx) = async_channel::bounded(1);
+
+        // Spawn the event.
+        self.sender
+            .send(Event::Capture { completion: sx })
+            .await
+            .expect("the channel should be open when trying to send a message");
+
+        // Wait for the pixels.
+        rx.recv().await.expect("the channel should be open")
+    }
+
+    /// Samples a color scale, without applying it to the plot.
+    ///
+    /// Returns the samples in the `RGBA` format, with one byte per channel,
+    /// such that editors can preview the gradient as it would be rendered.
+    #[wasm_bindgen(js_name = previewColorScale)]
+    pub async fn preview_color_scale(&self, scale: ColorScaleDescription) -> js_sys::Uint8Array {
+        let (sx, rx) = async_channel::bounded(1);
+
+        // Spawn the event.
+        self.sender
+            .send(Event::PreviewColorScale {
+                color_scale: scale.into_color_scale(),
+                completion: sx,
+            })
+            .await
+            .expect("the channel should be open when trying to send a message");
+
+        // Wait for the samples.
+        rx.recv().await.expect("the channel should be open")
+    }
+
+    /// Requests a textual and structured description of the current plot.
+    pub async fn describe(&self) -> api::DescriptionObject {
+        let (sx, rx) = async_channel::bounded(1);
+
+        // Spawn the event.
+        self.sender
+            .send(Event::Describe { completion: sx })
+            .await
+            .expect("the channel should be open when trying to send a message");
+
+        // Wait for the description.
+        rx.recv().await.expect("the channel should be open")
+    }
+
+    /// Requests the fitted spline segments of the probability curves of all
+    /// labels, such that they can be evaluated outside of the plot.
+    #[wasm_bindgen(js_name = exportCurves)]
+    pub async fn export_curves(&self) -> api::CurvesObject {
+        let (sx, rx) = async_channel::bounded(1);
+
+        // Spawn the event.
+        self.sender
+            .send(Event::ExportCurves { completion: sx })
+            .await
+            .expect("the channel should be open when trying to send a message");
+
+        // Wait for the curves.
+        rx.recv().await.expect("the channel should be open")
+    }
+
+    /// Requests the edit history of the brushes of a label, such that
+    /// annotation tools can revert it with `revertLabelTo`.
+    ///
+    /// The history is empty if the label doesn't exist.
+    #[wasm_bindgen(js_name = labelHistory)]
+    pub async fn label_history(&self, label: String) -> api::LabelHistoryObject {
+        let (sx, rx) = async_channel::bounded(1);
+
+        // Spawn the event.
+        self.sender
+            .send(Event::QueryLabelHistory {
+                label,
+                completion: sx,
+            })
+            .await
+            .expect("the channel should be open when trying to send a message");
+
+        // Wait for the history.
+        rx.recv().await.expect("the channel should be open")
+    }
+
+    /// Requests a stable hash of the semantic state of the plot, i.e. the
+    /// axes, their order, the brushes, the labels and the coloring, such that
+    /// diverging copies of the state can be detected without comparing them.
+    #[wasm_bindgen(js_name = stateFingerprint)]
+    pub async fn state_fingerprint(&self) -> String {
+        let (sx, rx) = async_channel::bounded(1);
+
+        // Spawn the event.
+        self.sender
+            .send(Event::QueryStateFingerprint { completion: sx })
+            .await
+            .expect("the channel should be open when trying to send a message");
+
+        // Wait for the fingerprint.
+        rx.recv().await.expect("the channel should be open")
+    }
+
+    /// Reads a buffer back from the GPU and formats its entries according
+    /// to the layout of the corresponding Rust struct, to debug layout and
+    /// shader issues.
+    #[wasm_bindgen(js_name = inspectBuffer)]
+    pub async fn inspect_buffer(&self, buffer: DebugBuffer) -> String {
+        let (sx, rx) = async_channel::bounded(1);
+
+        // Spawn the event.
+        self.sender
+            .send(Event::InspectBuffer {
+                buffer,
+                completion: sx,
+            })
+            .await
+            .expect("the channel should be open when trying to send a message");
+
+        // Wait for the content.
+        rx.recv().await.expect("the channel should be open")
+    }
+
+    /// Requests the values of the data point at `index`, keyed by the axes,
+    /// or `undefined` if the index lies outside of the data.
+    #[wasm_bindgen(js_name = getDatum)]
+    pub async fn get_datum(&self, index: u32) -> Option<api::DatumObject> {
+        let data = self.get_data(Box::new([index])).await;
+        let datum = data.get(0);
+        (!datum.is_undefined()).then(|| datum.unchecked_into())
+    }
+
+    /// Requests the values of the data points at `indices`, such that the
+    /// host application doesn't need to keep its own copy of the data.
+    #[wasm_bindgen(js_name = getData)]
+    pub async fn get_data(&self, indices: Box<[u32]>) -> api::DataObject {
+        let (sx, rx) = async_channel::bounded(1);
+
+        // Spawn the event.
+        self.sender
+            .send(Event::QueryData {
+                indices: indices.iter().map(|&index| index as usize).collect(),
+                completion: sx,
+            })
+            .await
+            .expect("the channel should be open when trying to send a message");
+
+        // Wait for the values.
+        rx.recv().await.expect("the channel should be open")
+    }
+
+    /// Requests the indices of all data points, sorted by their probability
+    /// of the active label in descending order, such that linked views can
+    /// show the best matching data points first.
+    ///
+    /// Data points with the same probability remain in their original order.
+    /// Returns an empty array if there is no active label.
+    #[wasm_bindgen(js_name = sortByProbability)]
+    pub async fn sort_by_probability(&self) -> js_sys::BigUint64Array {
+        let (sx, rx) = async_channel::bounded(1);
+
+        // Spawn the event.
+        self.sender
+            .send(Event::SortByProbability { completion: sx })
+            .await
+            .expect("the channel should be open when trying to send a message");
+
+        // Wait for the indices.
+        rx.recv().await.expect("the channel should be open")
+    }
+}
+
+#[cfg(feature = "dev-tools")]
+#[wasm_bindgen]
+impl EventQueue {
+    /// Recreates the render pipeline `name` from the provided WGSL source.
+    ///
+    /// The name matches the file name of the shader, e.g. `data_lines`. The
+    /// shader must keep the bindings of the original, and is rejected if it
+    /// fails to compile, in which case the previous pipeline is kept.
+    #[wasm_bindgen(js_name = reloadShader)]
+    pub async fn reload_shader(&self, name: String, source: String) -> Result<(), JsValue> {
+        let (sx, rx) = async_channel::bounded(1);
+
+        // Spawn the event.
+        self.sender
+            .send(Event::ReloadShader {
+                name,
+                source,
+                completion: sx,
+            })
+            .await
+            .expect("the channel should be open when trying to send a message");
+
+        // Wait for the pipeline.
+        rx.recv()
+            .await
+            .expect("the channel should be open")
+            .map_err(|e| JsValue::from(js_sys::Error::new(&e)))
+    }
 }
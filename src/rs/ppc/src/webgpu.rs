@@ -15,6 +15,8 @@
 #[derive(Debug, Clone)]
 pub struct Device {
     device: web_sys::GpuDevice,
+    limits: Limits,
+    features: Features,
 }
 
 impl Device {
@@ -23,7 +25,56 @@ pub fn new(raw: web_sys::GpuDevice) -> Self {
             panic!("Invalid device provided");
         }
 
-        Self { device: raw }
+        let limits = js_sys::Reflect::get(&raw, &"limits".into()).unwrap();
+        let features = js_sys::Reflect::get(&raw, &"features".into()).unwrap();
+        Self {
+            device: raw,
+            limits: Limits::from_js(&limits),
+            features: Features::from_js(&features),
+        }
+    }
+
+    /// Requests a new device from the `adapter`.
+    pub async fn request(
+        adapter: &web_sys::GpuAdapter,
+        descriptor: DeviceDescriptor<'_>,
+    ) -> Result<Self, JsValue> {
+        let adapter_limits = Limits::from_js(&js_sys::Reflect::get(adapter, &"limits".into())?);
+        let adapter_features =
+            Features::from_js(&js_sys::Reflect::get(adapter, &"features".into())?);
+        let limits = descriptor.desired_limits.min(adapter_limits);
+        let features = descriptor.optional_features.intersection(adapter_features);
+
+        let mut raw_descriptor = web_sys::GpuDeviceDescriptor::new();
+        descriptor.label.map(|x| raw_descriptor.label(&x));
+        js_sys::Reflect::set(
+            &raw_descriptor,
+            &"requiredFeatures".into(),
+            &features.to_js(),
+        )?;
+        js_sys::Reflect::set(&raw_descriptor, &"requiredLimits".into(), &limits.to_js())?;
+
+        let device =
+            JsFuture::from(adapter.request_device_with_descriptor(&raw_descriptor)).await?;
+        if device.is_falsy() {
+            return Err("could not request device".into());
+        }
+
+        Ok(Self::new(device.dyn_into()?))
+    }
+
+    pub fn raw(&self) -> &web_sys::GpuDevice {
+        &self.device
+    }
+
+    /// Returns the limits granted to the device.
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    /// Returns the optional features enabled on the device.
+    pub fn features(&self) -> Features {
+        self.features
     }
 
     pub fn label(&self) -> String {
@@ -172,6 +223,23 @@ pub fn create_shader_module(&self, descriptor: ShaderModuleDescriptor<'_>) -> Sh
         }
     }
 
+    pub fn push_error_scope(&self, filter: ErrorFilter) {
+        self.device.push_error_scope(filter.into());
+    }
+
+    /// Pops the innermost error scope and returns the message of the first
+    /// error it captured, if any.
+    pub async fn pop_error_scope(&self) -> Option<String> {
+        let error = JsFuture::from(self.device.pop_error_scope())
+            .await
+            .expect("the error scope stack should not be empty");
+        if error.is_null() {
+            None
+        } else {
+            Some(error.unchecked_into::<web_sys::GpuError>().message())
+        }
+    }
+
     pub fn create_texture<const N: usize, const M: usize>(
         &self,
         descriptor: TextureDescriptor<'_, N, M>,
@@ -185,6 +253,167 @@ pub fn create_texture<const N: usize, const M: usize>(
     }
 }
 
+/// Descriptor of a requested [`Device`].
+///
+/// The optional features and the desired limits are only requested to the
+/// extent supported by the adapter.
+#[derive(Debug, Default)]
+pub struct DeviceDescriptor<'a> {
+    pub label: Option<Cow<'a, str>>,
+    pub optional_features: Features,
+    pub desired_limits: Limits,
+}
+
+/// Limits of a [`Device`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub max_texture_dimension_2d: u32,
+    pub max_texture_array_layers: u32,
+    pub max_buffer_size: u64,
+    pub max_storage_buffer_binding_size: u64,
+    pub max_compute_workgroup_size_x: u32,
+    pub max_compute_invocations_per_workgroup: u32,
+}
+
+impl Limits {
+    fn entries(&self) -> [(&'static str, f64); 6] {
+        [
+            (
+                "maxTextureDimension2D",
+                self.max_texture_dimension_2d as f64,
+            ),
+            (
+                "maxTextureArrayLayers",
+                self.max_texture_array_layers as f64,
+            ),
+            ("maxBufferSize", self.max_buffer_size as f64),
+            (
+                "maxStorageBufferBindingSize",
+                self.max_storage_buffer_binding_size as f64,
+            ),
+            (
+                "maxComputeWorkgroupSizeX",
+                self.max_compute_workgroup_size_x as f64,
+            ),
+            (
+                "maxComputeInvocationsPerWorkgroup",
+                self.max_compute_invocations_per_workgroup as f64,
+            ),
+        ]
+    }
+
+    fn from_js(limits: &JsValue) -> Self {
+        let default = Self::default();
+        let [a, b, c, d, e, f] = default.entries().map(|(name, default)| {
+            js_sys::Reflect::get(limits, &name.into())
+                .ok()
+                .and_then(|x| x.as_f64())
+                .unwrap_or(default)
+        });
+
+        Self {
+            max_texture_dimension_2d: a as u32,
+            max_texture_array_layers: b as u32,
+            max_buffer_size: c as u64,
+            max_storage_buffer_binding_size: d as u64,
+            max_compute_workgroup_size_x: e as u32,
+            max_compute_invocations_per_workgroup: f as u32,
+        }
+    }
+
+    fn to_js(self) -> js_sys::Object {
+        let limits = js_sys::Object::new();
+        for (name, value) in self.entries() {
+            js_sys::Reflect::set(&limits, &name.into(), &value.into()).unwrap();
+        }
+        limits
+    }
+
+    /// Returns the smaller value of each limit.
+    pub fn min(self, other: Self) -> Self {
+        Self {
+            max_texture_dimension_2d: self
+                .max_texture_dimension_2d
+                .min(other.max_texture_dimension_2d),
+            max_texture_array_layers: self
+                .max_texture_array_layers
+                .min(other.max_texture_array_layers),
+            max_buffer_size: self.max_buffer_size.min(other.max_buffer_size),
+            max_storage_buffer_binding_size: self
+                .max_storage_buffer_binding_size
+                .min(other.max_storage_buffer_binding_size),
+            max_compute_workgroup_size_x: self
+                .max_compute_workgroup_size_x
+                .min(other.max_compute_workgroup_size_x),
+            max_compute_invocations_per_workgroup: self
+                .max_compute_invocations_per_workgroup
+                .min(other.max_compute_invocations_per_workgroup),
+        }
+    }
+}
+
+impl Default for Limits {
+    /// Limits guaranteed to be supported by every device.
+    fn default() -> Self {
+        Self {
+            max_texture_dimension_2d: 8192,
+            max_texture_array_layers: 256,
+            max_buffer_size: 268435456,
+            max_storage_buffer_binding_size: 134217728,
+            max_compute_workgroup_size_x: 256,
+            max_compute_invocations_per_workgroup: 256,
+        }
+    }
+}
+
+/// Optional features of a [`Device`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Features {
+    pub timestamp_query: bool,
+    pub float32_filterable: bool,
+}
+
+impl Features {
+    fn entries(&self) -> [(&'static str, bool); 2] {
+        [
+            ("timestamp-query", self.timestamp_query),
+            ("float32-filterable", self.float32_filterable),
+        ]
+    }
+
+    fn from_js(features: &JsValue) -> Self {
+        let has = js_sys::Reflect::get(features, &"has".into())
+            .ok()
+            .and_then(|x| x.dyn_into::<js_sys::Function>().ok());
+        let [timestamp_query, float32_filterable] = Self::default().entries().map(|(name, _)| {
+            has.as_ref()
+                .and_then(|has| has.call1(features, &name.into()).ok())
+                .is_some_and(|x| x.is_truthy())
+        });
+
+        Self {
+            timestamp_query,
+            float32_filterable,
+        }
+    }
+
+    fn to_js(self) -> js_sys::Array {
+        self.entries()
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| JsValue::from_str(name))
+            .collect()
+    }
+
+    /// Returns the features enabled in both sets.
+    pub fn intersection(self, other: Self) -> Self {
+        Self {
+            timestamp_query: self.timestamp_query && other.timestamp_query,
+            float32_filterable: self.float32_filterable && other.float32_filterable,
+        }
+    }
+}
+
 // Wrapper of a [`web_sys::GpuQueue`].
 #[derive(Debug, Clone)]
 pub struct Queue {
@@ -207,28 +436,7 @@ pub fn submit(&self, command_buffers: &[CommandBuffer]) {
     }
 
     pub fn write_buffer<T: HostSharable>(&self, buffer: &Buffer, buffer_offset: u32, data: &[T]) {
-        let data_offset = data as *const [T] as *const () as usize;
-        let data_size = std::mem::size_of_val(data);
-        assert!(data_offset <= u32::MAX as usize);
-        assert!(data_size <= u32::MAX as usize);
-
-        // Due to padding it is unsound to simply cast the slice to
-        // a `[u8]`, as the padding bytes are uninitialized.
-        // A workaround is to copy the data manually into a new buffer
-        // and to initialize the padding bytes. While correct, it results
-        // in doubling of the memory footprint and is slower than a simple
-        // memory copy. Instead, we utilize the fact that we are in a runtime.
-        // Inside the wasm runtime we have access to the memory object, which
-        // represents the address space available to the program. Program
-        // pointers map 1:1 to indices inside, i.e. an object at the pointer
-        // `0xCAFE` with size `16` lies in `Memory[0xCAFE..0xCAFE + 16]`.
-        // Knowing this, we can pass the buffer, and the respective ranges
-        // to the queue, and avoid a slow copy operation.
-        let memory = wasm_bindgen::memory()
-            .dyn_into::<js_sys::WebAssembly::Memory>()
-            .unwrap();
-        let memory_data = memory.buffer().dyn_into::<js_sys::ArrayBuffer>().unwrap();
-        let memory_data = js_sys::DataView::new(&memory_data, data_offset, data_size);
+        let memory_data = memory_view(data);
         self.queue.write_buffer_with_u32_and_buffer_source_and_u32(
             &buffer.buffer,
             buffer_offset,
@@ -251,6 +459,50 @@ pub fn write_buffer_raw(&self, buffer: &Buffer, buffer_offset: u32, data: &[u8])
         self.queue
             .write_buffer_with_u32_and_u8_array(&buffer.buffer, buffer_offset, data)
     }
+
+    pub fn write_texture<T: HostSharable, const N: usize>(
+        &self,
+        destination: ImageCopyTexture,
+        data: &[T],
+        data_layout: ImageDataLayout,
+        size: [u32; N],
+    ) {
+        let memory_data = memory_view(data);
+        let size = js_sys::Array::from_iter(size.map(JsValue::from));
+        self.queue
+            .write_texture_with_buffer_source_and_u32_sequence(
+                &destination.into(),
+                &memory_data,
+                &data_layout.into(),
+                &size,
+            )
+    }
+}
+
+/// Returns a view of the memory backing the slice.
+fn memory_view<T: HostSharable>(data: &[T]) -> js_sys::DataView {
+    let data_offset = data as *const [T] as *const () as usize;
+    let data_size = std::mem::size_of_val(data);
+    assert!(data_offset <= u32::MAX as usize);
+    assert!(data_size <= u32::MAX as usize);
+
+    // Due to padding it is unsound to simply cast the slice to
+    // a `[u8]`, as the padding bytes are uninitialized.
+    // A workaround is to copy the data manually into a new buffer
+    // and to initialize the padding bytes. While correct, it results
+    // in doubling of the memory footprint and is slower than a simple
+    // memory copy. Instead, we utilize the fact that we are in a runtime.
+    // Inside the wasm runtime we have access to the memory object, which
+    // represents the address space available to the program. Program
+    // pointers map 1:1 to indices inside, i.e. an object at the pointer
+    // `0xCAFE` with size `16` lies in `Memory[0xCAFE..0xCAFE + 16]`.
+    // Knowing this, we can pass the buffer, and the respective ranges
+    // to the queue, and avoid a slow copy operation.
+    let memory = wasm_bindgen::memory()
+        .dyn_into::<js_sys::WebAssembly::Memory>()
+        .unwrap();
+    let memory_data = memory.buffer().dyn_into::<js_sys::ArrayBuffer>().unwrap();
+    js_sys::DataView::new(&memory_data, data_offset, data_size)
 }
 
 /// Wrapper of a [`web_sys::GpuBindGroup`].
@@ -429,6 +681,48 @@ pub fn copy_buffer_to_buffer(
         )
     }
 
+    pub fn copy_buffer_to_texture<const N: usize>(
+        &self,
+        source: ImageCopyBuffer,
+        destination: ImageCopyTexture,
+        size: [u32; N],
+    ) {
+        let size = js_sys::Array::from_iter(size.map(JsValue::from));
+        self.encoder.copy_buffer_to_texture_with_u32_sequence(
+            &source.into(),
+            &destination.into(),
+            &size,
+        )
+    }
+
+    pub fn copy_texture_to_buffer<const N: usize>(
+        &self,
+        source: ImageCopyTexture,
+        destination: ImageCopyBuffer,
+        size: [u32; N],
+    ) {
+        let size = js_sys::Array::from_iter(size.map(JsValue::from));
+        self.encoder.copy_texture_to_buffer_with_u32_sequence(
+            &source.into(),
+            &destination.into(),
+            &size,
+        )
+    }
+
+    pub fn copy_texture_to_texture<const N: usize>(
+        &self,
+        source: ImageCopyTexture,
+        destination: ImageCopyTexture,
+        size: [u32; N],
+    ) {
+        let size = js_sys::Array::from_iter(size.map(JsValue::from));
+        self.encoder.copy_texture_to_texture_with_u32_sequence(
+            &source.into(),
+            &destination.into(),
+            &size,
+        )
+    }
+
     pub fn finish(&self, descriptor: Option<CommandBufferDescriptor<'_>>) -> CommandBuffer {
         let command_buffer = if let Some(descriptor) = descriptor {
             self.encoder.finish_with_descriptor(&descriptor.into())
@@ -586,10 +880,107 @@ pub fn draw_with_instance_count_and_first_vertex_and_first_instance(
             )
     }
 
+    pub fn draw_indirect(&self, indirect_buffer: &Buffer, indirect_offset: usize) {
+        self.encoder
+            .draw_indirect_with_f64(&indirect_buffer.buffer, indirect_offset as f64)
+    }
+
+    pub fn draw_indexed_indirect(&self, indirect_buffer: &Buffer, indirect_offset: usize) {
+        self.encoder
+            .draw_indexed_indirect_with_f64(&indirect_buffer.buffer, indirect_offset as f64)
+    }
+
+    pub fn draw_indexed(&self, index_count: usize) {
+        self.encoder.draw_indexed(index_count as u32)
+    }
+
+    pub fn draw_indexed_with_instance_count(&self, index_count: usize, instance_count: usize) {
+        self.encoder
+            .draw_indexed_with_instance_count(index_count as u32, instance_count as u32)
+    }
+
+    pub fn draw_indexed_with_instance_count_and_first_index(
+        &self,
+        index_count: usize,
+        instance_count: usize,
+        first_index: usize,
+    ) {
+        self.encoder
+            .draw_indexed_with_instance_count_and_first_index(
+                index_count as u32,
+                instance_count as u32,
+                first_index as u32,
+            )
+    }
+
+    pub fn draw_indexed_with_instance_count_and_first_index_and_base_vertex(
+        &self,
+        index_count: usize,
+        instance_count: usize,
+        first_index: usize,
+        base_vertex: i32,
+    ) {
+        self.encoder
+            .draw_indexed_with_instance_count_and_first_index_and_base_vertex(
+                index_count as u32,
+                instance_count as u32,
+                first_index as u32,
+                base_vertex,
+            )
+    }
+
+    pub fn draw_indexed_with_instance_count_and_first_index_and_base_vertex_and_first_instance(
+        &self,
+        index_count: usize,
+        instance_count: usize,
+        first_index: usize,
+        base_vertex: i32,
+        first_instance: usize,
+    ) {
+        self.encoder
+            .draw_indexed_with_instance_count_and_first_index_and_base_vertex_and_first_instance(
+                index_count as u32,
+                instance_count as u32,
+                first_index as u32,
+                base_vertex,
+                first_instance as u32,
+            )
+    }
+
     pub fn set_pipeline(&self, pipeline: &RenderPipeline) {
         self.encoder.set_pipeline(&pipeline.pipeline)
     }
 
+    pub fn set_index_buffer(&self, buffer: &Buffer, index_format: IndexFormat) {
+        self.encoder
+            .set_index_buffer(&buffer.buffer, index_format.into())
+    }
+
+    pub fn set_index_buffer_with_offset(
+        &self,
+        buffer: &Buffer,
+        index_format: IndexFormat,
+        offset: usize,
+    ) {
+        self.encoder
+            .set_index_buffer_with_u32(&buffer.buffer, index_format.into(), offset as u32)
+    }
+
+    pub fn set_index_buffer_with_offset_and_size(
+        &self,
+        buffer: &Buffer,
+        index_format: IndexFormat,
+        offset: usize,
+        size: usize,
+    ) {
+        self.encoder.set_index_buffer_with_u32_and_u32(
+            &buffer.buffer,
+            index_format.into(),
+            offset as u32,
+            size as u32,
+        )
+    }
+
     pub fn set_vertex_buffer(&self, slot: u32, buffer: &Buffer) {
         self.encoder.set_vertex_buffer(slot, &buffer.buffer)
     }
@@ -1138,6 +1529,32 @@ fn bitxor_assign(&mut self, rhs: Self) {
     }
 }
 
+#[cfg(feature = "wgpu-backend")]
+impl From<BufferUsage> for wgpu::BufferUsages {
+    fn from(value: BufferUsage) -> Self {
+        // The usage flags of WebGPU and wgpu share the same bit values.
+        wgpu::BufferUsages::from_bits_truncate(value.0)
+    }
+}
+
+/// Representation of a [`web_sys::GpuErrorFilter`].
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ErrorFilter {
+    Validation,
+    OutOfMemory,
+    Internal,
+}
+
+impl From<ErrorFilter> for web_sys::GpuErrorFilter {
+    fn from(value: ErrorFilter) -> Self {
+        match value {
+            ErrorFilter::Validation => web_sys::GpuErrorFilter::Validation,
+            ErrorFilter::OutOfMemory => web_sys::GpuErrorFilter::OutOfMemory,
+            ErrorFilter::Internal => web_sys::GpuErrorFilter::Internal,
+        }
+    }
+}
+
 /// Representation of a [`web_sys::GpuBufferMapState`].
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum BufferMapState {
@@ -2315,6 +2732,62 @@ fn from(value: TextureViewDescriptor<'a>) -> Self {
     }
 }
 
+/// Representation of a [`web_sys::GpuImageDataLayout`].
+#[derive(Debug, Default)]
+pub struct ImageDataLayout {
+    pub offset: Option<usize>,
+    pub bytes_per_row: Option<u32>,
+    pub rows_per_image: Option<u32>,
+}
+
+impl From<ImageDataLayout> for web_sys::GpuImageDataLayout {
+    fn from(value: ImageDataLayout) -> Self {
+        let mut layout = web_sys::GpuImageDataLayout::new();
+        value.offset.map(|x| layout.offset(x as f64));
+        value.bytes_per_row.map(|x| layout.bytes_per_row(x));
+        value.rows_per_image.map(|x| layout.rows_per_image(x));
+        layout
+    }
+}
+
+/// Representation of a [`web_sys::GpuImageCopyBuffer`].
+#[derive(Debug)]
+pub struct ImageCopyBuffer {
+    pub buffer: Buffer,
+    pub layout: ImageDataLayout,
+}
+
+impl From<ImageCopyBuffer> for web_sys::GpuImageCopyBuffer {
+    fn from(value: ImageCopyBuffer) -> Self {
+        let mut copy = web_sys::GpuImageCopyBuffer::new(&value.buffer.buffer);
+        value.layout.offset.map(|x| copy.offset(x as f64));
+        value.layout.bytes_per_row.map(|x| copy.bytes_per_row(x));
+        value.layout.rows_per_image.map(|x| copy.rows_per_image(x));
+        copy
+    }
+}
+
+/// Representation of a [`web_sys::GpuImageCopyTexture`].
+#[derive(Debug)]
+pub struct ImageCopyTexture {
+    pub texture: Texture,
+    pub aspect: Option<TextureAspect>,
+    pub mip_level: Option<u32>,
+    pub origin: Option<[u32; 3]>,
+}
+
+impl From<ImageCopyTexture> for web_sys::GpuImageCopyTexture {
+    fn from(value: ImageCopyTexture) -> Self {
+        let mut copy = web_sys::GpuImageCopyTexture::new(&value.texture.texture);
+        value.aspect.map(|x| copy.aspect(x.into()));
+        value.mip_level.map(|x| copy.mip_level(x));
+        value
+            .origin
+            .map(|x| copy.origin(&js_sys::Array::from_iter(x.map(JsValue::from))));
+        copy
+    }
+}
+
 /// Representation of a [`web_sys::GpuTextureAspect`].
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TextureAspect {
@@ -9,6 +9,15 @@
 /// See the wgsl specification to check if a type is host-sharable.
 pub unsafe trait HostSharable: Copy {}
 
+/// Host-sharable struct with a definition in WGSL.
+///
+/// The definition is included into the shaders by the shader preprocessor,
+/// such that the layout is only maintained next to the Rust struct.
+pub trait WgslStruct: HostSharable {
+    /// WGSL definition of the struct.
+    const DEFINITION: &'static str;
+}
+
 unsafe impl HostSharable for i32 {}
 unsafe impl HostSharable for u32 {}
 unsafe impl HostSharable for f32 {}